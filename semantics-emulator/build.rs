@@ -0,0 +1,53 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use bumpalo::Bump;
+use quote::quote;
+
+use semantics::semantics2::arena::Arena;
+use semantics::semantics2::rust_codegen::{RustCodegenError, RustModule};
+
+/// Path of the file listing the instructions to generate code for, see encodings.txt.
+const TRACE_VARIABLE: &str = "SEMANTICS_EMULATOR_TRACE";
+
+fn parse_hex(line: &str) -> Vec<u8> {
+    let digits = line.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    assert_eq!(digits.len() % 2, 0, "odd number of hex digits in {line}");
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_else(|_| panic!("not hex: {line}")))
+        .collect()
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={TRACE_VARIABLE}");
+    let trace_path = env::var_os(TRACE_VARIABLE).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("encodings.txt"));
+    println!("cargo:rerun-if-changed={}", trace_path.display());
+    let text = fs::read_to_string(&trace_path).unwrap_or_else(|err| panic!("{}: {err}", trace_path.display()));
+    let trace = text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_hex)
+        .collect::<BTreeSet<_>>();
+
+    let bump = Bump::new();
+    let mut module = RustModule::new(Arena::new(&bump), quote!(::semantics::semantics2));
+    for bytes in trace.iter() {
+        match module.add_instruction(bytes) {
+            Ok(()) => {}
+            // left to the interpreter
+            Err(err @ (RustCodegenError::Unsupported(_) | RustCodegenError::Semantics(_))) => {
+                println!("cargo:warning=no code generated for {bytes:02x?}: {err:?}");
+            }
+            Err(err @ RustCodegenError::Decode(_)) => panic!("{bytes:02x?}: {err:?}"),
+        }
+    }
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("step.rs"), module.tokens().to_string()).unwrap();
+    let encodings = trace.iter().map(|bytes| quote!(&[#(#bytes),*]));
+    let trace_tokens = quote!(pub const TRACE: &[&[u8]] = &[#(#encodings),*];);
+    fs::write(out_dir.join("trace.rs"), trace_tokens.to_string()).unwrap();
+}
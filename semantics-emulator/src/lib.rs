@@ -0,0 +1,54 @@
+use bumpalo::Bump;
+use semantics::semantics2::{apply_instruction_with_address_width, ConcreteError, SemanticsError};
+use semantics::semantics2::arena::Arena;
+use semantics::semantics2::semantic_steps::{apply_instructions_to_concrete_in_place, Fault};
+use semantics::semantics2::state::ConcreteX86MachineState64;
+use xed_enum::{DecodeError, EncodeDecodeContext, X86Instruction};
+
+/// Native code for the instructions listed in encodings.txt, or in the file `SEMANTICS_EMULATOR_TRACE` names,
+/// written out by the build script. See [`semantics::semantics2::rust_codegen::RustModule`].
+#[allow(unused, clippy::all)]
+pub mod generated {
+    include!(concat!(env!("OUT_DIR"), "/step.rs"));
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum StepError {
+    Decode(DecodeError),
+    /// There are no semantics for the instruction.
+    Unsupported(X86Instruction),
+    Fault(Fault),
+}
+
+impl From<SemanticsError> for StepError {
+    fn from(err: SemanticsError) -> Self {
+        match err {
+            SemanticsError::Unsupported(instr) => StepError::Unsupported(instr),
+        }
+    }
+}
+
+impl From<ConcreteError> for StepError {
+    fn from(err: ConcreteError) -> Self {
+        match err {
+            ConcreteError::Unsupported(instr) => StepError::Unsupported(instr),
+            ConcreteError::Fault(fault) => StepError::Fault(fault),
+        }
+    }
+}
+
+/// Runs the 64 bit instruction `instr` starts with. Only the exact encodings code was generated for run natively,
+/// and only integer instructions get code generated, so anything else with semantics, including every vector, float
+/// and x87 instruction, is interpreted. The state is left alone if the instruction faults.
+pub fn step(state: &mut ConcreteX86MachineState64, instr: &[u8]) -> Result<(), StepError> {
+    if let Some(res) = generated::step(state, instr) {
+        return res.map_err(StepError::Fault);
+    }
+    let (instruction, address_width, rest) = X86Instruction::decode_one_with_address_width(instr, &mut EncodeDecodeContext::new()).map_err(StepError::Decode)?;
+    let bump = Bump::new();
+    let steps = apply_instruction_with_address_width(Arena::new(&bump), instruction, instr.len() - rest.len(), address_width)?;
+    apply_instructions_to_concrete_in_place(state, steps.as_slice()).map_err(StepError::Fault)
+}
+
+#[cfg(test)]
+pub mod test;
@@ -0,0 +1,102 @@
+use bumpalo::Bump;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use semantics::semantics2::apply_instruction;
+use semantics::semantics2::arena::Arena;
+use semantics::semantics2::expression::Flag;
+use semantics::semantics2::semantic_steps::{apply_instructions_to_concrete, Fault};
+use semantics::semantics2::state::ConcreteX86MachineState64;
+use wrapper_common::registers::Reg64WithRIP;
+use xed_enum::{EncodeDecodeContext, X86Instruction};
+
+use crate::{generated, step, StepError};
+
+include!(concat!(env!("OUT_DIR"), "/trace.rs"));
+
+const GPRS: [Reg64WithRIP; 17] = [
+    Reg64WithRIP::RAX,
+    Reg64WithRIP::RBX,
+    Reg64WithRIP::RCX,
+    Reg64WithRIP::RDX,
+    Reg64WithRIP::RSI,
+    Reg64WithRIP::RDI,
+    Reg64WithRIP::RSP,
+    Reg64WithRIP::RBP,
+    Reg64WithRIP::R8,
+    Reg64WithRIP::R9,
+    Reg64WithRIP::R10,
+    Reg64WithRIP::R11,
+    Reg64WithRIP::R12,
+    Reg64WithRIP::R13,
+    Reg64WithRIP::R14,
+    Reg64WithRIP::R15,
+    Reg64WithRIP::RIP,
+];
+
+const FLAGS: [Flag; 6] = [Flag::CF, Flag::PF, Flag::AF, Flag::ZF, Flag::SF, Flag::OF];
+
+/// Random registers and flags, with random bytes wherever a register points. A quarter of the registers are small,
+/// so that shift counts vary and divisions don't always overflow.
+fn random_state(rng: &mut StdRng) -> ConcreteX86MachineState64 {
+    let mut state = ConcreteX86MachineState64::zeroed();
+    for reg in GPRS {
+        let value = if rng.gen_bool(0.25) { rng.gen_range(0..0x40) } else { rng.gen() };
+        *state.gpr64_mut(reg) = value;
+        state = state.memory(value, &rng.gen::<[u8; 16]>());
+    }
+    for flag in FLAGS {
+        state.set_flag(flag, rng.gen());
+    }
+    state
+}
+
+#[test]
+pub fn test_generated_step_agrees_with_interpreter() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for bytes in TRACE {
+        let (instr, rest) = X86Instruction::decode_one(bytes, &mut EncodeDecodeContext::new()).unwrap();
+        let bump = Bump::new();
+        let steps = apply_instruction(Arena::new(&bump), instr, bytes.len() - rest.len()).unwrap();
+        for _ in 0..200 {
+            let initial = random_state(&mut rng);
+            let mut stepped = initial.clone();
+            let res = generated::step(&mut stepped, bytes).unwrap_or_else(|| panic!("no code generated for {bytes:02x?}"));
+            match apply_instructions_to_concrete(initial.clone(), steps.as_slice()) {
+                Ok(expected) => {
+                    assert_eq!(res, Ok(()), "{bytes:02x?}");
+                    assert_eq!(stepped, expected, "{bytes:02x?}");
+                }
+                Err(fault) => {
+                    assert_eq!(res, Err(fault), "{bytes:02x?}");
+                    assert_eq!(stepped, initial, "{bytes:02x?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_step_falls_back_to_interpreter() {
+    // sub rbx, rax isn't in encodings.txt
+    let bytes = [0x48, 0x29, 0xC3];
+    let initial = ConcreteX86MachineState64::zeroed().rax(3).rbx(5).rip(0x1000);
+    assert!(generated::step(&mut initial.clone(), &bytes).is_none());
+    let mut stepped = initial.clone();
+    step(&mut stepped, &bytes).unwrap();
+    assert_eq!((stepped.gpr64(Reg64WithRIP::RBX), stepped.gpr64(Reg64WithRIP::RIP)), (2, 0x1003));
+
+    // div rbx is, and divides by zero
+    let mut stepped = ConcreteX86MachineState64::zeroed();
+    assert_eq!(step(&mut stepped, &[0x48, 0xF7, 0xF3]), Err(StepError::Fault(Fault::DivideError)));
+
+    // div ecx isn't, and the interpreter puts RIP back when it divides by zero
+    let initial = ConcreteX86MachineState64::zeroed().rax(3).rip(0x1000);
+    let mut stepped = initial.clone();
+    assert_eq!(step(&mut stepped, &[0xF7, 0xF1]), Err(StepError::Fault(Fault::DivideError)));
+    assert_eq!(stepped, initial);
+
+    // jmp far [rax] has no semantics
+    let mut stepped = ConcreteX86MachineState64::zeroed();
+    assert!(matches!(step(&mut stepped, &[0xFF, 0x28]), Err(StepError::Unsupported(X86Instruction::JMP_FAR(_)))));
+}
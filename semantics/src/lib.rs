@@ -1,11 +1,4 @@
 #![allow(unused)]
 
 
-pub mod iform_semantics;
-pub mod x86_machine;
-pub mod xed_iform_safe;
-
 pub mod semantics2;
-
-#[cfg(test)]
-pub mod test;
\ No newline at end of file
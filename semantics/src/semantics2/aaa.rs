@@ -1,13 +1,8 @@
-use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8};
-use xed_enum::{AAA, ADC};
+use xed_enum::AAA;
 
 use crate::semantics2::arena::Arena;
 use crate::semantics2::builder::SemanticsBuilder;
-use crate::semantics2::expression::Expression;
-use crate::semantics2::read_write::{Readable, Writeable};
 use crate::semantics2::semantic_steps::InstructionSemanticsStep;
-use crate::x86_machine::semantics_builder::FlagTag;
-use crate::x86_machine::values::NumericValue;
 
 ///Operation
 //            IF 64-Bit Mode
@@ -26,33 +21,36 @@ use crate::x86_machine::values::NumericValue;
 //                   AL := AL AND 0FH;
 //            FI;
 //
-
+impl<'arena> SemanticsBuilder<'arena> {
+    /// The ELSE branch of AAA, kept separate from the mode check for the adjustment to be testable on its own.
+    pub(crate) fn ascii_adjust_after_addition(&mut self) {
+        let condition = self.bitor(self.less(
+            self.constant(9u8),
+            self.bitand(self.al(), self.constant(0xFu8)),
+        ), self.equal(self.af(), self.constant(true)));
+        self.emit_conditional(
+            condition,
+            |s| {
+                s.set_ax(s.add(s.ax(), s.constant(0x106u16)));
+                s.set_af(s.constant(true));
+                s.set_cf(s.constant(true));
+            },
+            |s| {
+                s.set_af(s.constant(false));
+                s.set_cf(s.constant(false));
+            },
+        );
+        self.set_al(self.bitand(self.al(), self.constant(0xFu8)));
+    }
+}
 
 pub fn apply_iform_aaa<'arena>(arena: Arena<'arena>, aaa: AAA) -> Vec<InstructionSemanticsStep<'arena>> {
     match aaa {
         AAA::AAA {} => {
             let mut s = SemanticsBuilder::new(arena);
             s.undefined_exception_if_64_bit();
-            let condition = s.bitor(s.less(
-                s.constant(9u8),
-                s.bitand(s.al(), s.constant(0xFu8)),
-            ), s.equal(s.af(), s.constant(true)));
-            s.emit_conditional(
-                condition,
-                |s| {
-                    s.set_ax(s.add(s.ax(), s.constant(0x106u16)));
-                    s.set_af(s.constant(true));
-                    s.set_cf(s.constant(true));
-                },
-                |s| {
-                    s.set_ax(s.constant(0u16));
-                    s.set_af(s.constant(false));
-                    s.set_cf(s.constant(false));
-                },
-            );
-            s.set_al(s.bitand(s.al(), s.constant(0xFu8)));
+            s.ascii_adjust_after_addition();
             s.finalize()
         }
     }
 }
-
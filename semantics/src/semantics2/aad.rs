@@ -1,32 +1,41 @@
 use xed_enum::AAD;
 use crate::semantics2::arena::Arena;
 use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::Expression;
 use crate::semantics2::read_write::Readable;
-use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+///IF 64-Bit Mode
+//     THEN
+//         #UD;
+//     ELSE
+//         tempAL := AL;
+//         tempAH := AH;
+//         AL := (tempAL + (tempAH ∗ imm8)) AND FFH;
+//         (* imm8 is set to 0AH for the AAD mnemonic.*)
+//         AH := 0;
+// FI;
+impl<'arena> SemanticsBuilder<'arena> {
+    /// The ELSE branch of AAD, kept separate from the mode check like [`SemanticsBuilder::ascii_adjust_after_addition`].
+    /// SF, ZF and PF are set from the new AL, and OF, AF and CF are undefined. They are calculated as for the 8 bit
+    /// addition, which is what hardware does.
+    pub(crate) fn ascii_adjust_before_division(&mut self, base: &'arena Expression<'arena>) {
+        let temp_al = self.al();
+        let product = self.umul(self.ah(), base);
+        self.set_al(self.add(temp_al, product));
+        self.set_ah(self.constant(0u8));
+        self.flag_calculate(FlagTag::Add, temp_al, product);
+    }
+}
 
 pub fn apply_iform_aad<'arena>(arena: Arena<'arena>, aad: AAD) -> Vec<InstructionSemanticsStep<'arena>> {
     match aad {
         AAD::AAD_IMMB { operand_0 } => {
-            ///IF 64-Bit Mode
-            //     THEN
-            //         #UD;
-            //     ELSE
-            //         tempAL := AL;
-            //         tempAH := AH;
-            //         AL := (tempAL + (tempAH ∗ imm8)) AND FFH;
-            //         (* imm8 is set to 0AH for the AAD mnemonic.*)
-            //         AH := 0;
-            // FI;
             let mut s = SemanticsBuilder::new(arena);
             s.undefined_exception_if_64_bit();
-            //todo how to deal with saving expression values when regs get changed.
-            s.set_al(s.bitand(
-                s.add(s.al(), s.umul(s.ah(), operand_0.read(&s))),
-                s.constant(0xFFu8),
-            ));
-            s.set_ah(s.constant(0u8));
+            let base = operand_0.read(&s);
+            s.ascii_adjust_before_division(base);
             s.finalize()
         }
     }
 }
-
@@ -33,10 +33,11 @@ fn adc_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable
     //     ELSE CF:DEST[31:0] := DEST[31:0] + SRC[31:0] + CF;
     // FI;
     let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
     let cf = s.cf();
     let dest = readable1.read(&s);
     let src = readable2.read(&s);
-    let cf_dest = s.add(s.zext_to(s.add(dest, src),65), s.zext_to(cf,65));
+    let cf_dest = s.add(s.add(s.zext_to(dest, width + 1), s.zext_to(src, width + 1)), s.zext_to(cf, width + 1));
     let dest = s.lower_bits(cf_dest, width);
     let cf = s.upper_bits(cf_dest, 1);
     writeable1.write(&mut s, dest);
@@ -1,10 +1,10 @@
-use wrapper_common::registers::{Reg64WithRIP, Reg8};
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8};
 use xed_enum::ADD;
 
 use crate::semantics2::arena::Arena;
 use crate::semantics2::builder::SemanticsBuilder;
 use crate::semantics2::read_write::{Readable, Writeable};
-use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
 
 pub fn add_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
     arena: Arena<'arena>,
@@ -15,10 +15,13 @@ pub fn add_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Read
 ) -> Vec<InstructionSemanticsStep<'arena>> {
     //DEST := DEST + SRC;
     let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
     let dest = readable1.read(&s);
-    let src = readable2.read(&s);
-    let dest = s.add(dest, src);
-    writeable1.write(&mut s, dest);
+    // immediates narrower than the operand size are sign extended
+    let src = s.sext_to(readable2.read(&s), width);
+    let res = s.add(dest, src);
+    writeable1.write(&mut s, res);
+    s.flag_calculate(FlagTag::Add, dest, src);
     s.finalize()
 }
 
@@ -124,13 +127,13 @@ pub fn apply_iform_add(arena: Arena, instr: ADD) -> Vec<InstructionSemanticsStep
             add_generic(arena, operand_0, operand_0, operand_1, 64)
         }
         ADD::ADD_ORAX_IMMZ_16 { operand_0 } => {
-            add_generic(arena, Reg64WithRIP::RAX,Reg64WithRIP::RAX, operand_0, 16)
+            add_generic(arena, Reg16WithRIP::AX, Reg16WithRIP::AX, operand_0, 16)
         }
-        ADD::ADD_ORAX_IMMZ_32 { operand_0  } => {
-            add_generic(arena, Reg64WithRIP::RAX,Reg64WithRIP::RAX, operand_0, 32)
+        ADD::ADD_ORAX_IMMZ_32 { operand_0 } => {
+            add_generic(arena, Reg32WithRIP::EAX, Reg32WithRIP::EAX, operand_0, 32)
         }
         ADD::ADD_ORAX_IMMZ_64 { operand_0 } => {
-            add_generic(arena, Reg64WithRIP::RAX,Reg64WithRIP::RAX, operand_0, 64)
+            add_generic(arena, Reg64WithRIP::RAX, Reg64WithRIP::RAX, operand_0, 64)
         }
     }
 }
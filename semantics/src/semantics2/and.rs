@@ -0,0 +1,243 @@
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8};
+use xed_enum::{AND, TEST};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+pub fn and_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := DEST AND SRC;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let src = s.sext_to(readable2.read(&s), width);
+    let res = s.bitand(dest, src);
+    writeable1.write(&mut s, res);
+    s.flag_calculate(FlagTag::And, dest, src);
+    s.finalize()
+}
+
+pub fn apply_iform_and(arena: Arena, instr: AND) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        AND::AND_MEMB_IMMB_80R4 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        AND::AND_GPR8_IMMB_80R4 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        AND::AND_MEMV_IMMZ_16 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        AND::AND_MEMV_IMMZ_32 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        AND::AND_MEMV_IMMZ_64 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        AND::AND_GPRV_IMMZ_16 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        AND::AND_GPRV_IMMZ_32 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        AND::AND_GPRV_IMMZ_64 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        AND::AND_MEMB_IMMB_82R4 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        AND::AND_GPR8_IMMB_82R4 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        AND::AND_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        AND::AND_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        AND::AND_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        AND::AND_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        AND::AND_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        AND::AND_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        AND::AND_MEMB_GPR8 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        AND::AND_GPR8_GPR8_20 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        AND::AND_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        AND::AND_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        AND::AND_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        AND::AND_GPRV_GPRV_21_16 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        AND::AND_GPRV_GPRV_21_32 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        AND::AND_GPRV_GPRV_21_64 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        AND::AND_GPR8_GPR8_22 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        AND::AND_GPR8_MEMB { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        AND::AND_GPRV_GPRV_23_16 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        AND::AND_GPRV_GPRV_23_32 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        AND::AND_GPRV_GPRV_23_64 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        AND::AND_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        AND::AND_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        AND::AND_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            and_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        AND::AND_AL_IMMB { operand_0 } => {
+            and_generic(arena, Reg8::AL, Reg8::AL, operand_0, 8)
+        }
+        AND::AND_ORAX_IMMZ_16 { operand_0 } => {
+            and_generic(arena, Reg16WithRIP::AX, Reg16WithRIP::AX, operand_0, 16)
+        }
+        AND::AND_ORAX_IMMZ_32 { operand_0 } => {
+            and_generic(arena, Reg32WithRIP::EAX, Reg32WithRIP::EAX, operand_0, 32)
+        }
+        AND::AND_ORAX_IMMZ_64 { operand_0 } => {
+            and_generic(arena, Reg64WithRIP::RAX, Reg64WithRIP::RAX, operand_0, 64)
+        }
+    }
+}
+
+/// TEST is AND without the write, so it lives here rather than in a module which would clash with the tests.
+pub fn test_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //TEMP := SRC1 AND SRC2;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let src1 = readable1.read(&s);
+    let src2 = s.sext_to(readable2.read(&s), width);
+    s.flag_calculate(FlagTag::And, src1, src2);
+    s.finalize()
+}
+
+pub fn apply_iform_test(arena: Arena, instr: TEST) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        TEST::TEST_MEMB_IMMB_F6R0 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 8)
+        }
+        TEST::TEST_GPR8_IMMB_F6R0 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 8)
+        }
+        TEST::TEST_MEMB_IMMB_F6R1 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 8)
+        }
+        TEST::TEST_GPR8_IMMB_F6R1 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 8)
+        }
+        TEST::TEST_MEMV_IMMZ_F7R0_16 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 16)
+        }
+        TEST::TEST_MEMV_IMMZ_F7R0_32 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 32)
+        }
+        TEST::TEST_MEMV_IMMZ_F7R0_64 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 64)
+        }
+        TEST::TEST_GPRV_IMMZ_F7R0_16 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 16)
+        }
+        TEST::TEST_GPRV_IMMZ_F7R0_32 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 32)
+        }
+        TEST::TEST_GPRV_IMMZ_F7R0_64 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 64)
+        }
+        TEST::TEST_MEMV_IMMZ_F7R1_16 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 16)
+        }
+        TEST::TEST_MEMV_IMMZ_F7R1_32 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 32)
+        }
+        TEST::TEST_MEMV_IMMZ_F7R1_64 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 64)
+        }
+        TEST::TEST_GPRV_IMMZ_F7R1_16 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 16)
+        }
+        TEST::TEST_GPRV_IMMZ_F7R1_32 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 32)
+        }
+        TEST::TEST_GPRV_IMMZ_F7R1_64 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 64)
+        }
+        TEST::TEST_MEMB_GPR8 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 8)
+        }
+        TEST::TEST_GPR8_GPR8 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 8)
+        }
+        TEST::TEST_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 16)
+        }
+        TEST::TEST_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 32)
+        }
+        TEST::TEST_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 64)
+        }
+        TEST::TEST_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 16)
+        }
+        TEST::TEST_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 32)
+        }
+        TEST::TEST_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            test_generic(arena, operand_0, operand_1, 64)
+        }
+        TEST::TEST_AL_IMMB { operand_0 } => {
+            test_generic(arena, Reg8::AL, operand_0, 8)
+        }
+        TEST::TEST_ORAX_IMMZ_16 { operand_0 } => {
+            test_generic(arena, Reg16WithRIP::AX, operand_0, 16)
+        }
+        TEST::TEST_ORAX_IMMZ_32 { operand_0 } => {
+            test_generic(arena, Reg32WithRIP::EAX, operand_0, 32)
+        }
+        TEST::TEST_ORAX_IMMZ_64 { operand_0 } => {
+            test_generic(arena, Reg64WithRIP::RAX, operand_0, 64)
+        }
+    }
+}
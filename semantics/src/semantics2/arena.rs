@@ -1,19 +1,79 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 use bumpalo::Bump;
 use crate::semantics2::expression::Expression;
+use crate::semantics2::state::X86Mode;
+use crate::semantics2::value::Value;
+
+/// An expression with its children swapped for a placeholder, and the addresses of the children. Children are
+/// interned before their parents, so this identifies an expression without hashing the whole tree under it.
+type InternKey<'arena> = (Expression<'arena>, Vec<*const Expression<'arena>>);
+
+type Interned<'arena> = RefCell<HashMap<InternKey<'arena>, &'arena Expression<'arena>>>;
 
 #[derive(Copy, Clone)]
 pub struct Arena<'arena> {
     bump: &'arena Bump,
+    /// None if expressions are neither shared nor simplified, see [`Arena::unsimplified`].
+    interned: Option<&'arena Interned<'arena>>,
+    placeholder: &'arena Expression<'arena>,
+    /// See [`Arena::in_mode`].
+    mode: X86Mode,
 }
 
 impl<'arena> Arena<'arena> {
+    /// Equal expressions allocated from this arena are shared, and builders simplify their steps when finalized.
     pub fn new(bump: &'arena Bump) -> Self {
+        Self {
+            interned: Some(bump.alloc(RefCell::new(HashMap::new()))),
+            ..Self::unsimplified(bump)
+        }
+    }
+
+    /// Every expression gets its own node and steps are left the way they were built, which is mostly useful for
+    /// checking the simplifier against.
+    pub fn unsimplified(bump: &'arena Bump) -> Self {
         Self {
             bump,
+            interned: None,
+            placeholder: bump.alloc(Expression::Constant { value: bump.alloc(Value::zero(1)) }),
+            mode: X86Mode::_64Bit,
         }
     }
 
+    /// Instructions lifted with the returned arena get the semantics they have in `mode`, rather than in 64 bit
+    /// mode. Only instructions which behave differently in the other modes, beyond what their iform already says,
+    /// are affected, like those using the stack.
+    pub fn in_mode(self, mode: X86Mode) -> Self {
+        Self {
+            mode,
+            ..self
+        }
+    }
+
+    pub fn simplifies(&self) -> bool {
+        self.interned.is_some()
+    }
+
+    pub fn mode(&self) -> X86Mode {
+        self.mode
+    }
+
     pub fn a<T>(&self, expr: T) -> &'arena T {
         self.bump.alloc(expr)
     }
+
+    /// Allocates `expr`, or returns the existing node if an equal expression was allocated before.
+    pub fn expr(&self, expr: Expression<'arena>) -> &'arena Expression<'arena> {
+        let Some(interned) = self.interned else {
+            return self.a(expr);
+        };
+        let mut children = vec![];
+        let shape = expr.map_children(|child| {
+            children.push(child as *const Expression<'arena>);
+            self.placeholder
+        });
+        *interned.borrow_mut().entry((shape, children)).or_insert_with(|| self.bump.alloc(expr))
+    }
 }
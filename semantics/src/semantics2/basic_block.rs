@@ -0,0 +1,98 @@
+use wrapper_common::registers::{Reg64WithRIP, Register};
+use xed_enum::{DecodeError, EncodeDecodeContext, X86Instruction};
+
+use crate::semantics2::{apply_instruction_with_address_width, SemanticsError};
+use crate::semantics2::arena::Arena;
+use crate::semantics2::semantic_steps::{apply_instructions_to_concrete, Fault, InstructionSemanticsStep};
+use crate::semantics2::state::{ConcreteX86MachineState64, X86Mode};
+
+pub struct LiftedInstruction<'arena> {
+    pub address: u64,
+    pub length: usize,
+    pub instruction: X86Instruction,
+    pub semantics: Vec<InstructionSemanticsStep<'arena>>,
+}
+
+/// Straight line code, ending with the first instruction which can write RIP, or earlier if the bytes run out.
+pub struct BasicBlock<'arena> {
+    pub start_address: u64,
+    pub instructions: Vec<LiftedInstruction<'arena>>,
+}
+
+impl BasicBlock<'_> {
+    /// Address of the instruction following the last one in the block, which is where execution continues if the
+    /// block doesn't branch.
+    pub fn fall_through_address(&self) -> u64 {
+        match self.instructions.last() {
+            Some(last) => last.address.wrapping_add(last.length as u64),
+            None => self.start_address,
+        }
+    }
+
+    /// Runs every instruction of the block in order. RIP should be [`Self::start_address`] to begin with, and
+    /// ends up wherever the block transfers control to.
+    pub fn apply_concrete(&self, mut state: ConcreteX86MachineState64) -> Result<ConcreteX86MachineState64, Fault> {
+        for instruction in self.instructions.iter() {
+            state = apply_instructions_to_concrete(state, instruction.semantics.as_slice())?;
+        }
+        Ok(state)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LiftError {
+    Decode(DecodeError),
+    /// There are no semantics for an instruction in the block, see [`SemanticsError::Unsupported`].
+    Unsupported { address: u64, instruction: X86Instruction },
+}
+
+fn ends_basic_block(instruction: &X86Instruction, context: &mut EncodeDecodeContext) -> bool {
+    instruction.registers_written(context)
+        .map(|written| written.registers.contains(&Register::GP64(Reg64WithRIP::RIP)))
+        // nothing is known about instructions xed won't encode again, so assume the worst
+        .unwrap_or(true)
+}
+
+fn context_for_mode(mode: X86Mode) -> EncodeDecodeContext {
+    match mode {
+        X86Mode::Real => EncodeDecodeContext::real(),
+        X86Mode::Protected => EncodeDecodeContext::legacy_32(),
+        X86Mode::_64Bit => EncodeDecodeContext::new(),
+    }
+}
+
+/// Decodes code for the mode of `arena` from `bytes`, which start at `start_address`, and lifts it up to the end of the first basic
+/// block. Nothing is lifted if any instruction on the way has no semantics.
+pub fn lift_basic_block<'arena>(arena: Arena<'arena>, bytes: &[u8], start_address: u64) -> Result<BasicBlock<'arena>, LiftError> {
+    let mut context = context_for_mode(arena.mode());
+    let mut instructions = vec![];
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let address = start_address.wrapping_add(offset as u64);
+        let remaining = &bytes[offset..];
+        // reported the same way as by xed_enum::Decoder
+        let (instruction, address_width, rest) = X86Instruction::decode_one_with_address_width(remaining, &mut context).map_err(|err| match err {
+            DecodeError::XedError(error) | DecodeError::Undecodable { error, .. } | DecodeError::Unsupported(error) => {
+                LiftError::Decode(DecodeError::Undecodable { offset, address, error })
+            }
+        })?;
+        let length = remaining.len() - rest.len();
+        offset += length;
+        let semantics = apply_instruction_with_address_width(arena, instruction, length, address_width).map_err(|err| match err {
+            SemanticsError::Unsupported(instruction) => LiftError::Unsupported { address, instruction },
+        })?;
+        instructions.push(LiftedInstruction {
+            address,
+            length,
+            instruction,
+            semantics,
+        });
+        if ends_basic_block(&instruction, &mut context) {
+            break;
+        }
+    }
+    Ok(BasicBlock {
+        start_address,
+        instructions,
+    })
+}
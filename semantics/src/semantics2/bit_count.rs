@@ -0,0 +1,201 @@
+use xed_enum::{BSF, BSR, LZCNT, POPCNT, TZCNT};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+#[derive(Copy, Clone)]
+pub enum BitScanDirection {
+    /// BSF
+    Forward,
+    /// BSR
+    Reverse,
+}
+
+pub fn bit_scan_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    direction: BitScanDirection,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF SRC = 0
+    //    THEN
+    //        ZF := 1;
+    //        DEST is undefined; (* hardware leaves DEST alone, including the upper half for 32 bit operands *)
+    //    ELSE
+    //        ZF := 0;
+    //        DEST := index of the lowest (BSF) or highest (BSR) set bit of SRC;
+    //FI;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let src = readable1.read(&s);
+    let src_is_zero = s.equal(src, s.constant_with_width(0, width));
+    s.emit_conditional(src_is_zero, |s| s.set_zf(s.constant(true)), |s| {
+        let index = match direction {
+            BitScanDirection::Forward => s.trailing_zeros(src),
+            BitScanDirection::Reverse => s.sub(s.constant_with_width(width as u64 - 1, width), s.leading_zeros(src)),
+        };
+        writeable1.write(s, index);
+        s.set_zf(s.constant(false));
+    });
+    s.finalize()
+}
+
+/// LZCNT and TZCNT, which unlike BSR and BSF give the operand size for a zero source.
+pub fn zero_count_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    direction: BitScanDirection,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := number of leading (LZCNT) or trailing (TZCNT) zero bits of SRC;
+    //IF DEST = OperandSize THEN CF := 1; ELSE CF := 0; FI
+    //IF DEST = 0 THEN ZF := 1; ELSE ZF := 0; FI
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let src = readable1.read(&s);
+    let res = match direction {
+        BitScanDirection::Forward => s.trailing_zeros(src),
+        BitScanDirection::Reverse => s.leading_zeros(src),
+    };
+    writeable1.write(&mut s, res);
+    s.set_cf(s.equal(src, s.constant_with_width(0, width)));
+    s.set_zf(s.equal(res, s.constant_with_width(0, width)));
+    s.finalize()
+}
+
+pub fn popcnt_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := number of set bits in SRC;
+    //OF, SF, AF, CF and PF are cleared, ZF is set if SRC = 0
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let src = readable1.read(&s);
+    let res = s.popcount(src);
+    writeable1.write(&mut s, res);
+    s.set_of(s.constant(false));
+    s.set_sf(s.constant(false));
+    s.set_zf(s.equal(src, s.constant_with_width(0, width)));
+    s.set_af(s.constant(false));
+    s.set_cf(s.constant(false));
+    s.set_pf(s.constant(false));
+    s.finalize()
+}
+
+pub fn apply_iform_bsf(arena: Arena, instr: BSF) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        BSF::BSF_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 16)
+        }
+        BSF::BSF_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 32)
+        }
+        BSF::BSF_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 64)
+        }
+        BSF::BSF_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 16)
+        }
+        BSF::BSF_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 32)
+        }
+        BSF::BSF_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 64)
+        }
+    }
+}
+pub fn apply_iform_bsr(arena: Arena, instr: BSR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        BSR::BSR_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 16)
+        }
+        BSR::BSR_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 32)
+        }
+        BSR::BSR_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 64)
+        }
+        BSR::BSR_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 16)
+        }
+        BSR::BSR_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 32)
+        }
+        BSR::BSR_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            bit_scan_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 64)
+        }
+    }
+}
+pub fn apply_iform_lzcnt(arena: Arena, instr: LZCNT) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        LZCNT::LZCNT_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 16)
+        }
+        LZCNT::LZCNT_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 32)
+        }
+        LZCNT::LZCNT_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 64)
+        }
+        LZCNT::LZCNT_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 16)
+        }
+        LZCNT::LZCNT_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 32)
+        }
+        LZCNT::LZCNT_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Reverse, operand_0, operand_1, 64)
+        }
+    }
+}
+pub fn apply_iform_tzcnt(arena: Arena, instr: TZCNT) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        TZCNT::TZCNT_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 16)
+        }
+        TZCNT::TZCNT_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 32)
+        }
+        TZCNT::TZCNT_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 64)
+        }
+        TZCNT::TZCNT_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 16)
+        }
+        TZCNT::TZCNT_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 32)
+        }
+        TZCNT::TZCNT_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            zero_count_generic(arena, BitScanDirection::Forward, operand_0, operand_1, 64)
+        }
+    }
+}
+pub fn apply_iform_popcnt(arena: Arena, instr: POPCNT) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        POPCNT::POPCNT_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            popcnt_generic(arena, operand_0, operand_1, 16)
+        }
+        POPCNT::POPCNT_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            popcnt_generic(arena, operand_0, operand_1, 32)
+        }
+        POPCNT::POPCNT_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            popcnt_generic(arena, operand_0, operand_1, 64)
+        }
+        POPCNT::POPCNT_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            popcnt_generic(arena, operand_0, operand_1, 16)
+        }
+        POPCNT::POPCNT_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            popcnt_generic(arena, operand_0, operand_1, 32)
+        }
+        POPCNT::POPCNT_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            popcnt_generic(arena, operand_0, operand_1, 64)
+        }
+    }
+}
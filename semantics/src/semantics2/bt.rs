@@ -0,0 +1,243 @@
+use xed_enum::{BT, BTC, BTR, BTS};
+use xed_wrapper::operands::MemoryOperands;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::Expression;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+#[derive(Copy, Clone)]
+pub enum BitTestOp {
+    /// BT
+    Test,
+    /// BTS
+    Set,
+    /// BTR
+    Reset,
+    /// BTC
+    Complement,
+}
+
+impl<'arena> SemanticsBuilder<'arena> {
+    /// The selected bit, and the new value of `value` unless `op` is [`BitTestOp::Test`]. `bit_offset` must already
+    /// be less than the width of `value`.
+    fn bit_test(&self, op: BitTestOp, value: &'arena Expression<'arena>, bit_offset: &'arena Expression<'arena>) -> (&'arena Expression<'arena>, Option<&'arena Expression<'arena>>) {
+        let width = value.width();
+        let mask = self.shl(self.constant_with_width(1, width), bit_offset);
+        let bit = self.not(self.equal(self.bitand(value, mask), self.constant_with_width(0, width)));
+        let res = match op {
+            BitTestOp::Test => None,
+            BitTestOp::Set => Some(self.bitor(value, mask)),
+            BitTestOp::Reset => Some(self.bitand(value, self.not(mask))),
+            BitTestOp::Complement => Some(self.bitxor(value, mask)),
+        };
+        (bit, res)
+    }
+}
+
+/// Register operands, and memory operands with an immediate offset. The offset is taken modulo the operand size.
+pub fn bit_test_generic<'arena, O1: Readable<'arena> + Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    op: BitTestOp,
+    operand1: O1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //CF := Bit(BitBase, BitOffset);
+    //Bit(BitBase, BitOffset) := 1; (* BTS, 0 for BTR, complemented for BTC *)
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let value = operand1.read(&s);
+    let bit_offset = s.bitand(s.zext_to(readable1.read(&s), width), s.constant_with_width(width as u64 - 1, width));
+    let (bit, res) = s.bit_test(op, value, bit_offset);
+    if let Some(res) = res {
+        operand1.write(&mut s, res);
+    }
+    s.set_cf(bit);
+    s.finalize()
+}
+
+/// Memory operands with a register offset. The offset is signed and isn't limited to the operand, it selects any
+/// bit relative to the address, so the operand size word holding the bit can be before or well after it.
+pub fn bit_test_memory_generic<'arena, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    op: BitTestOp,
+    operand: MemoryOperands,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    let mut s = SemanticsBuilder::new(arena);
+    let offset = readable1.read(&s);
+    let word_index = s.ashr(s.sext_to(offset, 64), s.constant_with_width(width.trailing_zeros() as u64, 64));
+    let address = s.add(s.effective_address(&operand), s.umul(word_index, s.constant_with_width(width as u64 / 8, 64)));
+    let value = s.load(address, width);
+    let bit_offset = s.bitand(offset, s.constant_with_width(width as u64 - 1, width));
+    let (bit, res) = s.bit_test(op, value, bit_offset);
+    if let Some(res) = res {
+        s.store(address, res);
+    }
+    s.set_cf(bit);
+    s.finalize()
+}
+
+pub fn apply_iform_bt(arena: Arena, instr: BT) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        BT::BT_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 16)
+        }
+        BT::BT_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 32)
+        }
+        BT::BT_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 64)
+        }
+        BT::BT_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 16)
+        }
+        BT::BT_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 32)
+        }
+        BT::BT_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 64)
+        }
+        BT::BT_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Test, operand_0, operand_1, 16)
+        }
+        BT::BT_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Test, operand_0, operand_1, 32)
+        }
+        BT::BT_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Test, operand_0, operand_1, 64)
+        }
+        BT::BT_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 16)
+        }
+        BT::BT_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 32)
+        }
+        BT::BT_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Test, operand_0, operand_1, 64)
+        }
+    }
+}
+pub fn apply_iform_bts(arena: Arena, instr: BTS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        BTS::BTS_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 16)
+        }
+        BTS::BTS_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 32)
+        }
+        BTS::BTS_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 64)
+        }
+        BTS::BTS_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 16)
+        }
+        BTS::BTS_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 32)
+        }
+        BTS::BTS_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 64)
+        }
+        BTS::BTS_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Set, operand_0, operand_1, 16)
+        }
+        BTS::BTS_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Set, operand_0, operand_1, 32)
+        }
+        BTS::BTS_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Set, operand_0, operand_1, 64)
+        }
+        BTS::BTS_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 16)
+        }
+        BTS::BTS_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 32)
+        }
+        BTS::BTS_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Set, operand_0, operand_1, 64)
+        }
+    }
+}
+pub fn apply_iform_btr(arena: Arena, instr: BTR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        BTR::BTR_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 16)
+        }
+        BTR::BTR_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 32)
+        }
+        BTR::BTR_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 64)
+        }
+        BTR::BTR_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 16)
+        }
+        BTR::BTR_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 32)
+        }
+        BTR::BTR_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 64)
+        }
+        BTR::BTR_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Reset, operand_0, operand_1, 16)
+        }
+        BTR::BTR_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Reset, operand_0, operand_1, 32)
+        }
+        BTR::BTR_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Reset, operand_0, operand_1, 64)
+        }
+        BTR::BTR_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 16)
+        }
+        BTR::BTR_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 32)
+        }
+        BTR::BTR_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Reset, operand_0, operand_1, 64)
+        }
+    }
+}
+pub fn apply_iform_btc(arena: Arena, instr: BTC) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        BTC::BTC_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 16)
+        }
+        BTC::BTC_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 32)
+        }
+        BTC::BTC_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 64)
+        }
+        BTC::BTC_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 16)
+        }
+        BTC::BTC_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 32)
+        }
+        BTC::BTC_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 64)
+        }
+        BTC::BTC_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Complement, operand_0, operand_1, 16)
+        }
+        BTC::BTC_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Complement, operand_0, operand_1, 32)
+        }
+        BTC::BTC_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            bit_test_memory_generic(arena, BitTestOp::Complement, operand_0, operand_1, 64)
+        }
+        BTC::BTC_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 16)
+        }
+        BTC::BTC_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 32)
+        }
+        BTC::BTC_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            bit_test_generic(arena, BitTestOp::Complement, operand_0, operand_1, 64)
+        }
+    }
+}
@@ -1,35 +1,97 @@
-use bitvec::bitvec;
 use wrapper_common::memory_operand::GeneralReg;
 
-use wrapper_common::registers::{Reg16WithRIP, Reg64WithRIP, Reg8};
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8, Register, RegSegment, RegSegmentBase, RegXMM, RegYMM, RegZMM};
+use xed_wrapper::operands::{MemoryOperands, RelativeBr};
 
 use crate::semantics2::arena::Arena;
-use crate::semantics2::expression::{ArithmeticOp, BitWiseOp, ComparisonOp, Expression, Flag, Signedness};
+use crate::semantics2::expression::{ArithmeticOp, BitCountOp, BitWiseOp, ComparisonOp, Expression, Flag, ShiftOp, Signedness};
+use crate::semantics2::float::{FloatArithmeticOp, FloatFormat, FloatPredicate};
 use crate::semantics2::num_traits::IntegerWidth;
-use crate::semantics2::semantic_steps::{InstructionSemanticsStep, ZeroUpper};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep, ZeroUpper};
+use crate::semantics2::simplify::simplify_steps;
+use crate::semantics2::state::X86Mode;
 use crate::semantics2::value::Value;
 
+/// Bit of each arithmetic flag in RFLAGS.
+const RFLAGS_ARITHMETIC_BITS: [(Flag, usize); 6] = [(Flag::CF, 0), (Flag::PF, 2), (Flag::AF, 4), (Flag::ZF, 6), (Flag::SF, 7), (Flag::OF, 11)];
+
+/// The reserved bit 1 and IF.
+const RFLAGS_FIXED_BITS: u64 = 0x202;
+
 pub struct SemanticsBuilder<'arena> {
-    pub(crate) semantics: Vec<InstructionSemanticsStep<'arena>>,
+    semantics: Vec<InstructionSemanticsStep<'arena>>,
     arena: Arena<'arena>,
+    /// Index the first step of this builder gets. Steps are numbered in the order they are emitted, with the
+    /// steps of a conditional's branches numbered before the conditional itself. See [`InstructionSemanticsStep::step_count`].
+    first_index: usize,
+    steps_emitted: usize,
+    /// Width in bits of memory operands. Memory operands don't know their own width, so instructions with one
+    /// set this from the iform.
+    memory_operand_width: Option<usize>,
 }
 
 impl<'arena> SemanticsBuilder<'arena> {
     pub fn new(arena: Arena<'arena>) -> Self {
+        Self::starting_at(arena, 0)
+    }
+
+    fn starting_at(arena: Arena<'arena>, first_index: usize) -> Self {
         Self {
             semantics: vec![],
             arena,
+            first_index,
+            steps_emitted: 0,
+            memory_operand_width: None,
         }
     }
 
+    pub fn set_memory_operand_width(&mut self, width: usize) {
+        self.memory_operand_width = Some(width);
+    }
+
+    pub fn memory_operand_width(&self) -> usize {
+        self.memory_operand_width.expect("memory operand width not set for this instruction")
+    }
+
+    /// Index the next emitted step will get. Register and flag reads see the state from just before that step.
+    fn current_index(&self) -> usize {
+        self.first_index + self.steps_emitted
+    }
+
+    pub(crate) fn push(&mut self, step: InstructionSemanticsStep<'arena>) {
+        self.steps_emitted += step.step_count();
+        self.semantics.push(step);
+    }
+
+    /// Mode the instruction is lifted for, see [`Arena::in_mode`].
+    pub fn mode(&self) -> X86Mode {
+        self.arena.mode()
+    }
+
+    /// #UD
+    pub fn undefined_exception(&mut self) {
+        self.push(InstructionSemanticsStep::UndefinedException);
+    }
+
+    /// #UD if lifting for 64 bit mode, for instructions which only exist outside of it.
     pub fn undefined_exception_if_64_bit(&mut self) {
-        todo!()/*if state.mode == X86Mode::_64Bit {
-            self.semantics.push(InstructionSemanticsStep::UndefinedException);
-        }*/
+        if self.mode() == X86Mode::_64Bit {
+            self.undefined_exception();
+        }
+    }
+
+    /// #DE
+    pub fn divide_error(&mut self) {
+        self.push(InstructionSemanticsStep::DivideError);
+    }
+
+    /// #GP(0)
+    pub fn general_protection(&mut self) {
+        self.push(InstructionSemanticsStep::GeneralProtection);
     }
 
     pub fn set_cf(&mut self, value: &'arena Expression<'arena>) {
-        self.semantics.push(InstructionSemanticsStep::SetFlag {
+        self.push(InstructionSemanticsStep::SetFlag {
             flag: Flag::CF,
             value,
         })
@@ -40,7 +102,7 @@ impl<'arena> SemanticsBuilder<'arena> {
     }
 
     pub fn set_af(&mut self, value: &'arena Expression<'arena>) {
-        self.semantics.push(InstructionSemanticsStep::SetFlag {
+        self.push(InstructionSemanticsStep::SetFlag {
             flag: Flag::AF,
             value,
         })
@@ -51,7 +113,7 @@ impl<'arena> SemanticsBuilder<'arena> {
     }
 
     pub fn set_zf(&mut self, value: &'arena Expression<'arena>) {
-        self.semantics.push(InstructionSemanticsStep::SetFlag {
+        self.push(InstructionSemanticsStep::SetFlag {
             flag: Flag::ZF,
             value,
         })
@@ -61,7 +123,7 @@ impl<'arena> SemanticsBuilder<'arena> {
     }
 
     pub fn set_pf(&mut self, value: &'arena Expression<'arena>) {
-        self.semantics.push(InstructionSemanticsStep::SetFlag {
+        self.push(InstructionSemanticsStep::SetFlag {
             flag: Flag::PF,
             value,
         })
@@ -72,7 +134,7 @@ impl<'arena> SemanticsBuilder<'arena> {
     }
 
     pub fn set_sf(&mut self, value: &'arena Expression<'arena>) {
-        self.semantics.push(InstructionSemanticsStep::SetFlag {
+        self.push(InstructionSemanticsStep::SetFlag {
             flag: Flag::SF,
             value,
         })
@@ -83,7 +145,7 @@ impl<'arena> SemanticsBuilder<'arena> {
     }
 
     pub fn set_of(&mut self, value: &'arena Expression<'arena>) {
-        self.semantics.push(InstructionSemanticsStep::SetFlag {
+        self.push(InstructionSemanticsStep::SetFlag {
             flag: Flag::OF,
             value,
         })
@@ -95,12 +157,125 @@ impl<'arena> SemanticsBuilder<'arena> {
 
 
     pub fn get_flag(&self, flag: Flag) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::GetFlag {
+        self.arena.expr(Expression::GetFlag {
+            flag,
+            at_index: self.current_index()
+        })
+    }
+
+    pub fn set_flag(&mut self, flag: Flag, value: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::SetFlag {
             flag,
-            at_index: self.semantics.len()
+            value,
         })
     }
 
+    /// Sets the arithmetic flags the way `flag_tag`'s instruction family does for `left` op `right`, which are the
+    /// operands before any write the instruction makes.
+    pub fn flag_calculate(&mut self, flag_tag: FlagTag, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::CalculateFlags {
+            flag_tag,
+            left,
+            right,
+        })
+    }
+
+    /// The flags a `CalculateFlags` step for `flag_tag` sets, as expressions of its operands and `carry`, the CF
+    /// it starts out with. The same as the concrete semantics, undefined flags included. CF is left out for the
+    /// instructions which leave it alone.
+    pub fn flag_values(&self, flag_tag: &FlagTag, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>, carry: &'arena Expression<'arena>) -> Vec<(Flag, &'arena Expression<'arena>)> {
+        assert_eq!(left.width(), right.width());
+        let width = left.width();
+        let zero = self.constant_with_width(0, 1);
+        let one = self.constant_with_width(1, width);
+        let wide = |value: &'arena Expression<'arena>| self.zext_to(value, width + 1);
+        let msb = |value: &'arena Expression<'arena>| self.extract(value, width - 1, width);
+        let differ = |left: &'arena Expression<'arena>, right: &'arena Expression<'arena>| self.not(self.equal(left, right));
+        let adjust = |res: &'arena Expression<'arena>| self.extract(self.bitxor(self.bitxor(left, right), res), 4, 5);
+        let add_overflow = |res: &'arena Expression<'arena>| self.bitand(self.equal(msb(left), msb(right)), differ(msb(res), msb(left)));
+        let sub_overflow = |res: &'arena Expression<'arena>| self.bitand(differ(msb(left), msb(right)), differ(msb(res), msb(left)));
+        let (res, cf, of, af) = match flag_tag {
+            FlagTag::Add | FlagTag::Inc | FlagTag::Adc => {
+                let mut sum = self.add(wide(left), wide(right));
+                if matches!(flag_tag, FlagTag::Adc) {
+                    sum = self.add(sum, self.zext_to(carry, width + 1));
+                }
+                let res = self.extract(sum, 0, width);
+                let cf = (!matches!(flag_tag, FlagTag::Inc)).then(|| self.extract(sum, width, width + 1));
+                (res, cf, add_overflow(res), adjust(res))
+            }
+            FlagTag::Sub | FlagTag::Dec => {
+                let res = self.sub(left, right);
+                let cf = matches!(flag_tag, FlagTag::Sub).then(|| self.unsigned_less(left, right));
+                (res, cf, sub_overflow(res), adjust(res))
+            }
+            FlagTag::Sbb => {
+                let res = self.sub(self.sub(left, right), self.zext_to(carry, width));
+                let cf = self.unsigned_less(wide(left), self.add(wide(right), self.zext_to(carry, width + 1)));
+                (res, Some(cf), sub_overflow(res), adjust(res))
+            }
+            FlagTag::Mul | FlagTag::IMul => {
+                let extend = |value: &'arena Expression<'arena>| match flag_tag {
+                    FlagTag::Mul => self.zext_to(value, 2 * width),
+                    _ => self.sext_to(value, 2 * width),
+                };
+                let full = self.umul(extend(left), extend(right));
+                let res = self.extract(full, 0, width);
+                let overflow = differ(extend(res), full);
+                (res, Some(overflow), overflow, zero)
+            }
+            FlagTag::And => (self.bitand(left, right), Some(zero), zero, zero),
+            FlagTag::Or => (self.bitor(left, right), Some(zero), zero, zero),
+            FlagTag::Xor => (self.bitxor(left, right), Some(zero), zero, zero),
+            FlagTag::Shl => {
+                let res = self.shl(left, right);
+                let width_constant = self.constant_with_width(width as u64, width);
+                let in_range = self.not(self.unsigned_less(width_constant, right));
+                let cf = self.bitand(in_range, self.extract(self.lshr(left, self.sub(width_constant, right)), 0, 1));
+                (res, Some(cf), differ(msb(res), cf), zero)
+            }
+            FlagTag::Shr => {
+                let cf = self.extract(self.lshr(left, self.sub(right, one)), 0, 1);
+                (self.lshr(left, right), Some(cf), msb(left), zero)
+            }
+            FlagTag::Sar => {
+                let cf = self.extract(self.ashr(left, self.sub(right, one)), 0, 1);
+                (self.ashr(left, right), Some(cf), zero, zero)
+            }
+        };
+        let parity = (1..8).fold(self.extract(res, 0, 1), |parity, i| self.bitxor(parity, self.extract(res, i, i + 1)));
+        let mut flags = vec![];
+        if let Some(cf) = cf {
+            flags.push((Flag::CF, cf));
+        }
+        flags.extend([
+            (Flag::PF, self.not(parity)),
+            (Flag::AF, af),
+            (Flag::ZF, self.equal(res, self.constant_with_width(0, width))),
+            (Flag::SF, msb(res)),
+            (Flag::OF, of),
+        ]);
+        flags
+    }
+
+    /// RFLAGS as PUSHF stores it. Only the arithmetic flags are modelled, of the rest the reserved bit 1 and IF
+    /// are set, as they always are in user mode, and everything else is clear.
+    pub fn rflags(&self) -> &'arena Expression<'arena> {
+        RFLAGS_ARITHMETIC_BITS.iter().fold(self.constant(RFLAGS_FIXED_BITS), |acc, (flag, bit)| {
+            let flag = self.shl(self.zext_to(self.get_flag(*flag), 64), self.constant(*bit as u64));
+            self.bitor(acc, flag)
+        })
+    }
+
+    /// Sets the arithmetic flags from their bits of an RFLAGS image, which may be truncated to 16 bits as with
+    /// POPF. The other bits aren't modelled and are ignored.
+    pub fn set_arithmetic_flags_from_rflags(&mut self, value: &'arena Expression<'arena>) {
+        for (flag, bit) in RFLAGS_ARITHMETIC_BITS {
+            let flag_value = self.extract(value, bit, bit + 1);
+            self.set_flag(flag, flag_value);
+        }
+    }
+
     //reg 8s:
     pub fn al(&self) -> &'arena Expression<'arena> {
         self.get_reg_8(Reg8::AL)
@@ -120,15 +295,12 @@ impl<'arena> SemanticsBuilder<'arena> {
 
 
     pub fn get_reg_8(&self, reg: Reg8) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::GetReg { reg: GeneralReg::Reg8(reg), at_index: self.semantics.len() })
+        self.arena.expr(Expression::GetReg { reg: GeneralReg::Reg8(reg), at_index: self.current_index() })
     }
 
+    /// 8 bit writes leave the rest of the register alone.
     pub fn set_reg_8(&mut self, reg: Reg8, value: &'arena Expression<'arena>) {
-        self.semantics.push(InstructionSemanticsStep::SetRegister {
-            zero_upper: ZeroUpper::NoZeroUpper,
-            register: GeneralReg::Reg8(reg),
-            value,
-        })
+        self.merge_into_gpr64(Register::GP8(reg), value)
     }
 
     pub fn ax(&self) -> &'arena Expression<'arena> {
@@ -141,49 +313,155 @@ impl<'arena> SemanticsBuilder<'arena> {
 
 
     pub fn get_reg_16(&self, reg: Reg16WithRIP) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::GetReg { reg: GeneralReg::Reg16(reg), at_index: self.semantics.len() })
+        self.arena.expr(Expression::GetReg { reg: GeneralReg::Reg16(reg), at_index: self.current_index() })
     }
 
+    /// 16 bit writes leave the rest of the register alone.
     pub fn set_reg_16(&mut self, reg: Reg16WithRIP, value: &'arena Expression<'arena>) {
-        self.semantics.push(InstructionSemanticsStep::SetRegister {
+        self.merge_into_gpr64(Register::GP16(reg), value)
+    }
+
+    pub fn get_reg_32(&self, reg: Reg32WithRIP) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetReg { reg: GeneralReg::Reg32(reg), at_index: self.current_index() })
+    }
+
+    /// 32 bit writes zero the upper half of the 64 bit register.
+    pub fn set_reg_32(&mut self, reg: Reg32WithRIP, value: &'arena Expression<'arena>) {
+        let Register::GP64(parent) = Register::GP32(reg).parent() else {
+            panic!("32 bit register without a 64 bit parent: {reg:?}")
+        };
+        self.set_reg_64(parent, self.zext_to(value, 64))
+    }
+
+    /// The address of the next instruction, since RIP is advanced before anything else in an instruction.
+    pub fn rip(&self) -> &'arena Expression<'arena> {
+        self.get_reg_64(Reg64WithRIP::RIP)
+    }
+
+    pub fn set_rip(&mut self, value: &'arena Expression<'arena>) {
+        self.set_reg_64(Reg64WithRIP::RIP, value)
+    }
+
+    pub fn rsp(&self) -> &'arena Expression<'arena> {
+        self.get_reg_64(Reg64WithRIP::RSP)
+    }
+
+    pub fn set_rsp(&mut self, value: &'arena Expression<'arena>) {
+        self.set_reg_64(Reg64WithRIP::RSP, value)
+    }
+
+    pub fn rbp(&self) -> &'arena Expression<'arena> {
+        self.get_reg_64(Reg64WithRIP::RBP)
+    }
+
+    pub fn set_rbp(&mut self, value: &'arena Expression<'arena>) {
+        self.set_reg_64(Reg64WithRIP::RBP, value)
+    }
+
+    pub fn get_reg_64(&self, reg: Reg64WithRIP) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetReg { reg: GeneralReg::Reg64(reg), at_index: self.current_index() })
+    }
+
+    pub fn set_reg_64(&mut self, reg: Reg64WithRIP, value: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::SetRegister {
             zero_upper: ZeroUpper::NoZeroUpper,
-            register: GeneralReg::Reg16(reg),
+            register: GeneralReg::Reg64(reg),
             value,
         })
     }
 
+    fn merge_into_gpr64(&mut self, reg: Register, value: &'arena Expression<'arena>) {
+        let Register::GP64(parent) = reg.parent() else {
+            panic!("general register without a 64 bit parent: {reg:?}")
+        };
+        let range = reg.bit_range();
+        let merged = self.change(self.get_reg_64(parent), range.start as usize, range.end as usize, value);
+        self.set_reg_64(parent, merged)
+    }
+
+    pub fn get_zmm(&self, reg: RegZMM) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetVectorReg { reg, at_index: self.current_index() })
+    }
+
+    pub fn set_zmm(&mut self, reg: RegZMM, value: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::SetVectorRegister {
+            register: reg,
+            value,
+        })
+    }
+
+    pub fn get_xmm(&self, reg: RegXMM) -> &'arena Expression<'arena> {
+        self.lower_bits(self.get_zmm(reg.widen_to_zmm()), 128)
+    }
+
+    /// Legacy SSE write, bits 128 and up of the zmm register are left alone.
+    pub fn set_xmm(&mut self, reg: RegXMM, value: &'arena Expression<'arena>) {
+        let zmm = reg.widen_to_zmm();
+        let merged = self.change(self.get_zmm(zmm), 0, 128, value);
+        self.set_zmm(zmm, merged)
+    }
+
+    /// VEX/EVEX encoded write, bits 128 and up of the zmm register are zeroed.
+    pub fn set_xmm_vex(&mut self, reg: RegXMM, value: &'arena Expression<'arena>) {
+        self.set_zmm(reg.widen_to_zmm(), self.zext_to(value, 512))
+    }
+
+    pub fn get_ymm(&self, reg: RegYMM) -> &'arena Expression<'arena> {
+        self.lower_bits(self.get_zmm(reg.widen_to_zmm()), 256)
+    }
+
+    /// ymm registers are only written by VEX/EVEX encoded instructions, so bits 256 and up are zeroed.
+    pub fn set_ymm(&mut self, reg: RegYMM, value: &'arena Expression<'arena>) {
+        self.set_zmm(reg.widen_to_zmm(), self.zext_to(value, 512))
+    }
+
+
+    pub fn sext_to(&self, value: &'arena Expression<'arena>, width: usize) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::SignExtend {
+            value,
+            len: width,
+        })
+    }
 
     pub fn zext_to(&self, value: &'arena Expression<'arena>, width: usize) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::ZeroExtend {
+        self.arena.expr(Expression::ZeroExtend {
             value,
             len: width,
         })
     }
 
     pub fn extract(&self, value: &'arena Expression<'arena>, low: usize, high: usize) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::Extract {
+        self.arena.expr(Expression::Extract {
             value,
             low,
             high,
         })
     }
 
+    /// `high` ends up in the upper bits.
+    pub fn concat(&self, high: &'arena Expression<'arena>, low: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::Concat {
+            left: high,
+            right: low,
+        })
+    }
+
     pub fn lower_bits(&self, value: &'arena Expression<'arena>, width: usize) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::LowerBits {
+        self.arena.expr(Expression::LowerBits {
             value,
             len: width,
         })
     }
 
     pub fn upper_bits(&self, value: &'arena Expression<'arena>, width: usize) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::UpperBits {
+        self.arena.expr(Expression::UpperBits {
             value,
             len: width,
         })
     }
 
     pub fn equal(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::IntCompare {
+        self.arena.expr(Expression::IntCompare {
             op: ComparisonOp::Equal,
             signedness: Signedness::Signed,
             left,
@@ -191,7 +469,7 @@ impl<'arena> SemanticsBuilder<'arena> {
         })
     }
     pub fn less(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::IntCompare {
+        self.arena.expr(Expression::IntCompare {
             op: ComparisonOp::Less,
             signedness: Signedness::Signed,
             left,
@@ -199,8 +477,25 @@ impl<'arena> SemanticsBuilder<'arena> {
         })
     }
 
+    pub fn unsigned_less(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::IntCompare {
+            op: ComparisonOp::Less,
+            signedness: Signedness::Unsigned,
+            left,
+            right,
+        })
+    }
+
+    pub fn select(&self, condition: &'arena Expression<'arena>, true_value: &'arena Expression<'arena>, false_value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::Conditional {
+            condition,
+            true_value,
+            false_value,
+        })
+    }
+
     pub fn bitand(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::BitWise {
+        self.arena.expr(Expression::BitWise {
             op: BitWiseOp::And,
             left,
             right,
@@ -208,7 +503,7 @@ impl<'arena> SemanticsBuilder<'arena> {
     }
 
     pub fn add(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::IntArithmetic {
+        self.arena.expr(Expression::IntArithmetic {
             op: ArithmeticOp::Add,
             signedness: Signedness::Signed,
             left,
@@ -216,15 +511,300 @@ impl<'arena> SemanticsBuilder<'arena> {
         })
     }
 
-    pub fn fadd(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::FAdd {
+    pub fn sub(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::IntArithmetic {
+            op: ArithmeticOp::Sub,
+            signedness: Signedness::Signed,
+            left,
+            right,
+        })
+    }
+
+    fn arithmetic(&self, op: ArithmeticOp, signedness: Signedness, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::IntArithmetic {
+            op,
+            signedness,
+            left,
+            right,
+        })
+    }
+
+    pub fn smul(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arithmetic(ArithmeticOp::Mul, Signedness::Signed, left, right)
+    }
+
+    pub fn udiv(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arithmetic(ArithmeticOp::Div, Signedness::Unsigned, left, right)
+    }
+
+    pub fn sdiv(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arithmetic(ArithmeticOp::Div, Signedness::Signed, left, right)
+    }
+
+    pub fn urem(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arithmetic(ArithmeticOp::Rem, Signedness::Unsigned, left, right)
+    }
+
+    pub fn srem(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arithmetic(ArithmeticOp::Rem, Signedness::Signed, left, right)
+    }
+
+    fn shift(&self, op: ShiftOp, value: &'arena Expression<'arena>, amount: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::Shift {
+            op,
+            value,
+            amount,
+        })
+    }
+
+    pub fn shl(&self, value: &'arena Expression<'arena>, amount: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.shift(ShiftOp::Shl, value, amount)
+    }
+
+    pub fn lshr(&self, value: &'arena Expression<'arena>, amount: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.shift(ShiftOp::LShr, value, amount)
+    }
+
+    pub fn ashr(&self, value: &'arena Expression<'arena>, amount: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.shift(ShiftOp::AShr, value, amount)
+    }
+
+    fn bit_count(&self, op: BitCountOp, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::BitCount {
+            op,
+            value,
+        })
+    }
+
+    pub fn popcount(&self, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.bit_count(BitCountOp::PopCount, value)
+    }
+
+    pub fn leading_zeros(&self, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.bit_count(BitCountOp::LeadingZeros, value)
+    }
+
+    pub fn trailing_zeros(&self, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.bit_count(BitCountOp::TrailingZeros, value)
+    }
+
+    pub fn mxcsr(&self) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetMxcsr { at_index: self.current_index() })
+    }
+
+    pub fn set_mxcsr(&mut self, value: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::SetMxcsr {
+            value,
+        })
+    }
+
+    pub fn float_arithmetic(&self, op: FloatArithmeticOp, format: FloatFormat, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::FloatArithmetic {
+            op,
+            format,
+            left,
+            right,
+            mxcsr: self.mxcsr(),
+        })
+    }
+
+    pub fn float_sqrt(&self, format: FloatFormat, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::FloatSqrt {
+            format,
+            value,
+            mxcsr: self.mxcsr(),
+        })
+    }
+
+    pub fn float_fma(&self, format: FloatFormat, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>, addend: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::FloatFma {
+            format,
+            left,
+            right,
+            addend,
+            mxcsr: self.mxcsr(),
+        })
+    }
+
+    pub fn float_compare(&self, predicate: FloatPredicate, format: FloatFormat, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::FloatCompare {
+            predicate,
+            format,
+            left,
+            right,
+            mxcsr: self.mxcsr(),
+        })
+    }
+
+    pub fn float_convert(&self, from: FloatFormat, to: FloatFormat, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::FloatConvert {
+            from,
+            to,
+            value,
+            mxcsr: self.mxcsr(),
+        })
+    }
+
+    pub fn int_to_float(&self, format: FloatFormat, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::IntToFloat {
+            format,
+            value,
+            mxcsr: self.mxcsr(),
+        })
+    }
+
+    pub fn float_to_int(&self, format: FloatFormat, value: &'arena Expression<'arena>, width: usize, truncate: bool) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::FloatToInt {
+            format,
+            value,
+            width,
+            truncate,
+            mxcsr: self.mxcsr(),
+        })
+    }
+
+    /// Sets the MXCSR flags of every exception `operations` raise between them, or raises #XM if one is unmasked.
+    /// Must come before the instruction writes its results.
+    pub fn raise_float_exceptions(&mut self, operations: &[&'arena Expression<'arena>]) {
+        let exceptions = operations.iter().copied()
+            .map(|operation| self.float_exception_flags(operation))
+            .reduce(|left, right| self.bitor(left, right))
+            .expect("no floating point operations");
+        self.push(InstructionSemanticsStep::RaiseFloatExceptions {
+            exceptions,
+        })
+    }
+
+    /// `op` applied to each `format` wide lane of `left` and `right`, which are as wide as each other. Returns the
+    /// lanes put back together along with each lane's result, for [`Self::raise_float_exceptions`].
+    pub fn float_lanes(
+        &self,
+        format: FloatFormat,
+        left: &'arena Expression<'arena>,
+        right: &'arena Expression<'arena>,
+        op: impl Fn(&Self, &'arena Expression<'arena>, &'arena Expression<'arena>) -> &'arena Expression<'arena>,
+    ) -> (&'arena Expression<'arena>, Vec<&'arena Expression<'arena>>) {
+        assert_eq!(left.width(), right.width());
+        let lane_width = format.width();
+        let lanes = (0..left.width() / lane_width)
+            .map(|lane| {
+                let low = lane * lane_width;
+                op(self, self.extract(left, low, low + lane_width), self.extract(right, low, low + lane_width))
+            })
+            .collect::<Vec<_>>();
+        let res = lanes.iter().copied()
+            .reduce(|low, high| self.concat(high, low))
+            .expect("no lanes");
+        (res, lanes)
+    }
+
+    /// The exceptions `operation`, one of the floating point expressions, raises. Six bits, in the order of the
+    /// MXCSR flags, which is the order of the x87 status word flags as well.
+    pub fn float_exception_flags(&self, operation: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::FloatExceptionFlags { operation })
+    }
+
+    /// ST(`st`), relative to TOP as it is when read.
+    pub fn x87_register(&self, st: usize) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetX87Register { st, at_index: self.current_index() })
+    }
+
+    pub fn x87_register_empty(&self, st: usize) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::X87RegisterEmpty { st, at_index: self.current_index() })
+    }
+
+    pub fn set_x87_register(&mut self, st: usize, value: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::SetX87Register {
+            st,
+            value,
+        })
+    }
+
+    pub fn x87_push(&mut self) {
+        self.push(InstructionSemanticsStep::X87Push)
+    }
+
+    pub fn x87_pop(&mut self) {
+        self.push(InstructionSemanticsStep::X87Pop)
+    }
+
+    pub fn x87_control(&self) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetX87Control { at_index: self.current_index() })
+    }
+
+    pub fn set_x87_control(&mut self, value: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::SetX87Control {
+            value,
+        })
+    }
+
+    pub fn x87_status(&self) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetX87Status { at_index: self.current_index() })
+    }
+
+    pub fn set_x87_status(&mut self, value: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::SetX87Status {
+            value,
+        })
+    }
+
+    pub fn x87_arithmetic(&self, op: FloatArithmeticOp, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::X87Arithmetic {
+            op,
             left,
             right,
+            control: self.x87_control(),
+        })
+    }
+
+    pub fn x87_compare(&self, predicate: FloatPredicate, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::X87Compare {
+            predicate,
+            left,
+            right,
+        })
+    }
+
+    pub fn x87_from_float(&self, format: FloatFormat, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::X87FromFloat {
+            format,
+            value,
+        })
+    }
+
+    pub fn x87_to_float(&self, format: FloatFormat, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::X87ToFloat {
+            format,
+            value,
+            control: self.x87_control(),
+        })
+    }
+
+    pub fn x87_from_int(&self, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::X87FromInt {
+            value,
+        })
+    }
+
+    pub fn x87_to_int(&self, value: &'arena Expression<'arena>, width: usize) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::X87ToInt {
+            value,
+            width,
+            control: self.x87_control(),
         })
     }
 
+    pub fn x87_rounded_up(&self, operation: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::X87RoundedUp { operation })
+    }
+
+    /// #MF
+    pub fn floating_point_error(&mut self) {
+        self.push(InstructionSemanticsStep::FloatingPointError)
+    }
+
     pub fn bitor(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::BitWise {
+        self.arena.expr(Expression::BitWise {
             op: BitWiseOp::Or,
             left,
             right,
@@ -232,7 +812,7 @@ impl<'arena> SemanticsBuilder<'arena> {
     }
 
     pub fn bitxor(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::BitWise {
+        self.arena.expr(Expression::BitWise {
             op: BitWiseOp::Xor,
             left,
             right,
@@ -240,14 +820,32 @@ impl<'arena> SemanticsBuilder<'arena> {
     }
 
     pub fn constant<T: IntegerWidth>(&self, value: T) -> &'arena Expression<'arena> {
-        let value = self.arena.a(Value::new(bitvec![value.to_u64();T::width()]));
-        self.arena.a(Expression::Constant {
+        let value = self.arena.a(Value::from_u64(value.to_u64(), T::width()));
+        self.arena.expr(Expression::Constant {
+            value,
+        })
+    }
+
+    pub fn constant_with_width(&self, value: u64, width: usize) -> &'arena Expression<'arena> {
+        let value = self.arena.a(Value::from_u64(value, width));
+        self.arena.expr(Expression::Constant {
             value,
         })
     }
 
+    pub fn ones(&self, width: usize) -> &'arena Expression<'arena> {
+        let value = self.arena.a(Value::ones(width));
+        self.arena.expr(Expression::Constant {
+            value,
+        })
+    }
+
+    pub fn not(&self, value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.bitxor(value, self.ones(value.width()))
+    }
+
     pub fn umul(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::IntArithmetic {
+        self.arena.expr(Expression::IntArithmetic {
             op: ArithmeticOp::Mul,
             signedness: Signedness::Unsigned,
             left,
@@ -255,8 +853,168 @@ impl<'arena> SemanticsBuilder<'arena> {
         })
     }
 
+    pub fn segment_base(&self, base: RegSegmentBase) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::SegmentBase { base, at_index: self.current_index() })
+    }
+
+    pub fn get_segment(&self, segment: RegSegment) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetSegment { segment, at_index: self.current_index() })
+    }
+
+    /// Loads the 16 bit selector `value`, see [`InstructionSemanticsStep::SetSegment`].
+    pub fn set_segment(&mut self, segment: RegSegment, value: &'arena Expression<'arena>) {
+        assert_eq!(value.width(), 16);
+        self.push(InstructionSemanticsStep::SetSegment {
+            segment,
+            value,
+        })
+    }
+
+    /// Linear address of `operand`, 64 bits wide. Only fs and gs have a base in 64 bit mode, and the bases of the
+    /// other segments aren't modelled outside of it, so they are taken to be 0.
+    pub fn effective_address(&self, operand: &MemoryOperands) -> &'arena Expression<'arena> {
+        let address = self.address_offset(operand);
+        match operand {
+            MemoryOperands::SIBAddressing { segment: Some(RegSegment::FS), .. } => self.add(address, self.segment_base(RegSegmentBase::FSBase)),
+            MemoryOperands::SIBAddressing { segment: Some(RegSegment::GS), .. } => self.add(address, self.segment_base(RegSegmentBase::GSBase)),
+            _ => address,
+        }
+    }
+
+    /// Address of `operand` within its segment, as computed by LEA, zero extended to 64 bits. 32 bit address
+    /// computations wrap before being zero extended. RIP-relative operands are relative to the next instruction,
+    /// since RIP has already been advanced past the current one.
+    pub fn address_offset(&self, operand: &MemoryOperands) -> &'arena Expression<'arena> {
+        match operand {
+            MemoryOperands::SIBAddressing { segment: _, scale, index, base, disp, disp_width: _ } => {
+                let address_width = base.or(*index).map(|reg| reg.bit_width()).unwrap_or(64);
+                let mut address = self.constant_with_width(*disp as u64, address_width);
+                if let Some(base) = base {
+                    address = self.add(address, self.get_reg(*base));
+                }
+                if let Some(index) = index {
+                    let scaled = self.umul(self.get_reg(*index), self.constant_with_width(scale.to_xed() as u64, address_width));
+                    address = self.add(address, scaled);
+                }
+                self.zext_to(address, 64)
+            }
+        }
+    }
+
+    /// Little endian load of `width` bits, which must be a whole number of bytes.
+    pub fn load(&self, address: &'arena Expression<'arena>, width: usize) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::Load { address, width, at_index: self.current_index() })
+    }
+
+    pub fn store(&mut self, address: &'arena Expression<'arena>, value: &'arena Expression<'arena>) {
+        self.push(InstructionSemanticsStep::Store {
+            address,
+            value,
+        })
+    }
+
+    /// See [`X86Mode::stack_operand_width`].
+    pub fn stack_operand_width(&self) -> usize {
+        self.mode().stack_operand_width()
+    }
+
+    /// Width of the stack pointer, which is all of RSP in 64 bit mode. Otherwise it comes from the stack segment,
+    /// which is taken to match the code segment, as it does in a flat memory model.
+    pub fn stack_width(&self) -> usize {
+        self.stack_operand_width()
+    }
+
+    /// SP, ESP or RSP, whichever [`Self::stack_width`] says the stack is addressed with.
+    pub fn stack_pointer(&self) -> &'arena Expression<'arena> {
+        self.get_reg(sized_reg(Reg64WithRIP::RSP, self.stack_width()))
+    }
+
+    /// Writes SP, ESP or RSP, with the usual partial write rules, so a 16 bit stack leaves the rest of RSP alone.
+    pub fn set_stack_pointer(&mut self, value: &'arena Expression<'arena>) {
+        self.set_reg(sized_reg(Reg64WithRIP::RSP, self.stack_width()), value)
+    }
+
+    /// BP, EBP or RBP, for a `width` of 16, 32 or 64.
+    pub fn frame_pointer(&self, width: usize) -> &'arena Expression<'arena> {
+        self.get_reg(sized_reg(Reg64WithRIP::RBP, width))
+    }
+
+    pub fn set_frame_pointer(&mut self, width: usize, value: &'arena Expression<'arena>) {
+        self.set_reg(sized_reg(Reg64WithRIP::RBP, width), value)
+    }
+
+    /// Linear address of the stack pointer value `sp`. The stack segment has no base in 64 bit mode, and its base
+    /// isn't modelled outside of it.
+    pub fn stack_address(&self, sp: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        if sp.width() == 64 {
+            sp
+        } else {
+            self.zext_to(sp, 64)
+        }
+    }
+
+    /// Stores all of `value` below the stack pointer and moves the stack pointer down by its size. The stack
+    /// pointer is as wide as the stack, whatever the operand size.
+    pub fn push_stack(&mut self, value: &'arena Expression<'arena>) {
+        self.push_stack_slot(value, value.width());
+    }
+
+    /// Moves the stack pointer down by `width` bits and stores `value`, which may be narrower, at the bottom of
+    /// the slot. The rest of the slot keeps its contents.
+    pub fn push_stack_slot(&mut self, value: &'arena Expression<'arena>, width: usize) {
+        let new_sp = self.sub(self.stack_pointer(), self.constant_with_width((width / 8) as u64, self.stack_width()));
+        self.store(self.stack_address(new_sp), value);
+        self.set_stack_pointer(new_sp);
+    }
+
+    /// Loads `width` bits from the stack pointer and moves the stack pointer up past them.
+    pub fn pop_stack(&mut self, width: usize) -> &'arena Expression<'arena> {
+        let sp = self.stack_pointer();
+        let value = self.load(self.stack_address(sp), width);
+        self.set_stack_pointer(self.add(sp, self.constant_with_width((width / 8) as u64, self.stack_width())));
+        value
+    }
+
+    /// Operand size of a near branch with `displacement`. A 16 bit displacement comes with a 16 bit operand size,
+    /// and a 32 bit one with a 32 bit operand size outside of 64 bit mode. Otherwise it is the default.
+    pub fn relative_branch_width(&self, displacement: RelativeBr) -> usize {
+        match displacement {
+            RelativeBr::Disp16(_) => 16,
+            RelativeBr::Disp32(_) if self.mode() != X86Mode::_64Bit => 32,
+            RelativeBr::Disp8(_) | RelativeBr::Disp32(_) => self.stack_operand_width(),
+        }
+    }
+
+    /// Target of a relative branch, which is relative to the next instruction. The target is truncated to the
+    /// operand size, see [`Self::relative_branch_width`].
+    pub fn relative_branch_target(&self, displacement: RelativeBr) -> &'arena Expression<'arena> {
+        let offset = match displacement {
+            RelativeBr::Disp8(displacement) => displacement as i64,
+            RelativeBr::Disp16(displacement) => displacement as i64,
+            RelativeBr::Disp32(displacement) => displacement as i64,
+        };
+        let target = self.add(self.rip(), self.constant(offset as u64));
+        match self.relative_branch_width(displacement) {
+            64 => target,
+            width => self.zext_to(self.lower_bits(target, width), 64),
+        }
+    }
+
+    pub fn get_reg(&self, reg: GeneralReg) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::GetReg { reg, at_index: self.current_index() })
+    }
+
+    pub fn set_reg(&mut self, reg: GeneralReg, value: &'arena Expression<'arena>) {
+        match reg {
+            GeneralReg::Reg64(reg) => self.set_reg_64(reg, value),
+            GeneralReg::Reg32(reg) => self.set_reg_32(reg, value),
+            GeneralReg::Reg16(reg) => self.set_reg_16(reg, value),
+            GeneralReg::Reg8(reg) => self.set_reg_8(reg, value),
+        }
+    }
+
     pub fn change(&self, value: &'arena Expression<'arena>, range_start_inclusive: usize, range_end_exclusive: usize, new_value: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(Expression::ChangeRange {
+        self.arena.expr(Expression::ChangeRange {
             value,
             range_start_inclusive,
             range_end_exclusive,
@@ -270,28 +1028,61 @@ impl<'arena> SemanticsBuilder<'arena> {
         then: impl FnOnce(&mut SemanticsBuilder<'arena>),
         otherwise: impl FnOnce(&mut SemanticsBuilder<'arena>),
     ) {
-        let mut then_builder = SemanticsBuilder::new(self.arena);
+        let mut then_builder = SemanticsBuilder::starting_at(self.arena, self.current_index());
+        then_builder.memory_operand_width = self.memory_operand_width;
         then(&mut then_builder);
-        let mut otherwise_builder = SemanticsBuilder::new(self.arena);
+        let mut otherwise_builder = SemanticsBuilder::starting_at(self.arena, then_builder.current_index());
+        otherwise_builder.memory_operand_width = self.memory_operand_width;
         otherwise(&mut otherwise_builder);
-        self.semantics.push(InstructionSemanticsStep::Conditional {
+        self.push(InstructionSemanticsStep::Conditional {
             condition,
-            true_semantics: then_builder.finalize(),
-            false_semantics: otherwise_builder.finalize(),
+            true_semantics: then_builder.semantics,
+            false_semantics: otherwise_builder.semantics,
         });
     }
 
+    /// The steps emitted, simplified unless the arena says otherwise. See [`Arena::unsimplified`].
     pub fn finalize(self) -> Vec<InstructionSemanticsStep<'arena>> {
-        self.semantics
+        if self.arena.simplifies() {
+            simplify_steps(self.arena, self.semantics)
+        } else {
+            self.semantics
+        }
     }
 
     pub fn sync_uninterruptable(&mut self) {
-        self.semantics.push(InstructionSemanticsStep::InstructionSyncPoint {
+        self.push(InstructionSemanticsStep::InstructionSyncPoint {
             interruptable: false,
         })
     }
 
     pub fn a(&self, expr: Expression<'arena>) -> &'arena Expression<'arena> {
-        self.arena.a(expr)
+        self.arena.expr(expr)
+    }
+}
+
+/// Semantics of a form which only exists outside of 64 bit mode. In 64 bit mode it raises #UD instead of running
+/// `semantics`.
+pub(crate) fn legacy_mode_form<'arena>(
+    arena: Arena<'arena>,
+    semantics: impl FnOnce(Arena<'arena>) -> Vec<InstructionSemanticsStep<'arena>>,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    if arena.mode() == X86Mode::_64Bit {
+        let mut s = SemanticsBuilder::new(arena);
+        s.undefined_exception();
+        s.finalize()
+    } else {
+        semantics(arena)
+    }
+}
+
+/// The `width` bit register at the bottom of `reg`, which is RSP or RBP.
+fn sized_reg(reg: Reg64WithRIP, width: usize) -> GeneralReg {
+    match (reg, width) {
+        (Reg64WithRIP::RSP, 16) => GeneralReg::Reg16(Reg16WithRIP::SP),
+        (Reg64WithRIP::RSP, 32) => GeneralReg::Reg32(Reg32WithRIP::ESP),
+        (Reg64WithRIP::RBP, 16) => GeneralReg::Reg16(Reg16WithRIP::BP),
+        (Reg64WithRIP::RBP, 32) => GeneralReg::Reg32(Reg32WithRIP::EBP),
+        (reg, _) => GeneralReg::Reg64(reg),
     }
 }
@@ -0,0 +1,70 @@
+use xed_enum::{CALL_FAR, CALL_NEAR, X86Instruction};
+use xed_wrapper::operands::RelativeBr;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::Readable;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+pub fn call_relative_generic<'arena>(arena: Arena<'arena>, displacement: RelativeBr) -> Vec<InstructionSemanticsStep<'arena>> {
+    //tempRIP := RIP + DEST;
+    //Push(RIP); (* the return address, as wide as the operand size *)
+    //RIP := tempRIP;
+    let mut s = SemanticsBuilder::new(arena);
+    let width = s.relative_branch_width(displacement);
+    let target = s.relative_branch_target(displacement);
+    s.push_stack(s.lower_bits(s.rip(), width));
+    s.set_rip(target);
+    s.finalize()
+}
+
+pub fn call_indirect_generic<'arena, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //tempRIP := DEST; (* read before the push, the operand may be addressed relative to RSP *)
+    //Push(RIP);
+    //RIP := tempRIP;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let target = s.zext_to(readable1.read(&s), 64);
+    s.push_stack(s.lower_bits(s.rip(), width));
+    s.set_rip(target);
+    s.finalize()
+}
+
+pub fn apply_iform_call_near(arena: Arena, instr: CALL_NEAR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CALL_NEAR::CALL_NEAR_MEMV_16 { operand_0 } => {
+            call_indirect_generic(arena, operand_0, 16)
+        }
+        CALL_NEAR::CALL_NEAR_MEMV_32 { operand_0 } => {
+            call_indirect_generic(arena, operand_0, 32)
+        }
+        CALL_NEAR::CALL_NEAR_MEMV_64 { operand_0 } => {
+            call_indirect_generic(arena, operand_0, 64)
+        }
+        CALL_NEAR::CALL_NEAR_GPRV_16 { operand_0 } => {
+            call_indirect_generic(arena, operand_0, 16)
+        }
+        CALL_NEAR::CALL_NEAR_GPRV_32 { operand_0 } => {
+            call_indirect_generic(arena, operand_0, 32)
+        }
+        CALL_NEAR::CALL_NEAR_GPRV_64 { operand_0 } => {
+            call_indirect_generic(arena, operand_0, 64)
+        }
+        CALL_NEAR::CALL_NEAR_RELBRZ { operand_0 } => {
+            call_relative_generic(arena, operand_0)
+        }
+        CALL_NEAR::CALL_NEAR_RELBRD { operand_0 } => {
+            call_relative_generic(arena, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_call_far(_arena: Arena, instr: CALL_FAR) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    // far branches load cs, segment selectors are not modelled
+    Err(SemanticsError::Unsupported(X86Instruction::CALL_FAR(instr)))
+}
@@ -0,0 +1,398 @@
+use xed_enum::{CMOVB, CMOVBE, CMOVL, CMOVLE, CMOVNB, CMOVNBE, CMOVNL, CMOVNLE, CMOVNO, CMOVNP, CMOVNS, CMOVNZ, CMOVO, CMOVP, CMOVS, CMOVZ};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::condition::Condition;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn cmovcc_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    condition: Condition,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //temp := SRC
+    //IF condition
+    //    THEN DEST := temp;
+    //FI;
+    //The source is read and a 32 bit destination has its upper half cleared either way, so DEST is always written.
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let src = readable2.read(&s);
+    let res = s.select(s.condition(condition), src, dest);
+    writeable1.write(&mut s, res);
+    s.finalize()
+}
+
+
+pub fn apply_iform_cmovb(arena: Arena, instr: CMOVB) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVB::CMOVB_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::B, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVB::CMOVB_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::B, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVB::CMOVB_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::B, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVB::CMOVB_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::B, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVB::CMOVB_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::B, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVB::CMOVB_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::B, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovbe(arena: Arena, instr: CMOVBE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVBE::CMOVBE_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::BE, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVBE::CMOVBE_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::BE, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVBE::CMOVBE_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::BE, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVBE::CMOVBE_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::BE, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVBE::CMOVBE_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::BE, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVBE::CMOVBE_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::BE, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovl(arena: Arena, instr: CMOVL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVL::CMOVL_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::L, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVL::CMOVL_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::L, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVL::CMOVL_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::L, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVL::CMOVL_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::L, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVL::CMOVL_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::L, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVL::CMOVL_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::L, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovle(arena: Arena, instr: CMOVLE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVLE::CMOVLE_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::LE, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVLE::CMOVLE_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::LE, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVLE::CMOVLE_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::LE, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVLE::CMOVLE_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::LE, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVLE::CMOVLE_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::LE, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVLE::CMOVLE_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::LE, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovnb(arena: Arena, instr: CMOVNB) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVNB::CMOVNB_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NB, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNB::CMOVNB_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NB, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNB::CMOVNB_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NB, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVNB::CMOVNB_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NB, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNB::CMOVNB_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NB, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNB::CMOVNB_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NB, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovnbe(arena: Arena, instr: CMOVNBE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVNBE::CMOVNBE_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NBE, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNBE::CMOVNBE_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NBE, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNBE::CMOVNBE_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NBE, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVNBE::CMOVNBE_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NBE, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNBE::CMOVNBE_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NBE, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNBE::CMOVNBE_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NBE, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovnl(arena: Arena, instr: CMOVNL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVNL::CMOVNL_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NL, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNL::CMOVNL_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NL, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNL::CMOVNL_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NL, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVNL::CMOVNL_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NL, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNL::CMOVNL_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NL, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNL::CMOVNL_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NL, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovnle(arena: Arena, instr: CMOVNLE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVNLE::CMOVNLE_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NLE, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNLE::CMOVNLE_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NLE, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNLE::CMOVNLE_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NLE, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVNLE::CMOVNLE_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NLE, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNLE::CMOVNLE_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NLE, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNLE::CMOVNLE_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NLE, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovno(arena: Arena, instr: CMOVNO) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVNO::CMOVNO_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NO, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNO::CMOVNO_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NO, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNO::CMOVNO_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NO, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVNO::CMOVNO_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NO, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNO::CMOVNO_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NO, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNO::CMOVNO_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NO, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovnp(arena: Arena, instr: CMOVNP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVNP::CMOVNP_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NP, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNP::CMOVNP_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NP, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNP::CMOVNP_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NP, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVNP::CMOVNP_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NP, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNP::CMOVNP_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NP, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNP::CMOVNP_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NP, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovns(arena: Arena, instr: CMOVNS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVNS::CMOVNS_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NS, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNS::CMOVNS_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NS, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNS::CMOVNS_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NS, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVNS::CMOVNS_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NS, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNS::CMOVNS_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NS, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNS::CMOVNS_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NS, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovnz(arena: Arena, instr: CMOVNZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVNZ::CMOVNZ_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NZ, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNZ::CMOVNZ_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NZ, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNZ::CMOVNZ_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NZ, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVNZ::CMOVNZ_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NZ, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVNZ::CMOVNZ_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NZ, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVNZ::CMOVNZ_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::NZ, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovo(arena: Arena, instr: CMOVO) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVO::CMOVO_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::O, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVO::CMOVO_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::O, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVO::CMOVO_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::O, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVO::CMOVO_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::O, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVO::CMOVO_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::O, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVO::CMOVO_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::O, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovp(arena: Arena, instr: CMOVP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVP::CMOVP_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::P, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVP::CMOVP_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::P, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVP::CMOVP_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::P, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVP::CMOVP_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::P, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVP::CMOVP_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::P, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVP::CMOVP_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::P, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovs(arena: Arena, instr: CMOVS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVS::CMOVS_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::S, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVS::CMOVS_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::S, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVS::CMOVS_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::S, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVS::CMOVS_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::S, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVS::CMOVS_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::S, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVS::CMOVS_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::S, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_cmovz(arena: Arena, instr: CMOVZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMOVZ::CMOVZ_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::Z, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVZ::CMOVZ_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::Z, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVZ::CMOVZ_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::Z, operand_0, operand_0, operand_1, 64)
+        }
+        CMOVZ::CMOVZ_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::Z, operand_0, operand_0, operand_1, 16)
+        }
+        CMOVZ::CMOVZ_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::Z, operand_0, operand_0, operand_1, 32)
+        }
+        CMOVZ::CMOVZ_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            cmovcc_generic(arena, Condition::Z, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
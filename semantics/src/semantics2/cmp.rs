@@ -0,0 +1,136 @@
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8};
+use xed_enum::CMP;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+pub fn cmp_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //temp := SRC1 − SignExtend(SRC2);
+    //ModifyStatusFlags; (* Modify status flags in the same manner as the SUB instruction*)
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let src1 = readable1.read(&s);
+    let src2 = s.sext_to(readable2.read(&s), width);
+    s.flag_calculate(FlagTag::Sub, src1, src2);
+    s.finalize()
+}
+
+pub fn apply_iform_cmp(arena: Arena, instr: CMP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMP::CMP_MEMB_IMMB_80R7 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 8)
+        }
+        CMP::CMP_GPR8_IMMB_80R7 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 8)
+        }
+        CMP::CMP_MEMV_IMMZ_16 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 16)
+        }
+        CMP::CMP_MEMV_IMMZ_32 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 32)
+        }
+        CMP::CMP_MEMV_IMMZ_64 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 64)
+        }
+        CMP::CMP_GPRV_IMMZ_16 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 16)
+        }
+        CMP::CMP_GPRV_IMMZ_32 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 32)
+        }
+        CMP::CMP_GPRV_IMMZ_64 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 64)
+        }
+        CMP::CMP_MEMB_IMMB_82R7 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 8)
+        }
+        CMP::CMP_GPR8_IMMB_82R7 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 8)
+        }
+        CMP::CMP_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 16)
+        }
+        CMP::CMP_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 32)
+        }
+        CMP::CMP_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 64)
+        }
+        CMP::CMP_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 16)
+        }
+        CMP::CMP_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 32)
+        }
+        CMP::CMP_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 64)
+        }
+        CMP::CMP_MEMB_GPR8 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 8)
+        }
+        CMP::CMP_GPR8_GPR8_38 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 8)
+        }
+        CMP::CMP_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 16)
+        }
+        CMP::CMP_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 32)
+        }
+        CMP::CMP_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 64)
+        }
+        CMP::CMP_GPRV_GPRV_39_16 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 16)
+        }
+        CMP::CMP_GPRV_GPRV_39_32 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 32)
+        }
+        CMP::CMP_GPRV_GPRV_39_64 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 64)
+        }
+        CMP::CMP_GPR8_MEMB { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 8)
+        }
+        CMP::CMP_GPR8_GPR8_3A { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 8)
+        }
+        CMP::CMP_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 16)
+        }
+        CMP::CMP_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 32)
+        }
+        CMP::CMP_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 64)
+        }
+        CMP::CMP_GPRV_GPRV_3B_16 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 16)
+        }
+        CMP::CMP_GPRV_GPRV_3B_32 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 32)
+        }
+        CMP::CMP_GPRV_GPRV_3B_64 { operand_0, operand_1 } => {
+            cmp_generic(arena, operand_0, operand_1, 64)
+        }
+        CMP::CMP_AL_IMMB { operand_0 } => {
+            cmp_generic(arena, Reg8::AL, operand_0, 8)
+        }
+        CMP::CMP_ORAX_IMMZ_16 { operand_0 } => {
+            cmp_generic(arena, Reg16WithRIP::AX, operand_0, 16)
+        }
+        CMP::CMP_ORAX_IMMZ_32 { operand_0 } => {
+            cmp_generic(arena, Reg32WithRIP::EAX, operand_0, 32)
+        }
+        CMP::CMP_ORAX_IMMZ_64 { operand_0 } => {
+            cmp_generic(arena, Reg64WithRIP::RAX, operand_0, 64)
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::Expression;
+
+/// The condition codes of Jcc, SETcc and CMOVcc, using xed's names.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Condition {
+    O,
+    NO,
+    /// CF = 1
+    B,
+    NB,
+    Z,
+    NZ,
+    /// CF = 1 or ZF = 1
+    BE,
+    NBE,
+    S,
+    NS,
+    P,
+    NP,
+    /// SF != OF
+    L,
+    NL,
+    /// ZF = 1 or SF != OF
+    LE,
+    NLE,
+}
+
+impl<'arena> SemanticsBuilder<'arena> {
+    /// One bit, set when `condition` holds for the current flags.
+    pub fn condition(&self, condition: Condition) -> &'arena Expression<'arena> {
+        match condition {
+            Condition::O => self.of(),
+            Condition::NO => self.not(self.of()),
+            Condition::B => self.cf(),
+            Condition::NB => self.not(self.cf()),
+            Condition::Z => self.zf(),
+            Condition::NZ => self.not(self.zf()),
+            Condition::BE => self.bitor(self.cf(), self.zf()),
+            Condition::NBE => self.not(self.bitor(self.cf(), self.zf())),
+            Condition::S => self.sf(),
+            Condition::NS => self.not(self.sf()),
+            Condition::P => self.pf(),
+            Condition::NP => self.not(self.pf()),
+            Condition::L => self.bitxor(self.sf(), self.of()),
+            Condition::NL => self.not(self.bitxor(self.sf(), self.of())),
+            Condition::LE => self.bitor(self.zf(), self.bitxor(self.sf(), self.of())),
+            Condition::NLE => self.not(self.bitor(self.zf(), self.bitxor(self.sf(), self.of()))),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use xed_enum::DEC;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::{legacy_mode_form, SemanticsBuilder};
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+pub fn dec_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := DEST – 1;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let one = s.constant_with_width(1, width);
+    let res = s.sub(dest, one);
+    writeable1.write(&mut s, res);
+    s.flag_calculate(FlagTag::Dec, dest, one);
+    s.finalize()
+}
+
+pub fn apply_iform_dec(arena: Arena, instr: DEC) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        DEC::DEC_MEMB { operand_0 } => {
+            dec_generic(arena, operand_0, operand_0, 8)
+        }
+        DEC::DEC_GPR8 { operand_0 } => {
+            dec_generic(arena, operand_0, operand_0, 8)
+        }
+        DEC::DEC_MEMV_16 { operand_0 } => {
+            dec_generic(arena, operand_0, operand_0, 16)
+        }
+        DEC::DEC_MEMV_32 { operand_0 } => {
+            dec_generic(arena, operand_0, operand_0, 32)
+        }
+        DEC::DEC_MEMV_64 { operand_0 } => {
+            dec_generic(arena, operand_0, operand_0, 64)
+        }
+        DEC::DEC_GPRV_FFR1_16 { operand_0 } => {
+            dec_generic(arena, operand_0, operand_0, 16)
+        }
+        DEC::DEC_GPRV_FFR1_32 { operand_0 } => {
+            dec_generic(arena, operand_0, operand_0, 32)
+        }
+        DEC::DEC_GPRV_FFR1_64 { operand_0 } => {
+            dec_generic(arena, operand_0, operand_0, 64)
+        }
+        DEC::DEC_GPRV_48_16 { operand_0 } => {
+            // 0x48 to 0x4F are REX prefixes in 64 bit mode
+            legacy_mode_form(arena, |arena| dec_generic(arena, operand_0, operand_0, 16))
+        }
+        DEC::DEC_GPRV_48_32 { operand_0 } => {
+            // 0x48 to 0x4F are REX prefixes in 64 bit mode
+            legacy_mode_form(arena, |arena| dec_generic(arena, operand_0, operand_0, 32))
+        }
+    }
+}
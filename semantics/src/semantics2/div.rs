@@ -0,0 +1,109 @@
+use xed_enum::{DIV, IDIV};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::Signedness;
+use crate::semantics2::read_write::{accumulator, accumulator_high, Readable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn div_generic<'arena, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+    width: usize,
+    signedness: Signedness,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF SRC = 0
+    //    THEN #DE; (* Divide Error *)
+    //FI;
+    //temp := DX:AX / SRC; (* or AX, EDX:EAX, RDX:RAX *)
+    //IF temp does not fit in the operand size
+    //    THEN #DE; (* Divide error *)
+    //    ELSE
+    //        AX := temp;
+    //        DX := DX:AX MOD SRC;
+    //FI;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let low = accumulator(width);
+    let high = accumulator_high(width);
+    let dividend = s.concat(s.get_reg(high), s.get_reg(low));
+    let divisor = readable1.read(&s);
+    let (quotient, remainder, quotient_fits) = match signedness {
+        Signedness::Unsigned => {
+            let divisor = s.zext_to(divisor, width * 2);
+            let quotient = s.udiv(dividend, divisor);
+            let fits = s.equal(s.zext_to(s.lower_bits(quotient, width), width * 2), quotient);
+            (quotient, s.urem(dividend, divisor), fits)
+        }
+        Signedness::Signed => {
+            let divisor = s.sext_to(divisor, width * 2);
+            let quotient = s.sdiv(dividend, divisor);
+            let fits = s.equal(s.sext_to(s.lower_bits(quotient, width), width * 2), quotient);
+            (quotient, s.srem(dividend, divisor), fits)
+        }
+    };
+    // evaluating quotient_fits raises #DE on its own for a zero divisor
+    s.emit_conditional(quotient_fits, |s| {
+        s.set_reg(low, s.lower_bits(quotient, width));
+        s.set_reg(high, s.lower_bits(remainder, width));
+    }, |s| s.divide_error());
+    s.finalize()
+}
+
+pub fn apply_iform_div(arena: Arena, instr: DIV) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        DIV::DIV_MEMB { operand_0 } => {
+            div_generic(arena, operand_0, 8, Signedness::Unsigned)
+        }
+        DIV::DIV_GPR8 { operand_0 } => {
+            div_generic(arena, operand_0, 8, Signedness::Unsigned)
+        }
+        DIV::DIV_MEMV_16 { operand_0 } => {
+            div_generic(arena, operand_0, 16, Signedness::Unsigned)
+        }
+        DIV::DIV_MEMV_32 { operand_0 } => {
+            div_generic(arena, operand_0, 32, Signedness::Unsigned)
+        }
+        DIV::DIV_MEMV_64 { operand_0 } => {
+            div_generic(arena, operand_0, 64, Signedness::Unsigned)
+        }
+        DIV::DIV_GPRV_16 { operand_0 } => {
+            div_generic(arena, operand_0, 16, Signedness::Unsigned)
+        }
+        DIV::DIV_GPRV_32 { operand_0 } => {
+            div_generic(arena, operand_0, 32, Signedness::Unsigned)
+        }
+        DIV::DIV_GPRV_64 { operand_0 } => {
+            div_generic(arena, operand_0, 64, Signedness::Unsigned)
+        }
+    }
+}
+
+pub fn apply_iform_idiv(arena: Arena, instr: IDIV) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        IDIV::IDIV_MEMB { operand_0 } => {
+            div_generic(arena, operand_0, 8, Signedness::Signed)
+        }
+        IDIV::IDIV_GPR8 { operand_0 } => {
+            div_generic(arena, operand_0, 8, Signedness::Signed)
+        }
+        IDIV::IDIV_MEMV_16 { operand_0 } => {
+            div_generic(arena, operand_0, 16, Signedness::Signed)
+        }
+        IDIV::IDIV_MEMV_32 { operand_0 } => {
+            div_generic(arena, operand_0, 32, Signedness::Signed)
+        }
+        IDIV::IDIV_MEMV_64 { operand_0 } => {
+            div_generic(arena, operand_0, 64, Signedness::Signed)
+        }
+        IDIV::IDIV_GPRV_16 { operand_0 } => {
+            div_generic(arena, operand_0, 16, Signedness::Signed)
+        }
+        IDIV::IDIV_GPRV_32 { operand_0 } => {
+            div_generic(arena, operand_0, 32, Signedness::Signed)
+        }
+        IDIV::IDIV_GPRV_64 { operand_0 } => {
+            div_generic(arena, operand_0, 64, Signedness::Signed)
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use xed_enum::ENTER;
+use xed_wrapper::operands::{Imm16, Imm8};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn enter_generic(arena: Arena, size: Imm16, nesting_level: Imm8) -> Vec<InstructionSemanticsStep> {
+    //AllocSize := imm16;
+    //NestingLevel := imm8 MOD 32;
+    //Push(RBP);
+    //FrameTemp := RSP;
+    //IF NestingLevel > 0
+    //    THEN
+    //        FOR i := 1 to (NestingLevel - 1)
+    //            DO
+    //                RBP := RBP - 8;
+    //                Push([RBP]);
+    //        OD;
+    //        Push(FrameTemp);
+    //FI;
+    //RBP := FrameTemp;
+    //RSP := RSP - AllocSize;
+    let mut s = SemanticsBuilder::new(arena);
+    let width = s.stack_operand_width();
+    let alloc_size = size.0 as u16 as u64;
+    let nesting_level = (nesting_level.0 as u8 % 32) as u64;
+    let old_rbp = s.frame_pointer(width);
+    s.push_stack(old_rbp);
+    let frame_temp = s.stack_pointer();
+    if nesting_level > 0 {
+        // the level is an immediate, so the loop unrolls. RBP is only written at the end, it would be overwritten
+        // with FrameTemp anyway.
+        for i in 1..nesting_level {
+            let address = s.sub(old_rbp, s.constant_with_width(i * (width / 8) as u64, width));
+            let frame_pointer = s.load(s.stack_address(address), width);
+            s.push_stack(frame_pointer);
+        }
+        s.push_stack(frame_temp);
+    }
+    s.set_frame_pointer(width, frame_temp);
+    s.set_stack_pointer(s.sub(s.stack_pointer(), s.constant_with_width(alloc_size, s.stack_width())));
+    s.finalize()
+}
+
+pub fn apply_iform_enter(arena: Arena, instr: ENTER) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        ENTER::ENTER_IMMW_IMMB { operand_0, operand_1 } => {
+            enter_generic(arena, operand_0, operand_1)
+        }
+    }
+}
@@ -1,23 +1,25 @@
 use wrapper_common::memory_operand::GeneralReg;
-use crate::semantics2::arena::Arena;
-use crate::semantics2::state::ConcreteX86MachineState64;
+use wrapper_common::registers::{RegSegment, RegSegmentBase, RegZMM};
+use crate::semantics2::float::{float_arithmetic, float_compare, float_convert, float_fma, float_sqrt, float_to_int, int_to_float, FloatArithmeticOp, FloatExceptions, FloatFormat, FloatPredicate, Mxcsr};
+use crate::semantics2::semantic_steps::{ConcreteExecution, Fault};
 
 use crate::semantics2::value::Value;
+use crate::semantics2::x87_float::{x87_arithmetic, x87_compare, x87_from_float, x87_from_int, x87_to_float, x87_to_int, X87ControlWord};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Signedness {
     Signed,
     Unsigned,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub enum BitWiseOp {
     And,
     Or,
     Xor,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ComparisonOp {
     Less,
     LessOrEqual,
@@ -26,7 +28,7 @@ pub enum ComparisonOp {
     Greater,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Flag {
     CF,
     PF,
@@ -36,15 +38,31 @@ pub enum Flag {
     OF,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ArithmeticOp {
     Add,
     Sub,
     Mul,
     Div,
+    /// Takes the sign of the dividend, like x86's IDIV.
+    Rem,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ShiftOp {
+    Shl,
+    LShr,
+    AShr,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BitCountOp {
+    PopCount,
+    LeadingZeros,
+    TrailingZeros,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Expression<'arena> {
     GetReg {
         reg: GeneralReg,
@@ -54,6 +72,11 @@ pub enum Expression<'arena> {
         flag: Flag,
         at_index: usize
     },
+    /// All 512 bits, narrower vector registers are the low bits of this.
+    GetVectorReg {
+        reg: RegZMM,
+        at_index: usize
+    },
     Constant {
         value: &'arena Value<'arena>,
     },
@@ -74,11 +97,13 @@ pub enum Expression<'arena> {
         left: &'arena Expression<'arena>,
         right: &'arena Expression<'arena>,
     },
+    /// Bits `low` up to but not including `high`.
     Extract {
         value: &'arena Expression<'arena>,
         low: usize,
         high: usize,
     },
+    /// `left` ends up in the upper bits.
     Concat {
         left: &'arena Expression<'arena>,
         right: &'arena Expression<'arena>,
@@ -95,6 +120,21 @@ pub enum Expression<'arena> {
         value: &'arena Expression<'arena>,
         len: usize,
     },
+    SignExtend {
+        value: &'arena Expression<'arena>,
+        len: usize,
+    },
+    /// `amount` is unsigned and can be any width. Shifting by the width of `value` or more shifts out every bit.
+    Shift {
+        op: ShiftOp,
+        value: &'arena Expression<'arena>,
+        amount: &'arena Expression<'arena>,
+    },
+    /// As wide as `value`.
+    BitCount {
+        op: BitCountOp,
+        value: &'arena Expression<'arena>,
+    },
     LowerBits {
         value: &'arena Expression<'arena>,
         len: usize,
@@ -104,7 +144,133 @@ pub enum Expression<'arena> {
         len: usize,
     },
     ChangeRange { value: &'arena Expression<'arena>, range_start_inclusive: usize, range_end_exclusive: usize, new_value: &'arena Expression<'arena> },
-    FAdd { left: &'arena Expression<'arena>, right: &'arena Expression<'arena> },
+    /// IEEE 754 arithmetic, rounded and with denormals handled the way `mxcsr`, the 32 bit MXCSR, says. Like all
+    /// the floating point expressions this only gives the result, the exceptions raised are [`Expression::FloatExceptionFlags`].
+    FloatArithmetic {
+        op: FloatArithmeticOp,
+        format: FloatFormat,
+        left: &'arena Expression<'arena>,
+        right: &'arena Expression<'arena>,
+        mxcsr: &'arena Expression<'arena>,
+    },
+    FloatSqrt {
+        format: FloatFormat,
+        value: &'arena Expression<'arena>,
+        mxcsr: &'arena Expression<'arena>,
+    },
+    /// `left * right + addend`, rounded once.
+    FloatFma {
+        format: FloatFormat,
+        left: &'arena Expression<'arena>,
+        right: &'arena Expression<'arena>,
+        addend: &'arena Expression<'arena>,
+        mxcsr: &'arena Expression<'arena>,
+    },
+    /// One bit, whether `left` and `right` compare the way `predicate` says.
+    FloatCompare {
+        predicate: FloatPredicate,
+        format: FloatFormat,
+        left: &'arena Expression<'arena>,
+        right: &'arena Expression<'arena>,
+        mxcsr: &'arena Expression<'arena>,
+    },
+    FloatConvert {
+        from: FloatFormat,
+        to: FloatFormat,
+        value: &'arena Expression<'arena>,
+        mxcsr: &'arena Expression<'arena>,
+    },
+    /// `value` is a signed integer of at most 64 bits.
+    IntToFloat {
+        format: FloatFormat,
+        value: &'arena Expression<'arena>,
+        mxcsr: &'arena Expression<'arena>,
+    },
+    /// A `width` bit signed integer, rounded the way `mxcsr` says unless `truncate`.
+    FloatToInt {
+        format: FloatFormat,
+        value: &'arena Expression<'arena>,
+        width: usize,
+        truncate: bool,
+        mxcsr: &'arena Expression<'arena>,
+    },
+    GetMxcsr {
+        at_index: usize,
+    },
+    /// Six bits, the exceptions `operation` raises in the order of the MXCSR flags. `operation` must be one of the
+    /// floating point expressions.
+    FloatExceptionFlags {
+        operation: &'arena Expression<'arena>,
+    },
+    /// ST(`st`) as of `at_index`, 80 bits. Which physical register that is depends on TOP at the time.
+    GetX87Register {
+        st: usize,
+        at_index: usize,
+    },
+    /// One bit, whether the tag word marks ST(`st`) empty.
+    X87RegisterEmpty {
+        st: usize,
+        at_index: usize,
+    },
+    GetX87Control {
+        at_index: usize,
+    },
+    /// The whole status word, TOP included.
+    GetX87Status {
+        at_index: usize,
+    },
+    /// Arithmetic on 80 bit values, rounded the way `control`, the x87 control word, says. Min and max aren't
+    /// allowed. Like the other floating point expressions the exceptions are [`Expression::FloatExceptionFlags`].
+    X87Arithmetic {
+        op: FloatArithmeticOp,
+        left: &'arena Expression<'arena>,
+        right: &'arena Expression<'arena>,
+        control: &'arena Expression<'arena>,
+    },
+    X87Compare {
+        predicate: FloatPredicate,
+        left: &'arena Expression<'arena>,
+        right: &'arena Expression<'arena>,
+    },
+    /// Exact widening of a binary32 or binary64 value to 80 bits.
+    X87FromFloat {
+        format: FloatFormat,
+        value: &'arena Expression<'arena>,
+    },
+    X87ToFloat {
+        format: FloatFormat,
+        value: &'arena Expression<'arena>,
+        control: &'arena Expression<'arena>,
+    },
+    /// `value` is a signed integer of any width up to 64 bits, the conversion is exact.
+    X87FromInt {
+        value: &'arena Expression<'arena>,
+    },
+    X87ToInt {
+        value: &'arena Expression<'arena>,
+        width: usize,
+        control: &'arena Expression<'arena>,
+    },
+    /// One bit, whether `operation`, one of the x87 floating point expressions, rounded the magnitude of its result
+    /// up. This is what C1 reports.
+    X87RoundedUp {
+        operation: &'arena Expression<'arena>,
+    },
+    /// Memory as of `at_index`, like `GetReg`.
+    Load {
+        address: &'arena Expression<'arena>,
+        width: usize,
+        at_index: usize,
+    },
+    SegmentBase {
+        base: RegSegmentBase,
+        at_index: usize,
+    },
+    /// The 16 bit selector of `segment`, as of `at_index` like `GetReg`.
+    GetSegment {
+        segment: RegSegment,
+        at_index: usize,
+    },
 }
 
 impl<'arena> Expression<'arena> {
@@ -112,7 +278,7 @@ impl<'arena> Expression<'arena> {
         match self {
             Expression::Constant { value } => value.width(),
             Expression::BitWise { left, right, .. } => left.width().max(right.width()),
-            Expression::Extract { value, low, high } => high - low + 1,
+            Expression::Extract { value, low, high } => high - low,
             Expression::Concat { left, right } => left.width() + right.width(),
             // Expression::Variable { name } => .inner.get(name).unwrap().width(),
             Expression::Conditional { condition, true_value, false_value } => {
@@ -129,9 +295,21 @@ impl<'arena> Expression<'arena> {
             Expression::GetFlag { .. } => {
                 1
             }
+            Expression::GetVectorReg { .. } => {
+                512
+            }
             Expression::ZeroExtend { value, len } => {
                 *len
             }
+            Expression::SignExtend { value, len } => {
+                *len
+            }
+            Expression::Shift { value, .. } => {
+                value.width()
+            }
+            Expression::BitCount { value, .. } => {
+                value.width()
+            }
             Expression::LowerBits { value, len } => {
                 *len
             }
@@ -142,59 +320,355 @@ impl<'arena> Expression<'arena> {
                 value.width()
             }
             Expression::IntArithmetic { op, signedness, left, right } => {
-                todo!()
+                left.width().max(right.width())
+            }
+            Expression::FloatArithmetic { format, .. } |
+            Expression::FloatSqrt { format, .. } |
+            Expression::FloatFma { format, .. } |
+            Expression::IntToFloat { format, .. } => {
+                format.width()
             }
-            Expression::FAdd { left, right } => {
-                todo!()
+            Expression::FloatCompare { .. } => {
+                1
+            }
+            Expression::FloatConvert { to, .. } => {
+                to.width()
+            }
+            Expression::FloatToInt { width, .. } => {
+                *width
+            }
+            Expression::GetMxcsr { .. } => {
+                32
+            }
+            Expression::FloatExceptionFlags { .. } => {
+                6
+            }
+            Expression::GetX87Register { .. } |
+            Expression::X87Arithmetic { .. } |
+            Expression::X87FromFloat { .. } |
+            Expression::X87FromInt { .. } => {
+                80
+            }
+            Expression::X87RegisterEmpty { .. } |
+            Expression::X87Compare { .. } |
+            Expression::X87RoundedUp { .. } => {
+                1
+            }
+            Expression::GetX87Control { .. } |
+            Expression::GetX87Status { .. } => {
+                16
+            }
+            Expression::X87ToFloat { format, .. } => {
+                format.width()
+            }
+            Expression::X87ToInt { width, .. } => {
+                *width
+            }
+            Expression::Load { width, .. } => {
+                *width
+            }
+            Expression::SegmentBase { .. } => {
+                64
+            }
+            Expression::GetSegment { .. } => {
+                16
             }
         }
     }
 
-    pub fn apply_concrete(&self, concrete: &ConcreteX86MachineState64) -> Value{
-        match self {
-            Expression::GetReg { reg, at_index } => {
-                concrete.get_reg(*reg, *at_index)
+    /// This expression with each child replaced by `f` of it, in the order the fields are declared.
+    pub fn map_children(&self, mut f: impl FnMut(&'arena Expression<'arena>) -> &'arena Expression<'arena>) -> Expression<'arena> {
+        match *self {
+            Expression::GetReg { .. } |
+            Expression::GetFlag { .. } |
+            Expression::GetVectorReg { .. } |
+            Expression::Constant { .. } |
+            Expression::GetMxcsr { .. } |
+            Expression::GetX87Register { .. } |
+            Expression::X87RegisterEmpty { .. } |
+            Expression::GetX87Control { .. } |
+            Expression::GetX87Status { .. } |
+            Expression::SegmentBase { .. } |
+            Expression::GetSegment { .. } => *self,
+            Expression::BitWise { op, left, right } => Expression::BitWise { op, left: f(left), right: f(right) },
+            Expression::IntCompare { op, signedness, left, right } => Expression::IntCompare { op, signedness, left: f(left), right: f(right) },
+            Expression::IntArithmetic { op, signedness, left, right } => Expression::IntArithmetic { op, signedness, left: f(left), right: f(right) },
+            Expression::Extract { value, low, high } => Expression::Extract { value: f(value), low, high },
+            Expression::Concat { left, right } => Expression::Concat { left: f(left), right: f(right) },
+            Expression::Conditional { condition, true_value, false_value } => {
+                Expression::Conditional { condition: f(condition), true_value: f(true_value), false_value: f(false_value) }
             }
-            Expression::GetFlag { .. } => {
-
+            Expression::ZeroExtend { value, len } => Expression::ZeroExtend { value: f(value), len },
+            Expression::SignExtend { value, len } => Expression::SignExtend { value: f(value), len },
+            Expression::Shift { op, value, amount } => Expression::Shift { op, value: f(value), amount: f(amount) },
+            Expression::BitCount { op, value } => Expression::BitCount { op, value: f(value) },
+            Expression::LowerBits { value, len } => Expression::LowerBits { value: f(value), len },
+            Expression::UpperBits { value, len } => Expression::UpperBits { value: f(value), len },
+            Expression::ChangeRange { value, range_start_inclusive, range_end_exclusive, new_value } => {
+                Expression::ChangeRange { value: f(value), range_start_inclusive, range_end_exclusive, new_value: f(new_value) }
             }
-            Expression::Constant { .. } => {
-
+            Expression::FloatArithmetic { op, format, left, right, mxcsr } => {
+                Expression::FloatArithmetic { op, format, left: f(left), right: f(right), mxcsr: f(mxcsr) }
             }
-            Expression::BitWise { .. } => {
-
+            Expression::FloatSqrt { format, value, mxcsr } => Expression::FloatSqrt { format, value: f(value), mxcsr: f(mxcsr) },
+            Expression::FloatFma { format, left, right, addend, mxcsr } => {
+                Expression::FloatFma { format, left: f(left), right: f(right), addend: f(addend), mxcsr: f(mxcsr) }
             }
-            Expression::IntCompare { .. } => {
-
+            Expression::FloatCompare { predicate, format, left, right, mxcsr } => {
+                Expression::FloatCompare { predicate, format, left: f(left), right: f(right), mxcsr: f(mxcsr) }
             }
-            Expression::IntArithmetic { .. } => {
-
+            Expression::FloatConvert { from, to, value, mxcsr } => Expression::FloatConvert { from, to, value: f(value), mxcsr: f(mxcsr) },
+            Expression::IntToFloat { format, value, mxcsr } => Expression::IntToFloat { format, value: f(value), mxcsr: f(mxcsr) },
+            Expression::FloatToInt { format, value, width, truncate, mxcsr } => {
+                Expression::FloatToInt { format, value: f(value), width, truncate, mxcsr: f(mxcsr) }
             }
-            Expression::Extract { .. } => {
-
+            Expression::FloatExceptionFlags { operation } => Expression::FloatExceptionFlags { operation: f(operation) },
+            Expression::X87Arithmetic { op, left, right, control } => {
+                Expression::X87Arithmetic { op, left: f(left), right: f(right), control: f(control) }
             }
-            Expression::Concat { .. } => {
+            Expression::X87Compare { predicate, left, right } => Expression::X87Compare { predicate, left: f(left), right: f(right) },
+            Expression::X87FromFloat { format, value } => Expression::X87FromFloat { format, value: f(value) },
+            Expression::X87ToFloat { format, value, control } => Expression::X87ToFloat { format, value: f(value), control: f(control) },
+            Expression::X87FromInt { value } => Expression::X87FromInt { value: f(value) },
+            Expression::X87ToInt { value, width, control } => Expression::X87ToInt { value: f(value), width, control: f(control) },
+            Expression::X87RoundedUp { operation } => Expression::X87RoundedUp { operation: f(operation) },
+            Expression::Load { address, width, at_index } => Expression::Load { address: f(address), width, at_index },
+        }
+    }
 
+    pub fn apply_concrete(&self, execution: &ConcreteExecution) -> Result<Value<'arena>, Fault> {
+        Ok(match self {
+            Expression::GetReg { reg, at_index } => {
+                execution.state_at(*at_index).get_reg(*reg)
             }
-            Expression::Conditional { .. } => {
-
+            Expression::GetFlag { flag, at_index } => {
+                Value::from_bool(execution.state_at(*at_index).get_flag(*flag))
             }
-            Expression::ZeroExtend { .. } => {
-
+            Expression::GetVectorReg { reg, at_index } => {
+                execution.state_at(*at_index).get_zmm(*reg)
             }
-            Expression::LowerBits { .. } => {
-
+            Expression::Constant { value } => {
+                (*value).clone()
             }
-            Expression::UpperBits { .. } => {
-
+            Expression::BitWise { op, left, right } => {
+                let width = self.width();
+                let left = left.apply_concrete(execution)?.zero_extend(width);
+                let right = right.apply_concrete(execution)?.zero_extend(width);
+                match op {
+                    BitWiseOp::And => left.bitwise(&right, |a, b| a & b),
+                    BitWiseOp::Or => left.bitwise(&right, |a, b| a | b),
+                    BitWiseOp::Xor => left.bitwise(&right, |a, b| a ^ b),
+                }
             }
-            Expression::ChangeRange { .. } => {
-
+            Expression::IntCompare { op, signedness, left, right } => {
+                let left = left.apply_concrete(execution)?;
+                let right = right.apply_concrete(execution)?;
+                let ordering = match signedness {
+                    Signedness::Signed => left.to_i128().cmp(&right.to_i128()),
+                    Signedness::Unsigned => left.to_u128().cmp(&right.to_u128()),
+                };
+                Value::from_bool(match op {
+                    ComparisonOp::Less => ordering.is_lt(),
+                    ComparisonOp::LessOrEqual => ordering.is_le(),
+                    ComparisonOp::Equal => ordering.is_eq(),
+                    ComparisonOp::GreaterOrEqual => ordering.is_ge(),
+                    ComparisonOp::Greater => ordering.is_gt(),
+                })
+            }
+            Expression::IntArithmetic { op, signedness, left, right } => {
+                let width = self.width();
+                assert!(width <= 128, "integer arithmetic wider than 128 bits is not supported");
+                let extend = |value: Value<'arena>| match signedness {
+                    Signedness::Signed => value.sign_extend(width),
+                    Signedness::Unsigned => value.zero_extend(width),
+                };
+                let left = extend(left.apply_concrete(execution)?);
+                let right = extend(right.apply_concrete(execution)?);
+                let res = match (op, signedness) {
+                    (ArithmeticOp::Add, _) => left.to_u128().wrapping_add(right.to_u128()),
+                    (ArithmeticOp::Sub, _) => left.to_u128().wrapping_sub(right.to_u128()),
+                    (ArithmeticOp::Mul, _) => left.to_u128().wrapping_mul(right.to_u128()),
+                    (ArithmeticOp::Div | ArithmeticOp::Rem, _) if right.is_zero() => return Err(Fault::DivideError),
+                    (ArithmeticOp::Div, Signedness::Unsigned) => left.to_u128() / right.to_u128(),
+                    (ArithmeticOp::Div, Signedness::Signed) => {
+                        Value::from_u128(left.to_i128().wrapping_div(right.to_i128()) as u128, width).to_u128()
+                    }
+                    (ArithmeticOp::Rem, Signedness::Unsigned) => left.to_u128() % right.to_u128(),
+                    (ArithmeticOp::Rem, Signedness::Signed) => {
+                        Value::from_u128(left.to_i128().wrapping_rem(right.to_i128()) as u128, width).to_u128()
+                    }
+                };
+                Value::from_u128(res, width)
+            }
+            Expression::Extract { value, low, high } => {
+                value.apply_concrete(execution)?.slice(*low, *high)
+            }
+            Expression::Concat { left, right } => {
+                Value::concat(&left.apply_concrete(execution)?, &right.apply_concrete(execution)?)
+            }
+            Expression::Conditional { condition, true_value, false_value } => {
+                if condition.apply_concrete(execution)?.is_true() {
+                    true_value.apply_concrete(execution)?
+                } else {
+                    false_value.apply_concrete(execution)?
+                }
+            }
+            Expression::ZeroExtend { value, len } => {
+                value.apply_concrete(execution)?.zero_extend(*len)
+            }
+            Expression::SignExtend { value, len } => {
+                value.apply_concrete(execution)?.sign_extend(*len)
             }
-            Expression::FAdd { .. } => {
+            Expression::Shift { op, value, amount } => {
+                let value = value.apply_concrete(execution)?;
+                let amount = amount.apply_concrete(execution)?;
+                let low_width = amount.width().min(64);
+                let amount = if amount.slice(low_width, amount.width()).is_zero() {
+                    amount.slice(0, low_width).to_u64().min(value.width() as u64) as usize
+                } else {
+                    value.width()
+                };
+                match op {
+                    ShiftOp::Shl => value.shift_left(amount),
+                    ShiftOp::LShr => value.shift_right(amount, false),
+                    ShiftOp::AShr => value.shift_right(amount, value.msb()),
+                }
+            }
+            Expression::BitCount { op, value } => {
+                let value = value.apply_concrete(execution)?;
+                let count = match op {
+                    BitCountOp::PopCount => value.count_ones(),
+                    BitCountOp::LeadingZeros => value.leading_zeros(),
+                    BitCountOp::TrailingZeros => value.trailing_zeros(),
+                };
+                Value::from_u64(count as u64, value.width())
+            }
+            Expression::LowerBits { value, len } => {
+                value.apply_concrete(execution)?.slice(0, *len)
+            }
+            Expression::UpperBits { value, len } => {
+                let value = value.apply_concrete(execution)?;
+                value.slice(value.width() - len, value.width())
+            }
+            Expression::ChangeRange { value, range_start_inclusive, range_end_exclusive, new_value } => {
+                let new_value = new_value.apply_concrete(execution)?;
+                assert_eq!(new_value.width(), range_end_exclusive - range_start_inclusive);
+                value.apply_concrete(execution)?.with_range(*range_start_inclusive, &new_value)
+            }
+            Expression::FloatArithmetic { .. } |
+            Expression::FloatSqrt { .. } |
+            Expression::FloatFma { .. } |
+            Expression::FloatCompare { .. } |
+            Expression::FloatConvert { .. } |
+            Expression::IntToFloat { .. } |
+            Expression::FloatToInt { .. } |
+            Expression::X87Arithmetic { .. } |
+            Expression::X87Compare { .. } |
+            Expression::X87FromFloat { .. } |
+            Expression::X87ToFloat { .. } |
+            Expression::X87FromInt { .. } |
+            Expression::X87ToInt { .. } => {
+                self.apply_concrete_float(execution)?.0
+            }
+            Expression::GetMxcsr { at_index } => {
+                Value::from_u64(execution.state_at(*at_index).mxcsr() as u64, 32)
+            }
+            Expression::FloatExceptionFlags { operation } => {
+                Value::from_u64(operation.apply_concrete_float(execution)?.1.bits() as u64, 6)
+            }
+            Expression::GetX87Register { st, at_index } => {
+                Value::from_u128(execution.state_at(*at_index).x87_register(*st), 80)
+            }
+            Expression::X87RegisterEmpty { st, at_index } => {
+                Value::from_u64(execution.state_at(*at_index).x87_register_empty(*st) as u64, 1)
+            }
+            Expression::GetX87Control { at_index } => {
+                Value::from_u64(execution.state_at(*at_index).x87_control() as u64, 16)
+            }
+            Expression::GetX87Status { at_index } => {
+                Value::from_u64(execution.state_at(*at_index).x87_status() as u64, 16)
+            }
+            Expression::X87RoundedUp { operation } => {
+                Value::from_u64(operation.apply_concrete_float(execution)?.2 as u64, 1)
+            }
+            Expression::Load { address, width, at_index } => {
+                let address = address.apply_concrete(execution)?.to_u64();
+                execution.state_at(*at_index).read_memory(address, *width)
+            }
+            Expression::SegmentBase { base, at_index } => {
+                Value::from_u64(execution.state_at(*at_index).segment_base(*base), 64)
+            }
+            Expression::GetSegment { segment, at_index } => {
+                Value::from_u64(execution.state_at(*at_index).get_segment(*segment) as u64, 16)
+            }
+        })
+    }
 
+    /// Result and exceptions of a floating point expression, and whether an x87 one rounded its result up.
+    fn apply_concrete_float(&self, execution: &ConcreteExecution) -> Result<(Value<'arena>, FloatExceptions, bool), Fault> {
+        let mxcsr = |mxcsr: &Expression<'arena>| -> Result<Mxcsr, Fault> {
+            Ok(Mxcsr(mxcsr.apply_concrete(execution)?.to_u64() as u32))
+        };
+        let control = |control: &Expression<'arena>| -> Result<X87ControlWord, Fault> {
+            Ok(X87ControlWord(control.apply_concrete(execution)?.to_u64() as u16))
+        };
+        let float = |value: &Expression<'arena>| -> Result<u64, Fault> {
+            Ok(value.apply_concrete(execution)?.to_u64())
+        };
+        let extended = |value: &Expression<'arena>| -> Result<u128, Fault> {
+            Ok(value.apply_concrete(execution)?.to_u128())
+        };
+        // SSE never reports rounding direction
+        let sse = |(res, exceptions): (u64, FloatExceptions)| (res as u128, exceptions, false);
+        let (res, exceptions, rounded_up) = match self {
+            Expression::FloatArithmetic { op, format, left, right, mxcsr: mxcsr_expr } => {
+                sse(float_arithmetic(*op, *format, float(left)?, float(right)?, mxcsr(mxcsr_expr)?))
             }
-        }
+            Expression::FloatSqrt { format, value, mxcsr: mxcsr_expr } => {
+                sse(float_sqrt(*format, float(value)?, mxcsr(mxcsr_expr)?))
+            }
+            Expression::FloatFma { format, left, right, addend, mxcsr: mxcsr_expr } => {
+                sse(float_fma(*format, float(left)?, float(right)?, float(addend)?, mxcsr(mxcsr_expr)?))
+            }
+            Expression::FloatCompare { predicate, format, left, right, mxcsr: mxcsr_expr } => {
+                let (res, exceptions) = float_compare(*predicate, *format, float(left)?, float(right)?, mxcsr(mxcsr_expr)?);
+                sse((res as u64, exceptions))
+            }
+            Expression::FloatConvert { from, to, value, mxcsr: mxcsr_expr } => {
+                sse(float_convert(*from, *to, float(value)?, mxcsr(mxcsr_expr)?))
+            }
+            Expression::IntToFloat { format, value, mxcsr: mxcsr_expr } => {
+                let value = value.apply_concrete(execution)?.sign_extend(64).to_u64() as i64;
+                sse(int_to_float(*format, value, mxcsr(mxcsr_expr)?))
+            }
+            Expression::FloatToInt { format, value, width, truncate, mxcsr: mxcsr_expr } => {
+                sse(float_to_int(*format, float(value)?, *width, *truncate, mxcsr(mxcsr_expr)?))
+            }
+            Expression::X87Arithmetic { op, left, right, control: control_expr } => {
+                x87_arithmetic(*op, extended(left)?, extended(right)?, control(control_expr)?)
+            }
+            Expression::X87Compare { predicate, left, right } => {
+                let (res, exceptions) = x87_compare(*predicate, extended(left)?, extended(right)?);
+                (res as u128, exceptions, false)
+            }
+            Expression::X87FromFloat { format, value } => {
+                let (res, exceptions) = x87_from_float(*format, float(value)?);
+                (res, exceptions, false)
+            }
+            Expression::X87ToFloat { format, value, control: control_expr } => {
+                let (res, exceptions, rounded_up) = x87_to_float(*format, extended(value)?, control(control_expr)?);
+                (res as u128, exceptions, rounded_up)
+            }
+            Expression::X87FromInt { value } => {
+                let value = value.apply_concrete(execution)?.sign_extend(64).to_u64() as i64;
+                (x87_from_int(value), FloatExceptions::NONE, false)
+            }
+            Expression::X87ToInt { value, width, control: control_expr } => {
+                let (res, exceptions, rounded_up) = x87_to_int(extended(value)?, *width, control(control_expr)?);
+                (res as u128, exceptions, rounded_up)
+            }
+            _ => panic!("not a floating point expression"),
+        };
+        Ok((Value::from_u128(res, self.width()), exceptions, rounded_up))
     }
 }
-
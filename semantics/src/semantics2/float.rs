@@ -0,0 +1,751 @@
+use std::cmp::Ordering;
+
+/// IEEE 754 binary32 and binary64, as used by SSE and AVX.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FloatFormat {
+    Single,
+    Double,
+}
+
+impl FloatFormat {
+    pub fn width(self) -> usize {
+        match self {
+            FloatFormat::Single => 32,
+            FloatFormat::Double => 64,
+        }
+    }
+
+    /// Significand bits, including the implicit leading one.
+    pub(crate) fn precision(self) -> i32 {
+        match self {
+            FloatFormat::Single => 24,
+            FloatFormat::Double => 53,
+        }
+    }
+
+    fn exponent_bits(self) -> u32 {
+        match self {
+            FloatFormat::Single => 8,
+            FloatFormat::Double => 11,
+        }
+    }
+
+    pub(crate) fn mantissa_bits(self) -> u32 {
+        self.precision() as u32 - 1
+    }
+
+    fn bias(self) -> i32 {
+        (1 << (self.exponent_bits() - 1)) - 1
+    }
+
+    pub(crate) fn max_biased_exponent(self) -> u64 {
+        (1 << self.exponent_bits()) - 1
+    }
+
+    /// Exponent of the smallest normal number.
+    fn min_exponent(self) -> i32 {
+        1 - self.bias()
+    }
+
+    fn max_exponent(self) -> i32 {
+        self.bias()
+    }
+
+    pub(crate) fn sign_bit(self) -> u64 {
+        1 << (self.width() - 1)
+    }
+
+    pub(crate) fn quiet_bit(self) -> u64 {
+        1 << (self.mantissa_bits() - 1)
+    }
+
+    pub(crate) fn pack(self, sign: bool, biased_exponent: u64, mantissa: u64) -> u64 {
+        ((sign as u64) << (self.width() - 1)) | (biased_exponent << self.mantissa_bits()) | mantissa
+    }
+
+    fn zero(self, sign: bool) -> u64 {
+        self.pack(sign, 0, 0)
+    }
+
+    fn infinity(self, sign: bool) -> u64 {
+        self.pack(sign, self.max_biased_exponent(), 0)
+    }
+
+    fn max_finite(self, sign: bool) -> u64 {
+        self.pack(sign, self.max_biased_exponent() - 1, (1 << self.mantissa_bits()) - 1)
+    }
+
+    /// The QNaN x86 returns for invalid operations, "real indefinite".
+    pub fn default_nan(self) -> u64 {
+        self.pack(true, self.max_biased_exponent(), self.quiet_bit())
+    }
+}
+
+/// MXCSR rounding control.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RoundingMode {
+    NearestEven,
+    /// Toward negative infinity.
+    Down,
+    /// Toward positive infinity.
+    Up,
+    TowardZero,
+}
+
+/// The exception flags of MXCSR, which are also its masks shifted left by 7.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct FloatExceptions(u8);
+
+impl FloatExceptions {
+    pub const NONE: FloatExceptions = FloatExceptions(0);
+    pub const INVALID: FloatExceptions = FloatExceptions(1);
+    pub const DENORMAL: FloatExceptions = FloatExceptions(1 << 1);
+    pub const DIVIDE_BY_ZERO: FloatExceptions = FloatExceptions(1 << 2);
+    pub const OVERFLOW: FloatExceptions = FloatExceptions(1 << 3);
+    pub const UNDERFLOW: FloatExceptions = FloatExceptions(1 << 4);
+    pub const PRECISION: FloatExceptions = FloatExceptions(1 << 5);
+
+    pub fn from_bits(bits: u8) -> Self {
+        FloatExceptions(bits & 0x3F)
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(self, other: FloatExceptions) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn union(self, other: FloatExceptions) -> Self {
+        FloatExceptions(self.0 | other.0)
+    }
+
+    pub(crate) fn raise(&mut self, other: FloatExceptions) {
+        self.0 |= other.0
+    }
+}
+
+/// The SSE control and status register. Only the low 16 bits are defined.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Mxcsr(pub u32);
+
+impl Mxcsr {
+    /// Value after reset: round to nearest, every exception masked and no flags set.
+    pub const DEFAULT: Mxcsr = Mxcsr(0x1F80);
+
+    pub fn rounding_mode(self) -> RoundingMode {
+        match (self.0 >> 13) & 3 {
+            0 => RoundingMode::NearestEven,
+            1 => RoundingMode::Down,
+            2 => RoundingMode::Up,
+            _ => RoundingMode::TowardZero,
+        }
+    }
+
+    /// DAZ, denormal inputs are read as zeros of the same sign.
+    pub fn denormals_are_zero(self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+
+    /// FTZ, tiny results are replaced with zeros of the same sign when underflow is masked.
+    pub fn flush_to_zero(self) -> bool {
+        self.0 & (1 << 15) != 0
+    }
+
+    pub fn exception_flags(self) -> FloatExceptions {
+        FloatExceptions::from_bits(self.0 as u8)
+    }
+
+    pub fn exception_masks(self) -> FloatExceptions {
+        FloatExceptions::from_bits((self.0 >> 7) as u8)
+    }
+
+    fn underflow_masked(self) -> bool {
+        self.exception_masks().contains(FloatExceptions::UNDERFLOW)
+    }
+}
+
+/// A finite nonzero value, `sig * 2^exp`.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Unpacked {
+    pub(crate) sign: bool,
+    pub(crate) exp: i32,
+    pub(crate) sig: u128,
+}
+
+impl Unpacked {
+    pub(crate) fn msb(&self) -> i32 {
+        127 - self.sig.leading_zeros() as i32
+    }
+
+    /// Same value, shifted so that the leading one of `sig` is bit `msb`.
+    pub(crate) fn normalized(self, msb: i32) -> Self {
+        let shift = msb - self.msb();
+        assert!(shift >= 0);
+        Unpacked {
+            sign: self.sign,
+            exp: self.exp - shift,
+            sig: self.sig << shift,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Class {
+    Zero { sign: bool },
+    Infinity { sign: bool },
+    NaN { quiet: bool },
+    Finite(Unpacked),
+}
+
+/// An operand as the instruction sees it, after DAZ.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Operand {
+    /// The bits with denormals replaced by zero under DAZ.
+    pub(crate) bits: u64,
+    pub(crate) class: Class,
+    /// A denormal which DAZ didn't replace.
+    pub(crate) denormal: bool,
+}
+
+impl Operand {
+    pub(crate) fn new(format: FloatFormat, bits: u64, mxcsr: Mxcsr) -> Self {
+        let bits = bits & (u64::MAX >> (64 - format.width()));
+        let sign = bits & format.sign_bit() != 0;
+        let biased_exponent = (bits >> format.mantissa_bits()) & format.max_biased_exponent();
+        let mantissa = bits & ((1 << format.mantissa_bits()) - 1);
+        let (bits, class, denormal) = if biased_exponent == format.max_biased_exponent() {
+            if mantissa == 0 {
+                (bits, Class::Infinity { sign }, false)
+            } else {
+                (bits, Class::NaN { quiet: mantissa & format.quiet_bit() != 0 }, false)
+            }
+        } else if biased_exponent == 0 {
+            if mantissa == 0 {
+                (bits, Class::Zero { sign }, false)
+            } else if mxcsr.denormals_are_zero() {
+                (format.zero(sign), Class::Zero { sign }, false)
+            } else {
+                let unpacked = Unpacked { sign, exp: format.min_exponent() - format.mantissa_bits() as i32, sig: mantissa as u128 };
+                (bits, Class::Finite(unpacked), true)
+            }
+        } else {
+            let exp = biased_exponent as i32 - format.bias() - format.mantissa_bits() as i32;
+            let sig = (mantissa | (1 << format.mantissa_bits())) as u128;
+            (bits, Class::Finite(Unpacked { sign, exp, sig }), false)
+        };
+        Operand { bits, class, denormal }
+    }
+
+    fn is_nan(&self) -> bool {
+        matches!(self.class, Class::NaN { .. })
+    }
+
+    fn is_signaling_nan(&self) -> bool {
+        matches!(self.class, Class::NaN { quiet: false })
+    }
+
+    fn sign(&self) -> bool {
+        match self.class {
+            Class::Zero { sign } | Class::Infinity { sign } => sign,
+            Class::Finite(unpacked) => unpacked.sign,
+            Class::NaN { .. } => false,
+        }
+    }
+}
+
+/// NaN operands propagate quietened, the first one if there is more than one. A signaling NaN raises invalid.
+fn propagate_nan(format: FloatFormat, operands: &[Operand], exceptions: &mut FloatExceptions) -> Option<u64> {
+    if operands.iter().any(|operand| operand.is_signaling_nan()) {
+        exceptions.raise(FloatExceptions::INVALID);
+    }
+    operands.iter()
+        .find(|operand| operand.is_nan())
+        .map(|operand| operand.bits | format.quiet_bit())
+}
+
+/// Denormal operands only count when the operation wasn't already invalid or a division by zero.
+fn raise_denormal(operands: &[Operand], exceptions: &mut FloatExceptions) {
+    let higher_priority = exceptions.contains(FloatExceptions::INVALID) || exceptions.contains(FloatExceptions::DIVIDE_BY_ZERO);
+    if !higher_priority && operands.iter().any(|operand| operand.denormal) {
+        exceptions.raise(FloatExceptions::DENORMAL);
+    }
+}
+
+/// `sig >> shift`, with the bits shifted out ORed into the lowest bit so that they still count towards rounding.
+pub(crate) fn shift_right_jamming(sig: u128, shift: i32) -> u128 {
+    if shift <= 0 {
+        sig
+    } else if shift >= 128 {
+        (sig != 0) as u128
+    } else {
+        (sig >> shift) | ((sig & ((1 << shift) - 1) != 0) as u128)
+    }
+}
+
+/// Rounds `sig * 2^exp` to a multiple of `2^lsb_exp`, returning the multiple and whether it is inexact. The
+/// multiple can carry into a new leading bit.
+pub(crate) fn round_at(sign: bool, exp: i32, sig: u128, lsb_exp: i32, mode: RoundingMode) -> (u128, bool) {
+    let shift = lsb_exp - exp;
+    let (kept, round_bit, rest_nonzero) = if shift <= 0 {
+        (sig << -shift, false, false)
+    } else if shift > 128 {
+        (0, false, sig != 0)
+    } else if shift == 128 {
+        (0, sig >> 127 == 1, sig << 1 != 0)
+    } else {
+        (sig >> shift, (sig >> (shift - 1)) & 1 == 1, sig & ((1 << (shift - 1)) - 1) != 0)
+    };
+    let inexact = round_bit || rest_nonzero;
+    let increment = match mode {
+        RoundingMode::NearestEven => round_bit && (rest_nonzero || kept & 1 == 1),
+        RoundingMode::Down => inexact && sign,
+        RoundingMode::Up => inexact && !sign,
+        RoundingMode::TowardZero => false,
+    };
+    (kept + increment as u128, inexact)
+}
+
+/// Rounds a nonzero `sig * 2^exp` to `format` the way MXCSR says to. Tininess is detected after rounding, as
+/// x86 does.
+pub(crate) fn round_pack(format: FloatFormat, value: Unpacked, mxcsr: Mxcsr, exceptions: &mut FloatExceptions) -> u64 {
+    let Unpacked { sign, exp, sig } = value;
+    let mode = mxcsr.rounding_mode();
+    let precision = format.precision();
+    // rounded as if the exponent range were unbounded
+    let top = exp + value.msb();
+    let mut lsb_exp = top - (precision - 1);
+    let (mut rounded, inexact) = round_at(sign, exp, sig, lsb_exp, mode);
+    if rounded == 1 << precision {
+        rounded >>= 1;
+        lsb_exp += 1;
+    }
+    let rounded_top = lsb_exp + precision - 1;
+    if rounded_top > format.max_exponent() {
+        exceptions.raise(FloatExceptions::OVERFLOW.union(FloatExceptions::PRECISION));
+        let to_infinity = match mode {
+            RoundingMode::NearestEven => true,
+            RoundingMode::Down => sign,
+            RoundingMode::Up => !sign,
+            RoundingMode::TowardZero => false,
+        };
+        return if to_infinity { format.infinity(sign) } else { format.max_finite(sign) };
+    }
+    if rounded_top < format.min_exponent() {
+        if mxcsr.flush_to_zero() && mxcsr.underflow_masked() {
+            exceptions.raise(FloatExceptions::UNDERFLOW.union(FloatExceptions::PRECISION));
+            return format.zero(sign);
+        }
+        let (denormal, inexact) = round_at(sign, exp, sig, format.min_exponent() - (precision - 1), mode);
+        if inexact {
+            exceptions.raise(FloatExceptions::UNDERFLOW.union(FloatExceptions::PRECISION));
+        } else if !mxcsr.underflow_masked() {
+            exceptions.raise(FloatExceptions::UNDERFLOW);
+        }
+        // a denormal which rounded up to the smallest normal carries into the exponent field by itself
+        return format.zero(sign) | denormal as u64;
+    }
+    if inexact {
+        exceptions.raise(FloatExceptions::PRECISION);
+    }
+    let biased_exponent = (rounded_top + format.bias()) as u64;
+    format.pack(sign, biased_exponent, rounded as u64 & ((1 << format.mantissa_bits()) - 1))
+}
+
+/// Exact sum of two finite nonzero values, `None` if it is zero.
+pub(crate) fn add_unpacked(left: Unpacked, right: Unpacked) -> Option<Unpacked> {
+    // the larger significand ends up at bit 125, which leaves room for a carry and enough bits below the result's
+    // leading one for the jammed bit not to matter
+    let left = left.normalized(125);
+    let right = right.normalized(125);
+    let (big, small) = if left.exp >= right.exp { (left, right) } else { (right, left) };
+    let small_sig = shift_right_jamming(small.sig, big.exp - small.exp);
+    let (sign, sig) = if big.sign == small.sign {
+        (big.sign, big.sig + small_sig)
+    } else if big.sig >= small_sig {
+        (big.sign, big.sig - small_sig)
+    } else {
+        (small.sign, small_sig - big.sig)
+    };
+    (sig != 0).then_some(Unpacked { sign, exp: big.exp, sig })
+}
+
+pub(crate) fn mul_unpacked(left: Unpacked, right: Unpacked) -> Unpacked {
+    Unpacked {
+        sign: left.sign != right.sign,
+        exp: left.exp + right.exp,
+        sig: left.sig * right.sig,
+    }
+}
+
+/// An exact zero result of adding `left_sign` and `right_sign` valued things, which is -0 only if both are
+/// negative, or when rounding down.
+pub(crate) fn exact_zero_sum_sign(left_sign: bool, right_sign: bool, mode: RoundingMode) -> bool {
+    if left_sign == right_sign {
+        left_sign
+    } else {
+        mode == RoundingMode::Down
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FloatArithmeticOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    /// MINPS and friends: the second operand unless the first is less than it, which also makes it the result
+    /// when either is NaN or both are zero.
+    Min,
+    Max,
+}
+
+fn add(format: FloatFormat, left: Operand, right: Operand, negate_right: bool, mxcsr: Mxcsr, exceptions: &mut FloatExceptions) -> u64 {
+    let mode = mxcsr.rounding_mode();
+    let negate = |sign: bool| sign != negate_right;
+    match (left.class, right.class) {
+        (Class::Infinity { sign: left_sign }, Class::Infinity { sign: right_sign }) => {
+            if left_sign != negate(right_sign) {
+                exceptions.raise(FloatExceptions::INVALID);
+                format.default_nan()
+            } else {
+                format.infinity(left_sign)
+            }
+        }
+        (Class::Infinity { sign }, _) => format.infinity(sign),
+        (_, Class::Infinity { sign }) => format.infinity(negate(sign)),
+        (Class::Zero { sign: left_sign }, Class::Zero { sign: right_sign }) => {
+            format.zero(exact_zero_sum_sign(left_sign, negate(right_sign), mode))
+        }
+        (Class::Zero { .. }, Class::Finite(right)) => {
+            round_pack(format, Unpacked { sign: negate(right.sign), ..right }, mxcsr, exceptions)
+        }
+        (Class::Finite(left), Class::Zero { .. }) => round_pack(format, left, mxcsr, exceptions),
+        (Class::Finite(left), Class::Finite(right)) => {
+            match add_unpacked(left, Unpacked { sign: negate(right.sign), ..right }) {
+                Some(sum) => round_pack(format, sum, mxcsr, exceptions),
+                None => format.zero(mode == RoundingMode::Down),
+            }
+        }
+        (Class::NaN { .. }, _) | (_, Class::NaN { .. }) => unreachable!(),
+    }
+}
+
+fn mul(format: FloatFormat, left: Operand, right: Operand, mxcsr: Mxcsr, exceptions: &mut FloatExceptions) -> u64 {
+    let sign = left.sign() != right.sign();
+    match (left.class, right.class) {
+        (Class::Infinity { .. }, Class::Zero { .. }) | (Class::Zero { .. }, Class::Infinity { .. }) => {
+            exceptions.raise(FloatExceptions::INVALID);
+            format.default_nan()
+        }
+        (Class::Infinity { .. }, _) | (_, Class::Infinity { .. }) => format.infinity(sign),
+        (Class::Zero { .. }, _) | (_, Class::Zero { .. }) => format.zero(sign),
+        (Class::Finite(left), Class::Finite(right)) => round_pack(format, mul_unpacked(left, right), mxcsr, exceptions),
+        (Class::NaN { .. }, _) | (_, Class::NaN { .. }) => unreachable!(),
+    }
+}
+
+fn div(format: FloatFormat, left: Operand, right: Operand, mxcsr: Mxcsr, exceptions: &mut FloatExceptions) -> u64 {
+    let sign = left.sign() != right.sign();
+    match (left.class, right.class) {
+        (Class::Infinity { .. }, Class::Infinity { .. }) | (Class::Zero { .. }, Class::Zero { .. }) => {
+            exceptions.raise(FloatExceptions::INVALID);
+            format.default_nan()
+        }
+        (Class::Infinity { .. }, _) => format.infinity(sign),
+        (_, Class::Zero { .. }) => {
+            exceptions.raise(FloatExceptions::DIVIDE_BY_ZERO);
+            format.infinity(sign)
+        }
+        (_, Class::Infinity { .. }) | (Class::Zero { .. }, _) => format.zero(sign),
+        (Class::Finite(left), Class::Finite(right)) => {
+            // at least 62 quotient bits, plenty to round to 53
+            let left = left.normalized(125);
+            let right = right.normalized(63);
+            let quotient = left.sig / right.sig;
+            let remainder = left.sig % right.sig;
+            let sig = quotient | (remainder != 0) as u128;
+            round_pack(format, Unpacked { sign, exp: left.exp - right.exp, sig }, mxcsr, exceptions)
+        }
+        (Class::NaN { .. }, _) | (_, Class::NaN { .. }) => unreachable!(),
+    }
+}
+
+/// Orders two non NaN operands, zeros of either sign being equal.
+fn compare_ordered(format: FloatFormat, left: &Operand, right: &Operand) -> Ordering {
+    let key = |operand: &Operand| -> i128 {
+        match operand.class {
+            Class::Zero { .. } => 0,
+            _ => {
+                let magnitude = (operand.bits & !format.sign_bit()) as i128;
+                if operand.sign() { -magnitude } else { magnitude }
+            }
+        }
+    };
+    key(left).cmp(&key(right))
+}
+
+fn min_max(format: FloatFormat, op: FloatArithmeticOp, left: Operand, right: Operand, exceptions: &mut FloatExceptions) -> u64 {
+    if left.is_nan() || right.is_nan() {
+        exceptions.raise(FloatExceptions::INVALID);
+        return right.bits;
+    }
+    raise_denormal(&[left, right], exceptions);
+    let take_left = match op {
+        FloatArithmeticOp::Min => compare_ordered(format, &left, &right).is_lt(),
+        FloatArithmeticOp::Max => compare_ordered(format, &left, &right).is_gt(),
+        _ => unreachable!(),
+    };
+    if take_left { left.bits } else { right.bits }
+}
+
+pub fn float_arithmetic(op: FloatArithmeticOp, format: FloatFormat, left: u64, right: u64, mxcsr: Mxcsr) -> (u64, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    let left = Operand::new(format, left, mxcsr);
+    let right = Operand::new(format, right, mxcsr);
+    if let FloatArithmeticOp::Min | FloatArithmeticOp::Max = op {
+        return (min_max(format, op, left, right, &mut exceptions), exceptions);
+    }
+    if let Some(nan) = propagate_nan(format, &[left, right], &mut exceptions) {
+        return (nan, exceptions);
+    }
+    let res = match op {
+        FloatArithmeticOp::Add => add(format, left, right, false, mxcsr, &mut exceptions),
+        FloatArithmeticOp::Sub => add(format, left, right, true, mxcsr, &mut exceptions),
+        FloatArithmeticOp::Mul => mul(format, left, right, mxcsr, &mut exceptions),
+        FloatArithmeticOp::Div => div(format, left, right, mxcsr, &mut exceptions),
+        FloatArithmeticOp::Min | FloatArithmeticOp::Max => unreachable!(),
+    };
+    raise_denormal(&[left, right], &mut exceptions);
+    (res, exceptions)
+}
+
+/// Square root of a u128, rounded down.
+fn isqrt(value: u128) -> u128 {
+    let mut res = 0u128;
+    let mut remainder = value;
+    let mut bit = 1u128 << 126;
+    while bit > value {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if remainder >= res + bit {
+            remainder -= res + bit;
+            res = (res >> 1) + bit;
+        } else {
+            res >>= 1;
+        }
+        bit >>= 2;
+    }
+    res
+}
+
+pub fn float_sqrt(format: FloatFormat, value: u64, mxcsr: Mxcsr) -> (u64, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    let value = Operand::new(format, value, mxcsr);
+    if let Some(nan) = propagate_nan(format, &[value], &mut exceptions) {
+        return (nan, exceptions);
+    }
+    let res = match value.class {
+        Class::Zero { sign } => format.zero(sign),
+        Class::Infinity { sign: false } => format.infinity(false),
+        Class::Infinity { sign: true } | Class::Finite(Unpacked { sign: true, .. }) => {
+            exceptions.raise(FloatExceptions::INVALID);
+            format.default_nan()
+        }
+        Class::Finite(unpacked) => {
+            // an even exponent, and at least 62 result bits
+            let mut unpacked = unpacked.normalized(124);
+            if unpacked.exp % 2 != 0 {
+                unpacked = Unpacked { sign: false, exp: unpacked.exp - 1, sig: unpacked.sig << 1 };
+            }
+            let root = isqrt(unpacked.sig);
+            let sig = root | (root * root != unpacked.sig) as u128;
+            round_pack(format, Unpacked { sign: false, exp: unpacked.exp / 2, sig }, mxcsr, &mut exceptions)
+        }
+        Class::NaN { .. } => unreachable!(),
+    };
+    raise_denormal(&[value], &mut exceptions);
+    (res, exceptions)
+}
+
+/// `left * right + addend` with a single rounding.
+pub fn float_fma(format: FloatFormat, left: u64, right: u64, addend: u64, mxcsr: Mxcsr) -> (u64, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    let mode = mxcsr.rounding_mode();
+    let left = Operand::new(format, left, mxcsr);
+    let right = Operand::new(format, right, mxcsr);
+    let addend = Operand::new(format, addend, mxcsr);
+    let product_invalid = matches!((left.class, right.class),
+        (Class::Infinity { .. }, Class::Zero { .. }) | (Class::Zero { .. }, Class::Infinity { .. }));
+    if let Some(nan) = propagate_nan(format, &[left, right, addend], &mut exceptions) {
+        return (nan, exceptions);
+    }
+    let product_sign = left.sign() != right.sign();
+    let res = match (left.class, right.class, addend.class) {
+        _ if product_invalid => {
+            exceptions.raise(FloatExceptions::INVALID);
+            format.default_nan()
+        }
+        (Class::Infinity { .. }, _, Class::Infinity { sign }) | (_, Class::Infinity { .. }, Class::Infinity { sign }) if sign != product_sign => {
+            exceptions.raise(FloatExceptions::INVALID);
+            format.default_nan()
+        }
+        (Class::Infinity { .. }, _, _) | (_, Class::Infinity { .. }, _) => format.infinity(product_sign),
+        (_, _, Class::Infinity { sign }) => format.infinity(sign),
+        (Class::Zero { .. }, _, Class::Zero { sign }) | (_, Class::Zero { .. }, Class::Zero { sign }) => {
+            format.zero(exact_zero_sum_sign(product_sign, sign, mode))
+        }
+        (Class::Zero { .. }, _, Class::Finite(addend)) | (_, Class::Zero { .. }, Class::Finite(addend)) => {
+            round_pack(format, addend, mxcsr, &mut exceptions)
+        }
+        (Class::Finite(left), Class::Finite(right), Class::Zero { .. }) => {
+            round_pack(format, mul_unpacked(left, right), mxcsr, &mut exceptions)
+        }
+        (Class::Finite(left), Class::Finite(right), Class::Finite(addend)) => {
+            match add_unpacked(mul_unpacked(left, right), addend) {
+                Some(sum) => round_pack(format, sum, mxcsr, &mut exceptions),
+                None => format.zero(mode == RoundingMode::Down),
+            }
+        }
+        _ => unreachable!(),
+    };
+    raise_denormal(&[left, right, addend], &mut exceptions);
+    (res, exceptions)
+}
+
+/// Which outcomes of a comparison make it true, as in the CMPPS predicates. Signaling predicates raise invalid
+/// for QNaN operands as well as SNaNs.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FloatPredicate {
+    pub less: bool,
+    pub equal: bool,
+    pub greater: bool,
+    pub unordered: bool,
+    pub signaling: bool,
+}
+
+impl FloatPredicate {
+    /// The VCMPPS immediate, of which the SSE CMPPS predicates are the first 8.
+    pub fn from_immediate(immediate: u8) -> Self {
+        // EQ, LT, LE, UNORD, NEQ, NLT, NLE, ORD, and then the same with the opposite signaling and NaN handling
+        // as EQ_UQ, NGE, NGT, FALSE, NEQ_OQ, GE, GT, TRUE
+        let (less, equal, greater, unordered) = match immediate & 0x7 {
+            0 => (false, true, false, false),
+            1 => (true, false, false, false),
+            2 => (true, true, false, false),
+            3 => (false, false, false, true),
+            4 => (true, false, true, true),
+            5 => (false, true, true, true),
+            6 => (false, false, true, true),
+            _ => (true, true, true, false),
+        };
+        let (less, equal, greater, unordered) = if immediate & 0x8 != 0 {
+            match immediate & 0x7 {
+                // false and true
+                3 => (false, false, false, false),
+                7 => (true, true, true, true),
+                _ => (less, equal, greater, !unordered),
+            }
+        } else {
+            (less, equal, greater, unordered)
+        };
+        // LT, LE, NLT and NLE signal, and the upper 16 flip that
+        let signaling = matches!(immediate & 0x7, 1 | 2 | 5 | 6) != (immediate & 0x10 != 0);
+        FloatPredicate { less, equal, greater, unordered, signaling }
+    }
+}
+
+pub fn float_compare(predicate: FloatPredicate, format: FloatFormat, left: u64, right: u64, mxcsr: Mxcsr) -> (bool, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    let left = Operand::new(format, left, mxcsr);
+    let right = Operand::new(format, right, mxcsr);
+    if left.is_nan() || right.is_nan() {
+        if predicate.signaling || left.is_signaling_nan() || right.is_signaling_nan() {
+            exceptions.raise(FloatExceptions::INVALID);
+        }
+        return (predicate.unordered, exceptions);
+    }
+    raise_denormal(&[left, right], &mut exceptions);
+    let res = match compare_ordered(format, &left, &right) {
+        Ordering::Less => predicate.less,
+        Ordering::Equal => predicate.equal,
+        Ordering::Greater => predicate.greater,
+    };
+    (res, exceptions)
+}
+
+/// Between binary32 and binary64. NaNs keep their sign and the top of their payload.
+pub fn float_convert(from: FloatFormat, to: FloatFormat, value: u64, mxcsr: Mxcsr) -> (u64, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    let value = Operand::new(from, value, mxcsr);
+    let res = match value.class {
+        Class::NaN { quiet } => {
+            if !quiet {
+                exceptions.raise(FloatExceptions::INVALID);
+            }
+            let sign = value.bits & from.sign_bit() != 0;
+            let payload = value.bits & ((1 << from.mantissa_bits()) - 1);
+            let payload = if from.mantissa_bits() > to.mantissa_bits() {
+                payload >> (from.mantissa_bits() - to.mantissa_bits())
+            } else {
+                payload << (to.mantissa_bits() - from.mantissa_bits())
+            };
+            to.pack(sign, to.max_biased_exponent(), payload | to.quiet_bit())
+        }
+        Class::Infinity { sign } => to.infinity(sign),
+        Class::Zero { sign } => to.zero(sign),
+        Class::Finite(unpacked) => {
+            raise_denormal(&[value], &mut exceptions);
+            round_pack(to, unpacked, mxcsr, &mut exceptions)
+        }
+    };
+    (res, exceptions)
+}
+
+/// `value` is a signed integer, sign extended to 64 bits.
+pub fn int_to_float(format: FloatFormat, value: i64, mxcsr: Mxcsr) -> (u64, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    if value == 0 {
+        return (format.zero(false), exceptions);
+    }
+    let unpacked = Unpacked { sign: value < 0, exp: 0, sig: value.unsigned_abs() as u128 };
+    (round_pack(format, unpacked, mxcsr, &mut exceptions), exceptions)
+}
+
+/// Converts to a `width` bit signed integer, rounding as MXCSR says unless `truncate`. NaNs and values out of
+/// range give the "integer indefinite" value, the most negative integer.
+pub fn float_to_int(format: FloatFormat, value: u64, width: usize, truncate: bool, mxcsr: Mxcsr) -> (u64, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    let indefinite = 1u64 << (width - 1);
+    let value = Operand::new(format, value, mxcsr);
+    let res = match value.class {
+        Class::NaN { .. } | Class::Infinity { .. } => {
+            exceptions.raise(FloatExceptions::INVALID);
+            indefinite
+        }
+        Class::Zero { .. } => 0,
+        Class::Finite(unpacked) => {
+            let mode = if truncate { RoundingMode::TowardZero } else { mxcsr.rounding_mode() };
+            if unpacked.exp + unpacked.msb() >= width as i32 {
+                exceptions.raise(FloatExceptions::INVALID);
+                indefinite
+            } else {
+                let (magnitude, inexact) = round_at(unpacked.sign, unpacked.exp, unpacked.sig, 0, mode);
+                let limit = if unpacked.sign { 1u128 << (width - 1) } else { (1u128 << (width - 1)) - 1 };
+                if magnitude > limit {
+                    exceptions.raise(FloatExceptions::INVALID);
+                    indefinite
+                } else {
+                    if inexact {
+                        exceptions.raise(FloatExceptions::PRECISION);
+                    }
+                    let res = if unpacked.sign { (magnitude as u64).wrapping_neg() } else { magnitude as u64 };
+                    res & (u64::MAX >> (64 - width))
+                }
+            }
+        }
+    };
+    (res, exceptions)
+}
@@ -0,0 +1,428 @@
+use wrapper_common::registers::RegZMM;
+use xed_enum::{CMPPD, CMPPS, CMPSD_XMM, CMPSS, COMISD, COMISS, UCOMISD, UCOMISS, VCMPPD, VCMPPS, VCMPSD, VCMPSS, VCOMISD, VCOMISS, VUCOMISD, VUCOMISS, X86Instruction};
+use xed_wrapper::operands::Imm8;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::Expression;
+use crate::semantics2::float::{FloatFormat, FloatPredicate};
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+/// COMISD and friends. `signaling` raises invalid for QNaN operands as well as SNaNs, which is what sets COMIS*
+/// apart from UCOMIS*.
+pub fn comis_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    readable1: S1,
+    readable2: S2,
+    signaling: bool,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // RESULT := OrderedCompare(DEST[lane] <> SRC[lane]);
+    // (* Set EFLAGS *) CASE (RESULT) OF
+    //     UNORDERED: ZF,PF,CF := 111;
+    //     GREATER_THAN: ZF,PF,CF := 000;
+    //     LESS_THAN: ZF,PF,CF := 001;
+    //     EQUAL: ZF,PF,CF := 100;
+    // ESAC;
+    // OF, AF, SF := 0;
+    let mut s = SemanticsBuilder::new(arena);
+    let width = format.width();
+    s.set_memory_operand_width(width);
+    let left = s.lower_bits(readable1.read(&s), width);
+    let right = s.lower_bits(readable2.read(&s), width);
+    let predicate = |less, equal, unordered| FloatPredicate { less, equal, greater: false, unordered, signaling };
+    let zf = s.float_compare(predicate(false, true, true), format, left, right);
+    let pf = s.float_compare(predicate(false, false, true), format, left, right);
+    let cf = s.float_compare(predicate(true, false, true), format, left, right);
+    // all three raise the same exceptions
+    s.raise_float_exceptions(&[zf]);
+    s.set_zf(zf);
+    s.set_pf(pf);
+    s.set_cf(cf);
+    s.set_of(s.constant(false));
+    s.set_af(s.constant(false));
+    s.set_sf(s.constant(false));
+    s.finalize()
+}
+
+/// Each `format` lane is all ones where `predicate` holds between the lanes of `left` and `right`, and zero where
+/// it doesn't. Returns the comparisons as well, for their exceptions.
+fn compare_lanes<'arena>(
+    s: &SemanticsBuilder<'arena>,
+    predicate: FloatPredicate,
+    format: FloatFormat,
+    left: &'arena Expression<'arena>,
+    right: &'arena Expression<'arena>,
+) -> (&'arena Expression<'arena>, Vec<&'arena Expression<'arena>>) {
+    assert_eq!(left.width(), right.width());
+    let lane_width = format.width();
+    let compares = (0..left.width() / lane_width)
+        .map(|lane| {
+            let low = lane * lane_width;
+            s.float_compare(predicate, format, s.extract(left, low, low + lane_width), s.extract(right, low, low + lane_width))
+        })
+        .collect::<Vec<_>>();
+    let res = compares.iter().copied()
+        .map(|compare| s.select(compare, s.ones(lane_width), s.constant_with_width(0, lane_width)))
+        .reduce(|low, high| s.concat(high, low))
+        .expect("no lanes");
+    (res, compares)
+}
+
+/// Legacy SSE only has the first 8 predicates, the rest of the immediate is ignored.
+fn sse_predicate(immediate: Imm8) -> FloatPredicate {
+    FloatPredicate::from_immediate(immediate.0 as u8 & 0x7)
+}
+
+fn vex_predicate(immediate: Imm8) -> FloatPredicate {
+    FloatPredicate::from_immediate(immediate.0 as u8 & 0x1f)
+}
+
+pub fn sse_compare_packed_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    immediate: Imm8,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // FOR EACH LANE: CMP := DEST[lane] OP SRC[lane]; DEST[lane] := CMP ? ALL ONES : 0
+    // DEST[MAXVL-1:128] (Unmodified)
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(128);
+    let left = readable1.read(&s);
+    let right = readable2.read(&s);
+    let (res, compares) = compare_lanes(&s, sse_predicate(immediate), format, left, right);
+    s.raise_float_exceptions(&compares);
+    writeable1.write(&mut s, res);
+    s.finalize()
+}
+
+pub fn sse_compare_scalar_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    immediate: Imm8,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // CMP := DEST[lane 0] OP SRC[lane 0]; DEST[lane 0] := CMP ? ALL ONES : 0
+    // DEST[MAXVL-1:lane width] (Unmodified)
+    let mut s = SemanticsBuilder::new(arena);
+    let width = format.width();
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let src = s.lower_bits(readable2.read(&s), width);
+    let (res, compares) = compare_lanes(&s, sse_predicate(immediate), format, s.lower_bits(dest, width), src);
+    s.raise_float_exceptions(&compares);
+    let merged = s.change(dest, 0, width, res);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+pub fn vex_compare_packed_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: RegZMM,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+    immediate: Imm8,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // FOR EACH LANE: CMP := SRC1[lane] OP SRC2[lane]; DEST[lane] := CMP ? ALL ONES : 0
+    // DEST[MAXVL-1:VL] := 0
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let left = readable1.read(&s);
+    let right = readable2.read(&s);
+    let (res, compares) = compare_lanes(&s, vex_predicate(immediate), format, left, right);
+    s.raise_float_exceptions(&compares);
+    let widened = s.zext_to(res, 512);
+    writeable1.write(&mut s, widened);
+    s.finalize()
+}
+
+pub fn vex_compare_scalar_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: RegZMM,
+    readable1: S1,
+    readable2: S2,
+    immediate: Imm8,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // CMP := SRC1[lane 0] OP SRC2[lane 0]; DEST[lane 0] := CMP ? ALL ONES : 0
+    // DEST[127:lane width] := SRC1[127:lane width]
+    // DEST[MAXVL-1:128] := 0
+    let mut s = SemanticsBuilder::new(arena);
+    let width = format.width();
+    s.set_memory_operand_width(width);
+    let left = readable1.read(&s);
+    let right = s.lower_bits(readable2.read(&s), width);
+    let (res, compares) = compare_lanes(&s, vex_predicate(immediate), format, s.lower_bits(left, width), right);
+    s.raise_float_exceptions(&compares);
+    let merged = s.zext_to(s.change(left, 0, width, res), 512);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+pub fn apply_iform_comisd(arena: Arena, instr: COMISD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        COMISD::COMISD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Double, operand_0, operand_1, true)
+        }
+        COMISD::COMISD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Double, operand_0, operand_1, true)
+        }
+    }
+}
+
+pub fn apply_iform_vcomisd(arena: Arena, instr: VCOMISD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCOMISD::VCOMISD_XMMQ_MEMQ { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Double, operand_0, operand_1, true)
+        }
+        VCOMISD::VCOMISD_XMMQ_XMMQ { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Double, operand_0, operand_1, true)
+        }
+        VCOMISD::VCOMISD_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCOMISD(instr)));
+        }
+        VCOMISD::VCOMISD_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCOMISD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_comiss(arena: Arena, instr: COMISS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        COMISS::COMISS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Single, operand_0, operand_1, true)
+        }
+        COMISS::COMISS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Single, operand_0, operand_1, true)
+        }
+    }
+}
+
+pub fn apply_iform_vcomiss(arena: Arena, instr: VCOMISS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCOMISS::VCOMISS_XMMD_MEMD { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Single, operand_0, operand_1, true)
+        }
+        VCOMISS::VCOMISS_XMMD_XMMD { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Single, operand_0, operand_1, true)
+        }
+        VCOMISS::VCOMISS_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCOMISS(instr)));
+        }
+        VCOMISS::VCOMISS_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCOMISS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_ucomisd(arena: Arena, instr: UCOMISD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        UCOMISD::UCOMISD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Double, operand_0, operand_1, false)
+        }
+        UCOMISD::UCOMISD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Double, operand_0, operand_1, false)
+        }
+    }
+}
+
+pub fn apply_iform_vucomisd(arena: Arena, instr: VUCOMISD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VUCOMISD::VUCOMISD_XMMDQ_MEMQ { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Double, operand_0, operand_1, false)
+        }
+        VUCOMISD::VUCOMISD_XMMDQ_XMMQ { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Double, operand_0, operand_1, false)
+        }
+        VUCOMISD::VUCOMISD_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VUCOMISD(instr)));
+        }
+        VUCOMISD::VUCOMISD_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VUCOMISD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_ucomiss(arena: Arena, instr: UCOMISS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        UCOMISS::UCOMISS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Single, operand_0, operand_1, false)
+        }
+        UCOMISS::UCOMISS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Single, operand_0, operand_1, false)
+        }
+    }
+}
+
+pub fn apply_iform_vucomiss(arena: Arena, instr: VUCOMISS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VUCOMISS::VUCOMISS_XMMDQ_MEMD { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Single, operand_0, operand_1, false)
+        }
+        VUCOMISS::VUCOMISS_XMMDQ_XMMD { operand_0, operand_1 } => {
+            comis_generic(arena, FloatFormat::Single, operand_0, operand_1, false)
+        }
+        VUCOMISS::VUCOMISS_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VUCOMISS(instr)));
+        }
+        VUCOMISS::VUCOMISS_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VUCOMISS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_cmppd(arena: Arena, instr: CMPPD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMPPD::CMPPD_XMMPD_MEMPD_IMMB_128 { operand_0, operand_1, operand_2 } => {
+            sse_compare_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, operand_2)
+        }
+        CMPPD::CMPPD_XMMPD_XMMPD_IMMB { operand_0, operand_1, operand_2 } => {
+            sse_compare_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, operand_2)
+        }
+    }
+}
+
+pub fn apply_iform_cmpps(arena: Arena, instr: CMPPS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMPPS::CMPPS_XMMPS_MEMPS_IMMB_128 { operand_0, operand_1, operand_2 } => {
+            sse_compare_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, operand_2)
+        }
+        CMPPS::CMPPS_XMMPS_XMMPS_IMMB { operand_0, operand_1, operand_2 } => {
+            sse_compare_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, operand_2)
+        }
+    }
+}
+
+pub fn apply_iform_cmpsd_xmm(arena: Arena, instr: CMPSD_XMM) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMPSD_XMM::CMPSD_XMM_XMMSD_MEMSD_IMMB { operand_0, operand_1, operand_2 } => {
+            sse_compare_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, operand_2)
+        }
+        CMPSD_XMM::CMPSD_XMM_XMMSD_XMMSD_IMMB { operand_0, operand_1, operand_2 } => {
+            sse_compare_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, operand_2)
+        }
+    }
+}
+
+pub fn apply_iform_cmpss(arena: Arena, instr: CMPSS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CMPSS::CMPSS_XMMSS_MEMSS_IMMB { operand_0, operand_1, operand_2 } => {
+            sse_compare_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, operand_2)
+        }
+        CMPSS::CMPSS_XMMSS_XMMSS_IMMB { operand_0, operand_1, operand_2 } => {
+            sse_compare_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, operand_2)
+        }
+    }
+}
+
+pub fn apply_iform_vcmppd(arena: Arena, instr: VCMPPD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCMPPD::VCMPPD_XMMDQ_XMMDQ_MEMDQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, operand_3)
+        }
+        VCMPPD::VCMPPD_XMMDQ_XMMDQ_XMMDQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, operand_3)
+        }
+        VCMPPD::VCMPPD_YMMQQ_YMMQQ_MEMQQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, operand_3)
+        }
+        VCMPPD::VCMPPD_YMMQQ_YMMQQ_YMMQQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, operand_3)
+        }
+        VCMPPD::VCMPPD_MASKMSKW_MASKMSKW_ZMMF64_ZMMF64_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPD(instr)));
+        }
+        VCMPPD::VCMPPD_MASKMSKW_MASKMSKW_ZMMF64_MEMF64_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPD(instr)));
+        }
+        VCMPPD::VCMPPD_MASKMSKW_MASKMSKW_XMMF64_XMMF64_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPD(instr)));
+        }
+        VCMPPD::VCMPPD_MASKMSKW_MASKMSKW_XMMF64_MEMF64_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPD(instr)));
+        }
+        VCMPPD::VCMPPD_MASKMSKW_MASKMSKW_YMMF64_YMMF64_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPD(instr)));
+        }
+        VCMPPD::VCMPPD_MASKMSKW_MASKMSKW_YMMF64_MEMF64_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vcmpps(arena: Arena, instr: VCMPPS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCMPPS::VCMPPS_XMMDQ_XMMDQ_MEMDQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, operand_3)
+        }
+        VCMPPS::VCMPPS_XMMDQ_XMMDQ_XMMDQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, operand_3)
+        }
+        VCMPPS::VCMPPS_YMMQQ_YMMQQ_MEMQQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, operand_3)
+        }
+        VCMPPS::VCMPPS_YMMQQ_YMMQQ_YMMQQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, operand_3)
+        }
+        VCMPPS::VCMPPS_MASKMSKW_MASKMSKW_ZMMF32_ZMMF32_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPS(instr)));
+        }
+        VCMPPS::VCMPPS_MASKMSKW_MASKMSKW_ZMMF32_MEMF32_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPS(instr)));
+        }
+        VCMPPS::VCMPPS_MASKMSKW_MASKMSKW_XMMF32_XMMF32_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPS(instr)));
+        }
+        VCMPPS::VCMPPS_MASKMSKW_MASKMSKW_XMMF32_MEMF32_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPS(instr)));
+        }
+        VCMPPS::VCMPPS_MASKMSKW_MASKMSKW_YMMF32_YMMF32_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPS(instr)));
+        }
+        VCMPPS::VCMPPS_MASKMSKW_MASKMSKW_YMMF32_MEMF32_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPPS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vcmpsd(arena: Arena, instr: VCMPSD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCMPSD::VCMPSD_XMMDQ_XMMDQ_MEMQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, operand_3)
+        }
+        VCMPSD::VCMPSD_XMMDQ_XMMDQ_XMMQ_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, operand_3)
+        }
+        VCMPSD::VCMPSD_MASKMSKW_MASKMSKW_XMMF64_XMMF64_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPSD(instr)));
+        }
+        VCMPSD::VCMPSD_MASKMSKW_MASKMSKW_XMMF64_MEMF64_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPSD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vcmpss(arena: Arena, instr: VCMPSS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCMPSS::VCMPSS_XMMDQ_XMMDQ_MEMD_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, operand_3)
+        }
+        VCMPSS::VCMPSS_XMMDQ_XMMDQ_XMMD_IMMB { operand_0, operand_1, operand_2, operand_3 } => {
+            vex_compare_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, operand_3)
+        }
+        VCMPSS::VCMPSS_MASKMSKW_MASKMSKW_XMMF32_XMMF32_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPSS(instr)));
+        }
+        VCMPSS::VCMPSS_MASKMSKW_MASKMSKW_XMMF32_MEMF32_IMM8_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCMPSS(instr)));
+        }
+    })
+}
@@ -0,0 +1,447 @@
+use wrapper_common::registers::RegZMM;
+use xed_enum::{CVTSD2SI, CVTSD2SS, CVTSI2SD, CVTSI2SS, CVTSS2SD, CVTSS2SI, CVTTSD2SI, CVTTSS2SI, VCVTSD2SI, VCVTSD2SS, VCVTSI2SD, VCVTSI2SS, VCVTSS2SD, VCVTSS2SI, VCVTTSD2SI, VCVTTSS2SI, X86Instruction};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::float::FloatFormat;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+/// CVTSD2SS and CVTSS2SD, the rest of the destination is left alone.
+pub fn convert_scalar_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    from: FloatFormat,
+    to: FloatFormat,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST[to width-1:0] := Convert(SRC[from width-1:0]);
+    // DEST[MAXVL-1:to width] (Unmodified)
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(from.width());
+    let dest = readable1.read(&s);
+    let src = s.lower_bits(readable2.read(&s), from.width());
+    let res = s.float_convert(from, to, src);
+    s.raise_float_exceptions(&[res]);
+    let merged = s.change(dest, 0, to.width(), res);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+pub fn vex_convert_scalar_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    from: FloatFormat,
+    to: FloatFormat,
+    writeable1: RegZMM,
+    readable1: S1,
+    readable2: S2,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST[to width-1:0] := Convert(SRC2[from width-1:0]);
+    // DEST[127:to width] := SRC1[127:to width]
+    // DEST[MAXVL-1:128] := 0
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(from.width());
+    let left = readable1.read(&s);
+    let src = s.lower_bits(readable2.read(&s), from.width());
+    let res = s.float_convert(from, to, src);
+    s.raise_float_exceptions(&[res]);
+    let merged = s.zext_to(s.change(left, 0, to.width(), res), 512);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+/// CVTSI2SD and CVTSI2SS from a `int_width` bit signed integer, the rest of the destination is left alone.
+pub fn int_to_float_scalar_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    int_width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST[lane 0] := Convert_Integer_To_Float(SRC);
+    // DEST[MAXVL-1:lane width] (Unmodified)
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(int_width);
+    let dest = readable1.read(&s);
+    let res = s.int_to_float(format, readable2.read(&s));
+    s.raise_float_exceptions(&[res]);
+    let merged = s.change(dest, 0, format.width(), res);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+pub fn vex_int_to_float_scalar_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: RegZMM,
+    readable1: S1,
+    readable2: S2,
+    int_width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST[lane 0] := Convert_Integer_To_Float(SRC2);
+    // DEST[127:lane width] := SRC1[127:lane width]
+    // DEST[MAXVL-1:128] := 0
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(int_width);
+    let left = readable1.read(&s);
+    let res = s.int_to_float(format, readable2.read(&s));
+    s.raise_float_exceptions(&[res]);
+    let merged = s.zext_to(s.change(left, 0, format.width(), res), 512);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+/// CVTSD2SI and friends into a `width` bit register. Out of range and NaN sources give the integer indefinite
+/// value, `1 << (width - 1)`.
+pub fn float_to_int_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+    truncate: bool,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST := Convert_Float_To_Integer(SRC[lane 0]); (or Convert_Float_To_Integer_Truncate)
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(format.width());
+    let src = s.lower_bits(readable1.read(&s), format.width());
+    let res = s.float_to_int(format, src, width, truncate);
+    s.raise_float_exceptions(&[res]);
+    writeable1.write(&mut s, res);
+    s.finalize()
+}
+
+pub fn apply_iform_cvtsd2ss(arena: Arena, instr: CVTSD2SS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CVTSD2SS::CVTSD2SS_XMMSS_MEMSD { operand_0, operand_1 } => {
+            convert_scalar_generic(arena, FloatFormat::Double, FloatFormat::Single, operand_0, operand_0, operand_1)
+        }
+        CVTSD2SS::CVTSD2SS_XMMSS_XMMSD { operand_0, operand_1 } => {
+            convert_scalar_generic(arena, FloatFormat::Double, FloatFormat::Single, operand_0, operand_0, operand_1)
+        }
+    }
+}
+
+pub fn apply_iform_vcvtsd2ss(arena: Arena, instr: VCVTSD2SS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCVTSD2SS::VCVTSD2SS_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_convert_scalar_generic(arena, FloatFormat::Double, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2)
+        }
+        VCVTSD2SS::VCVTSD2SS_XMMDQ_XMMDQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            vex_convert_scalar_generic(arena, FloatFormat::Double, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2)
+        }
+        VCVTSD2SS::VCVTSD2SS_XMMF32_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSD2SS(instr)));
+        }
+        VCVTSD2SS::VCVTSD2SS_XMMF32_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSD2SS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_cvtss2sd(arena: Arena, instr: CVTSS2SD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CVTSS2SD::CVTSS2SD_XMMSD_MEMSS { operand_0, operand_1 } => {
+            convert_scalar_generic(arena, FloatFormat::Single, FloatFormat::Double, operand_0, operand_0, operand_1)
+        }
+        CVTSS2SD::CVTSS2SD_XMMSD_XMMSS { operand_0, operand_1 } => {
+            convert_scalar_generic(arena, FloatFormat::Single, FloatFormat::Double, operand_0, operand_0, operand_1)
+        }
+    }
+}
+
+pub fn apply_iform_vcvtss2sd(arena: Arena, instr: VCVTSS2SD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCVTSS2SD::VCVTSS2SD_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_convert_scalar_generic(arena, FloatFormat::Single, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2)
+        }
+        VCVTSS2SD::VCVTSS2SD_XMMDQ_XMMDQ_XMMD { operand_0, operand_1, operand_2 } => {
+            vex_convert_scalar_generic(arena, FloatFormat::Single, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2)
+        }
+        VCVTSS2SD::VCVTSS2SD_XMMF64_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSS2SD(instr)));
+        }
+        VCVTSS2SD::VCVTSS2SD_XMMF64_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSS2SD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_cvtsi2sd(arena: Arena, instr: CVTSI2SD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CVTSI2SD::CVTSI2SD_XMMSD_MEMD { operand_0, operand_1 } => {
+            int_to_float_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, 32)
+        }
+        CVTSI2SD::CVTSI2SD_XMMSD_GPR32D { operand_0, operand_1 } => {
+            int_to_float_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, 32)
+        }
+        CVTSI2SD::CVTSI2SD_XMMSD_MEMQ { operand_0, operand_1 } => {
+            int_to_float_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, 64)
+        }
+        CVTSI2SD::CVTSI2SD_XMMSD_GPR64Q { operand_0, operand_1 } => {
+            int_to_float_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_vcvtsi2sd(arena: Arena, instr: VCVTSI2SD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCVTSI2SD::VCVTSI2SD_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_int_to_float_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 32)
+        }
+        VCVTSI2SD::VCVTSI2SD_XMMDQ_XMMDQ_GPR32D { operand_0, operand_1, operand_2 } => {
+            vex_int_to_float_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 32)
+        }
+        VCVTSI2SD::VCVTSI2SD_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_int_to_float_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 64)
+        }
+        VCVTSI2SD::VCVTSI2SD_XMMDQ_XMMDQ_GPR64Q { operand_0, operand_1, operand_2 } => {
+            vex_int_to_float_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 64)
+        }
+        VCVTSI2SD::VCVTSI2SD_XMMF64_XMMF64_GPR32I32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSI2SD(instr)));
+        }
+        VCVTSI2SD::VCVTSI2SD_XMMF64_XMMF64_MEMI32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSI2SD(instr)));
+        }
+        VCVTSI2SD::VCVTSI2SD_XMMF64_XMMF64_GPR64I64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSI2SD(instr)));
+        }
+        VCVTSI2SD::VCVTSI2SD_XMMF64_XMMF64_MEMI64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSI2SD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_cvtsi2ss(arena: Arena, instr: CVTSI2SS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CVTSI2SS::CVTSI2SS_XMMSS_MEMD { operand_0, operand_1 } => {
+            int_to_float_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, 32)
+        }
+        CVTSI2SS::CVTSI2SS_XMMSS_GPR32D { operand_0, operand_1 } => {
+            int_to_float_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, 32)
+        }
+        CVTSI2SS::CVTSI2SS_XMMSS_MEMQ { operand_0, operand_1 } => {
+            int_to_float_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, 64)
+        }
+        CVTSI2SS::CVTSI2SS_XMMSS_GPR64Q { operand_0, operand_1 } => {
+            int_to_float_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
+
+pub fn apply_iform_vcvtsi2ss(arena: Arena, instr: VCVTSI2SS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCVTSI2SS::VCVTSI2SS_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_int_to_float_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 32)
+        }
+        VCVTSI2SS::VCVTSI2SS_XMMDQ_XMMDQ_GPR32D { operand_0, operand_1, operand_2 } => {
+            vex_int_to_float_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 32)
+        }
+        VCVTSI2SS::VCVTSI2SS_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_int_to_float_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 64)
+        }
+        VCVTSI2SS::VCVTSI2SS_XMMDQ_XMMDQ_GPR64Q { operand_0, operand_1, operand_2 } => {
+            vex_int_to_float_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 64)
+        }
+        VCVTSI2SS::VCVTSI2SS_XMMF32_XMMF32_GPR32I32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSI2SS(instr)));
+        }
+        VCVTSI2SS::VCVTSI2SS_XMMF32_XMMF32_MEMI32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSI2SS(instr)));
+        }
+        VCVTSI2SS::VCVTSI2SS_XMMF32_XMMF32_GPR64I64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSI2SS(instr)));
+        }
+        VCVTSI2SS::VCVTSI2SS_XMMF32_XMMF32_MEMI64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSI2SS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_cvtsd2si(arena: Arena, instr: CVTSD2SI) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CVTSD2SI::CVTSD2SI_GPR32D_MEMSD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 32, false)
+        }
+        CVTSD2SI::CVTSD2SI_GPR32D_XMMSD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 32, false)
+        }
+        CVTSD2SI::CVTSD2SI_GPR64Q_MEMSD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 64, false)
+        }
+        CVTSD2SI::CVTSD2SI_GPR64Q_XMMSD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 64, false)
+        }
+    }
+}
+
+pub fn apply_iform_vcvtsd2si(arena: Arena, instr: VCVTSD2SI) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCVTSD2SI::VCVTSD2SI_GPR32D_MEMQ { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 32, false)
+        }
+        VCVTSD2SI::VCVTSD2SI_GPR32D_XMMQ { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 32, false)
+        }
+        VCVTSD2SI::VCVTSD2SI_GPR64Q_MEMQ { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 64, false)
+        }
+        VCVTSD2SI::VCVTSD2SI_GPR64Q_XMMQ { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 64, false)
+        }
+        VCVTSD2SI::VCVTSD2SI_GPR32I32_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSD2SI(instr)));
+        }
+        VCVTSD2SI::VCVTSD2SI_GPR32I32_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSD2SI(instr)));
+        }
+        VCVTSD2SI::VCVTSD2SI_GPR64I64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSD2SI(instr)));
+        }
+        VCVTSD2SI::VCVTSD2SI_GPR64I64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSD2SI(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_cvtss2si(arena: Arena, instr: CVTSS2SI) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CVTSS2SI::CVTSS2SI_GPR32D_MEMSS { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 32, false)
+        }
+        CVTSS2SI::CVTSS2SI_GPR32D_XMMSS { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 32, false)
+        }
+        CVTSS2SI::CVTSS2SI_GPR64Q_MEMSS { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 64, false)
+        }
+        CVTSS2SI::CVTSS2SI_GPR64Q_XMMSS { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 64, false)
+        }
+    }
+}
+
+pub fn apply_iform_vcvtss2si(arena: Arena, instr: VCVTSS2SI) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCVTSS2SI::VCVTSS2SI_GPR32D_MEMD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 32, false)
+        }
+        VCVTSS2SI::VCVTSS2SI_GPR32D_XMMD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 32, false)
+        }
+        VCVTSS2SI::VCVTSS2SI_GPR64Q_MEMD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 64, false)
+        }
+        VCVTSS2SI::VCVTSS2SI_GPR64Q_XMMD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 64, false)
+        }
+        VCVTSS2SI::VCVTSS2SI_GPR32I32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSS2SI(instr)));
+        }
+        VCVTSS2SI::VCVTSS2SI_GPR32I32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSS2SI(instr)));
+        }
+        VCVTSS2SI::VCVTSS2SI_GPR64I64_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSS2SI(instr)));
+        }
+        VCVTSS2SI::VCVTSS2SI_GPR64I64_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTSS2SI(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_cvttsd2si(arena: Arena, instr: CVTTSD2SI) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CVTTSD2SI::CVTTSD2SI_GPR32D_MEMSD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 32, true)
+        }
+        CVTTSD2SI::CVTTSD2SI_GPR32D_XMMSD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 32, true)
+        }
+        CVTTSD2SI::CVTTSD2SI_GPR64Q_MEMSD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 64, true)
+        }
+        CVTTSD2SI::CVTTSD2SI_GPR64Q_XMMSD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 64, true)
+        }
+    }
+}
+
+pub fn apply_iform_vcvttsd2si(arena: Arena, instr: VCVTTSD2SI) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCVTTSD2SI::VCVTTSD2SI_GPR32D_MEMQ { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 32, true)
+        }
+        VCVTTSD2SI::VCVTTSD2SI_GPR32D_XMMQ { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 32, true)
+        }
+        VCVTTSD2SI::VCVTTSD2SI_GPR64Q_MEMQ { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 64, true)
+        }
+        VCVTTSD2SI::VCVTTSD2SI_GPR64Q_XMMQ { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Double, operand_0, operand_1, 64, true)
+        }
+        VCVTTSD2SI::VCVTTSD2SI_GPR32I32_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTTSD2SI(instr)));
+        }
+        VCVTTSD2SI::VCVTTSD2SI_GPR32I32_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTTSD2SI(instr)));
+        }
+        VCVTTSD2SI::VCVTTSD2SI_GPR64I64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTTSD2SI(instr)));
+        }
+        VCVTTSD2SI::VCVTTSD2SI_GPR64I64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTTSD2SI(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_cvttss2si(arena: Arena, instr: CVTTSS2SI) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        CVTTSS2SI::CVTTSS2SI_GPR32D_MEMSS { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 32, true)
+        }
+        CVTTSS2SI::CVTTSS2SI_GPR32D_XMMSS { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 32, true)
+        }
+        CVTTSS2SI::CVTTSS2SI_GPR64Q_MEMSS { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 64, true)
+        }
+        CVTTSS2SI::CVTTSS2SI_GPR64Q_XMMSS { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 64, true)
+        }
+    }
+}
+
+pub fn apply_iform_vcvttss2si(arena: Arena, instr: VCVTTSS2SI) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VCVTTSS2SI::VCVTTSS2SI_GPR32D_MEMD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 32, true)
+        }
+        VCVTTSS2SI::VCVTTSS2SI_GPR32D_XMMD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 32, true)
+        }
+        VCVTTSS2SI::VCVTTSS2SI_GPR64Q_MEMD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 64, true)
+        }
+        VCVTTSS2SI::VCVTTSS2SI_GPR64Q_XMMD { operand_0, operand_1 } => {
+            float_to_int_generic(arena, FloatFormat::Single, operand_0, operand_1, 64, true)
+        }
+        VCVTTSS2SI::VCVTTSS2SI_GPR32I32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTTSS2SI(instr)));
+        }
+        VCVTTSS2SI::VCVTTSS2SI_GPR32I32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTTSS2SI(instr)));
+        }
+        VCVTTSS2SI::VCVTTSS2SI_GPR64I64_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTTSS2SI(instr)));
+        }
+        VCVTTSS2SI::VCVTTSS2SI_GPR64I64_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VCVTTSS2SI(instr)));
+        }
+    })
+}
@@ -0,0 +1,386 @@
+use wrapper_common::registers::RegZMM;
+use xed_enum::{VFMADD132PD, VFMADD132PS, VFMADD132SD, VFMADD132SS, VFMADD213PD, VFMADD213PS, VFMADD213SD, VFMADD213SS, VFMADD231PD, VFMADD231PS, VFMADD231SD, VFMADD231SS, X86Instruction};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::float::FloatFormat;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+// The 132, 213 and 231 forms only differ in which operands get multiplied, so the match arms below pass them in
+// multiplicand, multiplier, addend order.
+
+/// `left * right + addend` on each `format` lane with a single rounding, bits `width` and up of the destination
+/// are zeroed.
+pub fn fma_packed_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>, S3: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: RegZMM,
+    readable1: S1,
+    readable2: S2,
+    readable3: S3,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // FOR EACH LANE: DEST[lane] := RoundFPControl_MXCSR(SRC1[lane] * SRC2[lane] + SRC3[lane])
+    // DEST[MAXVL-1:VL] := 0
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let left = readable1.read(&s);
+    let right = readable2.read(&s);
+    let addend = readable3.read(&s);
+    let lane_width = format.width();
+    let lanes = (0..width / lane_width)
+        .map(|lane| {
+            let low = lane * lane_width;
+            let high = low + lane_width;
+            s.float_fma(format, s.extract(left, low, high), s.extract(right, low, high), s.extract(addend, low, high))
+        })
+        .collect::<Vec<_>>();
+    let res = lanes.iter().copied()
+        .reduce(|low, high| s.concat(high, low))
+        .expect("no lanes");
+    s.raise_float_exceptions(&lanes);
+    let widened = s.zext_to(res, 512);
+    writeable1.write(&mut s, widened);
+    s.finalize()
+}
+
+/// `left * right + addend` on the low `format` lane. The rest of the low 128 bits of the destination are left
+/// alone, and bits 128 and up are zeroed.
+pub fn fma_scalar_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>, S3: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: RegZMM,
+    readable1: S1,
+    readable2: S2,
+    readable3: S3,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST[lane 0] := RoundFPControl_MXCSR(SRC1[lane 0] * SRC2[lane 0] + SRC3[lane 0])
+    // DEST[127:lane width] := DEST[127:lane width]
+    // DEST[MAXVL-1:128] := 0
+    let mut s = SemanticsBuilder::new(arena);
+    let width = format.width();
+    s.set_memory_operand_width(width);
+    let dest = s.lower_bits(writeable1.read(&s), 128);
+    let left = s.lower_bits(readable1.read(&s), width);
+    let right = s.lower_bits(readable2.read(&s), width);
+    let addend = s.lower_bits(readable3.read(&s), width);
+    let res = s.float_fma(format, left, right, addend);
+    s.raise_float_exceptions(&[res]);
+    let merged = s.zext_to(s.change(dest, 0, width, res), 512);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+pub fn apply_iform_vfmadd132pd(arena: Arena, instr: VFMADD132PD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD132PD::VFMADD132PD_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1, 128)
+        }
+        VFMADD132PD::VFMADD132PD_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1, 128)
+        }
+        VFMADD132PD::VFMADD132PD_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1, 256)
+        }
+        VFMADD132PD::VFMADD132PD_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1, 256)
+        }
+        VFMADD132PD::VFMADD132PD_ZMMF64_MASKMSKW_ZMMF64_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PD(instr)));
+        }
+        VFMADD132PD::VFMADD132PD_ZMMF64_MASKMSKW_ZMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PD(instr)));
+        }
+        VFMADD132PD::VFMADD132PD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PD(instr)));
+        }
+        VFMADD132PD::VFMADD132PD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PD(instr)));
+        }
+        VFMADD132PD::VFMADD132PD_YMMF64_MASKMSKW_YMMF64_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PD(instr)));
+        }
+        VFMADD132PD::VFMADD132PD_YMMF64_MASKMSKW_YMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd132ps(arena: Arena, instr: VFMADD132PS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD132PS::VFMADD132PS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1, 128)
+        }
+        VFMADD132PS::VFMADD132PS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1, 128)
+        }
+        VFMADD132PS::VFMADD132PS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1, 256)
+        }
+        VFMADD132PS::VFMADD132PS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1, 256)
+        }
+        VFMADD132PS::VFMADD132PS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PS(instr)));
+        }
+        VFMADD132PS::VFMADD132PS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PS(instr)));
+        }
+        VFMADD132PS::VFMADD132PS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PS(instr)));
+        }
+        VFMADD132PS::VFMADD132PS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PS(instr)));
+        }
+        VFMADD132PS::VFMADD132PS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PS(instr)));
+        }
+        VFMADD132PS::VFMADD132PS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132PS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd132sd(arena: Arena, instr: VFMADD132SD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD132SD::VFMADD132SD_XMMDQ_XMMQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1)
+        }
+        VFMADD132SD::VFMADD132SD_XMMDQ_XMMQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1)
+        }
+        VFMADD132SD::VFMADD132SD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132SD(instr)));
+        }
+        VFMADD132SD::VFMADD132SD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132SD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd132ss(arena: Arena, instr: VFMADD132SS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD132SS::VFMADD132SS_XMMDQ_XMMD_MEMD { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1)
+        }
+        VFMADD132SS::VFMADD132SS_XMMDQ_XMMD_XMMD { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_0, operand_2, operand_1)
+        }
+        VFMADD132SS::VFMADD132SS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132SS(instr)));
+        }
+        VFMADD132SS::VFMADD132SS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD132SS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd213pd(arena: Arena, instr: VFMADD213PD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD213PD::VFMADD213PD_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2, 128)
+        }
+        VFMADD213PD::VFMADD213PD_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2, 128)
+        }
+        VFMADD213PD::VFMADD213PD_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2, 256)
+        }
+        VFMADD213PD::VFMADD213PD_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2, 256)
+        }
+        VFMADD213PD::VFMADD213PD_ZMMF64_MASKMSKW_ZMMF64_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PD(instr)));
+        }
+        VFMADD213PD::VFMADD213PD_ZMMF64_MASKMSKW_ZMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PD(instr)));
+        }
+        VFMADD213PD::VFMADD213PD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PD(instr)));
+        }
+        VFMADD213PD::VFMADD213PD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PD(instr)));
+        }
+        VFMADD213PD::VFMADD213PD_YMMF64_MASKMSKW_YMMF64_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PD(instr)));
+        }
+        VFMADD213PD::VFMADD213PD_YMMF64_MASKMSKW_YMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd213ps(arena: Arena, instr: VFMADD213PS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD213PS::VFMADD213PS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2, 128)
+        }
+        VFMADD213PS::VFMADD213PS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2, 128)
+        }
+        VFMADD213PS::VFMADD213PS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2, 256)
+        }
+        VFMADD213PS::VFMADD213PS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2, 256)
+        }
+        VFMADD213PS::VFMADD213PS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PS(instr)));
+        }
+        VFMADD213PS::VFMADD213PS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PS(instr)));
+        }
+        VFMADD213PS::VFMADD213PS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PS(instr)));
+        }
+        VFMADD213PS::VFMADD213PS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PS(instr)));
+        }
+        VFMADD213PS::VFMADD213PS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PS(instr)));
+        }
+        VFMADD213PS::VFMADD213PS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213PS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd213sd(arena: Arena, instr: VFMADD213SD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD213SD::VFMADD213SD_XMMDQ_XMMQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2)
+        }
+        VFMADD213SD::VFMADD213SD_XMMDQ_XMMQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2)
+        }
+        VFMADD213SD::VFMADD213SD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213SD(instr)));
+        }
+        VFMADD213SD::VFMADD213SD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213SD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd213ss(arena: Arena, instr: VFMADD213SS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD213SS::VFMADD213SS_XMMDQ_XMMD_MEMD { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2)
+        }
+        VFMADD213SS::VFMADD213SS_XMMDQ_XMMD_XMMD { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_0, operand_2)
+        }
+        VFMADD213SS::VFMADD213SS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213SS(instr)));
+        }
+        VFMADD213SS::VFMADD213SS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD213SS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd231pd(arena: Arena, instr: VFMADD231PD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD231PD::VFMADD231PD_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0, 128)
+        }
+        VFMADD231PD::VFMADD231PD_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0, 128)
+        }
+        VFMADD231PD::VFMADD231PD_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0, 256)
+        }
+        VFMADD231PD::VFMADD231PD_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0, 256)
+        }
+        VFMADD231PD::VFMADD231PD_ZMMF64_MASKMSKW_ZMMF64_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PD(instr)));
+        }
+        VFMADD231PD::VFMADD231PD_ZMMF64_MASKMSKW_ZMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PD(instr)));
+        }
+        VFMADD231PD::VFMADD231PD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PD(instr)));
+        }
+        VFMADD231PD::VFMADD231PD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PD(instr)));
+        }
+        VFMADD231PD::VFMADD231PD_YMMF64_MASKMSKW_YMMF64_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PD(instr)));
+        }
+        VFMADD231PD::VFMADD231PD_YMMF64_MASKMSKW_YMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd231ps(arena: Arena, instr: VFMADD231PS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD231PS::VFMADD231PS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0, 128)
+        }
+        VFMADD231PS::VFMADD231PS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0, 128)
+        }
+        VFMADD231PS::VFMADD231PS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0, 256)
+        }
+        VFMADD231PS::VFMADD231PS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            fma_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0, 256)
+        }
+        VFMADD231PS::VFMADD231PS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PS(instr)));
+        }
+        VFMADD231PS::VFMADD231PS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PS(instr)));
+        }
+        VFMADD231PS::VFMADD231PS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PS(instr)));
+        }
+        VFMADD231PS::VFMADD231PS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PS(instr)));
+        }
+        VFMADD231PS::VFMADD231PS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PS(instr)));
+        }
+        VFMADD231PS::VFMADD231PS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231PS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd231sd(arena: Arena, instr: VFMADD231SD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD231SD::VFMADD231SD_XMMDQ_XMMQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0)
+        }
+        VFMADD231SD::VFMADD231SD_XMMDQ_XMMQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0)
+        }
+        VFMADD231SD::VFMADD231SD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231SD(instr)));
+        }
+        VFMADD231SD::VFMADD231SD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231SD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vfmadd231ss(arena: Arena, instr: VFMADD231SS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VFMADD231SS::VFMADD231SS_XMMDQ_XMMD_MEMD { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0)
+        }
+        VFMADD231SS::VFMADD231SS_XMMDQ_XMMD_XMMD { operand_0, operand_1, operand_2 } => {
+            fma_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, operand_0)
+        }
+        VFMADD231SS::VFMADD231SS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231SS(instr)));
+        }
+        VFMADD231SS::VFMADD231SS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VFMADD231SS(instr)));
+        }
+    })
+}
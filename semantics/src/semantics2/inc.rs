@@ -0,0 +1,66 @@
+use xed_enum::INC;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::{legacy_mode_form, SemanticsBuilder};
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+pub fn inc_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := DEST + 1;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let one = s.constant_with_width(1, width);
+    let res = s.add(dest, one);
+    writeable1.write(&mut s, res);
+    s.flag_calculate(FlagTag::Inc, dest, one);
+    s.finalize()
+}
+
+pub fn apply_iform_inc(arena: Arena, instr: INC) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        INC::INC_MEMB { operand_0 } => {
+            inc_generic(arena, operand_0, operand_0, 8)
+        }
+        INC::INC_GPR8 { operand_0 } => {
+            inc_generic(arena, operand_0, operand_0, 8)
+        }
+        INC::INC_MEMV_16 { operand_0 } => {
+            inc_generic(arena, operand_0, operand_0, 16)
+        }
+        INC::INC_MEMV_32 { operand_0 } => {
+            inc_generic(arena, operand_0, operand_0, 32)
+        }
+        INC::INC_MEMV_64 { operand_0 } => {
+            inc_generic(arena, operand_0, operand_0, 64)
+        }
+        INC::INC_GPRV_FFR0_16 { operand_0 } => {
+            inc_generic(arena, operand_0, operand_0, 16)
+        }
+        INC::INC_GPRV_FFR0_32 { operand_0 } => {
+            inc_generic(arena, operand_0, operand_0, 32)
+        }
+        INC::INC_GPRV_FFR0_64 { operand_0 } => {
+            inc_generic(arena, operand_0, operand_0, 64)
+        }
+        INC::INC_GPRV_40_16 { operand_0 } => {
+            // 0x40 to 0x47 are REX prefixes in 64 bit mode
+            legacy_mode_form(arena, |arena| inc_generic(arena, operand_0, operand_0, 16))
+        }
+        INC::INC_GPRV_40_32 { operand_0 } => {
+            // 0x40 to 0x47 are REX prefixes in 64 bit mode
+            legacy_mode_form(arena, |arena| inc_generic(arena, operand_0, operand_0, 32))
+        }
+        INC::INC_GPRV_40_64 { .. } => {
+            // a REX prefix in 64 bit mode, and there are no 64 bit registers outside of it
+            let mut s = SemanticsBuilder::new(arena);
+            s.undefined_exception();
+            s.finalize()
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use wrapper_common::memory_operand::GeneralReg;
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP};
+use xed_enum::{JB, JBE, JCXZ, JECXZ, JL, JLE, JNB, JNBE, JNL, JNLE, JNO, JNP, JNS, JNZ, JO, JP, JRCXZ, JS, JZ, LOOP, LOOPE, LOOPNE};
+use xed_wrapper::operands::RelativeBr;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::condition::Condition;
+use crate::semantics2::expression::Expression;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+impl<'arena> SemanticsBuilder<'arena> {
+    /// RIP := the target of `displacement` if `taken` is set, otherwise RIP stays at the next instruction.
+    pub fn conditional_branch(&mut self, taken: &'arena Expression<'arena>, displacement: RelativeBr) {
+        let target = self.select(taken, self.relative_branch_target(displacement), self.rip());
+        self.set_rip(target);
+    }
+}
+
+pub fn jcc_generic<'arena>(arena: Arena<'arena>, condition: Condition, displacement: RelativeBr) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF condition
+    //    THEN
+    //        tempRIP := RIP + SignExtend(DEST);
+    //        RIP := tempRIP;
+    //FI;
+    let mut s = SemanticsBuilder::new(arena);
+    s.conditional_branch(s.condition(condition), displacement);
+    s.finalize()
+}
+
+/// JCXZ, JECXZ and JRCXZ, which differ only in the width of the counter they test.
+pub fn jrcxz_generic<'arena>(arena: Arena<'arena>, counter: GeneralReg, displacement: RelativeBr) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF Count = 0
+    //    THEN RIP := RIP + SignExtend(DEST);
+    //FI;
+    let mut s = SemanticsBuilder::new(arena);
+    let count = s.get_reg(counter);
+    s.conditional_branch(s.equal(count, s.constant_with_width(0, counter.bit_width())), displacement);
+    s.finalize()
+}
+
+/// LOOP, LOOPE and LOOPNE. The counter is CX, ECX or RCX by the address size, which is taken to be the mode's
+/// default, see [`crate::semantics2::apply_instruction_with_address_width`].
+pub fn loop_generic<'arena>(arena: Arena<'arena>, condition: Option<Condition>, displacement: RelativeBr) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF AddressSize = 32
+    //    THEN Count is ECX;
+    //ELSE IF AddressSize = 64
+    //    THEN Count is RCX;
+    //ELSE Count is CX;
+    //FI;
+    //Count := Count – 1;
+    //IF Count ≠ 0 and condition (* ZF = 1 for LOOPE, ZF = 0 for LOOPNE *)
+    //    THEN RIP := RIP + SignExtend(DEST);
+    //FI;
+    //The flags are not affected.
+    let counter = match arena.mode().address_width() {
+        16 => GeneralReg::Reg16(Reg16WithRIP::CX),
+        32 => GeneralReg::Reg32(Reg32WithRIP::ECX),
+        _ => GeneralReg::Reg64(Reg64WithRIP::RCX),
+    };
+    let width = counter.bit_width();
+    let mut s = SemanticsBuilder::new(arena);
+    let count = s.sub(s.get_reg(counter), s.constant_with_width(1, width));
+    s.set_reg(counter, count);
+    let mut taken = s.not(s.equal(count, s.constant_with_width(0, width)));
+    if let Some(condition) = condition {
+        taken = s.bitand(taken, s.condition(condition));
+    }
+    s.conditional_branch(taken, displacement);
+    s.finalize()
+}
+
+
+pub fn apply_iform_jb(arena: Arena, instr: JB) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JB::JB_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::B, operand_0)
+        }
+        JB::JB_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::B, operand_0)
+        }
+        JB::JB_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::B, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jbe(arena: Arena, instr: JBE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JBE::JBE_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::BE, operand_0)
+        }
+        JBE::JBE_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::BE, operand_0)
+        }
+        JBE::JBE_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::BE, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jl(arena: Arena, instr: JL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JL::JL_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::L, operand_0)
+        }
+        JL::JL_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::L, operand_0)
+        }
+        JL::JL_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::L, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jle(arena: Arena, instr: JLE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JLE::JLE_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::LE, operand_0)
+        }
+        JLE::JLE_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::LE, operand_0)
+        }
+        JLE::JLE_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::LE, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jnb(arena: Arena, instr: JNB) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JNB::JNB_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::NB, operand_0)
+        }
+        JNB::JNB_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::NB, operand_0)
+        }
+        JNB::JNB_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::NB, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jnbe(arena: Arena, instr: JNBE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JNBE::JNBE_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::NBE, operand_0)
+        }
+        JNBE::JNBE_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::NBE, operand_0)
+        }
+        JNBE::JNBE_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::NBE, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jnl(arena: Arena, instr: JNL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JNL::JNL_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::NL, operand_0)
+        }
+        JNL::JNL_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::NL, operand_0)
+        }
+        JNL::JNL_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::NL, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jnle(arena: Arena, instr: JNLE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JNLE::JNLE_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::NLE, operand_0)
+        }
+        JNLE::JNLE_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::NLE, operand_0)
+        }
+        JNLE::JNLE_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::NLE, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jno(arena: Arena, instr: JNO) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JNO::JNO_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::NO, operand_0)
+        }
+        JNO::JNO_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::NO, operand_0)
+        }
+        JNO::JNO_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::NO, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jnp(arena: Arena, instr: JNP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JNP::JNP_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::NP, operand_0)
+        }
+        JNP::JNP_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::NP, operand_0)
+        }
+        JNP::JNP_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::NP, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jns(arena: Arena, instr: JNS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JNS::JNS_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::NS, operand_0)
+        }
+        JNS::JNS_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::NS, operand_0)
+        }
+        JNS::JNS_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::NS, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jnz(arena: Arena, instr: JNZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JNZ::JNZ_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::NZ, operand_0)
+        }
+        JNZ::JNZ_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::NZ, operand_0)
+        }
+        JNZ::JNZ_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::NZ, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jo(arena: Arena, instr: JO) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JO::JO_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::O, operand_0)
+        }
+        JO::JO_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::O, operand_0)
+        }
+        JO::JO_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::O, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jp(arena: Arena, instr: JP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JP::JP_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::P, operand_0)
+        }
+        JP::JP_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::P, operand_0)
+        }
+        JP::JP_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::P, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_js(arena: Arena, instr: JS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JS::JS_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::S, operand_0)
+        }
+        JS::JS_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::S, operand_0)
+        }
+        JS::JS_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::S, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jz(arena: Arena, instr: JZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JZ::JZ_RELBRB { operand_0 } => {
+            jcc_generic(arena, Condition::Z, operand_0)
+        }
+        JZ::JZ_RELBRZ { operand_0 } => {
+            jcc_generic(arena, Condition::Z, operand_0)
+        }
+        JZ::JZ_RELBRD { operand_0 } => {
+            jcc_generic(arena, Condition::Z, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jcxz(arena: Arena, instr: JCXZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JCXZ::JCXZ_RELBRB { operand_0 } => {
+            jrcxz_generic(arena, GeneralReg::Reg16(Reg16WithRIP::CX), operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jecxz(arena: Arena, instr: JECXZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JECXZ::JECXZ_RELBRB { operand_0 } => {
+            jrcxz_generic(arena, GeneralReg::Reg32(Reg32WithRIP::ECX), operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jrcxz(arena: Arena, instr: JRCXZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JRCXZ::JRCXZ_RELBRB { operand_0 } => {
+            jrcxz_generic(arena, GeneralReg::Reg64(Reg64WithRIP::RCX), operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_loop(arena: Arena, instr: LOOP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        LOOP::LOOP_RELBRB { operand_0 } => {
+            loop_generic(arena, None, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_loope(arena: Arena, instr: LOOPE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        LOOPE::LOOPE_RELBRB { operand_0 } => {
+            loop_generic(arena, Some(Condition::Z), operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_loopne(arena: Arena, instr: LOOPNE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        LOOPNE::LOOPNE_RELBRB { operand_0 } => {
+            loop_generic(arena, Some(Condition::NZ), operand_0)
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use xed_enum::{JMP, JMP_FAR, X86Instruction};
+use xed_wrapper::operands::RelativeBr;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::Readable;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+pub fn jmp_relative_generic<'arena>(arena: Arena<'arena>, displacement: RelativeBr) -> Vec<InstructionSemanticsStep<'arena>> {
+    //tempRIP := RIP + DEST;
+    //RIP := tempRIP;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_rip(s.relative_branch_target(displacement));
+    s.finalize()
+}
+
+pub fn jmp_indirect_generic<'arena, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //tempRIP := DEST; (* zero extended from the operand size *)
+    //RIP := tempRIP;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let target = s.zext_to(readable1.read(&s), 64);
+    s.set_rip(target);
+    s.finalize()
+}
+
+pub fn apply_iform_jmp(arena: Arena, instr: JMP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        JMP::JMP_MEMV_16 { operand_0 } => {
+            jmp_indirect_generic(arena, operand_0, 16)
+        }
+        JMP::JMP_MEMV_32 { operand_0 } => {
+            jmp_indirect_generic(arena, operand_0, 32)
+        }
+        JMP::JMP_MEMV_64 { operand_0 } => {
+            jmp_indirect_generic(arena, operand_0, 64)
+        }
+        JMP::JMP_GPRV_16 { operand_0 } => {
+            jmp_indirect_generic(arena, operand_0, 16)
+        }
+        JMP::JMP_GPRV_32 { operand_0 } => {
+            jmp_indirect_generic(arena, operand_0, 32)
+        }
+        JMP::JMP_GPRV_64 { operand_0 } => {
+            jmp_indirect_generic(arena, operand_0, 64)
+        }
+        JMP::JMP_RELBRZ { operand_0 } => {
+            jmp_relative_generic(arena, operand_0)
+        }
+        JMP::JMP_RELBRD { operand_0 } => {
+            jmp_relative_generic(arena, operand_0)
+        }
+        JMP::JMP_RELBRB { operand_0 } => {
+            jmp_relative_generic(arena, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_jmp_far(_arena: Arena, instr: JMP_FAR) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    // far branches load cs, segment selectors are not modelled
+    Err(SemanticsError::Unsupported(X86Instruction::JMP_FAR(instr)))
+}
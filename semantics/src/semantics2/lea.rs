@@ -0,0 +1,34 @@
+use xed_enum::LEA;
+use xed_wrapper::operands::MemoryOperands;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::Writeable;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn lea_generic<'arena, D1: Writeable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    operand: MemoryOperands,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := EffectiveAddress(SRC); (* truncated or zero extended to the operand size *)
+    let mut s = SemanticsBuilder::new(arena);
+    let address = s.lower_bits(s.address_offset(&operand), width);
+    writeable1.write(&mut s, address);
+    s.finalize()
+}
+
+pub fn apply_iform_lea(arena: Arena, instr: LEA) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        LEA::LEA_GPRV_AGEN_16 { operand_0, operand_1 } => {
+            lea_generic(arena, operand_0, operand_1, 16)
+        }
+        LEA::LEA_GPRV_AGEN_32 { operand_0, operand_1 } => {
+            lea_generic(arena, operand_0, operand_1, 32)
+        }
+        LEA::LEA_GPRV_AGEN_64 { operand_0, operand_1 } => {
+            lea_generic(arena, operand_0, operand_1, 64)
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use xed_enum::LEAVE;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn leave_generic(arena: Arena) -> Vec<InstructionSemanticsStep> {
+    //RSP := RBP;
+    //RBP := Pop();
+    let mut s = SemanticsBuilder::new(arena);
+    let width = s.stack_operand_width();
+    s.set_stack_pointer(s.frame_pointer(s.stack_width()));
+    let rbp = s.pop_stack(width);
+    s.set_frame_pointer(width, rbp);
+    s.finalize()
+}
+
+pub fn apply_iform_leave(arena: Arena, instr: LEAVE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        LEAVE::LEAVE {} => {
+            leave_generic(arena)
+        }
+    }
+}
@@ -0,0 +1,874 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use wrapper_common::registers::{Reg64WithRIP, RegSegment};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::basic_block::BasicBlock;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::{ArithmeticOp, BitCountOp, BitWiseOp, ComparisonOp, Expression, Flag, ShiftOp, Signedness};
+use crate::semantics2::semantic_steps::{loaded_segment_base, steps_count, Fault, InstructionSemanticsStep, ZeroUpper};
+use crate::semantics2::smtlib2::StateComponent;
+use crate::semantics2::state::ConcreteX86MachineState64;
+use crate::semantics2::value::Value;
+
+/// The state lifted functions work on, laid out like remill's: the general purpose registers in the order of
+/// [`Reg64WithRIP`], RIP last, the flags in the order of [`Flag`] as one byte each, the ZMM registers, MXCSR,
+/// the FS and GS bases, the vector of the exception raised, see [`exception_vector`], and the segment selectors in
+/// the order of [`RegSegment`].
+pub const STATE_TYPE: &str = "%struct.State = type { [17 x i64], [6 x i8], [32 x i512], i32, [2 x i64], i8, [6 x i16] }";
+
+/// Memory is only accessed through the remill intrinsics, which take and return this opaque handle.
+pub const MEMORY_TYPE: &str = "%struct.Memory = type opaque";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LlvmIrError {
+    /// Floating point values would need the rounding and exception behaviour of MXCSR, and the x87 stack is
+    /// addressed relative to TOP, so semantics using either can't be lowered.
+    Unsupported(&'static str),
+}
+
+/// The interrupt vector of `fault`, which is what lifted functions store in the state before calling
+/// `__remill_error`.
+pub fn exception_vector(fault: Fault) -> u8 {
+    match fault {
+        Fault::DivideError => 0,
+        Fault::UndefinedOpcode => 6,
+        Fault::GeneralProtection => 13,
+        Fault::FloatingPointError => 16,
+        Fault::SimdFloatingPointException => 19,
+    }
+}
+
+/// A textual LLVM module of lifted code, in the style of remill's. Every function has the signature of a remill
+/// lifted block, `%struct.Memory* (%struct.State*, i64 pc, %struct.Memory*)`, loads what it reads from the state
+/// on entry, and stores what it wrote before returning the memory handle. Memory goes through
+/// `__remill_read_memory_N` and `__remill_write_memory_N`. When an instruction faults, the state is left the way
+/// it was before that instruction, with RIP pointing at it, and the function returns what `__remill_error` does.
+/// Where the code continues is left in RIP, for the caller to dispatch on.
+pub struct LlvmIrModule<'arena> {
+    builder: SemanticsBuilder<'arena>,
+    declarations: BTreeSet<String>,
+    functions: String,
+}
+
+impl<'arena> LlvmIrModule<'arena> {
+    pub fn new(arena: Arena<'arena>) -> Self {
+        Self {
+            builder: SemanticsBuilder::new(arena),
+            declarations: BTreeSet::new(),
+            functions: String::new(),
+        }
+    }
+
+    /// Adds `block` as `@block_<start address in hex>`.
+    pub fn add_block(&mut self, block: &BasicBlock<'arena>) -> Result<(), LlvmIrError> {
+        let instructions = block.instructions.iter()
+            .map(|instruction| instruction.semantics.as_slice())
+            .collect::<Vec<_>>();
+        self.add_function(&format!("block_{:x}", block.start_address), instructions.as_slice())
+    }
+
+    /// Adds a function named `name` which runs the semantics of each of `instructions` in turn. The module is
+    /// left alone if they can't be lowered.
+    pub fn add_function(&mut self, name: &str, instructions: &[&[InstructionSemanticsStep<'arena>]]) -> Result<(), LlvmIrError> {
+        let mut lowering = Lowering::new(&self.builder);
+        for steps in instructions {
+            if !lowering.reachable {
+                break;
+            }
+            lowering.start_instruction();
+            lowering.run(steps, 0)?;
+        }
+        if lowering.reachable {
+            let current = lowering.current.clone();
+            lowering.store_state(&current);
+            let memory = lowering.read_current(StateComponent::Memory);
+            writeln!(lowering.body, "  ret %struct.Memory* {memory}").unwrap();
+        }
+        writeln!(self.functions).unwrap();
+        writeln!(self.functions, "define %struct.Memory* @{name}(%struct.State* noalias %state, i64 %pc, %struct.Memory* %{}) {{", StateComponent::Memory.name()).unwrap();
+        writeln!(self.functions, "entry:").unwrap();
+        self.functions.push_str(lowering.prologue.as_str());
+        writeln!(self.functions, "  br label %b0").unwrap();
+        self.functions.push_str(lowering.body.as_str());
+        writeln!(self.functions, "}}").unwrap();
+        self.declarations.extend(lowering.declarations);
+        Ok(())
+    }
+
+    /// The module, ready for `llvm-as`. Pointers are typed, which every LLVM from 14 on reads.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        writeln!(text, "{STATE_TYPE}").unwrap();
+        writeln!(text, "{MEMORY_TYPE}").unwrap();
+        if !self.declarations.is_empty() {
+            writeln!(text).unwrap();
+        }
+        for declaration in self.declarations.iter() {
+            writeln!(text, "{declaration}").unwrap();
+        }
+        text.push_str(self.functions.as_str());
+        text
+    }
+}
+
+/// `value` as an LLVM integer constant, hexadecimal when it doesn't fit in 64 bits.
+fn literal(value: &Value) -> String {
+    if value.width() <= 64 {
+        value.to_u64().to_string()
+    } else {
+        let digits = value.to_u64s().iter().rev().map(|word| format!("{word:016X}")).collect::<String>();
+        format!("u0x{digits}")
+    }
+}
+
+fn int_type(width: usize) -> String {
+    format!("i{width}")
+}
+
+impl StateComponent {
+    fn llvm_type(&self) -> String {
+        match self {
+            StateComponent::Gpr(_) | StateComponent::SegmentBase(_) => int_type(64),
+            StateComponent::Flag(_) => int_type(1),
+            StateComponent::Zmm(_) => int_type(512),
+            StateComponent::Mxcsr => int_type(32),
+            StateComponent::Segment(_) => int_type(16),
+            StateComponent::Memory => "%struct.Memory*".to_string(),
+        }
+    }
+
+    /// Indices of the component in `%struct.State`, flags are stored as bytes.
+    fn state_indices(&self) -> String {
+        match self {
+            StateComponent::Gpr(reg) => format!("i32 0, i32 0, i32 {}", *reg as usize),
+            StateComponent::Flag(flag) => format!("i32 0, i32 1, i32 {}", *flag as usize),
+            StateComponent::Zmm(reg) => format!("i32 0, i32 2, i32 {}", *reg as usize),
+            StateComponent::Mxcsr => "i32 0, i32 3".to_string(),
+            StateComponent::SegmentBase(base) => format!("i32 0, i32 4, i32 {}", *base as usize),
+            StateComponent::Segment(segment) => format!("i32 0, i32 6, i32 {}", *segment as usize),
+            StateComponent::Memory => panic!("memory isn't part of the state"),
+        }
+    }
+}
+
+/// Values of everything written so far, anything missing still has its value from the state.
+type SsaState = BTreeMap<StateComponent, String>;
+
+struct Lowering<'builder, 'arena> {
+    builder: &'builder SemanticsBuilder<'arena>,
+    declarations: BTreeSet<String>,
+    /// Loads of the state, which go in the entry block so that they dominate every use.
+    prologue: String,
+    body: String,
+    loaded: BTreeSet<StateComponent>,
+    pointers: BTreeSet<StateComponent>,
+    next_value: usize,
+    next_label: usize,
+    /// Label of the block being added to, for phis to name their predecessors.
+    block: String,
+    /// False once the block being added to has ended by raising a fault, nothing after that is lowered.
+    reachable: bool,
+    current: SsaState,
+    /// Like [`crate::semantics2::semantic_steps::ConcreteExecution`], the state before each step, for reads to
+    /// see the state as of the step they were emitted before.
+    history: Vec<(usize, SsaState)>,
+    /// The state before the current instruction, which is what a fault leaves behind.
+    instruction_start: SsaState,
+    /// Values of the expressions of the current instruction which dominate the block being added to, by address.
+    lowered: HashMap<*const Expression<'arena>, String>,
+    can_divide: HashMap<*const Expression<'arena>, bool>,
+}
+
+impl<'builder, 'arena> Lowering<'builder, 'arena> {
+    fn new(builder: &'builder SemanticsBuilder<'arena>) -> Self {
+        let mut res = Self {
+            builder,
+            declarations: BTreeSet::new(),
+            prologue: String::new(),
+            body: String::new(),
+            loaded: BTreeSet::new(),
+            pointers: BTreeSet::new(),
+            next_value: 0,
+            next_label: 0,
+            block: String::new(),
+            reachable: true,
+            current: SsaState::new(),
+            history: vec![],
+            instruction_start: SsaState::new(),
+            lowered: HashMap::new(),
+            can_divide: HashMap::new(),
+        };
+        let entry = res.new_label();
+        res.start_block(entry);
+        res
+    }
+
+    fn start_instruction(&mut self) {
+        self.history.clear();
+        self.lowered.clear();
+        self.instruction_start = self.current.clone();
+    }
+
+    /// Adds `instruction` to the current block, naming its result.
+    fn emit(&mut self, instruction: String) -> String {
+        let name = format!("%t{}", self.next_value);
+        self.next_value += 1;
+        writeln!(self.body, "  {name} = {instruction}").unwrap();
+        name
+    }
+
+    fn emit_void(&mut self, instruction: String) {
+        writeln!(self.body, "  {instruction}").unwrap();
+    }
+
+    fn new_label(&mut self) -> String {
+        let label = format!("b{}", self.next_label);
+        self.next_label += 1;
+        label
+    }
+
+    fn start_block(&mut self, label: String) {
+        writeln!(self.body).unwrap();
+        writeln!(self.body, "{label}:").unwrap();
+        self.block = label;
+    }
+
+    fn declare(&mut self, declaration: String) {
+        self.declarations.insert(declaration);
+    }
+
+    /// Pointer to `component` in the state, computed in the entry block.
+    fn pointer(&mut self, component: StateComponent) -> String {
+        let name = format!("%{}.ptr", component.name());
+        if self.pointers.insert(component) {
+            writeln!(self.prologue, "  {name} = getelementptr inbounds %struct.State, %struct.State* %state, {}", component.state_indices()).unwrap();
+        }
+        name
+    }
+
+    /// `component`'s value on entry.
+    fn load(&mut self, component: StateComponent) -> String {
+        let name = format!("%{}", component.name());
+        if component == StateComponent::Memory || !self.loaded.insert(component) {
+            return name;
+        }
+        let pointer = self.pointer(component);
+        match component {
+            StateComponent::Flag(_) => {
+                writeln!(self.prologue, "  {name}.byte = load i8, i8* {pointer}").unwrap();
+                writeln!(self.prologue, "  {name} = icmp ne i8 {name}.byte, 0").unwrap();
+            }
+            _ => {
+                let llvm_type = component.llvm_type();
+                writeln!(self.prologue, "  {name} = load {llvm_type}, {llvm_type}* {pointer}").unwrap();
+            }
+        }
+        name
+    }
+
+    fn read_from(&mut self, state: Option<&SsaState>, component: StateComponent) -> String {
+        match state.unwrap_or(&self.current).get(&component) {
+            Some(value) => value.clone(),
+            None => self.load(component),
+        }
+    }
+
+    fn read(&mut self, at_index: usize, component: StateComponent) -> String {
+        let state = self.history.iter()
+            .find(|(index, _)| *index >= at_index)
+            .map(|(_, state)| state.clone());
+        self.read_from(state.as_ref(), component)
+    }
+
+    fn read_current(&mut self, component: StateComponent) -> String {
+        self.read_from(None, component)
+    }
+
+    fn write(&mut self, component: StateComponent, value: String) {
+        self.current.insert(component, value);
+    }
+
+    /// Stores everything `state` has a new value for.
+    fn store_state(&mut self, state: &SsaState) {
+        for (component, value) in state.iter() {
+            if *component == StateComponent::Memory || *value == format!("%{}", component.name()) {
+                continue;
+            }
+            let pointer = self.pointer(*component);
+            match component {
+                StateComponent::Flag(_) => {
+                    let byte = self.emit(format!("zext i1 {value} to i8"));
+                    self.emit_void(format!("store i8 {byte}, i8* {pointer}"));
+                }
+                _ => {
+                    let llvm_type = component.llvm_type();
+                    self.emit_void(format!("store {llvm_type} {value}, {llvm_type}* {pointer}"));
+                }
+            }
+        }
+    }
+
+    /// Ends the current block by raising `fault`.
+    fn fault(&mut self, fault: Fault) {
+        let state = self.instruction_start.clone();
+        self.store_state(&state);
+        let vector = self.emit("getelementptr inbounds %struct.State, %struct.State* %state, i32 0, i32 5".to_string());
+        self.emit_void(format!("store i8 {}, i8* {vector}", exception_vector(fault)));
+        let rip = self.read_from(Some(&state), StateComponent::Gpr(Reg64WithRIP::RIP));
+        let memory = self.read_from(Some(&state), StateComponent::Memory);
+        self.declare("declare %struct.Memory* @__remill_error(%struct.State*, i64, %struct.Memory*)".to_string());
+        let memory = self.emit(format!("call %struct.Memory* @__remill_error(%struct.State* %state, i64 {rip}, %struct.Memory* {memory})"));
+        self.emit_void(format!("ret %struct.Memory* {memory}"));
+    }
+
+    /// Raises `fault` if the one bit `condition` is set, carrying on in a new block otherwise.
+    fn fault_if(&mut self, condition: &str, fault: Fault) {
+        let faults = self.new_label();
+        let continues = self.new_label();
+        self.emit_void(format!("br i1 {condition}, label %{faults}, label %{continues}"));
+        self.start_block(faults);
+        self.fault(fault);
+        self.start_block(continues);
+    }
+
+    fn extend(&mut self, value: String, from: usize, to: usize, signed: bool) -> String {
+        assert!(to >= from);
+        if to == from {
+            return value;
+        }
+        let op = if signed { "sext" } else { "zext" };
+        self.emit(format!("{op} {} {value} to {}", int_type(from), int_type(to)))
+    }
+
+    fn truncate(&mut self, value: String, from: usize, to: usize) -> String {
+        assert!(to <= from);
+        if to == from {
+            return value;
+        }
+        self.emit(format!("trunc {} {value} to {}", int_type(from), int_type(to)))
+    }
+
+    fn extract(&mut self, value: String, width: usize, low: usize, high_exclusive: usize) -> String {
+        let shifted = if low == 0 {
+            value
+        } else {
+            self.emit(format!("lshr {} {value}, {low}", int_type(width)))
+        };
+        self.truncate(shifted, width, high_exclusive - low)
+    }
+
+    /// `value` zero extended from `from` to `width` bits and moved up to start at bit `start`.
+    fn place(&mut self, value: String, from: usize, width: usize, start: usize) -> String {
+        let extended = self.extend(value, from, width, false);
+        if start == 0 {
+            extended
+        } else {
+            self.emit(format!("shl {} {extended}, {start}", int_type(width)))
+        }
+    }
+
+    fn concat(&mut self, high: String, high_width: usize, low: String, low_width: usize) -> String {
+        let width = high_width + low_width;
+        let high = self.place(high, high_width, width, low_width);
+        let low = self.extend(low, low_width, width, false);
+        self.emit(format!("or {} {high}, {low}", int_type(width)))
+    }
+
+    /// The bits of `value`, which is `width` wide, with `start` up to `start + new_width` replaced by `new_value`.
+    fn with_range(&mut self, value: String, width: usize, start: usize, new_value: String, new_width: usize) -> String {
+        if new_width == width {
+            return new_value;
+        }
+        let mask = Value::ones(width).with_range(start, &Value::zero(new_width));
+        let kept = self.emit(format!("and {} {value}, {}", int_type(width), literal(&mask)));
+        let placed = self.place(new_value, new_width, width, start);
+        self.emit(format!("or {} {kept}, {placed}", int_type(width)))
+    }
+
+    fn declare_intrinsic(&mut self, name: &str, width: usize, extra_arguments: &str) -> String {
+        let function = format!("@llvm.{name}.{}", int_type(width));
+        self.declare(format!("declare {} {function}({}{extra_arguments})", int_type(width), int_type(width)));
+        function
+    }
+
+    /// Reads `width` bits from `address` through `memory`, in the widest pieces the intrinsics allow.
+    fn load_memory(&mut self, memory: &str, address: &str, width: usize) -> String {
+        assert_eq!(width % 8, 0);
+        let mut res: Option<String> = None;
+        let mut offset = 0;
+        while offset < width {
+            let piece = [64, 32, 16, 8].into_iter().find(|piece| offset + piece <= width).unwrap();
+            let piece_address = self.offset_address(address, offset / 8);
+            self.declare(format!("declare {} @__remill_read_memory_{piece}(%struct.Memory*, i64)", int_type(piece)));
+            let value = self.emit(format!("call {} @__remill_read_memory_{piece}(%struct.Memory* {memory}, i64 {piece_address})", int_type(piece)));
+            res = Some(match res {
+                None => value,
+                Some(low) => self.concat(value, piece, low, offset),
+            });
+            offset += piece;
+        }
+        res.unwrap()
+    }
+
+    /// Writes `value`, which is `width` wide, to `address`, returning the new memory handle.
+    fn store_memory(&mut self, memory: String, address: &str, value: &str, width: usize) -> String {
+        assert_eq!(width % 8, 0);
+        let mut memory = memory;
+        let mut offset = 0;
+        while offset < width {
+            let piece = [64, 32, 16, 8].into_iter().find(|piece| offset + piece <= width).unwrap();
+            let piece_address = self.offset_address(address, offset / 8);
+            let piece_value = self.extract(value.to_string(), width, offset, offset + piece);
+            self.declare(format!("declare %struct.Memory* @__remill_write_memory_{piece}(%struct.Memory*, i64, {})", int_type(piece)));
+            memory = self.emit(format!("call %struct.Memory* @__remill_write_memory_{piece}(%struct.Memory* {memory}, i64 {piece_address}, {} {piece_value})", int_type(piece)));
+            offset += piece;
+        }
+        memory
+    }
+
+    fn offset_address(&mut self, address: &str, offset: usize) -> String {
+        if offset == 0 {
+            address.to_string()
+        } else {
+            self.emit(format!("add i64 {address}, {offset}"))
+        }
+    }
+
+    /// Whether evaluating `expr` can divide by zero, in which case it can't be evaluated unless it's needed.
+    fn can_divide_by_zero(&mut self, expr: &'arena Expression<'arena>) -> bool {
+        let key = expr as *const Expression<'arena>;
+        if let Some(&res) = self.can_divide.get(&key) {
+            return res;
+        }
+        let mut res = matches!(expr, Expression::IntArithmetic { op: ArithmeticOp::Div | ArithmeticOp::Rem, .. });
+        expr.map_children(|child| {
+            res |= self.can_divide_by_zero(child);
+            child
+        });
+        self.can_divide.insert(key, res);
+        res
+    }
+
+    fn expression(&mut self, expr: &'arena Expression<'arena>) -> Result<String, LlvmIrError> {
+        let key = expr as *const Expression<'arena>;
+        if let Some(value) = self.lowered.get(&key) {
+            return Ok(value.clone());
+        }
+        let value = self.expression_uncached(expr)?;
+        self.lowered.insert(key, value.clone());
+        Ok(value)
+    }
+
+    fn expression_uncached(&mut self, expr: &'arena Expression<'arena>) -> Result<String, LlvmIrError> {
+        let width = expr.width();
+        Ok(match *expr {
+            Expression::GetReg { reg, at_index } => {
+                let (parent, low, high) = ConcreteX86MachineState64::gpr_location(reg);
+                let full = self.read(at_index, StateComponent::Gpr(parent));
+                self.extract(full, 64, low, high)
+            }
+            Expression::GetFlag { flag, at_index } => {
+                self.read(at_index, StateComponent::Flag(flag))
+            }
+            Expression::GetVectorReg { reg, at_index } => {
+                self.read(at_index, StateComponent::Zmm(reg))
+            }
+            Expression::Constant { value } => {
+                literal(value)
+            }
+            Expression::BitWise { op, left, right } => {
+                let (left_width, right_width) = (left.width(), right.width());
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                let left = self.extend(left, left_width, width, false);
+                let right = self.extend(right, right_width, width, false);
+                let op = match op {
+                    BitWiseOp::And => "and",
+                    BitWiseOp::Or => "or",
+                    BitWiseOp::Xor => "xor",
+                };
+                self.emit(format!("{op} {} {left}, {right}", int_type(width)))
+            }
+            Expression::IntCompare { op, signedness, left, right } => {
+                let signed = matches!(signedness, Signedness::Signed);
+                let (left_width, right_width) = (left.width(), right.width());
+                let compared_width = left_width.max(right_width);
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                let left = self.extend(left, left_width, compared_width, signed);
+                let right = self.extend(right, right_width, compared_width, signed);
+                let predicate = match (op, signedness) {
+                    (ComparisonOp::Equal, _) => "eq",
+                    (ComparisonOp::Less, Signedness::Unsigned) => "ult",
+                    (ComparisonOp::Less, Signedness::Signed) => "slt",
+                    (ComparisonOp::LessOrEqual, Signedness::Unsigned) => "ule",
+                    (ComparisonOp::LessOrEqual, Signedness::Signed) => "sle",
+                    (ComparisonOp::GreaterOrEqual, Signedness::Unsigned) => "uge",
+                    (ComparisonOp::GreaterOrEqual, Signedness::Signed) => "sge",
+                    (ComparisonOp::Greater, Signedness::Unsigned) => "ugt",
+                    (ComparisonOp::Greater, Signedness::Signed) => "sgt",
+                };
+                self.emit(format!("icmp {predicate} {} {left}, {right}", int_type(compared_width)))
+            }
+            Expression::IntArithmetic { op, signedness, left, right } => {
+                let signed = matches!(signedness, Signedness::Signed);
+                let (left_width, right_width) = (left.width(), right.width());
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                let left = self.extend(left, left_width, width, signed);
+                let mut right = self.extend(right, right_width, width, signed);
+                let llvm_type = int_type(width);
+                if matches!(op, ArithmeticOp::Div | ArithmeticOp::Rem) {
+                    let zero = self.emit(format!("icmp eq {llvm_type} {right}, 0"));
+                    self.fault_if(&zero, Fault::DivideError);
+                    if signed {
+                        // the concrete evaluator wraps dividing the minimum by -1, which LLVM leaves undefined,
+                        // dividing by 1 instead gives the same quotient and remainder
+                        let min = Value::zero(width).with_range(width - 1, &Value::ones(1));
+                        let is_min = self.emit(format!("icmp eq {llvm_type} {left}, {}", literal(&min)));
+                        let is_minus_one = self.emit(format!("icmp eq {llvm_type} {right}, {}", literal(&Value::ones(width))));
+                        let overflows = self.emit(format!("and i1 {is_min}, {is_minus_one}"));
+                        right = self.emit(format!("select i1 {overflows}, {llvm_type} 1, {llvm_type} {right}"));
+                    }
+                }
+                let op = match (op, signedness) {
+                    (ArithmeticOp::Add, _) => "add",
+                    (ArithmeticOp::Sub, _) => "sub",
+                    (ArithmeticOp::Mul, _) => "mul",
+                    (ArithmeticOp::Div, Signedness::Unsigned) => "udiv",
+                    (ArithmeticOp::Div, Signedness::Signed) => "sdiv",
+                    (ArithmeticOp::Rem, Signedness::Unsigned) => "urem",
+                    (ArithmeticOp::Rem, Signedness::Signed) => "srem",
+                };
+                self.emit(format!("{op} {llvm_type} {left}, {right}"))
+            }
+            Expression::Extract { value, low, high } => {
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                self.extract(value, value_width, low, high)
+            }
+            Expression::Concat { left, right } => {
+                let (left_width, right_width) = (left.width(), right.width());
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                self.concat(left, left_width, right, right_width)
+            }
+            Expression::Conditional { condition, true_value, false_value } => {
+                let condition = self.expression(condition)?;
+                let llvm_type = int_type(width);
+                if !self.can_divide_by_zero(true_value) && !self.can_divide_by_zero(false_value) {
+                    let true_value = self.expression(true_value)?;
+                    let false_value = self.expression(false_value)?;
+                    self.emit(format!("select i1 {condition}, {llvm_type} {true_value}, {llvm_type} {false_value}"))
+                } else {
+                    // only the value picked is evaluated, so that a division in the other one can't fault
+                    let taken = self.new_label();
+                    let not_taken = self.new_label();
+                    let join = self.new_label();
+                    self.emit_void(format!("br i1 {condition}, label %{taken}, label %{not_taken}"));
+                    let lowered = self.lowered.clone();
+                    self.start_block(taken);
+                    let true_value = self.expression(true_value)?;
+                    let true_block = self.block.clone();
+                    self.emit_void(format!("br label %{join}"));
+                    self.lowered = lowered.clone();
+                    self.start_block(not_taken);
+                    let false_value = self.expression(false_value)?;
+                    let false_block = self.block.clone();
+                    self.emit_void(format!("br label %{join}"));
+                    self.lowered = lowered;
+                    self.start_block(join);
+                    self.emit(format!("phi {llvm_type} [ {true_value}, %{true_block} ], [ {false_value}, %{false_block} ]"))
+                }
+            }
+            Expression::ZeroExtend { value, len } | Expression::SignExtend { value, len } => {
+                let signed = matches!(expr, Expression::SignExtend { .. });
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                self.extend(value, value_width, len, signed)
+            }
+            Expression::Shift { op, value, amount } => {
+                let amount_width = amount.width();
+                let value = self.expression(value)?;
+                let amount = self.expression(amount)?;
+                let llvm_type = int_type(width);
+                // LLVM's shifts by the width or more are poison, where the concrete evaluator shifts every bit out
+                let in_range = if amount_width < 64 && (1usize << amount_width) <= width {
+                    None
+                } else {
+                    Some(self.emit(format!("icmp ult {} {amount}, {width}", int_type(amount_width))))
+                };
+                let mut amount = if amount_width <= width {
+                    self.extend(amount, amount_width, width, false)
+                } else {
+                    self.truncate(amount, amount_width, width)
+                };
+                if let Some(in_range) = in_range.as_ref() {
+                    amount = self.emit(format!("select i1 {in_range}, {llvm_type} {amount}, {llvm_type} 0"));
+                }
+                let op = match op {
+                    ShiftOp::Shl => "shl",
+                    ShiftOp::LShr => "lshr",
+                    ShiftOp::AShr => "ashr",
+                };
+                let shifted = self.emit(format!("{op} {llvm_type} {value}, {amount}"));
+                match in_range {
+                    None => shifted,
+                    Some(in_range) => {
+                        let out_of_range = match op {
+                            "ashr" => self.emit(format!("ashr {llvm_type} {value}, {}", width - 1)),
+                            _ => "0".to_string(),
+                        };
+                        self.emit(format!("select i1 {in_range}, {llvm_type} {shifted}, {llvm_type} {out_of_range}"))
+                    }
+                }
+            }
+            Expression::BitCount { op, value } => {
+                let value = self.expression(value)?;
+                let llvm_type = int_type(width);
+                match op {
+                    BitCountOp::PopCount => {
+                        let function = self.declare_intrinsic("ctpop", width, "");
+                        self.emit(format!("call {llvm_type} {function}({llvm_type} {value})"))
+                    }
+                    BitCountOp::LeadingZeros | BitCountOp::TrailingZeros => {
+                        let name = if matches!(op, BitCountOp::LeadingZeros) { "ctlz" } else { "cttz" };
+                        // counts the width for zero, like the concrete evaluator, rather than giving poison
+                        let function = self.declare_intrinsic(name, width, ", i1");
+                        self.emit(format!("call {llvm_type} {function}({llvm_type} {value}, i1 false)"))
+                    }
+                }
+            }
+            Expression::LowerBits { value, len } => {
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                self.truncate(value, value_width, len)
+            }
+            Expression::UpperBits { value, len } => {
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                self.extract(value, value_width, value_width - len, value_width)
+            }
+            Expression::ChangeRange { value, range_start_inclusive, range_end_exclusive, new_value } => {
+                let value = self.expression(value)?;
+                let new_value = self.expression(new_value)?;
+                self.with_range(value, width, range_start_inclusive, new_value, range_end_exclusive - range_start_inclusive)
+            }
+            Expression::GetMxcsr { at_index } => {
+                self.read(at_index, StateComponent::Mxcsr)
+            }
+            Expression::Load { address, width, at_index } => {
+                let address = self.expression(address)?;
+                let memory = self.read(at_index, StateComponent::Memory);
+                self.load_memory(&memory, &address, width)
+            }
+            Expression::SegmentBase { base, at_index } => {
+                self.read(at_index, StateComponent::SegmentBase(base))
+            }
+            Expression::GetSegment { segment, at_index } => {
+                self.read(at_index, StateComponent::Segment(segment))
+            }
+            Expression::FloatArithmetic { .. } |
+            Expression::FloatSqrt { .. } |
+            Expression::FloatFma { .. } |
+            Expression::FloatCompare { .. } |
+            Expression::FloatConvert { .. } |
+            Expression::IntToFloat { .. } |
+            Expression::FloatToInt { .. } |
+            Expression::FloatExceptionFlags { .. } => {
+                return Err(LlvmIrError::Unsupported("floating point expressions"));
+            }
+            Expression::GetX87Register { .. } |
+            Expression::X87RegisterEmpty { .. } |
+            Expression::GetX87Control { .. } |
+            Expression::GetX87Status { .. } |
+            Expression::X87Arithmetic { .. } |
+            Expression::X87Compare { .. } |
+            Expression::X87FromFloat { .. } |
+            Expression::X87ToFloat { .. } |
+            Expression::X87FromInt { .. } |
+            Expression::X87ToInt { .. } |
+            Expression::X87RoundedUp { .. } => {
+                return Err(LlvmIrError::Unsupported("the x87 stack"));
+            }
+        })
+    }
+
+    /// Lowers `steps`, the first of which is numbered `first_index`.
+    fn run(&mut self, steps: &[InstructionSemanticsStep<'arena>], first_index: usize) -> Result<(), LlvmIrError> {
+        let mut index = first_index;
+        for step in steps {
+            if !self.reachable {
+                break;
+            }
+            match step {
+                InstructionSemanticsStep::Conditional { condition, true_semantics, false_semantics } => {
+                    let condition = self.expression(*condition)?;
+                    let taken = self.new_label();
+                    let not_taken = self.new_label();
+                    let join = self.new_label();
+                    self.emit_void(format!("br i1 {condition}, label %{taken}, label %{not_taken}"));
+                    let before = self.current.clone();
+                    let history_len = self.history.len();
+                    let lowered = self.lowered.clone();
+
+                    self.start_block(taken);
+                    self.run(true_semantics, index)?;
+                    let after_true = (self.reachable, self.block.clone(), std::mem::replace(&mut self.current, before));
+                    if self.reachable {
+                        self.emit_void(format!("br label %{join}"));
+                    }
+                    // the concrete evaluator only ever runs one of the branches
+                    self.history.truncate(history_len);
+                    self.lowered = lowered.clone();
+                    self.reachable = true;
+
+                    self.start_block(not_taken);
+                    self.run(false_semantics, index + steps_count(true_semantics))?;
+                    let after_false = (self.reachable, self.block.clone(), std::mem::take(&mut self.current));
+                    if self.reachable {
+                        self.emit_void(format!("br label %{join}"));
+                    }
+                    self.lowered = lowered;
+                    self.merge(join, after_true, after_false);
+                }
+                // nothing can read the state from before the advance, so there's no need to keep it
+                InstructionSemanticsStep::AdvanceRip { .. } => {
+                    self.step(step, index)?;
+                }
+                step => {
+                    self.history.push((index, self.current.clone()));
+                    self.step(step, index)?;
+                }
+            }
+            index += step.step_count();
+        }
+        Ok(())
+    }
+
+    /// Continues at `join` after both branches of a conditional, each given as whether it reaches the join, the
+    /// block it ends in and the state it ends with.
+    fn merge(&mut self, join: String, after_true: (bool, String, SsaState), after_false: (bool, String, SsaState)) {
+        match (after_true, after_false) {
+            ((false, _, _), (false, _, _)) => {
+                self.reachable = false;
+            }
+            ((true, _, state), (false, _, _)) | ((false, _, _), (true, _, state)) => {
+                self.reachable = true;
+                self.start_block(join);
+                self.current = state;
+            }
+            ((true, true_block, after_true), (true, false_block, after_false)) => {
+                self.reachable = true;
+                self.start_block(join);
+                let components = after_true.keys().chain(after_false.keys()).copied().collect::<BTreeSet<_>>();
+                for component in components {
+                    let true_value = self.read_from(Some(&after_true), component);
+                    let false_value = self.read_from(Some(&after_false), component);
+                    if true_value == false_value {
+                        self.current.insert(component, true_value);
+                    } else {
+                        let phi = self.emit(format!("phi {} [ {true_value}, %{true_block} ], [ {false_value}, %{false_block} ]", component.llvm_type()));
+                        self.write(component, phi);
+                    }
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, step: &InstructionSemanticsStep<'arena>, index: usize) -> Result<(), LlvmIrError> {
+        match step {
+            InstructionSemanticsStep::Conditional { .. } => {
+                unreachable!()
+            }
+            InstructionSemanticsStep::SetRegister { zero_upper, register, value } => {
+                let value = self.expression(*value)?;
+                let (parent, low, high) = ConcreteX86MachineState64::gpr_location(*register);
+                let component = StateComponent::Gpr(parent);
+                let value = match zero_upper {
+                    ZeroUpper::ZeroUpper => self.place(value, high - low, 64, low),
+                    ZeroUpper::NoZeroUpper => {
+                        let old = self.read_current(component);
+                        self.with_range(old, 64, low, value, high - low)
+                    }
+                };
+                self.write(component, value);
+            }
+            InstructionSemanticsStep::SetVectorRegister { register, value } => {
+                let value = self.expression(*value)?;
+                self.write(StateComponent::Zmm(*register), value);
+            }
+            InstructionSemanticsStep::SetFlag { flag, value } => {
+                let value = self.expression(*value)?;
+                self.write(StateComponent::Flag(*flag), value);
+            }
+            InstructionSemanticsStep::InstructionSyncPoint { .. } => {}
+            InstructionSemanticsStep::UndefinedException |
+            InstructionSemanticsStep::DivideError |
+            InstructionSemanticsStep::GeneralProtection |
+            InstructionSemanticsStep::FloatingPointError => {
+                let fault = match step {
+                    InstructionSemanticsStep::UndefinedException => Fault::UndefinedOpcode,
+                    InstructionSemanticsStep::DivideError => Fault::DivideError,
+                    InstructionSemanticsStep::GeneralProtection => Fault::GeneralProtection,
+                    _ => Fault::FloatingPointError,
+                };
+                self.fault(fault);
+                self.reachable = false;
+            }
+            InstructionSemanticsStep::CalculateFlags { flag_tag, left, right } => {
+                let carry = self.builder.a(Expression::GetFlag { flag: Flag::CF, at_index: index });
+                for (flag, value) in self.builder.flag_values(flag_tag, *left, *right, carry) {
+                    let value = self.expression(value)?;
+                    self.write(StateComponent::Flag(flag), value);
+                }
+            }
+            InstructionSemanticsStep::Store { address, value } => {
+                let width = value.width();
+                let address = self.expression(*address)?;
+                let value = self.expression(*value)?;
+                let memory = self.read_current(StateComponent::Memory);
+                let memory = self.store_memory(memory, &address, &value, width);
+                self.write(StateComponent::Memory, memory);
+            }
+            InstructionSemanticsStep::RaiseFloatExceptions { exceptions } => {
+                let exceptions = self.expression(*exceptions)?;
+                let mxcsr = self.read_current(StateComponent::Mxcsr);
+                let masks = self.extract(mxcsr.clone(), 32, 7, 13);
+                let unmasked = self.emit(format!("xor i6 {masks}, 63"));
+                let unmasked = self.emit(format!("and i6 {exceptions}, {unmasked}"));
+                let raised = self.emit(format!("icmp ne i6 {unmasked}, 0"));
+                self.fault_if(&raised, Fault::SimdFloatingPointException);
+                let exceptions = self.extend(exceptions, 6, 32, false);
+                let mxcsr = self.emit(format!("or i32 {mxcsr}, {exceptions}"));
+                self.write(StateComponent::Mxcsr, mxcsr);
+            }
+            InstructionSemanticsStep::SetMxcsr { value } => {
+                let value = self.expression(*value)?;
+                self.write(StateComponent::Mxcsr, value);
+            }
+            InstructionSemanticsStep::SetSegment { segment, value } => {
+                let value = self.expression(*value)?;
+                self.write(StateComponent::Segment(*segment), value);
+                if let Some(base) = loaded_segment_base(*segment) {
+                    self.write(StateComponent::SegmentBase(base), "0".to_string());
+                }
+            }
+            InstructionSemanticsStep::SetX87Register { .. } |
+            InstructionSemanticsStep::X87Push |
+            InstructionSemanticsStep::X87Pop |
+            InstructionSemanticsStep::SetX87Control { .. } |
+            InstructionSemanticsStep::SetX87Status { .. } => {
+                return Err(LlvmIrError::Unsupported("the x87 stack"));
+            }
+            InstructionSemanticsStep::AdvanceRip { length } => {
+                let rip = self.read_current(StateComponent::Gpr(Reg64WithRIP::RIP));
+                let rip = self.emit(format!("add i64 {rip}, {length}"));
+                self.write(StateComponent::Gpr(Reg64WithRIP::RIP), rip);
+            }
+        }
+        Ok(())
+    }
+}
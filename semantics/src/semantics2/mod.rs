@@ -1,3 +1,7 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use bumpalo::Bump;
 use xed_enum::X86Instruction;
 
 use crate::semantics2::aaa::apply_iform_aaa;
@@ -6,9 +10,49 @@ use crate::semantics2::adc::apply_iform_adc;
 use crate::semantics2::adcx::apply_iform_adcx;
 use crate::semantics2::add::apply_iform_add;
 use crate::semantics2::addpd::apply_iform_addpd;
+use crate::semantics2::and::{apply_iform_and, apply_iform_test};
 use crate::semantics2::arena::Arena;
-use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::bit_count::{apply_iform_bsf, apply_iform_bsr, apply_iform_lzcnt, apply_iform_popcnt, apply_iform_tzcnt};
+use crate::semantics2::bt::{apply_iform_bt, apply_iform_btc, apply_iform_btr, apply_iform_bts};
+use crate::semantics2::call::{apply_iform_call_far, apply_iform_call_near};
+use crate::semantics2::cmovcc::{apply_iform_cmovb, apply_iform_cmovbe, apply_iform_cmovl, apply_iform_cmovle, apply_iform_cmovnb, apply_iform_cmovnbe, apply_iform_cmovnl, apply_iform_cmovnle, apply_iform_cmovno, apply_iform_cmovnp, apply_iform_cmovns, apply_iform_cmovnz, apply_iform_cmovo, apply_iform_cmovp, apply_iform_cmovs, apply_iform_cmovz};
+use crate::semantics2::cmp::apply_iform_cmp;
+use crate::semantics2::dec::apply_iform_dec;
+use crate::semantics2::div::{apply_iform_div, apply_iform_idiv};
+use crate::semantics2::enter::apply_iform_enter;
+use crate::semantics2::float_compare::{apply_iform_cmppd, apply_iform_cmpps, apply_iform_cmpsd_xmm, apply_iform_cmpss, apply_iform_comisd, apply_iform_comiss, apply_iform_ucomisd, apply_iform_ucomiss, apply_iform_vcmppd, apply_iform_vcmpps, apply_iform_vcmpsd, apply_iform_vcmpss, apply_iform_vcomisd, apply_iform_vcomiss, apply_iform_vucomisd, apply_iform_vucomiss};
+use crate::semantics2::float_convert::{apply_iform_cvtsd2si, apply_iform_cvtsd2ss, apply_iform_cvtsi2sd, apply_iform_cvtsi2ss, apply_iform_cvtss2sd, apply_iform_cvtss2si, apply_iform_cvttsd2si, apply_iform_cvttss2si, apply_iform_vcvtsd2si, apply_iform_vcvtsd2ss, apply_iform_vcvtsi2sd, apply_iform_vcvtsi2ss, apply_iform_vcvtss2sd, apply_iform_vcvtss2si, apply_iform_vcvttsd2si, apply_iform_vcvttss2si};
+use crate::semantics2::fma::{apply_iform_vfmadd132pd, apply_iform_vfmadd132ps, apply_iform_vfmadd132sd, apply_iform_vfmadd132ss, apply_iform_vfmadd213pd, apply_iform_vfmadd213ps, apply_iform_vfmadd213sd, apply_iform_vfmadd213ss, apply_iform_vfmadd231pd, apply_iform_vfmadd231ps, apply_iform_vfmadd231sd, apply_iform_vfmadd231ss};
+use crate::semantics2::inc::apply_iform_inc;
+use crate::semantics2::jcc::{apply_iform_jb, apply_iform_jbe, apply_iform_jcxz, apply_iform_jecxz, apply_iform_jl, apply_iform_jle, apply_iform_jnb, apply_iform_jnbe, apply_iform_jnl, apply_iform_jnle, apply_iform_jno, apply_iform_jnp, apply_iform_jns, apply_iform_jnz, apply_iform_jo, apply_iform_jp, apply_iform_jrcxz, apply_iform_js, apply_iform_jz, apply_iform_loop, apply_iform_loope, apply_iform_loopne};
+use crate::semantics2::jmp::{apply_iform_jmp, apply_iform_jmp_far};
+use crate::semantics2::lea::apply_iform_lea;
+use crate::semantics2::leave::apply_iform_leave;
+use crate::semantics2::mov::apply_iform_mov;
+use crate::semantics2::movsx::{apply_iform_movsx, apply_iform_movsxd};
+use crate::semantics2::movzx::apply_iform_movzx;
+use crate::semantics2::mul::{apply_iform_imul, apply_iform_mul};
+use crate::semantics2::mxcsr::{apply_iform_ldmxcsr, apply_iform_stmxcsr, apply_iform_vldmxcsr, apply_iform_vstmxcsr};
+use crate::semantics2::neg::apply_iform_neg;
+use crate::semantics2::not::apply_iform_not;
+use crate::semantics2::or::apply_iform_or;
+use crate::semantics2::pop::apply_iform_pop;
+use crate::semantics2::popf::{apply_iform_popf, apply_iform_popfd, apply_iform_popfq};
+use crate::semantics2::push::apply_iform_push;
+use crate::semantics2::pushf::{apply_iform_pushf, apply_iform_pushfd, apply_iform_pushfq};
+use crate::semantics2::ret::{apply_iform_ret_far, apply_iform_ret_near};
+use crate::semantics2::rotate::{apply_iform_rcl, apply_iform_rcr, apply_iform_rol, apply_iform_ror};
+use crate::semantics2::sbb::apply_iform_sbb;
+use crate::semantics2::semantic_steps::{apply_instructions_to_concrete, Fault, InstructionSemanticsStep};
+use crate::semantics2::setcc::{apply_iform_setb, apply_iform_setbe, apply_iform_setl, apply_iform_setle, apply_iform_setnb, apply_iform_setnbe, apply_iform_setnl, apply_iform_setnle, apply_iform_setno, apply_iform_setnp, apply_iform_setns, apply_iform_setnz, apply_iform_seto, apply_iform_setp, apply_iform_sets, apply_iform_setz};
+use crate::semantics2::shift::{apply_iform_sar, apply_iform_shl, apply_iform_shld, apply_iform_shr, apply_iform_shrd};
+use crate::semantics2::simd_float::{apply_iform_addps, apply_iform_addsd, apply_iform_addss, apply_iform_divpd, apply_iform_divps, apply_iform_divsd, apply_iform_divss, apply_iform_maxpd, apply_iform_maxps, apply_iform_maxsd, apply_iform_maxss, apply_iform_minpd, apply_iform_minps, apply_iform_minsd, apply_iform_minss, apply_iform_mulpd, apply_iform_mulps, apply_iform_mulsd, apply_iform_mulss, apply_iform_sqrtpd, apply_iform_sqrtps, apply_iform_sqrtsd, apply_iform_sqrtss, apply_iform_subpd, apply_iform_subps, apply_iform_subsd, apply_iform_subss, apply_iform_vaddps, apply_iform_vaddsd, apply_iform_vaddss, apply_iform_vdivpd, apply_iform_vdivps, apply_iform_vdivsd, apply_iform_vdivss, apply_iform_vmaxpd, apply_iform_vmaxps, apply_iform_vmaxsd, apply_iform_vmaxss, apply_iform_vminpd, apply_iform_vminps, apply_iform_vminsd, apply_iform_vminss, apply_iform_vmulpd, apply_iform_vmulps, apply_iform_vmulsd, apply_iform_vmulss, apply_iform_vsqrtpd, apply_iform_vsqrtps, apply_iform_vsqrtsd, apply_iform_vsqrtss, apply_iform_vsubpd, apply_iform_vsubps, apply_iform_vsubsd, apply_iform_vsubss};
+use crate::semantics2::state::ConcreteX86MachineState64;
+use crate::semantics2::sub::apply_iform_sub;
 use crate::semantics2::vaddpd::apply_iform_vaddpd;
+use crate::semantics2::x87::{apply_iform_fadd, apply_iform_faddp, apply_iform_fcom, apply_iform_fcomp, apply_iform_fcompp, apply_iform_fdiv, apply_iform_fdivp, apply_iform_fdivr, apply_iform_fild, apply_iform_fistp, apply_iform_fld, apply_iform_fldcw, apply_iform_fmul, apply_iform_fmulp, apply_iform_fnstcw, apply_iform_fnstsw, apply_iform_fst, apply_iform_fstp, apply_iform_fsub, apply_iform_fsubp, apply_iform_fxch};
+use crate::semantics2::xchg::apply_iform_xchg;
+use crate::semantics2::xor::apply_iform_xor;
 
 pub mod arena;
 pub mod variables;
@@ -26,20 +70,366 @@ pub mod adcx;
 pub mod add;
 pub mod addpd;
 pub mod vaddpd;
+pub mod condition;
+pub mod and;
+pub mod bit_count;
+pub mod bt;
+pub mod cmovcc;
+pub mod cmp;
+pub mod dec;
+pub mod div;
+pub mod inc;
+pub mod lea;
+pub mod mov;
+pub mod movsx;
+pub mod movzx;
+pub mod mul;
+pub mod neg;
+pub mod not;
+pub mod or;
+pub mod rotate;
+pub mod sbb;
+pub mod setcc;
+pub mod shift;
+pub mod sub;
+pub mod xchg;
+pub mod xor;
+pub mod basic_block;
+pub mod call;
+pub mod jcc;
+pub mod jmp;
+pub mod ret;
+pub mod push;
+pub mod pop;
+pub mod pushf;
+pub mod popf;
+pub mod enter;
+pub mod leave;
+pub mod float;
+pub mod simd_float;
+pub mod float_compare;
+pub mod float_convert;
+pub mod fma;
+pub mod mxcsr;
+pub mod x87_float;
+pub mod x87;
+pub mod smtlib2;
+pub mod simplify;
+pub mod symbolic;
+pub mod llvm_ir;
+pub mod rust_codegen;
 
 pub mod num_traits;
 
-pub fn apply_instruction(arena: Arena, instr: X86Instruction) -> Vec<InstructionSemanticsStep> {
-    match instr {
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SemanticsError {
+    /// There are no semantics for `instr`. Either its iclass hasn't been done yet, or it is a form which needs
+    /// something that isn't modelled, like avx512 masking or segment descriptors.
+    Unsupported(X86Instruction),
+}
+
+impl Display for SemanticsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemanticsError::Unsupported(instr) => write!(f, "no semantics for {instr:?}"),
+        }
+    }
+}
+
+impl Error for SemanticsError {}
+
+/// Why [`apply_instruction_concrete`] gave no state.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ConcreteError {
+    /// See [`SemanticsError::Unsupported`].
+    Unsupported(X86Instruction),
+    /// The instruction faulted, the way it would natively.
+    Fault(Fault),
+}
+
+impl From<SemanticsError> for ConcreteError {
+    fn from(value: SemanticsError) -> Self {
+        match value {
+            SemanticsError::Unsupported(instr) => ConcreteError::Unsupported(instr),
+        }
+    }
+}
+
+impl From<Fault> for ConcreteError {
+    fn from(value: Fault) -> Self {
+        ConcreteError::Fault(value)
+    }
+}
+
+/// Semantics of `instr`, which is `length` bytes long. RIP is advanced past the instruction before anything else,
+/// see [`InstructionSemanticsStep::AdvanceRip`].
+pub fn apply_instruction(arena: Arena, instr: X86Instruction, length: usize) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    let mut res = vec![InstructionSemanticsStep::AdvanceRip { length }];
+    res.extend(apply_iform(arena, instr)?);
+    Ok(res)
+}
+
+/// [`apply_instruction`] for an instruction decoded with an effective address width of `address_width` bits. An
+/// address size prefix is already visible in the registers of memory operands, of the instructions with semantics
+/// only LOOP, LOOPE and LOOPNE have an implicit operand it changes, so those are unsupported with one.
+pub fn apply_instruction_with_address_width(arena: Arena, instr: X86Instruction, length: usize, address_width: usize) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    let overridden = address_width != arena.mode().address_width();
+    if overridden && matches!(instr, X86Instruction::LOOP(_) | X86Instruction::LOOPE(_) | X86Instruction::LOOPNE(_)) {
+        return Err(SemanticsError::Unsupported(instr));
+    }
+    apply_instruction(arena, instr, length)
+}
+
+fn apply_iform(arena: Arena, instr: X86Instruction) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
         X86Instruction::AAA(a) => apply_iform_aaa(arena, a),
         X86Instruction::AAD(a) => apply_iform_aad(arena, a),
         X86Instruction::ADC(a) => apply_iform_adc(arena, a),
         X86Instruction::ADCX(a) => apply_iform_adcx(arena, a),
         X86Instruction::ADD(a) => apply_iform_add(arena, a),
         X86Instruction::ADDPD(a) => apply_iform_addpd(arena, a),
-        X86Instruction::VADDPD(a) => apply_iform_vaddpd(arena, a),
-        _ => todo!()
-    }
+        X86Instruction::ADDPS(a) => apply_iform_addps(arena, a),
+        X86Instruction::ADDSD(a) => apply_iform_addsd(arena, a),
+        X86Instruction::ADDSS(a) => apply_iform_addss(arena, a),
+        X86Instruction::AND(a) => apply_iform_and(arena, a),
+        X86Instruction::BSF(a) => apply_iform_bsf(arena, a),
+        X86Instruction::BSR(a) => apply_iform_bsr(arena, a),
+        X86Instruction::BT(a) => apply_iform_bt(arena, a),
+        X86Instruction::BTC(a) => apply_iform_btc(arena, a),
+        X86Instruction::BTR(a) => apply_iform_btr(arena, a),
+        X86Instruction::BTS(a) => apply_iform_bts(arena, a),
+        X86Instruction::CALL_FAR(a) => apply_iform_call_far(arena, a)?,
+        X86Instruction::CALL_NEAR(a) => apply_iform_call_near(arena, a),
+        X86Instruction::CMOVB(a) => apply_iform_cmovb(arena, a),
+        X86Instruction::CMOVBE(a) => apply_iform_cmovbe(arena, a),
+        X86Instruction::CMOVL(a) => apply_iform_cmovl(arena, a),
+        X86Instruction::CMOVLE(a) => apply_iform_cmovle(arena, a),
+        X86Instruction::CMOVNB(a) => apply_iform_cmovnb(arena, a),
+        X86Instruction::CMOVNBE(a) => apply_iform_cmovnbe(arena, a),
+        X86Instruction::CMOVNL(a) => apply_iform_cmovnl(arena, a),
+        X86Instruction::CMOVNLE(a) => apply_iform_cmovnle(arena, a),
+        X86Instruction::CMOVNO(a) => apply_iform_cmovno(arena, a),
+        X86Instruction::CMOVNP(a) => apply_iform_cmovnp(arena, a),
+        X86Instruction::CMOVNS(a) => apply_iform_cmovns(arena, a),
+        X86Instruction::CMOVNZ(a) => apply_iform_cmovnz(arena, a),
+        X86Instruction::CMOVO(a) => apply_iform_cmovo(arena, a),
+        X86Instruction::CMOVP(a) => apply_iform_cmovp(arena, a),
+        X86Instruction::CMOVS(a) => apply_iform_cmovs(arena, a),
+        X86Instruction::CMOVZ(a) => apply_iform_cmovz(arena, a),
+        X86Instruction::CMP(a) => apply_iform_cmp(arena, a),
+        X86Instruction::CMPPD(a) => apply_iform_cmppd(arena, a),
+        X86Instruction::CMPPS(a) => apply_iform_cmpps(arena, a),
+        X86Instruction::CMPSD_XMM(a) => apply_iform_cmpsd_xmm(arena, a),
+        X86Instruction::CMPSS(a) => apply_iform_cmpss(arena, a),
+        X86Instruction::COMISD(a) => apply_iform_comisd(arena, a),
+        X86Instruction::COMISS(a) => apply_iform_comiss(arena, a),
+        X86Instruction::CVTSD2SI(a) => apply_iform_cvtsd2si(arena, a),
+        X86Instruction::CVTSD2SS(a) => apply_iform_cvtsd2ss(arena, a),
+        X86Instruction::CVTSI2SD(a) => apply_iform_cvtsi2sd(arena, a),
+        X86Instruction::CVTSI2SS(a) => apply_iform_cvtsi2ss(arena, a),
+        X86Instruction::CVTSS2SD(a) => apply_iform_cvtss2sd(arena, a),
+        X86Instruction::CVTSS2SI(a) => apply_iform_cvtss2si(arena, a),
+        X86Instruction::CVTTSD2SI(a) => apply_iform_cvttsd2si(arena, a),
+        X86Instruction::CVTTSS2SI(a) => apply_iform_cvttss2si(arena, a),
+        X86Instruction::DEC(a) => apply_iform_dec(arena, a),
+        X86Instruction::DIV(a) => apply_iform_div(arena, a),
+        X86Instruction::DIVPD(a) => apply_iform_divpd(arena, a),
+        X86Instruction::DIVPS(a) => apply_iform_divps(arena, a),
+        X86Instruction::DIVSD(a) => apply_iform_divsd(arena, a),
+        X86Instruction::DIVSS(a) => apply_iform_divss(arena, a),
+        X86Instruction::ENTER(a) => apply_iform_enter(arena, a),
+        X86Instruction::FADD(a) => apply_iform_fadd(arena, a),
+        X86Instruction::FADDP(a) => apply_iform_faddp(arena, a),
+        X86Instruction::FCOM(a) => apply_iform_fcom(arena, a),
+        X86Instruction::FCOMP(a) => apply_iform_fcomp(arena, a),
+        X86Instruction::FCOMPP(a) => apply_iform_fcompp(arena, a),
+        X86Instruction::FDIV(a) => apply_iform_fdiv(arena, a),
+        X86Instruction::FDIVP(a) => apply_iform_fdivp(arena, a),
+        X86Instruction::FDIVR(a) => apply_iform_fdivr(arena, a),
+        X86Instruction::FILD(a) => apply_iform_fild(arena, a),
+        X86Instruction::FISTP(a) => apply_iform_fistp(arena, a),
+        X86Instruction::FLD(a) => apply_iform_fld(arena, a),
+        X86Instruction::FLDCW(a) => apply_iform_fldcw(arena, a),
+        X86Instruction::FMUL(a) => apply_iform_fmul(arena, a),
+        X86Instruction::FMULP(a) => apply_iform_fmulp(arena, a),
+        X86Instruction::FNSTCW(a) => apply_iform_fnstcw(arena, a),
+        X86Instruction::FNSTSW(a) => apply_iform_fnstsw(arena, a),
+        X86Instruction::FST(a) => apply_iform_fst(arena, a),
+        X86Instruction::FSTP(a) => apply_iform_fstp(arena, a),
+        X86Instruction::FSUB(a) => apply_iform_fsub(arena, a),
+        X86Instruction::FSUBP(a) => apply_iform_fsubp(arena, a),
+        X86Instruction::FXCH(a) => apply_iform_fxch(arena, a),
+        X86Instruction::IDIV(a) => apply_iform_idiv(arena, a),
+        X86Instruction::IMUL(a) => apply_iform_imul(arena, a),
+        X86Instruction::INC(a) => apply_iform_inc(arena, a),
+        X86Instruction::JB(a) => apply_iform_jb(arena, a),
+        X86Instruction::JBE(a) => apply_iform_jbe(arena, a),
+        X86Instruction::JCXZ(a) => apply_iform_jcxz(arena, a),
+        X86Instruction::JECXZ(a) => apply_iform_jecxz(arena, a),
+        X86Instruction::JL(a) => apply_iform_jl(arena, a),
+        X86Instruction::JLE(a) => apply_iform_jle(arena, a),
+        X86Instruction::JMP(a) => apply_iform_jmp(arena, a),
+        X86Instruction::JMP_FAR(a) => apply_iform_jmp_far(arena, a)?,
+        X86Instruction::JNB(a) => apply_iform_jnb(arena, a),
+        X86Instruction::JNBE(a) => apply_iform_jnbe(arena, a),
+        X86Instruction::JNL(a) => apply_iform_jnl(arena, a),
+        X86Instruction::JNLE(a) => apply_iform_jnle(arena, a),
+        X86Instruction::JNO(a) => apply_iform_jno(arena, a),
+        X86Instruction::JNP(a) => apply_iform_jnp(arena, a),
+        X86Instruction::JNS(a) => apply_iform_jns(arena, a),
+        X86Instruction::JNZ(a) => apply_iform_jnz(arena, a),
+        X86Instruction::JO(a) => apply_iform_jo(arena, a),
+        X86Instruction::JP(a) => apply_iform_jp(arena, a),
+        X86Instruction::JRCXZ(a) => apply_iform_jrcxz(arena, a),
+        X86Instruction::JS(a) => apply_iform_js(arena, a),
+        X86Instruction::JZ(a) => apply_iform_jz(arena, a),
+        X86Instruction::LDMXCSR(a) => apply_iform_ldmxcsr(arena, a),
+        X86Instruction::LEA(a) => apply_iform_lea(arena, a),
+        X86Instruction::LEAVE(a) => apply_iform_leave(arena, a),
+        X86Instruction::LOOP(a) => apply_iform_loop(arena, a),
+        X86Instruction::LOOPE(a) => apply_iform_loope(arena, a),
+        X86Instruction::LOOPNE(a) => apply_iform_loopne(arena, a),
+        X86Instruction::LZCNT(a) => apply_iform_lzcnt(arena, a),
+        X86Instruction::MAXPD(a) => apply_iform_maxpd(arena, a),
+        X86Instruction::MAXPS(a) => apply_iform_maxps(arena, a),
+        X86Instruction::MAXSD(a) => apply_iform_maxsd(arena, a),
+        X86Instruction::MAXSS(a) => apply_iform_maxss(arena, a),
+        X86Instruction::MINPD(a) => apply_iform_minpd(arena, a),
+        X86Instruction::MINPS(a) => apply_iform_minps(arena, a),
+        X86Instruction::MINSD(a) => apply_iform_minsd(arena, a),
+        X86Instruction::MINSS(a) => apply_iform_minss(arena, a),
+        X86Instruction::MOV(a) => apply_iform_mov(arena, a)?,
+        X86Instruction::MOVSX(a) => apply_iform_movsx(arena, a),
+        X86Instruction::MOVSXD(a) => apply_iform_movsxd(arena, a),
+        X86Instruction::MOVZX(a) => apply_iform_movzx(arena, a),
+        X86Instruction::MUL(a) => apply_iform_mul(arena, a),
+        X86Instruction::MULPD(a) => apply_iform_mulpd(arena, a),
+        X86Instruction::MULPS(a) => apply_iform_mulps(arena, a),
+        X86Instruction::MULSD(a) => apply_iform_mulsd(arena, a),
+        X86Instruction::MULSS(a) => apply_iform_mulss(arena, a),
+        X86Instruction::NEG(a) => apply_iform_neg(arena, a),
+        X86Instruction::NOT(a) => apply_iform_not(arena, a),
+        X86Instruction::OR(a) => apply_iform_or(arena, a),
+        X86Instruction::POP(a) => apply_iform_pop(arena, a)?,
+        X86Instruction::POPCNT(a) => apply_iform_popcnt(arena, a),
+        X86Instruction::POPF(a) => apply_iform_popf(arena, a),
+        X86Instruction::POPFD(a) => apply_iform_popfd(arena, a),
+        X86Instruction::POPFQ(a) => apply_iform_popfq(arena, a),
+        X86Instruction::PUSH(a) => apply_iform_push(arena, a)?,
+        X86Instruction::PUSHF(a) => apply_iform_pushf(arena, a),
+        X86Instruction::PUSHFD(a) => apply_iform_pushfd(arena, a),
+        X86Instruction::PUSHFQ(a) => apply_iform_pushfq(arena, a),
+        X86Instruction::RCL(a) => apply_iform_rcl(arena, a),
+        X86Instruction::RCR(a) => apply_iform_rcr(arena, a),
+        X86Instruction::RET_FAR(a) => apply_iform_ret_far(arena, a)?,
+        X86Instruction::RET_NEAR(a) => apply_iform_ret_near(arena, a),
+        X86Instruction::ROL(a) => apply_iform_rol(arena, a),
+        X86Instruction::ROR(a) => apply_iform_ror(arena, a),
+        X86Instruction::SAR(a) => apply_iform_sar(arena, a),
+        X86Instruction::SBB(a) => apply_iform_sbb(arena, a),
+        X86Instruction::SETB(a) => apply_iform_setb(arena, a),
+        X86Instruction::SETBE(a) => apply_iform_setbe(arena, a),
+        X86Instruction::SETL(a) => apply_iform_setl(arena, a),
+        X86Instruction::SETLE(a) => apply_iform_setle(arena, a),
+        X86Instruction::SETNB(a) => apply_iform_setnb(arena, a),
+        X86Instruction::SETNBE(a) => apply_iform_setnbe(arena, a),
+        X86Instruction::SETNL(a) => apply_iform_setnl(arena, a),
+        X86Instruction::SETNLE(a) => apply_iform_setnle(arena, a),
+        X86Instruction::SETNO(a) => apply_iform_setno(arena, a),
+        X86Instruction::SETNP(a) => apply_iform_setnp(arena, a),
+        X86Instruction::SETNS(a) => apply_iform_setns(arena, a),
+        X86Instruction::SETNZ(a) => apply_iform_setnz(arena, a),
+        X86Instruction::SETO(a) => apply_iform_seto(arena, a),
+        X86Instruction::SETP(a) => apply_iform_setp(arena, a),
+        X86Instruction::SETS(a) => apply_iform_sets(arena, a),
+        X86Instruction::SETZ(a) => apply_iform_setz(arena, a),
+        X86Instruction::SHL(a) => apply_iform_shl(arena, a),
+        X86Instruction::SHLD(a) => apply_iform_shld(arena, a),
+        X86Instruction::SHR(a) => apply_iform_shr(arena, a),
+        X86Instruction::SHRD(a) => apply_iform_shrd(arena, a),
+        X86Instruction::SQRTPD(a) => apply_iform_sqrtpd(arena, a),
+        X86Instruction::SQRTPS(a) => apply_iform_sqrtps(arena, a),
+        X86Instruction::SQRTSD(a) => apply_iform_sqrtsd(arena, a),
+        X86Instruction::SQRTSS(a) => apply_iform_sqrtss(arena, a),
+        X86Instruction::STMXCSR(a) => apply_iform_stmxcsr(arena, a),
+        X86Instruction::SUB(a) => apply_iform_sub(arena, a),
+        X86Instruction::SUBPD(a) => apply_iform_subpd(arena, a),
+        X86Instruction::SUBPS(a) => apply_iform_subps(arena, a),
+        X86Instruction::SUBSD(a) => apply_iform_subsd(arena, a),
+        X86Instruction::SUBSS(a) => apply_iform_subss(arena, a),
+        X86Instruction::TEST(a) => apply_iform_test(arena, a),
+        X86Instruction::TZCNT(a) => apply_iform_tzcnt(arena, a),
+        X86Instruction::UCOMISD(a) => apply_iform_ucomisd(arena, a),
+        X86Instruction::UCOMISS(a) => apply_iform_ucomiss(arena, a),
+        X86Instruction::VADDPD(a) => apply_iform_vaddpd(arena, a)?,
+        X86Instruction::VADDPS(a) => apply_iform_vaddps(arena, a)?,
+        X86Instruction::VADDSD(a) => apply_iform_vaddsd(arena, a)?,
+        X86Instruction::VADDSS(a) => apply_iform_vaddss(arena, a)?,
+        X86Instruction::VCMPPD(a) => apply_iform_vcmppd(arena, a)?,
+        X86Instruction::VCMPPS(a) => apply_iform_vcmpps(arena, a)?,
+        X86Instruction::VCMPSD(a) => apply_iform_vcmpsd(arena, a)?,
+        X86Instruction::VCMPSS(a) => apply_iform_vcmpss(arena, a)?,
+        X86Instruction::VCOMISD(a) => apply_iform_vcomisd(arena, a)?,
+        X86Instruction::VCOMISS(a) => apply_iform_vcomiss(arena, a)?,
+        X86Instruction::VCVTSD2SI(a) => apply_iform_vcvtsd2si(arena, a)?,
+        X86Instruction::VCVTSD2SS(a) => apply_iform_vcvtsd2ss(arena, a)?,
+        X86Instruction::VCVTSI2SD(a) => apply_iform_vcvtsi2sd(arena, a)?,
+        X86Instruction::VCVTSI2SS(a) => apply_iform_vcvtsi2ss(arena, a)?,
+        X86Instruction::VCVTSS2SD(a) => apply_iform_vcvtss2sd(arena, a)?,
+        X86Instruction::VCVTSS2SI(a) => apply_iform_vcvtss2si(arena, a)?,
+        X86Instruction::VCVTTSD2SI(a) => apply_iform_vcvttsd2si(arena, a)?,
+        X86Instruction::VCVTTSS2SI(a) => apply_iform_vcvttss2si(arena, a)?,
+        X86Instruction::VDIVPD(a) => apply_iform_vdivpd(arena, a)?,
+        X86Instruction::VDIVPS(a) => apply_iform_vdivps(arena, a)?,
+        X86Instruction::VDIVSD(a) => apply_iform_vdivsd(arena, a)?,
+        X86Instruction::VDIVSS(a) => apply_iform_vdivss(arena, a)?,
+        X86Instruction::VFMADD132PD(a) => apply_iform_vfmadd132pd(arena, a)?,
+        X86Instruction::VFMADD132PS(a) => apply_iform_vfmadd132ps(arena, a)?,
+        X86Instruction::VFMADD132SD(a) => apply_iform_vfmadd132sd(arena, a)?,
+        X86Instruction::VFMADD132SS(a) => apply_iform_vfmadd132ss(arena, a)?,
+        X86Instruction::VFMADD213PD(a) => apply_iform_vfmadd213pd(arena, a)?,
+        X86Instruction::VFMADD213PS(a) => apply_iform_vfmadd213ps(arena, a)?,
+        X86Instruction::VFMADD213SD(a) => apply_iform_vfmadd213sd(arena, a)?,
+        X86Instruction::VFMADD213SS(a) => apply_iform_vfmadd213ss(arena, a)?,
+        X86Instruction::VFMADD231PD(a) => apply_iform_vfmadd231pd(arena, a)?,
+        X86Instruction::VFMADD231PS(a) => apply_iform_vfmadd231ps(arena, a)?,
+        X86Instruction::VFMADD231SD(a) => apply_iform_vfmadd231sd(arena, a)?,
+        X86Instruction::VFMADD231SS(a) => apply_iform_vfmadd231ss(arena, a)?,
+        X86Instruction::VLDMXCSR(a) => apply_iform_vldmxcsr(arena, a),
+        X86Instruction::VMAXPD(a) => apply_iform_vmaxpd(arena, a)?,
+        X86Instruction::VMAXPS(a) => apply_iform_vmaxps(arena, a)?,
+        X86Instruction::VMAXSD(a) => apply_iform_vmaxsd(arena, a)?,
+        X86Instruction::VMAXSS(a) => apply_iform_vmaxss(arena, a)?,
+        X86Instruction::VMINPD(a) => apply_iform_vminpd(arena, a)?,
+        X86Instruction::VMINPS(a) => apply_iform_vminps(arena, a)?,
+        X86Instruction::VMINSD(a) => apply_iform_vminsd(arena, a)?,
+        X86Instruction::VMINSS(a) => apply_iform_vminss(arena, a)?,
+        X86Instruction::VMULPD(a) => apply_iform_vmulpd(arena, a)?,
+        X86Instruction::VMULPS(a) => apply_iform_vmulps(arena, a)?,
+        X86Instruction::VMULSD(a) => apply_iform_vmulsd(arena, a)?,
+        X86Instruction::VMULSS(a) => apply_iform_vmulss(arena, a)?,
+        X86Instruction::VSQRTPD(a) => apply_iform_vsqrtpd(arena, a)?,
+        X86Instruction::VSQRTPS(a) => apply_iform_vsqrtps(arena, a)?,
+        X86Instruction::VSQRTSD(a) => apply_iform_vsqrtsd(arena, a)?,
+        X86Instruction::VSQRTSS(a) => apply_iform_vsqrtss(arena, a)?,
+        X86Instruction::VSTMXCSR(a) => apply_iform_vstmxcsr(arena, a),
+        X86Instruction::VSUBPD(a) => apply_iform_vsubpd(arena, a)?,
+        X86Instruction::VSUBPS(a) => apply_iform_vsubps(arena, a)?,
+        X86Instruction::VSUBSD(a) => apply_iform_vsubsd(arena, a)?,
+        X86Instruction::VSUBSS(a) => apply_iform_vsubss(arena, a)?,
+        X86Instruction::VUCOMISD(a) => apply_iform_vucomisd(arena, a)?,
+        X86Instruction::VUCOMISS(a) => apply_iform_vucomiss(arena, a)?,
+        X86Instruction::XCHG(a) => apply_iform_xchg(arena, a),
+        X86Instruction::XOR(a) => apply_iform_xor(arena, a),
+        _ => return Err(SemanticsError::Unsupported(instr)),
+    })
+}
+
+/// Runs the semantics of `instr`, which is `length` bytes long, on a concrete state.
+pub fn apply_instruction_concrete(instr: X86Instruction, length: usize, state: ConcreteX86MachineState64) -> Result<ConcreteX86MachineState64, ConcreteError> {
+    let bump = Bump::new();
+    let steps = apply_instruction(Arena::new(&bump), instr, length)?;
+    Ok(apply_instructions_to_concrete(state, steps.as_slice())?)
 }
 
 #[cfg(test)]
@@ -0,0 +1,160 @@
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8};
+use xed_enum::{MOV, X86Instruction};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+pub fn mov_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := SRC;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    // imm32 sources of 64 bit moves are sign extended
+    let src = s.sext_to(readable1.read(&s), width);
+    writeable1.write(&mut s, src);
+    s.finalize()
+}
+
+pub fn apply_iform_mov(arena: Arena, instr: MOV) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        MOV::MOV_GPR8_IMMB_C6R0 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 8)
+        }
+        MOV::MOV_MEMB_IMMB { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 8)
+        }
+        MOV::MOV_GPRV_IMMZ_16 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 16)
+        }
+        MOV::MOV_GPRV_IMMZ_32 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 32)
+        }
+        MOV::MOV_GPRV_IMMZ_64 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 64)
+        }
+        MOV::MOV_MEMV_IMMZ_16 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 16)
+        }
+        MOV::MOV_MEMV_IMMZ_32 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 32)
+        }
+        MOV::MOV_MEMV_IMMZ_64 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 64)
+        }
+        MOV::MOV_GPR8_GPR8_88 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 8)
+        }
+        MOV::MOV_MEMB_GPR8 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 8)
+        }
+        MOV::MOV_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 16)
+        }
+        MOV::MOV_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 32)
+        }
+        MOV::MOV_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 64)
+        }
+        MOV::MOV_GPRV_GPRV_89_16 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 16)
+        }
+        MOV::MOV_GPRV_GPRV_89_32 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 32)
+        }
+        MOV::MOV_GPRV_GPRV_89_64 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 64)
+        }
+        MOV::MOV_GPR8_MEMB { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 8)
+        }
+        MOV::MOV_GPR8_GPR8_8A { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 8)
+        }
+        MOV::MOV_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 16)
+        }
+        MOV::MOV_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 32)
+        }
+        MOV::MOV_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 64)
+        }
+        MOV::MOV_GPRV_GPRV_8B_16 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 16)
+        }
+        MOV::MOV_GPRV_GPRV_8B_32 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 32)
+        }
+        MOV::MOV_GPRV_GPRV_8B_64 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 64)
+        }
+        MOV::MOV_MEMW_SEG { .. } => {
+            // moves out of segment registers aren't done yet
+            return Err(SemanticsError::Unsupported(X86Instruction::MOV(instr)));
+        }
+        MOV::MOV_GPRV_SEG_16 { .. } => {
+            // moves out of segment registers aren't done yet
+            return Err(SemanticsError::Unsupported(X86Instruction::MOV(instr)));
+        }
+        MOV::MOV_GPRV_SEG_32 { .. } => {
+            // moves out of segment registers aren't done yet
+            return Err(SemanticsError::Unsupported(X86Instruction::MOV(instr)));
+        }
+        MOV::MOV_GPRV_SEG_64 { .. } => {
+            // moves out of segment registers aren't done yet
+            return Err(SemanticsError::Unsupported(X86Instruction::MOV(instr)));
+        }
+        MOV::MOV_SEG_MEMW { .. } => {
+            // moves into segment registers aren't done yet
+            return Err(SemanticsError::Unsupported(X86Instruction::MOV(instr)));
+        }
+        MOV::MOV_SEG_GPR16 { .. } => {
+            // moves into segment registers aren't done yet
+            return Err(SemanticsError::Unsupported(X86Instruction::MOV(instr)));
+        }
+        MOV::MOV_AL_MEMB { operand_0 } => {
+            mov_generic(arena, Reg8::AL, operand_0, 8)
+        }
+        MOV::MOV_ORAX_MEMV_16 { operand_0 } => {
+            mov_generic(arena, Reg16WithRIP::AX, operand_0, 16)
+        }
+        MOV::MOV_ORAX_MEMV_32 { operand_0 } => {
+            mov_generic(arena, Reg32WithRIP::EAX, operand_0, 32)
+        }
+        MOV::MOV_ORAX_MEMV_64 { operand_0 } => {
+            mov_generic(arena, Reg64WithRIP::RAX, operand_0, 64)
+        }
+        MOV::MOV_MEMB_AL { operand_0 } => {
+            mov_generic(arena, operand_0, Reg8::AL, 8)
+        }
+        MOV::MOV_MEMV_ORAX_16 { operand_0 } => {
+            mov_generic(arena, operand_0, Reg16WithRIP::AX, 16)
+        }
+        MOV::MOV_MEMV_ORAX_32 { operand_0 } => {
+            mov_generic(arena, operand_0, Reg32WithRIP::EAX, 32)
+        }
+        MOV::MOV_MEMV_ORAX_64 { operand_0 } => {
+            mov_generic(arena, operand_0, Reg64WithRIP::RAX, 64)
+        }
+        MOV::MOV_GPR8_IMMB_B0 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 8)
+        }
+        MOV::MOV_GPRV_IMMV_16 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 16)
+        }
+        MOV::MOV_GPRV_IMMV_32 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 32)
+        }
+        MOV::MOV_GPRV_IMMV_64 { operand_0, operand_1 } => {
+            mov_generic(arena, operand_0, operand_1, 64)
+        }
+    })
+}
@@ -0,0 +1,85 @@
+use xed_enum::{MOVSX, MOVSXD};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn movsx_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+    src_width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := SignExtend(SRC);
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(src_width);
+    let src = s.sext_to(readable1.read(&s), width);
+    writeable1.write(&mut s, src);
+    s.finalize()
+}
+
+pub fn apply_iform_movsx(arena: Arena, instr: MOVSX) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MOVSX::MOVSX_GPRV_MEMB_16 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 16, 8)
+        }
+        MOVSX::MOVSX_GPRV_MEMB_32 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 32, 8)
+        }
+        MOVSX::MOVSX_GPRV_MEMB_64 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 64, 8)
+        }
+        MOVSX::MOVSX_GPRV_GPR8_16 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 16, 8)
+        }
+        MOVSX::MOVSX_GPRV_GPR8_32 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 32, 8)
+        }
+        MOVSX::MOVSX_GPRV_GPR8_64 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 64, 8)
+        }
+        MOVSX::MOVSX_GPRV_MEMW_16 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 16, 16)
+        }
+        MOVSX::MOVSX_GPRV_MEMW_32 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 32, 16)
+        }
+        MOVSX::MOVSX_GPRV_MEMW_64 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 64, 16)
+        }
+        MOVSX::MOVSX_GPRV_GPR16_16 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 16, 16)
+        }
+        MOVSX::MOVSX_GPRV_GPR16_32 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 32, 16)
+        }
+        MOVSX::MOVSX_GPRV_GPR16_64 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 64, 16)
+        }
+    }
+}
+
+pub fn apply_iform_movsxd(arena: Arena, instr: MOVSXD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MOVSXD::MOVSXD_GPRV_MEMZ_16 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 16, 16)
+        }
+        MOVSXD::MOVSXD_GPRV_MEMZ_32 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 32, 32)
+        }
+        MOVSXD::MOVSXD_GPRV_MEMZ_64 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 64, 32)
+        }
+        MOVSXD::MOVSXD_GPRV_GPRZ_16_16 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 16, 16)
+        }
+        MOVSXD::MOVSXD_GPRV_GPRZ_32_32 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 32, 32)
+        }
+        MOVSXD::MOVSXD_GPRV_GPRZ_64_32 { operand_0, operand_1 } => {
+            movsx_generic(arena, operand_0, operand_1, 64, 32)
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use xed_enum::MOVZX;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn movzx_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+    src_width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := ZeroExtend(SRC);
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(src_width);
+    let src = s.zext_to(readable1.read(&s), width);
+    writeable1.write(&mut s, src);
+    s.finalize()
+}
+
+pub fn apply_iform_movzx(arena: Arena, instr: MOVZX) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MOVZX::MOVZX_GPRV_MEMB_16 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 16, 8)
+        }
+        MOVZX::MOVZX_GPRV_MEMB_32 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 32, 8)
+        }
+        MOVZX::MOVZX_GPRV_MEMB_64 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 64, 8)
+        }
+        MOVZX::MOVZX_GPRV_GPR8_16 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 16, 8)
+        }
+        MOVZX::MOVZX_GPRV_GPR8_32 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 32, 8)
+        }
+        MOVZX::MOVZX_GPRV_GPR8_64 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 64, 8)
+        }
+        MOVZX::MOVZX_GPRV_MEMW_16 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 16, 16)
+        }
+        MOVZX::MOVZX_GPRV_MEMW_32 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 32, 16)
+        }
+        MOVZX::MOVZX_GPRV_MEMW_64 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 64, 16)
+        }
+        MOVZX::MOVZX_GPRV_GPR16_16 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 16, 16)
+        }
+        MOVZX::MOVZX_GPRV_GPR16_32 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 32, 16)
+        }
+        MOVZX::MOVZX_GPRV_GPR16_64 { operand_0, operand_1 } => {
+            movzx_generic(arena, operand_0, operand_1, 64, 16)
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use xed_enum::{IMUL, MUL};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::Signedness;
+use crate::semantics2::read_write::{accumulator, accumulator_high, Readable, Writeable};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+/// The one operand forms, which multiply the accumulator by `readable1` into the accumulator and its high half.
+pub fn widening_mul_generic<'arena, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+    width: usize,
+    signedness: Signedness,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF (Byte operation)
+    //    THEN AX := AL ∗ SRC;
+    //    ELSE DX:AX := AX ∗ SRC; (* or EDX:EAX, RDX:RAX *)
+    //FI;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let low = accumulator(width);
+    let high = accumulator_high(width);
+    let left = s.get_reg(low);
+    let right = readable1.read(&s);
+    let (product, flag_tag) = match signedness {
+        Signedness::Unsigned => (s.umul(s.zext_to(left, width * 2), s.zext_to(right, width * 2)), FlagTag::Mul),
+        Signedness::Signed => (s.smul(s.sext_to(left, width * 2), s.sext_to(right, width * 2)), FlagTag::IMul),
+    };
+    s.set_reg(low, s.lower_bits(product, width));
+    s.set_reg(high, s.upper_bits(product, width));
+    s.flag_calculate(flag_tag, left, right);
+    s.finalize()
+}
+
+/// The two and three operand forms of IMUL, which keep only the low half of the product.
+pub fn imul_truncating_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //TMP_XP := SRC1 ∗ SignExtend(SRC2);
+    //DEST := TruncateToOperandSize(TMP_XP);
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let left = readable1.read(&s);
+    let right = s.sext_to(readable2.read(&s), width);
+    let res = s.smul(left, right);
+    writeable1.write(&mut s, res);
+    s.flag_calculate(FlagTag::IMul, left, right);
+    s.finalize()
+}
+
+pub fn apply_iform_mul(arena: Arena, instr: MUL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MUL::MUL_MEMB { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 8, Signedness::Unsigned)
+        }
+        MUL::MUL_GPR8 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 8, Signedness::Unsigned)
+        }
+        MUL::MUL_MEMV_16 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 16, Signedness::Unsigned)
+        }
+        MUL::MUL_MEMV_32 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 32, Signedness::Unsigned)
+        }
+        MUL::MUL_MEMV_64 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 64, Signedness::Unsigned)
+        }
+        MUL::MUL_GPRV_16 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 16, Signedness::Unsigned)
+        }
+        MUL::MUL_GPRV_32 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 32, Signedness::Unsigned)
+        }
+        MUL::MUL_GPRV_64 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 64, Signedness::Unsigned)
+        }
+    }
+}
+
+pub fn apply_iform_imul(arena: Arena, instr: IMUL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        IMUL::IMUL_MEMB { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 8, Signedness::Signed)
+        }
+        IMUL::IMUL_GPR8 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 8, Signedness::Signed)
+        }
+        IMUL::IMUL_MEMV_16 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 16, Signedness::Signed)
+        }
+        IMUL::IMUL_MEMV_32 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 32, Signedness::Signed)
+        }
+        IMUL::IMUL_MEMV_64 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 64, Signedness::Signed)
+        }
+        IMUL::IMUL_GPRV_16 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 16, Signedness::Signed)
+        }
+        IMUL::IMUL_GPRV_32 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 32, Signedness::Signed)
+        }
+        IMUL::IMUL_GPRV_64 { operand_0 } => {
+            widening_mul_generic(arena, operand_0, 64, Signedness::Signed)
+        }
+        IMUL::IMUL_GPRV_MEMV_IMMZ_16 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 16)
+        }
+        IMUL::IMUL_GPRV_MEMV_IMMZ_32 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 32)
+        }
+        IMUL::IMUL_GPRV_MEMV_IMMZ_64 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 64)
+        }
+        IMUL::IMUL_GPRV_GPRV_IMMZ_16 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 16)
+        }
+        IMUL::IMUL_GPRV_GPRV_IMMZ_32 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 32)
+        }
+        IMUL::IMUL_GPRV_GPRV_IMMZ_64 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 64)
+        }
+        IMUL::IMUL_GPRV_MEMV_IMMB_16 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 16)
+        }
+        IMUL::IMUL_GPRV_MEMV_IMMB_32 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 32)
+        }
+        IMUL::IMUL_GPRV_MEMV_IMMB_64 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 64)
+        }
+        IMUL::IMUL_GPRV_GPRV_IMMB_16 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 16)
+        }
+        IMUL::IMUL_GPRV_GPRV_IMMB_32 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 32)
+        }
+        IMUL::IMUL_GPRV_GPRV_IMMB_64 { operand_0, operand_1, operand_2 } => {
+            imul_truncating_generic(arena, operand_0, operand_1, operand_2, 64)
+        }
+        IMUL::IMUL_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            imul_truncating_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        IMUL::IMUL_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            imul_truncating_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        IMUL::IMUL_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            imul_truncating_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        IMUL::IMUL_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            imul_truncating_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        IMUL::IMUL_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            imul_truncating_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        IMUL::IMUL_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            imul_truncating_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use xed_enum::{LDMXCSR, STMXCSR, VLDMXCSR, VSTMXCSR};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn ldmxcsr_generic<'arena, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // MXCSR := m32;
+    // #GP(0) if any of the reserved bits 31:16 are set
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(32);
+    let value = readable1.read(&s);
+    let reserved_clear = s.equal(s.upper_bits(value, 16), s.constant(0u16));
+    s.emit_conditional(reserved_clear, |s| s.set_mxcsr(value), |s| s.general_protection());
+    s.finalize()
+}
+
+pub fn stmxcsr_generic<'arena, D1: Writeable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // m32 := MXCSR;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(32);
+    let mxcsr = s.mxcsr();
+    writeable1.write(&mut s, mxcsr);
+    s.finalize()
+}
+
+pub fn apply_iform_ldmxcsr(arena: Arena, instr: LDMXCSR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        LDMXCSR::LDMXCSR_MEMD { operand_0 } => {
+            ldmxcsr_generic(arena, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_vldmxcsr(arena: Arena, instr: VLDMXCSR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        VLDMXCSR::VLDMXCSR_MEMD { operand_0 } => {
+            ldmxcsr_generic(arena, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_stmxcsr(arena: Arena, instr: STMXCSR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        STMXCSR::STMXCSR_MEMD { operand_0 } => {
+            stmxcsr_generic(arena, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_vstmxcsr(arena: Arena, instr: VSTMXCSR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        VSTMXCSR::VSTMXCSR_MEMD { operand_0 } => {
+            stmxcsr_generic(arena, operand_0)
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use xed_enum::NEG;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+pub fn neg_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF DEST = 0
+    //    THEN CF := 0;
+    //    ELSE CF := 1;
+    //FI;
+    //DEST := [– (DEST)]
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let zero = s.constant_with_width(0, width);
+    let res = s.sub(zero, dest);
+    writeable1.write(&mut s, res);
+    // 0 - DEST borrows exactly when DEST isn't zero, so SUB's flags are NEG's
+    s.flag_calculate(FlagTag::Sub, zero, dest);
+    s.finalize()
+}
+
+pub fn apply_iform_neg(arena: Arena, instr: NEG) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        NEG::NEG_MEMB { operand_0 } => {
+            neg_generic(arena, operand_0, operand_0, 8)
+        }
+        NEG::NEG_GPR8 { operand_0 } => {
+            neg_generic(arena, operand_0, operand_0, 8)
+        }
+        NEG::NEG_MEMV_16 { operand_0 } => {
+            neg_generic(arena, operand_0, operand_0, 16)
+        }
+        NEG::NEG_MEMV_32 { operand_0 } => {
+            neg_generic(arena, operand_0, operand_0, 32)
+        }
+        NEG::NEG_MEMV_64 { operand_0 } => {
+            neg_generic(arena, operand_0, operand_0, 64)
+        }
+        NEG::NEG_GPRV_16 { operand_0 } => {
+            neg_generic(arena, operand_0, operand_0, 16)
+        }
+        NEG::NEG_GPRV_32 { operand_0 } => {
+            neg_generic(arena, operand_0, operand_0, 32)
+        }
+        NEG::NEG_GPRV_64 { operand_0 } => {
+            neg_generic(arena, operand_0, operand_0, 64)
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use xed_enum::NOT;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn not_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := NOT DEST;
+    //Flags Affected: None
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let res = s.not(dest);
+    writeable1.write(&mut s, res);
+    s.finalize()
+}
+
+pub fn apply_iform_not(arena: Arena, instr: NOT) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        NOT::NOT_MEMB { operand_0 } => {
+            not_generic(arena, operand_0, operand_0, 8)
+        }
+        NOT::NOT_GPR8 { operand_0 } => {
+            not_generic(arena, operand_0, operand_0, 8)
+        }
+        NOT::NOT_MEMV_16 { operand_0 } => {
+            not_generic(arena, operand_0, operand_0, 16)
+        }
+        NOT::NOT_MEMV_32 { operand_0 } => {
+            not_generic(arena, operand_0, operand_0, 32)
+        }
+        NOT::NOT_MEMV_64 { operand_0 } => {
+            not_generic(arena, operand_0, operand_0, 64)
+        }
+        NOT::NOT_GPRV_16 { operand_0 } => {
+            not_generic(arena, operand_0, operand_0, 16)
+        }
+        NOT::NOT_GPRV_32 { operand_0 } => {
+            not_generic(arena, operand_0, operand_0, 32)
+        }
+        NOT::NOT_GPRV_64 { operand_0 } => {
+            not_generic(arena, operand_0, operand_0, 64)
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub trait IntegerWidth {
+    /// Width in bits.
     fn width() -> usize;
     fn to_u64(&self) -> u64;
 }
@@ -19,7 +20,7 @@ impl IntegerWidth for bool {
 
 impl IntegerWidth for u8 {
     fn width() -> usize {
-        1
+        8
     }
 
     fn to_u64(&self) -> u64 {
@@ -29,7 +30,7 @@ impl IntegerWidth for u8 {
 
 impl IntegerWidth for u16 {
     fn width() -> usize {
-        2
+        16
     }
 
     fn to_u64(&self) -> u64 {
@@ -39,7 +40,7 @@ impl IntegerWidth for u16 {
 
 impl IntegerWidth for u32 {
     fn width() -> usize {
-        4
+        32
     }
 
     fn to_u64(&self) -> u64 {
@@ -49,7 +50,7 @@ impl IntegerWidth for u32 {
 
 impl IntegerWidth for u64 {
     fn width() -> usize {
-        8
+        64
     }
 
     fn to_u64(&self) -> u64 {
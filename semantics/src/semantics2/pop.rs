@@ -0,0 +1,81 @@
+use wrapper_common::registers::RegSegment;
+use xed_enum::POP;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::{legacy_mode_form, SemanticsBuilder};
+use crate::semantics2::read_write::Writeable;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+pub fn pop_generic<'arena, D1: Writeable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := Memory[SS:RSP];
+    //RSP := RSP + OperandSize/8;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let value = s.pop_stack(width);
+    // written after the increment, so a memory destination addressed through RSP uses the incremented RSP, and
+    // POP RSP leaves the popped value in RSP
+    writeable1.write(&mut s, value);
+    s.finalize()
+}
+
+/// POP of a segment register moves the stack pointer up by the operand size and loads the low 16 bits of the slot
+/// as the selector, see [`InstructionSemanticsStep::SetSegment`]. Like PUSH, the slot is as wide as the default
+/// operand size.
+pub fn pop_segment<'arena>(arena: Arena<'arena>, segment: RegSegment) -> Vec<InstructionSemanticsStep<'arena>> {
+    let mut s = SemanticsBuilder::new(arena);
+    let value = s.pop_stack(s.stack_operand_width());
+    s.set_segment(segment, s.lower_bits(value, 16));
+    s.finalize()
+}
+
+pub fn apply_iform_pop(arena: Arena, instr: POP) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        POP::POP_MEMV_16 { operand_0 } => {
+            pop_generic(arena, operand_0, 16)
+        }
+        POP::POP_MEMV_32 { operand_0 } => {
+            legacy_mode_form(arena, |arena| pop_generic(arena, operand_0, 32))
+        }
+        POP::POP_MEMV_64 { operand_0 } => {
+            pop_generic(arena, operand_0, 64)
+        }
+        POP::POP_GPRV_8F_16 { operand_0 } => {
+            pop_generic(arena, operand_0, 16)
+        }
+        POP::POP_GPRV_8F_32 { operand_0 } => {
+            legacy_mode_form(arena, |arena| pop_generic(arena, operand_0, 32))
+        }
+        POP::POP_GPRV_8F_64 { operand_0 } => {
+            pop_generic(arena, operand_0, 64)
+        }
+        POP::POP_ES { .. } => {
+            legacy_mode_form(arena, |arena| pop_segment(arena, RegSegment::ES))
+        }
+        POP::POP_SS { .. } => {
+            legacy_mode_form(arena, |arena| pop_segment(arena, RegSegment::SS))
+        }
+        POP::POP_DS { .. } => {
+            legacy_mode_form(arena, |arena| pop_segment(arena, RegSegment::DS))
+        }
+        POP::POP_GPRV_58_16 { operand_0 } => {
+            pop_generic(arena, operand_0, 16)
+        }
+        POP::POP_GPRV_58_32 { operand_0 } => {
+            legacy_mode_form(arena, |arena| pop_generic(arena, operand_0, 32))
+        }
+        POP::POP_GPRV_58_64 { operand_0 } => {
+            pop_generic(arena, operand_0, 64)
+        }
+        POP::POP_FS { .. } => {
+            pop_segment(arena, RegSegment::FS)
+        }
+        POP::POP_GS { .. } => {
+            pop_segment(arena, RegSegment::GS)
+        }
+    })
+}
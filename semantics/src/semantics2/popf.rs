@@ -0,0 +1,38 @@
+use xed_enum::{POPF, POPFD, POPFQ};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::{legacy_mode_form, SemanticsBuilder};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn popf_generic(arena: Arena, width: usize) -> Vec<InstructionSemanticsStep> {
+    //tempFLAGS := Pop();
+    //RFLAGS := tempFLAGS; (* only the flags the current privilege level may change, of which just the arithmetic flags are modelled *)
+    let mut s = SemanticsBuilder::new(arena);
+    let flags = s.pop_stack(width);
+    s.set_arithmetic_flags_from_rflags(flags);
+    s.finalize()
+}
+
+pub fn apply_iform_popf(arena: Arena, instr: POPF) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        POPF::POPF {} => {
+            popf_generic(arena, 16)
+        }
+    }
+}
+
+pub fn apply_iform_popfd(arena: Arena, instr: POPFD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        POPFD::POPFD {} => {
+            legacy_mode_form(arena, |arena| popf_generic(arena, 32))
+        }
+    }
+}
+
+pub fn apply_iform_popfq(arena: Arena, instr: POPFQ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        POPFQ::POPFQ {} => {
+            popf_generic(arena, 64)
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use wrapper_common::registers::RegSegment;
+use xed_enum::PUSH;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::{legacy_mode_form, SemanticsBuilder};
+use crate::semantics2::read_write::Readable;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+pub fn push_generic<'arena, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //RSP := RSP - OperandSize/8;
+    //Memory[SS:RSP] := SRC; (* PUSH RSP pushes the value from before the decrement *)
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    // immediates are sign extended to the operand size
+    let src = s.sext_to(readable1.read(&s), width);
+    s.push_stack(src);
+    s.finalize()
+}
+
+/// PUSH of a segment register moves the stack pointer down by the operand size, but only writes the 16 bit selector
+/// to the bottom of the slot. The operand size prefix isn't visible in the decoded instruction, so the slot is as
+/// wide as the default operand size. The ES, CS, SS and DS forms, and their POP counterparts, only exist outside of
+/// 64 bit mode.
+pub fn push_segment<'arena>(arena: Arena<'arena>, segment: RegSegment) -> Vec<InstructionSemanticsStep<'arena>> {
+    let mut s = SemanticsBuilder::new(arena);
+    let selector = s.get_segment(segment);
+    s.push_stack_slot(selector, s.stack_operand_width());
+    s.finalize()
+}
+
+/// The operand size of PUSH and POP is 64 bits by default in 64 bit mode, and can only be changed to 16 bits.
+/// The 32 bit forms only exist outside of 64 bit mode.
+pub fn apply_iform_push(arena: Arena, instr: PUSH) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        PUSH::PUSH_MEMV_16 { operand_0 } => {
+            push_generic(arena, operand_0, 16)
+        }
+        PUSH::PUSH_MEMV_32 { operand_0 } => {
+            legacy_mode_form(arena, |arena| push_generic(arena, operand_0, 32))
+        }
+        PUSH::PUSH_MEMV_64 { operand_0 } => {
+            push_generic(arena, operand_0, 64)
+        }
+        PUSH::PUSH_GPRV_FFR6_16 { operand_0 } => {
+            push_generic(arena, operand_0, 16)
+        }
+        PUSH::PUSH_GPRV_FFR6_32 { operand_0 } => {
+            legacy_mode_form(arena, |arena| push_generic(arena, operand_0, 32))
+        }
+        PUSH::PUSH_GPRV_FFR6_64 { operand_0 } => {
+            push_generic(arena, operand_0, 64)
+        }
+        PUSH::PUSH_ES { .. } => {
+            legacy_mode_form(arena, |arena| push_segment(arena, RegSegment::ES))
+        }
+        PUSH::PUSH_CS { .. } => {
+            legacy_mode_form(arena, |arena| push_segment(arena, RegSegment::CS))
+        }
+        PUSH::PUSH_SS { .. } => {
+            legacy_mode_form(arena, |arena| push_segment(arena, RegSegment::SS))
+        }
+        PUSH::PUSH_DS { .. } => {
+            legacy_mode_form(arena, |arena| push_segment(arena, RegSegment::DS))
+        }
+        PUSH::PUSH_GPRV_50_16 { operand_0 } => {
+            push_generic(arena, operand_0, 16)
+        }
+        PUSH::PUSH_GPRV_50_32 { operand_0 } => {
+            legacy_mode_form(arena, |arena| push_generic(arena, operand_0, 32))
+        }
+        PUSH::PUSH_GPRV_50_64 { operand_0 } => {
+            push_generic(arena, operand_0, 64)
+        }
+        PUSH::PUSH_IMMZ_16 { operand_0 } => {
+            push_generic(arena, operand_0, 16)
+        }
+        PUSH::PUSH_IMMZ_32 { operand_0 } => {
+            legacy_mode_form(arena, |arena| push_generic(arena, operand_0, 32))
+        }
+        PUSH::PUSH_IMMZ_64 { operand_0 } => {
+            push_generic(arena, operand_0, 64)
+        }
+        PUSH::PUSH_IMMB { operand_0 } => {
+            push_generic(arena, operand_0, arena.mode().stack_operand_width())
+        }
+        PUSH::PUSH_FS { .. } => {
+            push_segment(arena, RegSegment::FS)
+        }
+        PUSH::PUSH_GS { .. } => {
+            push_segment(arena, RegSegment::GS)
+        }
+    })
+}
@@ -0,0 +1,37 @@
+use xed_enum::{PUSHF, PUSHFD, PUSHFQ};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::{legacy_mode_form, SemanticsBuilder};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn pushf_generic(arena: Arena, width: usize) -> Vec<InstructionSemanticsStep> {
+    //Push(RFLAGS AND 00FCFFFFH); (* VM and RF are cleared in the image, with a 16 bit operand size only FLAGS is pushed *)
+    let mut s = SemanticsBuilder::new(arena);
+    let flags = s.lower_bits(s.rflags(), width);
+    s.push_stack(flags);
+    s.finalize()
+}
+
+pub fn apply_iform_pushf(arena: Arena, instr: PUSHF) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        PUSHF::PUSHF {} => {
+            pushf_generic(arena, 16)
+        }
+    }
+}
+
+pub fn apply_iform_pushfd(arena: Arena, instr: PUSHFD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        PUSHFD::PUSHFD {} => {
+            legacy_mode_form(arena, |arena| pushf_generic(arena, 32))
+        }
+    }
+}
+
+pub fn apply_iform_pushfq(arena: Arena, instr: PUSHFQ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        PUSHFQ::PUSHFQ {} => {
+            pushf_generic(arena, 64)
+        }
+    }
+}
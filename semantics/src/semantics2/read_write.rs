@@ -1,6 +1,6 @@
 use wrapper_common::memory_operand::GeneralReg;
 use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8, RegXMM, RegYMM, RegZMM};
-use xed_wrapper::operands::{Imm16, Imm32, Imm8, MemoryOperands};
+use xed_wrapper::operands::{Imm16, Imm32, Imm64, Imm8, MemoryOperands};
 use crate::semantics2;
 
 use crate::semantics2::builder::SemanticsBuilder;
@@ -30,37 +30,37 @@ impl<'arena> Writeable<'arena> for Reg8 {
 
 impl<'arena> Readable<'arena> for Reg16WithRIP {
     fn read(&self, semantics: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
-        todo!()
+        semantics.get_reg_16(*self)
     }
 }
 
 impl<'arena> Writeable<'arena> for Reg16WithRIP {
     fn write(&self, semantics: &mut SemanticsBuilder<'arena>, expr: &'arena Expression<'arena>) {
-        todo!()
+        semantics.set_reg_16(*self, expr);
     }
 }
 
 impl<'arena> Readable<'arena> for Reg32WithRIP {
     fn read(&self, semantics: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
-        todo!()
+        semantics.get_reg_32(*self)
     }
 }
 
 impl<'arena> Writeable<'arena> for Reg32WithRIP {
     fn write(&self, semantics: &mut SemanticsBuilder<'arena>, expr: &'arena Expression<'arena>) {
-        todo!()
+        semantics.set_reg_32(*self, expr);
     }
 }
 
 impl<'arena> Readable<'arena> for Reg64WithRIP {
     fn read(&self, semantics: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
-        todo!()
+        semantics.get_reg_64(*self)
     }
 }
 
 impl<'arena> Writeable<'arena> for Reg64WithRIP {
     fn write(&self, semantics: &mut SemanticsBuilder<'arena>, expr: &'arena Expression<'arena>) {
-        todo!()
+        semantics.set_reg_64(*self, expr);
     }
 }
 
@@ -82,50 +82,93 @@ impl <'arena> Readable<'arena> for Imm32 {
     }
 }
 
+impl <'arena> Readable<'arena> for Imm64 {
+    fn read(&self, s: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
+        s.constant(self.0)
+    }
+}
+
+impl<'arena> Readable<'arena> for GeneralReg {
+    fn read(&self, semantics: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
+        semantics.get_reg(*self)
+    }
+}
+
+impl<'arena> Writeable<'arena> for GeneralReg {
+    fn write(&self, semantics: &mut SemanticsBuilder<'arena>, expr: &'arena Expression<'arena>) {
+        semantics.set_reg(*self, expr);
+    }
+}
+
+/// AL, AX, EAX or RAX, for the implicit accumulator operand of `width` bit instructions.
+pub fn accumulator(width: usize) -> GeneralReg {
+    match width {
+        8 => GeneralReg::Reg8(Reg8::AL),
+        16 => GeneralReg::Reg16(Reg16WithRIP::AX),
+        32 => GeneralReg::Reg32(Reg32WithRIP::EAX),
+        64 => GeneralReg::Reg64(Reg64WithRIP::RAX),
+        width => panic!("no {width} bit accumulator"),
+    }
+}
+
+/// Where multiplies and divides keep the upper half of a double width value whose lower half is in
+/// [`accumulator`]. AH for byte operands, otherwise DX, EDX or RDX.
+pub fn accumulator_high(width: usize) -> GeneralReg {
+    match width {
+        8 => GeneralReg::Reg8(Reg8::AH),
+        16 => GeneralReg::Reg16(Reg16WithRIP::DX),
+        32 => GeneralReg::Reg32(Reg32WithRIP::EDX),
+        64 => GeneralReg::Reg64(Reg64WithRIP::RDX),
+        width => panic!("no {width} bit accumulator"),
+    }
+}
+
 impl <'arena> Readable<'arena> for MemoryOperands{
     fn read(&self, semantics: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
-        todo!()
+        semantics.load(semantics.effective_address(self), semantics.memory_operand_width())
     }
 }
 
 impl <'arena> Writeable<'arena> for MemoryOperands {
     fn write(&self, semantics: &mut SemanticsBuilder<'arena>, expr: &'arena Expression<'arena>) {
-        todo!()
+        let address = semantics.effective_address(self);
+        semantics.store(address, expr);
     }
 }
 
 impl <'arena> Readable<'arena> for RegXMM {
     fn read(&self, semantics: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
-        todo!()
+        semantics.get_xmm(*self)
     }
 }
 
+/// Legacy SSE semantics, VEX/EVEX encoded instructions write through [`RegXMM::widen_to_zmm`] instead.
 impl <'arena> Writeable<'arena> for RegXMM {
     fn write(&self, semantics: &mut SemanticsBuilder<'arena>, expr: &'arena Expression<'arena>) {
-        todo!()
+        semantics.set_xmm(*self, expr);
     }
 }
 
 impl <'arena> Readable<'arena> for RegYMM {
     fn read(&self, semantics: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
-        todo!()
+        semantics.get_ymm(*self)
     }
 }
 
 impl <'arena> Writeable<'arena> for RegYMM {
     fn write(&self, semantics: &mut SemanticsBuilder<'arena>, expr: &'arena Expression<'arena>) {
-        todo!()
+        semantics.set_ymm(*self, expr);
     }
 }
 
 impl <'arena> Readable<'arena> for RegZMM {
     fn read(&self, semantics: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
-        todo!()
+        semantics.get_zmm(*self)
     }
 }
 
 impl <'arena> Writeable<'arena> for RegZMM {
     fn write(&self, semantics: &mut SemanticsBuilder<'arena>, expr: &'arena Expression<'arena>) {
-        todo!()
+        semantics.set_zmm(*self, expr);
     }
 }
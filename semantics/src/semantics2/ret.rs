@@ -0,0 +1,41 @@
+use xed_enum::{RET_FAR, RET_NEAR, X86Instruction};
+use xed_wrapper::operands::Imm16;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::Readable;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+pub fn ret_near_generic<'arena>(arena: Arena<'arena>, release: Option<Imm16>) -> Vec<InstructionSemanticsStep<'arena>> {
+    //RIP := Pop();
+    //IF instruction has immediate operand
+    //    THEN RSP := RSP + SRC;
+    //FI;
+    let mut s = SemanticsBuilder::new(arena);
+    let width = s.stack_operand_width();
+    let target = s.pop_stack(width);
+    s.set_rip(if width == 64 { target } else { s.zext_to(target, 64) });
+    if let Some(release) = release {
+        // the immediate is an unsigned byte count
+        let sp = s.add(s.stack_pointer(), s.zext_to(release.read(&s), s.stack_width()));
+        s.set_stack_pointer(sp);
+    }
+    s.finalize()
+}
+
+pub fn apply_iform_ret_near(arena: Arena, instr: RET_NEAR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        RET_NEAR::RET_NEAR_IMMW { operand_0 } => {
+            ret_near_generic(arena, Some(operand_0))
+        }
+        RET_NEAR::RET_NEAR {} => {
+            ret_near_generic(arena, None)
+        }
+    }
+}
+
+pub fn apply_iform_ret_far(_arena: Arena, instr: RET_FAR) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    // far branches load cs, segment selectors are not modelled
+    Err(SemanticsError::Unsupported(X86Instruction::RET_FAR(instr)))
+}
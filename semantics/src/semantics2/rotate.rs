@@ -0,0 +1,406 @@
+use wrapper_common::registers::Reg8;
+use xed_enum::{RCL, RCR, ROL, ROR};
+use xed_wrapper::operands::Imm8;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+#[derive(Copy, Clone)]
+pub enum RotateDirection {
+    Left,
+    Right,
+}
+
+pub fn rotate_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    direction: RotateDirection,
+    writeable1: D1,
+    readable1: S1,
+    count: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //tempCOUNT := (COUNT & COUNTMASK) MOD SIZE
+    //DEST := DEST rotated by tempCOUNT;
+    //IF (COUNT & COUNTMASK) ≠ 0
+    //    THEN CF and OF are set, OF is undefined unless (COUNT & COUNTMASK) = 1;
+    //FI;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let count = s.shift_count(count.read(&s), width);
+    let size = s.constant_with_width(width as u64, width);
+    let rotation = s.urem(count, size);
+    let inverse = s.sub(size, rotation);
+    let res = match direction {
+        RotateDirection::Left => s.bitor(s.shl(dest, rotation), s.lshr(dest, inverse)),
+        RotateDirection::Right => s.bitor(s.lshr(dest, rotation), s.shl(dest, inverse)),
+    };
+    writeable1.write(&mut s, res);
+    let msb = s.extract(res, width - 1, width);
+    let (cf, of) = match direction {
+        //CF := LSB(DEST);
+        //OF := MSB(DEST) XOR CF;
+        RotateDirection::Left => {
+            let cf = s.extract(res, 0, 1);
+            (cf, s.bitxor(msb, cf))
+        }
+        //CF := MSB(DEST);
+        //OF := MSB(DEST) XOR MSB − 1(DEST);
+        RotateDirection::Right => (msb, s.bitxor(msb, s.extract(res, width - 2, width - 1))),
+    };
+    let count_is_zero = s.equal(count, s.constant_with_width(0, width));
+    s.emit_conditional(count_is_zero, |_| {}, |s| {
+        s.set_cf(cf);
+        s.set_of(of);
+    });
+    s.finalize()
+}
+
+/// RCL and RCR, which rotate `SIZE + 1` bits made up of CF above the destination.
+pub fn rotate_through_carry_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    direction: RotateDirection,
+    writeable1: D1,
+    readable1: S1,
+    count: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //tempCOUNT := (COUNT AND COUNTMASK) MOD (SIZE + 1), the MOD only matters for 8 and 16 bit operands
+    //(CF:DEST) := (CF:DEST) rotated by tempCOUNT;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let carry = s.cf();
+    let count = s.shift_count(count.read(&s), width);
+    let wide_width = width + 1;
+    let size = s.constant_with_width(wide_width as u64, wide_width);
+    let rotation = s.urem(s.zext_to(count, wide_width), size);
+    let inverse = s.sub(size, rotation);
+    let wide = s.concat(carry, dest);
+    let rotated = match direction {
+        RotateDirection::Left => s.bitor(s.shl(wide, rotation), s.lshr(wide, inverse)),
+        RotateDirection::Right => s.bitor(s.lshr(wide, rotation), s.shl(wide, inverse)),
+    };
+    let res = s.lower_bits(rotated, width);
+    writeable1.write(&mut s, res);
+    let new_carry = s.extract(rotated, width, wide_width);
+    let of = match direction {
+        //OF := MSB(DEST) XOR CF; (* after the rotate *)
+        RotateDirection::Left => s.bitxor(s.extract(res, width - 1, width), new_carry),
+        //OF := MSB(DEST) XOR CF; (* before the rotate *)
+        RotateDirection::Right => s.bitxor(s.extract(dest, width - 1, width), carry),
+    };
+    let count_is_zero = s.equal(count, s.constant_with_width(0, width));
+    s.emit_conditional(count_is_zero, |_| {}, |s| {
+        s.set_cf(new_carry);
+        s.set_of(of);
+    });
+    s.finalize()
+}
+
+pub fn apply_iform_rol(arena: Arena, instr: ROL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        ROL::ROL_MEMB_IMMB { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 8)
+        }
+        ROL::ROL_GPR8_IMMB { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 8)
+        }
+        ROL::ROL_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 16)
+        }
+        ROL::ROL_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 32)
+        }
+        ROL::ROL_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 64)
+        }
+        ROL::ROL_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 16)
+        }
+        ROL::ROL_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 32)
+        }
+        ROL::ROL_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 64)
+        }
+        ROL::ROL_MEMB_ONE { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 8)
+        }
+        ROL::ROL_GPR8_ONE { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 8)
+        }
+        ROL::ROL_MEMV_ONE_16 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 16)
+        }
+        ROL::ROL_MEMV_ONE_32 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 32)
+        }
+        ROL::ROL_MEMV_ONE_64 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 64)
+        }
+        ROL::ROL_GPRV_ONE_16 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 16)
+        }
+        ROL::ROL_GPRV_ONE_32 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 32)
+        }
+        ROL::ROL_GPRV_ONE_64 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 64)
+        }
+        ROL::ROL_MEMB_CL { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 8)
+        }
+        ROL::ROL_GPR8_CL { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 8)
+        }
+        ROL::ROL_MEMV_CL_16 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 16)
+        }
+        ROL::ROL_MEMV_CL_32 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 32)
+        }
+        ROL::ROL_MEMV_CL_64 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 64)
+        }
+        ROL::ROL_GPRV_CL_16 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 16)
+        }
+        ROL::ROL_GPRV_CL_32 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 32)
+        }
+        ROL::ROL_GPRV_CL_64 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 64)
+        }
+    }
+}
+pub fn apply_iform_ror(arena: Arena, instr: ROR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        ROR::ROR_MEMB_IMMB { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 8)
+        }
+        ROR::ROR_GPR8_IMMB { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 8)
+        }
+        ROR::ROR_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 16)
+        }
+        ROR::ROR_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 32)
+        }
+        ROR::ROR_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 64)
+        }
+        ROR::ROR_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 16)
+        }
+        ROR::ROR_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 32)
+        }
+        ROR::ROR_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 64)
+        }
+        ROR::ROR_MEMB_ONE { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 8)
+        }
+        ROR::ROR_GPR8_ONE { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 8)
+        }
+        ROR::ROR_MEMV_ONE_16 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 16)
+        }
+        ROR::ROR_MEMV_ONE_32 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 32)
+        }
+        ROR::ROR_MEMV_ONE_64 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 64)
+        }
+        ROR::ROR_GPRV_ONE_16 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 16)
+        }
+        ROR::ROR_GPRV_ONE_32 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 32)
+        }
+        ROR::ROR_GPRV_ONE_64 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 64)
+        }
+        ROR::ROR_MEMB_CL { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 8)
+        }
+        ROR::ROR_GPR8_CL { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 8)
+        }
+        ROR::ROR_MEMV_CL_16 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 16)
+        }
+        ROR::ROR_MEMV_CL_32 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 32)
+        }
+        ROR::ROR_MEMV_CL_64 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 64)
+        }
+        ROR::ROR_GPRV_CL_16 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 16)
+        }
+        ROR::ROR_GPRV_CL_32 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 32)
+        }
+        ROR::ROR_GPRV_CL_64 { operand_0 } => {
+            rotate_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 64)
+        }
+    }
+}
+pub fn apply_iform_rcl(arena: Arena, instr: RCL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        RCL::RCL_MEMB_IMMB { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 8)
+        }
+        RCL::RCL_GPR8_IMMB { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 8)
+        }
+        RCL::RCL_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 16)
+        }
+        RCL::RCL_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 32)
+        }
+        RCL::RCL_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 64)
+        }
+        RCL::RCL_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 16)
+        }
+        RCL::RCL_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 32)
+        }
+        RCL::RCL_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, 64)
+        }
+        RCL::RCL_MEMB_ONE { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 8)
+        }
+        RCL::RCL_GPR8_ONE { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 8)
+        }
+        RCL::RCL_MEMV_ONE_16 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 16)
+        }
+        RCL::RCL_MEMV_ONE_32 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 32)
+        }
+        RCL::RCL_MEMV_ONE_64 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 64)
+        }
+        RCL::RCL_GPRV_ONE_16 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 16)
+        }
+        RCL::RCL_GPRV_ONE_32 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 32)
+        }
+        RCL::RCL_GPRV_ONE_64 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Imm8(1), 64)
+        }
+        RCL::RCL_MEMB_CL { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 8)
+        }
+        RCL::RCL_GPR8_CL { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 8)
+        }
+        RCL::RCL_MEMV_CL_16 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 16)
+        }
+        RCL::RCL_MEMV_CL_32 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 32)
+        }
+        RCL::RCL_MEMV_CL_64 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 64)
+        }
+        RCL::RCL_GPRV_CL_16 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 16)
+        }
+        RCL::RCL_GPRV_CL_32 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 32)
+        }
+        RCL::RCL_GPRV_CL_64 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Left, operand_0, operand_0, Reg8::CL, 64)
+        }
+    }
+}
+pub fn apply_iform_rcr(arena: Arena, instr: RCR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        RCR::RCR_MEMB_IMMB { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 8)
+        }
+        RCR::RCR_GPR8_IMMB { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 8)
+        }
+        RCR::RCR_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 16)
+        }
+        RCR::RCR_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 32)
+        }
+        RCR::RCR_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 64)
+        }
+        RCR::RCR_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 16)
+        }
+        RCR::RCR_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 32)
+        }
+        RCR::RCR_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, 64)
+        }
+        RCR::RCR_MEMB_ONE { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 8)
+        }
+        RCR::RCR_GPR8_ONE { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 8)
+        }
+        RCR::RCR_MEMV_ONE_16 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 16)
+        }
+        RCR::RCR_MEMV_ONE_32 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 32)
+        }
+        RCR::RCR_MEMV_ONE_64 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 64)
+        }
+        RCR::RCR_GPRV_ONE_16 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 16)
+        }
+        RCR::RCR_GPRV_ONE_32 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 32)
+        }
+        RCR::RCR_GPRV_ONE_64 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Imm8(1), 64)
+        }
+        RCR::RCR_MEMB_CL { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 8)
+        }
+        RCR::RCR_GPR8_CL { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 8)
+        }
+        RCR::RCR_MEMV_CL_16 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 16)
+        }
+        RCR::RCR_MEMV_CL_32 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 32)
+        }
+        RCR::RCR_MEMV_CL_64 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 64)
+        }
+        RCR::RCR_GPRV_CL_16 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 16)
+        }
+        RCR::RCR_GPRV_CL_32 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 32)
+        }
+        RCR::RCR_GPRV_CL_64 { operand_0 } => {
+            rotate_through_carry_generic(arena, RotateDirection::Right, operand_0, operand_0, Reg8::CL, 64)
+        }
+    }
+}
@@ -0,0 +1,757 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote};
+use xed_enum::{DecodeError, EncodeDecodeContext, X86Instruction};
+
+use crate::semantics2::{apply_instruction_with_address_width, SemanticsError};
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::{ArithmeticOp, BitCountOp, BitWiseOp, ComparisonOp, Expression, ShiftOp, Signedness};
+use crate::semantics2::semantic_steps::{loaded_segment_base, steps_count, InstructionSemanticsStep, ZeroUpper};
+use crate::semantics2::smtlib2::StateComponent;
+
+// generated code only names this module, so everything it refers to is reachable from here
+pub use wrapper_common::registers::{Reg64WithRIP, RegSegment, RegSegmentBase};
+pub use crate::semantics2::expression::Flag;
+pub use crate::semantics2::semantic_steps::Fault;
+pub use crate::semantics2::state::ConcreteX86MachineState64;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RustCodegenError {
+    /// Values are kept in `u128`s, so nothing touching the ZMM registers can be generated, nor can floating point
+    /// expressions or the x87 stack, which need the soft float code of the concrete evaluator.
+    Unsupported(&'static str),
+    Decode(DecodeError),
+    Semantics(SemanticsError),
+}
+
+/// A store an instruction has made, as the address, the value and its width in bits. Generated code keeps them to
+/// itself until the instruction is done, so that a fault leaves memory alone.
+pub type PendingStore = (u64, u128, usize);
+
+/// Little endian read of `width` bits at `address`, of memory as it is once `stores` are made. Used by generated
+/// code.
+pub fn load(state: &ConcreteX86MachineState64, stores: &[PendingStore], address: u64, width: usize) -> u128 {
+    let mut res = 0;
+    for i in 0..width / 8 {
+        let byte_address = address.wrapping_add(i as u64);
+        let stored = stores.iter().rev().find_map(|&(store_address, value, store_width)| {
+            let offset = byte_address.wrapping_sub(store_address);
+            (offset < (store_width / 8) as u64).then(|| (value >> (offset * 8)) as u8)
+        });
+        let byte = stored.unwrap_or_else(|| *state.memory.get(&byte_address).unwrap_or(&0));
+        res |= (byte as u128) << (i * 8);
+    }
+    res
+}
+
+/// Makes `stores`, in order. Used by generated code.
+pub fn store_all(state: &mut ConcreteX86MachineState64, stores: &[PendingStore]) {
+    for &(address, value, width) in stores {
+        for i in 0..width / 8 {
+            state.memory.insert(address.wrapping_add(i as u64), (value >> (i * 8)) as u8);
+        }
+    }
+}
+
+/// Native Rust for the instructions of a trace, with a `step` function dispatching on their bytes:
+///
+/// `pub fn step(state: &mut ConcreteX86MachineState64, instr: &[u8]) -> Option<Result<(), Fault>>`
+///
+/// `step` runs the instruction `instr` starts with, if it was added, and gives None otherwise so that the caller
+/// can fall back to the interpreter. Semantics functions are handed decoded operands, so code is generated for
+/// each encoding rather than for each iform, and only for integer semantics: vector registers, floating point
+/// expressions and the x87 stack give [`RustCodegenError::Unsupported`], so those instructions are always left to
+/// the interpreter. The generated code behaves like the concrete evaluator, except that an instruction which faults
+/// leaves the state the way it was, with RIP pointing at it.
+///
+/// The tokens are meant for a build script to write out and `include!`, as the semantics-emulator crate does for
+/// the instructions of a trace, falling back to the interpreter for everything else. They refer to this module
+/// through the path given to [`RustModule::new`], for example `::semantics::semantics2` outside this crate.
+pub struct RustModule<'arena> {
+    arena: Arena<'arena>,
+    builder: SemanticsBuilder<'arena>,
+    runtime: TokenStream,
+    functions: BTreeMap<Vec<u8>, TokenStream>,
+}
+
+impl<'arena> RustModule<'arena> {
+    pub fn new(arena: Arena<'arena>, semantics2_path: TokenStream) -> Self {
+        Self {
+            arena,
+            builder: SemanticsBuilder::new(arena),
+            runtime: quote!(#semantics2_path::rust_codegen),
+            functions: BTreeMap::new(),
+        }
+    }
+
+    /// Decodes the instruction `bytes` start with and adds it. Anything after it is ignored.
+    pub fn add_instruction(&mut self, bytes: &[u8]) -> Result<(), RustCodegenError> {
+        let mut context = EncodeDecodeContext::new();
+        let (instruction, address_width, rest) = X86Instruction::decode_one_with_address_width(bytes, &mut context).map_err(RustCodegenError::Decode)?;
+        let length = bytes.len() - rest.len();
+        let steps = apply_instruction_with_address_width(self.arena, instruction, length, address_width).map_err(RustCodegenError::Semantics)?;
+        self.add_function(&bytes[..length], steps.as_slice())
+    }
+
+    /// Adds `steps` as the semantics of the instruction encoded as `bytes`, which must be the whole of its
+    /// encoding. The module is left alone if they can't be generated.
+    pub fn add_function(&mut self, bytes: &[u8], steps: &[InstructionSemanticsStep<'arena>]) -> Result<(), RustCodegenError> {
+        let mut generator = Generator::new(&self.builder, self.runtime.clone(), stores_count(steps));
+        generator.run(steps, 0)?;
+        let function = generator.function(&function_name(bytes));
+        self.functions.insert(bytes.to_vec(), function);
+        Ok(())
+    }
+
+    /// `step` followed by the function of each instruction added.
+    pub fn tokens(&self) -> TokenStream {
+        let runtime = &self.runtime;
+        let arms = self.functions.keys().map(|bytes| {
+            let name = function_name(bytes);
+            // no encoding is a prefix of another, so the arms can't overlap
+            quote!([#(#bytes,)* ..] => Some(#name(state)),)
+        });
+        let functions = self.functions.values();
+        quote! {
+            pub fn step(state: &mut #runtime::ConcreteX86MachineState64, instr: &[u8]) -> Option<Result<(), #runtime::Fault>> {
+                match instr {
+                    #(#arms)*
+                    _ => None,
+                }
+            }
+
+            #(#functions)*
+        }
+    }
+}
+
+fn function_name(bytes: &[u8]) -> Ident {
+    let hex = bytes.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    format_ident!("instruction_{hex}")
+}
+
+/// The most stores any path through `steps` makes.
+fn stores_count(steps: &[InstructionSemanticsStep]) -> usize {
+    steps.iter()
+        .map(|step| match step {
+            InstructionSemanticsStep::Conditional { true_semantics, false_semantics, .. } => {
+                stores_count(true_semantics).max(stores_count(false_semantics))
+            }
+            InstructionSemanticsStep::Store { .. } => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn mask(width: usize) -> u128 {
+    if width >= 128 {
+        u128::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+fn literal(value: u128) -> Literal {
+    Literal::u128_suffixed(value)
+}
+
+fn shift_amount(amount: usize) -> Literal {
+    Literal::u32_suffixed(amount as u32)
+}
+
+/// Bits `low` up to `high_exclusive` of `value`.
+fn extract(value: &TokenStream, low: usize, high_exclusive: usize) -> TokenStream {
+    let mask = literal(mask(high_exclusive - low));
+    if low == 0 {
+        quote!((#value & #mask))
+    } else {
+        let low = shift_amount(low);
+        quote!(((#value >> #low) & #mask))
+    }
+}
+
+/// `value`, which is `from` bits wide, sign extended to an `i128`.
+fn sign_extend(value: &TokenStream, from: usize) -> TokenStream {
+    if from >= 128 {
+        quote!((#value as i128))
+    } else {
+        let unused = shift_amount(128 - from);
+        quote!((((#value << #unused) as i128) >> #unused))
+    }
+}
+
+fn extend(value: TokenStream, from: usize, to: usize, signed: bool) -> TokenStream {
+    assert!(to >= from);
+    if !signed || to == from {
+        return value;
+    }
+    let extended = sign_extend(&value, from);
+    let mask = literal(mask(to));
+    quote!(((#extended as u128) & #mask))
+}
+
+/// Values of everything written so far, anything missing still has its value from the state. Memory's value is
+/// the number of stores made so far.
+type RustState = BTreeMap<StateComponent, TokenStream>;
+
+struct Generator<'builder, 'arena> {
+    builder: &'builder SemanticsBuilder<'arena>,
+    runtime: TokenStream,
+    /// Reads of the state, which go first so that every use of them can see them.
+    prologue: Vec<TokenStream>,
+    /// Statements of the block being added to.
+    body: Vec<TokenStream>,
+    loaded: BTreeSet<StateComponent>,
+    next_value: usize,
+    /// Length of the array of [`PendingStore`]s.
+    max_stores: usize,
+    /// False once the block being added to has ended by raising a fault, nothing after that is generated.
+    reachable: bool,
+    current: RustState,
+    /// Like [`crate::semantics2::semantic_steps::ConcreteExecution`], the state before each step, for reads to
+    /// see the state as of the step they were emitted before.
+    history: Vec<(usize, RustState)>,
+    /// Values of the expressions which are in scope in the block being added to, by address.
+    generated: HashMap<*const Expression<'arena>, TokenStream>,
+}
+
+impl<'builder, 'arena> Generator<'builder, 'arena> {
+    fn new(builder: &'builder SemanticsBuilder<'arena>, runtime: TokenStream, max_stores: usize) -> Self {
+        Self {
+            builder,
+            runtime,
+            prologue: vec![],
+            body: vec![],
+            loaded: BTreeSet::new(),
+            next_value: 0,
+            max_stores,
+            reachable: true,
+            current: RustState::new(),
+            history: vec![],
+            generated: HashMap::new(),
+        }
+    }
+
+    /// Adds a `let` of `value` to the current block, giving its name.
+    fn bind_as(&mut self, value_type: TokenStream, value: TokenStream) -> TokenStream {
+        let name = format_ident!("v{}", self.next_value);
+        self.next_value += 1;
+        self.body.push(quote!(let #name: #value_type = #value;));
+        quote!(#name)
+    }
+
+    fn bind(&mut self, value: TokenStream) -> TokenStream {
+        self.bind_as(quote!(u128), value)
+    }
+
+    /// `component`'s value on entry.
+    fn load(&mut self, component: StateComponent) -> TokenStream {
+        let name = format_ident!("{}", component.name());
+        if self.loaded.insert(component) {
+            let runtime = &self.runtime;
+            let load = match component {
+                StateComponent::Gpr(reg) => {
+                    let reg = format_ident!("{reg:?}");
+                    quote!(let #name: u128 = state.gpr64(#runtime::Reg64WithRIP::#reg) as u128;)
+                }
+                StateComponent::Flag(flag) => {
+                    let flag = format_ident!("{flag:?}");
+                    quote!(let #name: u128 = state.get_flag(#runtime::Flag::#flag) as u128;)
+                }
+                StateComponent::Mxcsr => {
+                    quote!(let #name: u128 = state.get_mxcsr() as u128;)
+                }
+                StateComponent::SegmentBase(base) => {
+                    let base = format_ident!("{base:?}");
+                    quote!(let #name: u128 = state.segment_base(#runtime::RegSegmentBase::#base) as u128;)
+                }
+                StateComponent::Segment(segment) => {
+                    let segment = format_ident!("{segment:?}");
+                    quote!(let #name: u128 = state.get_segment(#runtime::RegSegment::#segment) as u128;)
+                }
+                StateComponent::Memory => {
+                    quote!(let #name: usize = 0;)
+                }
+                StateComponent::Zmm(_) => panic!("ZMM registers don't fit in a u128"),
+            };
+            self.prologue.push(load);
+        }
+        quote!(#name)
+    }
+
+    fn read_from(&mut self, state: Option<&RustState>, component: StateComponent) -> TokenStream {
+        match state.unwrap_or(&self.current).get(&component) {
+            Some(value) => value.clone(),
+            None => self.load(component),
+        }
+    }
+
+    fn read(&mut self, at_index: usize, component: StateComponent) -> TokenStream {
+        let state = self.history.iter()
+            .find(|(index, _)| *index >= at_index)
+            .map(|(_, state)| state.clone());
+        self.read_from(state.as_ref(), component)
+    }
+
+    fn read_current(&mut self, component: StateComponent) -> TokenStream {
+        self.read_from(None, component)
+    }
+
+    fn write(&mut self, component: StateComponent, value: TokenStream) {
+        self.current.insert(component, value);
+    }
+
+    /// Ends the current block by raising `fault`.
+    fn fault(&mut self, fault: Fault) {
+        let runtime = &self.runtime;
+        let fault = format_ident!("{fault:?}");
+        self.body.push(quote!(return Err(#runtime::Fault::#fault);));
+        self.reachable = false;
+    }
+
+    /// The function running everything generated so far, which writes the state back if it gets to the end.
+    fn function(mut self, name: &Ident) -> TokenStream {
+        let runtime = self.runtime.clone();
+        if self.reachable {
+            for (component, value) in std::mem::take(&mut self.current) {
+                if value.to_string() == component.name() {
+                    continue;
+                }
+                self.body.push(match component {
+                    StateComponent::Gpr(reg) => {
+                        let reg = format_ident!("{reg:?}");
+                        quote!(*state.gpr64_mut(#runtime::Reg64WithRIP::#reg) = #value as u64;)
+                    }
+                    StateComponent::Flag(flag) => {
+                        let flag = format_ident!("{flag:?}");
+                        quote!(state.set_flag(#runtime::Flag::#flag, #value != 0);)
+                    }
+                    StateComponent::Mxcsr => {
+                        quote!(state.set_mxcsr(#value as u32);)
+                    }
+                    StateComponent::SegmentBase(base) => {
+                        let base = format_ident!("{base:?}");
+                        quote!(state.set_segment_base(#runtime::RegSegmentBase::#base, #value as u64);)
+                    }
+                    StateComponent::Segment(segment) => {
+                        let segment = format_ident!("{segment:?}");
+                        quote!(state.set_segment(#runtime::RegSegment::#segment, #value as u16);)
+                    }
+                    StateComponent::Memory => {
+                        quote!(#runtime::store_all(state, &stores[..#value]);)
+                    }
+                    StateComponent::Zmm(_) => {
+                        panic!("{component:?} is never written")
+                    }
+                });
+            }
+            self.body.push(quote!(Ok(())));
+        }
+        let max_stores = self.max_stores;
+        let stores = (max_stores > 0).then(|| quote!(let mut stores: [#runtime::PendingStore; #max_stores] = [(0, 0, 0); #max_stores];));
+        let prologue = &self.prologue;
+        let body = &self.body;
+        quote! {
+            #[allow(unused_variables, unused_mut, unused_parens, unreachable_code, clippy::all)]
+            fn #name(state: &mut #runtime::ConcreteX86MachineState64) -> Result<(), #runtime::Fault> {
+                #stores
+                #(#prologue)*
+                #(#body)*
+            }
+        }
+    }
+
+    fn expression(&mut self, expr: &'arena Expression<'arena>) -> Result<TokenStream, RustCodegenError> {
+        let key = expr as *const Expression<'arena>;
+        if let Some(value) = self.generated.get(&key) {
+            return Ok(value.clone());
+        }
+        if expr.width() > 128 {
+            return Err(RustCodegenError::Unsupported("values wider than 128 bits"));
+        }
+        let value = self.expression_uncached(expr)?;
+        self.generated.insert(key, value.clone());
+        Ok(value)
+    }
+
+    fn expression_uncached(&mut self, expr: &'arena Expression<'arena>) -> Result<TokenStream, RustCodegenError> {
+        let width = expr.width();
+        let value = match *expr {
+            Expression::GetReg { reg, at_index } => {
+                let (parent, low, high) = ConcreteX86MachineState64::gpr_location(reg);
+                let full = self.read(at_index, StateComponent::Gpr(parent));
+                extract(&full, low, high)
+            }
+            Expression::GetFlag { flag, at_index } => {
+                return Ok(self.read(at_index, StateComponent::Flag(flag)));
+            }
+            Expression::Constant { value } => {
+                let value = literal(value.to_u128());
+                return Ok(quote!(#value));
+            }
+            Expression::BitWise { op, left, right } => {
+                // both sides are already zero extended to the width of the result
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                match op {
+                    BitWiseOp::And => quote!((#left & #right)),
+                    BitWiseOp::Or => quote!((#left | #right)),
+                    BitWiseOp::Xor => quote!((#left ^ #right)),
+                }
+            }
+            Expression::IntCompare { op, signedness, left, right } => {
+                let (left_width, right_width) = (left.width(), right.width());
+                let mut left = self.expression(left)?;
+                let mut right = self.expression(right)?;
+                if matches!(signedness, Signedness::Signed) {
+                    left = sign_extend(&left, left_width);
+                    right = sign_extend(&right, right_width);
+                }
+                let compared = match op {
+                    ComparisonOp::Less => quote!(#left < #right),
+                    ComparisonOp::LessOrEqual => quote!(#left <= #right),
+                    ComparisonOp::Equal => quote!(#left == #right),
+                    ComparisonOp::GreaterOrEqual => quote!(#left >= #right),
+                    ComparisonOp::Greater => quote!(#left > #right),
+                };
+                quote!(((#compared) as u128))
+            }
+            Expression::IntArithmetic { op, signedness, left, right } => {
+                let signed = matches!(signedness, Signedness::Signed);
+                let (left_width, right_width) = (left.width(), right.width());
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                let left = extend(left, left_width, width, signed);
+                let right = extend(right, right_width, width, signed);
+                let mask = literal(mask(width));
+                if matches!(op, ArithmeticOp::Div | ArithmeticOp::Rem) {
+                    let runtime = &self.runtime;
+                    self.body.push(quote!(if #right == 0 { return Err(#runtime::Fault::DivideError); }));
+                }
+                match (op, signedness) {
+                    (ArithmeticOp::Add, _) => quote!((#left.wrapping_add(#right) & #mask)),
+                    (ArithmeticOp::Sub, _) => quote!((#left.wrapping_sub(#right) & #mask)),
+                    (ArithmeticOp::Mul, _) => quote!((#left.wrapping_mul(#right) & #mask)),
+                    (ArithmeticOp::Div, Signedness::Unsigned) => quote!((#left / #right)),
+                    (ArithmeticOp::Rem, Signedness::Unsigned) => quote!((#left % #right)),
+                    (ArithmeticOp::Div | ArithmeticOp::Rem, Signedness::Signed) => {
+                        let left = sign_extend(&left, width);
+                        let right = sign_extend(&right, width);
+                        // wraps dividing the minimum by -1, like the concrete evaluator
+                        match op {
+                            ArithmeticOp::Div => quote!(((#left.wrapping_div(#right) as u128) & #mask)),
+                            _ => quote!(((#left.wrapping_rem(#right) as u128) & #mask)),
+                        }
+                    }
+                }
+            }
+            Expression::Extract { value, low, high } => {
+                let value = self.expression(value)?;
+                extract(&value, low, high)
+            }
+            Expression::Concat { left, right } => {
+                let right_width = right.width();
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                if right_width >= 128 {
+                    return Ok(right);
+                }
+                let right_width = shift_amount(right_width);
+                quote!(((#left << #right_width) | #right))
+            }
+            Expression::Conditional { condition, true_value, false_value } => {
+                let condition = self.expression(condition)?;
+                // only the value picked is evaluated, so that a division in the other one can't fault
+                let outer = std::mem::take(&mut self.body);
+                let generated = self.generated.clone();
+                let true_value = self.expression(true_value)?;
+                let true_body = std::mem::take(&mut self.body);
+                self.generated = generated.clone();
+                let false_value = self.expression(false_value)?;
+                let false_body = std::mem::replace(&mut self.body, outer);
+                self.generated = generated;
+                quote!((if #condition != 0 { #(#true_body)* #true_value } else { #(#false_body)* #false_value }))
+            }
+            Expression::ZeroExtend { value, .. } => {
+                return self.expression(value);
+            }
+            Expression::SignExtend { value, len } => {
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                extend(value, value_width, len, true)
+            }
+            Expression::Shift { op, value, amount } => {
+                let value = self.expression(value)?;
+                let amount = self.expression(amount)?;
+                let width_literal = literal(width as u128);
+                let mask = literal(mask(width));
+                // shifting by the width or more shifts every bit out, where Rust's shifts would overflow
+                match op {
+                    ShiftOp::Shl => quote!((if #amount < #width_literal { (#value << (#amount as u32)) & #mask } else { 0 })),
+                    ShiftOp::LShr => quote!((if #amount < #width_literal { #value >> (#amount as u32) } else { 0 })),
+                    ShiftOp::AShr => {
+                        let value = sign_extend(&value, width);
+                        quote!((((#value >> (#amount.min(127) as u32)) as u128) & #mask))
+                    }
+                }
+            }
+            Expression::BitCount { op, value } => {
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                match op {
+                    BitCountOp::PopCount => quote!((#value.count_ones() as u128)),
+                    BitCountOp::LeadingZeros => {
+                        let unused = shift_amount(128 - value_width);
+                        quote!(((#value.leading_zeros() - #unused) as u128))
+                    }
+                    BitCountOp::TrailingZeros => {
+                        let value_width = shift_amount(value_width);
+                        quote!((#value.trailing_zeros().min(#value_width) as u128))
+                    }
+                }
+            }
+            Expression::LowerBits { value, len } => {
+                let value = self.expression(value)?;
+                extract(&value, 0, len)
+            }
+            Expression::UpperBits { value, len } => {
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                extract(&value, value_width - len, value_width)
+            }
+            Expression::ChangeRange { value, range_start_inclusive, range_end_exclusive, new_value } => {
+                let value = self.expression(value)?;
+                let new_value = self.expression(new_value)?;
+                if range_end_exclusive - range_start_inclusive == width {
+                    return Ok(new_value);
+                }
+                let kept = literal(mask(width) & !(mask(range_end_exclusive - range_start_inclusive) << range_start_inclusive));
+                let start = shift_amount(range_start_inclusive);
+                quote!(((#value & #kept) | (#new_value << #start)))
+            }
+            Expression::GetMxcsr { at_index } => {
+                return Ok(self.read(at_index, StateComponent::Mxcsr));
+            }
+            Expression::Load { address, width, at_index } => {
+                let address = self.expression(address)?;
+                let stores = if self.max_stores == 0 {
+                    quote!(&[])
+                } else {
+                    let stores_made = self.read(at_index, StateComponent::Memory);
+                    quote!(&stores[..#stores_made])
+                };
+                let runtime = &self.runtime;
+                quote!(#runtime::load(state, #stores, #address as u64, #width))
+            }
+            Expression::SegmentBase { base, at_index } => {
+                return Ok(self.read(at_index, StateComponent::SegmentBase(base)));
+            }
+            Expression::GetSegment { segment, at_index } => {
+                return Ok(self.read(at_index, StateComponent::Segment(segment)));
+            }
+            Expression::GetVectorReg { .. } => {
+                return Err(RustCodegenError::Unsupported("values wider than 128 bits"));
+            }
+            Expression::FloatArithmetic { .. } |
+            Expression::FloatSqrt { .. } |
+            Expression::FloatFma { .. } |
+            Expression::FloatCompare { .. } |
+            Expression::FloatConvert { .. } |
+            Expression::IntToFloat { .. } |
+            Expression::FloatToInt { .. } |
+            Expression::FloatExceptionFlags { .. } => {
+                return Err(RustCodegenError::Unsupported("floating point expressions"));
+            }
+            Expression::GetX87Register { .. } |
+            Expression::X87RegisterEmpty { .. } |
+            Expression::GetX87Control { .. } |
+            Expression::GetX87Status { .. } |
+            Expression::X87Arithmetic { .. } |
+            Expression::X87Compare { .. } |
+            Expression::X87FromFloat { .. } |
+            Expression::X87ToFloat { .. } |
+            Expression::X87FromInt { .. } |
+            Expression::X87ToInt { .. } |
+            Expression::X87RoundedUp { .. } => {
+                return Err(RustCodegenError::Unsupported("the x87 stack"));
+            }
+        };
+        Ok(self.bind(value))
+    }
+
+    /// Generates `steps`, the first of which is numbered `first_index`.
+    fn run(&mut self, steps: &[InstructionSemanticsStep<'arena>], first_index: usize) -> Result<(), RustCodegenError> {
+        let mut index = first_index;
+        for step in steps {
+            if !self.reachable {
+                break;
+            }
+            match step {
+                InstructionSemanticsStep::Conditional { condition, true_semantics, false_semantics } => {
+                    let condition = self.expression(*condition)?;
+                    let before = self.current.clone();
+                    let history_len = self.history.len();
+                    let generated = self.generated.clone();
+                    let outer = std::mem::take(&mut self.body);
+
+                    self.run(true_semantics, index)?;
+                    let true_body = std::mem::take(&mut self.body);
+                    let after_true = (self.reachable, std::mem::replace(&mut self.current, before.clone()));
+                    // the concrete evaluator only ever runs one of the branches
+                    self.history.truncate(history_len);
+                    self.generated = generated.clone();
+                    self.reachable = true;
+
+                    self.run(false_semantics, index + steps_count(true_semantics))?;
+                    let false_body = std::mem::replace(&mut self.body, outer);
+                    let after_false = (self.reachable, std::mem::replace(&mut self.current, before.clone()));
+                    self.generated = generated;
+                    self.merge(condition, before, (true_body, after_true), (false_body, after_false));
+                }
+                // nothing can read the state from before the advance, so there's no need to keep it
+                InstructionSemanticsStep::AdvanceRip { .. } => {
+                    self.step(step, index)?;
+                }
+                step => {
+                    self.history.push((index, self.current.clone()));
+                    self.step(step, index)?;
+                }
+            }
+            index += step.step_count();
+        }
+        Ok(())
+    }
+
+    /// Adds an `if` on `condition` with the statements of both branches of a conditional, each given along with
+    /// whether it carries on afterwards and the state it ends with. The `if` gives the values of everything either
+    /// of the branches which carry on has written.
+    fn merge(&mut self, condition: TokenStream, before: RustState, after_true: (Vec<TokenStream>, (bool, RustState)), after_false: (Vec<TokenStream>, (bool, RustState))) {
+        let (true_body, (true_reachable, true_state)) = after_true;
+        let (false_body, (false_reachable, false_state)) = after_false;
+        let mut written = BTreeSet::new();
+        for (reachable, state) in [(true_reachable, &true_state), (false_reachable, &false_state)] {
+            if reachable {
+                written.extend(state.iter()
+                    .filter(|(component, value)| before.get(*component).map(|before| before.to_string()) != Some(value.to_string()))
+                    .map(|(component, _)| *component));
+            }
+        }
+        let mut branch = |reachable: bool, body: Vec<TokenStream>, state: RustState| {
+            if reachable {
+                let values = written.iter().map(|component| self.read_from(Some(&state), *component)).collect::<Vec<_>>();
+                quote!({ #(#body)* (#(#values,)*) })
+            } else {
+                quote!({ #(#body)* })
+            }
+        };
+        let true_branch = branch(true_reachable, true_body, true_state);
+        let false_branch = branch(false_reachable, false_body, false_state);
+        self.current = before;
+        self.reachable = true_reachable || false_reachable;
+        if written.is_empty() {
+            self.body.push(quote!(if #condition != 0 #true_branch else #false_branch));
+            return;
+        }
+        let names = written.iter().map(|_| {
+            let name = format_ident!("v{}", self.next_value);
+            self.next_value += 1;
+            name
+        }).collect::<Vec<_>>();
+        self.body.push(quote!(let (#(#names,)*) = if #condition != 0 #true_branch else #false_branch;));
+        for (component, name) in written.into_iter().zip(names) {
+            self.write(component, quote!(#name));
+        }
+    }
+
+    fn step(&mut self, step: &InstructionSemanticsStep<'arena>, index: usize) -> Result<(), RustCodegenError> {
+        match step {
+            InstructionSemanticsStep::Conditional { .. } => {
+                unreachable!()
+            }
+            InstructionSemanticsStep::SetRegister { zero_upper, register, value } => {
+                let value = self.expression(*value)?;
+                let (parent, low, high) = ConcreteX86MachineState64::gpr_location(*register);
+                let component = StateComponent::Gpr(parent);
+                let start = shift_amount(low);
+                let value = match zero_upper {
+                    ZeroUpper::ZeroUpper => self.bind(quote!((#value << #start))),
+                    ZeroUpper::NoZeroUpper if high - low == 64 => value,
+                    ZeroUpper::NoZeroUpper => {
+                        let old = self.read_current(component);
+                        let kept = literal(mask(64) & !(mask(high - low) << low));
+                        self.bind(quote!(((#old & #kept) | (#value << #start))))
+                    }
+                };
+                self.write(component, value);
+            }
+            InstructionSemanticsStep::SetVectorRegister { .. } => {
+                return Err(RustCodegenError::Unsupported("values wider than 128 bits"));
+            }
+            InstructionSemanticsStep::SetFlag { flag, value } => {
+                let value = self.expression(*value)?;
+                self.write(StateComponent::Flag(*flag), value);
+            }
+            InstructionSemanticsStep::InstructionSyncPoint { .. } => {}
+            InstructionSemanticsStep::UndefinedException => self.fault(Fault::UndefinedOpcode),
+            InstructionSemanticsStep::DivideError => self.fault(Fault::DivideError),
+            InstructionSemanticsStep::GeneralProtection => self.fault(Fault::GeneralProtection),
+            InstructionSemanticsStep::FloatingPointError => self.fault(Fault::FloatingPointError),
+            InstructionSemanticsStep::CalculateFlags { flag_tag, left, right } => {
+                let carry = self.builder.a(Expression::GetFlag { flag: Flag::CF, at_index: index });
+                for (flag, value) in self.builder.flag_values(flag_tag, *left, *right, carry) {
+                    let value = self.expression(value)?;
+                    self.write(StateComponent::Flag(flag), value);
+                }
+            }
+            InstructionSemanticsStep::Store { address, value } => {
+                let width = value.width();
+                let address = self.expression(*address)?;
+                let value = self.expression(*value)?;
+                let stores_made = self.read_current(StateComponent::Memory);
+                self.body.push(quote!(stores[#stores_made] = (#address as u64, #value, #width);));
+                let stores_made = self.bind_as(quote!(usize), quote!(#stores_made + 1));
+                self.write(StateComponent::Memory, stores_made);
+            }
+            InstructionSemanticsStep::RaiseFloatExceptions { exceptions } => {
+                let exceptions = self.expression(*exceptions)?;
+                let mxcsr = self.read_current(StateComponent::Mxcsr);
+                let runtime = &self.runtime;
+                self.body.push(quote! {
+                    if (#exceptions & !(#mxcsr >> 7u32) & 63) != 0 {
+                        return Err(#runtime::Fault::SimdFloatingPointException);
+                    }
+                });
+                let mxcsr = self.bind(quote!((#mxcsr | #exceptions)));
+                self.write(StateComponent::Mxcsr, mxcsr);
+            }
+            InstructionSemanticsStep::SetMxcsr { value } => {
+                let value = self.expression(*value)?;
+                self.write(StateComponent::Mxcsr, value);
+            }
+            InstructionSemanticsStep::SetSegment { segment, value } => {
+                let value = self.expression(*value)?;
+                self.write(StateComponent::Segment(*segment), value);
+                if let Some(base) = loaded_segment_base(*segment) {
+                    self.write(StateComponent::SegmentBase(base), quote!(0u128));
+                }
+            }
+            InstructionSemanticsStep::SetX87Register { .. } |
+            InstructionSemanticsStep::X87Push |
+            InstructionSemanticsStep::X87Pop |
+            InstructionSemanticsStep::SetX87Control { .. } |
+            InstructionSemanticsStep::SetX87Status { .. } => {
+                return Err(RustCodegenError::Unsupported("the x87 stack"));
+            }
+            InstructionSemanticsStep::AdvanceRip { length } => {
+                let rip = self.read_current(StateComponent::Gpr(Reg64WithRIP::RIP));
+                let length = literal(*length as u128);
+                let mask = literal(mask(64));
+                let rip = self.bind(quote!(((#rip + #length) & #mask)));
+                self.write(StateComponent::Gpr(Reg64WithRIP::RIP), rip);
+            }
+        }
+        Ok(())
+    }
+}
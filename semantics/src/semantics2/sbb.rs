@@ -0,0 +1,140 @@
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8};
+use xed_enum::SBB;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+pub fn sbb_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := (DEST – (SRC + CF));
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let src = s.sext_to(readable2.read(&s), width);
+    let borrow = s.zext_to(s.cf(), width);
+    let res = s.sub(s.sub(dest, src), borrow);
+    writeable1.write(&mut s, res);
+    // the flag calculation reads the borrow from CF, which the write above leaves alone
+    s.flag_calculate(FlagTag::Sbb, dest, src);
+    s.finalize()
+}
+
+pub fn apply_iform_sbb(arena: Arena, instr: SBB) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SBB::SBB_MEMB_IMMB_80R3 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SBB::SBB_GPR8_IMMB_80R3 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SBB::SBB_MEMV_IMMZ_16 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SBB::SBB_MEMV_IMMZ_32 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SBB::SBB_MEMV_IMMZ_64 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SBB::SBB_GPRV_IMMZ_16 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SBB::SBB_GPRV_IMMZ_32 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SBB::SBB_GPRV_IMMZ_64 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SBB::SBB_MEMB_IMMB_82R3 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SBB::SBB_GPR8_IMMB_82R3 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SBB::SBB_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SBB::SBB_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SBB::SBB_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SBB::SBB_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SBB::SBB_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SBB::SBB_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SBB::SBB_MEMB_GPR8 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SBB::SBB_GPR8_GPR8_18 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SBB::SBB_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SBB::SBB_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SBB::SBB_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SBB::SBB_GPRV_GPRV_19_16 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SBB::SBB_GPRV_GPRV_19_32 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SBB::SBB_GPRV_GPRV_19_64 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SBB::SBB_GPR8_GPR8_1A { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SBB::SBB_GPR8_MEMB { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SBB::SBB_GPRV_GPRV_1B_16 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SBB::SBB_GPRV_GPRV_1B_32 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SBB::SBB_GPRV_GPRV_1B_64 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SBB::SBB_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SBB::SBB_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SBB::SBB_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            sbb_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SBB::SBB_AL_IMMB { operand_0 } => {
+            sbb_generic(arena, Reg8::AL, Reg8::AL, operand_0, 8)
+        }
+        SBB::SBB_ORAX_IMMZ_16 { operand_0 } => {
+            sbb_generic(arena, Reg16WithRIP::AX, Reg16WithRIP::AX, operand_0, 16)
+        }
+        SBB::SBB_ORAX_IMMZ_32 { operand_0 } => {
+            sbb_generic(arena, Reg32WithRIP::EAX, Reg32WithRIP::EAX, operand_0, 32)
+        }
+        SBB::SBB_ORAX_IMMZ_64 { operand_0 } => {
+            sbb_generic(arena, Reg64WithRIP::RAX, Reg64WithRIP::RAX, operand_0, 64)
+        }
+    }
+}
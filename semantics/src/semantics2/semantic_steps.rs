@@ -1,7 +1,31 @@
 use wrapper_common::memory_operand::GeneralReg;
+use wrapper_common::registers::{Reg64WithRIP, RegSegment, RegSegmentBase, RegZMM};
 use crate::semantics2::expression::{Expression, Flag};
-use crate::semantics2::state::ConcreteX86MachineState64;
-use crate::x86_machine::semantics_builder::FlagTag;
+use crate::semantics2::float::{FloatExceptions, Mxcsr};
+use crate::semantics2::state::{x87_physical, ConcreteX86MachineState64};
+use crate::semantics2::value::Value;
+
+pub enum FlagTag {
+    Add,
+    /// `left + right + CF`, with the carry in read from the state the flags are calculated in.
+    Adc,
+    Sub,
+    /// `left - right - CF`, with the carry in read from the state the flags are calculated in.
+    Sbb,
+    /// Like `Add` and `Sub`, but CF is left alone.
+    Inc,
+    Dec,
+    Mul,
+    /// Signed, CF and OF are set when the product doesn't fit in the operand size.
+    IMul,
+    And,
+    Or,
+    Xor,
+    /// `right` is the masked shift count, which must not be zero.
+    Shl,
+    Shr,
+    Sar,
+}
 
 pub enum ZeroUpper {
     ZeroUpper,
@@ -19,6 +43,11 @@ pub enum InstructionSemanticsStep<'arena> {
         register: GeneralReg,
         value: &'arena Expression<'arena>,
     },
+    /// Writes all 512 bits, narrower writes are merged or zero extended before getting here.
+    SetVectorRegister {
+        register: RegZMM,
+        value: &'arena Expression<'arena>,
+    },
     SetFlag {
         flag: Flag,
         value: &'arena Expression<'arena>,
@@ -27,31 +56,554 @@ pub enum InstructionSemanticsStep<'arena> {
         interruptable: bool,
     },
     UndefinedException,
+    /// #DE
+    DivideError,
     CalculateFlags { flag_tag: FlagTag, left: &'arena Expression<'arena>, right: &'arena Expression<'arena> },
+    /// Little endian store of all of `value`, which must be a whole number of bytes.
+    Store {
+        address: &'arena Expression<'arena>,
+        value: &'arena Expression<'arena>,
+    },
+    /// Sets the MXCSR flags of `exceptions`, six bits in the order of the flags, or raises #XM if any of them is
+    /// unmasked. Comes before the instruction writes anything, since an unmasked exception leaves the destination
+    /// alone.
+    RaiseFloatExceptions {
+        exceptions: &'arena Expression<'arena>,
+    },
+    /// All 32 bits, reserved bits are checked before getting here.
+    SetMxcsr {
+        value: &'arena Expression<'arena>,
+    },
+    /// #GP(0)
+    GeneralProtection,
+    /// Writes ST(`st`), relative to TOP as it is when this step runs, and sets its tag from `value`.
+    SetX87Register {
+        st: usize,
+        value: &'arena Expression<'arena>,
+    },
+    /// Decrements TOP, after which ST(0) is the register which was ST(7).
+    X87Push,
+    /// Marks ST(0) empty and increments TOP.
+    X87Pop,
+    SetX87Control {
+        value: &'arena Expression<'arena>,
+    },
+    /// Writes the whole status word, TOP included, so it comes before any push or pop in the same instruction.
+    SetX87Status {
+        value: &'arena Expression<'arena>,
+    },
+    /// #MF, raised by a waiting x87 instruction when an earlier one left an unmasked exception pending.
+    FloatingPointError,
+    /// Loads the 16 bit selector `value` into `segment`. Descriptors aren't modelled, so nothing is checked, and
+    /// the base of FS or GS, see [`loaded_segment_base`], is cleared the way loading a null selector clears it.
+    SetSegment {
+        segment: RegSegment,
+        value: &'arena Expression<'arena>,
+    },
+    /// RIP += `length`, the first step of every instruction. It takes up no index, so every read the instruction
+    /// makes sees RIP as the address of the next instruction, which is what relative branches and RIP-relative
+    /// memory operands are relative to.
+    AdvanceRip {
+        length: usize,
+    },
 }
 
-fn apply_instructions_to_concrete<'arena>(concrete: ConcreteX86MachineState64, instructions: &[InstructionSemanticsStep<'arena>]) -> ConcreteX86MachineState64 {
-    for instruction in instructions.iter() {
-        match instruction {
-            InstructionSemanticsStep::Conditional { condition, true_semantics, false_semantics } => {
-                condition.apply_concrete(&concrete);
+impl InstructionSemanticsStep<'_> {
+    /// Number of indices this step takes up, which is one plus the steps nested in it. Matches the numbering
+    /// `SemanticsBuilder` uses for `at_index`.
+    pub fn step_count(&self) -> usize {
+        match self {
+            InstructionSemanticsStep::Conditional { true_semantics, false_semantics, .. } => {
+                1 + steps_count(true_semantics) + steps_count(false_semantics)
             }
-            InstructionSemanticsStep::SetRegister { zero_upper, register, value } => {
+            InstructionSemanticsStep::AdvanceRip { .. } => 0,
+            _ => 1,
+        }
+    }
+}
 
-            }
-            InstructionSemanticsStep::SetFlag { flag, value } => {
+impl<'arena> InstructionSemanticsStep<'arena> {
+    /// This step with every expression in it, those of nested steps included, replaced by `f` of it.
+    pub fn map_expressions(self, f: &mut impl FnMut(&'arena Expression<'arena>) -> &'arena Expression<'arena>) -> Self {
+        match self {
+            InstructionSemanticsStep::Conditional { condition, true_semantics, false_semantics } => InstructionSemanticsStep::Conditional {
+                condition: f(condition),
+                true_semantics: true_semantics.into_iter().map(|step| step.map_expressions(f)).collect(),
+                false_semantics: false_semantics.into_iter().map(|step| step.map_expressions(f)).collect(),
+            },
+            InstructionSemanticsStep::SetRegister { zero_upper, register, value } => InstructionSemanticsStep::SetRegister { zero_upper, register, value: f(value) },
+            InstructionSemanticsStep::SetVectorRegister { register, value } => InstructionSemanticsStep::SetVectorRegister { register, value: f(value) },
+            InstructionSemanticsStep::SetFlag { flag, value } => InstructionSemanticsStep::SetFlag { flag, value: f(value) },
+            InstructionSemanticsStep::CalculateFlags { flag_tag, left, right } => InstructionSemanticsStep::CalculateFlags { flag_tag, left: f(left), right: f(right) },
+            InstructionSemanticsStep::Store { address, value } => InstructionSemanticsStep::Store { address: f(address), value: f(value) },
+            InstructionSemanticsStep::RaiseFloatExceptions { exceptions } => InstructionSemanticsStep::RaiseFloatExceptions { exceptions: f(exceptions) },
+            InstructionSemanticsStep::SetMxcsr { value } => InstructionSemanticsStep::SetMxcsr { value: f(value) },
+            InstructionSemanticsStep::SetX87Register { st, value } => InstructionSemanticsStep::SetX87Register { st, value: f(value) },
+            InstructionSemanticsStep::SetX87Control { value } => InstructionSemanticsStep::SetX87Control { value: f(value) },
+            InstructionSemanticsStep::SetX87Status { value } => InstructionSemanticsStep::SetX87Status { value: f(value) },
+            InstructionSemanticsStep::SetSegment { segment, value } => InstructionSemanticsStep::SetSegment { segment, value: f(value) },
+            step @ (InstructionSemanticsStep::InstructionSyncPoint { .. } |
+            InstructionSemanticsStep::UndefinedException |
+            InstructionSemanticsStep::DivideError |
+            InstructionSemanticsStep::GeneralProtection |
+            InstructionSemanticsStep::X87Push |
+            InstructionSemanticsStep::X87Pop |
+            InstructionSemanticsStep::FloatingPointError |
+            InstructionSemanticsStep::AdvanceRip { .. }) => step,
+        }
+    }
+}
+
+pub(crate) fn steps_count(steps: &[InstructionSemanticsStep]) -> usize {
+    steps.iter().map(|step| step.step_count()).sum()
+}
 
+/// The base a load of `segment`'s selector replaces. FS and GS are the only segments with a base in 64 bit mode,
+/// and the bases of the others aren't modelled.
+pub fn loaded_segment_base(segment: RegSegment) -> Option<RegSegmentBase> {
+    match segment {
+        RegSegment::FS => Some(RegSegmentBase::FSBase),
+        RegSegment::GS => Some(RegSegmentBase::GSBase),
+        RegSegment::CS | RegSegment::DS | RegSegment::SS | RegSegment::ES => None,
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Fault {
+    /// #UD
+    UndefinedOpcode,
+    /// #DE
+    DivideError,
+    /// #XM
+    SimdFloatingPointException,
+    /// #GP
+    GeneralProtection,
+    /// #MF
+    FloatingPointError,
+}
+
+/// The old value of something a step wrote.
+enum Undo {
+    Gpr(Reg64WithRIP, u64),
+    Flag(Flag, bool),
+    Zmm(RegZMM, [u64; 8]),
+    Mxcsr(u32),
+    /// The x87 stack moves as a whole on pushes and pops, so its registers, status and tag words are kept together.
+    X87 { registers: [u128; 8], status: u16, tag: u16 },
+    X87Control(u16),
+    Segment(RegSegment, u16),
+    SegmentBase(RegSegmentBase, u64),
+    Memory(u64, Option<u8>),
+}
+
+/// The state while concretely executing steps, along with an undo log of what each step which has run so far
+/// overwrote, so that register and flag reads can see the state as of the step they were emitted before without
+/// keeping a copy of the state per step.
+pub struct ConcreteExecution {
+    current: ConcreteX86MachineState64,
+    /// In the order they were written, tagged with the number of the step which wrote them.
+    undo: Vec<(usize, Undo)>,
+    /// The number of the step being run.
+    index: usize,
+}
+
+impl ConcreteExecution {
+    pub fn new(state: ConcreteX86MachineState64) -> Self {
+        Self {
+            current: state,
+            undo: vec![],
+            index: 0,
+        }
+    }
+
+    /// State just before the step numbered `at_index`, or the current state if no step from `at_index` on has
+    /// run yet.
+    pub fn state_at(&self, at_index: usize) -> StateAt<'_> {
+        StateAt { execution: self, at_index }
+    }
+
+    pub fn current(&self) -> &ConcreteX86MachineState64 {
+        &self.current
+    }
+
+    /// Runs `steps`, the first of which is numbered `first_index`.
+    fn run(&mut self, steps: &[InstructionSemanticsStep], first_index: usize) -> Result<(), Fault> {
+        let mut index = first_index;
+        for step in steps {
+            match step {
+                InstructionSemanticsStep::Conditional { condition, true_semantics, false_semantics } => {
+                    if condition.apply_concrete(self)?.is_true() {
+                        self.run(true_semantics, index)?;
+                    } else {
+                        self.run(false_semantics, index + steps_count(true_semantics))?;
+                    }
+                }
+                step => {
+                    self.index = index;
+                    self.apply_step(step)?;
+                }
             }
-            InstructionSemanticsStep::InstructionSyncPoint { interruptable } => {
+            index += step.step_count();
+        }
+        Ok(())
+    }
 
+    fn apply_step(&mut self, step: &InstructionSemanticsStep) -> Result<(), Fault> {
+        match step {
+            InstructionSemanticsStep::Conditional { .. } => {
+                unreachable!()
+            }
+            InstructionSemanticsStep::SetRegister { zero_upper, register, value } => {
+                let value = value.apply_concrete(self)?;
+                self.set_reg(*register, &value, zero_upper);
             }
+            InstructionSemanticsStep::SetVectorRegister { register, value } => {
+                let value = value.apply_concrete(self)?;
+                self.log(Undo::Zmm(*register, self.current.zmms[*register as usize]));
+                self.current.set_zmm(*register, &value);
+            }
+            InstructionSemanticsStep::SetFlag { flag, value } => {
+                let value = value.apply_concrete(self)?;
+                self.set_flag(*flag, value.is_true());
+            }
+            InstructionSemanticsStep::InstructionSyncPoint { .. } => {}
             InstructionSemanticsStep::UndefinedException => {
-
+                return Err(Fault::UndefinedOpcode);
+            }
+            InstructionSemanticsStep::DivideError => {
+                return Err(Fault::DivideError);
             }
             InstructionSemanticsStep::CalculateFlags { flag_tag, left, right } => {
+                let left = left.apply_concrete(self)?;
+                let right = right.apply_concrete(self)?;
+                calculate_flags(self, flag_tag, &left, &right);
+            }
+            InstructionSemanticsStep::Store { address, value } => {
+                let address = address.apply_concrete(self)?.to_u64();
+                let value = value.apply_concrete(self)?;
+                for i in 0..value.width() / 8 {
+                    let byte_address = address.wrapping_add(i as u64);
+                    self.log(Undo::Memory(byte_address, self.current.memory.get(&byte_address).copied()));
+                }
+                self.current.write_memory(address, &value);
+            }
+            InstructionSemanticsStep::RaiseFloatExceptions { exceptions } => {
+                let exceptions = FloatExceptions::from_bits(exceptions.apply_concrete(self)?.to_u64() as u8);
+                let mxcsr = Mxcsr(self.current.mxcsr);
+                if exceptions.bits() & !mxcsr.exception_masks().bits() != 0 {
+                    return Err(Fault::SimdFloatingPointException);
+                }
+                self.log(Undo::Mxcsr(self.current.mxcsr));
+                self.current.mxcsr |= exceptions.bits() as u32;
+            }
+            InstructionSemanticsStep::SetMxcsr { value } => {
+                let value = value.apply_concrete(self)?.to_u64() as u32;
+                self.log(Undo::Mxcsr(self.current.mxcsr));
+                self.current.mxcsr = value;
+            }
+            InstructionSemanticsStep::GeneralProtection => {
+                return Err(Fault::GeneralProtection);
+            }
+            InstructionSemanticsStep::SetX87Register { st, value } => {
+                let value = value.apply_concrete(self)?;
+                self.log_x87();
+                self.current.set_x87_register(*st, value.to_u128());
+            }
+            InstructionSemanticsStep::X87Push => {
+                self.log_x87();
+                self.current.push_x87();
+            }
+            InstructionSemanticsStep::X87Pop => {
+                self.log_x87();
+                self.current.pop_x87();
+            }
+            InstructionSemanticsStep::SetX87Control { value } => {
+                let value = value.apply_concrete(self)?.to_u64() as u16;
+                self.log(Undo::X87Control(self.current.x87_control));
+                self.current.x87_control = value;
+            }
+            InstructionSemanticsStep::SetX87Status { value } => {
+                let value = value.apply_concrete(self)?.to_u64() as u16;
+                self.log_x87();
+                self.current.x87_status = value;
+            }
+            InstructionSemanticsStep::FloatingPointError => {
+                return Err(Fault::FloatingPointError);
+            }
+            InstructionSemanticsStep::SetSegment { segment, value } => {
+                let value = value.apply_concrete(self)?.to_u64() as u16;
+                self.log(Undo::Segment(*segment, self.current.get_segment(*segment)));
+                self.current.set_segment(*segment, value);
+                if let Some(base) = loaded_segment_base(*segment) {
+                    self.log(Undo::SegmentBase(base, self.current.segment_base(base)));
+                    self.current.set_segment_base(base, 0);
+                }
+            }
+            InstructionSemanticsStep::AdvanceRip { length } => {
+                self.log(Undo::Gpr(Reg64WithRIP::RIP, self.current.rip));
+                self.current.rip = self.current.rip.wrapping_add(*length as u64);
+            }
+        }
+        Ok(())
+    }
+
+    fn log(&mut self, undo: Undo) {
+        self.undo.push((self.index, undo));
+    }
+
+    fn log_x87(&mut self) {
+        self.log(Undo::X87 {
+            registers: self.current.x87_registers,
+            status: self.current.x87_status,
+            tag: self.current.x87_tag,
+        });
+    }
 
+    fn set_reg(&mut self, reg: GeneralReg, value: &Value, zero_upper: &ZeroUpper) {
+        let (parent, _, _) = ConcreteX86MachineState64::gpr_location(reg);
+        self.log(Undo::Gpr(parent, self.current.gpr64(parent)));
+        self.current.set_reg(reg, value, zero_upper);
+    }
+
+    fn set_flag(&mut self, flag: Flag, value: bool) {
+        self.log(Undo::Flag(flag, self.current.get_flag(flag)));
+        self.current.set_flag(flag, value);
+    }
+
+    /// Puts back everything the steps run so far have overwritten.
+    fn roll_back(&mut self) {
+        while let Some((_, undo)) = self.undo.pop() {
+            match undo {
+                Undo::Gpr(reg, value) => *self.current.gpr64_mut(reg) = value,
+                Undo::Flag(flag, value) => self.current.set_flag(flag, value),
+                Undo::Zmm(reg, value) => self.current.zmms[reg as usize] = value,
+                Undo::Mxcsr(value) => self.current.mxcsr = value,
+                Undo::X87 { registers, status, tag } => {
+                    self.current.x87_registers = registers;
+                    self.current.x87_status = status;
+                    self.current.x87_tag = tag;
+                }
+                Undo::X87Control(value) => self.current.x87_control = value,
+                Undo::Segment(segment, value) => self.current.set_segment(segment, value),
+                Undo::SegmentBase(base, value) => self.current.set_segment_base(base, value),
+                Undo::Memory(address, Some(value)) => {
+                    self.current.memory.insert(address, value);
+                }
+                Undo::Memory(address, None) => {
+                    self.current.memory.remove(&address);
+                }
             }
         }
     }
-    concrete
+
+    /// What the first step from `at_index` on to overwrite the part of the state `old` picks out found there, or
+    /// None if no such step has run, in which case the current value is still the one from before `at_index`.
+    fn old<T>(&self, at_index: usize, old: impl Fn(&Undo) -> Option<T>) -> Option<T> {
+        let first = self.undo.partition_point(|(index, _)| *index < at_index);
+        self.undo[first..].iter().find_map(|(_, undo)| old(undo))
+    }
+}
+
+/// A view of the state just before a step, for [`ConcreteExecution::state_at`].
+#[derive(Copy, Clone)]
+pub struct StateAt<'execution> {
+    execution: &'execution ConcreteExecution,
+    at_index: usize,
+}
+
+impl StateAt<'_> {
+    fn current(&self) -> &ConcreteX86MachineState64 {
+        &self.execution.current
+    }
+
+    fn old<T>(&self, old: impl Fn(&Undo) -> Option<T>) -> Option<T> {
+        self.execution.old(self.at_index, old)
+    }
+
+    pub fn gpr64(&self, reg: Reg64WithRIP) -> u64 {
+        self.old(|undo| match undo {
+            Undo::Gpr(old_reg, value) if *old_reg == reg => Some(*value),
+            _ => None,
+        }).unwrap_or_else(|| self.current().gpr64(reg))
+    }
+
+    pub fn get_reg<'arena>(&self, reg: GeneralReg) -> Value<'arena> {
+        let (parent, low, high) = ConcreteX86MachineState64::gpr_location(reg);
+        Value::from_u64(self.gpr64(parent), 64).slice(low, high)
+    }
+
+    pub fn get_flag(&self, flag: Flag) -> bool {
+        self.old(|undo| match undo {
+            Undo::Flag(old_flag, value) if *old_flag == flag => Some(*value),
+            _ => None,
+        }).unwrap_or_else(|| self.current().get_flag(flag))
+    }
+
+    pub fn get_zmm<'arena>(&self, reg: RegZMM) -> Value<'arena> {
+        self.old(|undo| match undo {
+            Undo::Zmm(old_reg, value) if *old_reg == reg => Some(Value::from_u64s(value, 512)),
+            _ => None,
+        }).unwrap_or_else(|| self.current().get_zmm(reg))
+    }
+
+    pub fn mxcsr(&self) -> u32 {
+        self.old(|undo| match undo {
+            Undo::Mxcsr(value) => Some(*value),
+            _ => None,
+        }).unwrap_or(self.current().mxcsr)
+    }
+
+    /// The x87 registers, status and tag words.
+    fn x87(&self) -> ([u128; 8], u16, u16) {
+        self.old(|undo| match undo {
+            Undo::X87 { registers, status, tag } => Some((*registers, *status, *tag)),
+            _ => None,
+        }).unwrap_or((self.current().x87_registers, self.current().x87_status, self.current().x87_tag))
+    }
+
+    pub fn x87_register(&self, st: usize) -> u128 {
+        let (registers, status, _) = self.x87();
+        registers[x87_physical(status, st)]
+    }
+
+    pub fn x87_register_empty(&self, st: usize) -> bool {
+        let (_, status, tag) = self.x87();
+        (tag >> (2 * x87_physical(status, st))) & 3 == 3
+    }
+
+    pub fn x87_status(&self) -> u16 {
+        self.x87().1
+    }
+
+    pub fn x87_control(&self) -> u16 {
+        self.old(|undo| match undo {
+            Undo::X87Control(value) => Some(*value),
+            _ => None,
+        }).unwrap_or(self.current().x87_control)
+    }
+
+    /// Little endian read of `width` bits starting at `address`. Addresses wrap around at 2^64.
+    pub fn read_memory<'arena>(&self, address: u64, width: usize) -> Value<'arena> {
+        assert_eq!(width % 8, 0);
+        let bytes = (0..width / 8)
+            .map(|i| {
+                let address = address.wrapping_add(i as u64);
+                let byte = self.old(|undo| match undo {
+                    Undo::Memory(old_address, byte) if *old_address == address => Some(*byte),
+                    _ => None,
+                }).unwrap_or_else(|| self.current().memory.get(&address).copied());
+                Value::from_u64(byte.unwrap_or(0) as u64, 8)
+            })
+            .collect::<Vec<_>>();
+        bytes.iter().fold(Value::zero(0), |acc, byte| Value::concat(byte, &acc))
+    }
+
+    pub fn get_segment(&self, segment: RegSegment) -> u16 {
+        self.old(|undo| match undo {
+            Undo::Segment(old_segment, value) if *old_segment == segment => Some(*value),
+            _ => None,
+        }).unwrap_or_else(|| self.current().get_segment(segment))
+    }
+
+    pub fn segment_base(&self, base: RegSegmentBase) -> u64 {
+        self.old(|undo| match undo {
+            Undo::SegmentBase(old_base, value) if *old_base == base => Some(*value),
+            _ => None,
+        }).unwrap_or_else(|| self.current().segment_base(base))
+    }
+}
+
+/// Sets CF, PF, AF, ZF, SF and OF the way `flag_tag`'s instruction family would for `left` op `right`. Flags the
+/// instruction leaves undefined get an arbitrary but deterministic value.
+fn calculate_flags(execution: &mut ConcreteExecution, flag_tag: &FlagTag, left: &Value, right: &Value) {
+    assert_eq!(left.width(), right.width());
+    let width = left.width();
+    assert!(width <= 64, "flags of operations wider than 64 bits are not supported");
+    let l = left.to_u128();
+    let r = right.to_u128();
+    let mask = (1u128 << width) - 1;
+    let adjust = |res: &Value| ((l ^ r ^ res.to_u128()) >> 4) & 1 == 1;
+    let add_overflow = |res: &Value| left.msb() == right.msb() && res.msb() != left.msb();
+    let sub_overflow = |res: &Value| left.msb() != right.msb() && res.msb() != left.msb();
+    // cf is None for instructions which leave it alone
+    let (res, cf, of, af) = match flag_tag {
+        FlagTag::Add | FlagTag::Inc => {
+            let res = Value::from_u128(l + r, width);
+            let cf = matches!(flag_tag, FlagTag::Add).then_some(l + r > mask);
+            (res.clone(), cf, add_overflow(&res), adjust(&res))
+        }
+        FlagTag::Adc => {
+            let carry = execution.current.get_flag(Flag::CF) as u128;
+            let res = Value::from_u128(l + r + carry, width);
+            (res.clone(), Some(l + r + carry > mask), add_overflow(&res), adjust(&res))
+        }
+        FlagTag::Sub | FlagTag::Dec => {
+            let res = Value::from_u128(l.wrapping_sub(r), width);
+            let cf = matches!(flag_tag, FlagTag::Sub).then_some(l < r);
+            (res.clone(), cf, sub_overflow(&res), adjust(&res))
+        }
+        FlagTag::Sbb => {
+            let carry = execution.current.get_flag(Flag::CF) as u128;
+            let res = Value::from_u128(l.wrapping_sub(r).wrapping_sub(carry), width);
+            (res.clone(), Some(l < r + carry), sub_overflow(&res), adjust(&res))
+        }
+        FlagTag::Mul => {
+            let full = l * r;
+            let high_nonzero = full >> width != 0;
+            (Value::from_u128(full, width), Some(high_nonzero), high_nonzero, false)
+        }
+        FlagTag::IMul => {
+            let full = left.to_i128() * right.to_i128();
+            let res = Value::from_u128(full as u128, width);
+            let overflow = res.to_i128() != full;
+            (res, Some(overflow), overflow, false)
+        }
+        FlagTag::And => (Value::from_u128(l & r, width), Some(false), false, false),
+        FlagTag::Or => (Value::from_u128(l | r, width), Some(false), false, false),
+        FlagTag::Xor => (Value::from_u128(l ^ r, width), Some(false), false, false),
+        FlagTag::Shl => {
+            assert_ne!(r, 0);
+            let res = Value::from_u128(l << r, width);
+            let cf = r <= width as u128 && (l >> (width as u128 - r)) & 1 == 1;
+            (res.clone(), Some(cf), res.msb() != cf, false)
+        }
+        FlagTag::Shr => {
+            assert_ne!(r, 0);
+            let cf = (l >> (r - 1)) & 1 == 1;
+            (Value::from_u128(l >> r, width), Some(cf), left.msb(), false)
+        }
+        FlagTag::Sar => {
+            assert_ne!(r, 0);
+            let l = left.to_i128();
+            let cf = (l >> (r - 1).min(127)) & 1 == 1;
+            (Value::from_u128((l >> r.min(127)) as u128, width), Some(cf), false, false)
+        }
+    };
+    if let Some(cf) = cf {
+        execution.set_flag(Flag::CF, cf);
+    }
+    execution.set_flag(Flag::PF, res.low_byte_parity_even());
+    execution.set_flag(Flag::AF, af);
+    execution.set_flag(Flag::ZF, res.is_zero());
+    execution.set_flag(Flag::SF, res.msb());
+    execution.set_flag(Flag::OF, of);
+}
+
+/// Runs `instructions` against `concrete`, returning the resulting state or the fault the instruction raised.
+pub fn apply_instructions_to_concrete(concrete: ConcreteX86MachineState64, instructions: &[InstructionSemanticsStep]) -> Result<ConcreteX86MachineState64, Fault> {
+    let mut execution = ConcreteExecution::new(concrete);
+    execution.run(instructions, 0)?;
+    Ok(execution.current)
+}
+
+/// Like [`apply_instructions_to_concrete`], but runs `instructions` on `concrete` where it is. If the instruction
+/// faults, whatever it had already written is put back.
+pub fn apply_instructions_to_concrete_in_place(concrete: &mut ConcreteX86MachineState64, instructions: &[InstructionSemanticsStep]) -> Result<(), Fault> {
+    let mut execution = ConcreteExecution::new(std::mem::replace(concrete, ConcreteX86MachineState64::zeroed()));
+    let res = execution.run(instructions, 0);
+    if res.is_err() {
+        execution.roll_back();
+    }
+    *concrete = execution.current;
+    res
 }
@@ -0,0 +1,200 @@
+use xed_enum::{SETB, SETBE, SETL, SETLE, SETNB, SETNBE, SETNL, SETNLE, SETNO, SETNP, SETNS, SETNZ, SETO, SETP, SETS, SETZ};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::condition::Condition;
+use crate::semantics2::read_write::Writeable;
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn setcc_generic<'arena, D1: Writeable<'arena>>(
+    arena: Arena<'arena>,
+    condition: Condition,
+    writeable1: D1,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF condition
+    //    THEN DEST := 1;
+    //    ELSE DEST := 0;
+    //FI;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(8);
+    let res = s.zext_to(s.condition(condition), 8);
+    writeable1.write(&mut s, res);
+    s.finalize()
+}
+
+
+pub fn apply_iform_setb(arena: Arena, instr: SETB) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETB::SETB_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::B, operand_0)
+        }
+        SETB::SETB_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::B, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setbe(arena: Arena, instr: SETBE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETBE::SETBE_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::BE, operand_0)
+        }
+        SETBE::SETBE_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::BE, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setl(arena: Arena, instr: SETL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETL::SETL_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::L, operand_0)
+        }
+        SETL::SETL_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::L, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setle(arena: Arena, instr: SETLE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETLE::SETLE_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::LE, operand_0)
+        }
+        SETLE::SETLE_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::LE, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setnb(arena: Arena, instr: SETNB) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETNB::SETNB_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::NB, operand_0)
+        }
+        SETNB::SETNB_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::NB, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setnbe(arena: Arena, instr: SETNBE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETNBE::SETNBE_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::NBE, operand_0)
+        }
+        SETNBE::SETNBE_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::NBE, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setnl(arena: Arena, instr: SETNL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETNL::SETNL_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::NL, operand_0)
+        }
+        SETNL::SETNL_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::NL, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setnle(arena: Arena, instr: SETNLE) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETNLE::SETNLE_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::NLE, operand_0)
+        }
+        SETNLE::SETNLE_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::NLE, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setno(arena: Arena, instr: SETNO) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETNO::SETNO_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::NO, operand_0)
+        }
+        SETNO::SETNO_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::NO, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setnp(arena: Arena, instr: SETNP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETNP::SETNP_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::NP, operand_0)
+        }
+        SETNP::SETNP_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::NP, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setns(arena: Arena, instr: SETNS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETNS::SETNS_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::NS, operand_0)
+        }
+        SETNS::SETNS_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::NS, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setnz(arena: Arena, instr: SETNZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETNZ::SETNZ_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::NZ, operand_0)
+        }
+        SETNZ::SETNZ_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::NZ, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_seto(arena: Arena, instr: SETO) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETO::SETO_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::O, operand_0)
+        }
+        SETO::SETO_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::O, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setp(arena: Arena, instr: SETP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETP::SETP_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::P, operand_0)
+        }
+        SETP::SETP_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::P, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_sets(arena: Arena, instr: SETS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETS::SETS_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::S, operand_0)
+        }
+        SETS::SETS_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::S, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_setz(arena: Arena, instr: SETZ) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SETZ::SETZ_MEMB { operand_0 } => {
+            setcc_generic(arena, Condition::Z, operand_0)
+        }
+        SETZ::SETZ_GPR8 { operand_0 } => {
+            setcc_generic(arena, Condition::Z, operand_0)
+        }
+    }
+}
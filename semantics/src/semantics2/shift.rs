@@ -0,0 +1,490 @@
+use wrapper_common::registers::Reg8;
+use xed_enum::{SAR, SHL, SHLD, SHR, SHRD};
+use xed_wrapper::operands::Imm8;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::{Expression, ShiftOp};
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::rotate::RotateDirection;
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+impl<'arena> SemanticsBuilder<'arena> {
+    /// Shift and rotate counts are masked to 5 bits, or 6 bits for 64 bit operands. The masked count is zero
+    /// extended to `width`.
+    pub fn shift_count(&self, count: &'arena Expression<'arena>, width: usize) -> &'arena Expression<'arena> {
+        let mask = if width == 64 { 0x3Fu8 } else { 0x1Fu8 };
+        self.zext_to(self.bitand(count, self.constant(mask)), width)
+    }
+
+    /// PF, ZF and SF of `res`, set the same way as for every arithmetic result.
+    pub fn set_result_flags(&mut self, res: &'arena Expression<'arena>) {
+        let width = res.width();
+        let parity = (1..8).fold(self.extract(res, 0, 1), |parity, i| self.bitxor(parity, self.extract(res, i, i + 1)));
+        let pf = self.not(parity);
+        let zf = self.equal(res, self.constant_with_width(0, width));
+        let sf = self.extract(res, width - 1, width);
+        self.set_pf(pf);
+        self.set_zf(zf);
+        self.set_sf(sf);
+    }
+}
+
+pub fn shift_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    op: ShiftOp,
+    writeable1: D1,
+    readable1: S1,
+    count: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //IF OperandSize = 64
+    //    THEN countMASK := 3FH;
+    //    ELSE countMASK := 1FH;
+    //FI
+    //tempCOUNT := (COUNT AND countMASK);
+    //DEST := DEST shifted by tempCOUNT;
+    //IF tempCOUNT = 0 THEN flags are not affected
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let count = s.shift_count(count.read(&s), width);
+    let (res, flag_tag) = match op {
+        ShiftOp::Shl => (s.shl(dest, count), FlagTag::Shl),
+        ShiftOp::LShr => (s.lshr(dest, count), FlagTag::Shr),
+        ShiftOp::AShr => (s.ashr(dest, count), FlagTag::Sar),
+    };
+    writeable1.write(&mut s, res);
+    let count_is_zero = s.equal(count, s.constant_with_width(0, width));
+    s.emit_conditional(count_is_zero, |_| {}, |s| s.flag_calculate(flag_tag, dest, count));
+    s.finalize()
+}
+
+/// SHLD and SHRD, which shift bits of `source` into the destination. A 16 bit shift by more than 16 leaves the
+/// destination and flags undefined, this gives what shifting the 32 bit `DEST:SRC` pair would.
+pub fn double_shift_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>, S3: Readable<'arena>>(
+    arena: Arena<'arena>,
+    direction: RotateDirection,
+    writeable1: D1,
+    readable1: S1,
+    source: S2,
+    count: S3,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //tempCOUNT := (COUNT AND countMASK);
+    //IF tempCOUNT = 0 THEN no operation, flags are not affected
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let source = source.read(&s);
+    let count = s.shift_count(count.read(&s), width);
+    // one bit wider than the pair, for the last bit shifted out to land in
+    let wide_width = 2 * width + 1;
+    let wide_count = s.zext_to(count, wide_width);
+    let (res, cf) = match direction {
+        //DEST := DEST shifted left by tempCOUNT, filled from the high bits of SRC;
+        //CF := BIT[DEST, SIZE – tempCOUNT];
+        RotateDirection::Left => {
+            let shifted = s.shl(s.zext_to(s.concat(dest, source), wide_width), wide_count);
+            (s.extract(shifted, width, 2 * width), s.extract(shifted, 2 * width, wide_width))
+        }
+        //DEST := DEST shifted right by tempCOUNT, filled from the low bits of SRC;
+        //CF := BIT[DEST, tempCOUNT – 1];
+        RotateDirection::Right => {
+            let shifted = s.lshr(s.concat(s.concat(source, dest), s.constant_with_width(0, 1)), wide_count);
+            (s.extract(shifted, 1, width + 1), s.extract(shifted, 0, 1))
+        }
+    };
+    writeable1.write(&mut s, res);
+    //OF is set if the sign changed when tempCOUNT = 1, and undefined otherwise. AF is undefined.
+    let of = s.bitxor(s.extract(res, width - 1, width), s.extract(dest, width - 1, width));
+    let count_is_zero = s.equal(count, s.constant_with_width(0, width));
+    s.emit_conditional(count_is_zero, |_| {}, |s| {
+        s.set_cf(cf);
+        s.set_of(of);
+        s.set_af(s.constant(false));
+        s.set_result_flags(res);
+    });
+    s.finalize()
+}
+
+pub fn apply_iform_shl(arena: Arena, instr: SHL) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SHL::SHL_MEMB_IMMB_C0R4 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 8)
+        }
+        SHL::SHL_GPR8_IMMB_C0R4 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 8)
+        }
+        SHL::SHL_MEMB_IMMB_C0R6 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 8)
+        }
+        SHL::SHL_GPR8_IMMB_C0R6 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 8)
+        }
+        SHL::SHL_MEMV_IMMB_C1R4_16 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 16)
+        }
+        SHL::SHL_MEMV_IMMB_C1R4_32 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 32)
+        }
+        SHL::SHL_MEMV_IMMB_C1R4_64 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 64)
+        }
+        SHL::SHL_GPRV_IMMB_C1R4_16 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 16)
+        }
+        SHL::SHL_GPRV_IMMB_C1R4_32 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 32)
+        }
+        SHL::SHL_GPRV_IMMB_C1R4_64 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 64)
+        }
+        SHL::SHL_MEMV_IMMB_C1R6_16 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 16)
+        }
+        SHL::SHL_MEMV_IMMB_C1R6_32 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 32)
+        }
+        SHL::SHL_MEMV_IMMB_C1R6_64 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 64)
+        }
+        SHL::SHL_GPRV_IMMB_C1R6_16 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 16)
+        }
+        SHL::SHL_GPRV_IMMB_C1R6_32 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 32)
+        }
+        SHL::SHL_GPRV_IMMB_C1R6_64 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, operand_1, 64)
+        }
+        SHL::SHL_MEMB_ONE_D0R4 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 8)
+        }
+        SHL::SHL_GPR8_ONE_D0R4 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 8)
+        }
+        SHL::SHL_MEMB_ONE_D0R6 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 8)
+        }
+        SHL::SHL_GPR8_ONE_D0R6 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 8)
+        }
+        SHL::SHL_MEMV_ONE_D1R6_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 16)
+        }
+        SHL::SHL_MEMV_ONE_D1R6_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 32)
+        }
+        SHL::SHL_MEMV_ONE_D1R6_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 64)
+        }
+        SHL::SHL_MEMV_ONE_D1R4_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 16)
+        }
+        SHL::SHL_MEMV_ONE_D1R4_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 32)
+        }
+        SHL::SHL_MEMV_ONE_D1R4_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 64)
+        }
+        SHL::SHL_GPRV_ONE_D1R4_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 16)
+        }
+        SHL::SHL_GPRV_ONE_D1R4_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 32)
+        }
+        SHL::SHL_GPRV_ONE_D1R4_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 64)
+        }
+        SHL::SHL_GPRV_ONE_D1R6_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 16)
+        }
+        SHL::SHL_GPRV_ONE_D1R6_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 32)
+        }
+        SHL::SHL_GPRV_ONE_D1R6_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Imm8(1), 64)
+        }
+        SHL::SHL_MEMB_CL_D2R4 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 8)
+        }
+        SHL::SHL_GPR8_CL_D2R4 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 8)
+        }
+        SHL::SHL_MEMB_CL_D2R6 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 8)
+        }
+        SHL::SHL_GPR8_CL_D2R6 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 8)
+        }
+        SHL::SHL_MEMV_CL_D3R4_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 16)
+        }
+        SHL::SHL_MEMV_CL_D3R4_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 32)
+        }
+        SHL::SHL_MEMV_CL_D3R4_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 64)
+        }
+        SHL::SHL_GPRV_CL_D3R4_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 16)
+        }
+        SHL::SHL_GPRV_CL_D3R4_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 32)
+        }
+        SHL::SHL_GPRV_CL_D3R4_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 64)
+        }
+        SHL::SHL_MEMV_CL_D3R6_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 16)
+        }
+        SHL::SHL_MEMV_CL_D3R6_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 32)
+        }
+        SHL::SHL_MEMV_CL_D3R6_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 64)
+        }
+        SHL::SHL_GPRV_CL_D3R6_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 16)
+        }
+        SHL::SHL_GPRV_CL_D3R6_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 32)
+        }
+        SHL::SHL_GPRV_CL_D3R6_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::Shl, operand_0, operand_0, Reg8::CL, 64)
+        }
+    }
+}
+pub fn apply_iform_shr(arena: Arena, instr: SHR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SHR::SHR_MEMB_IMMB { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, operand_1, 8)
+        }
+        SHR::SHR_GPR8_IMMB { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, operand_1, 8)
+        }
+        SHR::SHR_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, operand_1, 16)
+        }
+        SHR::SHR_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, operand_1, 32)
+        }
+        SHR::SHR_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, operand_1, 64)
+        }
+        SHR::SHR_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, operand_1, 16)
+        }
+        SHR::SHR_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, operand_1, 32)
+        }
+        SHR::SHR_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, operand_1, 64)
+        }
+        SHR::SHR_MEMB_ONE { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Imm8(1), 8)
+        }
+        SHR::SHR_GPR8_ONE { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Imm8(1), 8)
+        }
+        SHR::SHR_MEMV_ONE_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Imm8(1), 16)
+        }
+        SHR::SHR_MEMV_ONE_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Imm8(1), 32)
+        }
+        SHR::SHR_MEMV_ONE_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Imm8(1), 64)
+        }
+        SHR::SHR_GPRV_ONE_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Imm8(1), 16)
+        }
+        SHR::SHR_GPRV_ONE_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Imm8(1), 32)
+        }
+        SHR::SHR_GPRV_ONE_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Imm8(1), 64)
+        }
+        SHR::SHR_MEMB_CL { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Reg8::CL, 8)
+        }
+        SHR::SHR_GPR8_CL { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Reg8::CL, 8)
+        }
+        SHR::SHR_MEMV_CL_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Reg8::CL, 16)
+        }
+        SHR::SHR_MEMV_CL_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Reg8::CL, 32)
+        }
+        SHR::SHR_MEMV_CL_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Reg8::CL, 64)
+        }
+        SHR::SHR_GPRV_CL_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Reg8::CL, 16)
+        }
+        SHR::SHR_GPRV_CL_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Reg8::CL, 32)
+        }
+        SHR::SHR_GPRV_CL_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::LShr, operand_0, operand_0, Reg8::CL, 64)
+        }
+    }
+}
+pub fn apply_iform_sar(arena: Arena, instr: SAR) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SAR::SAR_MEMB_IMMB { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, operand_1, 8)
+        }
+        SAR::SAR_GPR8_IMMB { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, operand_1, 8)
+        }
+        SAR::SAR_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, operand_1, 16)
+        }
+        SAR::SAR_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, operand_1, 32)
+        }
+        SAR::SAR_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, operand_1, 64)
+        }
+        SAR::SAR_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, operand_1, 16)
+        }
+        SAR::SAR_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, operand_1, 32)
+        }
+        SAR::SAR_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, operand_1, 64)
+        }
+        SAR::SAR_MEMB_ONE { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Imm8(1), 8)
+        }
+        SAR::SAR_GPR8_ONE { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Imm8(1), 8)
+        }
+        SAR::SAR_MEMV_ONE_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Imm8(1), 16)
+        }
+        SAR::SAR_MEMV_ONE_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Imm8(1), 32)
+        }
+        SAR::SAR_MEMV_ONE_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Imm8(1), 64)
+        }
+        SAR::SAR_GPRV_ONE_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Imm8(1), 16)
+        }
+        SAR::SAR_GPRV_ONE_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Imm8(1), 32)
+        }
+        SAR::SAR_GPRV_ONE_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Imm8(1), 64)
+        }
+        SAR::SAR_MEMB_CL { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Reg8::CL, 8)
+        }
+        SAR::SAR_GPR8_CL { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Reg8::CL, 8)
+        }
+        SAR::SAR_MEMV_CL_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Reg8::CL, 16)
+        }
+        SAR::SAR_MEMV_CL_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Reg8::CL, 32)
+        }
+        SAR::SAR_MEMV_CL_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Reg8::CL, 64)
+        }
+        SAR::SAR_GPRV_CL_16 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Reg8::CL, 16)
+        }
+        SAR::SAR_GPRV_CL_32 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Reg8::CL, 32)
+        }
+        SAR::SAR_GPRV_CL_64 { operand_0 } => {
+            shift_generic(arena, ShiftOp::AShr, operand_0, operand_0, Reg8::CL, 64)
+        }
+    }
+}
+pub fn apply_iform_shld(arena: Arena, instr: SHLD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SHLD::SHLD_GPRV_GPRV_IMMB_16 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, operand_2, 16)
+        }
+        SHLD::SHLD_GPRV_GPRV_IMMB_32 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, operand_2, 32)
+        }
+        SHLD::SHLD_GPRV_GPRV_IMMB_64 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, operand_2, 64)
+        }
+        SHLD::SHLD_MEMV_GPRV_IMMB_16 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, operand_2, 16)
+        }
+        SHLD::SHLD_MEMV_GPRV_IMMB_32 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, operand_2, 32)
+        }
+        SHLD::SHLD_MEMV_GPRV_IMMB_64 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, operand_2, 64)
+        }
+        SHLD::SHLD_GPRV_GPRV_CL_16 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, Reg8::CL, 16)
+        }
+        SHLD::SHLD_GPRV_GPRV_CL_32 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, Reg8::CL, 32)
+        }
+        SHLD::SHLD_GPRV_GPRV_CL_64 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, Reg8::CL, 64)
+        }
+        SHLD::SHLD_MEMV_GPRV_CL_16 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, Reg8::CL, 16)
+        }
+        SHLD::SHLD_MEMV_GPRV_CL_32 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, Reg8::CL, 32)
+        }
+        SHLD::SHLD_MEMV_GPRV_CL_64 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Left, operand_0, operand_0, operand_1, Reg8::CL, 64)
+        }
+    }
+}
+pub fn apply_iform_shrd(arena: Arena, instr: SHRD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SHRD::SHRD_GPRV_GPRV_IMMB_16 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, operand_2, 16)
+        }
+        SHRD::SHRD_GPRV_GPRV_IMMB_32 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, operand_2, 32)
+        }
+        SHRD::SHRD_GPRV_GPRV_IMMB_64 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, operand_2, 64)
+        }
+        SHRD::SHRD_MEMV_GPRV_IMMB_16 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, operand_2, 16)
+        }
+        SHRD::SHRD_MEMV_GPRV_IMMB_32 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, operand_2, 32)
+        }
+        SHRD::SHRD_MEMV_GPRV_IMMB_64 { operand_0, operand_1, operand_2 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, operand_2, 64)
+        }
+        SHRD::SHRD_GPRV_GPRV_CL_16 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, Reg8::CL, 16)
+        }
+        SHRD::SHRD_GPRV_GPRV_CL_32 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, Reg8::CL, 32)
+        }
+        SHRD::SHRD_GPRV_GPRV_CL_64 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, Reg8::CL, 64)
+        }
+        SHRD::SHRD_MEMV_GPRV_CL_16 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, Reg8::CL, 16)
+        }
+        SHRD::SHRD_MEMV_GPRV_CL_32 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, Reg8::CL, 32)
+        }
+        SHRD::SHRD_MEMV_GPRV_CL_64 { operand_0, operand_1 } => {
+            double_shift_generic(arena, RotateDirection::Right, operand_0, operand_0, operand_1, Reg8::CL, 64)
+        }
+    }
+}
@@ -0,0 +1,1104 @@
+use wrapper_common::registers::RegZMM;
+use xed_enum::{ADDPS, ADDSD, ADDSS, DIVPD, DIVPS, DIVSD, DIVSS, MAXPD, MAXPS, MAXSD, MAXSS, MINPD, MINPS, MINSD, MINSS, MULPD, MULPS, MULSD, MULSS, SQRTPD, SQRTPS, SQRTSD, SQRTSS, SUBPD, SUBPS, SUBSD, SUBSS, VADDPS, VADDSD, VADDSS, VDIVPD, VDIVPS, VDIVSD, VDIVSS, VMAXPD, VMAXPS, VMAXSD, VMAXSS, VMINPD, VMINPS, VMINSD, VMINSS, VMULPD, VMULPS, VMULSD, VMULSS, VSQRTPD, VSQRTPS, VSQRTSD, VSQRTSS, VSUBPD, VSUBPS, VSUBSD, VSUBSS, X86Instruction};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::Expression;
+use crate::semantics2::float::{FloatArithmeticOp, FloatFormat};
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::SemanticsError;
+
+/// `op` as the per lane operation of the generic functions below.
+pub fn lane_arithmetic<'arena>(op: FloatArithmeticOp, format: FloatFormat) -> impl Fn(&SemanticsBuilder<'arena>, &'arena Expression<'arena>, &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+    move |s, left, right| s.float_arithmetic(op, format, left, right)
+}
+
+/// Square root of the second operand, the first only decides what ends up in the rest of the destination.
+pub fn lane_sqrt<'arena>(format: FloatFormat) -> impl Fn(&SemanticsBuilder<'arena>, &'arena Expression<'arena>, &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+    move |s, _, value| s.float_sqrt(format, value)
+}
+
+/// Legacy SSE packed operation, `op` on each `format` lane of the 128 bit operands. Bits 128 and up of the
+/// destination are left alone.
+pub fn sse_packed_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    op: impl Fn(&SemanticsBuilder<'arena>, &'arena Expression<'arena>, &'arena Expression<'arena>) -> &'arena Expression<'arena>,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // FOR EACH LANE: DEST[lane] := SRC1[lane] op SRC2[lane]
+    // DEST[MAXVL-1:128] (Unmodified)
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(128);
+    let left = readable1.read(&s);
+    let right = readable2.read(&s);
+    let (res, lanes) = s.float_lanes(format, left, right, op);
+    s.raise_float_exceptions(&lanes);
+    writeable1.write(&mut s, res);
+    s.finalize()
+}
+
+/// Legacy SSE scalar operation on the low `format` lane, the rest of the destination is left alone. Memory
+/// sources are only as wide as the lane.
+pub fn sse_scalar_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    op: impl Fn(&SemanticsBuilder<'arena>, &'arena Expression<'arena>, &'arena Expression<'arena>) -> &'arena Expression<'arena>,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST[lane 0] := DEST[lane 0] op SRC[lane 0]
+    // DEST[MAXVL-1:lane width] (Unmodified)
+    let mut s = SemanticsBuilder::new(arena);
+    let width = format.width();
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    let src = s.lower_bits(readable2.read(&s), width);
+    let res = op(&s, s.lower_bits(dest, width), src);
+    s.raise_float_exceptions(&[res]);
+    let merged = s.change(dest, 0, width, res);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+/// VEX encoded packed operation on `width` bit operands, bits `width` and up of the destination are zeroed.
+pub fn vex_packed_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: RegZMM,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+    op: impl Fn(&SemanticsBuilder<'arena>, &'arena Expression<'arena>, &'arena Expression<'arena>) -> &'arena Expression<'arena>,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // FOR EACH LANE: DEST[lane] := SRC1[lane] op SRC2[lane]
+    // DEST[MAXVL-1:VL] := 0
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let left = readable1.read(&s);
+    let right = readable2.read(&s);
+    let (res, lanes) = s.float_lanes(format, left, right, op);
+    s.raise_float_exceptions(&lanes);
+    let widened = s.zext_to(res, 512);
+    writeable1.write(&mut s, widened);
+    s.finalize()
+}
+
+/// VEX encoded scalar operation on the low `format` lane. The rest of the low 128 bits come from `readable1`, and
+/// bits 128 and up of the destination are zeroed.
+pub fn vex_scalar_generic<'arena, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    format: FloatFormat,
+    writeable1: RegZMM,
+    readable1: S1,
+    readable2: S2,
+    op: impl Fn(&SemanticsBuilder<'arena>, &'arena Expression<'arena>, &'arena Expression<'arena>) -> &'arena Expression<'arena>,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST[lane 0] := SRC1[lane 0] op SRC2[lane 0]
+    // DEST[127:lane width] := SRC1[127:lane width]
+    // DEST[MAXVL-1:128] := 0
+    let mut s = SemanticsBuilder::new(arena);
+    let width = format.width();
+    s.set_memory_operand_width(width);
+    let left = readable1.read(&s);
+    let right = s.lower_bits(readable2.read(&s), width);
+    let res = op(&s, s.lower_bits(left, width), right);
+    s.raise_float_exceptions(&[res]);
+    let merged = s.zext_to(s.change(left, 0, width, res), 512);
+    writeable1.write(&mut s, merged);
+    s.finalize()
+}
+
+pub fn apply_iform_addps(arena: Arena, instr: ADDPS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        ADDPS::ADDPS_XMMPS_MEMPS_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+        ADDPS::ADDPS_XMMPS_XMMPS { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_addsd(arena: Arena, instr: ADDSD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        ADDSD::ADDSD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Double))
+        }
+        ADDSD::ADDSD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_addss(arena: Arena, instr: ADDSS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        ADDSS::ADDSS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+        ADDSS::ADDSS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_vaddps(arena: Arena, instr: VADDPS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VADDPS::VADDPS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+        VADDPS::VADDPS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+        VADDPS::VADDPS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+        VADDPS::VADDPS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+        VADDPS::VADDPS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDPS(instr)));
+        }
+        VADDPS::VADDPS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDPS(instr)));
+        }
+        VADDPS::VADDPS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDPS(instr)));
+        }
+        VADDPS::VADDPS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDPS(instr)));
+        }
+        VADDPS::VADDPS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDPS(instr)));
+        }
+        VADDPS::VADDPS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDPS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vaddsd(arena: Arena, instr: VADDSD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VADDSD::VADDSD_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Double))
+        }
+        VADDSD::VADDSD_XMMDQ_XMMDQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Double))
+        }
+        VADDSD::VADDSD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDSD(instr)));
+        }
+        VADDSD::VADDSD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDSD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vaddss(arena: Arena, instr: VADDSS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VADDSS::VADDSS_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+        VADDSS::VADDSS_XMMDQ_XMMDQ_XMMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Add, FloatFormat::Single))
+        }
+        VADDSS::VADDSS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDSS(instr)));
+        }
+        VADDSS::VADDSS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VADDSS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_subpd(arena: Arena, instr: SUBPD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SUBPD::SUBPD_XMMPD_MEMPD_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+        SUBPD::SUBPD_XMMPD_XMMPD { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_subps(arena: Arena, instr: SUBPS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SUBPS::SUBPS_XMMPS_MEMPS_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+        SUBPS::SUBPS_XMMPS_XMMPS { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_subsd(arena: Arena, instr: SUBSD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SUBSD::SUBSD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+        SUBSD::SUBSD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_subss(arena: Arena, instr: SUBSS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SUBSS::SUBSS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+        SUBSS::SUBSS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_vsubpd(arena: Arena, instr: VSUBPD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VSUBPD::VSUBPD_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+        VSUBPD::VSUBPD_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+        VSUBPD::VSUBPD_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+        VSUBPD::VSUBPD_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+        VSUBPD::VSUBPD_ZMMF64_MASKMSKW_ZMMF64_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPD(instr)));
+        }
+        VSUBPD::VSUBPD_ZMMF64_MASKMSKW_ZMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPD(instr)));
+        }
+        VSUBPD::VSUBPD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPD(instr)));
+        }
+        VSUBPD::VSUBPD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPD(instr)));
+        }
+        VSUBPD::VSUBPD_YMMF64_MASKMSKW_YMMF64_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPD(instr)));
+        }
+        VSUBPD::VSUBPD_YMMF64_MASKMSKW_YMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vsubps(arena: Arena, instr: VSUBPS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VSUBPS::VSUBPS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+        VSUBPS::VSUBPS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+        VSUBPS::VSUBPS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+        VSUBPS::VSUBPS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+        VSUBPS::VSUBPS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPS(instr)));
+        }
+        VSUBPS::VSUBPS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPS(instr)));
+        }
+        VSUBPS::VSUBPS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPS(instr)));
+        }
+        VSUBPS::VSUBPS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPS(instr)));
+        }
+        VSUBPS::VSUBPS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPS(instr)));
+        }
+        VSUBPS::VSUBPS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBPS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vsubsd(arena: Arena, instr: VSUBSD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VSUBSD::VSUBSD_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+        VSUBSD::VSUBSD_XMMDQ_XMMDQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Double))
+        }
+        VSUBSD::VSUBSD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBSD(instr)));
+        }
+        VSUBSD::VSUBSD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBSD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vsubss(arena: Arena, instr: VSUBSS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VSUBSS::VSUBSS_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+        VSUBSS::VSUBSS_XMMDQ_XMMDQ_XMMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Sub, FloatFormat::Single))
+        }
+        VSUBSS::VSUBSS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBSS(instr)));
+        }
+        VSUBSS::VSUBSS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSUBSS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_mulpd(arena: Arena, instr: MULPD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MULPD::MULPD_XMMPD_MEMPD_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+        MULPD::MULPD_XMMPD_XMMPD { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_mulps(arena: Arena, instr: MULPS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MULPS::MULPS_XMMPS_MEMPS_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+        MULPS::MULPS_XMMPS_XMMPS { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_mulsd(arena: Arena, instr: MULSD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MULSD::MULSD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+        MULSD::MULSD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_mulss(arena: Arena, instr: MULSS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MULSS::MULSS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+        MULSS::MULSS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_vmulpd(arena: Arena, instr: VMULPD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMULPD::VMULPD_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+        VMULPD::VMULPD_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+        VMULPD::VMULPD_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+        VMULPD::VMULPD_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+        VMULPD::VMULPD_ZMMF64_MASKMSKW_ZMMF64_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPD(instr)));
+        }
+        VMULPD::VMULPD_ZMMF64_MASKMSKW_ZMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPD(instr)));
+        }
+        VMULPD::VMULPD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPD(instr)));
+        }
+        VMULPD::VMULPD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPD(instr)));
+        }
+        VMULPD::VMULPD_YMMF64_MASKMSKW_YMMF64_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPD(instr)));
+        }
+        VMULPD::VMULPD_YMMF64_MASKMSKW_YMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vmulps(arena: Arena, instr: VMULPS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMULPS::VMULPS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+        VMULPS::VMULPS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+        VMULPS::VMULPS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+        VMULPS::VMULPS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+        VMULPS::VMULPS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPS(instr)));
+        }
+        VMULPS::VMULPS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPS(instr)));
+        }
+        VMULPS::VMULPS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPS(instr)));
+        }
+        VMULPS::VMULPS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPS(instr)));
+        }
+        VMULPS::VMULPS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPS(instr)));
+        }
+        VMULPS::VMULPS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULPS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vmulsd(arena: Arena, instr: VMULSD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMULSD::VMULSD_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+        VMULSD::VMULSD_XMMDQ_XMMDQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Double))
+        }
+        VMULSD::VMULSD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULSD(instr)));
+        }
+        VMULSD::VMULSD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULSD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vmulss(arena: Arena, instr: VMULSS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMULSS::VMULSS_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+        VMULSS::VMULSS_XMMDQ_XMMDQ_XMMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Mul, FloatFormat::Single))
+        }
+        VMULSS::VMULSS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULSS(instr)));
+        }
+        VMULSS::VMULSS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMULSS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_divpd(arena: Arena, instr: DIVPD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        DIVPD::DIVPD_XMMPD_MEMPD_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+        DIVPD::DIVPD_XMMPD_XMMPD { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_divps(arena: Arena, instr: DIVPS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        DIVPS::DIVPS_XMMPS_MEMPS_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+        DIVPS::DIVPS_XMMPS_XMMPS { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_divsd(arena: Arena, instr: DIVSD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        DIVSD::DIVSD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+        DIVSD::DIVSD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_divss(arena: Arena, instr: DIVSS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        DIVSS::DIVSS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+        DIVSS::DIVSS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_vdivpd(arena: Arena, instr: VDIVPD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VDIVPD::VDIVPD_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+        VDIVPD::VDIVPD_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+        VDIVPD::VDIVPD_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+        VDIVPD::VDIVPD_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+        VDIVPD::VDIVPD_ZMMF64_MASKMSKW_ZMMF64_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPD(instr)));
+        }
+        VDIVPD::VDIVPD_ZMMF64_MASKMSKW_ZMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPD(instr)));
+        }
+        VDIVPD::VDIVPD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPD(instr)));
+        }
+        VDIVPD::VDIVPD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPD(instr)));
+        }
+        VDIVPD::VDIVPD_YMMF64_MASKMSKW_YMMF64_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPD(instr)));
+        }
+        VDIVPD::VDIVPD_YMMF64_MASKMSKW_YMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vdivps(arena: Arena, instr: VDIVPS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VDIVPS::VDIVPS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+        VDIVPS::VDIVPS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+        VDIVPS::VDIVPS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+        VDIVPS::VDIVPS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+        VDIVPS::VDIVPS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPS(instr)));
+        }
+        VDIVPS::VDIVPS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPS(instr)));
+        }
+        VDIVPS::VDIVPS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPS(instr)));
+        }
+        VDIVPS::VDIVPS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPS(instr)));
+        }
+        VDIVPS::VDIVPS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPS(instr)));
+        }
+        VDIVPS::VDIVPS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVPS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vdivsd(arena: Arena, instr: VDIVSD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VDIVSD::VDIVSD_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+        VDIVSD::VDIVSD_XMMDQ_XMMDQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Double))
+        }
+        VDIVSD::VDIVSD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVSD(instr)));
+        }
+        VDIVSD::VDIVSD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVSD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vdivss(arena: Arena, instr: VDIVSS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VDIVSS::VDIVSS_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+        VDIVSS::VDIVSS_XMMDQ_XMMDQ_XMMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Div, FloatFormat::Single))
+        }
+        VDIVSS::VDIVSS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVSS(instr)));
+        }
+        VDIVSS::VDIVSS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VDIVSS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_minpd(arena: Arena, instr: MINPD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MINPD::MINPD_XMMPD_MEMPD_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+        MINPD::MINPD_XMMPD_XMMPD { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_minps(arena: Arena, instr: MINPS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MINPS::MINPS_XMMPS_MEMPS_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+        MINPS::MINPS_XMMPS_XMMPS { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_minsd(arena: Arena, instr: MINSD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MINSD::MINSD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+        MINSD::MINSD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_minss(arena: Arena, instr: MINSS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MINSS::MINSS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+        MINSS::MINSS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_vminpd(arena: Arena, instr: VMINPD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMINPD::VMINPD_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+        VMINPD::VMINPD_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+        VMINPD::VMINPD_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+        VMINPD::VMINPD_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+        VMINPD::VMINPD_ZMMF64_MASKMSKW_ZMMF64_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPD(instr)));
+        }
+        VMINPD::VMINPD_ZMMF64_MASKMSKW_ZMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPD(instr)));
+        }
+        VMINPD::VMINPD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPD(instr)));
+        }
+        VMINPD::VMINPD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPD(instr)));
+        }
+        VMINPD::VMINPD_YMMF64_MASKMSKW_YMMF64_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPD(instr)));
+        }
+        VMINPD::VMINPD_YMMF64_MASKMSKW_YMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vminps(arena: Arena, instr: VMINPS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMINPS::VMINPS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+        VMINPS::VMINPS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+        VMINPS::VMINPS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+        VMINPS::VMINPS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+        VMINPS::VMINPS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPS(instr)));
+        }
+        VMINPS::VMINPS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPS(instr)));
+        }
+        VMINPS::VMINPS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPS(instr)));
+        }
+        VMINPS::VMINPS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPS(instr)));
+        }
+        VMINPS::VMINPS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPS(instr)));
+        }
+        VMINPS::VMINPS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINPS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vminsd(arena: Arena, instr: VMINSD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMINSD::VMINSD_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+        VMINSD::VMINSD_XMMDQ_XMMDQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Double))
+        }
+        VMINSD::VMINSD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINSD(instr)));
+        }
+        VMINSD::VMINSD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINSD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vminss(arena: Arena, instr: VMINSS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMINSS::VMINSS_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+        VMINSS::VMINSS_XMMDQ_XMMDQ_XMMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Min, FloatFormat::Single))
+        }
+        VMINSS::VMINSS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINSS(instr)));
+        }
+        VMINSS::VMINSS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMINSS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_maxpd(arena: Arena, instr: MAXPD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MAXPD::MAXPD_XMMPD_MEMPD_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+        MAXPD::MAXPD_XMMPD_XMMPD { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_maxps(arena: Arena, instr: MAXPS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MAXPS::MAXPS_XMMPS_MEMPS_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+        MAXPS::MAXPS_XMMPS_XMMPS { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_maxsd(arena: Arena, instr: MAXSD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MAXSD::MAXSD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+        MAXSD::MAXSD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_maxss(arena: Arena, instr: MAXSS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        MAXSS::MAXSS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+        MAXSS::MAXSS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_vmaxpd(arena: Arena, instr: VMAXPD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMAXPD::VMAXPD_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+        VMAXPD::VMAXPD_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+        VMAXPD::VMAXPD_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+        VMAXPD::VMAXPD_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+        VMAXPD::VMAXPD_ZMMF64_MASKMSKW_ZMMF64_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPD(instr)));
+        }
+        VMAXPD::VMAXPD_ZMMF64_MASKMSKW_ZMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPD(instr)));
+        }
+        VMAXPD::VMAXPD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPD(instr)));
+        }
+        VMAXPD::VMAXPD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPD(instr)));
+        }
+        VMAXPD::VMAXPD_YMMF64_MASKMSKW_YMMF64_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPD(instr)));
+        }
+        VMAXPD::VMAXPD_YMMF64_MASKMSKW_YMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vmaxps(arena: Arena, instr: VMAXPS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMAXPS::VMAXPS_XMMDQ_XMMDQ_MEMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+        VMAXPS::VMAXPS_XMMDQ_XMMDQ_XMMDQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 128, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+        VMAXPS::VMAXPS_YMMQQ_YMMQQ_MEMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+        VMAXPS::VMAXPS_YMMQQ_YMMQQ_YMMQQ { operand_0, operand_1, operand_2 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, 256, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+        VMAXPS::VMAXPS_ZMMF32_MASKMSKW_ZMMF32_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPS(instr)));
+        }
+        VMAXPS::VMAXPS_ZMMF32_MASKMSKW_ZMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPS(instr)));
+        }
+        VMAXPS::VMAXPS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPS(instr)));
+        }
+        VMAXPS::VMAXPS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPS(instr)));
+        }
+        VMAXPS::VMAXPS_YMMF32_MASKMSKW_YMMF32_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPS(instr)));
+        }
+        VMAXPS::VMAXPS_YMMF32_MASKMSKW_YMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXPS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vmaxsd(arena: Arena, instr: VMAXSD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMAXSD::VMAXSD_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+        VMAXSD::VMAXSD_XMMDQ_XMMDQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Double))
+        }
+        VMAXSD::VMAXSD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXSD(instr)));
+        }
+        VMAXSD::VMAXSD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXSD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vmaxss(arena: Arena, instr: VMAXSS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VMAXSS::VMAXSS_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+        VMAXSS::VMAXSS_XMMDQ_XMMDQ_XMMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_arithmetic(FloatArithmeticOp::Max, FloatFormat::Single))
+        }
+        VMAXSS::VMAXSS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXSS(instr)));
+        }
+        VMAXSS::VMAXSS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VMAXSS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_sqrtpd(arena: Arena, instr: SQRTPD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SQRTPD::SQRTPD_XMMPD_MEMPD_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_sqrt(FloatFormat::Double))
+        }
+        SQRTPD::SQRTPD_XMMPD_XMMPD { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_sqrt(FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_sqrtps(arena: Arena, instr: SQRTPS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SQRTPS::SQRTPS_XMMPS_MEMPS_128 { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_sqrt(FloatFormat::Single))
+        }
+        SQRTPS::SQRTPS_XMMPS_XMMPS { operand_0, operand_1 } => {
+            sse_packed_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_sqrt(FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_sqrtsd(arena: Arena, instr: SQRTSD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SQRTSD::SQRTSD_XMMSD_MEMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_sqrt(FloatFormat::Double))
+        }
+        SQRTSD::SQRTSD_XMMSD_XMMSD { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Double, operand_0, operand_0, operand_1, lane_sqrt(FloatFormat::Double))
+        }
+    }
+}
+
+pub fn apply_iform_sqrtss(arena: Arena, instr: SQRTSS) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SQRTSS::SQRTSS_XMMSS_MEMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_sqrt(FloatFormat::Single))
+        }
+        SQRTSS::SQRTSS_XMMSS_XMMSS { operand_0, operand_1 } => {
+            sse_scalar_generic(arena, FloatFormat::Single, operand_0, operand_0, operand_1, lane_sqrt(FloatFormat::Single))
+        }
+    }
+}
+
+pub fn apply_iform_vsqrtpd(arena: Arena, instr: VSQRTPD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VSQRTPD::VSQRTPD_XMMDQ_MEMDQ { operand_0, operand_1 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_1, 128, lane_sqrt(FloatFormat::Double))
+        }
+        VSQRTPD::VSQRTPD_XMMDQ_XMMDQ { operand_0, operand_1 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_1, 128, lane_sqrt(FloatFormat::Double))
+        }
+        VSQRTPD::VSQRTPD_YMMQQ_MEMQQ { operand_0, operand_1 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_1, 256, lane_sqrt(FloatFormat::Double))
+        }
+        VSQRTPD::VSQRTPD_YMMQQ_YMMQQ { operand_0, operand_1 } => {
+            vex_packed_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_1, 256, lane_sqrt(FloatFormat::Double))
+        }
+        VSQRTPD::VSQRTPD_ZMMF64_MASKMSKW_ZMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPD(instr)));
+        }
+        VSQRTPD::VSQRTPD_ZMMF64_MASKMSKW_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPD(instr)));
+        }
+        VSQRTPD::VSQRTPD_XMMF64_MASKMSKW_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPD(instr)));
+        }
+        VSQRTPD::VSQRTPD_XMMF64_MASKMSKW_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPD(instr)));
+        }
+        VSQRTPD::VSQRTPD_YMMF64_MASKMSKW_YMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPD(instr)));
+        }
+        VSQRTPD::VSQRTPD_YMMF64_MASKMSKW_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vsqrtps(arena: Arena, instr: VSQRTPS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VSQRTPS::VSQRTPS_XMMDQ_MEMDQ { operand_0, operand_1 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_1, 128, lane_sqrt(FloatFormat::Single))
+        }
+        VSQRTPS::VSQRTPS_XMMDQ_XMMDQ { operand_0, operand_1 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_1, 128, lane_sqrt(FloatFormat::Single))
+        }
+        VSQRTPS::VSQRTPS_YMMQQ_MEMQQ { operand_0, operand_1 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_1, 256, lane_sqrt(FloatFormat::Single))
+        }
+        VSQRTPS::VSQRTPS_YMMQQ_YMMQQ { operand_0, operand_1 } => {
+            vex_packed_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_1, 256, lane_sqrt(FloatFormat::Single))
+        }
+        VSQRTPS::VSQRTPS_ZMMF32_MASKMSKW_ZMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPS(instr)));
+        }
+        VSQRTPS::VSQRTPS_ZMMF32_MASKMSKW_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPS(instr)));
+        }
+        VSQRTPS::VSQRTPS_XMMF32_MASKMSKW_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPS(instr)));
+        }
+        VSQRTPS::VSQRTPS_XMMF32_MASKMSKW_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPS(instr)));
+        }
+        VSQRTPS::VSQRTPS_YMMF32_MASKMSKW_YMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPS(instr)));
+        }
+        VSQRTPS::VSQRTPS_YMMF32_MASKMSKW_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTPS(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vsqrtsd(arena: Arena, instr: VSQRTSD) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VSQRTSD::VSQRTSD_XMMDQ_XMMDQ_MEMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_sqrt(FloatFormat::Double))
+        }
+        VSQRTSD::VSQRTSD_XMMDQ_XMMDQ_XMMQ { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Double, operand_0.widen_to_zmm(), operand_1, operand_2, lane_sqrt(FloatFormat::Double))
+        }
+        VSQRTSD::VSQRTSD_XMMF64_MASKMSKW_XMMF64_XMMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTSD(instr)));
+        }
+        VSQRTSD::VSQRTSD_XMMF64_MASKMSKW_XMMF64_MEMF64_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTSD(instr)));
+        }
+    })
+}
+
+pub fn apply_iform_vsqrtss(arena: Arena, instr: VSQRTSS) -> Result<Vec<InstructionSemanticsStep>, SemanticsError> {
+    Ok(match instr {
+        VSQRTSS::VSQRTSS_XMMDQ_XMMDQ_MEMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_sqrt(FloatFormat::Single))
+        }
+        VSQRTSS::VSQRTSS_XMMDQ_XMMDQ_XMMD { operand_0, operand_1, operand_2 } => {
+            vex_scalar_generic(arena, FloatFormat::Single, operand_0.widen_to_zmm(), operand_1, operand_2, lane_sqrt(FloatFormat::Single))
+        }
+        VSQRTSS::VSQRTSS_XMMF32_MASKMSKW_XMMF32_XMMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTSS(instr)));
+        }
+        VSQRTSS::VSQRTSS_XMMF32_MASKMSKW_XMMF32_MEMF32_AVX512 { .. } => {
+            return Err(SemanticsError::Unsupported(X86Instruction::VSQRTSS(instr)));
+        }
+    })
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::ptr;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::expression::{ArithmeticOp, BitWiseOp, ComparisonOp, Expression, ShiftOp, Signedness};
+use crate::semantics2::semantic_steps::{ConcreteExecution, InstructionSemanticsStep};
+use crate::semantics2::state::ConcreteX86MachineState64;
+use crate::semantics2::value::Value;
+
+/// Rewrites expressions bottom up into equivalent ones which are no bigger. New nodes come from `arena`, which
+/// should share equal expressions, since operands like the two sides of `x ^ x` are only spotted as equal by
+/// address.
+pub struct Simplifier<'arena> {
+    arena: Arena<'arena>,
+    simplified: HashMap<*const Expression<'arena>, &'arena Expression<'arena>>,
+    can_fault: HashMap<*const Expression<'arena>, bool>,
+    /// Expressions with constant operands are folded by evaluating them, which reads nothing from this.
+    folding: ConcreteExecution,
+}
+
+fn constant_value<'arena>(expr: &'arena Expression<'arena>) -> Option<&'arena Value<'arena>> {
+    match expr {
+        Expression::Constant { value } => Some(value),
+        _ => None,
+    }
+}
+
+impl<'arena> Simplifier<'arena> {
+    pub fn new(arena: Arena<'arena>) -> Self {
+        Self {
+            arena,
+            simplified: HashMap::new(),
+            can_fault: HashMap::new(),
+            folding: ConcreteExecution::new(ConcreteX86MachineState64::zeroed()),
+        }
+    }
+
+    pub fn simplify(&mut self, expr: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        let key = expr as *const Expression<'arena>;
+        if let Some(&simplified) = self.simplified.get(&key) {
+            return simplified;
+        }
+        let rebuilt = expr.map_children(|child| self.simplify(child));
+        let res = self.rewrite(rebuilt);
+        self.simplified.insert(key, res);
+        res
+    }
+
+    /// Whether evaluating `expr` can raise #DE. Rules which drop an operand only do so if it can't, so that
+    /// simplifying never loses a fault.
+    fn can_fault(&mut self, expr: &'arena Expression<'arena>) -> bool {
+        let key = expr as *const Expression<'arena>;
+        if let Some(&can_fault) = self.can_fault.get(&key) {
+            return can_fault;
+        }
+        let res = self.operands_can_fault(expr) || match expr {
+            Expression::IntArithmetic { op: ArithmeticOp::Div | ArithmeticOp::Rem, right, .. } => {
+                constant_value(right).map_or(true, |divisor| divisor.is_zero())
+            }
+            _ => false,
+        };
+        self.can_fault.insert(key, res);
+        res
+    }
+
+    fn operands_can_fault(&mut self, expr: &Expression<'arena>) -> bool {
+        let mut res = false;
+        expr.map_children(|child| {
+            res |= self.can_fault(child);
+            child
+        });
+        res
+    }
+
+    fn constant(&self, value: Value<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::Constant { value: self.arena.a(value) })
+    }
+
+    fn zero(&self, width: usize) -> &'arena Expression<'arena> {
+        self.constant(Value::zero(width))
+    }
+
+    /// Integer expressions whose operands are all constants, evaluated. Floating point ones are left alone, since
+    /// the exceptions they raise are read off the expression itself.
+    fn fold_constants(&self, expr: &Expression<'arena>) -> Option<&'arena Expression<'arena>> {
+        let foldable = matches!(expr,
+            Expression::BitWise { .. } | Expression::IntCompare { .. } | Expression::IntArithmetic { .. } |
+            Expression::Extract { .. } | Expression::Concat { .. } | Expression::Conditional { .. } |
+            Expression::ZeroExtend { .. } | Expression::SignExtend { .. } | Expression::Shift { .. } |
+            Expression::BitCount { .. } | Expression::LowerBits { .. } | Expression::UpperBits { .. } |
+            Expression::ChangeRange { .. });
+        let mut operands_constant = true;
+        expr.map_children(|child| {
+            operands_constant &= constant_value(child).is_some();
+            child
+        });
+        if !foldable || !operands_constant {
+            return None;
+        }
+        // dividing by zero is left for the evaluation of the steps to fault on
+        let value = expr.apply_concrete(&self.folding).ok()?;
+        Some(self.constant(value))
+    }
+
+    /// `expr`, whose operands are already simplified, simplified.
+    fn rewrite(&mut self, expr: Expression<'arena>) -> &'arena Expression<'arena> {
+        if let Some(folded) = self.fold_constants(&expr) {
+            return folded;
+        }
+        // conditionals only evaluate the value they pick, so those are always safe to drop
+        let can_drop = !self.operands_can_fault(&expr);
+        match expr {
+            Expression::Conditional { condition, true_value, false_value } => {
+                if let Some(condition) = constant_value(condition) {
+                    return if condition.is_true() { true_value } else { false_value };
+                }
+                if ptr::eq(true_value, false_value) && can_drop {
+                    return true_value;
+                }
+                if let (Some(true_value), Some(false_value)) = (constant_value(true_value), constant_value(false_value)) {
+                    if condition.width() == 1 && true_value.width() == 1 && false_value.width() == 1 && true_value != false_value {
+                        return if true_value.is_true() { condition } else { self.rewrite_bitwise(BitWiseOp::Xor, condition, self.constant(Value::ones(1)), can_drop) };
+                    }
+                }
+            }
+            Expression::LowerBits { value, len } => {
+                return self.rewrite(Expression::Extract { value, low: 0, high: len });
+            }
+            Expression::UpperBits { value, len } => {
+                return self.rewrite(Expression::Extract { value, low: value.width() - len, high: value.width() });
+            }
+            Expression::Extract { value, low, high } if can_drop => {
+                if low == 0 && high == value.width() {
+                    return value;
+                }
+                match *value {
+                    Expression::Concat { left, right } => {
+                        let right_width = right.width();
+                        if high <= right_width {
+                            return self.rewrite(Expression::Extract { value: right, low, high });
+                        }
+                        if low >= right_width {
+                            return self.rewrite(Expression::Extract { value: left, low: low - right_width, high: high - right_width });
+                        }
+                    }
+                    Expression::Extract { value: inner, low: inner_low, .. } => {
+                        return self.rewrite(Expression::Extract { value: inner, low: inner_low + low, high: inner_low + high });
+                    }
+                    Expression::ZeroExtend { value: inner, .. } | Expression::SignExtend { value: inner, .. } if high <= inner.width() => {
+                        return self.rewrite(Expression::Extract { value: inner, low, high });
+                    }
+                    Expression::ZeroExtend { value: inner, .. } if low >= inner.width() => {
+                        return self.zero(high - low);
+                    }
+                    Expression::Shift { op: ShiftOp::LShr, value: inner, amount } => {
+                        if let Some(amount) = constant_value(amount).filter(|amount| amount.width() <= 64) {
+                            let amount = amount.to_u64() as usize;
+                            if amount <= inner.width() - high {
+                                return self.rewrite(Expression::Extract { value: inner, low: low + amount, high: high + amount });
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Expression::ZeroExtend { value, len } | Expression::SignExtend { value, len } if len == value.width() => {
+                return value;
+            }
+            Expression::ZeroExtend { value, len } => {
+                if let Expression::ZeroExtend { value: inner, .. } = *value {
+                    return self.rewrite(Expression::ZeroExtend { value: inner, len });
+                }
+            }
+            Expression::SignExtend { value, len } => {
+                match *value {
+                    Expression::SignExtend { value: inner, .. } => {
+                        return self.rewrite(Expression::SignExtend { value: inner, len });
+                    }
+                    // the zero extension already made the sign bit clear
+                    Expression::ZeroExtend { value: inner, .. } => {
+                        return self.rewrite(Expression::ZeroExtend { value: inner, len });
+                    }
+                    _ => {}
+                }
+            }
+            Expression::Concat { left, right } => {
+                if let (Expression::Extract { value: high_value, low: middle, high }, Expression::Extract { value: low_value, low, high: low_high }) = (*left, *right) {
+                    if ptr::eq(high_value, low_value) && middle == low_high {
+                        return self.rewrite(Expression::Extract { value: high_value, low, high });
+                    }
+                }
+            }
+            Expression::ChangeRange { value, range_start_inclusive: 0, range_end_exclusive, new_value } if range_end_exclusive == value.width() && can_drop => {
+                return new_value;
+            }
+            Expression::BitWise { op, left, right } if left.width() == right.width() => {
+                return self.rewrite_bitwise(op, left, right, can_drop);
+            }
+            Expression::IntArithmetic { op, signedness, left, right } if left.width() == right.width() => {
+                let width = left.width();
+                let is_zero = |expr: &'arena Expression<'arena>| constant_value(expr).map_or(false, |value| value.is_zero());
+                match op {
+                    ArithmeticOp::Add if is_zero(left) => return right,
+                    ArithmeticOp::Add | ArithmeticOp::Sub if is_zero(right) => return left,
+                    ArithmeticOp::Sub if ptr::eq(left, right) && can_drop => return self.zero(width),
+                    ArithmeticOp::Sub if is_zero(left) => {
+                        // double negation
+                        if let Expression::IntArithmetic { op: ArithmeticOp::Sub, left: inner_left, right: inner, .. } = *right {
+                            if is_zero(inner_left) && inner_left.width() == width && inner.width() == width {
+                                return inner;
+                            }
+                        }
+                    }
+                    ArithmeticOp::Add | ArithmeticOp::Sub => {
+                        if let Some(res) = self.rewrite_offsets(op, signedness, left, right, can_drop) {
+                            return res;
+                        }
+                    }
+                    _ => {}
+                }
+                return self.arena.expr(Expression::IntArithmetic { op, signedness, left, right });
+            }
+            Expression::IntCompare { op, left, right, .. } if ptr::eq(left, right) && can_drop => {
+                let holds = matches!(op, ComparisonOp::LessOrEqual | ComparisonOp::Equal | ComparisonOp::GreaterOrEqual);
+                return self.constant(Value::from_bool(holds));
+            }
+            Expression::Shift { value, amount, .. } if constant_value(amount).map_or(false, |amount| amount.is_zero()) => {
+                return value;
+            }
+            _ => {}
+        }
+        self.arena.expr(expr)
+    }
+
+    /// `expr` as a base plus a constant offset of the same width, the offset being zero if `expr` isn't the sum
+    /// or difference of something and a constant.
+    fn split_offset(&mut self, expr: &'arena Expression<'arena>) -> (&'arena Expression<'arena>, &'arena Expression<'arena>) {
+        let width = expr.width();
+        if let Expression::IntArithmetic { op, left, right, .. } = *expr {
+            if left.width() == width && right.width() == width {
+                match op {
+                    ArithmeticOp::Add if constant_value(right).is_some() => return (left, right),
+                    ArithmeticOp::Add if constant_value(left).is_some() => return (right, left),
+                    ArithmeticOp::Sub if constant_value(right).is_some() => {
+                        let zero = self.zero(width);
+                        return (left, self.fold(ArithmeticOp::Sub, zero, right));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (expr, self.zero(width))
+    }
+
+    /// `left` op `right` for constant operands, folded.
+    fn fold(&mut self, op: ArithmeticOp, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.rewrite(Expression::IntArithmetic { op, signedness: Signedness::Unsigned, left, right })
+    }
+
+    /// Sums and differences of constant offsets from the same base, like the addresses of neighbouring stack
+    /// slots, with the offsets combined.
+    fn rewrite_offsets(&mut self, op: ArithmeticOp, signedness: Signedness, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>, can_drop: bool) -> Option<&'arena Expression<'arena>> {
+        let (left_base, left_offset) = self.split_offset(left);
+        let (right_base, right_offset) = self.split_offset(right);
+        if op == ArithmeticOp::Sub && ptr::eq(left_base, right_base) && can_drop {
+            return Some(self.fold(ArithmeticOp::Sub, left_offset, right_offset));
+        }
+        if constant_value(right).is_some() && !ptr::eq(left_base, left) {
+            let offset = self.fold(op, left_offset, right);
+            return Some(self.rewrite(Expression::IntArithmetic { op: ArithmeticOp::Add, signedness, left: left_base, right: offset }));
+        }
+        if op == ArithmeticOp::Add && constant_value(left).is_some() && !ptr::eq(right_base, right) {
+            let offset = self.fold(ArithmeticOp::Add, right_offset, left);
+            return Some(self.rewrite(Expression::IntArithmetic { op: ArithmeticOp::Add, signedness, left: right_base, right: offset }));
+        }
+        None
+    }
+
+    /// Bitwise operations on operands of the same width, for which zero and all ones are identities or absorb.
+    fn rewrite_bitwise(&mut self, op: BitWiseOp, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>, can_drop: bool) -> &'arena Expression<'arena> {
+        let width = left.width();
+        // constants go on the right
+        let (left, right) = if constant_value(left).is_some() { (right, left) } else { (left, right) };
+        if ptr::eq(left, right) {
+            match op {
+                BitWiseOp::Xor if can_drop => return self.zero(width),
+                BitWiseOp::Xor => {}
+                BitWiseOp::And | BitWiseOp::Or => return left,
+            }
+        }
+        if let Some(constant) = constant_value(right) {
+            let ones = *constant == Value::ones(width);
+            match op {
+                BitWiseOp::And if constant.is_zero() && can_drop => return right,
+                BitWiseOp::Or if ones && can_drop => return right,
+                BitWiseOp::And if ones => return left,
+                BitWiseOp::Or | BitWiseOp::Xor if constant.is_zero() => return left,
+                _ => {}
+            }
+            // double negation, or more generally xors with constants combining
+            if let (BitWiseOp::Xor, Expression::BitWise { op: BitWiseOp::Xor, left: inner, right: inner_right }) = (op, *left) {
+                if let Some(inner_constant) = constant_value(inner_right) {
+                    if inner.width() == width && inner_constant.width() == width {
+                        let combined = self.constant(inner_constant.bitwise(constant, |a, b| a ^ b));
+                        return self.rewrite_bitwise(BitWiseOp::Xor, inner, combined, can_drop);
+                    }
+                }
+            }
+        }
+        self.arena.expr(Expression::BitWise { op, left, right })
+    }
+}
+
+/// `steps` with all of their expressions simplified. Steps themselves are kept as they are, even conditionals
+/// on a constant, since expressions refer to steps by index.
+pub fn simplify_steps<'arena>(arena: Arena<'arena>, steps: Vec<InstructionSemanticsStep<'arena>>) -> Vec<InstructionSemanticsStep<'arena>> {
+    let mut simplifier = Simplifier::new(arena);
+    steps.into_iter()
+        .map(|step| step.map_expressions(&mut |expr| simplifier.simplify(expr)))
+        .collect()
+}
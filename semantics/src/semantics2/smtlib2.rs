@@ -0,0 +1,624 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use wrapper_common::registers::{Reg64WithRIP, RegSegment, RegSegmentBase, RegZMM};
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::{ArithmeticOp, BitCountOp, BitWiseOp, ComparisonOp, Expression, Flag, ShiftOp, Signedness};
+use crate::semantics2::semantic_steps::{loaded_segment_base, InstructionSemanticsStep, ZeroUpper};
+use crate::semantics2::state::ConcreteX86MachineState64;
+use crate::semantics2::value::Value;
+
+/// A piece of machine state which is an input or output of an exported script.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum StateComponent {
+    Gpr(Reg64WithRIP),
+    Flag(Flag),
+    Zmm(RegZMM),
+    Mxcsr,
+    SegmentBase(RegSegmentBase),
+    /// The 16 bit selector.
+    Segment(RegSegment),
+    /// Byte addressed, as an array from 64 bit addresses to bytes.
+    Memory,
+}
+
+impl StateComponent {
+    /// Name of the constant holding the value before the steps run.
+    pub fn name(&self) -> String {
+        match self {
+            StateComponent::Gpr(reg) => format!("{reg:?}").to_lowercase(),
+            StateComponent::Flag(flag) => format!("{flag:?}").to_lowercase(),
+            StateComponent::Zmm(reg) => format!("{reg:?}").to_lowercase(),
+            StateComponent::Mxcsr => "mxcsr".to_string(),
+            StateComponent::SegmentBase(RegSegmentBase::FSBase) => "fs_base".to_string(),
+            StateComponent::SegmentBase(RegSegmentBase::GSBase) => "gs_base".to_string(),
+            StateComponent::Segment(segment) => format!("{segment:?}").to_lowercase(),
+            StateComponent::Memory => "mem".to_string(),
+        }
+    }
+
+    /// Name of the function giving the value after the steps run.
+    pub fn output_name(&self) -> String {
+        format!("{}_out", self.name())
+    }
+
+    pub fn sort(&self) -> String {
+        match self {
+            StateComponent::Gpr(_) | StateComponent::SegmentBase(_) => bitvec_sort(64),
+            StateComponent::Flag(_) => bitvec_sort(1),
+            StateComponent::Zmm(_) => bitvec_sort(512),
+            StateComponent::Mxcsr => bitvec_sort(32),
+            StateComponent::Segment(_) => bitvec_sort(16),
+            StateComponent::Memory => format!("(Array {} {})", bitvec_sort(64), bitvec_sort(8)),
+        }
+    }
+
+    /// Term for this component's value in `state`, for binding a script's inputs or checking its outputs.
+    pub fn value_in(&self, state: &ConcreteX86MachineState64) -> String {
+        match self {
+            StateComponent::Gpr(reg) => constant(state.gpr64(*reg), 64),
+            StateComponent::Flag(flag) => constant(state.get_flag(*flag) as u64, 1),
+            StateComponent::Zmm(reg) => literal(&state.get_zmm(*reg)),
+            StateComponent::Mxcsr => constant(state.mxcsr as u64, 32),
+            StateComponent::SegmentBase(base) => constant(state.segment_base(*base), 64),
+            StateComponent::Segment(segment) => constant(state.get_segment(*segment) as u64, 16),
+            StateComponent::Memory => {
+                let zeroed = format!("((as const {}) #x00)", self.sort());
+                state.memory.iter().fold(zeroed, |memory, (address, byte)| {
+                    format!("(store {memory} {} {})", constant(*address, 64), constant(*byte as u64, 8))
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SmtLib2Error {
+    /// Floating point values and the x87 stack have no bitvector counterpart, so semantics using them can't be
+    /// exported.
+    Unsupported(&'static str),
+}
+
+/// An SMT-LIB2 script declaring a constant for every input the steps read and defining a function for every
+/// output they write, along with `fault`, which is true when the steps raise an exception. The outputs are
+/// only meaningful when `fault` is false. There are no assertions or `check-sat`, those are up to the user.
+pub struct SmtLib2Script {
+    pub text: String,
+    pub inputs: Vec<StateComponent>,
+    pub outputs: Vec<StateComponent>,
+}
+
+fn bitvec_sort(width: usize) -> String {
+    format!("(_ BitVec {width})")
+}
+
+/// `value` as an SMT-LIB2 bitvector literal, hexadecimal when the width allows for it and binary otherwise.
+pub fn literal(value: &Value) -> String {
+    let width = value.width();
+    assert_ne!(width, 0, "bitvectors can't be empty");
+    if width % 4 == 0 {
+        let words = value.to_u64s();
+        let digits = (0..width / 4).rev()
+            .map(|i| std::char::from_digit(((words[i / 16] >> (i % 16 * 4)) & 0xF) as u32, 16).unwrap())
+            .collect::<String>();
+        format!("#x{digits}")
+    } else {
+        let digits = (0..width).rev().map(|i| if value.bit(i) { '1' } else { '0' }).collect::<String>();
+        format!("#b{digits}")
+    }
+}
+
+fn constant(value: u64, width: usize) -> String {
+    literal(&Value::from_u128(value as u128, width))
+}
+
+fn extract(term: &str, low: usize, high_exclusive: usize) -> String {
+    format!("((_ extract {} {low}) {term})", high_exclusive - 1)
+}
+
+fn extend(term: &str, from: usize, to: usize, signed: bool) -> String {
+    assert!(to >= from);
+    if to == from {
+        return term.to_string();
+    }
+    let op = if signed { "sign_extend" } else { "zero_extend" };
+    format!("((_ {op} {}) {term})", to - from)
+}
+
+/// Bool to a one bit bitvector.
+fn bit(condition: &str) -> String {
+    format!("(ite {condition} #b1 #b0)")
+}
+
+/// One bit bitvector to a Bool.
+fn is_set(term: &str) -> String {
+    format!("(= {term} #b1)")
+}
+
+/// `op` applied pairwise from the left, as the binary forms are all QF_BV guarantees.
+fn left_assoc(op: &str, terms: Vec<String>) -> String {
+    let mut terms = terms.into_iter();
+    let first = terms.next().unwrap();
+    terms.fold(first, |acc, term| format!("({op} {acc} {term})"))
+}
+
+/// The bits of `value`, which is `width` wide, with `start` up to `start + new_width` replaced by `new_value`.
+fn with_range(value: &str, width: usize, start: usize, new_value: &str, new_width: usize) -> String {
+    let end = start + new_width;
+    let mut pieces = vec![];
+    if end < width {
+        pieces.push(extract(value, end, width));
+    }
+    pieces.push(new_value.to_string());
+    if start > 0 {
+        pieces.push(extract(value, 0, start));
+    }
+    left_assoc("concat", pieces)
+}
+
+fn or(conditions: impl IntoIterator<Item=Option<String>>) -> Option<String> {
+    let conditions = conditions.into_iter().flatten().collect::<Vec<_>>();
+    if conditions.is_empty() {
+        None
+    } else {
+        Some(left_assoc("or", conditions))
+    }
+}
+
+#[derive(Clone)]
+struct Translated {
+    term: String,
+    /// When evaluating the expression divides by zero, which the concrete evaluator raises #DE for.
+    divide_by_zero: Option<String>,
+}
+
+type SymbolicState = BTreeMap<StateComponent, String>;
+
+struct Exporter<'arena> {
+    builder: SemanticsBuilder<'arena>,
+    definitions: String,
+    next_definition: usize,
+    inputs: BTreeSet<StateComponent>,
+    /// Terms of everything written so far, anything missing still has its input value.
+    current: SymbolicState,
+    /// Like [`crate::semantics2::semantic_steps::ConcreteExecution`], the state before each step, for reads to
+    /// see the state as of the step they were emitted before.
+    history: Vec<(usize, SymbolicState)>,
+    /// When the steps being exported run.
+    path: String,
+    fault: String,
+    translated: HashMap<*const Expression<'arena>, Translated>,
+}
+
+impl<'arena> Exporter<'arena> {
+    fn new(arena: Arena<'arena>) -> Self {
+        Self {
+            builder: SemanticsBuilder::new(arena),
+            definitions: String::new(),
+            next_definition: 0,
+            inputs: BTreeSet::new(),
+            current: SymbolicState::new(),
+            history: vec![],
+            path: "true".to_string(),
+            fault: "false".to_string(),
+            translated: HashMap::new(),
+        }
+    }
+
+    /// Names `term`, so that every expression is written out once however often it is used.
+    fn define(&mut self, sort: &str, term: String) -> String {
+        let name = format!("t{}", self.next_definition);
+        self.next_definition += 1;
+        writeln!(self.definitions, "(define-fun {name} () {sort} {term})").unwrap();
+        name
+    }
+
+    fn read_from(&mut self, state: Option<&SymbolicState>, component: StateComponent) -> String {
+        match state.unwrap_or(&self.current).get(&component) {
+            Some(term) => term.clone(),
+            None => {
+                self.inputs.insert(component);
+                component.name()
+            }
+        }
+    }
+
+    fn read(&mut self, at_index: usize, component: StateComponent) -> String {
+        let state = self.history.iter()
+            .find(|(index, _)| *index >= at_index)
+            .map(|(_, state)| state.clone());
+        self.read_from(state.as_ref(), component)
+    }
+
+    fn read_current(&mut self, component: StateComponent) -> String {
+        self.read_from(None, component)
+    }
+
+    fn write(&mut self, component: StateComponent, term: String) {
+        let name = self.define(&component.sort(), term);
+        self.current.insert(component, name);
+    }
+
+    fn raise_fault_if(&mut self, condition: Option<String>) {
+        if let Some(condition) = condition {
+            let fault = format!("(or {} (and {} {condition}))", self.fault, self.path);
+            self.fault = self.define("Bool", fault);
+        }
+    }
+
+    fn expression(&mut self, expr: &'arena Expression<'arena>) -> Result<Translated, SmtLib2Error> {
+        let key = expr as *const Expression<'arena>;
+        if let Some(translated) = self.translated.get(&key) {
+            return Ok(translated.clone());
+        }
+        let (term, divide_by_zero) = self.expression_uncached(expr)?;
+        let term = match expr {
+            Expression::GetReg { .. } | Expression::GetFlag { .. } | Expression::GetVectorReg { .. } | Expression::Constant { .. } |
+            Expression::GetMxcsr { .. } | Expression::SegmentBase { .. } | Expression::GetSegment { .. } => term,
+            _ => self.define(&bitvec_sort(expr.width()), term),
+        };
+        let translated = Translated { term, divide_by_zero };
+        self.translated.insert(key, translated.clone());
+        Ok(translated)
+    }
+
+    fn expression_uncached(&mut self, expr: &'arena Expression<'arena>) -> Result<(String, Option<String>), SmtLib2Error> {
+        let width = expr.width();
+        Ok(match expr {
+            Expression::GetReg { reg, at_index } => {
+                let (parent, low, high) = ConcreteX86MachineState64::gpr_location(*reg);
+                let full = self.read(*at_index, StateComponent::Gpr(parent));
+                if low == 0 && high == 64 {
+                    (full, None)
+                } else {
+                    (extract(&full, low, high), None)
+                }
+            }
+            Expression::GetFlag { flag, at_index } => {
+                (self.read(*at_index, StateComponent::Flag(*flag)), None)
+            }
+            Expression::GetVectorReg { reg, at_index } => {
+                (self.read(*at_index, StateComponent::Zmm(*reg)), None)
+            }
+            Expression::Constant { value } => {
+                (literal(value), None)
+            }
+            Expression::BitWise { op, left, right } => {
+                let left_width = left.width();
+                let right_width = right.width();
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                let op = match op {
+                    BitWiseOp::And => "bvand",
+                    BitWiseOp::Or => "bvor",
+                    BitWiseOp::Xor => "bvxor",
+                };
+                let term = format!("({op} {} {})", extend(&left.term, left_width, width, false), extend(&right.term, right_width, width, false));
+                (term, or([left.divide_by_zero, right.divide_by_zero]))
+            }
+            Expression::IntCompare { op, signedness, left, right } => {
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                let op = match (op, signedness) {
+                    (ComparisonOp::Equal, _) => "=",
+                    (ComparisonOp::Less, Signedness::Unsigned) => "bvult",
+                    (ComparisonOp::Less, Signedness::Signed) => "bvslt",
+                    (ComparisonOp::LessOrEqual, Signedness::Unsigned) => "bvule",
+                    (ComparisonOp::LessOrEqual, Signedness::Signed) => "bvsle",
+                    (ComparisonOp::GreaterOrEqual, Signedness::Unsigned) => "bvuge",
+                    (ComparisonOp::GreaterOrEqual, Signedness::Signed) => "bvsge",
+                    (ComparisonOp::Greater, Signedness::Unsigned) => "bvugt",
+                    (ComparisonOp::Greater, Signedness::Signed) => "bvsgt",
+                };
+                (bit(&format!("({op} {} {})", left.term, right.term)), or([left.divide_by_zero, right.divide_by_zero]))
+            }
+            Expression::IntArithmetic { op, signedness, left, right } => {
+                let signed = matches!(signedness, Signedness::Signed);
+                let left_width = left.width();
+                let right_width = right.width();
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                let left_term = extend(&left.term, left_width, width, signed);
+                let right_term = extend(&right.term, right_width, width, signed);
+                let op = match (op, signedness) {
+                    (ArithmeticOp::Add, _) => "bvadd",
+                    (ArithmeticOp::Sub, _) => "bvsub",
+                    (ArithmeticOp::Mul, _) => "bvmul",
+                    (ArithmeticOp::Div, Signedness::Unsigned) => "bvudiv",
+                    (ArithmeticOp::Div, Signedness::Signed) => "bvsdiv",
+                    (ArithmeticOp::Rem, Signedness::Unsigned) => "bvurem",
+                    (ArithmeticOp::Rem, Signedness::Signed) => "bvsrem",
+                };
+                let divide_by_zero = matches!(op, "bvudiv" | "bvsdiv" | "bvurem" | "bvsrem")
+                    .then(|| format!("(= {right_term} {})", constant(0, width)));
+                (format!("({op} {left_term} {right_term})"), or([left.divide_by_zero, right.divide_by_zero, divide_by_zero]))
+            }
+            Expression::Extract { value, low, high } => {
+                let value = self.expression(value)?;
+                (extract(&value.term, *low, *high), value.divide_by_zero)
+            }
+            Expression::Concat { left, right } => {
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                (format!("(concat {} {})", left.term, right.term), or([left.divide_by_zero, right.divide_by_zero]))
+            }
+            Expression::Conditional { condition, true_value, false_value } => {
+                let condition = self.expression(condition)?;
+                let true_value = self.expression(true_value)?;
+                let false_value = self.expression(false_value)?;
+                let taken = is_set(&condition.term);
+                // only the value chosen is evaluated
+                let divide_by_zero = or([
+                    condition.divide_by_zero,
+                    true_value.divide_by_zero.map(|divide_by_zero| format!("(and {taken} {divide_by_zero})")),
+                    false_value.divide_by_zero.map(|divide_by_zero| format!("(and (not {taken}) {divide_by_zero})")),
+                ]);
+                (format!("(ite {taken} {} {})", true_value.term, false_value.term), divide_by_zero)
+            }
+            Expression::ZeroExtend { value, len } | Expression::SignExtend { value, len } => {
+                let signed = matches!(expr, Expression::SignExtend { .. });
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                (extend(&value.term, value_width, *len, signed), value.divide_by_zero)
+            }
+            Expression::Shift { op, value, amount } => {
+                let amount_width = amount.width();
+                let value = self.expression(value)?;
+                let amount = self.expression(amount)?;
+                let divide_by_zero = or([value.divide_by_zero, amount.divide_by_zero]);
+                let (op, signed) = match op {
+                    ShiftOp::Shl => ("bvshl", false),
+                    ShiftOp::LShr => ("bvlshr", false),
+                    ShiftOp::AShr => ("bvashr", true),
+                };
+                // the SMT-LIB shifts also shift out every bit for amounts of the width or more, they only need
+                // the operands to be the same width
+                if amount_width <= width {
+                    (format!("({op} {} {})", value.term, extend(&amount.term, amount_width, width, false)), divide_by_zero)
+                } else {
+                    let shifted = format!("({op} {} {})", extend(&value.term, width, amount_width, signed), amount.term);
+                    (extract(&shifted, 0, width), divide_by_zero)
+                }
+            }
+            Expression::BitCount { op, value } => {
+                let value = self.expression(value)?;
+                let set = |i: usize| is_set(&extract(&value.term, i, i + 1));
+                let term = match op {
+                    BitCountOp::PopCount => {
+                        left_assoc("bvadd", (0..width).map(|i| extend(&extract(&value.term, i, i + 1), 1, width, false)).collect())
+                    }
+                    // the outermost ite is the last one folded in, so the bits are checked from the counted end
+                    BitCountOp::LeadingZeros => (0..width).fold(constant(width as u64, width), |acc, i| {
+                        format!("(ite {} {} {acc})", set(i), constant((width - 1 - i) as u64, width))
+                    }),
+                    BitCountOp::TrailingZeros => (0..width).rev().fold(constant(width as u64, width), |acc, i| {
+                        format!("(ite {} {} {acc})", set(i), constant(i as u64, width))
+                    }),
+                };
+                (term, value.divide_by_zero)
+            }
+            Expression::LowerBits { value, len } => {
+                let value = self.expression(value)?;
+                (extract(&value.term, 0, *len), value.divide_by_zero)
+            }
+            Expression::UpperBits { value, len } => {
+                let value_width = value.width();
+                let value = self.expression(value)?;
+                (extract(&value.term, value_width - len, value_width), value.divide_by_zero)
+            }
+            Expression::ChangeRange { value, range_start_inclusive, range_end_exclusive, new_value } => {
+                let value = self.expression(value)?;
+                let new_value = self.expression(new_value)?;
+                let term = with_range(&value.term, width, *range_start_inclusive, &new_value.term, range_end_exclusive - range_start_inclusive);
+                (term, or([value.divide_by_zero, new_value.divide_by_zero]))
+            }
+            Expression::GetMxcsr { at_index } => {
+                (self.read(*at_index, StateComponent::Mxcsr), None)
+            }
+            Expression::Load { address, width, at_index } => {
+                let address = self.expression(address)?;
+                let memory = self.read(*at_index, StateComponent::Memory);
+                let bytes = (0..width / 8).rev()
+                    .map(|i| format!("(select {memory} (bvadd {} {}))", address.term, constant(i as u64, 64)))
+                    .collect();
+                (left_assoc("concat", bytes), address.divide_by_zero)
+            }
+            Expression::SegmentBase { base, at_index } => {
+                (self.read(*at_index, StateComponent::SegmentBase(*base)), None)
+            }
+            Expression::GetSegment { segment, at_index } => {
+                (self.read(*at_index, StateComponent::Segment(*segment)), None)
+            }
+            Expression::FloatArithmetic { .. } |
+            Expression::FloatSqrt { .. } |
+            Expression::FloatFma { .. } |
+            Expression::FloatCompare { .. } |
+            Expression::FloatConvert { .. } |
+            Expression::IntToFloat { .. } |
+            Expression::FloatToInt { .. } |
+            Expression::FloatExceptionFlags { .. } => {
+                return Err(SmtLib2Error::Unsupported("floating point expressions"));
+            }
+            Expression::GetX87Register { .. } |
+            Expression::X87RegisterEmpty { .. } |
+            Expression::GetX87Control { .. } |
+            Expression::GetX87Status { .. } |
+            Expression::X87Arithmetic { .. } |
+            Expression::X87Compare { .. } |
+            Expression::X87FromFloat { .. } |
+            Expression::X87ToFloat { .. } |
+            Expression::X87FromInt { .. } |
+            Expression::X87ToInt { .. } |
+            Expression::X87RoundedUp { .. } => {
+                return Err(SmtLib2Error::Unsupported("the x87 stack"));
+            }
+        })
+    }
+
+    /// Translates `expr` and raises #DE on the current path if it divides by zero.
+    fn value(&mut self, expr: &'arena Expression<'arena>) -> Result<String, SmtLib2Error> {
+        let translated = self.expression(expr)?;
+        self.raise_fault_if(translated.divide_by_zero);
+        Ok(translated.term)
+    }
+
+    /// Exports `steps`, the first of which is numbered `first_index`.
+    fn run(&mut self, steps: &'arena [InstructionSemanticsStep<'arena>], first_index: usize) -> Result<(), SmtLib2Error> {
+        let mut index = first_index;
+        for step in steps {
+            match step {
+                InstructionSemanticsStep::Conditional { condition, true_semantics, false_semantics } => {
+                    let condition = self.value(condition)?;
+                    let taken = self.define("Bool", is_set(&condition));
+                    let path = self.path.clone();
+                    let before = self.current.clone();
+                    let history_len = self.history.len();
+
+                    self.path = self.define("Bool", format!("(and {path} {taken})"));
+                    self.run(true_semantics, index)?;
+                    let after_true = std::mem::replace(&mut self.current, before);
+                    // the concrete evaluator only ever runs one of the branches
+                    self.history.truncate(history_len);
+
+                    self.path = self.define("Bool", format!("(and {path} (not {taken}))"));
+                    self.run(false_semantics, index + true_semantics.iter().map(|step| step.step_count()).sum::<usize>())?;
+                    let after_false = std::mem::take(&mut self.current);
+
+                    self.path = path;
+                    let components = after_true.keys().chain(after_false.keys()).copied().collect::<BTreeSet<_>>();
+                    for component in components {
+                        let true_term = self.read_from(Some(&after_true), component);
+                        let false_term = self.read_from(Some(&after_false), component);
+                        if true_term == false_term {
+                            self.current.insert(component, true_term);
+                        } else {
+                            self.write(component, format!("(ite {taken} {true_term} {false_term})"));
+                        }
+                    }
+                }
+                // nothing can read the state from before the advance, so there's no need to keep it
+                InstructionSemanticsStep::AdvanceRip { .. } => {
+                    self.step(step, index)?;
+                }
+                step => {
+                    self.history.push((index, self.current.clone()));
+                    self.step(step, index)?;
+                }
+            }
+            index += step.step_count();
+        }
+        Ok(())
+    }
+
+    /// Exports `step`, which is numbered `index`.
+    fn step(&mut self, step: &'arena InstructionSemanticsStep<'arena>, index: usize) -> Result<(), SmtLib2Error> {
+        match step {
+            InstructionSemanticsStep::Conditional { .. } => {
+                unreachable!()
+            }
+            InstructionSemanticsStep::SetRegister { zero_upper, register, value } => {
+                let value = self.value(value)?;
+                let (parent, low, high) = ConcreteX86MachineState64::gpr_location(*register);
+                let component = StateComponent::Gpr(parent);
+                let old = match zero_upper {
+                    ZeroUpper::ZeroUpper => constant(0, 64),
+                    ZeroUpper::NoZeroUpper => self.read_current(component),
+                };
+                self.write(component, with_range(&old, 64, low, &value, high - low));
+            }
+            InstructionSemanticsStep::SetVectorRegister { register, value } => {
+                let value = self.value(value)?;
+                self.write(StateComponent::Zmm(*register), value);
+            }
+            InstructionSemanticsStep::SetFlag { flag, value } => {
+                let value = self.value(value)?;
+                self.write(StateComponent::Flag(*flag), value);
+            }
+            InstructionSemanticsStep::InstructionSyncPoint { .. } => {}
+            InstructionSemanticsStep::UndefinedException |
+            InstructionSemanticsStep::DivideError |
+            InstructionSemanticsStep::GeneralProtection |
+            InstructionSemanticsStep::FloatingPointError => {
+                self.raise_fault_if(Some("true".to_string()));
+            }
+            InstructionSemanticsStep::CalculateFlags { flag_tag, left, right } => {
+                let carry = self.builder.a(Expression::GetFlag { flag: Flag::CF, at_index: index });
+                let flags = self.builder.flag_values(flag_tag, left, right, carry).into_iter()
+                    .map(|(flag, value)| Ok((flag, self.value(value)?)))
+                    .collect::<Result<Vec<_>, SmtLib2Error>>()?;
+                for (flag, value) in flags {
+                    self.write(StateComponent::Flag(flag), value);
+                }
+            }
+            InstructionSemanticsStep::Store { address, value } => {
+                let width = value.width();
+                let address = self.value(address)?;
+                let value = self.value(value)?;
+                let memory = self.read_current(StateComponent::Memory);
+                let stored = (0..width / 8).fold(memory, |memory, i| {
+                    format!("(store {memory} (bvadd {address} {}) {})", constant(i as u64, 64), extract(&value, i * 8, i * 8 + 8))
+                });
+                self.write(StateComponent::Memory, stored);
+            }
+            InstructionSemanticsStep::RaiseFloatExceptions { exceptions } => {
+                let exceptions = self.value(exceptions)?;
+                let mxcsr = self.read_current(StateComponent::Mxcsr);
+                let unmasked = format!("(bvand {exceptions} (bvnot {}))", extract(&mxcsr, 7, 13));
+                self.raise_fault_if(Some(format!("(not (= {unmasked} #b000000))")));
+                self.write(StateComponent::Mxcsr, format!("(bvor {mxcsr} {})", extend(&exceptions, 6, 32, false)));
+            }
+            InstructionSemanticsStep::SetMxcsr { value } => {
+                let value = self.value(value)?;
+                self.write(StateComponent::Mxcsr, value);
+            }
+            InstructionSemanticsStep::SetSegment { segment, value } => {
+                let value = self.value(value)?;
+                self.write(StateComponent::Segment(*segment), value);
+                if let Some(base) = loaded_segment_base(*segment) {
+                    self.write(StateComponent::SegmentBase(base), constant(0, 64));
+                }
+            }
+            InstructionSemanticsStep::SetX87Register { .. } |
+            InstructionSemanticsStep::X87Push |
+            InstructionSemanticsStep::X87Pop |
+            InstructionSemanticsStep::SetX87Control { .. } |
+            InstructionSemanticsStep::SetX87Status { .. } => {
+                return Err(SmtLib2Error::Unsupported("the x87 stack"));
+            }
+            InstructionSemanticsStep::AdvanceRip { length } => {
+                let rip = self.read_current(StateComponent::Gpr(Reg64WithRIP::RIP));
+                self.write(StateComponent::Gpr(Reg64WithRIP::RIP), format!("(bvadd {rip} {})", constant(*length as u64, 64)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Exports `steps` as a QF_BV script, or QF_ABV if they touch memory. See [`SmtLib2Script`].
+pub fn to_smtlib2<'arena>(arena: Arena<'arena>, steps: &'arena [InstructionSemanticsStep<'arena>]) -> Result<SmtLib2Script, SmtLib2Error> {
+    let mut exporter = Exporter::new(arena);
+    exporter.run(steps, 0)?;
+    let inputs = exporter.inputs.iter().copied().collect::<Vec<_>>();
+    let outputs = exporter.current.iter()
+        .filter(|(component, term)| **term != component.name())
+        .map(|(component, _)| *component)
+        .collect::<Vec<_>>();
+    let logic = if inputs.contains(&StateComponent::Memory) || outputs.contains(&StateComponent::Memory) {
+        "QF_ABV"
+    } else {
+        "QF_BV"
+    };
+    let mut text = String::new();
+    writeln!(text, "(set-logic {logic})").unwrap();
+    for input in inputs.iter() {
+        writeln!(text, "(declare-const {} {})", input.name(), input.sort()).unwrap();
+    }
+    text.push_str(exporter.definitions.as_str());
+    for output in outputs.iter() {
+        writeln!(text, "(define-fun {} () {} {})", output.output_name(), output.sort(), exporter.current[output]).unwrap();
+    }
+    writeln!(text, "(define-fun fault () Bool {})", exporter.fault).unwrap();
+    Ok(SmtLib2Script { text, inputs, outputs })
+}
@@ -1,43 +1,355 @@
+use std::collections::BTreeMap;
+
 use wrapper_common::memory_operand::GeneralReg;
+use wrapper_common::registers::{Reg64WithRIP, RegSegment, RegSegmentBase, Register, RegZMM};
 use crate::semantics2::arena::Arena;
-use crate::semantics2::expression::Expression;
-use crate::x86_machine::X86Mode;
+use crate::semantics2::expression::{ArithmeticOp, ComparisonOp, Expression, Flag, ShiftOp, Signedness};
+use crate::semantics2::float::Mxcsr;
+use crate::semantics2::semantic_steps::ZeroUpper;
+use crate::semantics2::value::Value;
+use crate::semantics2::x87_float::{x87_tag, X87ControlWord};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum X86Mode {
+    Real,
+    Protected,
+    _64Bit,
+}
+
+impl X86Mode {
+    /// Default operand size of stack operations and near branches. It is 64 bits in 64 bit mode, and otherwise
+    /// comes from the code segment, which is 16 bits in real mode and taken to be 32 bits in protected mode.
+    pub fn stack_operand_width(&self) -> usize {
+        match self {
+            X86Mode::Real => 16,
+            X86Mode::Protected => 32,
+            X86Mode::_64Bit => 64,
+        }
+    }
 
+    /// Default address size, which an address size prefix switches away from. Like the operand size it comes from
+    /// the code segment outside of 64 bit mode.
+    pub fn address_width(&self) -> usize {
+        match self {
+            X86Mode::Real => 16,
+            X86Mode::Protected => 32,
+            X86Mode::_64Bit => 64,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
 pub struct Flags<'arena> {
-    pub(crate) cf: Expression<'arena>,
-    pub(crate) pf: Expression<'arena>,
-    pub(crate) af: Expression<'arena>,
-    pub(crate) zf: Expression<'arena>,
-    pub(crate) sf: Expression<'arena>,
-    pub(crate) of: Expression<'arena>,
+    pub(crate) cf: &'arena Expression<'arena>,
+    pub(crate) pf: &'arena Expression<'arena>,
+    pub(crate) af: &'arena Expression<'arena>,
+    pub(crate) zf: &'arena Expression<'arena>,
+    pub(crate) sf: &'arena Expression<'arena>,
+    pub(crate) of: &'arena Expression<'arena>,
 }
 
+/// Machine state with an expression for each component, as threaded through a block by
+/// [`SymbolicExecutor`](crate::semantics2::symbolic::SymbolicExecutor). The expressions are over the state
+/// execution started in, every read in them is at index 0, so evaluating one against
+/// `ConcreteExecution::new(initial)` gives that component's value when starting from `initial`.
+#[derive(Clone)]
 pub struct X86MachineState64<'arena> {
     pub(crate) arena: Arena<'arena>,
     pub(crate) mode: X86Mode,
-    pub(crate) rax: Expression<'arena>,
-    pub(crate) rbx: Expression<'arena>,
-    pub(crate) rcx: Expression<'arena>,
-    pub(crate) rdx: Expression<'arena>,
-    pub(crate) rsi: Expression<'arena>,
-    pub(crate) rdi: Expression<'arena>,
-    pub(crate) rsp: Expression<'arena>,
-    pub(crate) rbp: Expression<'arena>,
-    pub(crate) r8: Expression<'arena>,
-    pub(crate) r9: Expression<'arena>,
-    pub(crate) r10: Expression<'arena>,
-    pub(crate) r11: Expression<'arena>,
-    pub(crate) r12: Expression<'arena>,
-    pub(crate) r13: Expression<'arena>,
-    pub(crate) r14: Expression<'arena>,
-    pub(crate) r15: Expression<'arena>,
-    pub(crate) rip: Expression<'arena>,
+    pub(crate) rax: &'arena Expression<'arena>,
+    pub(crate) rbx: &'arena Expression<'arena>,
+    pub(crate) rcx: &'arena Expression<'arena>,
+    pub(crate) rdx: &'arena Expression<'arena>,
+    pub(crate) rsi: &'arena Expression<'arena>,
+    pub(crate) rdi: &'arena Expression<'arena>,
+    pub(crate) rsp: &'arena Expression<'arena>,
+    pub(crate) rbp: &'arena Expression<'arena>,
+    pub(crate) r8: &'arena Expression<'arena>,
+    pub(crate) r9: &'arena Expression<'arena>,
+    pub(crate) r10: &'arena Expression<'arena>,
+    pub(crate) r11: &'arena Expression<'arena>,
+    pub(crate) r12: &'arena Expression<'arena>,
+    pub(crate) r13: &'arena Expression<'arena>,
+    pub(crate) r14: &'arena Expression<'arena>,
+    pub(crate) r15: &'arena Expression<'arena>,
+    pub(crate) rip: &'arena Expression<'arena>,
     pub(crate) flags: Flags<'arena>,
+    /// Vector registers written so far, the others still hold their initial value.
+    pub(crate) zmms: BTreeMap<RegZMM, &'arena Expression<'arena>>,
+    pub(crate) mxcsr: &'arena Expression<'arena>,
+    /// Segment selectors written so far, the others still hold their initial value.
+    pub(crate) segments: BTreeMap<RegSegment, &'arena Expression<'arena>>,
+    /// FS and GS bases written so far, by loads of their selectors.
+    pub(crate) segment_bases: BTreeMap<RegSegmentBase, &'arena Expression<'arena>>,
+    /// Every store so far as address and value, oldest first. Bytes none of them cover hold their initial value.
+    pub(crate) stores: Vec<(&'arena Expression<'arena>, &'arena Expression<'arena>)>,
 }
 
-impl<'arena> X86MachineState64<'arena> {}
+impl<'arena> X86MachineState64<'arena> {
+    /// The state execution starts in, with every component reading its own initial value.
+    pub fn initial(arena: Arena<'arena>) -> Self {
+        let gpr = |reg: Reg64WithRIP| arena.expr(Expression::GetReg { reg: GeneralReg::Reg64(reg), at_index: 0 });
+        let flag = |flag: Flag| arena.expr(Expression::GetFlag { flag, at_index: 0 });
+        Self {
+            arena,
+            mode: arena.mode(),
+            rax: gpr(Reg64WithRIP::RAX),
+            rbx: gpr(Reg64WithRIP::RBX),
+            rcx: gpr(Reg64WithRIP::RCX),
+            rdx: gpr(Reg64WithRIP::RDX),
+            rsi: gpr(Reg64WithRIP::RSI),
+            rdi: gpr(Reg64WithRIP::RDI),
+            rsp: gpr(Reg64WithRIP::RSP),
+            rbp: gpr(Reg64WithRIP::RBP),
+            r8: gpr(Reg64WithRIP::R8),
+            r9: gpr(Reg64WithRIP::R9),
+            r10: gpr(Reg64WithRIP::R10),
+            r11: gpr(Reg64WithRIP::R11),
+            r12: gpr(Reg64WithRIP::R12),
+            r13: gpr(Reg64WithRIP::R13),
+            r14: gpr(Reg64WithRIP::R14),
+            r15: gpr(Reg64WithRIP::R15),
+            rip: gpr(Reg64WithRIP::RIP),
+            flags: Flags {
+                cf: flag(Flag::CF),
+                pf: flag(Flag::PF),
+                af: flag(Flag::AF),
+                zf: flag(Flag::ZF),
+                sf: flag(Flag::SF),
+                of: flag(Flag::OF),
+            },
+            zmms: BTreeMap::new(),
+            mxcsr: arena.expr(Expression::GetMxcsr { at_index: 0 }),
+            segments: BTreeMap::new(),
+            segment_bases: BTreeMap::new(),
+            stores: vec![],
+        }
+    }
+
+    pub fn mode(&self) -> X86Mode {
+        self.mode
+    }
+
+    pub fn gpr64(&self, reg: Reg64WithRIP) -> &'arena Expression<'arena> {
+        match reg {
+            Reg64WithRIP::RAX => self.rax,
+            Reg64WithRIP::RBX => self.rbx,
+            Reg64WithRIP::RCX => self.rcx,
+            Reg64WithRIP::RDX => self.rdx,
+            Reg64WithRIP::RSI => self.rsi,
+            Reg64WithRIP::RDI => self.rdi,
+            Reg64WithRIP::RBP => self.rbp,
+            Reg64WithRIP::RSP => self.rsp,
+            Reg64WithRIP::R8 => self.r8,
+            Reg64WithRIP::R9 => self.r9,
+            Reg64WithRIP::R10 => self.r10,
+            Reg64WithRIP::R11 => self.r11,
+            Reg64WithRIP::R12 => self.r12,
+            Reg64WithRIP::R13 => self.r13,
+            Reg64WithRIP::R14 => self.r14,
+            Reg64WithRIP::R15 => self.r15,
+            Reg64WithRIP::RIP => self.rip,
+        }
+    }
+
+    pub fn gpr64_mut(&mut self, reg: Reg64WithRIP) -> &mut &'arena Expression<'arena> {
+        match reg {
+            Reg64WithRIP::RAX => &mut self.rax,
+            Reg64WithRIP::RBX => &mut self.rbx,
+            Reg64WithRIP::RCX => &mut self.rcx,
+            Reg64WithRIP::RDX => &mut self.rdx,
+            Reg64WithRIP::RSI => &mut self.rsi,
+            Reg64WithRIP::RDI => &mut self.rdi,
+            Reg64WithRIP::RBP => &mut self.rbp,
+            Reg64WithRIP::RSP => &mut self.rsp,
+            Reg64WithRIP::R8 => &mut self.r8,
+            Reg64WithRIP::R9 => &mut self.r9,
+            Reg64WithRIP::R10 => &mut self.r10,
+            Reg64WithRIP::R11 => &mut self.r11,
+            Reg64WithRIP::R12 => &mut self.r12,
+            Reg64WithRIP::R13 => &mut self.r13,
+            Reg64WithRIP::R14 => &mut self.r14,
+            Reg64WithRIP::R15 => &mut self.r15,
+            Reg64WithRIP::RIP => &mut self.rip,
+        }
+    }
 
-#[derive(Debug, Clone, Copy)]
+    pub fn get_reg(&self, reg: GeneralReg) -> &'arena Expression<'arena> {
+        let (parent, low, high) = ConcreteX86MachineState64::gpr_location(reg);
+        self.arena.expr(Expression::Extract { value: self.gpr64(parent), low, high })
+    }
+
+    /// Writes `value`, which must be as wide as `reg`, the same way [`ConcreteX86MachineState64::set_reg`] does.
+    pub fn set_reg(&mut self, reg: GeneralReg, value: &'arena Expression<'arena>, zero_upper: &ZeroUpper) {
+        assert_eq!(value.width(), reg.bit_width());
+        let (parent, low, high) = ConcreteX86MachineState64::gpr_location(reg);
+        let old = match zero_upper {
+            ZeroUpper::ZeroUpper => self.constant(0, 64),
+            ZeroUpper::NoZeroUpper => self.gpr64(parent),
+        };
+        *self.gpr64_mut(parent) = self.arena.expr(Expression::ChangeRange {
+            value: old,
+            range_start_inclusive: low,
+            range_end_exclusive: high,
+            new_value: value,
+        });
+    }
+
+    pub fn get_zmm(&self, reg: RegZMM) -> &'arena Expression<'arena> {
+        match self.zmms.get(&reg) {
+            Some(&value) => value,
+            None => self.arena.expr(Expression::GetVectorReg { reg, at_index: 0 }),
+        }
+    }
+
+    pub fn set_zmm(&mut self, reg: RegZMM, value: &'arena Expression<'arena>) {
+        assert_eq!(value.width(), 512);
+        self.zmms.insert(reg, value);
+    }
+
+    pub fn mxcsr(&self) -> &'arena Expression<'arena> {
+        self.mxcsr
+    }
+
+    pub fn set_mxcsr(&mut self, value: &'arena Expression<'arena>) {
+        assert_eq!(value.width(), 32);
+        self.mxcsr = value;
+    }
+
+    pub fn get_segment(&self, segment: RegSegment) -> &'arena Expression<'arena> {
+        match self.segments.get(&segment) {
+            Some(&value) => value,
+            None => self.arena.expr(Expression::GetSegment { segment, at_index: 0 }),
+        }
+    }
+
+    pub fn set_segment(&mut self, segment: RegSegment, value: &'arena Expression<'arena>) {
+        assert_eq!(value.width(), 16);
+        self.segments.insert(segment, value);
+    }
+
+    pub fn segment_base(&self, base: RegSegmentBase) -> &'arena Expression<'arena> {
+        match self.segment_bases.get(&base) {
+            Some(&value) => value,
+            None => self.arena.expr(Expression::SegmentBase { base, at_index: 0 }),
+        }
+    }
+
+    pub fn set_segment_base(&mut self, base: RegSegmentBase, value: &'arena Expression<'arena>) {
+        assert_eq!(value.width(), 64);
+        self.segment_bases.insert(base, value);
+    }
+
+    pub fn get_flag(&self, flag: Flag) -> &'arena Expression<'arena> {
+        match flag {
+            Flag::CF => self.flags.cf,
+            Flag::PF => self.flags.pf,
+            Flag::AF => self.flags.af,
+            Flag::ZF => self.flags.zf,
+            Flag::SF => self.flags.sf,
+            Flag::OF => self.flags.of,
+        }
+    }
+
+    pub fn set_flag(&mut self, flag: Flag, value: &'arena Expression<'arena>) {
+        assert_eq!(value.width(), 1);
+        match flag {
+            Flag::CF => self.flags.cf = value,
+            Flag::PF => self.flags.pf = value,
+            Flag::AF => self.flags.af = value,
+            Flag::ZF => self.flags.zf = value,
+            Flag::SF => self.flags.sf = value,
+            Flag::OF => self.flags.of = value,
+        }
+    }
+
+    /// Little endian read of `width` bits starting at `address`. Every byte is the value of the last store
+    /// covering it, which means a conditional per store unless the addresses simplify to constant offsets of each
+    /// other.
+    pub fn read_memory(&self, address: &'arena Expression<'arena>, width: usize) -> &'arena Expression<'arena> {
+        assert_eq!(width % 8, 0);
+        (0..width / 8)
+            .map(|i| self.read_byte(self.add(address, self.constant(i as u64, 64))))
+            .reduce(|acc, byte| self.arena.expr(Expression::Concat { left: byte, right: acc }))
+            .expect("memory reads are at least a byte wide")
+    }
+
+    fn read_byte(&self, address: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        let initial = self.arena.expr(Expression::Load { address, width: 8, at_index: 0 });
+        self.stores.iter().fold(initial, |byte, &(store_address, value)| {
+            let offset = self.arena.expr(Expression::IntArithmetic {
+                op: ArithmeticOp::Sub,
+                signedness: Signedness::Unsigned,
+                left: address,
+                right: store_address,
+            });
+            let covered = self.arena.expr(Expression::IntCompare {
+                op: ComparisonOp::Less,
+                signedness: Signedness::Unsigned,
+                left: offset,
+                right: self.constant((value.width() / 8) as u64, 64),
+            });
+            let shifted = self.arena.expr(Expression::Shift {
+                op: ShiftOp::LShr,
+                value,
+                amount: self.arena.expr(Expression::Shift { op: ShiftOp::Shl, value: offset, amount: self.constant(3, 64) }),
+            });
+            let stored = self.arena.expr(Expression::Extract { value: shifted, low: 0, high: 8 });
+            self.arena.expr(Expression::Conditional { condition: covered, true_value: stored, false_value: byte })
+        })
+    }
+
+    /// Little endian store of all of `value`, which must be a whole number of bytes.
+    pub fn write_memory(&mut self, address: &'arena Expression<'arena>, value: &'arena Expression<'arena>) {
+        assert_eq!(value.width() % 8, 0);
+        self.stores.push((address, value));
+    }
+
+    pub fn stores(&self) -> &[(&'arena Expression<'arena>, &'arena Expression<'arena>)] {
+        self.stores.as_slice()
+    }
+
+    /// This state with every expression in it replaced by `f` of it.
+    pub fn map_expressions(&self, mut f: impl FnMut(&'arena Expression<'arena>) -> &'arena Expression<'arena>) -> Self {
+        let mut res = self.clone();
+        for reg in [Reg64WithRIP::RAX, Reg64WithRIP::RBX, Reg64WithRIP::RCX, Reg64WithRIP::RDX, Reg64WithRIP::RSI,
+            Reg64WithRIP::RDI, Reg64WithRIP::RBP, Reg64WithRIP::RSP, Reg64WithRIP::R8, Reg64WithRIP::R9,
+            Reg64WithRIP::R10, Reg64WithRIP::R11, Reg64WithRIP::R12, Reg64WithRIP::R13, Reg64WithRIP::R14,
+            Reg64WithRIP::R15, Reg64WithRIP::RIP] {
+            *res.gpr64_mut(reg) = f(self.gpr64(reg));
+        }
+        for flag in [Flag::CF, Flag::PF, Flag::AF, Flag::ZF, Flag::SF, Flag::OF] {
+            res.set_flag(flag, f(self.get_flag(flag)));
+        }
+        for value in res.zmms.values_mut() {
+            *value = f(*value);
+        }
+        res.mxcsr = f(self.mxcsr);
+        for value in res.segments.values_mut().chain(res.segment_bases.values_mut()) {
+            *value = f(*value);
+        }
+        for (address, value) in res.stores.iter_mut() {
+            *address = f(*address);
+            *value = f(*value);
+        }
+        res
+    }
+
+    fn constant(&self, value: u64, width: usize) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::Constant { value: self.arena.a(Value::from_u64(value, width)) })
+    }
+
+    fn add(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.arena.expr(Expression::IntArithmetic {
+            op: ArithmeticOp::Add,
+            signedness: Signedness::Unsigned,
+            left,
+            right,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ConcreteX86MachineState64 {
     pub(crate) rax: u64,
     pub(crate) rbx: u64,
@@ -57,8 +369,21 @@ pub struct ConcreteX86MachineState64 {
     pub(crate) r15: u64,
     pub(crate) rip: u64,
     pub(crate) flags: ConcreteFlags,
-    pub(crate) zmms: [[u64;8];32]
-
+    pub(crate) zmms: [[u64;8];32],
+    pub(crate) mxcsr: u32,
+    /// Physical x87 registers R0 to R7, 80 bits each. ST(i) is R((TOP + i) mod 8).
+    pub(crate) x87_registers: [u128; 8],
+    pub(crate) x87_control: u16,
+    /// TOP is bits 11 to 13.
+    pub(crate) x87_status: u16,
+    /// Two bits per physical register: valid, zero, special or empty.
+    pub(crate) x87_tag: u16,
+    /// Selectors in the order of [`RegSegment`]. Descriptors aren't modelled.
+    pub(crate) segments: [u16; 6],
+    pub(crate) fs_base: u64,
+    pub(crate) gs_base: u64,
+    /// Bytes which have never been written read as zero.
+    pub(crate) memory: BTreeMap<u64, u8>,
 }
 
 pub trait XMMValue {
@@ -112,6 +437,15 @@ impl ConcreteX86MachineState64 {
                 of: false,
             },
             zmms: [[0;8]; 32],
+            mxcsr: Mxcsr::DEFAULT.0,
+            x87_registers: [0; 8],
+            x87_control: X87ControlWord::DEFAULT.0,
+            x87_status: 0,
+            x87_tag: 0xFFFF,
+            segments: [0; 6],
+            fs_base: 0,
+            gs_base: 0,
+            memory: BTreeMap::new(),
         }
     }
 
@@ -145,6 +479,16 @@ impl ConcreteX86MachineState64 {
         self
     }
 
+    pub fn rsp(mut self, value: u64) -> Self {
+        self.rsp = value;
+        self
+    }
+
+    pub fn rbp(mut self, value: u64) -> Self {
+        self.rbp = value;
+        self
+    }
+
     pub fn r8(mut self, value: u64) -> Self {
         self.r8 = value;
         self
@@ -186,6 +530,56 @@ impl ConcreteX86MachineState64 {
         self
     }
 
+    pub fn rip(mut self, value: u64) -> Self {
+        self.rip = value;
+        self
+    }
+
+    pub fn segment(mut self, segment: RegSegment, selector: u16) -> Self {
+        self.segments[segment as usize] = selector;
+        self
+    }
+
+    pub fn fs_base(mut self, value: u64) -> Self {
+        self.fs_base = value;
+        self
+    }
+
+    pub fn gs_base(mut self, value: u64) -> Self {
+        self.gs_base = value;
+        self
+    }
+
+    pub fn mxcsr(mut self, value: u32) -> Self {
+        self.mxcsr = value;
+        self
+    }
+
+    pub fn x87_control(mut self, value: u16) -> Self {
+        self.x87_control = value;
+        self
+    }
+
+    /// Replaces the whole status word, TOP included.
+    pub fn x87_status(mut self, value: u16) -> Self {
+        self.x87_status = value;
+        self
+    }
+
+    /// Pushes `value` onto the x87 stack the way FLD does, minus the overflow check.
+    pub fn x87_push(mut self, value: u128) -> Self {
+        self.push_x87();
+        self.set_x87_register(0, value);
+        self
+    }
+
+    pub fn memory(mut self, address: u64, bytes: &[u8]) -> Self {
+        for (i, byte) in bytes.iter().enumerate() {
+            self.memory.insert(address.wrapping_add(i as u64), *byte);
+        }
+        self
+    }
+
     pub fn flags(mut self, value: ConcreteFlags) -> Self {
         self.flags = value;
         self
@@ -336,11 +730,204 @@ impl ConcreteX86MachineState64 {
         self
     }
 
+    pub fn gpr64(&self, reg: Reg64WithRIP) -> u64 {
+        match reg {
+            Reg64WithRIP::RAX => self.rax,
+            Reg64WithRIP::RBX => self.rbx,
+            Reg64WithRIP::RCX => self.rcx,
+            Reg64WithRIP::RDX => self.rdx,
+            Reg64WithRIP::RSI => self.rsi,
+            Reg64WithRIP::RDI => self.rdi,
+            Reg64WithRIP::RBP => self.rbp,
+            Reg64WithRIP::RSP => self.rsp,
+            Reg64WithRIP::R8 => self.r8,
+            Reg64WithRIP::R9 => self.r9,
+            Reg64WithRIP::R10 => self.r10,
+            Reg64WithRIP::R11 => self.r11,
+            Reg64WithRIP::R12 => self.r12,
+            Reg64WithRIP::R13 => self.r13,
+            Reg64WithRIP::R14 => self.r14,
+            Reg64WithRIP::R15 => self.r15,
+            Reg64WithRIP::RIP => self.rip,
+        }
+    }
+
+    pub fn gpr64_mut(&mut self, reg: Reg64WithRIP) -> &mut u64 {
+        match reg {
+            Reg64WithRIP::RAX => &mut self.rax,
+            Reg64WithRIP::RBX => &mut self.rbx,
+            Reg64WithRIP::RCX => &mut self.rcx,
+            Reg64WithRIP::RDX => &mut self.rdx,
+            Reg64WithRIP::RSI => &mut self.rsi,
+            Reg64WithRIP::RDI => &mut self.rdi,
+            Reg64WithRIP::RBP => &mut self.rbp,
+            Reg64WithRIP::RSP => &mut self.rsp,
+            Reg64WithRIP::R8 => &mut self.r8,
+            Reg64WithRIP::R9 => &mut self.r9,
+            Reg64WithRIP::R10 => &mut self.r10,
+            Reg64WithRIP::R11 => &mut self.r11,
+            Reg64WithRIP::R12 => &mut self.r12,
+            Reg64WithRIP::R13 => &mut self.r13,
+            Reg64WithRIP::R14 => &mut self.r14,
+            Reg64WithRIP::R15 => &mut self.r15,
+            Reg64WithRIP::RIP => &mut self.rip,
+        }
+    }
+
+    /// The 64 bit register `reg` lives in, and which bits of it `reg` is.
+    pub(crate) fn gpr_location(reg: GeneralReg) -> (Reg64WithRIP, usize, usize) {
+        let register = match reg {
+            GeneralReg::Reg64(reg) => Register::GP64(reg),
+            GeneralReg::Reg32(reg) => Register::GP32(reg),
+            GeneralReg::Reg16(reg) => Register::GP16(reg),
+            GeneralReg::Reg8(reg) => Register::GP8(reg),
+        };
+        let Register::GP64(parent) = register.parent() else {
+            panic!("general register without a 64 bit parent: {reg:?}")
+        };
+        let range = register.bit_range();
+        (parent, range.start as usize, range.end as usize)
+    }
+
+    pub fn get_reg<'arena>(&self, reg: GeneralReg) -> Value<'arena> {
+        let (parent, low, high) = Self::gpr_location(reg);
+        Value::from_u64(self.gpr64(parent), 64).slice(low, high)
+    }
+
+    /// Writes `value`, which must be as wide as `reg`. `ZeroUpper::ZeroUpper` clears the rest of the 64 bit
+    /// register, otherwise the other bits are left alone.
+    pub fn set_reg(&mut self, reg: GeneralReg, value: &Value, zero_upper: &ZeroUpper) {
+        assert_eq!(value.width(), reg.bit_width());
+        let (parent, low, _) = Self::gpr_location(reg);
+        let old = match zero_upper {
+            ZeroUpper::ZeroUpper => Value::zero(64),
+            ZeroUpper::NoZeroUpper => Value::from_u64(self.gpr64(parent), 64),
+        };
+        *self.gpr64_mut(parent) = old.with_range(low, value).to_u64();
+    }
+
+    pub fn get_zmm<'arena>(&self, reg: RegZMM) -> Value<'arena> {
+        Value::from_u64s(&self.zmms[reg as usize], 512)
+    }
+
+    pub fn set_zmm(&mut self, reg: RegZMM, value: &Value) {
+        assert_eq!(value.width(), 512);
+        self.zmms[reg as usize].copy_from_slice(value.to_u64s().as_slice());
+    }
+
+    pub fn get_segment(&self, segment: RegSegment) -> u16 {
+        self.segments[segment as usize]
+    }
+
+    pub fn set_segment(&mut self, segment: RegSegment, selector: u16) {
+        self.segments[segment as usize] = selector;
+    }
+
+    pub fn segment_base(&self, base: RegSegmentBase) -> u64 {
+        match base {
+            RegSegmentBase::FSBase => self.fs_base,
+            RegSegmentBase::GSBase => self.gs_base,
+        }
+    }
+
+    pub fn set_segment_base(&mut self, base: RegSegmentBase, value: u64) {
+        match base {
+            RegSegmentBase::FSBase => self.fs_base = value,
+            RegSegmentBase::GSBase => self.gs_base = value,
+        }
+    }
+
+    pub fn get_mxcsr(&self) -> u32 {
+        self.mxcsr
+    }
+
+    pub fn set_mxcsr(&mut self, value: u32) {
+        self.mxcsr = value;
+    }
+
+    /// Little endian read of `width` bits starting at `address`. Addresses wrap around at 2^64.
+    pub fn read_memory<'arena>(&self, address: u64, width: usize) -> Value<'arena> {
+        assert_eq!(width % 8, 0);
+        let bytes = (0..width / 8)
+            .map(|i| Value::from_u64(*self.memory.get(&address.wrapping_add(i as u64)).unwrap_or(&0) as u64, 8))
+            .collect::<Vec<_>>();
+        bytes.iter().fold(Value::zero(0), |acc, byte| Value::concat(byte, &acc))
+    }
+
+    pub fn write_memory(&mut self, address: u64, value: &Value) {
+        assert_eq!(value.width() % 8, 0);
+        for i in 0..value.width() / 8 {
+            self.memory.insert(address.wrapping_add(i as u64), value.slice(i * 8, i * 8 + 8).to_u64() as u8);
+        }
+    }
+
+    pub fn x87_top(&self) -> usize {
+        ((self.x87_status >> 11) & 7) as usize
+    }
+
+    fn x87_physical(&self, st: usize) -> usize {
+        x87_physical(self.x87_status, st)
+    }
+
+    /// ST(`st`), whether or not it is empty.
+    pub fn x87_register(&self, st: usize) -> u128 {
+        self.x87_registers[self.x87_physical(st)]
+    }
+
+    /// Writes ST(`st`) and tags it according to `value`.
+    pub fn set_x87_register(&mut self, st: usize, value: u128) {
+        assert!(value >> 80 == 0);
+        let physical = self.x87_physical(st);
+        self.x87_registers[physical] = value;
+        self.x87_tag = (self.x87_tag & !(3 << (2 * physical))) | (x87_tag(value) << (2 * physical));
+    }
+
+    pub fn x87_register_empty(&self, st: usize) -> bool {
+        (self.x87_tag >> (2 * self.x87_physical(st))) & 3 == 3
+    }
+
+    /// Decrements TOP. The new ST(0) keeps its contents and tag until written.
+    pub fn push_x87(&mut self) {
+        let top = (self.x87_top() + 7) % 8;
+        self.x87_status = (self.x87_status & !(7 << 11)) | ((top as u16) << 11);
+    }
+
+    /// Marks ST(0) empty and increments TOP.
+    pub fn pop_x87(&mut self) {
+        self.x87_tag |= 3 << (2 * self.x87_physical(0));
+        let top = (self.x87_top() + 1) % 8;
+        self.x87_status = (self.x87_status & !(7 << 11)) | ((top as u16) << 11);
+    }
+
+    pub fn get_flag(&self, flag: Flag) -> bool {
+        match flag {
+            Flag::CF => self.flags.cf,
+            Flag::PF => self.flags.pf,
+            Flag::AF => self.flags.af,
+            Flag::ZF => self.flags.zf,
+            Flag::SF => self.flags.sf,
+            Flag::OF => self.flags.of,
+        }
+    }
+
+    pub fn set_flag(&mut self, flag: Flag, value: bool) {
+        match flag {
+            Flag::CF => self.flags.cf = value,
+            Flag::PF => self.flags.pf = value,
+            Flag::AF => self.flags.af = value,
+            Flag::ZF => self.flags.zf = value,
+            Flag::SF => self.flags.sf = value,
+            Flag::OF => self.flags.of = value,
+        }
+    }
+}
 
-    pub fn get_reg(reg: GeneralReg)
+/// The physical register ST(`st`) is when the x87 status word is `status`.
+pub(crate) fn x87_physical(status: u16, st: usize) -> usize {
+    (((status >> 11) & 7) as usize + st) % 8
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct ConcreteFlags {
     pub(crate) cf: bool,
     pub(crate) pf: bool,
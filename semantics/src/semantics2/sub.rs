@@ -0,0 +1,139 @@
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8};
+use xed_enum::SUB;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::{FlagTag, InstructionSemanticsStep};
+
+pub fn sub_generic<'arena, D1: Writeable<'arena>, S1: Readable<'arena>, S2: Readable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+    readable1: S1,
+    readable2: S2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //DEST := (DEST – SRC);
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let dest = readable1.read(&s);
+    // immediates narrower than the operand size are sign extended
+    let src = s.sext_to(readable2.read(&s), width);
+    let res = s.sub(dest, src);
+    writeable1.write(&mut s, res);
+    s.flag_calculate(FlagTag::Sub, dest, src);
+    s.finalize()
+}
+
+pub fn apply_iform_sub(arena: Arena, instr: SUB) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        SUB::SUB_MEMB_IMMB_80R5 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SUB::SUB_GPR8_IMMB_80R5 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SUB::SUB_MEMV_IMMZ_16 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SUB::SUB_MEMV_IMMZ_32 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SUB::SUB_MEMV_IMMZ_64 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SUB::SUB_GPRV_IMMZ_16 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SUB::SUB_GPRV_IMMZ_32 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SUB::SUB_GPRV_IMMZ_64 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SUB::SUB_MEMB_IMMB_82R5 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SUB::SUB_GPR8_IMMB_82R5 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SUB::SUB_MEMV_IMMB_16 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SUB::SUB_MEMV_IMMB_32 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SUB::SUB_MEMV_IMMB_64 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SUB::SUB_GPRV_IMMB_16 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SUB::SUB_GPRV_IMMB_32 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SUB::SUB_GPRV_IMMB_64 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SUB::SUB_MEMB_GPR8 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SUB::SUB_GPR8_GPR8_28 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SUB::SUB_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SUB::SUB_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SUB::SUB_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SUB::SUB_GPRV_GPRV_29_16 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SUB::SUB_GPRV_GPRV_29_32 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SUB::SUB_GPRV_GPRV_29_64 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SUB::SUB_GPR8_GPR8_2A { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SUB::SUB_GPR8_MEMB { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 8)
+        }
+        SUB::SUB_GPRV_GPRV_2B_16 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SUB::SUB_GPRV_GPRV_2B_32 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SUB::SUB_GPRV_GPRV_2B_64 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SUB::SUB_GPRV_MEMV_16 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 16)
+        }
+        SUB::SUB_GPRV_MEMV_32 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 32)
+        }
+        SUB::SUB_GPRV_MEMV_64 { operand_0, operand_1 } => {
+            sub_generic(arena, operand_0, operand_0, operand_1, 64)
+        }
+        SUB::SUB_AL_IMMB { operand_0 } => {
+            sub_generic(arena, Reg8::AL, Reg8::AL, operand_0, 8)
+        }
+        SUB::SUB_ORAX_IMMZ_16 { operand_0 } => {
+            sub_generic(arena, Reg16WithRIP::AX, Reg16WithRIP::AX, operand_0, 16)
+        }
+        SUB::SUB_ORAX_IMMZ_32 { operand_0 } => {
+            sub_generic(arena, Reg32WithRIP::EAX, Reg32WithRIP::EAX, operand_0, 32)
+        }
+        SUB::SUB_ORAX_IMMZ_64 { operand_0 } => {
+            sub_generic(arena, Reg64WithRIP::RAX, Reg64WithRIP::RAX, operand_0, 64)
+        }
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::basic_block::BasicBlock;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::{ArithmeticOp, Expression, Flag};
+use crate::semantics2::semantic_steps::{loaded_segment_base, steps_count, Fault, FlagTag, InstructionSemanticsStep};
+use crate::semantics2::simplify::Simplifier;
+use crate::semantics2::state::X86MachineState64;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SymbolicError {
+    /// The x87 stack is addressed relative to TOP, so semantics using it aren't supported.
+    Unsupported(&'static str),
+}
+
+/// One way through a block.
+pub struct SymbolicPath<'arena> {
+    /// One bit, set for exactly the initial states which take this path.
+    pub condition: &'arena Expression<'arena>,
+    /// The state at the end of the block, or when `fault` was raised.
+    pub state: X86MachineState64<'arena>,
+    pub fault: Option<Fault>,
+}
+
+/// A path still being executed, along with the state from before each step of the current instruction which has
+/// run so far.
+#[derive(Clone)]
+struct Path<'arena> {
+    condition: &'arena Expression<'arena>,
+    state: X86MachineState64<'arena>,
+    history: Vec<(usize, X86MachineState64<'arena>)>,
+    /// Expressions of the current instruction with their reads replaced by what they read, by address.
+    substituted: HashMap<*const Expression<'arena>, &'arena Expression<'arena>>,
+}
+
+impl<'arena> Path<'arena> {
+    fn state_at(&self, at_index: usize) -> &X86MachineState64<'arena> {
+        self.history.iter()
+            .find(|(index, _)| *index >= at_index)
+            .map(|(_, state)| state)
+            .unwrap_or(&self.state)
+    }
+}
+
+fn is_false(expr: &Expression) -> bool {
+    matches!(expr, Expression::Constant { value } if value.is_zero())
+}
+
+/// Runs blocks on symbolic state, forking on conditional steps, on expressions which can divide by zero and on
+/// branches, so that every path comes with the condition the initial state has to meet to take it.
+pub struct SymbolicExecutor<'arena> {
+    builder: SemanticsBuilder<'arena>,
+    simplifier: Simplifier<'arena>,
+    divides_by_zero: HashMap<*const Expression<'arena>, Option<&'arena Expression<'arena>>>,
+}
+
+impl<'arena> SymbolicExecutor<'arena> {
+    /// `arena` should share equal expressions, see [`Arena::new`], since paths are only dropped as infeasible when
+    /// their condition simplifies to false.
+    pub fn new(arena: Arena<'arena>) -> Self {
+        Self {
+            builder: SemanticsBuilder::new(arena),
+            simplifier: Simplifier::new(arena),
+            divides_by_zero: HashMap::new(),
+        }
+    }
+
+    /// Every path through `block` from `initial`, usually [`X86MachineState64::initial`]. Paths ending in a fault
+    /// stop there, the others run to the end of the block, with RIP wherever the block transfers control to. Paths
+    /// whose condition simplifies to false are left out, which doesn't make all of the remaining ones feasible.
+    pub fn execute_block(&mut self, initial: X86MachineState64<'arena>, block: &BasicBlock<'arena>) -> Result<Vec<SymbolicPath<'arena>>, SymbolicError> {
+        let mut finished = vec![];
+        let mut paths = vec![Path {
+            condition: self.builder.constant_with_width(1, 1),
+            state: initial,
+            history: vec![],
+            substituted: HashMap::new(),
+        }];
+        for instruction in block.instructions.iter() {
+            let mut next = vec![];
+            for path in paths {
+                for mut path in self.run(path, instruction.semantics.as_slice(), 0, &mut finished)? {
+                    // indices start over with every instruction
+                    path.history.clear();
+                    path.substituted.clear();
+                    path.state = self.simplified(&path.state);
+                    self.fork_on_rip(path, &mut next);
+                }
+            }
+            paths = next;
+        }
+        for path in finished.iter_mut() {
+            path.state = self.simplified(&path.state);
+        }
+        finished.extend(paths.into_iter().map(|path| SymbolicPath {
+            condition: path.condition,
+            state: path.state,
+            fault: None,
+        }));
+        Ok(finished)
+    }
+
+    fn simplified(&mut self, state: &X86MachineState64<'arena>) -> X86MachineState64<'arena> {
+        state.map_expressions(|expr| self.simplifier.simplify(expr))
+    }
+
+    /// Runs `steps`, the first of which is numbered `first_index`, returning the parts of `path` which make it
+    /// through them. The parts which fault end up in `finished`.
+    fn run(&mut self, path: Path<'arena>, steps: &[InstructionSemanticsStep<'arena>], first_index: usize, finished: &mut Vec<SymbolicPath<'arena>>) -> Result<Vec<Path<'arena>>, SymbolicError> {
+        let mut paths = vec![path];
+        let mut index = first_index;
+        for step in steps {
+            let mut next = vec![];
+            for mut path in paths {
+                match step {
+                    InstructionSemanticsStep::Conditional { condition, true_semantics, false_semantics } => {
+                        let condition = self.value(&mut path, *condition)?;
+                        let Some(path) = self.raise_if_divides_by_zero(path, &[condition], finished) else {
+                            continue;
+                        };
+                        let (taken, not_taken) = self.fork(path, condition);
+                        if let Some(taken) = taken {
+                            next.extend(self.run(taken, true_semantics, index, finished)?);
+                        }
+                        if let Some(not_taken) = not_taken {
+                            next.extend(self.run(not_taken, false_semantics, index + steps_count(true_semantics), finished)?);
+                        }
+                    }
+                    InstructionSemanticsStep::AdvanceRip { length } => {
+                        let length = self.builder.constant_with_width(*length as u64, 64);
+                        path.state.rip = self.builder.add(path.state.rip, length);
+                        next.push(path);
+                    }
+                    step => {
+                        path.history.push((index, path.state.clone()));
+                        next.extend(self.step(path, step, finished)?);
+                    }
+                }
+            }
+            paths = next;
+            index += step.step_count();
+        }
+        Ok(paths)
+    }
+
+    /// Applies `step`, which is neither a conditional nor an advance of RIP, returning what is left of `path`
+    /// after any fault it raises.
+    fn step(&mut self, mut path: Path<'arena>, step: &InstructionSemanticsStep<'arena>, finished: &mut Vec<SymbolicPath<'arena>>) -> Result<Option<Path<'arena>>, SymbolicError> {
+        Ok(match step {
+            InstructionSemanticsStep::Conditional { .. } |
+            InstructionSemanticsStep::AdvanceRip { .. } => {
+                unreachable!()
+            }
+            InstructionSemanticsStep::SetRegister { zero_upper, register, value } => {
+                let value = self.value(&mut path, *value)?;
+                self.raise_if_divides_by_zero(path, &[value], finished).map(|mut path| {
+                    path.state.set_reg(*register, value, zero_upper);
+                    path
+                })
+            }
+            InstructionSemanticsStep::SetVectorRegister { register, value } => {
+                let value = self.value(&mut path, *value)?;
+                self.raise_if_divides_by_zero(path, &[value], finished).map(|mut path| {
+                    path.state.set_zmm(*register, value);
+                    path
+                })
+            }
+            InstructionSemanticsStep::SetFlag { flag, value } => {
+                let value = self.value(&mut path, *value)?;
+                self.raise_if_divides_by_zero(path, &[value], finished).map(|mut path| {
+                    path.state.set_flag(*flag, value);
+                    path
+                })
+            }
+            InstructionSemanticsStep::InstructionSyncPoint { .. } => Some(path),
+            InstructionSemanticsStep::UndefinedException => self.raise(path, Fault::UndefinedOpcode, finished),
+            InstructionSemanticsStep::DivideError => self.raise(path, Fault::DivideError, finished),
+            InstructionSemanticsStep::GeneralProtection => self.raise(path, Fault::GeneralProtection, finished),
+            InstructionSemanticsStep::FloatingPointError => self.raise(path, Fault::FloatingPointError, finished),
+            InstructionSemanticsStep::CalculateFlags { flag_tag, left, right } => {
+                let left = self.value(&mut path, *left)?;
+                let right = self.value(&mut path, *right)?;
+                self.raise_if_divides_by_zero(path, &[left, right], finished).map(|mut path| {
+                    self.calculate_flags(&mut path.state, flag_tag, left, right);
+                    path
+                })
+            }
+            InstructionSemanticsStep::Store { address, value } => {
+                let address = self.value(&mut path, *address)?;
+                let value = self.value(&mut path, *value)?;
+                self.raise_if_divides_by_zero(path, &[address, value], finished).map(|mut path| {
+                    path.state.write_memory(address, value);
+                    path
+                })
+            }
+            InstructionSemanticsStep::RaiseFloatExceptions { exceptions } => {
+                let exceptions = self.value(&mut path, *exceptions)?;
+                let Some(path) = self.raise_if_divides_by_zero(path, &[exceptions], finished) else {
+                    return Ok(None);
+                };
+                let b = &self.builder;
+                let unmasked = b.bitand(exceptions, b.not(b.extract(path.state.mxcsr(), 7, 13)));
+                let raised = b.not(b.equal(unmasked, b.constant_with_width(0, 6)));
+                self.raise_if(path, Some(raised), Fault::SimdFloatingPointException, finished).map(|mut path| {
+                    let mxcsr = self.builder.bitor(path.state.mxcsr(), self.builder.zext_to(exceptions, 32));
+                    path.state.set_mxcsr(mxcsr);
+                    path
+                })
+            }
+            InstructionSemanticsStep::SetMxcsr { value } => {
+                let value = self.value(&mut path, *value)?;
+                self.raise_if_divides_by_zero(path, &[value], finished).map(|mut path| {
+                    path.state.set_mxcsr(value);
+                    path
+                })
+            }
+            InstructionSemanticsStep::SetSegment { segment, value } => {
+                let value = self.value(&mut path, *value)?;
+                self.raise_if_divides_by_zero(path, &[value], finished).map(|mut path| {
+                    path.state.set_segment(*segment, value);
+                    if let Some(base) = loaded_segment_base(*segment) {
+                        path.state.set_segment_base(base, self.builder.constant_with_width(0, 64));
+                    }
+                    path
+                })
+            }
+            InstructionSemanticsStep::SetX87Register { .. } |
+            InstructionSemanticsStep::X87Push |
+            InstructionSemanticsStep::X87Pop |
+            InstructionSemanticsStep::SetX87Control { .. } |
+            InstructionSemanticsStep::SetX87Status { .. } => {
+                return Err(SymbolicError::Unsupported("the x87 stack"));
+            }
+        })
+    }
+
+    /// `expr` with the reads in it replaced by what they read on `path`, simplified.
+    fn value(&mut self, path: &mut Path<'arena>, expr: &'arena Expression<'arena>) -> Result<&'arena Expression<'arena>, SymbolicError> {
+        let substituted = self.substitute(path, expr)?;
+        Ok(self.simplifier.simplify(substituted))
+    }
+
+    fn substitute(&mut self, path: &mut Path<'arena>, expr: &'arena Expression<'arena>) -> Result<&'arena Expression<'arena>, SymbolicError> {
+        let key = expr as *const Expression<'arena>;
+        if let Some(&substituted) = path.substituted.get(&key) {
+            return Ok(substituted);
+        }
+        let res = match *expr {
+            Expression::GetReg { reg, at_index } => path.state_at(at_index).get_reg(reg),
+            Expression::GetFlag { flag, at_index } => path.state_at(at_index).get_flag(flag),
+            Expression::GetVectorReg { reg, at_index } => path.state_at(at_index).get_zmm(reg),
+            Expression::GetMxcsr { at_index } => path.state_at(at_index).mxcsr(),
+            Expression::SegmentBase { base, at_index } => path.state_at(at_index).segment_base(base),
+            Expression::GetSegment { segment, at_index } => path.state_at(at_index).get_segment(segment),
+            Expression::Load { address, width, at_index } => {
+                let address = self.substitute(path, address)?;
+                path.state_at(at_index).read_memory(address, width)
+            }
+            Expression::GetX87Register { .. } |
+            Expression::X87RegisterEmpty { .. } |
+            Expression::GetX87Control { .. } |
+            Expression::GetX87Status { .. } => {
+                return Err(SymbolicError::Unsupported("the x87 stack"));
+            }
+            _ => {
+                let mut error = None;
+                let substituted = expr.map_children(|child| match self.substitute(path, child) {
+                    Ok(child) => child,
+                    Err(err) => {
+                        error = Some(err);
+                        child
+                    }
+                });
+                if let Some(err) = error {
+                    return Err(err);
+                }
+                self.builder.a(substituted)
+            }
+        };
+        path.substituted.insert(key, res);
+        Ok(res)
+    }
+
+    /// One bit, set for the initial states on which evaluating `expr` divides by zero, or None if it can't.
+    /// `expr` reads nothing but the initial state.
+    fn divides_by_zero(&mut self, expr: &'arena Expression<'arena>) -> Option<&'arena Expression<'arena>> {
+        let key = expr as *const Expression<'arena>;
+        if let Some(&res) = self.divides_by_zero.get(&key) {
+            return res;
+        }
+        // conditions are combined with `or_else`, so that each only gets evaluated where those before it don't
+        // hold, the same way evaluating `expr` stops at the first division by zero
+        let res = match *expr {
+            // only the value picked is evaluated
+            Expression::Conditional { condition, true_value, false_value } => {
+                let condition_faults = self.divides_by_zero(condition);
+                let picked_faults = match (self.divides_by_zero(true_value), self.divides_by_zero(false_value)) {
+                    (None, None) => None,
+                    (taken, not_taken) => {
+                        let never = self.builder.constant_with_width(0, 1);
+                        Some(self.builder.select(condition, taken.unwrap_or(never), not_taken.unwrap_or(never)))
+                    }
+                };
+                self.any([condition_faults, picked_faults])
+            }
+            _ => {
+                let mut conditions = vec![];
+                expr.map_children(|child| {
+                    conditions.push(self.divides_by_zero(child));
+                    child
+                });
+                if let Expression::IntArithmetic { op: ArithmeticOp::Div | ArithmeticOp::Rem, right, .. } = *expr {
+                    conditions.push(Some(self.builder.equal(right, self.builder.constant_with_width(0, right.width()))));
+                }
+                self.any(conditions)
+            }
+        };
+        let res = res
+            .map(|condition| self.simplifier.simplify(condition))
+            .filter(|condition| !is_false(condition));
+        self.divides_by_zero.insert(key, res);
+        res
+    }
+
+    fn any(&self, conditions: impl IntoIterator<Item=Option<&'arena Expression<'arena>>>) -> Option<&'arena Expression<'arena>> {
+        conditions.into_iter().flatten().reduce(|left, right| self.or_else(left, right))
+    }
+
+    /// `left || right`, which only evaluates `right` if `left` doesn't hold.
+    fn or_else(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.builder.select(left, self.builder.constant_with_width(1, 1), right)
+    }
+
+    /// `left && right`, which only evaluates `right` if `left` holds. Path conditions are built from these, so
+    /// evaluating one never divides by zero.
+    fn and_then(&self, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+        self.builder.select(left, right, self.builder.constant_with_width(0, 1))
+    }
+
+    fn raise_if_divides_by_zero(&mut self, path: Path<'arena>, values: &[&'arena Expression<'arena>], finished: &mut Vec<SymbolicPath<'arena>>) -> Option<Path<'arena>> {
+        let conditions = values.iter().map(|&value| self.divides_by_zero(value)).collect::<Vec<_>>();
+        let condition = self.any(conditions);
+        self.raise_if(path, condition, Fault::DivideError, finished)
+    }
+
+    fn raise(&mut self, path: Path<'arena>, fault: Fault, finished: &mut Vec<SymbolicPath<'arena>>) -> Option<Path<'arena>> {
+        let always = self.builder.constant_with_width(1, 1);
+        self.raise_if(path, Some(always), fault, finished)
+    }
+
+    /// Ends the part of `path` where `condition` holds with `fault`, returning the rest of it, if any.
+    fn raise_if(&mut self, path: Path<'arena>, condition: Option<&'arena Expression<'arena>>, fault: Fault, finished: &mut Vec<SymbolicPath<'arena>>) -> Option<Path<'arena>> {
+        let Some(condition) = condition else {
+            return Some(path);
+        };
+        let (faulting, rest) = self.fork(path, condition);
+        if let Some(faulting) = faulting {
+            finished.push(SymbolicPath {
+                condition: faulting.condition,
+                state: faulting.state,
+                fault: Some(fault),
+            });
+        }
+        rest
+    }
+
+    /// `path` split into the part where `condition` holds and the part where it doesn't. Either is left out if its
+    /// condition simplifies to false.
+    fn fork(&mut self, path: Path<'arena>, condition: &'arena Expression<'arena>) -> (Option<Path<'arena>>, Option<Path<'arena>>) {
+        let negated = self.builder.not(condition);
+        let taken = self.restrict(path.condition, condition);
+        let not_taken = self.restrict(path.condition, negated);
+        match (taken, not_taken) {
+            (Some(taken), Some(not_taken)) => (Some(Path { condition: taken, ..path.clone() }), Some(Path { condition: not_taken, ..path })),
+            (Some(taken), None) => (Some(Path { condition: taken, ..path }), None),
+            (None, Some(not_taken)) => (None, Some(Path { condition: not_taken, ..path })),
+            (None, None) => (None, None),
+        }
+    }
+
+    fn restrict(&mut self, condition: &'arena Expression<'arena>, extra: &'arena Expression<'arena>) -> Option<&'arena Expression<'arena>> {
+        let condition = self.simplifier.simplify(self.and_then(condition, extra));
+        (!is_false(condition)).then_some(condition)
+    }
+
+    /// Branches are lifted as a conditional write of RIP, so `path`, which has just finished an instruction, is
+    /// split until RIP is no longer a conditional.
+    fn fork_on_rip(&mut self, path: Path<'arena>, paths: &mut Vec<Path<'arena>>) {
+        let Expression::Conditional { condition, true_value, false_value } = *path.state.rip else {
+            paths.push(path);
+            return;
+        };
+        let (taken, not_taken) = self.fork(path, condition);
+        for (path, rip) in [(taken, true_value), (not_taken, false_value)] {
+            if let Some(mut path) = path {
+                path.state.rip = rip;
+                self.fork_on_rip(path, paths);
+            }
+        }
+    }
+
+    /// Sets the flags the same way the concrete semantics do for `flag_tag`.
+    fn calculate_flags(&self, state: &mut X86MachineState64<'arena>, flag_tag: &FlagTag, left: &'arena Expression<'arena>, right: &'arena Expression<'arena>) {
+        for (flag, value) in self.builder.flag_values(flag_tag, left, right, state.get_flag(Flag::CF)) {
+            state.set_flag(flag, value);
+        }
+    }
+}
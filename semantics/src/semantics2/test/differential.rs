@@ -0,0 +1,402 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use wrapper_common::registers::Reg64WithRIP;
+use xed_enum::{EncodeDecodeContext, X86Instruction};
+use xed_enum::operand_info::X86Flag;
+
+use crate::semantics2::{apply_instruction_concrete, ConcreteError};
+use crate::semantics2::expression::Flag;
+use crate::semantics2::float::Mxcsr;
+use crate::semantics2::state::{ConcreteFlags, ConcreteX86MachineState64};
+use crate::semantics2::test::instruction_64::{host_has_avx, host_has_avx512, run_instruction_64};
+
+/// Registers the native harness loads and stores. r15 and rip are used by the harness itself.
+const COMPARED_GPRS: [Reg64WithRIP; 15] = [
+    Reg64WithRIP::RAX,
+    Reg64WithRIP::RBX,
+    Reg64WithRIP::RCX,
+    Reg64WithRIP::RDX,
+    Reg64WithRIP::RSI,
+    Reg64WithRIP::RDI,
+    Reg64WithRIP::RSP,
+    Reg64WithRIP::RBP,
+    Reg64WithRIP::R8,
+    Reg64WithRIP::R9,
+    Reg64WithRIP::R10,
+    Reg64WithRIP::R11,
+    Reg64WithRIP::R12,
+    Reg64WithRIP::R13,
+    Reg64WithRIP::R14,
+];
+
+const FLAGS: [Flag; 6] = [Flag::CF, Flag::PF, Flag::AF, Flag::ZF, Flag::SF, Flag::OF];
+
+const EDGE_CASES: [u64; 14] = [
+    0,
+    1,
+    u64::MAX,
+    0x7F,
+    0x80,
+    0xFF,
+    0x7FFF,
+    0x8000,
+    0xFFFF,
+    0x7FFF_FFFF,
+    0x8000_0000,
+    0xFFFF_FFFF,
+    i64::MAX as u64,
+    i64::MIN as u64,
+];
+
+/// Vector lanes which are interesting as a binary64, or as the two binary32 halves.
+const FLOAT_EDGE_CASES: [u64; 14] = [
+    // -0.0, infinities, QNaN, SNaN, smallest denormal, largest denormal, smallest normal, largest finite, 1.0
+    0x8000_0000_0000_0000,
+    0x7FF0_0000_0000_0000,
+    0xFFF0_0000_0000_0000,
+    0x7FF8_0000_0000_0000,
+    0x7FF0_0000_0000_0001,
+    0x0000_0000_0000_0001,
+    0x000F_FFFF_FFFF_FFFF,
+    0x0010_0000_0000_0000,
+    0x7FEF_FFFF_FFFF_FFFF,
+    0x3FF0_0000_0000_0000,
+    // binary32 pairs of the same
+    0x7F80_0000_8000_0000,
+    0x7FC0_0000_7F80_0001,
+    0x0000_0001_007F_FFFF,
+    0x7F7F_FFFF_3F80_0000,
+];
+
+/// A part of the machine state which can differ between native execution and the lifted semantics.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StateField {
+    Gpr(Reg64WithRIP),
+    Flag(Flag),
+    /// A 64 bit lane of a vector register.
+    Zmm { reg: usize, lane: usize },
+    /// Memory around RSP, see [`run_instruction_64`].
+    Memory,
+    Mxcsr,
+    /// A physical x87 register, whatever its tag.
+    X87Register(usize),
+    X87Control,
+    X87Status,
+    X87Tag,
+    /// The lifted semantics raised a fault where native execution did not, or there are none.
+    Fault,
+}
+
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub field: StateField,
+    /// Shrunk input which still diverges on `field`.
+    pub input: ConcreteX86MachineState64,
+    pub native: ConcreteX86MachineState64,
+    pub lifted: Result<ConcreteX86MachineState64, ConcreteError>,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} diverges for input {:?}: ", self.field, self.input)?;
+        match &self.lifted {
+            Ok(lifted) => {
+                match self.field {
+                    StateField::Gpr(reg) => write!(f, "native {:#x}, lifted {:#x}", self.native.gpr64(reg), lifted.gpr64(reg)),
+                    StateField::Flag(flag) => write!(f, "native {}, lifted {}", self.native.get_flag(flag), lifted.get_flag(flag)),
+                    StateField::Zmm { reg, lane } => write!(f, "native {:#x}, lifted {:#x}", self.native.zmms[reg][lane], lifted.zmms[reg][lane]),
+                    StateField::Mxcsr => write!(f, "native {:#x}, lifted {:#x}", self.native.mxcsr, lifted.mxcsr),
+                    StateField::X87Register(physical) => write!(f, "native {:#x}, lifted {:#x}", self.native.x87_registers[physical], lifted.x87_registers[physical]),
+                    StateField::X87Control => write!(f, "native {:#x}, lifted {:#x}", self.native.x87_control, lifted.x87_control),
+                    StateField::X87Status => write!(f, "native {:#x}, lifted {:#x}", self.native.x87_status, lifted.x87_status),
+                    StateField::X87Tag => write!(f, "native {:#x}, lifted {:#x}", self.native.x87_tag, lifted.x87_tag),
+                    StateField::Memory => {
+                        let address = first_memory_difference(&self.native, lifted).unwrap();
+                        write!(f, "at {address:#x} native {:?}, lifted {:?}", self.native.read_memory(address, 8), lifted.read_memory(address, 8))
+                    }
+                    StateField::Fault => unreachable!(),
+                }
+            }
+            Err(fault) => write!(f, "lifted semantics raised {fault:?}"),
+        }
+    }
+}
+
+/// Lowest address at which the bytes of `left` and `right` differ, with bytes missing from either being zero.
+fn first_memory_difference(left: &ConcreteX86MachineState64, right: &ConcreteX86MachineState64) -> Option<u64> {
+    left.memory.keys().chain(right.memory.keys())
+        .filter(|address| left.memory.get(address).unwrap_or(&0) != right.memory.get(address).unwrap_or(&0))
+        .min()
+        .copied()
+}
+
+/// Runs one instruction natively and through [`apply_instruction_concrete`] on the same inputs and compares the
+/// results. Flags xed reports as undefined for the instruction are not compared, and vector state the host can't
+/// load (anything above ymm0-15 without AVX-512, and above xmm0-15 without AVX) is neither generated nor compared.
+///
+/// The instruction must not fault natively, or touch memory other than the stack around RSP. The harness has no way
+/// of recovering from either. Generated states have an empty stack, and MXCSR and the x87 control word mask every
+/// exception for the same reason.
+pub struct DifferentialTester {
+    instr: X86Instruction,
+    length: usize,
+    rng: StdRng,
+    undefined_flags: HashSet<Flag>,
+    avx512: bool,
+    avx: bool,
+}
+
+impl DifferentialTester {
+    pub fn new(instr: X86Instruction, seed: u64) -> Self {
+        let operand_info = instr.operand_info(&mut EncodeDecodeContext::new())
+            .expect("instruction can't be encoded in 64 bit mode");
+        let (_, length) = instr.encode(&mut EncodeDecodeContext::new()).unwrap();
+        let undefined_flags = operand_info.flags_undefined.iter()
+            .filter_map(|flag| match flag {
+                X86Flag::CF => Some(Flag::CF),
+                X86Flag::PF => Some(Flag::PF),
+                X86Flag::AF => Some(Flag::AF),
+                X86Flag::ZF => Some(Flag::ZF),
+                X86Flag::SF => Some(Flag::SF),
+                X86Flag::OF => Some(Flag::OF),
+                _ => None,
+            })
+            .collect();
+        Self {
+            instr,
+            length,
+            rng: StdRng::seed_from_u64(seed),
+            undefined_flags,
+            avx512: host_has_avx512(),
+            avx: host_has_avx(),
+        }
+    }
+
+    fn vector_lanes(&self) -> (usize, usize) {
+        if self.avx512 {
+            (32, 8)
+        } else if self.avx {
+            (16, 4)
+        } else {
+            (16, 2)
+        }
+    }
+
+    fn gen_u64(&mut self) -> u64 {
+        if self.rng.gen_ratio(1, 4) {
+            EDGE_CASES[self.rng.gen_range(0..EDGE_CASES.len())]
+        } else {
+            self.rng.gen()
+        }
+    }
+
+    fn gen_vector_u64(&mut self) -> u64 {
+        if self.rng.gen_ratio(1, 4) {
+            FLOAT_EDGE_CASES[self.rng.gen_range(0..FLOAT_EDGE_CASES.len())]
+        } else {
+            self.gen_u64()
+        }
+    }
+
+    /// Any rounding mode, DAZ and FTZ, and exception flags, with every exception masked.
+    fn gen_mxcsr(&mut self) -> u32 {
+        let rounding = self.rng.gen_range(0..4u32) << 13;
+        let daz = (self.rng.gen::<bool>() as u32) << 6;
+        let ftz = (self.rng.gen::<bool>() as u32) << 15;
+        let flags = self.rng.gen_range(0..0x40u32);
+        Mxcsr::DEFAULT.0 | rounding | daz | ftz | flags
+    }
+
+    /// An 80 bit value, biased towards zeros, denormals, infinities, NaNs and the unsupported encodings.
+    fn gen_x87_register(&mut self) -> u128 {
+        let sign = (self.rng.gen::<bool>() as u128) << 79;
+        let exponent: u128 = match self.rng.gen_range(0..8) {
+            0 => 0,
+            1 => 0x7FFF,
+            2 => self.rng.gen_range(0x3FF0..0x4010),
+            _ => self.rng.gen_range(0..0x8000),
+        };
+        let significand = match self.rng.gen_range(0..8) {
+            0 => 0,
+            1 => 1 << 63,
+            2 => 0xC000_0000_0000_0000,
+            3 => self.rng.gen::<u64>() & !(1 << 63),
+            _ => self.rng.gen::<u64>() | (1 << 63),
+        };
+        sign | (exponent << 64) | significand as u128
+    }
+
+    /// Random registers, tags, TOP, condition codes and flags. Every exception is masked, so ES is never set, and
+    /// precision control is never the reserved value.
+    fn gen_x87(&mut self, state: &mut ConcreteX86MachineState64) {
+        let precision = [0u16, 2, 3][self.rng.gen_range(0..3)] << 8;
+        let rounding = self.rng.gen_range(0..4u16) << 10;
+        state.x87_control = 0x7F | precision | rounding;
+        state.x87_status = self.rng.gen::<u16>() & 0x7F7F;
+        for st in 0..8 {
+            if self.rng.gen_ratio(2, 3) {
+                let value = self.gen_x87_register();
+                state.set_x87_register(st, value);
+            } else {
+                state.x87_registers[st] = self.gen_x87_register();
+            }
+        }
+    }
+
+    /// A random state, with values from [`EDGE_CASES`] and [`FLOAT_EDGE_CASES`] mixed in.
+    pub fn gen_state(&mut self) -> ConcreteX86MachineState64 {
+        let mut state = ConcreteX86MachineState64::zeroed();
+        for reg in COMPARED_GPRS {
+            *state.gpr64_mut(reg) = self.gen_u64();
+        }
+        state = state.flags(ConcreteFlags::zeroed()
+            .cf(self.rng.gen())
+            .pf(self.rng.gen())
+            .af(self.rng.gen())
+            .zf(self.rng.gen())
+            .sf(self.rng.gen())
+            .of(self.rng.gen()));
+        let (regs, lanes) = self.vector_lanes();
+        for reg in 0..regs {
+            for lane in 0..lanes {
+                state.zmms[reg][lane] = self.gen_vector_u64();
+            }
+        }
+        self.gen_x87(&mut state);
+        let mxcsr = self.gen_mxcsr();
+        state.mxcsr(mxcsr)
+    }
+
+    fn diverging_fields(&self, native: &ConcreteX86MachineState64, lifted: &Result<ConcreteX86MachineState64, ConcreteError>) -> Vec<StateField> {
+        let lifted = match lifted {
+            Ok(lifted) => lifted,
+            Err(_) => return vec![StateField::Fault],
+        };
+        let mut res = vec![];
+        for reg in COMPARED_GPRS {
+            if native.gpr64(reg) != lifted.gpr64(reg) {
+                res.push(StateField::Gpr(reg));
+            }
+        }
+        for flag in FLAGS {
+            if !self.undefined_flags.contains(&flag) && native.get_flag(flag) != lifted.get_flag(flag) {
+                res.push(StateField::Flag(flag));
+            }
+        }
+        let (regs, lanes) = self.vector_lanes();
+        for reg in 0..regs {
+            for lane in 0..lanes {
+                if native.zmms[reg][lane] != lifted.zmms[reg][lane] {
+                    res.push(StateField::Zmm { reg, lane });
+                }
+            }
+        }
+        if native.mxcsr != lifted.mxcsr {
+            res.push(StateField::Mxcsr);
+        }
+        for physical in 0..8 {
+            if native.x87_registers[physical] != lifted.x87_registers[physical] {
+                res.push(StateField::X87Register(physical));
+            }
+        }
+        if native.x87_control != lifted.x87_control {
+            res.push(StateField::X87Control);
+        }
+        if native.x87_status != lifted.x87_status {
+            res.push(StateField::X87Status);
+        }
+        if native.x87_tag != lifted.x87_tag {
+            res.push(StateField::X87Tag);
+        }
+        if first_memory_difference(native, lifted).is_some() {
+            res.push(StateField::Memory);
+        }
+        res
+    }
+
+    fn run(&self, input: ConcreteX86MachineState64) -> (ConcreteX86MachineState64, Result<ConcreteX86MachineState64, ConcreteError>) {
+        (run_instruction_64(self.instr, input.clone()), apply_instruction_concrete(self.instr, self.length, input))
+    }
+
+    fn diverges_on(&self, input: ConcreteX86MachineState64, field: StateField) -> bool {
+        let (native, lifted) = self.run(input);
+        self.diverging_fields(&native, &lifted).contains(&field)
+    }
+
+    /// Greedily zeroes registers, clears flags, resets MXCSR and the x87 state and then clears single bits of `input` for as long as
+    /// it still diverges on `field`.
+    fn shrink(&self, mut input: ConcreteX86MachineState64, field: StateField) -> ConcreteX86MachineState64 {
+        let (regs, lanes) = self.vector_lanes();
+        let try_candidate = |candidate: ConcreteX86MachineState64, input: &mut ConcreteX86MachineState64| {
+            if candidate != *input && self.diverges_on(candidate.clone(), field) {
+                *input = candidate;
+            }
+        };
+        for reg in COMPARED_GPRS {
+            let mut candidate = input.clone();
+            *candidate.gpr64_mut(reg) = 0;
+            try_candidate(candidate, &mut input);
+        }
+        for flag in FLAGS {
+            let mut candidate = input.clone();
+            candidate.set_flag(flag, false);
+            try_candidate(candidate, &mut input);
+        }
+        for reg in 0..regs {
+            for lane in 0..lanes {
+                let mut candidate = input.clone();
+                candidate.zmms[reg][lane] = 0;
+                try_candidate(candidate, &mut input);
+            }
+        }
+        try_candidate(input.clone().mxcsr(Mxcsr::DEFAULT.0), &mut input);
+        let zeroed = ConcreteX86MachineState64::zeroed();
+        let mut candidate = input.clone();
+        candidate.x87_registers = zeroed.x87_registers;
+        candidate.x87_control = zeroed.x87_control;
+        candidate.x87_status = zeroed.x87_status;
+        candidate.x87_tag = zeroed.x87_tag;
+        try_candidate(candidate, &mut input);
+        for reg in COMPARED_GPRS {
+            for bit in (0..64).rev() {
+                if input.gpr64(reg) & (1 << bit) != 0 {
+                    let mut candidate = input.clone();
+                    *candidate.gpr64_mut(reg) &= !(1 << bit);
+                    try_candidate(candidate, &mut input);
+                }
+            }
+        }
+        input
+    }
+
+    /// Tests `iterations` generated states, returning the first divergence found for each field, shrunk.
+    pub fn run_iterations(&mut self, iterations: usize) -> Vec<Divergence> {
+        let mut found = HashSet::new();
+        let mut res = vec![];
+        for _ in 0..iterations {
+            let input = self.gen_state();
+            let (native, lifted) = self.run(input.clone());
+            for field in self.diverging_fields(&native, &lifted) {
+                if found.insert(field) {
+                    let input = self.shrink(input.clone(), field);
+                    let (native, lifted) = self.run(input.clone());
+                    res.push(Divergence { field, input, native, lifted });
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Panics listing every divergence found in `iterations` random states.
+pub fn assert_no_divergence(instr: X86Instruction, iterations: usize) {
+    let divergences = DifferentialTester::new(instr, 0).run_iterations(iterations);
+    if !divergences.is_empty() {
+        let report = divergences.iter().map(|divergence| divergence.to_string()).collect::<Vec<_>>().join("\n");
+        panic!("{instr:?} diverges from native execution:\n{report}");
+    }
+}
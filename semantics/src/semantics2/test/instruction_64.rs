@@ -1,13 +1,12 @@
 use std::arch::asm;
-use std::arch::x86_64::__cpuid;
-use std::ffi::{c_uint, CStr};
+use std::arch::x86_64::{__cpuid, __cpuid_count, __get_cpuid_max, _xgetbv};
 use std::ptr::null_mut;
-use xed_sys::{xed_encode, xed_error_enum_t2str};
 use wrapper_common::memory_operand::{GeneralReg, X86Scale};
 use wrapper_common::registers::Reg64WithRIP;
 use xed_enum::{EncodeDecodeContext, JMP, X86Instruction};
 use xed_wrapper::operands::MemoryOperands;
 use crate::semantics2::state::{ConcreteFlags, ConcreteX86MachineState64};
+use crate::semantics2::x87_float::x87_tag;
 
 #[repr(C, align(64))]
 pub struct Registers {
@@ -18,7 +17,7 @@ pub struct Registers {
     rdx: u64,
     rsi: u64,
     rdi: u64,
-    rbp_unused: u64,
+    rbp: u64,
     r8: u64,
     r9: u64,
     r10: u64,
@@ -30,32 +29,100 @@ pub struct Registers {
     rip: u64,
     return_address: u64,
     zmms: [[u64;8];32],
+    mxcsr: u32,
+    /// The harness' own MXCSR while the instruction runs.
+    host_mxcsr: u32,
+    x87: FxSaveArea,
+    /// The harness' own x87 state while the instruction runs.
+    host_x87: FxSaveArea,
 }
 
+/// The FXSAVE image. Only the x87 part of it is used, MXCSR is loaded and stored separately.
+#[repr(C, align(16))]
+#[derive(Copy, Clone)]
+struct FxSaveArea([u8; 512]);
+
+impl FxSaveArea {
+    fn from_state(state: &ConcreteX86MachineState64) -> Self {
+        let mut area = [0u8; 512];
+        area[0..2].copy_from_slice(&state.x87_control.to_le_bytes());
+        area[2..4].copy_from_slice(&state.x87_status.to_le_bytes());
+        // the abridged tag word has a bit per physical register, set if it isn't empty
+        area[4] = (0..8).filter(|physical| (state.x87_tag >> (2 * physical)) & 3 != 3).fold(0, |tag, physical| tag | (1 << physical));
+        area[24..28].copy_from_slice(&0x1F80u32.to_le_bytes());
+        for st in 0..8 {
+            area[32 + 16 * st..32 + 16 * st + 10].copy_from_slice(&state.x87_register(st).to_le_bytes()[..10]);
+        }
+        FxSaveArea(area)
+    }
+
+    /// Control, status, tag and physical registers. Tags of registers which aren't empty are worked out from their
+    /// contents, the way FXRSTOR does.
+    fn to_state(&self) -> (u16, u16, u16, [u128; 8]) {
+        let control = u16::from_le_bytes([self.0[0], self.0[1]]);
+        let status = u16::from_le_bytes([self.0[2], self.0[3]]);
+        let top = ((status >> 11) & 7) as usize;
+        let mut registers = [0u128; 8];
+        for st in 0..8 {
+            let mut bytes = [0u8; 16];
+            bytes[..10].copy_from_slice(&self.0[32 + 16 * st..32 + 16 * st + 10]);
+            registers[(top + st) % 8] = u128::from_le_bytes(bytes);
+        }
+        let tag = (0..8).fold(0, |tag, physical| {
+            let physical_tag = if self.0[4] & (1 << physical) == 0 { 3 } else { x87_tag(registers[physical]) };
+            tag | (physical_tag << (2 * physical))
+        });
+        (control, status, tag, registers)
+    }
+}
+
+/// AVX-512F is supported by the cpu and enabled by the OS, so the harness can load and store zmm registers.
+pub(crate) fn host_has_avx512() -> bool {
+    unsafe {
+        let os_xsave = __cpuid(1).ecx & (1 << 27) != 0;
+        let avx512f = __get_cpuid_max(0).0 >= 7 && __cpuid_count(7, 0).ebx & (1 << 16) != 0;
+        // xmm, ymm, opmask, zmm0-15 upper halves and zmm16-31 state enabled in XCR0
+        os_xsave && avx512f && _xgetbv(0) & 0xE6 == 0xE6
+    }
+}
+
+/// AVX is supported by the cpu and enabled by the OS, so the harness can load and store ymm0-15.
+pub(crate) fn host_has_avx() -> bool {
+    unsafe {
+        let cpuid = __cpuid(1).ecx;
+        let os_xsave = cpuid & (1 << 27) != 0;
+        let avx = cpuid & (1 << 28) != 0;
+        // xmm and ymm state enabled in XCR0
+        os_xsave && avx && _xgetbv(0) & 0x6 == 0x6
+    }
+}
+
+/// Bytes of `memory` either side of `start.rsp` which are copied onto the harness stack, and back afterwards.
+pub(crate) const STACK_WINDOW: usize = 2048;
+
+/// Without AVX-512 only the low 256 bits of ymm0-15 are loaded and stored, or the low 128 bits of xmm0-15 without AVX
+/// either. The rest of `zmms` is passed through unchanged.
+///
+/// The instruction runs on a stack the harness maps, which stands in for the [`STACK_WINDOW`] bytes of memory
+/// around `start.rsp`. RSP is translated back and forth, so pushes and pops behave as they would at `start.rsp`,
+/// but an instruction which reads RSP as a value sees the address of the harness stack. Bytes of the window the
+/// instruction changes are written back to `memory`, nothing else of `memory` is visible natively.
 pub(crate) fn run_instruction_64(instr: X86Instruction, start: ConcreteX86MachineState64) -> ConcreteX86MachineState64 {
     let mut encode = EncodeDecodeContext::new();
     let mut array = [0u8; 32];
-    let mut encoder_request = instr.to_xed(&mut encode);
-    let mut olen = 0;
-    let err = unsafe { xed_encode(&mut encoder_request, array.as_mut_ptr(), array.len() as c_uint, &mut olen) };
-    if err != 0 {
-        panic!()
-    }
-    let mut encoder_request = X86Instruction::JMP(JMP::JMP_MEMV_64 {
+    let (instr_bytes, instr_len) = instr.encode(&mut encode).unwrap();
+    array[..instr_len].copy_from_slice(&instr_bytes[..instr_len]);
+    let (jmp_bytes, jmp_len) = X86Instruction::JMP(JMP::JMP_MEMV_64 {
         operand_0: MemoryOperands::SIBAddressing {
             segment: None,
             scale: X86Scale::One,
             index: None,
-            base: GeneralReg::Reg64(Reg64WithRIP::R15),
+            base: Some(GeneralReg::Reg64(Reg64WithRIP::R15)),
             disp: 136,
             disp_width: 32,
         }
-    }).to_xed(&mut encode);
-    let err = unsafe { xed_encode(&mut encoder_request, array[(olen as usize)..].as_mut_ptr(), array.len() as c_uint, &mut olen) };
-    if err != 0 {
-        unsafe { dbg!(CStr::from_ptr(unsafe { xed_error_enum_t2str(err) }).to_str().unwrap()); }
-        panic!()
-    }
+    }).encode(&mut encode).unwrap();
+    array[instr_len..instr_len + jmp_len].copy_from_slice(&jmp_bytes[..jmp_len]);
     let instructions = unsafe {
         libc::mmap(null_mut(), 4096, libc::PROT_READ | libc::PROT_WRITE | libc::PROT_EXEC, libc::MAP_ANON | libc::MAP_PRIVATE, -1, 0)
     };
@@ -66,17 +133,18 @@ pub(crate) fn run_instruction_64(instr: X86Instruction, start: ConcreteX86Machin
 
     unsafe { std::hint::black_box(__cpuid(0)); }
 
-    let mut stack = unsafe {
+    let stack_mapping = unsafe {
         libc::mmap(null_mut(), 4096, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_ANON | libc::MAP_PRIVATE, -1, 0)
     };
-    if stack.is_null() {
+    if stack_mapping.is_null() {
         panic!()
     }
-    unsafe {
-        libc::memset(stack, 0, 4096);
-        stack = stack.add(2048);
-        let align = stack.align_offset(64);
-        stack = stack.add(align);
+    let stack = unsafe { stack_mapping.add(STACK_WINDOW) };
+    // native address stack_mapping + i stands in for stack_window_start + i
+    let stack_window_start = start.rsp.wrapping_sub(STACK_WINDOW as u64);
+    for i in 0..2 * STACK_WINDOW {
+        let byte = *start.memory.get(&stack_window_start.wrapping_add(i as u64)).unwrap_or(&0);
+        unsafe { *(stack_mapping as *mut u8).add(i) = byte; }
     }
     let mut zmms = start.zmms;
     let mut registers = Registers {
@@ -87,7 +155,7 @@ pub(crate) fn run_instruction_64(instr: X86Instruction, start: ConcreteX86Machin
         rdx: start.rdx,
         rsi: start.rsi,
         rdi: start.rdi,
-        rbp_unused: 0,
+        rbp: start.rbp,
         r8: start.r8,
         r9: start.r9,
         r10: start.r10,
@@ -99,8 +167,31 @@ pub(crate) fn run_instruction_64(instr: X86Instruction, start: ConcreteX86Machin
         rip: instructions as u64,
         return_address: 0,
         zmms,
+        mxcsr: start.mxcsr,
+        host_mxcsr: 0,
+        x87: FxSaveArea::from_state(&start),
+        host_x87: FxSaveArea([0; 512]),
     };
-    run_instruction_64_impl(&mut registers);
+    if host_has_avx512() {
+        run_instruction_64_impl(&mut registers);
+    } else if host_has_avx() {
+        run_instruction_64_impl_avx(&mut registers);
+    } else {
+        run_instruction_64_impl_sse(&mut registers);
+    }
+    let mut memory = start.memory;
+    for i in 0..2 * STACK_WINDOW {
+        let address = stack_window_start.wrapping_add(i as u64);
+        let byte = unsafe { *(stack_mapping as *const u8).add(i) };
+        if byte != *memory.get(&address).unwrap_or(&0) {
+            memory.insert(address, byte);
+        }
+    }
+    unsafe {
+        libc::munmap(instructions, 4096);
+        libc::munmap(stack_mapping, 4096);
+    }
+    let (x87_control, x87_status, x87_tag, x87_registers) = registers.x87.to_state();
     ConcreteX86MachineState64 {
         rax: registers.rax,
         rbx: registers.rbx,
@@ -108,8 +199,8 @@ pub(crate) fn run_instruction_64(instr: X86Instruction, start: ConcreteX86Machin
         rdx: registers.rdx,
         rsi: registers.rsi,
         rdi: registers.rdi,
-        rsp: registers.rsp,
-        rbp: registers.rbp_unused,
+        rsp: start.rsp.wrapping_add(registers.rsp.wrapping_sub(stack as u64)),
+        rbp: registers.rbp,
         r8: registers.r8,
         r9: registers.r9,
         r10: registers.r10,
@@ -128,6 +219,15 @@ pub(crate) fn run_instruction_64(instr: X86Instruction, start: ConcreteX86Machin
             of: registers.flags & 2048 != 0,
         },
         zmms: registers.zmms,
+        mxcsr: registers.mxcsr,
+        x87_registers,
+        x87_control,
+        x87_status,
+        x87_tag,
+        segments: start.segments,
+        fs_base: start.fs_base,
+        gs_base: start.gs_base,
+        memory,
     }
 }
 
@@ -139,11 +239,15 @@ fn run_instruction_64_impl(registers: &mut Registers) {
         asm!(
         "push r15",
         "push rbx",
+        "push rbp",
         "mov r15, {0}",
-        "xchg rsp, [r15]",
+        "fxsave [r15 + 2720]",
+        "fxrstor [r15 + 2208]",
+        // flags are loaded on the host stack, so nothing of the harness' own ends up on the instruction's stack
         "mov rax, [r15 + 120]",
         "push rax",
         "popfq",
+        "xchg rsp, [r15]",
         "lea rax, [rip + ___capstone_wrapper_semantics_test_internal]",
         "mov [r15 + 136], rax",
         "mov rax, [r15 + 8]",
@@ -152,7 +256,7 @@ fn run_instruction_64_impl(registers: &mut Registers) {
         "mov rdx, [r15 + 32]",
         "mov rsi, [r15 + 40]",
         "mov rdi, [r15 + 48]",
-        // "mov rbp, [r15 + 56]",
+        "mov rbp, [r15 + 56]",
         "mov r8, [r15 + 64]",
         "mov r9, [r15 + 72]",
         "mov r10, [r15 + 80]",
@@ -192,18 +296,23 @@ fn run_instruction_64_impl(registers: &mut Registers) {
         "vmovdqu64 zmm29, [r15 + 144 + 29*64]",
         "vmovdqu64 zmm30, [r15 + 144 + 30*64]",
         "vmovdqu64 zmm31, [r15 + 144 + 31*64]",
+        "stmxcsr dword ptr [r15 + 2196]",
+        "ldmxcsr dword ptr [r15 + 2192]",
         "jmp [r15 + 128]",
         "___capstone_wrapper_semantics_test_internal:",
         "xchg rsp, [r15]",
         "pushfq",
         "pop qword ptr [r15 + 120]",
+        "stmxcsr dword ptr [r15 + 2192]",
+        "ldmxcsr dword ptr [r15 + 2196]",
+        "fxsave [r15 + 2208]",
         "mov [r15 + 8], rax",
         "mov [r15 + 16], rbx",
         "mov [r15 + 24], rcx",
         "mov [r15 + 32], rdx",
         "mov [r15 + 40], rsi",
         "mov [r15 + 48], rdi",
-        // "mov [r15 + 56], rbp",
+        "mov [r15 + 56], rbp",
         "mov [r15 + 64], r8",
         "mov [r15 + 72], r9",
         "mov [r15 + 80], r10",
@@ -243,6 +352,8 @@ fn run_instruction_64_impl(registers: &mut Registers) {
         "vmovdqu64 [r15 + 144 + 29*64], zmm29",
         "vmovdqu64 [r15 + 144 + 30*64], zmm30",
         "vmovdqu64 [r15 + 144 + 31*64], zmm31",
+        "fxrstor [r15 + 2720]",
+        "pop rbp",
         "pop rbx",
         "pop r15",
         in(reg) registers_ptr,
@@ -294,3 +405,259 @@ fn run_instruction_64_impl(registers: &mut Registers) {
         )
     }
 }
+
+#[inline(never)]
+#[allow(named_asm_labels)]
+fn run_instruction_64_impl_sse(registers: &mut Registers) {
+    unsafe {
+        let registers_ptr = ((registers) as *mut Registers);
+        asm!(
+        "push r15",
+        "push rbx",
+        "push rbp",
+        "mov r15, {0}",
+        "fxsave [r15 + 2720]",
+        "fxrstor [r15 + 2208]",
+        // flags are loaded on the host stack, so nothing of the harness' own ends up on the instruction's stack
+        "mov rax, [r15 + 120]",
+        "push rax",
+        "popfq",
+        "xchg rsp, [r15]",
+        "lea rax, [rip + ___capstone_wrapper_semantics_test_internal_sse]",
+        "mov [r15 + 136], rax",
+        "mov rax, [r15 + 8]",
+        "mov rbx, [r15 + 16]",
+        "mov rcx, [r15 + 24]",
+        "mov rdx, [r15 + 32]",
+        "mov rsi, [r15 + 40]",
+        "mov rdi, [r15 + 48]",
+        "mov rbp, [r15 + 56]",
+        "mov r8, [r15 + 64]",
+        "mov r9, [r15 + 72]",
+        "mov r10, [r15 + 80]",
+        "mov r11, [r15 + 88]",
+        "mov r12, [r15 + 96]",
+        "mov r13, [r15 + 104]",
+        "mov r14, [r15 + 112]",
+        "movdqu xmm0, [r15 + 144]",
+        "movdqu xmm1, [r15 + 144 + 1*64]",
+        "movdqu xmm2, [r15 + 144 + 2*64]",
+        "movdqu xmm3, [r15 + 144 + 3*64]",
+        "movdqu xmm4, [r15 + 144 + 4*64]",
+        "movdqu xmm5, [r15 + 144 + 5*64]",
+        "movdqu xmm6, [r15 + 144 + 6*64]",
+        "movdqu xmm7, [r15 + 144 + 7*64]",
+        "movdqu xmm8, [r15 + 144 + 8*64]",
+        "movdqu xmm9, [r15 + 144 + 9*64]",
+        "movdqu xmm10, [r15 + 144 + 10*64]",
+        "movdqu xmm11, [r15 + 144 + 11*64]",
+        "movdqu xmm12, [r15 + 144 + 12*64]",
+        "movdqu xmm13, [r15 + 144 + 13*64]",
+        "movdqu xmm14, [r15 + 144 + 14*64]",
+        "movdqu xmm15, [r15 + 144 + 15*64]",
+        "stmxcsr dword ptr [r15 + 2196]",
+        "ldmxcsr dword ptr [r15 + 2192]",
+        "jmp [r15 + 128]",
+        "___capstone_wrapper_semantics_test_internal_sse:",
+        "xchg rsp, [r15]",
+        "pushfq",
+        "pop qword ptr [r15 + 120]",
+        "stmxcsr dword ptr [r15 + 2192]",
+        "ldmxcsr dword ptr [r15 + 2196]",
+        "fxsave [r15 + 2208]",
+        "mov [r15 + 8], rax",
+        "mov [r15 + 16], rbx",
+        "mov [r15 + 24], rcx",
+        "mov [r15 + 32], rdx",
+        "mov [r15 + 40], rsi",
+        "mov [r15 + 48], rdi",
+        "mov [r15 + 56], rbp",
+        "mov [r15 + 64], r8",
+        "mov [r15 + 72], r9",
+        "mov [r15 + 80], r10",
+        "mov [r15 + 88], r11",
+        "mov [r15 + 96], r12",
+        "mov [r15 + 104], r13",
+        "mov [r15 + 112], r14",
+        "movdqu [r15 + 144], xmm0",
+        "movdqu [r15 + 144 + 1*64], xmm1",
+        "movdqu [r15 + 144 + 2*64], xmm2",
+        "movdqu [r15 + 144 + 3*64], xmm3",
+        "movdqu [r15 + 144 + 4*64], xmm4",
+        "movdqu [r15 + 144 + 5*64], xmm5",
+        "movdqu [r15 + 144 + 6*64], xmm6",
+        "movdqu [r15 + 144 + 7*64], xmm7",
+        "movdqu [r15 + 144 + 8*64], xmm8",
+        "movdqu [r15 + 144 + 9*64], xmm9",
+        "movdqu [r15 + 144 + 10*64], xmm10",
+        "movdqu [r15 + 144 + 11*64], xmm11",
+        "movdqu [r15 + 144 + 12*64], xmm12",
+        "movdqu [r15 + 144 + 13*64], xmm13",
+        "movdqu [r15 + 144 + 14*64], xmm14",
+        "movdqu [r15 + 144 + 15*64], xmm15",
+        "fxrstor [r15 + 2720]",
+        "pop rbp",
+        "pop rbx",
+        "pop r15",
+        in(reg) registers_ptr,
+        out("xmm0") _,
+        out("xmm1") _,
+        out("xmm2") _,
+        out("xmm3") _,
+        out("xmm4") _,
+        out("xmm5") _,
+        out("xmm6") _,
+        out("xmm7") _,
+        out("xmm8") _,
+        out("xmm9") _,
+        out("xmm10") _,
+        out("xmm11") _,
+        out("xmm12") _,
+        out("xmm13") _,
+        out("xmm14") _,
+        out("xmm15") _,
+        out("rax") _,
+        // out("rbx") _,
+        out("rcx") _,
+        out("rdx") _,
+        out("rsi") _,
+        out("rdi") _,
+        out("r8") _,
+        out("r9") _,
+        out("r10") _,
+        out("r11") _,
+        out("r12") _,
+        out("r13") _,
+        out("r14") _,
+        out("r15") _,
+        )
+    }
+}
+
+#[inline(never)]
+#[allow(named_asm_labels)]
+fn run_instruction_64_impl_avx(registers: &mut Registers) {
+    unsafe {
+        let registers_ptr = ((registers) as *mut Registers);
+        asm!(
+        "push r15",
+        "push rbx",
+        "push rbp",
+        "mov r15, {0}",
+        "fxsave [r15 + 2720]",
+        "fxrstor [r15 + 2208]",
+        // flags are loaded on the host stack, so nothing of the harness' own ends up on the instruction's stack
+        "mov rax, [r15 + 120]",
+        "push rax",
+        "popfq",
+        "xchg rsp, [r15]",
+        "lea rax, [rip + ___capstone_wrapper_semantics_test_internal_avx]",
+        "mov [r15 + 136], rax",
+        "mov rax, [r15 + 8]",
+        "mov rbx, [r15 + 16]",
+        "mov rcx, [r15 + 24]",
+        "mov rdx, [r15 + 32]",
+        "mov rsi, [r15 + 40]",
+        "mov rdi, [r15 + 48]",
+        "mov rbp, [r15 + 56]",
+        "mov r8, [r15 + 64]",
+        "mov r9, [r15 + 72]",
+        "mov r10, [r15 + 80]",
+        "mov r11, [r15 + 88]",
+        "mov r12, [r15 + 96]",
+        "mov r13, [r15 + 104]",
+        "mov r14, [r15 + 112]",
+        "vmovdqu ymm0, [r15 + 144]",
+        "vmovdqu ymm1, [r15 + 144 + 1*64]",
+        "vmovdqu ymm2, [r15 + 144 + 2*64]",
+        "vmovdqu ymm3, [r15 + 144 + 3*64]",
+        "vmovdqu ymm4, [r15 + 144 + 4*64]",
+        "vmovdqu ymm5, [r15 + 144 + 5*64]",
+        "vmovdqu ymm6, [r15 + 144 + 6*64]",
+        "vmovdqu ymm7, [r15 + 144 + 7*64]",
+        "vmovdqu ymm8, [r15 + 144 + 8*64]",
+        "vmovdqu ymm9, [r15 + 144 + 9*64]",
+        "vmovdqu ymm10, [r15 + 144 + 10*64]",
+        "vmovdqu ymm11, [r15 + 144 + 11*64]",
+        "vmovdqu ymm12, [r15 + 144 + 12*64]",
+        "vmovdqu ymm13, [r15 + 144 + 13*64]",
+        "vmovdqu ymm14, [r15 + 144 + 14*64]",
+        "vmovdqu ymm15, [r15 + 144 + 15*64]",
+        "stmxcsr dword ptr [r15 + 2196]",
+        "ldmxcsr dword ptr [r15 + 2192]",
+        "jmp [r15 + 128]",
+        "___capstone_wrapper_semantics_test_internal_avx:",
+        "xchg rsp, [r15]",
+        "pushfq",
+        "pop qword ptr [r15 + 120]",
+        "stmxcsr dword ptr [r15 + 2192]",
+        "ldmxcsr dword ptr [r15 + 2196]",
+        "fxsave [r15 + 2208]",
+        "mov [r15 + 8], rax",
+        "mov [r15 + 16], rbx",
+        "mov [r15 + 24], rcx",
+        "mov [r15 + 32], rdx",
+        "mov [r15 + 40], rsi",
+        "mov [r15 + 48], rdi",
+        "mov [r15 + 56], rbp",
+        "mov [r15 + 64], r8",
+        "mov [r15 + 72], r9",
+        "mov [r15 + 80], r10",
+        "mov [r15 + 88], r11",
+        "mov [r15 + 96], r12",
+        "mov [r15 + 104], r13",
+        "mov [r15 + 112], r14",
+        "vmovdqu [r15 + 144], ymm0",
+        "vmovdqu [r15 + 144 + 1*64], ymm1",
+        "vmovdqu [r15 + 144 + 2*64], ymm2",
+        "vmovdqu [r15 + 144 + 3*64], ymm3",
+        "vmovdqu [r15 + 144 + 4*64], ymm4",
+        "vmovdqu [r15 + 144 + 5*64], ymm5",
+        "vmovdqu [r15 + 144 + 6*64], ymm6",
+        "vmovdqu [r15 + 144 + 7*64], ymm7",
+        "vmovdqu [r15 + 144 + 8*64], ymm8",
+        "vmovdqu [r15 + 144 + 9*64], ymm9",
+        "vmovdqu [r15 + 144 + 10*64], ymm10",
+        "vmovdqu [r15 + 144 + 11*64], ymm11",
+        "vmovdqu [r15 + 144 + 12*64], ymm12",
+        "vmovdqu [r15 + 144 + 13*64], ymm13",
+        "vmovdqu [r15 + 144 + 14*64], ymm14",
+        "vmovdqu [r15 + 144 + 15*64], ymm15",
+        "fxrstor [r15 + 2720]",
+        "pop rbp",
+        "pop rbx",
+        "pop r15",
+        in(reg) registers_ptr,
+        out("xmm0") _,
+        out("xmm1") _,
+        out("xmm2") _,
+        out("xmm3") _,
+        out("xmm4") _,
+        out("xmm5") _,
+        out("xmm6") _,
+        out("xmm7") _,
+        out("xmm8") _,
+        out("xmm9") _,
+        out("xmm10") _,
+        out("xmm11") _,
+        out("xmm12") _,
+        out("xmm13") _,
+        out("xmm14") _,
+        out("xmm15") _,
+        out("rax") _,
+        // out("rbx") _,
+        out("rcx") _,
+        out("rdx") _,
+        out("rsi") _,
+        out("rdi") _,
+        out("r8") _,
+        out("r9") _,
+        out("r10") _,
+        out("r11") _,
+        out("r12") _,
+        out("r13") _,
+        out("r14") _,
+        out("r15") _,
+        )
+    }
+}
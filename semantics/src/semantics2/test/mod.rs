@@ -3,23 +3,42 @@ use std::arch::x86_64::{__cpuid, __get_cpuid_max};
 use std::ffi::{c_uint, CStr};
 use std::mem::transmute;
 use std::ops::Add;
+use std::ptr;
 use std::ptr::null_mut;
+use std::io::Write;
+use std::process::{Command, Stdio};
 use bumpalo::Bump;
 use rand::{Rng, SeedableRng};
 
 use xed_sys::{xed_encode, xed_error_enum_t2str};
 
 use wrapper_common::memory_operand::{GeneralReg, X86Scale};
-use wrapper_common::registers::{Reg32WithRIP, Reg64WithRIP, Reg8, RegXMM};
-use xed_enum::{ADC, ADCX, ADD, EncodeDecodeContext, JMP, X86Instruction};
-use xed_wrapper::operands::MemoryOperands;
-use crate::semantics2::apply_instruction;
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8, RegFloat, RegSegment, RegXMM};
+use xed_enum::{AAA, AAD, ADC, ADCX, ADD, ADDPD, ADDSD, BSF, BTS, CALL_NEAR, CMOVL, CMP, CMPPS, CMPSD_XMM, COMISD, CVTSD2SI, CVTSD2SS, CVTSI2SD, CVTSI2SS, CVTSS2SD, CVTTSS2SI, DIV, DIVPD, DIVSD, ENTER, EncodeDecodeContext, FADD, FADDP, FCOM, FCOMPP, FDIV, FDIVR, FILD, FISTP, FLD, FLDCW, FNSTCW, FNSTSW, FSTP, FSUB, FXCH, IMUL, JMP, JZ, LDMXCSR, LEA, LEAVE, LOOP, MAXPS, MINSD, MOVZX, MULSD, MULSS, POP, POPFQ, PUSH, PUSHFQ, RCR, RET_NEAR, SBB, SETNBE, SHL, SHLD, SHR, SHRD, SQRTPS, SQRTSD, STMXCSR, SUB, SUBPS, TEST, UCOMISD, UCOMISS, VFMADD132SS, VFMADD231PD, X86Instruction, XCHG};
+use xed_wrapper::operands::{Imm16, Imm32, Imm8, MemoryOperands, RelativeBr};
+use crate::semantics2::{apply_instruction, apply_instruction_concrete, apply_instruction_with_address_width, ConcreteError};
 use crate::semantics2::arena::Arena;
+use crate::semantics2::basic_block::{lift_basic_block, BasicBlock, LiftError};
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::{Expression, Flag};
+use crate::semantics2::float::{FloatExceptions, Mxcsr};
+use crate::semantics2::llvm_ir::{exception_vector, LlvmIrError, LlvmIrModule};
+use crate::semantics2::rust_codegen::{load, store_all, RustCodegenError, RustModule};
+use crate::semantics2::semantic_steps::{apply_instructions_to_concrete, apply_instructions_to_concrete_in_place, ConcreteExecution, Fault};
+use crate::semantics2::simplify::Simplifier;
+use crate::semantics2::smtlib2::{to_smtlib2, SmtLib2Error, StateComponent};
+use crate::semantics2::symbolic::{SymbolicExecutor, SymbolicPath};
 
-use crate::semantics2::state::{ConcreteFlags, ConcreteX86MachineState64};
+use crate::semantics2::state::{ConcreteFlags, ConcreteX86MachineState64, X86MachineState64, X86Mode};
+use crate::semantics2::test::differential::assert_no_divergence;
 use crate::semantics2::test::instruction_64::run_instruction_64;
+use crate::semantics2::x87_float::X87_INDEFINITE;
 
-//todo actually execute the semantics to compare.
+/// [`apply_instruction_concrete`] with the length `instr` encodes to in 64 bit mode.
+fn apply_encoded_concrete(instr: X86Instruction, state: ConcreteX86MachineState64) -> Result<ConcreteX86MachineState64, ConcreteError> {
+    let (_, length) = instr.encode(&mut EncodeDecodeContext::new()).unwrap();
+    apply_instruction_concrete(instr, length, state)
+}
 
 #[test]
 pub fn test_adc() {
@@ -28,7 +47,7 @@ pub fn test_adc() {
     let b = 0x12u8;
     let c = 0x34u8;
     let state = run_instruction_64(instr, ConcreteX86MachineState64::zeroed().rbx(b as u64).rcx(c as u64));
-    let res = apply_instruction(Arena::new(&bump), instr);
+    let res = apply_instruction(Arena::new(&bump), instr, 2).unwrap();
 
     assert_eq!(state.rbx, (b + c) as u64);
     //generate a random number from a seeded entropy source
@@ -39,12 +58,14 @@ pub fn test_adc() {
         let carry_in = rng.gen::<bool>();
 
         let instr = X86Instruction::ADC(ADC::ADC_GPRV_GPRV_13_64 { operand_0: Reg64WithRIP::R8, operand_1: Reg64WithRIP::R9 });
-        let state = run_instruction_64(instr, ConcreteX86MachineState64::zeroed().r8(r8).r9(r9).flags(ConcreteFlags::zeroed().cf(carry_in)));
+        let initial = ConcreteX86MachineState64::zeroed().r8(r8).r9(r9).flags(ConcreteFlags::zeroed().cf(carry_in));
         let carry_in = if carry_in { 1 } else { 0 };
         let mut out = 0;
         let carry_out = unsafe { core::arch::x86_64::_addcarry_u64(carry_in, r8, r9, &mut out) };
-        assert_eq!(state.r8, out);
-        assert_eq!(state.flags.cf, carry_out != 0);
+        for state in [run_instruction_64(instr, initial.clone()), apply_encoded_concrete(instr, initial).unwrap()] {
+            assert_eq!(state.r8, out);
+            assert_eq!(state.flags.cf, carry_out != 0);
+        }
     }
 }
 
@@ -57,23 +78,27 @@ pub fn test_adcx() {
         let carry_in = rng.gen::<bool>();
 
         let instr = X86Instruction::ADCX(ADCX::ADCX_GPR64Q_GPR64Q { operand_0: Reg64WithRIP::R8, operand_1: Reg64WithRIP::R9 });
-        let state = run_instruction_64(instr, ConcreteX86MachineState64::zeroed().r8(r8).r9(r9).flags(ConcreteFlags::zeroed().cf(carry_in)));
+        let initial = ConcreteX86MachineState64::zeroed().r8(r8).r9(r9).flags(ConcreteFlags::zeroed().cf(carry_in));
         let carry_in = if carry_in { 1 } else { 0 };
         let mut out = 0;
         let carry_out = unsafe { core::arch::x86_64::_addcarryx_u64(carry_in, r8, r9, &mut out) };
-        assert_eq!(state.r8, out);
-        assert_eq!(state.flags.cf, carry_out != 0);
+        for state in [run_instruction_64(instr, initial.clone()), apply_encoded_concrete(instr, initial).unwrap()] {
+            assert_eq!(state.r8, out);
+            assert_eq!(state.flags.cf, carry_out != 0);
+        }
 
         let instr = X86Instruction::ADCX(ADCX::ADCX_GPR32D_GPR32D { operand_0: Reg32WithRIP::EDX, operand_1: Reg32WithRIP::EBX });
         let edx = rng.gen::<u32>();
         let ebx = rng.gen::<u32>();
         let carry_in = rng.gen::<bool>();
-        let state = run_instruction_64(instr, ConcreteX86MachineState64::zeroed().rdx(edx as u64).rbx(ebx as u64).flags(ConcreteFlags::zeroed().cf(carry_in)));
+        let initial = ConcreteX86MachineState64::zeroed().rdx(edx as u64).rbx(ebx as u64).flags(ConcreteFlags::zeroed().cf(carry_in));
         let carry_in = if carry_in { 1 } else { 0 };
         let mut out = 0;
         let carry_out = unsafe { core::arch::x86_64::_addcarryx_u32(carry_in, edx, ebx, &mut out) };
-        assert_eq!(state.rdx, out as u64);
-        assert_eq!(state.flags.cf, carry_out != 0);
+        for state in [run_instruction_64(instr, initial.clone()), apply_encoded_concrete(instr, initial).unwrap()] {
+            assert_eq!(state.rdx, out as u64);
+            assert_eq!(state.flags.cf, carry_out != 0);
+        }
     }
 }
 
@@ -84,8 +109,11 @@ pub fn test_add() {
         let r8 = rng.gen::<u64>();
         let r9 = rng.gen::<u64>();
         let instr = X86Instruction::ADD(ADD::ADD_GPRV_GPRV_01_64 { operand_0: Reg64WithRIP::R8, operand_1: Reg64WithRIP::R9 });
-        let state = run_instruction_64(instr, ConcreteX86MachineState64::zeroed().r8(r8).r9(r9));
-        assert_eq!(state.r8, r8.wrapping_add(r9));
+        let initial = ConcreteX86MachineState64::zeroed().r8(r8).r9(r9);
+        for state in [run_instruction_64(instr, initial.clone()), apply_encoded_concrete(instr, initial).unwrap()] {
+            assert_eq!(state.r8, r8.wrapping_add(r9));
+            assert_eq!(state.flags.cf, r8.checked_add(r9).is_none());
+        }
     }
 }
 
@@ -96,12 +124,1531 @@ pub fn test_addpd() {
         let xmm0 = rng.gen::<[f64;2]>();
         let xmm10 = rng.gen::<[f64;2]>();
         let instr = X86Instruction::ADDPD(xed_enum::ADDPD::ADDPD_XMMPD_XMMPD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM10 });
-        let state = run_instruction_64(instr, ConcreteX86MachineState64::zeroed().xmm0(xmm0).xmm10(xmm10));
-        unsafe {
-            assert_eq!(transmute::<u64,f64>(state.zmms[0][0]), xmm0[0].add(xmm10[0]));
-            assert_eq!(transmute::<u64,f64>(state.zmms[0][1]), xmm0[1].add(xmm10[1]));
+        let initial = ConcreteX86MachineState64::zeroed().xmm0(xmm0).xmm10(xmm10);
+        for state in [run_instruction_64(instr, initial.clone()), apply_encoded_concrete(instr, initial).unwrap()] {
+            unsafe {
+                assert_eq!(transmute::<u64,f64>(state.zmms[0][0]), xmm0[0].add(xmm10[0]));
+                assert_eq!(transmute::<u64,f64>(state.zmms[0][1]), xmm0[1].add(xmm10[1]));
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_concrete_adc_8() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for _ in 0..100 {
+        let rbx = rng.gen::<u64>();
+        let rcx = rng.gen::<u64>();
+        let instr = X86Instruction::ADC(ADC::ADC_GPR8_GPR8_12 { operand_0: Reg8::BL, operand_1: Reg8::CL });
+        let initial = ConcreteX86MachineState64::zeroed().rbx(rbx).rcx(rcx);
+        let native = run_instruction_64(instr, initial.clone());
+        let concrete = apply_encoded_concrete(instr, initial).unwrap();
+        assert_eq!(concrete.rbx, native.rbx);
+        assert_eq!(concrete.rcx, native.rcx);
+        assert_eq!(concrete.flags, native.flags);
+    }
+}
+
+#[test]
+pub fn test_concrete_aaa_faults() {
+    let instr = X86Instruction::AAA(AAA::AAA {});
+    assert_eq!(apply_instruction_concrete(instr, 1, ConcreteX86MachineState64::zeroed()), Err(ConcreteError::Fault(Fault::UndefinedOpcode)));
+    let instr = X86Instruction::AAD(AAD::AAD_IMMB { operand_0: Imm8(10) });
+    assert_eq!(apply_instruction_concrete(instr, 2, ConcreteX86MachineState64::zeroed()), Err(ConcreteError::Fault(Fault::UndefinedOpcode)));
+}
+
+/// AAA and AAD are #UD in 64 bit mode, so the adjustments are run without the mode check.
+fn ascii_adjust_concrete(adjust: impl FnOnce(&mut SemanticsBuilder), state: ConcreteX86MachineState64) -> ConcreteX86MachineState64 {
+    let bump = Bump::new();
+    let mut s = SemanticsBuilder::new(Arena::new(&bump));
+    adjust(&mut s);
+    apply_instructions_to_concrete(state, s.finalize().as_slice()).unwrap()
+}
+
+#[test]
+pub fn test_concrete_aaa() {
+    // 'Z' + 1 has a low nibble above 9
+    let res = ascii_adjust_concrete(|s| s.ascii_adjust_after_addition(), ConcreteX86MachineState64::zeroed().rax(0xFFFF_0000_0000_0000 | ('Z' as u64 + 1)));
+    assert_eq!(res.rax, 0xFFFF_0000_0000_0101);
+    assert!(res.flags.af && res.flags.cf);
+
+    // AX is left alone when there's nothing to adjust, apart from the high nibble of AL
+    let initial = ConcreteX86MachineState64::zeroed().rax(0x1234).flags(ConcreteFlags::zeroed().cf(true));
+    let res = ascii_adjust_concrete(|s| s.ascii_adjust_after_addition(), initial);
+    assert_eq!(res.rax, 0x1204);
+    assert!(!res.flags.af && !res.flags.cf);
+
+    let initial = ConcreteX86MachineState64::zeroed().rax(0x0912).flags(ConcreteFlags::zeroed().af(true));
+    let res = ascii_adjust_concrete(|s| s.ascii_adjust_after_addition(), initial);
+    assert_eq!(res.rax, 0x0A08);
+    assert!(res.flags.af && res.flags.cf);
+}
+
+#[test]
+pub fn test_concrete_aad() {
+    let ten = |s: &mut SemanticsBuilder| {
+        let base = s.constant(10u8);
+        s.ascii_adjust_before_division(base);
+    };
+    let res = ascii_adjust_concrete(ten, ConcreteX86MachineState64::zeroed().rax(0xAB_0907));
+    assert_eq!(res.rax, 0xAB_0061);
+    assert!(!res.flags.zf && !res.flags.sf && !res.flags.pf);
+
+    // AL wraps, and the flags come from the new AL
+    let res = ascii_adjust_concrete(ten, ConcreteX86MachineState64::zeroed().rax(0x1A_FC));
+    assert_eq!(res.rax, 0);
+    assert!(res.flags.zf && !res.flags.sf && res.flags.pf);
+}
+
+#[test]
+pub fn test_concrete_reads_see_state_before_their_step() {
+    let bump = Bump::new();
+    let mut s = SemanticsBuilder::new(Arena::new(&bump));
+    let old_al = s.al();
+    s.set_al(s.constant(0x11u8));
+    s.emit_conditional(
+        s.equal(s.al(), s.constant(0x11u8)),
+        |s| {
+            s.set_al(s.constant(0x22u8));
+            s.set_ah(s.al());
+        },
+        |s| s.set_ah(s.constant(0xFFu8)),
+    );
+    s.set_al(old_al);
+    let steps = s.finalize();
+    let res = apply_instructions_to_concrete(ConcreteX86MachineState64::zeroed().rax(0x1234_5678), steps.as_slice()).unwrap();
+    assert_eq!(res.rax, 0x1234_2278);
+
+    // flags and memory are read from before their writes too, once other steps have run in between
+    let mut s = SemanticsBuilder::new(Arena::new(&bump));
+    let address = s.constant(0x100u64);
+    let old_cf = s.cf();
+    let old_memory = s.load(address, 16);
+    s.set_cf(s.constant_with_width(0, 1));
+    s.store(address, s.constant(0xBEEFu16));
+    s.set_al(s.constant(0x33u8));
+    s.set_reg_64(Reg64WithRIP::RBX, s.concat(s.zext_to(old_cf, 48), old_memory));
+    let steps = s.finalize();
+    let initial = ConcreteX86MachineState64::zeroed().memory(0x100, &[0x34, 0x12]).flags(ConcreteFlags::zeroed().cf(true));
+    let res = apply_instructions_to_concrete(initial, steps.as_slice()).unwrap();
+    assert_eq!(res.rbx, 0x1_1234);
+    assert!(!res.get_flag(Flag::CF));
+    assert_eq!(res.read_memory(0x100, 16).to_u64(), 0xBEEF);
+}
+
+#[test]
+pub fn test_concrete_in_place_rolls_back_on_fault() {
+    let bump = Bump::new();
+    let mut s = SemanticsBuilder::new(Arena::new(&bump));
+    s.set_al(s.constant(0x33u8));
+    s.set_cf(s.constant_with_width(0, 1));
+    s.store(s.constant(0x100u64), s.constant(0xBEEF_CAFEu32));
+    s.divide_error();
+    let steps = s.finalize();
+    let initial = ConcreteX86MachineState64::zeroed().rax(0x1234).memory(0x100, &[0x34, 0x12]).flags(ConcreteFlags::zeroed().cf(true));
+    let mut state = initial.clone();
+    assert_eq!(apply_instructions_to_concrete_in_place(&mut state, steps.as_slice()), Err(Fault::DivideError));
+    assert_eq!(state, initial);
+
+    let steps = &steps[..steps.len() - 1];
+    assert_eq!(apply_instructions_to_concrete_in_place(&mut state, steps), Ok(()));
+    assert_eq!(state, apply_instructions_to_concrete(initial, steps).unwrap());
+}
+
+#[test]
+pub fn test_concrete_memory_operands() {
+    let load = X86Instruction::ADD(ADD::ADD_GPR8_MEMB {
+        operand_0: Reg8::AL,
+        operand_1: MemoryOperands::SIBAddressing {
+            segment: None,
+            scale: X86Scale::Two,
+            index: Some(GeneralReg::Reg64(Reg64WithRIP::RCX)),
+            base: Some(GeneralReg::Reg64(Reg64WithRIP::RBX)),
+            disp: 0x10,
+            disp_width: 8,
+        },
+    });
+    let state = ConcreteX86MachineState64::zeroed().rax(3).rbx(0x1000).rcx(0x8).memory(0x1020, &[5]);
+    assert_eq!(apply_encoded_concrete(load, state).unwrap().rax, 8);
+
+    // 32 bit addresses wrap, and fs adds its base
+    let store = X86Instruction::ADD(ADD::ADD_MEMB_IMMB_80R0 {
+        operand_0: MemoryOperands::SIBAddressing {
+            segment: Some(RegSegment::FS),
+            scale: X86Scale::One,
+            index: None,
+            base: Some(GeneralReg::Reg32(Reg32WithRIP::EBX)),
+            disp: 0x20,
+            disp_width: 8,
+        },
+        operand_1: Imm8(1),
+    });
+    let state = ConcreteX86MachineState64::zeroed().rbx(0xFFFF_FFF0).fs_base(0x5000).memory(0x5010, &[0x41, 0x42]);
+    let res = apply_encoded_concrete(store, state).unwrap();
+    assert_eq!(res.read_memory(0x5010, 16).to_u64(), 0x4242);
+}
+
+#[test]
+pub fn test_concrete_partial_register_writes() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for _ in 0..100 {
+        let initial = ConcreteX86MachineState64::zeroed().rax(rng.gen()).rbx(rng.gen());
+        let instrs = [
+            X86Instruction::ADD(ADD::ADD_GPRV_GPRV_01_64 { operand_0: Reg64WithRIP::RAX, operand_1: Reg64WithRIP::RBX }),
+            X86Instruction::ADD(ADD::ADD_GPRV_GPRV_01_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+            X86Instruction::ADD(ADD::ADD_GPRV_GPRV_01_16 { operand_0: Reg16WithRIP::AX, operand_1: Reg16WithRIP::BX }),
+            X86Instruction::ADD(ADD::ADD_GPR8_GPR8_00 { operand_0: Reg8::AH, operand_1: Reg8::BL }),
+        ];
+        for instr in instrs {
+            let native = run_instruction_64(instr, initial.clone());
+            let concrete = apply_encoded_concrete(instr, initial.clone()).unwrap();
+            assert_eq!(concrete.rax, native.rax);
+            assert_eq!(concrete.rbx, native.rbx);
+        }
+    }
+}
+
+#[test]
+pub fn test_concrete_vector_writes() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let xmm0 = rng.gen::<[f64; 2]>();
+    let xmm10 = rng.gen::<[f64; 2]>();
+    let mut initial = ConcreteX86MachineState64::zeroed().xmm0(xmm0).xmm10(xmm10);
+    initial.zmms[0][2..].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+    let sum = [xmm0[0] + xmm10[0], xmm0[1] + xmm10[1]].map(f64::to_bits);
+
+    let legacy = X86Instruction::ADDPD(xed_enum::ADDPD::ADDPD_XMMPD_XMMPD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM10 });
+    let res = apply_encoded_concrete(legacy, initial.clone()).unwrap();
+    assert_eq!(res.zmms[0], [sum[0], sum[1], 1, 2, 3, 4, 5, 6]);
+
+    let vex = X86Instruction::VADDPD(xed_enum::VADDPD::VADDPD_XMMDQ_XMMDQ_XMMDQ { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM0, operand_2: RegXMM::XMM10 });
+    let res = apply_encoded_concrete(vex, initial).unwrap();
+    assert_eq!(res.zmms[0], [sum[0], sum[1], 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+pub fn test_concrete_float_rounding_modes() {
+    let addsd = X86Instruction::ADDSD(ADDSD::ADDSD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let initial = ConcreteX86MachineState64::zeroed().xmm0([1.0f64, 3.0]).xmm1([2f64.powi(-60), 5.0]);
+    let nearest = apply_encoded_concrete(addsd, initial.clone()).unwrap();
+    assert_eq!(nearest.zmms[0][..2], [1.0f64.to_bits(), 3.0f64.to_bits()]);
+    assert_eq!(nearest.mxcsr, Mxcsr::DEFAULT.0 | FloatExceptions::PRECISION.bits() as u32);
+    // RC = 10, round up
+    let up = apply_encoded_concrete(addsd, initial.clone().mxcsr(Mxcsr::DEFAULT.0 | 2 << 13)).unwrap();
+    assert_eq!(up.zmms[0][0], 1.0f64.to_bits() + 1);
+    // flags are sticky, an exact result leaves precision set
+    let exact = apply_encoded_concrete(addsd, nearest.xmm1([1.0f64, 0.0])).unwrap();
+    assert_eq!(exact.zmms[0][0], 2.0f64.to_bits());
+    assert_eq!(exact.mxcsr, Mxcsr::DEFAULT.0 | FloatExceptions::PRECISION.bits() as u32);
+}
+
+#[test]
+pub fn test_concrete_float_ftz_and_daz() {
+    // half the smallest normal is an exact denormal, which FTZ flushes to zero as an inexact underflow
+    let mulsd = X86Instruction::MULSD(MULSD::MULSD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let initial = ConcreteX86MachineState64::zeroed().xmm0([f64::MIN_POSITIVE, 0.0]).xmm1([0.5f64, 0.0]);
+    let res = apply_encoded_concrete(mulsd, initial.clone()).unwrap();
+    assert_eq!(res.zmms[0][0], (f64::MIN_POSITIVE / 2.0).to_bits());
+    assert_eq!(res.mxcsr, Mxcsr::DEFAULT.0);
+    let ftz = apply_encoded_concrete(mulsd, initial.mxcsr(Mxcsr::DEFAULT.0 | 1 << 15)).unwrap();
+    assert_eq!(ftz.zmms[0][0], 0);
+    assert_eq!(ftz.mxcsr & 0x3F, FloatExceptions::UNDERFLOW.union(FloatExceptions::PRECISION).bits() as u32);
+
+    // a denormal source is reported, unless DAZ treats it as zero
+    let addsd = X86Instruction::ADDSD(ADDSD::ADDSD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let initial = ConcreteX86MachineState64::zeroed().xmm0([1u64, 0]);
+    let res = apply_encoded_concrete(addsd, initial.clone()).unwrap();
+    assert_eq!(res.zmms[0][0], 1);
+    assert_eq!(res.mxcsr & 0x3F, FloatExceptions::DENORMAL.bits() as u32);
+    let daz = apply_encoded_concrete(addsd, initial.mxcsr(Mxcsr::DEFAULT.0 | 1 << 6)).unwrap();
+    assert_eq!(daz.zmms[0][0], 0);
+    assert_eq!(daz.mxcsr & 0x3F, 0);
+}
+
+#[test]
+pub fn test_concrete_float_unmasked_exception_faults() {
+    let divsd = X86Instruction::DIVSD(DIVSD::DIVSD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let initial = ConcreteX86MachineState64::zeroed().xmm0([1.0f64, 0.0]);
+    let masked = apply_encoded_concrete(divsd, initial.clone()).unwrap();
+    assert_eq!(masked.zmms[0][0], f64::INFINITY.to_bits());
+    assert_eq!(masked.mxcsr & 0x3F, FloatExceptions::DIVIDE_BY_ZERO.bits() as u32);
+    let unmasked = Mxcsr::DEFAULT.0 & !((FloatExceptions::DIVIDE_BY_ZERO.bits() as u32) << 7);
+    assert_eq!(apply_encoded_concrete(divsd, initial.mxcsr(unmasked)), Err(ConcreteError::Fault(Fault::SimdFloatingPointException)));
+}
+
+#[test]
+pub fn test_concrete_ldmxcsr_and_stmxcsr() {
+    let operand_0 = MemoryOperands::SIBAddressing {
+        segment: None,
+        scale: X86Scale::One,
+        index: None,
+        base: Some(GeneralReg::Reg64(Reg64WithRIP::RBX)),
+        disp: 0,
+        disp_width: 0,
+    };
+    let ldmxcsr = X86Instruction::LDMXCSR(LDMXCSR::LDMXCSR_MEMD { operand_0 });
+    let state = ConcreteX86MachineState64::zeroed().rbx(0x1000).memory(0x1000, &0x7F80u32.to_le_bytes());
+    assert_eq!(apply_encoded_concrete(ldmxcsr, state).unwrap().mxcsr, 0x7F80);
+    let reserved = ConcreteX86MachineState64::zeroed().rbx(0x1000).memory(0x1000, &0x1_1F80u32.to_le_bytes());
+    assert_eq!(apply_encoded_concrete(ldmxcsr, reserved), Err(ConcreteError::Fault(Fault::GeneralProtection)));
+
+    let stmxcsr = X86Instruction::STMXCSR(STMXCSR::STMXCSR_MEMD { operand_0 });
+    let res = apply_encoded_concrete(stmxcsr, ConcreteX86MachineState64::zeroed().rbx(0x1000).mxcsr(0x3FA1)).unwrap();
+    assert_eq!(res.read_memory(0x1000, 32).to_u64(), 0x3FA1);
+}
+
+#[test]
+pub fn test_concrete_comisd_flags() {
+    let comisd = X86Instruction::COMISD(COMISD::COMISD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let ucomisd = X86Instruction::UCOMISD(UCOMISD::UCOMISD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let flags = ConcreteFlags::zeroed().af(true).sf(true).of(true);
+    let compare = |instr, left: f64, right: f64| {
+        let initial = ConcreteX86MachineState64::zeroed().xmm0([left, 0.0]).xmm1([right, 0.0]).flags(flags);
+        let res = apply_encoded_concrete(instr, initial).unwrap();
+        (res.flags, res.mxcsr & 0x3F)
+    };
+    assert_eq!(compare(comisd, 1.0, 2.0), (ConcreteFlags::zeroed().cf(true), 0));
+    assert_eq!(compare(comisd, 2.0, 1.0), (ConcreteFlags::zeroed(), 0));
+    assert_eq!(compare(comisd, -0.0, 0.0), (ConcreteFlags::zeroed().zf(true), 0));
+    let unordered = ConcreteFlags::zeroed().zf(true).pf(true).cf(true);
+    // only COMISD signals on a QNaN
+    assert_eq!(compare(comisd, f64::NAN, 1.0), (unordered, FloatExceptions::INVALID.bits() as u32));
+    assert_eq!(compare(ucomisd, f64::NAN, 1.0), (unordered, 0));
+}
+
+#[test]
+pub fn test_differential_sse_float_ops() {
+    let xmm0 = RegXMM::XMM0;
+    let xmm1 = RegXMM::XMM1;
+    let instrs = [
+        X86Instruction::ADDPD(ADDPD::ADDPD_XMMPD_XMMPD { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::ADDSD(ADDSD::ADDSD_XMMSD_XMMSD { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::SUBPS(SUBPS::SUBPS_XMMPS_XMMPS { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::MULSS(MULSS::MULSS_XMMSS_XMMSS { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::DIVPD(DIVPD::DIVPD_XMMPD_XMMPD { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::SQRTSD(SQRTSD::SQRTSD_XMMSD_XMMSD { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::SQRTPS(SQRTPS::SQRTPS_XMMPS_XMMPS { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::MINSD(MINSD::MINSD_XMMSD_XMMSD { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::MAXPS(MAXPS::MAXPS_XMMPS_XMMPS { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::COMISD(COMISD::COMISD_XMMSD_XMMSD { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::UCOMISS(UCOMISS::UCOMISS_XMMSS_XMMSS { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::CMPPS(CMPPS::CMPPS_XMMPS_XMMPS_IMMB { operand_0: xmm0, operand_1: xmm1, operand_2: Imm8(5) }),
+        X86Instruction::CMPSD_XMM(CMPSD_XMM::CMPSD_XMM_XMMSD_XMMSD_IMMB { operand_0: xmm0, operand_1: xmm1, operand_2: Imm8(1) }),
+        X86Instruction::CVTSD2SS(CVTSD2SS::CVTSD2SS_XMMSS_XMMSD { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::CVTSS2SD(CVTSS2SD::CVTSS2SD_XMMSD_XMMSS { operand_0: xmm0, operand_1: xmm1 }),
+        X86Instruction::CVTSI2SD(CVTSI2SD::CVTSI2SD_XMMSD_GPR64Q { operand_0: xmm0, operand_1: Reg64WithRIP::RAX }),
+        X86Instruction::CVTSI2SS(CVTSI2SS::CVTSI2SS_XMMSS_GPR32D { operand_0: xmm0, operand_1: Reg32WithRIP::EAX }),
+        X86Instruction::CVTSD2SI(CVTSD2SI::CVTSD2SI_GPR32D_XMMSD { operand_0: Reg32WithRIP::EAX, operand_1: xmm1 }),
+        X86Instruction::CVTTSS2SI(CVTTSS2SI::CVTTSS2SI_GPR64Q_XMMSS { operand_0: Reg64WithRIP::RAX, operand_1: xmm1 }),
+    ];
+    for instr in instrs {
+        assert_no_divergence(instr, 200);
+    }
+}
+
+#[test]
+pub fn test_differential_fma() {
+    if !is_x86_feature_detected!("fma") {
+        return;
+    }
+    let instrs = [
+        X86Instruction::VFMADD231PD(VFMADD231PD::VFMADD231PD_XMMDQ_XMMDQ_XMMDQ { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1, operand_2: RegXMM::XMM2 }),
+        X86Instruction::VFMADD132SS(VFMADD132SS::VFMADD132SS_XMMDQ_XMMD_XMMD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1, operand_2: RegXMM::XMM2 }),
+    ];
+    for instr in instrs {
+        assert_no_divergence(instr, 200);
+    }
+}
+
+#[test]
+pub fn test_differential_add_adc_adcx() {
+    let instrs = [
+        X86Instruction::ADD(ADD::ADD_GPR8_GPR8_00 { operand_0: Reg8::AH, operand_1: Reg8::BL }),
+        X86Instruction::ADD(ADD::ADD_GPRV_IMMB_64 { operand_0: Reg64WithRIP::RBX, operand_1: Imm8(-1) }),
+        X86Instruction::ADD(ADD::ADD_ORAX_IMMZ_16 { operand_0: Imm16(-0x7FFF) }),
+        X86Instruction::ADD(ADD::ADD_ORAX_IMMZ_32 { operand_0: Imm32(0x7FFF_FFFF) }),
+        X86Instruction::ADD(ADD::ADD_ORAX_IMMZ_64 { operand_0: Imm32(-2) }),
+        X86Instruction::ADC(ADC::ADC_GPR8_GPR8_12 { operand_0: Reg8::BL, operand_1: Reg8::CL }),
+        X86Instruction::ADC(ADC::ADC_GPRV_IMMB_32 { operand_0: Reg32WithRIP::EBX, operand_1: Imm8(-128) }),
+        X86Instruction::ADC(ADC::ADC_ORAX_IMMZ_64 { operand_0: Imm32(-1) }),
+        X86Instruction::ADCX(ADCX::ADCX_GPR32D_GPR32D { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::ADCX(ADCX::ADCX_GPR64Q_GPR64Q { operand_0: Reg64WithRIP::RAX, operand_1: Reg64WithRIP::RBX }),
+    ];
+    for instr in instrs {
+        assert_no_divergence(instr, 200);
+    }
+}
+
+#[test]
+pub fn test_differential_integer_ops() {
+    let rax = Reg64WithRIP::RAX;
+    let rbx = Reg64WithRIP::RBX;
+    let instrs = [
+        X86Instruction::SUB(SUB::SUB_GPRV_GPRV_29_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::SBB(SBB::SBB_GPR8_GPR8_18 { operand_0: Reg8::AH, operand_1: Reg8::BL }),
+        X86Instruction::CMP(CMP::CMP_GPRV_GPRV_39_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::TEST(TEST::TEST_GPRV_GPRV_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::SHL(SHL::SHL_GPRV_ONE_D1R4_64 { operand_0: rax }),
+        X86Instruction::RCR(RCR::RCR_GPR8_ONE { operand_0: Reg8::AL }),
+        X86Instruction::IMUL(IMUL::IMUL_GPRV_64 { operand_0: rbx }),
+        X86Instruction::IMUL(IMUL::IMUL_GPRV_GPRV_IMMZ_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX, operand_2: Imm32(-3) }),
+        X86Instruction::BSF(BSF::BSF_GPRV_GPRV_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::BTS(BTS::BTS_GPRV_GPRV_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::CMOVL(CMOVL::CMOVL_GPRV_GPRV_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::SETNBE(SETNBE::SETNBE_GPR8 { operand_0: Reg8::BH }),
+        X86Instruction::XCHG(XCHG::XCHG_GPRV_GPRV_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+    ];
+    for instr in instrs {
+        assert_no_divergence(instr, 200);
+    }
+}
+
+#[test]
+pub fn test_differential_double_shift() {
+    let instrs = [
+        X86Instruction::SHLD(SHLD::SHLD_GPRV_GPRV_IMMB_64 { operand_0: Reg64WithRIP::RAX, operand_1: Reg64WithRIP::RBX, operand_2: Imm8(1) }),
+        X86Instruction::SHRD(SHRD::SHRD_GPRV_GPRV_IMMB_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX, operand_2: Imm8(1) }),
+        X86Instruction::SHLD(SHLD::SHLD_GPRV_GPRV_IMMB_16 { operand_0: Reg16WithRIP::AX, operand_1: Reg16WithRIP::BX, operand_2: Imm8(1) }),
+    ];
+    for instr in instrs {
+        assert_no_divergence(instr, 200);
+    }
+}
+
+/// CF is the last bit shifted out for every count, but OF is only defined for a count of one, and a count of zero
+/// leaves the flags alone.
+#[test]
+pub fn test_concrete_double_shift_counts() {
+    let instrs = [
+        X86Instruction::SHLD(SHLD::SHLD_GPRV_GPRV_CL_64 { operand_0: Reg64WithRIP::RAX, operand_1: Reg64WithRIP::RBX }),
+        X86Instruction::SHRD(SHRD::SHRD_GPRV_GPRV_CL_64 { operand_0: Reg64WithRIP::RAX, operand_1: Reg64WithRIP::RBX }),
+        X86Instruction::SHLD(SHLD::SHLD_GPRV_GPRV_CL_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::SHRD(SHRD::SHRD_GPRV_GPRV_CL_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+    ];
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for instr in instrs {
+        for count in 0..0x48u64 {
+            let flags = ConcreteFlags::zeroed().cf(rng.gen()).of(rng.gen()).zf(rng.gen());
+            let initial = ConcreteX86MachineState64::zeroed().rax(rng.gen()).rbx(rng.gen()).rcx(count).flags(flags);
+            let native = run_instruction_64(instr, initial.clone());
+            let lifted = apply_encoded_concrete(instr, initial).unwrap();
+            assert_eq!(lifted.rax, native.rax, "{instr:?} by {count}");
+            let mut compared = vec![Flag::CF, Flag::PF, Flag::ZF, Flag::SF];
+            let mask = if instr.effective_operand_width() == 64 { 0x3F } else { 0x1F };
+            if count & mask == 1 {
+                compared.push(Flag::OF);
+            }
+            for flag in compared {
+                assert_eq!(lifted.get_flag(flag), native.get_flag(flag), "{flag:?} of {instr:?} by {count}");
+            }
+            if count & mask == 0 {
+                assert_eq!(lifted.flags, flags);
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_concrete_shift_by_zero_keeps_flags() {
+    let instr = X86Instruction::SHR(SHR::SHR_GPRV_CL_32 { operand_0: Reg32WithRIP::EAX });
+    let flags = ConcreteFlags::zeroed().cf(true).zf(true);
+    let initial = ConcreteX86MachineState64::zeroed().rax(0xFFFF_FFFF_8000_0000).rcx(0x20).flags(flags);
+    let res = apply_encoded_concrete(instr, initial).unwrap();
+    // the count is masked to 0, but the 32 bit write still clears the upper half
+    assert_eq!(res.rax, 0x8000_0000);
+    assert_eq!(res.flags, flags);
+}
+
+#[test]
+pub fn test_concrete_div() {
+    let instr = X86Instruction::DIV(DIV::DIV_GPRV_64 { operand_0: Reg64WithRIP::RBX });
+    let initial = ConcreteX86MachineState64::zeroed().rdx(1).rax(5).rbx(2);
+    let res = apply_encoded_concrete(instr, initial).unwrap();
+    assert_eq!(res.rax, 0x8000_0000_0000_0002);
+    assert_eq!(res.rdx, 1);
+
+    let divide_by_zero = ConcreteX86MachineState64::zeroed().rax(5);
+    assert_eq!(apply_encoded_concrete(instr, divide_by_zero), Err(ConcreteError::Fault(Fault::DivideError)));
+    // the quotient doesn't fit in rax
+    let overflow = ConcreteX86MachineState64::zeroed().rdx(2).rbx(2);
+    assert_eq!(apply_encoded_concrete(instr, overflow), Err(ConcreteError::Fault(Fault::DivideError)));
+}
+
+#[test]
+pub fn test_concrete_lea_and_movzx() {
+    let lea = X86Instruction::LEA(LEA::LEA_GPRV_AGEN_32 {
+        operand_0: Reg32WithRIP::EAX,
+        operand_1: MemoryOperands::SIBAddressing {
+            segment: Some(RegSegment::FS),
+            scale: X86Scale::Eight,
+            index: Some(GeneralReg::Reg64(Reg64WithRIP::RCX)),
+            base: Some(GeneralReg::Reg64(Reg64WithRIP::RBX)),
+            disp: -8,
+            disp_width: 8,
+        },
+    });
+    // LEA ignores the segment base and truncates to the operand size
+    let state = ConcreteX86MachineState64::zeroed().rax(u64::MAX).rbx(0x1_0000_0000).rcx(2).fs_base(0x5000);
+    assert_eq!(apply_encoded_concrete(lea, state).unwrap().rax, 8);
+
+    let movzx = X86Instruction::MOVZX(MOVZX::MOVZX_GPRV_GPR8_64 { operand_0: Reg64WithRIP::RAX, operand_1: Reg8::BH });
+    let state = ConcreteX86MachineState64::zeroed().rax(u64::MAX).rbx(0xABCD);
+    assert_eq!(apply_encoded_concrete(movzx, state).unwrap().rax, 0xAB);
+}
+
+#[test]
+pub fn test_mov_segment_unsupported() {
+    // mov eax, ds
+    let (instr, _) = X86Instruction::decode_one(&[0x8C, 0xD8], &mut EncodeDecodeContext::new()).unwrap();
+    let res = apply_instruction_concrete(instr, 2, ConcreteX86MachineState64::zeroed());
+    assert_eq!(res, Err(ConcreteError::Unsupported(instr)));
+}
+
+#[test]
+pub fn test_concrete_conditional_branch() {
+    let instr = X86Instruction::JZ(JZ::JZ_RELBRB { operand_0: RelativeBr::Disp8(-0x10) });
+    let initial = ConcreteX86MachineState64::zeroed().rip(0x1000);
+    let taken = initial.clone().flags(ConcreteFlags::zeroed().zf(true));
+    assert_eq!(apply_instruction_concrete(instr, 2, taken).unwrap().rip, 0xFF2);
+    assert_eq!(apply_instruction_concrete(instr, 2, initial).unwrap().rip, 0x1002);
+
+    let instr = X86Instruction::LOOP(LOOP::LOOP_RELBRB { operand_0: RelativeBr::Disp8(0x10) });
+    let res = apply_instruction_concrete(instr, 2, ConcreteX86MachineState64::zeroed().rip(0x1000).rcx(2)).unwrap();
+    assert_eq!((res.rip, res.rcx), (0x1012, 1));
+    let res = apply_instruction_concrete(instr, 2, res.rip(0x1000)).unwrap();
+    assert_eq!((res.rip, res.rcx), (0x1002, 0));
+}
+
+#[test]
+pub fn test_concrete_call_and_return() {
+    let call = X86Instruction::CALL_NEAR(CALL_NEAR::CALL_NEAR_RELBRD { operand_0: RelativeBr::Disp32(0x100) });
+    let initial = ConcreteX86MachineState64::zeroed().rip(0x1000).rsp(0x8000);
+    let called = apply_instruction_concrete(call, 5, initial).unwrap();
+    assert_eq!((called.rip, called.rsp), (0x1105, 0x7FF8));
+    assert_eq!(called.read_memory(0x7FF8, 64).to_u64(), 0x1005);
+
+    let ret = X86Instruction::RET_NEAR(RET_NEAR::RET_NEAR {});
+    let returned = apply_instruction_concrete(ret, 1, called).unwrap();
+    assert_eq!((returned.rip, returned.rsp), (0x1005, 0x8000));
+}
+
+#[test]
+pub fn test_concrete_rip_relative_is_relative_to_next_instruction() {
+    let lea = X86Instruction::LEA(LEA::LEA_GPRV_AGEN_64 {
+        operand_0: Reg64WithRIP::RAX,
+        operand_1: MemoryOperands::SIBAddressing {
+            segment: None,
+            scale: X86Scale::One,
+            index: None,
+            base: Some(GeneralReg::Reg64(Reg64WithRIP::RIP)),
+            disp: 0x20,
+            disp_width: 32,
+        },
+    });
+    let res = apply_encoded_concrete(lea, ConcreteX86MachineState64::zeroed().rip(0x1000)).unwrap();
+    assert_eq!(res.rax, 0x1027);
+    assert_eq!(res.rip, 0x1007);
+}
+
+#[test]
+pub fn test_lift_basic_block() {
+    // add rax, rbx; jz +2; nop
+    let bytes = [0x48, 0x01, 0xD8, 0x74, 0x02, 0x90];
+    let bump = Bump::new();
+    let block = lift_basic_block(Arena::new(&bump), &bytes, 0x1000).unwrap();
+    assert_eq!(block.instructions.len(), 2);
+    assert_eq!(block.fall_through_address(), 0x1005);
+
+    let initial = ConcreteX86MachineState64::zeroed().rip(0x1000).rbx(1);
+    assert_eq!(block.apply_concrete(initial.clone()).unwrap().rip, 0x1005);
+    assert_eq!(block.apply_concrete(initial.rax(u64::MAX)).unwrap().rip, 0x1007);
+}
+
+#[test]
+pub fn test_lift_basic_block_unsupported() {
+    // add rax, rbx; jmp far [rax]
+    let bytes = [0x48, 0x01, 0xD8, 0xFF, 0x28];
+    let bump = Bump::new();
+    let res = lift_basic_block(Arena::new(&bump), &bytes, 0x1000);
+    assert!(matches!(res, Err(LiftError::Unsupported { address: 0x1003, instruction: X86Instruction::JMP_FAR(_) })));
+}
+
+#[test]
+pub fn test_concrete_push_and_pop() {
+    let push = X86Instruction::PUSH(PUSH::PUSH_IMMB { operand_0: Imm8(-2) });
+    let pushed = apply_encoded_concrete(push, ConcreteX86MachineState64::zeroed().rsp(0x8000)).unwrap();
+    assert_eq!(pushed.rsp, 0x7FF8);
+    assert_eq!(pushed.read_memory(0x7FF8, 64).to_u64(), -2i64 as u64);
+
+    // the destination is addressed with RSP after the pop
+    let pop = X86Instruction::POP(POP::POP_MEMV_64 {
+        operand_0: MemoryOperands::SIBAddressing {
+            segment: None,
+            scale: X86Scale::One,
+            index: None,
+            base: Some(GeneralReg::Reg64(Reg64WithRIP::RSP)),
+            disp: 0,
+            disp_width: 0,
+        },
+    });
+    let popped = apply_encoded_concrete(pop, pushed).unwrap();
+    assert_eq!(popped.rsp, 0x8000);
+    assert_eq!(popped.read_memory(0x8000, 64).to_u64(), -2i64 as u64);
+
+    let pop_rsp = X86Instruction::POP(POP::POP_GPRV_58_64 { operand_0: Reg64WithRIP::RSP });
+    let state = ConcreteX86MachineState64::zeroed().rsp(0x8000).memory(0x8000, &0x1234u64.to_le_bytes());
+    assert_eq!(apply_encoded_concrete(pop_rsp, state).unwrap().rsp, 0x1234);
+}
+
+#[test]
+pub fn test_concrete_pushf_and_popf() {
+    let flags = ConcreteFlags::zeroed().cf(true).zf(true).of(true);
+    let pushf = X86Instruction::PUSHFQ(PUSHFQ::PUSHFQ {});
+    let pushed = apply_encoded_concrete(pushf, ConcreteX86MachineState64::zeroed().rsp(0x8000).flags(flags)).unwrap();
+    // the reserved bit 1 and IF are always set
+    assert_eq!(pushed.read_memory(0x7FF8, 64).to_u64(), 0x843 | 0x200);
+
+    let popf = X86Instruction::POPFQ(POPFQ::POPFQ {});
+    let popped = apply_encoded_concrete(popf, pushed.flags(ConcreteFlags::zeroed())).unwrap();
+    assert_eq!(popped.flags, flags);
+    assert_eq!(popped.rsp, 0x8000);
+}
+
+#[test]
+pub fn test_concrete_enter_and_leave() {
+    let enter = X86Instruction::ENTER(ENTER::ENTER_IMMW_IMMB { operand_0: Imm16(0x20), operand_1: Imm8(0) });
+    let initial = ConcreteX86MachineState64::zeroed().rsp(0x8000).rbp(0x9000);
+    let entered = apply_encoded_concrete(enter, initial).unwrap();
+    assert_eq!((entered.rsp, entered.rbp), (0x7FD8, 0x7FF8));
+    assert_eq!(entered.read_memory(0x7FF8, 64).to_u64(), 0x9000);
+
+    let leave = X86Instruction::LEAVE(LEAVE::LEAVE {});
+    let left = apply_encoded_concrete(leave, entered).unwrap();
+    assert_eq!((left.rsp, left.rbp), (0x8000, 0x9000));
+
+    // a nested frame copies the enclosing frame pointer and then pushes its own
+    let nested = X86Instruction::ENTER(ENTER::ENTER_IMMW_IMMB { operand_0: Imm16(0), operand_1: Imm8(2) });
+    let initial = ConcreteX86MachineState64::zeroed().rsp(0x8000).rbp(0x9000).memory(0x8FF8, &0xAAAAu64.to_le_bytes());
+    let entered = apply_encoded_concrete(nested, initial).unwrap();
+    assert_eq!((entered.rsp, entered.rbp), (0x7FE8, 0x7FF8));
+    assert_eq!(entered.read_memory(0x7FF0, 64).to_u64(), 0xAAAA);
+    assert_eq!(entered.read_memory(0x7FE8, 64).to_u64(), 0x7FF8);
+}
+
+/// Decodes `bytes` as one instruction for `mode` and runs its semantics, lifted for the same mode.
+fn apply_decoded_in_mode(bytes: &[u8], mode: X86Mode, state: ConcreteX86MachineState64) -> Result<ConcreteX86MachineState64, ConcreteError> {
+    let mut context = match mode {
+        X86Mode::Real => EncodeDecodeContext::real(),
+        X86Mode::Protected => EncodeDecodeContext::legacy_32(),
+        X86Mode::_64Bit => EncodeDecodeContext::new(),
+    };
+    let (instr, address_width, _) = X86Instruction::decode_one_with_address_width(bytes, &mut context).unwrap();
+    let bump = Bump::new();
+    let steps = apply_instruction_with_address_width(Arena::new(&bump).in_mode(mode), instr, bytes.len(), address_width)?;
+    Ok(apply_instructions_to_concrete(state, steps.as_slice())?)
+}
+
+#[test]
+pub fn test_concrete_stack_in_legacy_modes() {
+    // push eax
+    let initial = ConcreteX86MachineState64::zeroed().rsp(0x2000).rax(0x1234_5678);
+    let pushed = apply_decoded_in_mode(&[0x50], X86Mode::Protected, initial).unwrap();
+    assert_eq!(pushed.rsp, 0x1FFC);
+    assert_eq!(pushed.read_memory(0x1FFC, 32).to_u64(), 0x1234_5678);
+
+    // pop ecx
+    let popped = apply_decoded_in_mode(&[0x59], X86Mode::Protected, pushed).unwrap();
+    assert_eq!((popped.rsp, popped.rcx), (0x2000, 0x1234_5678));
+
+    // pushfd
+    let flags = ConcreteFlags::zeroed().cf(true).zf(true).of(true);
+    let pushed = apply_decoded_in_mode(&[0x9C], X86Mode::Protected, ConcreteX86MachineState64::zeroed().rsp(0x2000).flags(flags)).unwrap();
+    assert_eq!(pushed.rsp, 0x1FFC);
+    assert_eq!(pushed.read_memory(0x1FFC, 32).to_u64(), 0x843 | 0x200);
+
+    // push ax, SP wraps around within the bits real mode can see
+    let initial = ConcreteX86MachineState64::zeroed().rsp(0xAB_0000).rax(0x1234);
+    let pushed = apply_decoded_in_mode(&[0x50], X86Mode::Real, initial).unwrap();
+    assert_eq!(pushed.rsp, 0xAB_FFFE);
+    assert_eq!(pushed.read_memory(0xFFFE, 16).to_u64(), 0x1234);
+
+    // inc eax, 0x40 is a REX prefix in 64 bit mode only
+    let incremented = apply_decoded_in_mode(&[0x40], X86Mode::Protected, ConcreteX86MachineState64::zeroed().rax(0x41)).unwrap();
+    assert_eq!(incremented.rax, 0x42);
+}
+
+#[test]
+pub fn test_loop_counter_follows_address_size() {
+    // loop to itself, counting down CX without touching the rest of RCX
+    let initial = ConcreteX86MachineState64::zeroed().rip(0x1000).rcx(0x1_0000);
+    let looped = apply_decoded_in_mode(&[0xE2, 0xFE], X86Mode::Real, initial).unwrap();
+    assert_eq!((looped.rip, looped.rcx), (0x1000, 0x1_FFFF));
+
+    // ECX reaches zero, so the loop falls through
+    let initial = ConcreteX86MachineState64::zeroed().rip(0x1000).rcx(0x1_0000_0001);
+    let looped = apply_decoded_in_mode(&[0xE2, 0xFE], X86Mode::Protected, initial).unwrap();
+    assert_eq!((looped.rip, looped.rcx), (0x1002, 0));
+
+    // an address size override would make the counter ECX, which isn't modelled
+    let res = apply_decoded_in_mode(&[0x67, 0xE2, 0xFD], X86Mode::_64Bit, ConcreteX86MachineState64::zeroed());
+    assert!(matches!(res, Err(ConcreteError::Unsupported(X86Instruction::LOOP(_)))));
+}
+
+#[test]
+pub fn test_concrete_call_and_ret_in_protected_mode() {
+    // call rel32, the target wraps around at 2^32
+    let initial = ConcreteX86MachineState64::zeroed().rip(0x1000).rsp(0x2000);
+    let called = apply_decoded_in_mode(&[0xE8, 0x00, 0xE0, 0xFF, 0xFF], X86Mode::Protected, initial).unwrap();
+    assert_eq!((called.rip, called.rsp), (0xFFFF_F005, 0x1FFC));
+    assert_eq!(called.read_memory(0x1FFC, 32).to_u64(), 0x1005);
+
+    // ret
+    let returned = apply_decoded_in_mode(&[0xC3], X86Mode::Protected, called).unwrap();
+    assert_eq!((returned.rip, returned.rsp), (0x1005, 0x2000));
+}
+
+#[test]
+pub fn test_segment_push_and_pop() {
+    // push es only exists outside of 64 bit mode
+    let (push_es, _) = X86Instruction::decode_one(&[0x06], &mut EncodeDecodeContext::legacy_32()).unwrap();
+    assert_eq!(apply_instruction_concrete(push_es, 1, ConcreteX86MachineState64::zeroed()), Err(ConcreteError::Fault(Fault::UndefinedOpcode)));
+
+    // push fs, only the selector is written and the rest of the slot is left alone
+    let initial = ConcreteX86MachineState64::zeroed().rsp(0x8000).segment(RegSegment::FS, 0x2B).memory(0x7FF8, &[0xAA; 8]);
+    let pushed = apply_decoded_in_mode(&[0x0F, 0xA0], X86Mode::_64Bit, initial).unwrap();
+    assert_eq!(pushed.rsp, 0x7FF8);
+    assert_eq!(pushed.read_memory(0x7FF8, 64).to_u64(), 0xAAAA_AAAA_AAAA_002B);
+
+    // pop gs loads the selector and clears the base
+    let popped = apply_decoded_in_mode(&[0x0F, 0xA9], X86Mode::_64Bit, pushed.gs_base(0x1234_0000)).unwrap();
+    assert_eq!(popped.rsp, 0x8000);
+    assert_eq!(popped.get_segment(RegSegment::GS), 0x2B);
+    assert_eq!(popped.gs_base, 0);
+
+    // push ds, pop es
+    let initial = ConcreteX86MachineState64::zeroed().rsp(0x2000).segment(RegSegment::DS, 0x23);
+    let pushed = apply_decoded_in_mode(&[0x1E], X86Mode::Protected, initial).unwrap();
+    assert_eq!(pushed.rsp, 0x1FFC);
+    assert_eq!(pushed.read_memory(0x1FFC, 16).to_u64(), 0x23);
+    let popped = apply_decoded_in_mode(&[0x07], X86Mode::Protected, pushed).unwrap();
+    assert_eq!(popped.rsp, 0x2000);
+    assert_eq!(popped.get_segment(RegSegment::ES), 0x23);
+
+    // push cs in real mode
+    let initial = ConcreteX86MachineState64::zeroed().rsp(0x2000).segment(RegSegment::CS, 0xF000);
+    let pushed = apply_decoded_in_mode(&[0x0E], X86Mode::Real, initial).unwrap();
+    assert_eq!(pushed.rsp, 0x1FFE);
+    assert_eq!(pushed.read_memory(0x1FFE, 16).to_u64(), 0xF000);
+}
+
+#[test]
+pub fn test_differential_stack_ops() {
+    let instrs = [
+        X86Instruction::PUSH(PUSH::PUSH_GPRV_50_64 { operand_0: Reg64WithRIP::RBX }),
+        X86Instruction::PUSH(PUSH::PUSH_GPRV_50_16 { operand_0: Reg16WithRIP::BX }),
+        X86Instruction::PUSH(PUSH::PUSH_IMMZ_64 { operand_0: Imm32(-0x1000) }),
+        X86Instruction::POP(POP::POP_GPRV_58_64 { operand_0: Reg64WithRIP::RAX }),
+        X86Instruction::POP(POP::POP_GPRV_58_16 { operand_0: Reg16WithRIP::AX }),
+        X86Instruction::PUSHFQ(PUSHFQ::PUSHFQ {}),
+        X86Instruction::POPFQ(POPFQ::POPFQ {}),
+    ];
+    for instr in instrs {
+        assert_no_divergence(instr, 100);
+    }
+}
+
+const X87_ONE: u128 = 0x3FFF_8000_0000_0000_0000;
+const X87_TWO: u128 = 0x4000_8000_0000_0000_0000;
+const X87_THREE: u128 = 0x4000_C000_0000_0000_0000;
+
+/// `[rsp - 8]`, which is 4 bytes long as the only operand of an x87 instruction.
+fn below_rsp() -> MemoryOperands {
+    MemoryOperands::SIBAddressing {
+        segment: None,
+        scale: X86Scale::One,
+        index: None,
+        base: Some(GeneralReg::Reg64(Reg64WithRIP::RSP)),
+        disp: -8,
+        disp_width: 8,
+    }
+}
+
+#[test]
+pub fn test_concrete_x87_arithmetic() {
+    // ST(0) = 2, ST(1) = 1
+    let initial = ConcreteX86MachineState64::zeroed().x87_push(X87_ONE).x87_push(X87_TWO);
+
+    let fadd = X86Instruction::FADD(FADD::FADD_ST0_X87 { operand_0: RegFloat::ST1 });
+    let res = apply_instruction_concrete(fadd, 2, initial.clone()).unwrap();
+    assert_eq!((res.x87_register(0), res.x87_register(1)), (X87_THREE, X87_ONE));
+
+    // the reversed and ST(i) destination forms swap the operands
+    let fdivr = X86Instruction::FDIVR(FDIVR::FDIVR_ST0_X87 { operand_0: RegFloat::ST1 });
+    assert_eq!(apply_instruction_concrete(fdivr, 2, initial.clone()).unwrap().x87_register(0), 0x3FFE_8000_0000_0000_0000);
+    let fsub = X86Instruction::FSUB(FSUB::FSUB_X87_ST0 { operand_0: RegFloat::ST1 });
+    let res = apply_instruction_concrete(fsub, 2, initial.clone()).unwrap();
+    assert_eq!((res.x87_register(0), res.x87_register(1)), (X87_TWO, 0xBFFF_8000_0000_0000_0000));
+
+    let faddp = X86Instruction::FADDP(FADDP::FADDP_X87_ST0 { operand_0: RegFloat::ST1 });
+    let res = apply_instruction_concrete(faddp, 2, initial).unwrap();
+    assert_eq!(res.x87_top(), 7);
+    assert_eq!(res.x87_register(0), X87_THREE);
+    assert!(res.x87_register_empty(7));
+
+    // a third with 24 bit precision control rounds up, which C1 reports
+    let third = ConcreteX86MachineState64::zeroed().x87_control(0x007F).x87_push(X87_THREE).x87_push(X87_ONE);
+    let fdiv = X86Instruction::FDIV(FDIV::FDIV_ST0_X87 { operand_0: RegFloat::ST1 });
+    let res = apply_instruction_concrete(fdiv, 2, third).unwrap();
+    assert_eq!(res.x87_register(0), 0x3FFD_AAAA_AB00_0000_0000);
+    assert_eq!(res.x87_status & 0x3FF, 0x220);
+}
+
+#[test]
+pub fn test_concrete_x87_stack_faults() {
+    // pushing onto a full stack pushes the indefinite value
+    let full = (0..8).fold(ConcreteX86MachineState64::zeroed(), |state, _| state.x87_push(X87_ONE));
+    let fld = X86Instruction::FLD(FLD::FLD_ST0_X87 { operand_0: RegFloat::ST0 });
+    let res = apply_instruction_concrete(fld, 2, full).unwrap();
+    assert_eq!(res.x87_register(0), X87_INDEFINITE);
+    // IE, SF and C1 for an overflow, with TOP = 7
+    assert_eq!(res.x87_status, 0x3A41);
+
+    // an empty operand gives the indefinite value and C1 clear
+    let fadd = X86Instruction::FADD(FADD::FADD_ST0_X87 { operand_0: RegFloat::ST1 });
+    let res = apply_instruction_concrete(fadd, 2, ConcreteX86MachineState64::zeroed().x87_push(X87_ONE)).unwrap();
+    assert_eq!(res.x87_register(0), X87_INDEFINITE);
+    assert_eq!(res.x87_status, 0x3841);
+
+    // the empty register is filled in before the exchange
+    let fxch = X86Instruction::FXCH(FXCH::FXCH_ST0_X87 { operand_0: RegFloat::ST1 });
+    let res = apply_instruction_concrete(fxch, 2, ConcreteX86MachineState64::zeroed().x87_push(X87_ONE)).unwrap();
+    assert_eq!((res.x87_register(0), res.x87_register(1)), (X87_INDEFINITE, X87_ONE));
+    assert!(!res.x87_register_empty(0));
+
+    // storing an empty register stores the default NaN and still pops
+    let fstp = X86Instruction::FSTP(FSTP::FSTP_MEMM64REAL_ST0 { operand_0: below_rsp() });
+    let res = apply_instruction_concrete(fstp, 4, ConcreteX86MachineState64::zeroed().rsp(0x8000)).unwrap();
+    assert_eq!(res.read_memory(0x7FF8, 64).to_u64(), 0xFFF8_0000_0000_0000);
+    assert_eq!(res.x87_top(), 1);
+}
+
+#[test]
+pub fn test_concrete_x87_compare() {
+    let fcom = X86Instruction::FCOM(FCOM::FCOM_ST0_X87 { operand_0: RegFloat::ST1 });
+    let condition_codes = |st0: u128, st1: u128| {
+        let state = ConcreteX86MachineState64::zeroed().x87_push(st1).x87_push(st0);
+        apply_instruction_concrete(fcom, 2, state).unwrap().x87_status & 0x4701
+    };
+    assert_eq!(condition_codes(X87_ONE, X87_TWO), 0x100);
+    assert_eq!(condition_codes(X87_TWO, X87_ONE), 0);
+    assert_eq!(condition_codes(X87_ONE, X87_ONE), 0x4000);
+    // unordered, and FCOM raises invalid for QNaNs
+    assert_eq!(condition_codes(X87_ONE, 0x7FFF_C000_0000_0000_0001), 0x4501);
+
+    let fcompp = X86Instruction::FCOMPP(FCOMPP::FCOMPP {});
+    let res = apply_instruction_concrete(fcompp, 2, ConcreteX86MachineState64::zeroed().x87_push(X87_ONE).x87_push(X87_TWO)).unwrap();
+    assert_eq!(res.x87_status, 0);
+    assert!(res.x87_register_empty(0) && res.x87_register_empty(7));
+}
+
+#[test]
+pub fn test_concrete_x87_memory() {
+    let state = ConcreteX86MachineState64::zeroed().rsp(0x8000).memory(0x7FF8, &(-5i32).to_le_bytes());
+    let fild = X86Instruction::FILD(FILD::FILD_ST0_MEMMEM32INT { operand_0: below_rsp() });
+    let loaded = apply_instruction_concrete(fild, 4, state).unwrap();
+    assert_eq!(loaded.x87_register(0), 0xC001_A000_0000_0000_0000);
+
+    let fistp = X86Instruction::FISTP(FISTP::FISTP_MEMMEM16INT_ST0 { operand_0: below_rsp() });
+    let stored = apply_instruction_concrete(fistp, 4, loaded).unwrap();
+    assert_eq!(stored.read_memory(0x7FF8, 16).to_u64(), 0xFFFB);
+    assert_eq!(stored.x87_top(), 0);
+    // out of range stores the integer indefinite value
+    let out_of_range = ConcreteX86MachineState64::zeroed().rsp(0x8000).x87_push(0x400F_8000_0000_0000_0000);
+    let res = apply_instruction_concrete(fistp, 4, out_of_range).unwrap();
+    assert_eq!(res.read_memory(0x7FF8, 16).to_u64(), 0x8000);
+    assert_eq!(res.x87_status & 0x3F, 1);
+
+    let state = ConcreteX86MachineState64::zeroed().rsp(0x8000).memory(0x7FF8, &1.5f64.to_bits().to_le_bytes());
+    let fld = X86Instruction::FLD(FLD::FLD_ST0_MEMM64REAL { operand_0: below_rsp() });
+    assert_eq!(apply_instruction_concrete(fld, 4, state).unwrap().x87_register(0), 0x3FFF_C000_0000_0000_0000);
+}
+
+#[test]
+pub fn test_concrete_x87_control_and_status() {
+    let fnstsw = X86Instruction::FNSTSW(FNSTSW::FNSTSW_AX {});
+    let state = ConcreteX86MachineState64::zeroed().rax(u64::MAX).x87_push(X87_ONE);
+    assert_eq!(apply_instruction_concrete(fnstsw, 2, state).unwrap().rax, 0xFFFF_FFFF_FFFF_3800);
+
+    let fldcw = X86Instruction::FLDCW(FLDCW::FLDCW_MEMMEM16 { operand_0: below_rsp() });
+    let state = ConcreteX86MachineState64::zeroed().rsp(0x8000).memory(0x7FF8, &[0xFF, 0xFF]);
+    assert_eq!(apply_instruction_concrete(fldcw, 4, state).unwrap().x87_control, 0x1F7F);
+
+    // unmasking a flag which is already set leaves an exception pending, which the next waiting instruction raises
+    let state = ConcreteX86MachineState64::zeroed().rsp(0x8000).x87_status(0x20).x87_push(X87_ONE).x87_push(X87_ONE);
+    let unmasked = apply_instruction_concrete(fldcw, 4, state).unwrap();
+    assert_eq!(unmasked.x87_control, 0x40);
+    assert_eq!(unmasked.x87_status & 0x80FF, 0x80A0);
+    let fadd = X86Instruction::FADD(FADD::FADD_ST0_X87 { operand_0: RegFloat::ST1 });
+    assert_eq!(apply_instruction_concrete(fadd, 2, unmasked.clone()), Err(ConcreteError::Fault(Fault::FloatingPointError)));
+    assert!(apply_instruction_concrete(fnstsw, 2, unmasked).is_ok());
+
+    // an unmasked invalid operation leaves the destination alone
+    let signaling_nan = 0x7FFF_A000_0000_0000_0000;
+    let state = ConcreteX86MachineState64::zeroed().x87_control(0x037E).x87_push(X87_ONE).x87_push(signaling_nan);
+    let res = apply_instruction_concrete(fadd, 2, state).unwrap();
+    assert_eq!(res.x87_register(0), signaling_nan);
+    assert_eq!(res.x87_status & 0x80FF, 0x8081);
+}
+
+#[test]
+pub fn test_differential_x87() {
+    let instrs = [
+        X86Instruction::FADD(FADD::FADD_MEMMEM32REAL { operand_0: below_rsp() }),
+        X86Instruction::FADD(FADD::FADD_MEMM64REAL { operand_0: below_rsp() }),
+        X86Instruction::FLDCW(FLDCW::FLDCW_MEMMEM16 { operand_0: below_rsp() }),
+        X86Instruction::FNSTCW(FNSTCW::FNSTCW_MEMMEM16 { operand_0: below_rsp() }),
+        X86Instruction::FNSTSW(FNSTSW::FNSTSW_MEMMEM16 { operand_0: below_rsp() }),
+    ];
+    for instr in instrs {
+        assert_no_divergence(instr, 200);
+    }
+}
+
+#[test]
+pub fn test_smtlib2_text() {
+    let bump = Bump::new();
+    let mut s = SemanticsBuilder::new(Arena::new(&bump));
+    s.set_al(s.add(s.al(), s.constant(1u8)));
+    let steps = s.finalize();
+    let script = to_smtlib2(Arena::new(&bump), steps.as_slice()).unwrap();
+    assert_eq!(script.text, "\
+(set-logic QF_BV)
+(declare-const rax (_ BitVec 64))
+(define-fun t0 () (_ BitVec 8) (bvadd ((_ extract 7 0) rax) #x01))
+(define-fun t1 () (_ BitVec 64) (concat ((_ extract 63 8) rax) t0))
+(define-fun rax_out () (_ BitVec 64) t1)
+(define-fun fault () Bool false)
+");
+
+    let mut s = SemanticsBuilder::new(Arena::new(&bump));
+    s.emit_conditional(s.zf(), |s| s.set_al(s.constant(2u8)), |s| s.divide_error());
+    let steps = s.finalize();
+    let script = to_smtlib2(Arena::new(&bump), steps.as_slice()).unwrap();
+    assert_eq!(script.inputs, vec![StateComponent::Gpr(Reg64WithRIP::RAX), StateComponent::Flag(Flag::ZF)]);
+    assert_eq!(script.outputs, vec![StateComponent::Gpr(Reg64WithRIP::RAX)]);
+    assert_eq!(script.text, "\
+(set-logic QF_BV)
+(declare-const rax (_ BitVec 64))
+(declare-const zf (_ BitVec 1))
+(define-fun t0 () Bool (= zf #b1))
+(define-fun t1 () Bool (and true t0))
+(define-fun t2 () (_ BitVec 64) (concat ((_ extract 63 8) rax) #x02))
+(define-fun t3 () Bool (and true (not t0)))
+(define-fun t4 () Bool (or false (and t3 true)))
+(define-fun t5 () (_ BitVec 64) (ite t0 t2 rax))
+(define-fun rax_out () (_ BitVec 64) t5)
+(define-fun fault () Bool t4)
+");
+}
+
+#[test]
+pub fn test_smtlib2_unsupported() {
+    let bump = Bump::new();
+    let addsd = X86Instruction::ADDSD(ADDSD::ADDSD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let steps = apply_instruction(Arena::new(&bump), addsd, 4).unwrap();
+    assert!(matches!(to_smtlib2(Arena::new(&bump), steps.as_slice()), Err(SmtLib2Error::Unsupported(_))));
+}
+
+/// A solver which reads a script from stdin, if one is installed.
+fn smt_solver() -> Option<Command> {
+    [("z3", &["-in"][..]), ("cvc5", &["--lang=smt2"][..]), ("bitwuzla", &[][..])].into_iter()
+        .find(|(solver, _)| Command::new(solver).arg("--version").output().is_ok())
+        .map(|(solver, args)| {
+            let mut command = Command::new(solver);
+            command.args(args);
+            command
+        })
+}
+
+/// Checks with `solver` that the exported semantics of `instr` can't disagree with the concrete evaluator when
+/// started from `state`.
+fn assert_smtlib2_agrees_with_concrete(solver: &mut Command, instr: X86Instruction, state: ConcreteX86MachineState64) {
+    let (_, length) = instr.encode(&mut EncodeDecodeContext::new()).unwrap();
+    let bump = Bump::new();
+    let steps = apply_instruction(Arena::new(&bump), instr, length).unwrap();
+    let script = to_smtlib2(Arena::new(&bump), steps.as_slice()).unwrap();
+    let mut text = script.text.clone();
+    for input in script.inputs.iter() {
+        text.push_str(&format!("(assert (= {} {}))\n", input.name(), input.value_in(&state)));
+    }
+    let expected = match apply_instructions_to_concrete(state, steps.as_slice()) {
+        Ok(res) => {
+            let outputs = script.outputs.iter()
+                .map(|output| format!("(= {} {})", output.output_name(), output.value_in(&res)))
+                .collect::<Vec<_>>();
+            format!("(and (not fault) {})", outputs.join(" "))
+        }
+        Err(_) => "fault".to_string(),
+    };
+    text.push_str(&format!("(assert (not {expected}))\n(check-sat)\n"));
+
+    let mut child = solver.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(text.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "unsat", "{instr:?} disagrees with the concrete evaluator:\n{text}");
+}
+
+#[test]
+pub fn test_smtlib2_agrees_with_concrete() {
+    let Some(mut solver) = smt_solver() else {
+        eprintln!("no SMT solver installed, skipping");
+        return;
+    };
+    let rax = Reg64WithRIP::RAX;
+    let rbx = Reg64WithRIP::RBX;
+    let flags = ConcreteFlags::zeroed().cf(true).sf(true);
+    let states = [
+        ConcreteX86MachineState64::zeroed().rip(0x1000).rax(0x8000_0000_0000_00FF).rbx(0xFFFF_FFFF_0000_0001).rcx(0x21).rdx(1).flags(flags),
+        ConcreteX86MachineState64::zeroed().rip(0x1000).rax(5).rcx(0x1F).rsp(0x8000).memory(0x7FF8, &[0xFE, 0xFF]),
+    ];
+    let instrs = [
+        X86Instruction::ADD(ADD::ADD_GPRV_GPRV_01_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::ADC(ADC::ADC_GPR8_GPR8_12 { operand_0: Reg8::BL, operand_1: Reg8::CL }),
+        X86Instruction::SUB(SUB::SUB_GPRV_GPRV_29_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::SBB(SBB::SBB_GPR8_GPR8_18 { operand_0: Reg8::AH, operand_1: Reg8::BL }),
+        X86Instruction::DIV(DIV::DIV_GPRV_64 { operand_0: rbx }),
+        X86Instruction::IMUL(IMUL::IMUL_GPRV_64 { operand_0: rbx }),
+        X86Instruction::SHR(SHR::SHR_GPRV_CL_32 { operand_0: Reg32WithRIP::EAX }),
+        X86Instruction::SHL(SHL::SHL_GPRV_ONE_D1R4_64 { operand_0: rax }),
+        X86Instruction::RCR(RCR::RCR_GPR8_ONE { operand_0: Reg8::AL }),
+        X86Instruction::BSF(BSF::BSF_GPRV_GPRV_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::CMOVL(CMOVL::CMOVL_GPRV_GPRV_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::XCHG(XCHG::XCHG_GPRV_GPRV_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::ADD(ADD::ADD_MEMB_IMMB_80R0 { operand_0: below_rsp(), operand_1: Imm8(3) }),
+        X86Instruction::PUSH(PUSH::PUSH_IMMB { operand_0: Imm8(-2) }),
+        X86Instruction::POP(POP::POP_GPRV_58_64 { operand_0: rbx }),
+    ];
+    for instr in instrs {
+        for state in states.iter() {
+            assert_smtlib2_agrees_with_concrete(&mut solver, instr, state.clone());
         }
     }
 }
 
-pub mod instruction_64;
\ No newline at end of file
+#[test]
+pub fn test_arena_shares_equal_expressions() {
+    let bump = Bump::new();
+    let s = SemanticsBuilder::new(Arena::new(&bump));
+    assert!(ptr::eq(s.add(s.al(), s.constant(1u8)), s.add(s.al(), s.constant(1u8))));
+    assert!(!ptr::eq(s.add(s.al(), s.constant(1u8)), s.add(s.al(), s.constant(2u8))));
+    let s = SemanticsBuilder::new(Arena::unsimplified(&bump));
+    assert!(!ptr::eq(s.al(), s.al()));
+}
+
+#[test]
+pub fn test_simplify_rules() {
+    let bump = Bump::new();
+    let s = SemanticsBuilder::new(Arena::unsimplified(&bump));
+    let mut simplifier = Simplifier::new(Arena::new(&bump));
+    let rax = s.get_reg_64(Reg64WithRIP::RAX);
+    let rbx = s.get_reg_64(Reg64WithRIP::RBX);
+    let quotient = s.udiv(rax, rbx);
+    // pairs of an expression and what it simplifies to
+    let cases = [
+        (s.add(s.constant(3u64), s.constant(4u64)), s.constant(7u64)),
+        (s.bitxor(rax, s.get_reg_64(Reg64WithRIP::RAX)), s.constant(0u64)),
+        (s.not(s.not(rax)), rax),
+        (s.sub(s.constant(0u64), s.sub(s.constant(0u64), rbx)), rbx),
+        (s.extract(s.concat(rax, rbx), 64, 96), s.extract(rax, 0, 32)),
+        (s.lower_bits(s.concat(rax, rbx), 64), rbx),
+        (s.concat(s.extract(rax, 16, 32), s.extract(rax, 8, 16)), s.extract(rax, 8, 32)),
+        (s.zext_to(s.constant(0xFFu8), 16), s.constant(0xFFu16)),
+        (s.extract(s.zext_to(s.lower_bits(rax, 8), 64), 8, 64), s.constant_with_width(0, 56)),
+        (s.select(s.constant_with_width(1, 1), rax, quotient), rax),
+        (s.select(s.equal(rax, s.get_reg_64(Reg64WithRIP::RAX)), rbx, quotient), rbx),
+        (s.bitand(rax, s.constant(0u64)), s.constant(0u64)),
+        (s.bitor(s.constant(0u64), rbx), rbx),
+        (s.sub(s.add(s.constant(8u64), rax), s.sub(rax, s.constant(8u64))), s.constant(16u64)),
+        (s.add(s.sub(rax, s.constant(8u64)), s.constant(3u64)), s.add(rax, s.constant(-5i64 as u64))),
+        (s.extract(s.lshr(rax, s.constant(8u64)), 0, 8), s.extract(rax, 8, 16)),
+        (s.select(s.equal(rax, rbx), s.constant_with_width(1, 1), s.constant_with_width(0, 1)), s.equal(rax, rbx)),
+        // the division still has to fault when rbx is zero
+        (s.bitxor(quotient, quotient), s.bitxor(quotient, quotient)),
+        (s.bitand(quotient, s.constant(0u64)), s.bitand(quotient, s.constant(0u64))),
+    ];
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let states = [
+        ConcreteX86MachineState64::zeroed().rax(rng.gen()).rbx(rng.gen()),
+        ConcreteX86MachineState64::zeroed().rax(rng.gen()),
+    ];
+    for (expr, expected) in cases {
+        let simplified = simplifier.simplify(expr);
+        assert!(ptr::eq(simplified, simplifier.simplify(expected)));
+        for state in states.iter() {
+            let execution = ConcreteExecution::new(state.clone());
+            assert!(expr.apply_concrete(&execution) == simplified.apply_concrete(&execution));
+        }
+    }
+}
+
+#[test]
+pub fn test_simplified_semantics_agree() {
+    let rax = Reg64WithRIP::RAX;
+    let rbx = Reg64WithRIP::RBX;
+    let instrs = [
+        X86Instruction::ADD(ADD::ADD_GPR8_GPR8_00 { operand_0: Reg8::AH, operand_1: Reg8::BL }),
+        X86Instruction::ADD(ADD::ADD_ORAX_IMMZ_16 { operand_0: Imm16(-0x7FFF) }),
+        X86Instruction::ADD(ADD::ADD_MEMB_IMMB_80R0 { operand_0: below_rsp(), operand_1: Imm8(3) }),
+        X86Instruction::ADC(ADC::ADC_GPRV_IMMB_32 { operand_0: Reg32WithRIP::EBX, operand_1: Imm8(-128) }),
+        X86Instruction::ADCX(ADCX::ADCX_GPR64Q_GPR64Q { operand_0: rax, operand_1: rbx }),
+        X86Instruction::SUB(SUB::SUB_GPRV_GPRV_29_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::SBB(SBB::SBB_GPR8_GPR8_18 { operand_0: Reg8::AH, operand_1: Reg8::BL }),
+        X86Instruction::CMP(CMP::CMP_GPRV_GPRV_39_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::TEST(TEST::TEST_GPRV_GPRV_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::SHL(SHL::SHL_GPRV_ONE_D1R4_64 { operand_0: rax }),
+        X86Instruction::SHR(SHR::SHR_GPRV_CL_32 { operand_0: Reg32WithRIP::EAX }),
+        X86Instruction::RCR(RCR::RCR_GPR8_ONE { operand_0: Reg8::AL }),
+        X86Instruction::IMUL(IMUL::IMUL_GPRV_64 { operand_0: rbx }),
+        X86Instruction::IMUL(IMUL::IMUL_GPRV_GPRV_IMMZ_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX, operand_2: Imm32(-3) }),
+        X86Instruction::DIV(DIV::DIV_GPRV_64 { operand_0: rbx }),
+        X86Instruction::BSF(BSF::BSF_GPRV_GPRV_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::BTS(BTS::BTS_GPRV_GPRV_64 { operand_0: rax, operand_1: rbx }),
+        X86Instruction::CMOVL(CMOVL::CMOVL_GPRV_GPRV_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::SETNBE(SETNBE::SETNBE_GPR8 { operand_0: Reg8::BH }),
+        X86Instruction::XCHG(XCHG::XCHG_GPRV_GPRV_32 { operand_0: Reg32WithRIP::EAX, operand_1: Reg32WithRIP::EBX }),
+        X86Instruction::MOVZX(MOVZX::MOVZX_GPRV_GPR8_64 { operand_0: rax, operand_1: Reg8::BH }),
+        X86Instruction::PUSH(PUSH::PUSH_IMMB { operand_0: Imm8(-2) }),
+        X86Instruction::POP(POP::POP_GPRV_58_64 { operand_0: rbx }),
+        X86Instruction::PUSHFQ(PUSHFQ::PUSHFQ {}),
+        X86Instruction::ADDSD(ADDSD::ADDSD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 }),
+        X86Instruction::FADD(FADD::FADD_ST0_X87 { operand_0: RegFloat::ST1 }),
+    ];
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for instr in instrs {
+        let (_, length) = instr.encode(&mut EncodeDecodeContext::new()).unwrap();
+        let bump = Bump::new();
+        let simplified = apply_instruction(Arena::new(&bump), instr, length).unwrap();
+        let unsimplified = apply_instruction(Arena::unsimplified(&bump), instr, length).unwrap();
+        for _ in 0..100 {
+            let flags = ConcreteFlags::zeroed().cf(rng.gen()).pf(rng.gen()).af(rng.gen()).zf(rng.gen()).sf(rng.gen()).of(rng.gen());
+            let state = ConcreteX86MachineState64::zeroed()
+                .rax(rng.gen())
+                .rbx(if rng.gen() { rng.gen() } else { rng.gen_range(0..4) })
+                .rcx(rng.gen())
+                .rdx(rng.gen_range(0..4))
+                .rsp(0x8000)
+                .memory(0x7FF8, &rng.gen::<u64>().to_le_bytes())
+                .xmm0([rng.gen::<f64>(), rng.gen()])
+                .xmm1([rng.gen::<f64>(), rng.gen()])
+                .x87_push(X87_INDEFINITE)
+                .x87_push(X87_INDEFINITE)
+                .flags(flags);
+            assert_eq!(
+                apply_instructions_to_concrete(state.clone(), simplified.as_slice()),
+                apply_instructions_to_concrete(state, unsimplified.as_slice()),
+                "{instr:?}"
+            );
+        }
+    }
+}
+
+/// Checks that `initial` takes exactly one of `paths`, and that it ends the way running `block` on `initial` does.
+fn assert_symbolic_agrees_with_concrete<'arena>(arena: Arena<'arena>, block: &BasicBlock<'arena>, paths: &[SymbolicPath<'arena>], initial: &ConcreteX86MachineState64) {
+    let execution = ConcreteExecution::new(initial.clone());
+    let taken = paths.iter()
+        .filter(|path| path.condition.apply_concrete(&execution).unwrap().is_true())
+        .collect::<Vec<_>>();
+    assert_eq!(taken.len(), 1, "{initial:?}");
+    let path = taken[0];
+    let concrete = match block.apply_concrete(initial.clone()) {
+        Ok(concrete) => concrete,
+        Err(fault) => {
+            assert_eq!(path.fault, Some(fault), "{initial:?}");
+            return;
+        }
+    };
+    assert_eq!(path.fault, None, "{initial:?}");
+    let regs = [Reg64WithRIP::RAX, Reg64WithRIP::RBX, Reg64WithRIP::RCX, Reg64WithRIP::RDX, Reg64WithRIP::RSI,
+        Reg64WithRIP::RDI, Reg64WithRIP::RBP, Reg64WithRIP::RSP, Reg64WithRIP::R8, Reg64WithRIP::R9, Reg64WithRIP::R10,
+        Reg64WithRIP::R11, Reg64WithRIP::R12, Reg64WithRIP::R13, Reg64WithRIP::R14, Reg64WithRIP::R15, Reg64WithRIP::RIP];
+    for reg in regs {
+        assert_eq!(path.state.gpr64(reg).apply_concrete(&execution).unwrap().to_u64(), concrete.gpr64(reg), "{reg:?} {initial:?}");
+    }
+    for flag in [Flag::CF, Flag::PF, Flag::AF, Flag::ZF, Flag::SF, Flag::OF] {
+        assert_eq!(path.state.get_flag(flag).apply_concrete(&execution).unwrap().is_true(), concrete.get_flag(flag), "{flag:?} {initial:?}");
+    }
+    let builder = SemanticsBuilder::new(arena);
+    for &address in concrete.memory.keys() {
+        let byte = path.state.read_memory(builder.constant_with_width(address, 64), 8);
+        assert_eq!(byte.apply_concrete(&execution).unwrap(), concrete.read_memory(address, 8), "{address:#x} {initial:?}");
+    }
+}
+
+#[test]
+pub fn test_symbolic_branch_conditions() {
+    // cmp rax, rbx; jz +2
+    let bytes = [0x48, 0x39, 0xD8, 0x74, 0x02];
+    let bump = Bump::new();
+    let arena = Arena::new(&bump);
+    let block = lift_basic_block(arena, &bytes, 0x1000).unwrap();
+    let paths = SymbolicExecutor::new(arena).execute_block(X86MachineState64::initial(arena), &block).unwrap();
+    assert_eq!(paths.len(), 2);
+    let start = ConcreteExecution::new(ConcreteX86MachineState64::zeroed().rip(0x1000));
+    let taken = paths.iter()
+        .find(|path| path.state.gpr64(Reg64WithRIP::RIP).apply_concrete(&start).unwrap().to_u64() == 0x1007)
+        .unwrap();
+    let reaches_branch = |rax: u64, rbx: u64| {
+        let execution = ConcreteExecution::new(ConcreteX86MachineState64::zeroed().rip(0x1000).rax(rax).rbx(rbx));
+        taken.condition.apply_concrete(&execution).unwrap().is_true()
+    };
+    assert!(reaches_branch(5, 5));
+    assert!(!reaches_branch(5, 6));
+    assert!(reaches_branch(u64::MAX, u64::MAX));
+
+    // push rax; pop rbx, which reads back what was pushed without a conditional per byte
+    let bytes = [0x50, 0x5B];
+    let block = lift_basic_block(arena, &bytes, 0x1000).unwrap();
+    let paths = SymbolicExecutor::new(arena).execute_block(X86MachineState64::initial(arena), &block).unwrap();
+    assert_eq!(paths.len(), 1);
+    let initial_rax = arena.expr(Expression::GetReg { reg: GeneralReg::Reg64(Reg64WithRIP::RAX), at_index: 0 });
+    assert!(ptr::eq(paths[0].state.gpr64(Reg64WithRIP::RBX), initial_rax));
+}
+
+#[test]
+pub fn test_symbolic_agrees_with_concrete() {
+    let blocks: [&[u8]; 2] = [
+        // add rax, rbx; adc rcx, rdx; sbb r10, r11; shl rsi, cl; sar rdi, 1; shr r12, 3; imul r8, r9; xor r13, r14;
+        // inc r15; push rax; pop rbx; jl +16
+        &[0x48, 0x01, 0xD8, 0x48, 0x11, 0xD1, 0x4D, 0x19, 0xDA, 0x48, 0xD3, 0xE6, 0x48, 0xD1, 0xFF, 0x49, 0xC1, 0xEC, 0x03,
+            0x4D, 0x0F, 0xAF, 0xC1, 0x4D, 0x31, 0xF5, 0x49, 0xFF, 0xC7, 0x50, 0x5B, 0x7C, 0x10],
+        // div rbx, which faults if rbx is zero or the quotient doesn't fit; cmp rax, rbx; jz +2
+        &[0x48, 0xF7, 0xF3, 0x48, 0x39, 0xD8, 0x74, 0x02],
+    ];
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for bytes in blocks {
+        let bump = Bump::new();
+        let arena = Arena::new(&bump);
+        let block = lift_basic_block(arena, bytes, 0x1000).unwrap();
+        let paths = SymbolicExecutor::new(arena).execute_block(X86MachineState64::initial(arena), &block).unwrap();
+        for _ in 0..200 {
+            let small = |rng: &mut rand::rngs::StdRng| if rng.gen() { rng.gen() } else { rng.gen_range(0..4) };
+            let flags = ConcreteFlags::zeroed().cf(rng.gen()).pf(rng.gen()).af(rng.gen()).zf(rng.gen()).sf(rng.gen()).of(rng.gen());
+            let state = ConcreteX86MachineState64::zeroed()
+                .rax(small(&mut rng))
+                .rbx(small(&mut rng))
+                .rcx(small(&mut rng))
+                .rdx(small(&mut rng))
+                .rsi(rng.gen())
+                .rdi(rng.gen())
+                .r8(small(&mut rng))
+                .r9(small(&mut rng))
+                .r10(rng.gen())
+                .r11(rng.gen())
+                .r12(rng.gen())
+                .r13(rng.gen())
+                .r14(rng.gen())
+                .r15(small(&mut rng))
+                .rsp(0x8000)
+                .memory(0x7FF8, &rng.gen::<u64>().to_le_bytes())
+                .rip(0x1000)
+                .flags(flags);
+            assert_symbolic_agrees_with_concrete(arena, &block, paths.as_slice(), &state);
+        }
+    }
+}
+
+#[test]
+pub fn test_llvm_ir_text() {
+    let bump = Bump::new();
+    let arena = Arena::new(&bump);
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_al(s.add(s.al(), s.constant(1u8)));
+    let steps = s.finalize();
+    let mut module = LlvmIrModule::new(arena);
+    module.add_function("add_al", &[steps.as_slice()]).unwrap();
+    assert_eq!(module.text(), "\
+%struct.State = type { [17 x i64], [6 x i8], [32 x i512], i32, [2 x i64], i8, [6 x i16] }
+%struct.Memory = type opaque
+
+define %struct.Memory* @add_al(%struct.State* noalias %state, i64 %pc, %struct.Memory* %mem) {
+entry:
+  %rax.ptr = getelementptr inbounds %struct.State, %struct.State* %state, i32 0, i32 0, i32 0
+  %rax = load i64, i64* %rax.ptr
+  br label %b0
+
+b0:
+  %t0 = trunc i64 %rax to i8
+  %t1 = add i8 %t0, 1
+  %t2 = and i64 %rax, 18446744073709551360
+  %t3 = zext i8 %t1 to i64
+  %t4 = or i64 %t2, %t3
+  store i64 %t4, i64* %rax.ptr
+  ret %struct.Memory* %mem
+}
+");
+
+    let mut s = SemanticsBuilder::new(arena);
+    s.emit_conditional(s.zf(), |s| s.set_al(s.constant(2u8)), |s| s.divide_error());
+    let steps = s.finalize();
+    let mut module = LlvmIrModule::new(arena);
+    module.add_function("set_al_or_fault", &[steps.as_slice()]).unwrap();
+    assert_eq!(module.text(), "\
+%struct.State = type { [17 x i64], [6 x i8], [32 x i512], i32, [2 x i64], i8, [6 x i16] }
+%struct.Memory = type opaque
+
+declare %struct.Memory* @__remill_error(%struct.State*, i64, %struct.Memory*)
+
+define %struct.Memory* @set_al_or_fault(%struct.State* noalias %state, i64 %pc, %struct.Memory* %mem) {
+entry:
+  %zf.ptr = getelementptr inbounds %struct.State, %struct.State* %state, i32 0, i32 1, i32 3
+  %zf.byte = load i8, i8* %zf.ptr
+  %zf = icmp ne i8 %zf.byte, 0
+  %rax.ptr = getelementptr inbounds %struct.State, %struct.State* %state, i32 0, i32 0, i32 0
+  %rax = load i64, i64* %rax.ptr
+  %rip.ptr = getelementptr inbounds %struct.State, %struct.State* %state, i32 0, i32 0, i32 16
+  %rip = load i64, i64* %rip.ptr
+  br label %b0
+
+b0:
+  br i1 %zf, label %b1, label %b2
+
+b1:
+  %t0 = and i64 %rax, 18446744073709551360
+  %t1 = zext i8 2 to i64
+  %t2 = or i64 %t0, %t1
+  br label %b3
+
+b2:
+  %t3 = getelementptr inbounds %struct.State, %struct.State* %state, i32 0, i32 5
+  store i8 0, i8* %t3
+  %t4 = call %struct.Memory* @__remill_error(%struct.State* %state, i64 %rip, %struct.Memory* %mem)
+  ret %struct.Memory* %t4
+
+b3:
+  store i64 %t2, i64* %rax.ptr
+  ret %struct.Memory* %mem
+}
+");
+}
+
+#[test]
+pub fn test_llvm_ir_unsupported() {
+    let bump = Bump::new();
+    let arena = Arena::new(&bump);
+    let addsd = X86Instruction::ADDSD(ADDSD::ADDSD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let steps = apply_instruction(arena, addsd, 4).unwrap();
+    let mut module = LlvmIrModule::new(arena);
+    assert!(matches!(module.add_function("addsd", &[steps.as_slice()]), Err(LlvmIrError::Unsupported(_))));
+    assert!(!module.text().contains("define"));
+}
+
+/// Definitions of the memory intrinsics over 64KiB of RAM, which addresses wrap around in, and a `main` running
+/// `function` on `state` which prints the registers, the flags, the exception vector and then the bytes at
+/// `addresses`, one per line in hexadecimal.
+fn llvm_ir_harness(state: &ConcreteX86MachineState64, function: &str, addresses: &[u64]) -> String {
+    let mut text = String::from("
+@ram = global [65536 x i8] zeroinitializer
+@s = global %struct.State zeroinitializer
+@format = private constant [6 x i8] c\"%llx\\0A\\00\"
+
+declare i32 @printf(i8*, ...)
+
+define %struct.Memory* @__remill_error(%struct.State* %state, i64 %pc, %struct.Memory* %memory) {
+  ret %struct.Memory* %memory
+}
+");
+    for width in [8, 16, 32, 64] {
+        text.push_str(&format!("
+define i{width} @__remill_read_memory_{width}(%struct.Memory* %memory, i64 %address) {{
+  %index = and i64 %address, 65535
+  %byte = getelementptr [65536 x i8], [65536 x i8]* @ram, i64 0, i64 %index
+  %pointer = bitcast i8* %byte to i{width}*
+  %value = load i{width}, i{width}* %pointer, align 1
+  ret i{width} %value
+}}
+
+define %struct.Memory* @__remill_write_memory_{width}(%struct.Memory* %memory, i64 %address, i{width} %value) {{
+  %index = and i64 %address, 65535
+  %byte = getelementptr [65536 x i8], [65536 x i8]* @ram, i64 0, i64 %index
+  %pointer = bitcast i8* %byte to i{width}*
+  store i{width} %value, i{width}* %pointer, align 1
+  ret %struct.Memory* %memory
+}}
+"));
+    }
+    let field = |indices: String| format!("getelementptr inbounds (%struct.State, %struct.State* @s, i32 0, {indices})");
+    let ram = |address: u64| format!("getelementptr inbounds ([65536 x i8], [65536 x i8]* @ram, i64 0, i64 {})", address & 0xFFFF);
+    let mut printed = vec![];
+    text.push_str("\ndefine i32 @main() {\n");
+    for (i, reg) in LLVM_IR_GPRS.into_iter().enumerate() {
+        text.push_str(&format!("  store i64 {}, i64* {}\n", state.gpr64(reg), field(format!("i32 0, i32 {i}"))));
+        printed.push(("i64", field(format!("i32 0, i32 {i}"))));
+    }
+    for (i, flag) in LLVM_IR_FLAGS.into_iter().enumerate() {
+        text.push_str(&format!("  store i8 {}, i8* {}\n", state.get_flag(flag) as u8, field(format!("i32 1, i32 {i}"))));
+        printed.push(("i8", field(format!("i32 1, i32 {i}"))));
+    }
+    text.push_str(&format!("  store i32 {}, i32* {}\n", state.mxcsr, field("i32 3".to_string())));
+    text.push_str(&format!("  store i8 255, i8* {}\n", field("i32 5".to_string())));
+    printed.push(("i8", field("i32 5".to_string())));
+    for (address, byte) in state.memory.iter() {
+        text.push_str(&format!("  store i8 {byte}, i8* {}\n", ram(*address)));
+    }
+    printed.extend(addresses.iter().map(|address| ("i8", ram(*address))));
+    text.push_str(&format!("  %memory = call %struct.Memory* @{function}(%struct.State* @s, i64 {}, %struct.Memory* null)\n", state.gpr64(Reg64WithRIP::RIP)));
+    for (i, (llvm_type, pointer)) in printed.into_iter().enumerate() {
+        text.push_str(&format!("  %v{i} = load {llvm_type}, {llvm_type}* {pointer}\n"));
+        let value = if llvm_type == "i64" {
+            format!("%v{i}")
+        } else {
+            text.push_str(&format!("  %w{i} = zext {llvm_type} %v{i} to i64\n"));
+            format!("%w{i}")
+        };
+        text.push_str(&format!("  call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([6 x i8], [6 x i8]* @format, i64 0, i64 0), i64 {value})\n"));
+    }
+    text.push_str("  ret i32 0\n}\n");
+    text
+}
+
+const LLVM_IR_GPRS: [Reg64WithRIP; 17] = [Reg64WithRIP::RAX, Reg64WithRIP::RBX, Reg64WithRIP::RCX, Reg64WithRIP::RDX,
+    Reg64WithRIP::RSI, Reg64WithRIP::RDI, Reg64WithRIP::RBP, Reg64WithRIP::RSP, Reg64WithRIP::R8, Reg64WithRIP::R9,
+    Reg64WithRIP::R10, Reg64WithRIP::R11, Reg64WithRIP::R12, Reg64WithRIP::R13, Reg64WithRIP::R14, Reg64WithRIP::R15,
+    Reg64WithRIP::RIP];
+
+const LLVM_IR_FLAGS: [Flag; 6] = [Flag::CF, Flag::PF, Flag::AF, Flag::ZF, Flag::SF, Flag::OF];
+
+/// Runs `block`, lowered in `module`, on `state` with `lli` and checks it ends up the way the concrete evaluator
+/// does, or in the state before the faulting instruction with the fault's vector.
+fn assert_llvm_ir_agrees_with_concrete(module: &str, block: &BasicBlock, state: &ConcreteX86MachineState64) {
+    let mut expected_state = state.clone();
+    let mut vector = 0xFF;
+    for instruction in block.instructions.iter() {
+        match apply_instructions_to_concrete(expected_state.clone(), instruction.semantics.as_slice()) {
+            Ok(next) => expected_state = next,
+            Err(fault) => {
+                vector = exception_vector(fault);
+                break;
+            }
+        }
+    }
+    // the harness's memory doesn't roll back writes made before a fault
+    let addresses = if vector == 0xFF { expected_state.memory.keys().copied().collect() } else { vec![] };
+    let mut expected = LLVM_IR_GPRS.into_iter().map(|reg| expected_state.gpr64(reg)).collect::<Vec<_>>();
+    expected.extend(LLVM_IR_FLAGS.into_iter().map(|flag| expected_state.get_flag(flag) as u64));
+    expected.push(vector as u64);
+    expected.extend(addresses.iter().map(|address| expected_state.read_memory(*address, 8).to_u64()));
+
+    // the harness defines the intrinsics the module declares
+    let lifted = module.lines()
+        .filter(|line| !(line.starts_with("declare") && line.contains("@__remill_")))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = format!("{lifted}\n{}", llvm_ir_harness(state, &format!("block_{:x}", block.start_address), addresses.as_slice()));
+    let mut child = Command::new("lli").stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(text.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}\n{text}", String::from_utf8_lossy(&output.stderr));
+    let actual = String::from_utf8_lossy(&output.stdout).lines()
+        .map(|line| u64::from_str_radix(line, 16).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected, "{state:?}");
+}
+
+#[test]
+pub fn test_llvm_ir_agrees_with_concrete() {
+    if Command::new("lli").arg("--version").output().is_err() {
+        eprintln!("lli not installed, skipping");
+        return;
+    }
+    let blocks: [&[u8]; 3] = [
+        // add rax, rbx; adc rcx, rdx; sbb r10, r11; shl rsi, cl; sar rdi, 1; shr r12, 3; imul r8, r9; xor r13, r14;
+        // inc r15; push rax; pop rbx; jl +16
+        &[0x48, 0x01, 0xD8, 0x48, 0x11, 0xD1, 0x4D, 0x19, 0xDA, 0x48, 0xD3, 0xE6, 0x48, 0xD1, 0xFF, 0x49, 0xC1, 0xEC, 0x03,
+            0x4D, 0x0F, 0xAF, 0xC1, 0x4D, 0x31, 0xF5, 0x49, 0xFF, 0xC7, 0x50, 0x5B, 0x7C, 0x10],
+        // div rbx; cmp rax, rbx; jz +2
+        &[0x48, 0xF7, 0xF3, 0x48, 0x39, 0xD8, 0x74, 0x02],
+        // bsf rax, rbx; popcnt rcx, rdx; lzcnt r8, r9; cmovl esi, edi; xchg r10d, r11d; add [rsp - 8], r12;
+        // sub dword [rsp - 4], 7; idiv cl; ret
+        &[0x48, 0x0F, 0xBC, 0xC3, 0xF3, 0x48, 0x0F, 0xB8, 0xCA, 0xF3, 0x4D, 0x0F, 0xBD, 0xC1, 0x0F, 0x4C, 0xF7, 0x45, 0x87,
+            0xDA, 0x4C, 0x01, 0x64, 0x24, 0xF8, 0x83, 0x6C, 0x24, 0xFC, 0x07, 0xF6, 0xF9, 0xC3],
+    ];
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for bytes in blocks {
+        let bump = Bump::new();
+        let arena = Arena::new(&bump);
+        let block = lift_basic_block(arena, bytes, 0x1000).unwrap();
+        let mut module = LlvmIrModule::new(arena);
+        module.add_block(&block).unwrap();
+        let text = module.text();
+        for _ in 0..20 {
+            let small = |rng: &mut rand::rngs::StdRng| if rng.gen() { rng.gen() } else { rng.gen_range(0..4) };
+            let flags = ConcreteFlags::zeroed().cf(rng.gen()).pf(rng.gen()).af(rng.gen()).zf(rng.gen()).sf(rng.gen()).of(rng.gen());
+            let state = ConcreteX86MachineState64::zeroed()
+                .rax(small(&mut rng))
+                .rbx(small(&mut rng))
+                .rcx(small(&mut rng))
+                .rdx(small(&mut rng))
+                .rsi(rng.gen())
+                .rdi(rng.gen())
+                .r8(small(&mut rng))
+                .r9(small(&mut rng))
+                .r10(rng.gen())
+                .r11(rng.gen())
+                .r12(rng.gen())
+                .r13(rng.gen())
+                .r14(rng.gen())
+                .r15(small(&mut rng))
+                .rsp(0x8000)
+                .memory(0x7FF8, &rng.gen::<u64>().to_le_bytes())
+                .rip(0x1000)
+                .flags(flags);
+            assert_llvm_ir_agrees_with_concrete(text.as_str(), &block, &state);
+        }
+    }
+}
+
+#[test]
+pub fn test_rust_codegen_step() {
+    let bump = Bump::new();
+    let arena = Arena::new(&bump);
+    let mut module = RustModule::new(arena, quote::quote!(crate::semantics2));
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_al(s.add(s.al(), s.constant(1u8)));
+    let steps = s.finalize();
+    module.add_function(&[0x04, 0x01], steps.as_slice()).unwrap();
+    let div = X86Instruction::DIV(DIV::DIV_GPRV_64 { operand_0: Reg64WithRIP::RBX });
+    let (bytes, length) = div.encode(&mut EncodeDecodeContext::new()).unwrap();
+    module.add_instruction(&bytes[..length]).unwrap();
+    let text = module.tokens().to_string();
+    assert!(text.contains("[4u8 , 1u8 , ..] => Some (instruction_0401 (state))"));
+    assert!(text.contains("fn instruction_0401 (state : & mut crate :: semantics2 :: rust_codegen :: ConcreteX86MachineState64)"));
+    assert!(text.contains("[72u8 , 247u8 , 243u8 , ..] => Some (instruction_48f7f3 (state))"));
+    assert!(text.contains("return Err (crate :: semantics2 :: rust_codegen :: Fault :: DivideError) ;"));
+    assert!(text.contains("_ => None"));
+}
+
+#[test]
+pub fn test_rust_codegen_unsupported() {
+    let bump = Bump::new();
+    let arena = Arena::new(&bump);
+    let addsd = X86Instruction::ADDSD(ADDSD::ADDSD_XMMSD_XMMSD { operand_0: RegXMM::XMM0, operand_1: RegXMM::XMM1 });
+    let steps = apply_instruction(arena, addsd, 4).unwrap();
+    let mut module = RustModule::new(arena, quote::quote!(crate::semantics2));
+    assert!(matches!(module.add_function(&[0xF2, 0x0F, 0x58, 0xC1], steps.as_slice()), Err(RustCodegenError::Unsupported(_))));
+    assert!(!module.tokens().to_string().contains("instruction_"));
+}
+
+#[test]
+pub fn test_rust_codegen_pending_stores() {
+    let state = ConcreteX86MachineState64::zeroed().memory(0x1000, &[0x11, 0x22, 0x33, 0x44]);
+    let stores = [(0x1001, 0xAABB, 16), (0x1002, 0xCC, 8)];
+    assert_eq!(load(&state, &stores, 0x1000, 32), 0x44CCBB11);
+    assert_eq!(load(&state, &[], 0x1000, 32), 0x44332211);
+    let mut stored = state.clone();
+    store_all(&mut stored, &stores);
+    assert_eq!(stored.read_memory(0x1000, 32).to_u64(), 0x44CCBB11);
+}
+
+pub mod instruction_64;
+pub mod differential;
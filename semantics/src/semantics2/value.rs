@@ -1,12 +1,14 @@
+use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 use bitvec::bitvec;
 use bitvec::prelude::BitVec;
 
+/// A bitvector. Bit `i` of `inner` is bit `i` of the value, so index 0 is the least significant bit.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct Value<'arena> {
     inner: BitVec,
     //phantom is for when/if higher perf bitvec is needed
-    // phantom: PhantomData<&'arena ()>,
+    phantom: PhantomData<&'arena ()>,
 }
 
 
@@ -29,8 +31,160 @@ impl<'arena> Value<'arena> {
         }
     }
 
+    pub fn zero(width: usize) -> Self {
+        Self::new(bitvec![0; width])
+    }
+
+    pub fn ones(width: usize) -> Self {
+        Self::new(bitvec![1; width])
+    }
+
+    pub fn from_bool(value: bool) -> Self {
+        Self::new(bitvec![value as usize; 1])
+    }
+
+    /// Bits of `value` above `width` are dropped.
+    pub fn from_u64(value: u64, width: usize) -> Self {
+        Self::from_u128(value as u128, width)
+    }
+
+    pub fn from_u128(value: u128, width: usize) -> Self {
+        let mut res = Self::zero(width);
+        for i in 0..width.min(128) {
+            res.inner.set(i, (value >> i) & 1 == 1);
+        }
+        res
+    }
+
+    /// Little endian, so `words[0]` holds the lowest 64 bits.
+    pub fn from_u64s(words: &[u64], width: usize) -> Self {
+        let mut res = Self::zero(width);
+        for i in 0..width.min(words.len() * 64) {
+            res.inner.set(i, (words[i / 64] >> (i % 64)) & 1 == 1);
+        }
+        res
+    }
+
+    pub fn bit(&self, i: usize) -> bool {
+        self.inner[i]
+    }
+
+    pub fn msb(&self) -> bool {
+        self.bit(self.width() - 1)
+    }
+
     pub fn is_true(&self) -> bool {
         assert_eq!(self.width(), 1);
         self == &Self::one_one()
     }
+
+    pub fn is_zero(&self) -> bool {
+        self.inner.not_any()
+    }
+
+    pub fn to_u64(&self) -> u64 {
+        assert!(self.width() <= 64);
+        self.to_u128() as u64
+    }
+
+    pub fn to_u128(&self) -> u128 {
+        assert!(self.width() <= 128);
+        self.inner.iter().by_vals().enumerate().fold(0, |acc, (i, bit)| acc | ((bit as u128) << i))
+    }
+
+    /// Sign extended to 128 bits.
+    pub fn to_i128(&self) -> i128 {
+        assert!(self.width() <= 128);
+        self.sign_extend(128).to_u128() as i128
+    }
+
+    pub fn to_u64s(&self) -> Vec<u64> {
+        let mut res = vec![0u64; (self.width() + 63) / 64];
+        for (i, bit) in self.inner.iter().by_vals().enumerate() {
+            res[i / 64] |= (bit as u64) << (i % 64);
+        }
+        res
+    }
+
+    /// Bits `low` up to but not including `high_exclusive`.
+    pub fn slice(&self, low: usize, high_exclusive: usize) -> Self {
+        Self::new(self.inner[low..high_exclusive].to_bitvec())
+    }
+
+    pub fn zero_extend(&self, width: usize) -> Self {
+        assert!(width >= self.width());
+        let mut inner = self.inner.clone();
+        inner.resize(width, false);
+        Self::new(inner)
+    }
+
+    pub fn sign_extend(&self, width: usize) -> Self {
+        assert!(width >= self.width());
+        let msb = self.width() > 0 && self.msb();
+        let mut inner = self.inner.clone();
+        inner.resize(width, msb);
+        Self::new(inner)
+    }
+
+    /// `high` ends up in the upper bits.
+    pub fn concat(high: &Self, low: &Self) -> Self {
+        let mut inner = low.inner.clone();
+        inner.extend_from_bitslice(high.inner.as_bitslice());
+        Self::new(inner)
+    }
+
+    /// Replaces the bits starting at `start` with `new_value`.
+    pub fn with_range(&self, start: usize, new_value: &Self) -> Self {
+        let mut inner = self.inner.clone();
+        inner[start..start + new_value.width()].copy_from_bitslice(new_value.inner.as_bitslice());
+        Self::new(inner)
+    }
+
+    pub fn bitwise(&self, other: &Self, op: impl Fn(bool, bool) -> bool) -> Self {
+        assert_eq!(self.width(), other.width());
+        Self::new(self.inner.iter().by_vals().zip(other.inner.iter().by_vals()).map(|(a, b)| op(a, b)).collect())
+    }
+
+    /// Shifting by `width` or more gives zero.
+    pub fn shift_left(&self, amount: usize) -> Self {
+        let amount = amount.min(self.width());
+        let mut inner = bitvec![0; amount];
+        inner.extend_from_bitslice(&self.inner[..self.width() - amount]);
+        Self::new(inner)
+    }
+
+    /// Shifted in bits are `fill`, so `self.msb()` for an arithmetic shift.
+    pub fn shift_right(&self, amount: usize, fill: bool) -> Self {
+        let amount = amount.min(self.width());
+        let mut inner = self.inner[amount..].to_bitvec();
+        inner.resize(self.width(), fill);
+        Self::new(inner)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.inner.count_ones()
+    }
+
+    pub fn leading_zeros(&self) -> usize {
+        self.inner.iter().by_vals().rev().take_while(|bit| !*bit).count()
+    }
+
+    pub fn trailing_zeros(&self) -> usize {
+        self.inner.iter().by_vals().take_while(|bit| !*bit).count()
+    }
+
+    /// Number of set bits in the low byte is even, as in PF.
+    pub fn low_byte_parity_even(&self) -> bool {
+        self.inner.iter().by_vals().take(8).filter(|bit| *bit).count() % 2 == 0
+    }
+}
+
+impl Debug for Value<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}'0x", self.width())?;
+        for word in self.to_u64s().iter().rev() {
+            write!(f, "{word:016x}")?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,593 @@
+use wrapper_common::registers::{Reg16WithRIP, RegFloat};
+use xed_enum::{FADD, FADDP, FCOM, FCOMP, FCOMPP, FDIV, FDIVP, FDIVR, FILD, FISTP, FLD, FLDCW, FMUL, FMULP, FNSTCW, FNSTSW, FST, FSTP, FSUB, FSUBP, FXCH};
+use xed_wrapper::operands::MemoryOperands;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::expression::Expression;
+use crate::semantics2::float::{FloatArithmeticOp, FloatFormat, FloatPredicate};
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+use crate::semantics2::x87_float::X87_INDEFINITE;
+
+/// Status word bits. The six exception flags are bits 0 to 5, in the same order as in MXCSR.
+const INVALID: u16 = 1;
+const STACK_FAULT: u16 = 1 << 6;
+const ERROR_SUMMARY: u16 = 1 << 7;
+const BUSY: u16 = 1 << 15;
+const C0_BIT: usize = 8;
+const C1_BIT: usize = 9;
+const C2_BIT: usize = 10;
+const C3_BIT: usize = 14;
+
+/// FLDCW keeps the masks, precision control and rounding control, and bit 6 always reads as set.
+const CONTROL_WORD_BITS: u16 = 0x1F3F;
+const CONTROL_WORD_FIXED_BITS: u16 = 0x40;
+
+/// Where an x87 instruction reads a value from, or writes one to.
+#[derive(Copy, Clone)]
+pub enum X87Operand {
+    /// ST(i), relative to TOP.
+    Register(usize),
+    /// binary32 or binary64 in memory.
+    Float(FloatFormat, MemoryOperands),
+    /// The 80 bit format in memory, which is copied as is.
+    Extended(MemoryOperands),
+    /// A signed integer of the given width in memory.
+    Int(usize, MemoryOperands),
+}
+
+impl X87Operand {
+    fn memory_operand_width(&self) -> Option<usize> {
+        match self {
+            X87Operand::Register(_) => None,
+            X87Operand::Float(format, _) => Some(format.width()),
+            X87Operand::Extended(_) => Some(80),
+            X87Operand::Int(width, _) => Some(*width),
+        }
+    }
+}
+
+fn st(reg: RegFloat) -> usize {
+    reg as usize
+}
+
+/// An operand read as an 80 bit value.
+struct Source<'arena> {
+    value: &'arena Expression<'arena>,
+    /// One bit, whether the operand is an empty register, in which case `value` is the indefinite value.
+    empty: &'arena Expression<'arena>,
+    /// Status word flags raised by reading the operand, a stack fault or the exceptions of converting it.
+    flags: &'arena Expression<'arena>,
+}
+
+fn indefinite<'arena>(s: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
+    s.concat(s.constant((X87_INDEFINITE >> 64) as u16), s.constant(X87_INDEFINITE as u64))
+}
+
+fn stack_fault<'arena>(s: &SemanticsBuilder<'arena>) -> &'arena Expression<'arena> {
+    s.constant(INVALID | STACK_FAULT)
+}
+
+/// Status word flags of the exceptions `operation` raises.
+fn exception_flags<'arena>(s: &SemanticsBuilder<'arena>, operation: &'arena Expression<'arena>) -> &'arena Expression<'arena> {
+    s.zext_to(s.float_exception_flags(operation), 16)
+}
+
+fn read_source<'arena>(s: &mut SemanticsBuilder<'arena>, operand: X87Operand) -> Source<'arena> {
+    if let Some(width) = operand.memory_operand_width() {
+        s.set_memory_operand_width(width);
+    }
+    match operand {
+        X87Operand::Register(st) => {
+            let empty = s.x87_register_empty(st);
+            Source {
+                value: s.select(empty, indefinite(s), s.x87_register(st)),
+                empty,
+                flags: s.select(empty, stack_fault(s), s.constant(0u16)),
+            }
+        }
+        X87Operand::Float(format, memory) => {
+            let value = s.x87_from_float(format, memory.read(s));
+            Source { value, empty: s.constant(false), flags: exception_flags(s, value) }
+        }
+        X87Operand::Extended(memory) => {
+            Source { value: memory.read(s), empty: s.constant(false), flags: s.constant(0u16) }
+        }
+        X87Operand::Int(_, memory) => {
+            Source { value: s.x87_from_int(memory.read(s)), empty: s.constant(false), flags: s.constant(0u16) }
+        }
+    }
+}
+
+/// Raises #MF if an earlier instruction left an unmasked exception pending, which every x87 instruction but the
+/// FN ones checks before doing anything else.
+fn check_pending_exceptions(s: &mut SemanticsBuilder) {
+    let pending = s.extract(s.x87_status(), 7, 8);
+    s.emit_conditional(pending, |s| s.floating_point_error(), |_| {});
+}
+
+/// Writes the status word with `flags` added to it, setting ES and B if any of them are unmasked, and with C1 and,
+/// if given, C0, C2 and C3 replaced, then runs `commit` to write the results. Unmasked invalid, denormal and divide
+/// by zero exceptions are raised before the result is computed, so when one of those is raised only the flags
+/// are recorded and `commit` doesn't run. Unmasked overflow and underflow are treated as if masked, without the
+/// exponent adjustment the hardware makes before delivering them.
+fn finish<'arena>(
+    s: &mut SemanticsBuilder<'arena>,
+    flags: &'arena Expression<'arena>,
+    c1: &'arena Expression<'arena>,
+    conditions: Option<[&'arena Expression<'arena>; 3]>,
+    commit: impl FnOnce(&mut SemanticsBuilder<'arena>),
+) {
+    let status = s.x87_status();
+    let unmasked = s.bitand(flags, s.not(s.x87_control()));
+    let any_unmasked = s.not(s.equal(s.bitand(unmasked, s.constant(0x3Fu16)), s.constant(0u16)));
+    let summary = s.select(any_unmasked, s.constant(ERROR_SUMMARY | BUSY), s.constant(0u16));
+    let raised = s.bitor(s.bitor(status, flags), summary);
+    let suppressed = s.not(s.equal(s.bitand(unmasked, s.constant(0x7u16)), s.constant(0u16)));
+    let mut completed = s.change(raised, C1_BIT, C1_BIT + 1, c1);
+    if let Some([c0, c2, c3]) = conditions {
+        completed = s.change(completed, C0_BIT, C0_BIT + 1, c0);
+        completed = s.change(completed, C2_BIT, C2_BIT + 1, c2);
+        completed = s.change(completed, C3_BIT, C3_BIT + 1, c3);
+    }
+    s.set_x87_status(s.select(suppressed, raised, completed));
+    s.emit_conditional(suppressed, |_| {}, commit);
+}
+
+/// FLD and FILD. Pushing onto a full stack overflows, which pushes the indefinite value.
+pub fn load_generic(arena: Arena, source: X87Operand) -> Vec<InstructionSemanticsStep> {
+    // TOP := TOP - 1;
+    // ST(0) := ConvertToDoubleExtendedPrecisionFP(SRC);
+    let mut s = SemanticsBuilder::new(arena);
+    check_pending_exceptions(&mut s);
+    let source = read_source(&mut s, source);
+    let overflow = s.not(s.x87_register_empty(7));
+    let value = s.select(overflow, indefinite(&s), source.value);
+    let flags = s.select(overflow, stack_fault(&s), source.flags);
+    finish(&mut s, flags, overflow, None, |s| {
+        s.x87_push();
+        s.set_x87_register(0, value);
+    });
+    s.finalize()
+}
+
+/// FST, FSTP and FISTP, storing ST(0) to `destination` and popping if `pop`. An empty ST(0) stores whatever
+/// converting the indefinite value gives.
+pub fn store_generic(arena: Arena, destination: X87Operand, pop: bool) -> Vec<InstructionSemanticsStep> {
+    // DEST := ST(0);
+    // IF pop THEN PopRegisterStack; FI;
+    let mut s = SemanticsBuilder::new(arena);
+    check_pending_exceptions(&mut s);
+    if let Some(width) = destination.memory_operand_width() {
+        s.set_memory_operand_width(width);
+    }
+    let source = read_source(&mut s, X87Operand::Register(0));
+    let (value, flags, c1) = match destination {
+        X87Operand::Register(_) | X87Operand::Extended(_) => (source.value, source.flags, s.constant(false)),
+        X87Operand::Float(format, _) => {
+            let value = s.x87_to_float(format, source.value);
+            (value, s.select(source.empty, source.flags, exception_flags(&s, value)), s.select(source.empty, s.constant(false), s.x87_rounded_up(value)))
+        }
+        X87Operand::Int(width, _) => {
+            let value = s.x87_to_int(source.value, width);
+            (value, s.select(source.empty, source.flags, exception_flags(&s, value)), s.select(source.empty, s.constant(false), s.x87_rounded_up(value)))
+        }
+    };
+    finish(&mut s, flags, c1, None, |s| {
+        match destination {
+            X87Operand::Register(st) => s.set_x87_register(st, value),
+            X87Operand::Float(_, memory) | X87Operand::Extended(memory) | X87Operand::Int(_, memory) => memory.write(s, value),
+        }
+        if pop {
+            s.x87_pop();
+        }
+    });
+    s.finalize()
+}
+
+/// FADD, FSUB, FMUL, FDIV and the reversed and popping forms: ST(`destination`) := `left` op `right`. An empty
+/// register operand makes the result the indefinite value.
+pub fn arithmetic_generic(arena: Arena, op: FloatArithmeticOp, destination: usize, left: X87Operand, right: X87Operand, pop: bool) -> Vec<InstructionSemanticsStep> {
+    // DEST := SRC1 op SRC2;
+    // IF pop THEN PopRegisterStack; FI;
+    let mut s = SemanticsBuilder::new(arena);
+    check_pending_exceptions(&mut s);
+    let left = read_source(&mut s, left);
+    let right = read_source(&mut s, right);
+    let underflow = s.bitor(left.empty, right.empty);
+    let operation = s.x87_arithmetic(op, left.value, right.value);
+    let value = s.select(underflow, indefinite(&s), operation);
+    let raised = s.bitor(s.bitor(left.flags, right.flags), exception_flags(&s, operation));
+    let flags = s.select(underflow, stack_fault(&s), raised);
+    let c1 = s.select(underflow, s.constant(false), s.x87_rounded_up(operation));
+    finish(&mut s, flags, c1, None, |s| {
+        s.set_x87_register(destination, value);
+        if pop {
+            s.x87_pop();
+        }
+    });
+    s.finalize()
+}
+
+/// FCOM, FCOMP and FCOMPP, comparing ST(0) with `right` and popping `pops` times. Unordered operands, which
+/// includes empty registers, set all of C0, C2 and C3, and raise invalid even for QNaNs.
+pub fn compare_generic(arena: Arena, right: X87Operand, pops: usize) -> Vec<InstructionSemanticsStep> {
+    // CASE (relation of operands) OF
+    //     ST > SRC: C3, C2, C0 := 000;
+    //     ST < SRC: C3, C2, C0 := 001;
+    //     ST = SRC: C3, C2, C0 := 100;
+    //     Unordered: C3, C2, C0 := 111;
+    // ESAC;
+    let mut s = SemanticsBuilder::new(arena);
+    check_pending_exceptions(&mut s);
+    let left = read_source(&mut s, X87Operand::Register(0));
+    let right = read_source(&mut s, right);
+    let predicate = |less, equal| FloatPredicate { less, equal, greater: false, unordered: true, signaling: true };
+    let c0 = s.x87_compare(predicate(true, false), left.value, right.value);
+    let c2 = s.x87_compare(predicate(false, false), left.value, right.value);
+    let c3 = s.x87_compare(predicate(false, true), left.value, right.value);
+    let underflow = s.bitor(left.empty, right.empty);
+    let raised = s.bitor(s.bitor(left.flags, right.flags), exception_flags(&s, c2));
+    let flags = s.select(underflow, stack_fault(&s), raised);
+    let c1 = s.constant(false);
+    finish(&mut s, flags, c1, Some([c0, c2, c3]), |s| {
+        for _ in 0..pops {
+            s.x87_pop();
+        }
+    });
+    s.finalize()
+}
+
+/// FXCH. An empty register is replaced with the indefinite value before the exchange.
+pub fn fxch_generic(arena: Arena, other: usize) -> Vec<InstructionSemanticsStep> {
+    // temp := ST(0);
+    // ST(0) := SRC;
+    // SRC := temp;
+    let mut s = SemanticsBuilder::new(arena);
+    check_pending_exceptions(&mut s);
+    let top = read_source(&mut s, X87Operand::Register(0));
+    let other_value = read_source(&mut s, X87Operand::Register(other));
+    let flags = s.bitor(top.flags, other_value.flags);
+    let c1 = s.constant(false);
+    finish(&mut s, flags, c1, None, |s| {
+        s.set_x87_register(0, other_value.value);
+        s.set_x87_register(other, top.value);
+    });
+    s.finalize()
+}
+
+pub fn fnstsw_generic<'arena, D1: Writeable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST := FPUStatusWord;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(16);
+    let status = s.x87_status();
+    writeable1.write(&mut s, status);
+    s.finalize()
+}
+
+pub fn fnstcw_generic<'arena, D1: Writeable<'arena>>(
+    arena: Arena<'arena>,
+    writeable1: D1,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // DEST := FPUControlWord;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(16);
+    let control = s.x87_control();
+    writeable1.write(&mut s, control);
+    s.finalize()
+}
+
+/// FLDCW. ES and B are recomputed against the new masks, so unmasking a flag which is already set makes the next
+/// waiting instruction raise #MF, and masking every set flag clears them.
+pub fn fldcw_generic<'arena, S1: Readable<'arena>>(
+    arena: Arena<'arena>,
+    readable1: S1,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    // FPUControlWord := SRC;
+    let mut s = SemanticsBuilder::new(arena);
+    check_pending_exceptions(&mut s);
+    s.set_memory_operand_width(16);
+    let value = readable1.read(&s);
+    let control = s.bitor(s.bitand(value, s.constant(CONTROL_WORD_BITS)), s.constant(CONTROL_WORD_FIXED_BITS));
+    let status = s.x87_status();
+    let unmasked = s.bitand(s.bitand(status, s.not(control)), s.constant(0x3Fu16));
+    let summary = s.not(s.equal(unmasked, s.constant(0u16)));
+    let status = s.change(s.change(status, 7, 8, summary), 15, 16, summary);
+    s.set_x87_control(control);
+    s.set_x87_status(status);
+    s.finalize()
+}
+
+pub fn apply_iform_fld(arena: Arena, instr: FLD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FLD::FLD_ST0_MEMMEM32REAL { operand_0 } => {
+            load_generic(arena, X87Operand::Float(FloatFormat::Single, operand_0))
+        }
+        FLD::FLD_ST0_X87 { operand_0 } => {
+            load_generic(arena, X87Operand::Register(st(operand_0)))
+        }
+        FLD::FLD_ST0_MEMMEM80REAL { operand_0 } => {
+            load_generic(arena, X87Operand::Extended(operand_0))
+        }
+        FLD::FLD_ST0_MEMM64REAL { operand_0 } => {
+            load_generic(arena, X87Operand::Float(FloatFormat::Double, operand_0))
+        }
+    }
+}
+
+pub fn apply_iform_fild(arena: Arena, instr: FILD) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FILD::FILD_ST0_MEMMEM32INT { operand_0 } => {
+            load_generic(arena, X87Operand::Int(32, operand_0))
+        }
+        FILD::FILD_ST0_MEMMEM16INT { operand_0 } => {
+            load_generic(arena, X87Operand::Int(16, operand_0))
+        }
+        FILD::FILD_ST0_MEMM64INT { operand_0 } => {
+            load_generic(arena, X87Operand::Int(64, operand_0))
+        }
+    }
+}
+
+pub fn apply_iform_fst(arena: Arena, instr: FST) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FST::FST_MEMMEM32REAL_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Float(FloatFormat::Single, operand_0), false)
+        }
+        FST::FST_MEMM64REAL_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Float(FloatFormat::Double, operand_0), false)
+        }
+        FST::FST_X87_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Register(st(operand_0)), false)
+        }
+    }
+}
+
+pub fn apply_iform_fstp(arena: Arena, instr: FSTP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FSTP::FSTP_MEMMEM32REAL_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Float(FloatFormat::Single, operand_0), true)
+        }
+        FSTP::FSTP_MEMMEM80REAL_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Extended(operand_0), true)
+        }
+        FSTP::FSTP_MEMM64REAL_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Float(FloatFormat::Double, operand_0), true)
+        }
+        FSTP::FSTP_X87_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Register(st(operand_0)), true)
+        }
+        FSTP::FSTP_X87_ST0_DFD0 { operand_0 } => {
+            store_generic(arena, X87Operand::Register(st(operand_0)), true)
+        }
+        FSTP::FSTP_X87_ST0_DFD1 { operand_0 } => {
+            store_generic(arena, X87Operand::Register(st(operand_0)), true)
+        }
+    }
+}
+
+pub fn apply_iform_fistp(arena: Arena, instr: FISTP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FISTP::FISTP_MEMMEM32INT_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Int(32, operand_0), true)
+        }
+        FISTP::FISTP_MEMMEM16INT_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Int(16, operand_0), true)
+        }
+        FISTP::FISTP_MEMM64INT_ST0 { operand_0 } => {
+            store_generic(arena, X87Operand::Int(64, operand_0), true)
+        }
+    }
+}
+
+pub fn apply_iform_fadd(arena: Arena, instr: FADD) -> Vec<InstructionSemanticsStep> {
+    let op = FloatArithmeticOp::Add;
+    match instr {
+        FADD::FADD_MEMMEM32REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Float(FloatFormat::Single, operand_0), false)
+        }
+        FADD::FADD_ST0_X87 { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Register(st(operand_0)), false)
+        }
+        FADD::FADD_MEMM64REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Float(FloatFormat::Double, operand_0), false)
+        }
+        FADD::FADD_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, op, st(operand_0), X87Operand::Register(st(operand_0)), X87Operand::Register(0), false)
+        }
+    }
+}
+
+pub fn apply_iform_faddp(arena: Arena, instr: FADDP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FADDP::FADDP_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, FloatArithmeticOp::Add, st(operand_0), X87Operand::Register(st(operand_0)), X87Operand::Register(0), true)
+        }
+    }
+}
+
+pub fn apply_iform_fsub(arena: Arena, instr: FSUB) -> Vec<InstructionSemanticsStep> {
+    let op = FloatArithmeticOp::Sub;
+    match instr {
+        FSUB::FSUB_ST0_MEMMEM32REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Float(FloatFormat::Single, operand_0), false)
+        }
+        FSUB::FSUB_ST0_X87 { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Register(st(operand_0)), false)
+        }
+        FSUB::FSUB_ST0_MEMM64REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Float(FloatFormat::Double, operand_0), false)
+        }
+        FSUB::FSUB_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, op, st(operand_0), X87Operand::Register(st(operand_0)), X87Operand::Register(0), false)
+        }
+    }
+}
+
+pub fn apply_iform_fsubp(arena: Arena, instr: FSUBP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FSUBP::FSUBP_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, FloatArithmeticOp::Sub, st(operand_0), X87Operand::Register(st(operand_0)), X87Operand::Register(0), true)
+        }
+    }
+}
+
+pub fn apply_iform_fmul(arena: Arena, instr: FMUL) -> Vec<InstructionSemanticsStep> {
+    let op = FloatArithmeticOp::Mul;
+    match instr {
+        FMUL::FMUL_ST0_MEMMEM32REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Float(FloatFormat::Single, operand_0), false)
+        }
+        FMUL::FMUL_ST0_X87 { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Register(st(operand_0)), false)
+        }
+        FMUL::FMUL_ST0_MEMM64REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Float(FloatFormat::Double, operand_0), false)
+        }
+        FMUL::FMUL_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, op, st(operand_0), X87Operand::Register(st(operand_0)), X87Operand::Register(0), false)
+        }
+    }
+}
+
+pub fn apply_iform_fmulp(arena: Arena, instr: FMULP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FMULP::FMULP_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, FloatArithmeticOp::Mul, st(operand_0), X87Operand::Register(st(operand_0)), X87Operand::Register(0), true)
+        }
+    }
+}
+
+pub fn apply_iform_fdiv(arena: Arena, instr: FDIV) -> Vec<InstructionSemanticsStep> {
+    let op = FloatArithmeticOp::Div;
+    match instr {
+        FDIV::FDIV_ST0_MEMMEM32REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Float(FloatFormat::Single, operand_0), false)
+        }
+        FDIV::FDIV_ST0_X87 { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Register(st(operand_0)), false)
+        }
+        FDIV::FDIV_ST0_MEMM64REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(0), X87Operand::Float(FloatFormat::Double, operand_0), false)
+        }
+        FDIV::FDIV_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, op, st(operand_0), X87Operand::Register(st(operand_0)), X87Operand::Register(0), false)
+        }
+    }
+}
+
+pub fn apply_iform_fdivp(arena: Arena, instr: FDIVP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FDIVP::FDIVP_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, FloatArithmeticOp::Div, st(operand_0), X87Operand::Register(st(operand_0)), X87Operand::Register(0), true)
+        }
+    }
+}
+
+pub fn apply_iform_fdivr(arena: Arena, instr: FDIVR) -> Vec<InstructionSemanticsStep> {
+    let op = FloatArithmeticOp::Div;
+    match instr {
+        FDIVR::FDIVR_ST0_MEMMEM32REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Float(FloatFormat::Single, operand_0), X87Operand::Register(0), false)
+        }
+        FDIVR::FDIVR_ST0_X87 { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Register(st(operand_0)), X87Operand::Register(0), false)
+        }
+        FDIVR::FDIVR_ST0_MEMM64REAL { operand_0 } => {
+            arithmetic_generic(arena, op, 0, X87Operand::Float(FloatFormat::Double, operand_0), X87Operand::Register(0), false)
+        }
+        FDIVR::FDIVR_X87_ST0 { operand_0 } => {
+            arithmetic_generic(arena, op, st(operand_0), X87Operand::Register(0), X87Operand::Register(st(operand_0)), false)
+        }
+    }
+}
+
+pub fn apply_iform_fcom(arena: Arena, instr: FCOM) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FCOM::FCOM_ST0_MEMMEM32REAL { operand_0 } => {
+            compare_generic(arena, X87Operand::Float(FloatFormat::Single, operand_0), 0)
+        }
+        FCOM::FCOM_ST0_MEMM64REAL { operand_0 } => {
+            compare_generic(arena, X87Operand::Float(FloatFormat::Double, operand_0), 0)
+        }
+        FCOM::FCOM_ST0_X87 { operand_0 } => {
+            compare_generic(arena, X87Operand::Register(st(operand_0)), 0)
+        }
+        FCOM::FCOM_ST0_X87_DCD0 { operand_0 } => {
+            compare_generic(arena, X87Operand::Register(st(operand_0)), 0)
+        }
+    }
+}
+
+pub fn apply_iform_fcomp(arena: Arena, instr: FCOMP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FCOMP::FCOMP_ST0_MEMMEM32REAL { operand_0 } => {
+            compare_generic(arena, X87Operand::Float(FloatFormat::Single, operand_0), 1)
+        }
+        FCOMP::FCOMP_ST0_X87 { operand_0 } => {
+            compare_generic(arena, X87Operand::Register(st(operand_0)), 1)
+        }
+        FCOMP::FCOMP_ST0_X87_DCD1 { operand_0 } => {
+            compare_generic(arena, X87Operand::Register(st(operand_0)), 1)
+        }
+        FCOMP::FCOMP_ST0_X87_DED0 { operand_0 } => {
+            compare_generic(arena, X87Operand::Register(st(operand_0)), 1)
+        }
+        FCOMP::FCOMP_ST0_MEMM64REAL { operand_0 } => {
+            compare_generic(arena, X87Operand::Float(FloatFormat::Double, operand_0), 1)
+        }
+    }
+}
+
+pub fn apply_iform_fcompp(arena: Arena, instr: FCOMPP) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FCOMPP::FCOMPP {} => {
+            compare_generic(arena, X87Operand::Register(1), 2)
+        }
+    }
+}
+
+pub fn apply_iform_fxch(arena: Arena, instr: FXCH) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FXCH::FXCH_ST0_X87 { operand_0 } => {
+            fxch_generic(arena, st(operand_0))
+        }
+        FXCH::FXCH_ST0_X87_DFC1 { operand_0 } => {
+            fxch_generic(arena, st(operand_0))
+        }
+        FXCH::FXCH_ST0_X87_DDC1 { operand_0 } => {
+            fxch_generic(arena, st(operand_0))
+        }
+    }
+}
+
+pub fn apply_iform_fnstsw(arena: Arena, instr: FNSTSW) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FNSTSW::FNSTSW_MEMMEM16 { operand_0 } => {
+            fnstsw_generic(arena, operand_0)
+        }
+        FNSTSW::FNSTSW_AX {} => {
+            fnstsw_generic(arena, Reg16WithRIP::AX)
+        }
+    }
+}
+
+pub fn apply_iform_fldcw(arena: Arena, instr: FLDCW) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FLDCW::FLDCW_MEMMEM16 { operand_0 } => {
+            fldcw_generic(arena, operand_0)
+        }
+    }
+}
+
+pub fn apply_iform_fnstcw(arena: Arena, instr: FNSTCW) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        FNSTCW::FNSTCW_MEMMEM16 { operand_0 } => {
+            fnstcw_generic(arena, operand_0)
+        }
+    }
+}
@@ -0,0 +1,489 @@
+use std::cmp::Ordering;
+
+use crate::semantics2::float::{add_unpacked, exact_zero_sum_sign, mul_unpacked, round_at, round_pack, Class, FloatArithmeticOp, FloatExceptions, FloatFormat, FloatPredicate, Mxcsr, Operand, RoundingMode, Unpacked};
+
+// The 80 bit double extended format of the x87 registers. Unlike binary32 and binary64 the integer bit of the
+// significand is explicit, bit 63.
+const EXPONENT_BIAS: i32 = 16383;
+const MAX_BIASED_EXPONENT: u128 = 0x7FFF;
+const MIN_EXPONENT: i32 = 1 - EXPONENT_BIAS;
+const MAX_EXPONENT: i32 = EXPONENT_BIAS;
+const INTEGER_BIT: u64 = 1 << 63;
+const QUIET_BIT: u64 = 1 << 62;
+
+/// The QNaN invalid operations return, "real indefinite".
+pub const X87_INDEFINITE: u128 = 0xFFFF_C000_0000_0000_0000;
+
+fn pack(sign: bool, biased_exponent: u128, significand: u64) -> u128 {
+    ((sign as u128) << 79) | (biased_exponent << 64) | significand as u128
+}
+
+fn zero(sign: bool) -> u128 {
+    pack(sign, 0, 0)
+}
+
+fn infinity(sign: bool) -> u128 {
+    pack(sign, MAX_BIASED_EXPONENT, INTEGER_BIT)
+}
+
+/// The x87 FPU control word. Unlike MXCSR it has no flags, those live in the status word.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct X87ControlWord(pub u16);
+
+impl X87ControlWord {
+    /// Value after FNINIT: round to nearest, 64 bit precision and every exception masked.
+    pub const DEFAULT: X87ControlWord = X87ControlWord(0x037F);
+
+    /// RC, which is encoded the same way as in MXCSR.
+    pub fn rounding_mode(self) -> RoundingMode {
+        match (self.0 >> 10) & 3 {
+            0 => RoundingMode::NearestEven,
+            1 => RoundingMode::Down,
+            2 => RoundingMode::Up,
+            _ => RoundingMode::TowardZero,
+        }
+    }
+
+    /// PC, the significand bits add, subtract, multiply and divide round to. The exponent range stays that of
+    /// the extended format. The reserved encoding is treated as 64 bits.
+    pub fn precision(self) -> i32 {
+        match (self.0 >> 8) & 3 {
+            0 => 24,
+            2 => 53,
+            _ => 64,
+        }
+    }
+
+    pub fn exception_masks(self) -> FloatExceptions {
+        FloatExceptions::from_bits(self.0 as u8)
+    }
+
+    fn underflow_masked(self) -> bool {
+        self.exception_masks().contains(FloatExceptions::UNDERFLOW)
+    }
+
+    /// MXCSR with the same rounding and masks, for rounding to binary32 and binary64.
+    fn as_mxcsr(self) -> Mxcsr {
+        Mxcsr(((self.0 as u32 & 0x3F) << 7) | (((self.0 as u32 >> 10) & 3) << 13))
+    }
+}
+
+/// An x87 register or 80 bit memory operand. `class` is `None` for the encodings the 387 onwards reject as
+/// invalid: pseudo NaNs, pseudo infinities and unnormals.
+#[derive(Copy, Clone, Debug)]
+struct ExtendedOperand {
+    bits: u128,
+    class: Option<Class>,
+    /// Denormals, and pseudo denormals which have the integer bit set but are otherwise read the same way.
+    denormal: bool,
+}
+
+impl ExtendedOperand {
+    fn new(bits: u128) -> Self {
+        let bits = bits & ((1 << 80) - 1);
+        let sign = bits >> 79 == 1;
+        let biased_exponent = (bits >> 64) & MAX_BIASED_EXPONENT;
+        let significand = bits as u64;
+        let integer = significand & INTEGER_BIT != 0;
+        let (class, denormal) = if biased_exponent == MAX_BIASED_EXPONENT {
+            if !integer {
+                (None, false)
+            } else if significand == INTEGER_BIT {
+                (Some(Class::Infinity { sign }), false)
+            } else {
+                (Some(Class::NaN { quiet: significand & QUIET_BIT != 0 }), false)
+            }
+        } else if biased_exponent == 0 {
+            if significand == 0 {
+                (Some(Class::Zero { sign }), false)
+            } else {
+                (Some(Class::Finite(Unpacked { sign, exp: MIN_EXPONENT - 63, sig: significand as u128 })), true)
+            }
+        } else if !integer {
+            (None, false)
+        } else {
+            let exp = biased_exponent as i32 - EXPONENT_BIAS - 63;
+            (Some(Class::Finite(Unpacked { sign, exp, sig: significand as u128 })), false)
+        };
+        ExtendedOperand { bits, class, denormal }
+    }
+
+    fn is_nan(&self) -> bool {
+        matches!(self.class, Some(Class::NaN { .. }))
+    }
+
+    fn is_signaling_nan(&self) -> bool {
+        matches!(self.class, Some(Class::NaN { quiet: false }))
+    }
+
+    fn sign(&self) -> bool {
+        self.bits >> 79 == 1
+    }
+
+    fn quietened(&self) -> u128 {
+        self.bits | QUIET_BIT as u128
+    }
+}
+
+/// NaN operands propagate quietened. Of two NaNs a QNaN wins over an SNaN, and otherwise the one with the larger
+/// significand, and then the positive one. A signaling NaN raises invalid.
+fn propagate_nan(left: &ExtendedOperand, right: &ExtendedOperand, exceptions: &mut FloatExceptions) -> Option<u128> {
+    if left.is_signaling_nan() || right.is_signaling_nan() {
+        exceptions.raise(FloatExceptions::INVALID);
+    }
+    match (left.is_nan(), right.is_nan()) {
+        (false, false) => None,
+        (true, false) => Some(left.quietened()),
+        (false, true) => Some(right.quietened()),
+        (true, true) => {
+            if left.is_signaling_nan() != right.is_signaling_nan() {
+                let quiet = if left.is_signaling_nan() { right } else { left };
+                return Some(quiet.quietened());
+            }
+            let larger = match (left.quietened() as u64).cmp(&(right.quietened() as u64)) {
+                Ordering::Less => right,
+                Ordering::Greater => left,
+                Ordering::Equal => if left.sign() { right } else { left },
+            };
+            Some(larger.quietened())
+        }
+    }
+}
+
+/// Denormal operands only count when the operation wasn't already invalid or a division by zero.
+fn raise_denormal(operands: &[ExtendedOperand], exceptions: &mut FloatExceptions) {
+    let higher_priority = exceptions.contains(FloatExceptions::INVALID) || exceptions.contains(FloatExceptions::DIVIDE_BY_ZERO);
+    if !higher_priority && operands.iter().any(|operand| operand.denormal) {
+        exceptions.raise(FloatExceptions::DENORMAL);
+    }
+}
+
+/// Orders the magnitudes of two finite nonzero values.
+fn compare_magnitude(left: Unpacked, right: Unpacked) -> Ordering {
+    let left = left.normalized(63);
+    let right = right.normalized(63);
+    left.exp.cmp(&right.exp).then(left.sig.cmp(&right.sig))
+}
+
+/// Rounds a nonzero `sig * 2^exp` to `precision` significand bits and the extended exponent range, the way the
+/// control word says to. Also returns whether the magnitude was rounded up, which is what C1 reports. Tininess is
+/// detected after rounding. Unmasked overflow and underflow deliver the same result as masked ones rather than
+/// the exponent adjusted one real hardware does.
+fn round_pack_extended(value: Unpacked, precision: i32, control: X87ControlWord, exceptions: &mut FloatExceptions) -> (u128, bool) {
+    let Unpacked { sign, exp, sig } = value;
+    let mode = control.rounding_mode();
+    let round = |lsb_exp: i32| {
+        let (rounded, inexact) = round_at(sign, exp, sig, lsb_exp, mode);
+        let (truncated, _) = round_at(sign, exp, sig, lsb_exp, RoundingMode::TowardZero);
+        (rounded, inexact, rounded != truncated)
+    };
+    // rounded as if the exponent range were unbounded
+    let top = exp + value.msb();
+    let mut lsb_exp = top - (precision - 1);
+    let (mut rounded, inexact, rounded_up) = round(lsb_exp);
+    if rounded == 1 << precision {
+        rounded >>= 1;
+        lsb_exp += 1;
+    }
+    let rounded_top = lsb_exp + precision - 1;
+    if rounded_top > MAX_EXPONENT {
+        exceptions.raise(FloatExceptions::OVERFLOW.union(FloatExceptions::PRECISION));
+        let to_infinity = match mode {
+            RoundingMode::NearestEven => true,
+            RoundingMode::Down => sign,
+            RoundingMode::Up => !sign,
+            RoundingMode::TowardZero => false,
+        };
+        return if to_infinity {
+            (infinity(sign), true)
+        } else {
+            let max_significand = u64::MAX << (64 - precision);
+            (pack(sign, MAX_BIASED_EXPONENT - 1, max_significand), false)
+        };
+    }
+    if rounded_top < MIN_EXPONENT {
+        // denormals round at the same bit whatever their leading one
+        let (denormal, inexact, rounded_up) = round(MIN_EXPONENT - (precision - 1));
+        if inexact {
+            exceptions.raise(FloatExceptions::UNDERFLOW.union(FloatExceptions::PRECISION));
+        } else if !control.underflow_masked() {
+            exceptions.raise(FloatExceptions::UNDERFLOW);
+        }
+        let significand = (denormal << (64 - precision)) as u64;
+        // the integer bit is explicit, so rounding up to the smallest normal needs the exponent setting
+        let biased_exponent = (significand & INTEGER_BIT != 0) as u128;
+        return (pack(sign, biased_exponent, significand), rounded_up);
+    }
+    if inexact {
+        exceptions.raise(FloatExceptions::PRECISION);
+    }
+    let biased_exponent = (rounded_top + EXPONENT_BIAS) as u128;
+    (pack(sign, biased_exponent, (rounded << (64 - precision)) as u64), rounded_up)
+}
+
+fn add(left: ExtendedOperand, right: ExtendedOperand, negate_right: bool, control: X87ControlWord, exceptions: &mut FloatExceptions) -> (u128, bool) {
+    let mode = control.rounding_mode();
+    let precision = control.precision();
+    let negate = |sign: bool| sign != negate_right;
+    match (left.class.unwrap(), right.class.unwrap()) {
+        (Class::Infinity { sign: left_sign }, Class::Infinity { sign: right_sign }) => {
+            if left_sign != negate(right_sign) {
+                exceptions.raise(FloatExceptions::INVALID);
+                (X87_INDEFINITE, false)
+            } else {
+                (infinity(left_sign), false)
+            }
+        }
+        (Class::Infinity { sign }, _) => (infinity(sign), false),
+        (_, Class::Infinity { sign }) => (infinity(negate(sign)), false),
+        (Class::Zero { sign: left_sign }, Class::Zero { sign: right_sign }) => {
+            (zero(exact_zero_sum_sign(left_sign, negate(right_sign), mode)), false)
+        }
+        (Class::Zero { .. }, Class::Finite(right)) => {
+            round_pack_extended(Unpacked { sign: negate(right.sign), ..right }, precision, control, exceptions)
+        }
+        (Class::Finite(left), Class::Zero { .. }) => round_pack_extended(left, precision, control, exceptions),
+        (Class::Finite(left), Class::Finite(right)) => {
+            match add_unpacked(left, Unpacked { sign: negate(right.sign), ..right }) {
+                Some(sum) => round_pack_extended(sum, precision, control, exceptions),
+                None => (zero(mode == RoundingMode::Down), false),
+            }
+        }
+        (Class::NaN { .. }, _) | (_, Class::NaN { .. }) => unreachable!(),
+    }
+}
+
+fn mul(left: ExtendedOperand, right: ExtendedOperand, control: X87ControlWord, exceptions: &mut FloatExceptions) -> (u128, bool) {
+    let sign = left.sign() != right.sign();
+    match (left.class.unwrap(), right.class.unwrap()) {
+        (Class::Infinity { .. }, Class::Zero { .. }) | (Class::Zero { .. }, Class::Infinity { .. }) => {
+            exceptions.raise(FloatExceptions::INVALID);
+            (X87_INDEFINITE, false)
+        }
+        (Class::Infinity { .. }, _) | (_, Class::Infinity { .. }) => (infinity(sign), false),
+        (Class::Zero { .. }, _) | (_, Class::Zero { .. }) => (zero(sign), false),
+        (Class::Finite(left), Class::Finite(right)) => {
+            round_pack_extended(mul_unpacked(left, right), control.precision(), control, exceptions)
+        }
+        (Class::NaN { .. }, _) | (_, Class::NaN { .. }) => unreachable!(),
+    }
+}
+
+fn div(left: ExtendedOperand, right: ExtendedOperand, control: X87ControlWord, exceptions: &mut FloatExceptions) -> (u128, bool) {
+    let sign = left.sign() != right.sign();
+    match (left.class.unwrap(), right.class.unwrap()) {
+        (Class::Infinity { .. }, Class::Infinity { .. }) | (Class::Zero { .. }, Class::Zero { .. }) => {
+            exceptions.raise(FloatExceptions::INVALID);
+            (X87_INDEFINITE, false)
+        }
+        (Class::Infinity { .. }, _) => (infinity(sign), false),
+        (_, Class::Zero { .. }) => {
+            exceptions.raise(FloatExceptions::DIVIDE_BY_ZERO);
+            (infinity(sign), false)
+        }
+        (_, Class::Infinity { .. }) | (Class::Zero { .. }, _) => (zero(sign), false),
+        (Class::Finite(left), Class::Finite(right)) => {
+            // 63 or 64 quotient bits, and then 8 more from the remainder so there is a guard bit at 64 bit
+            // precision
+            let left = left.normalized(126);
+            let right = right.normalized(63);
+            let quotient = left.sig / right.sig;
+            let remainder = left.sig % right.sig;
+            let quotient = (quotient << 8) | ((remainder << 8) / right.sig);
+            let remainder = (remainder << 8) % right.sig;
+            let sig = quotient | (remainder != 0) as u128;
+            let unpacked = Unpacked { sign, exp: left.exp - right.exp - 8, sig };
+            round_pack_extended(unpacked, control.precision(), control, exceptions)
+        }
+        (Class::NaN { .. }, _) | (_, Class::NaN { .. }) => unreachable!(),
+    }
+}
+
+/// FADD, FSUB, FMUL and FDIV, rounded to the precision control of `control`. Also returns whether the result was
+/// rounded up, for C1. Min and Max have no x87 equivalent.
+pub fn x87_arithmetic(op: FloatArithmeticOp, left: u128, right: u128, control: X87ControlWord) -> (u128, FloatExceptions, bool) {
+    let mut exceptions = FloatExceptions::NONE;
+    let left = ExtendedOperand::new(left);
+    let right = ExtendedOperand::new(right);
+    if left.class.is_none() || right.class.is_none() {
+        exceptions.raise(FloatExceptions::INVALID);
+        return (X87_INDEFINITE, exceptions, false);
+    }
+    if let Some(nan) = propagate_nan(&left, &right, &mut exceptions) {
+        return (nan, exceptions, false);
+    }
+    let (res, rounded_up) = match op {
+        FloatArithmeticOp::Add => add(left, right, false, control, &mut exceptions),
+        FloatArithmeticOp::Sub => add(left, right, true, control, &mut exceptions),
+        FloatArithmeticOp::Mul => mul(left, right, control, &mut exceptions),
+        FloatArithmeticOp::Div => div(left, right, control, &mut exceptions),
+        FloatArithmeticOp::Min | FloatArithmeticOp::Max => unreachable!("no x87 min or max"),
+    };
+    raise_denormal(&[left, right], &mut exceptions);
+    (res, exceptions, rounded_up)
+}
+
+/// Orders two non NaN operands, zeros of either sign being equal.
+fn compare_ordered(left: &ExtendedOperand, right: &ExtendedOperand) -> Ordering {
+    let magnitude = |class: Class, other: Class| match (class, other) {
+        (Class::Zero { .. }, Class::Zero { .. }) | (Class::Infinity { .. }, Class::Infinity { .. }) => Ordering::Equal,
+        (Class::Zero { .. }, _) | (_, Class::Infinity { .. }) => Ordering::Less,
+        (_, Class::Zero { .. }) | (Class::Infinity { .. }, _) => Ordering::Greater,
+        (Class::Finite(class), Class::Finite(other)) => compare_magnitude(class, other),
+        (Class::NaN { .. }, _) | (_, Class::NaN { .. }) => unreachable!(),
+    };
+    let left_class = left.class.unwrap();
+    let right_class = right.class.unwrap();
+    let negative = |operand: &ExtendedOperand, class: Class| operand.sign() && !matches!(class, Class::Zero { .. });
+    match (negative(left, left_class), negative(right, right_class)) {
+        (false, false) => magnitude(left_class, right_class),
+        (true, true) => magnitude(right_class, left_class),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+    }
+}
+
+/// FCOM and friends. Unsupported encodings compare unordered and raise invalid like SNaNs do.
+pub fn x87_compare(predicate: FloatPredicate, left: u128, right: u128) -> (bool, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    let left = ExtendedOperand::new(left);
+    let right = ExtendedOperand::new(right);
+    if left.class.is_none() || right.class.is_none() {
+        exceptions.raise(FloatExceptions::INVALID);
+        return (predicate.unordered, exceptions);
+    }
+    if left.is_nan() || right.is_nan() {
+        if predicate.signaling || left.is_signaling_nan() || right.is_signaling_nan() {
+            exceptions.raise(FloatExceptions::INVALID);
+        }
+        return (predicate.unordered, exceptions);
+    }
+    raise_denormal(&[left, right], &mut exceptions);
+    let res = match compare_ordered(&left, &right) {
+        Ordering::Less => predicate.less,
+        Ordering::Equal => predicate.equal,
+        Ordering::Greater => predicate.greater,
+    };
+    (res, exceptions)
+}
+
+/// Widens binary32 or binary64 to the extended format, which is always exact. SNaNs are quietened.
+pub fn x87_from_float(format: FloatFormat, value: u64) -> (u128, FloatExceptions) {
+    let mut exceptions = FloatExceptions::NONE;
+    let operand = Operand::new(format, value, Mxcsr(0));
+    let sign = operand.bits & format.sign_bit() != 0;
+    let res = match operand.class {
+        Class::NaN { quiet } => {
+            if !quiet {
+                exceptions.raise(FloatExceptions::INVALID);
+            }
+            let payload = (operand.bits & ((1 << format.mantissa_bits()) - 1)) << (63 - format.mantissa_bits());
+            pack(sign, MAX_BIASED_EXPONENT, INTEGER_BIT | QUIET_BIT | payload)
+        }
+        Class::Infinity { sign } => infinity(sign),
+        Class::Zero { sign } => zero(sign),
+        Class::Finite(unpacked) => {
+            if operand.denormal {
+                exceptions.raise(FloatExceptions::DENORMAL);
+            }
+            let unpacked = unpacked.normalized(63);
+            pack(sign, (unpacked.exp + 63 + EXPONENT_BIAS) as u128, unpacked.sig as u64)
+        }
+    };
+    (res, exceptions)
+}
+
+/// Narrows to binary32 or binary64 with the rounding control of `control`, precision control doesn't apply.
+/// Also returns whether the result was rounded up, for C1.
+pub fn x87_to_float(format: FloatFormat, value: u128, control: X87ControlWord) -> (u64, FloatExceptions, bool) {
+    let mut exceptions = FloatExceptions::NONE;
+    let operand = ExtendedOperand::new(value);
+    let sign = operand.sign();
+    let res = match operand.class {
+        None => {
+            exceptions.raise(FloatExceptions::INVALID);
+            (format.default_nan(), false)
+        }
+        Some(Class::NaN { quiet }) => {
+            if !quiet {
+                exceptions.raise(FloatExceptions::INVALID);
+            }
+            let payload = (operand.bits as u64 & !INTEGER_BIT) >> (63 - format.mantissa_bits());
+            (format.pack(sign, format.max_biased_exponent(), payload | format.quiet_bit()), false)
+        }
+        Some(Class::Infinity { sign }) => (format.pack(sign, format.max_biased_exponent(), 0), false),
+        Some(Class::Zero { sign }) => (format.pack(sign, 0, 0), false),
+        Some(Class::Finite(unpacked)) => {
+            let res = round_pack(format, unpacked, control.as_mxcsr(), &mut exceptions);
+            let rounded_up = match Operand::new(format, res, Mxcsr(0)).class {
+                Class::Infinity { .. } => true,
+                Class::Zero { .. } => false,
+                Class::Finite(rounded) => compare_magnitude(rounded, unpacked).is_gt(),
+                Class::NaN { .. } => unreachable!(),
+            };
+            (res, rounded_up)
+        }
+    };
+    (res.0, exceptions, res.1)
+}
+
+/// FILD, which is always exact. `value` is a signed integer sign extended to 64 bits.
+pub fn x87_from_int(value: i64) -> u128 {
+    if value == 0 {
+        return zero(false);
+    }
+    let unpacked = Unpacked { sign: value < 0, exp: 0, sig: value.unsigned_abs() as u128 }.normalized(63);
+    pack(unpacked.sign, (unpacked.exp + 63 + EXPONENT_BIAS) as u128, unpacked.sig as u64)
+}
+
+/// FIST and FISTP, converting to a `width` bit signed integer with the rounding control of `control`. NaNs and
+/// values out of range give the "integer indefinite" value, the most negative integer. Also returns whether the
+/// magnitude was rounded up, for C1.
+pub fn x87_to_int(value: u128, width: usize, control: X87ControlWord) -> (u64, FloatExceptions, bool) {
+    let mut exceptions = FloatExceptions::NONE;
+    let indefinite = 1u64 << (width - 1);
+    let operand = ExtendedOperand::new(value);
+    let res = match operand.class {
+        None | Some(Class::NaN { .. }) | Some(Class::Infinity { .. }) => {
+            exceptions.raise(FloatExceptions::INVALID);
+            (indefinite, false)
+        }
+        Some(Class::Zero { .. }) => (0, false),
+        Some(Class::Finite(unpacked)) => {
+            let Unpacked { sign, exp, sig } = unpacked;
+            if exp + unpacked.msb() >= width as i32 {
+                exceptions.raise(FloatExceptions::INVALID);
+                (indefinite, false)
+            } else {
+                let (magnitude, inexact) = round_at(sign, exp, sig, 0, control.rounding_mode());
+                let (truncated, _) = round_at(sign, exp, sig, 0, RoundingMode::TowardZero);
+                let limit = if sign { 1u128 << (width - 1) } else { (1u128 << (width - 1)) - 1 };
+                if magnitude > limit {
+                    exceptions.raise(FloatExceptions::INVALID);
+                    (indefinite, false)
+                } else {
+                    if inexact {
+                        exceptions.raise(FloatExceptions::PRECISION);
+                    }
+                    let res = if sign { (magnitude as u64).wrapping_neg() } else { magnitude as u64 };
+                    (res & (u64::MAX >> (64 - width)), magnitude != truncated)
+                }
+            }
+        }
+    };
+    (res.0, exceptions, res.1)
+}
+
+/// The two bit tag the FPU keeps for a non empty register: valid, zero or special, which covers NaNs,
+/// infinities, denormals and unsupported encodings. Empty is 3.
+pub fn x87_tag(value: u128) -> u16 {
+    let biased_exponent = (value >> 64) & MAX_BIASED_EXPONENT;
+    let significand = value as u64;
+    if biased_exponent == 0 && significand == 0 {
+        1
+    } else if biased_exponent == 0 || biased_exponent == MAX_BIASED_EXPONENT || significand & INTEGER_BIT == 0 {
+        2
+    } else {
+        0
+    }
+}
@@ -0,0 +1,68 @@
+use wrapper_common::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP};
+use xed_enum::XCHG;
+
+use crate::semantics2::arena::Arena;
+use crate::semantics2::builder::SemanticsBuilder;
+use crate::semantics2::read_write::{Readable, Writeable};
+use crate::semantics2::semantic_steps::InstructionSemanticsStep;
+
+pub fn xchg_generic<'arena, O1: Readable<'arena> + Writeable<'arena>, O2: Readable<'arena> + Writeable<'arena>>(
+    arena: Arena<'arena>,
+    operand1: O1,
+    operand2: O2,
+    width: usize,
+) -> Vec<InstructionSemanticsStep<'arena>> {
+    //TEMP := DEST;
+    //DEST := SRC;
+    //SRC := TEMP;
+    let mut s = SemanticsBuilder::new(arena);
+    s.set_memory_operand_width(width);
+    let value1 = operand1.read(&s);
+    let value2 = operand2.read(&s);
+    operand1.write(&mut s, value2);
+    operand2.write(&mut s, value1);
+    s.finalize()
+}
+
+pub fn apply_iform_xchg(arena: Arena, instr: XCHG) -> Vec<InstructionSemanticsStep> {
+    match instr {
+        XCHG::XCHG_MEMB_GPR8 { operand_0, operand_1 } => {
+            xchg_generic(arena, operand_0, operand_1, 8)
+        }
+        XCHG::XCHG_GPR8_GPR8 { operand_0, operand_1 } => {
+            xchg_generic(arena, operand_0, operand_1, 8)
+        }
+        XCHG::XCHG_MEMV_GPRV_16 { operand_0, operand_1 } => {
+            xchg_generic(arena, operand_0, operand_1, 16)
+        }
+        XCHG::XCHG_MEMV_GPRV_32 { operand_0, operand_1 } => {
+            xchg_generic(arena, operand_0, operand_1, 32)
+        }
+        XCHG::XCHG_MEMV_GPRV_64 { operand_0, operand_1 } => {
+            xchg_generic(arena, operand_0, operand_1, 64)
+        }
+        XCHG::XCHG_GPRV_GPRV_16 { operand_0, operand_1 } => {
+            xchg_generic(arena, operand_0, operand_1, 16)
+        }
+        XCHG::XCHG_GPRV_GPRV_32 { operand_0, operand_1 } => {
+            xchg_generic(arena, operand_0, operand_1, 32)
+        }
+        XCHG::XCHG_GPRV_GPRV_64 { operand_0, operand_1 } => {
+            xchg_generic(arena, operand_0, operand_1, 64)
+        }
+        XCHG::XCHG_GPRV_ORAX_16 { operand_0 } => {
+            xchg_generic(arena, operand_0, Reg16WithRIP::AX, 16)
+        }
+        XCHG::XCHG_GPRV_ORAX_32 { operand_0 } => {
+            if operand_0 == Reg32WithRIP::EAX {
+                // 0x90 is NOP, so unlike other 32 bit writes this leaves the upper half of rax alone
+                SemanticsBuilder::new(arena).finalize()
+            } else {
+                xchg_generic(arena, operand_0, Reg32WithRIP::EAX, 32)
+            }
+        }
+        XCHG::XCHG_GPRV_ORAX_64 { operand_0 } => {
+            xchg_generic(arena, operand_0, Reg64WithRIP::RAX, 64)
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::memory_operand::{
 };
 use crate::registers::{OperandSize, Register16, Register32, Register64, Register8};
 use crate::utils::{imm_i16, imm_i32, imm_i8};
+use crate::DisassembleError;
 use capstone::arch::x86::{X86InsnDetail, X86Operand, X86OperandType};
 use capstone::prelude::DetailsArchInsn;
 use itertools::Itertools;
@@ -85,175 +86,169 @@ pub enum AddInstruction {
 }
 
 impl AddInstruction {
-    pub fn from_details(detail: &X86InsnDetail) -> Self {
+    pub fn from_details(detail: &X86InsnDetail) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
         if operands.len() != 2 {
-            todo!()
+            return Err(DisassembleError::BadOperandCount(operands.len()));
         }
-        let target_op_size = OperandSize::from_capstone_size(operands[0].size);
+        let target_op_size = OperandSize::from_capstone_size(operands[0].size)?;
         match &operands[0].op_type {
             X86OperandType::Reg(reg_id) => match target_op_size {
                 OperandSize::QuadWord => {
-                    let target = MemoryOperandOrRegister64::Reg(Register64::new(*reg_id));
+                    let target = MemoryOperandOrRegister64::Reg(Register64::new(*reg_id)?);
                     Self::with_target64(&operands[1], target)
                 }
                 OperandSize::DoubleWord => {
-                    let target = MemoryOperandOrRegister32::Reg(Register32::new(*reg_id));
+                    let target = MemoryOperandOrRegister32::Reg(Register32::new(*reg_id)?);
                     Self::with_target32(&operands[1], target)
                 }
                 OperandSize::Word => {
-                    let target = MemoryOperandOrRegister16::Reg(Register16::new(*reg_id));
+                    let target = MemoryOperandOrRegister16::Reg(Register16::new(*reg_id)?);
                     Self::with_target16(&operands[1], target)
                 }
                 OperandSize::HalfWord => {
-                    let target = MemoryOperandOrRegister8::Reg(Register8::new(*reg_id));
+                    let target = MemoryOperandOrRegister8::Reg(Register8::new(*reg_id)?);
                     Self::with_target8(&operands[1], target)
                 }
             },
             X86OperandType::Mem(mem) => match target_op_size {
                 OperandSize::QuadWord => {
-                    let target = MemoryOperandOrRegister64::Mem(MemoryOperand::from_mem(mem));
+                    let target = MemoryOperandOrRegister64::Mem(MemoryOperand::from_mem(mem)?);
                     Self::with_target64(&operands[1], target)
                 }
                 OperandSize::DoubleWord => {
-                    let target = MemoryOperandOrRegister32::Mem(MemoryOperand::from_mem(mem));
+                    let target = MemoryOperandOrRegister32::Mem(MemoryOperand::from_mem(mem)?);
                     Self::with_target32(&operands[1], target)
                 }
                 OperandSize::Word => {
-                    let target = MemoryOperandOrRegister16::Mem(MemoryOperand::from_mem(mem));
+                    let target = MemoryOperandOrRegister16::Mem(MemoryOperand::from_mem(mem)?);
                     Self::with_target16(&operands[1], target)
                 }
                 OperandSize::HalfWord => {
-                    let target = MemoryOperandOrRegister8::Mem(MemoryOperand::from_mem(mem));
+                    let target = MemoryOperandOrRegister8::Mem(MemoryOperand::from_mem(mem)?);
                     Self::with_target8(&operands[1], target)
                 }
             },
             X86OperandType::Imm(_) | X86OperandType::Invalid => {
-                panic!("Unexpected first operand type")
+                Err(DisassembleError::UnexpectedOperandType)
             }
         }
     }
 
-    fn with_target8(next_operand: &X86Operand, target: MemoryOperandOrRegister8) -> AddInstruction {
-        match &next_operand.op_type {
+    fn with_target8(
+        next_operand: &X86Operand,
+        target: MemoryOperandOrRegister8,
+    ) -> Result<AddInstruction, DisassembleError> {
+        Ok(match &next_operand.op_type {
             X86OperandType::Reg(reg) => AddInstruction::Rm8R8 {
                 target,
-                to_add: Register8::new(*reg),
+                to_add: Register8::new(*reg)?,
             },
             X86OperandType::Imm(imm) => {
-                assert_eq!(
-                    OperandSize::from_capstone_size(next_operand.size),
-                    OperandSize::HalfWord
-                );
+                if OperandSize::from_capstone_size(next_operand.size)? != OperandSize::HalfWord {
+                    return Err(DisassembleError::UnexpectedOperandSize(next_operand.size));
+                }
                 AddInstruction::Rm8imm8 {
                     target,
-                    imm8: imm_i8(*imm),
+                    imm8: imm_i8(*imm)?,
                 }
             }
             X86OperandType::Mem(mem) => AddInstruction::R8Rm8 {
-                target: target.unwrap_reg(),
-                to_add: MemoryOperandOrRegister8::Mem(MemoryOperand::from_mem(mem)),
+                target: target.unwrap_reg()?,
+                to_add: MemoryOperandOrRegister8::Mem(MemoryOperand::from_mem(mem)?),
             },
-            X86OperandType::Invalid => {
-                todo!()
-            }
-        }
+            X86OperandType::Invalid => return Err(DisassembleError::UnexpectedOperandType),
+        })
     }
 
     fn with_target16(
         next_operand: &X86Operand,
         target: MemoryOperandOrRegister16,
-    ) -> AddInstruction {
-        match &next_operand.op_type {
+    ) -> Result<AddInstruction, DisassembleError> {
+        Ok(match &next_operand.op_type {
             X86OperandType::Reg(reg) => AddInstruction::Rm16R16 {
                 target,
-                to_add: Register16::new(*reg),
+                to_add: Register16::new(*reg)?,
             },
-            X86OperandType::Imm(imm) => match OperandSize::from_capstone_size(next_operand.size) {
+            X86OperandType::Imm(imm) => match OperandSize::from_capstone_size(next_operand.size)? {
                 OperandSize::QuadWord | OperandSize::DoubleWord => {
-                    panic!("Unexpected large constant")
+                    return Err(DisassembleError::UnexpectedOperandSize(next_operand.size))
                 }
                 OperandSize::Word => AddInstruction::Rm16imm16 {
                     target,
-                    imm16: imm_i16(*imm),
+                    imm16: imm_i16(*imm)?,
                 },
                 OperandSize::HalfWord => AddInstruction::Rm16imm8 {
                     target,
-                    imm8: imm_i8(*imm),
+                    imm8: imm_i8(*imm)?,
                 },
             },
             X86OperandType::Mem(mem) => AddInstruction::R16Rm16 {
-                target: target.unwrap_reg(),
-                to_add: MemoryOperandOrRegister16::Mem(MemoryOperand::from_mem(mem)),
+                target: target.unwrap_reg()?,
+                to_add: MemoryOperandOrRegister16::Mem(MemoryOperand::from_mem(mem)?),
             },
-            X86OperandType::Invalid => {
-                todo!()
-            }
-        }
+            X86OperandType::Invalid => return Err(DisassembleError::UnexpectedOperandType),
+        })
     }
 
     fn with_target32(
         next_operand: &X86Operand,
         target: MemoryOperandOrRegister32,
-    ) -> AddInstruction {
-        match &next_operand.op_type {
+    ) -> Result<AddInstruction, DisassembleError> {
+        Ok(match &next_operand.op_type {
             X86OperandType::Reg(reg) => AddInstruction::Rm32R32 {
                 target,
-                to_add: Register32::new(*reg),
+                to_add: Register32::new(*reg)?,
             },
-            X86OperandType::Imm(imm) => match OperandSize::from_capstone_size(next_operand.size) {
+            X86OperandType::Imm(imm) => match OperandSize::from_capstone_size(next_operand.size)? {
                 OperandSize::DoubleWord => AddInstruction::Rm32imm32 {
                     target,
-                    imm32: imm_i32(*imm),
+                    imm32: imm_i32(*imm)?,
                 },
                 OperandSize::QuadWord | OperandSize::Word => {
-                    panic!("Unexpected constant size")
+                    return Err(DisassembleError::UnexpectedOperandSize(next_operand.size))
                 }
                 OperandSize::HalfWord => AddInstruction::Rm32imm8 {
                     target,
-                    imm8: imm_i8(*imm),
+                    imm8: imm_i8(*imm)?,
                 },
             },
             X86OperandType::Mem(mem) => AddInstruction::R32Rm32 {
-                target: target.unwrap_reg(),
-                to_add: MemoryOperandOrRegister32::Mem(MemoryOperand::from_mem(mem)),
+                target: target.unwrap_reg()?,
+                to_add: MemoryOperandOrRegister32::Mem(MemoryOperand::from_mem(mem)?),
             },
-            X86OperandType::Invalid => {
-                todo!()
-            }
-        }
+            X86OperandType::Invalid => return Err(DisassembleError::UnexpectedOperandType),
+        })
     }
 
     fn with_target64(
         next_operand: &X86Operand,
         target: MemoryOperandOrRegister64,
-    ) -> AddInstruction {
-        match &next_operand.op_type {
+    ) -> Result<AddInstruction, DisassembleError> {
+        Ok(match &next_operand.op_type {
             X86OperandType::Reg(reg_id) => {
-                let to_add = Register64::new(*reg_id);
+                let to_add = Register64::new(*reg_id)?;
                 AddInstruction::Rm64R64 { target, to_add }
             }
-            X86OperandType::Imm(imm) => match OperandSize::from_capstone_size(next_operand.size) {
+            X86OperandType::Imm(imm) => match OperandSize::from_capstone_size(next_operand.size)? {
                 OperandSize::QuadWord => AddInstruction::Rm64imm32 {
                     target,
-                    imm32: imm_i32(*imm),
+                    imm32: imm_i32(*imm)?,
                 },
                 OperandSize::DoubleWord | OperandSize::Word => {
-                    panic!("Unexpected constant size")
+                    return Err(DisassembleError::UnexpectedOperandSize(next_operand.size))
                 }
                 OperandSize::HalfWord => AddInstruction::Rm64imm8 {
                     target,
-                    imm8: imm_i8(*imm),
+                    imm8: imm_i8(*imm)?,
                 },
             },
             X86OperandType::Mem(mem) => AddInstruction::R64Rm64 {
-                target: target.unwrap_reg(),
-                to_add: MemoryOperandOrRegister64::Mem(MemoryOperand::from_mem(mem)),
+                target: target.unwrap_reg()?,
+                to_add: MemoryOperandOrRegister64::Mem(MemoryOperand::from_mem(mem)?),
             },
-            X86OperandType::Invalid => {
-                todo!()
-            }
-        }
+            X86OperandType::Invalid => return Err(DisassembleError::UnexpectedOperandType),
+        })
     }
 }
 
@@ -350,6 +345,7 @@ pub mod test {
             &res[7],
             &X86Instruction::Add(AddInstruction::Rm32imm32 {
                 target: MemoryOperandOrRegister32::Mem(MemoryOperand {
+                    seg: None,
                     base: GeneralRegister::Register64(Register64::RAX),
                     scale: X86Scale::Eight,
                     index: Some(GeneralRegister::Register64(Register64::RCX)),
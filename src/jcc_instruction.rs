@@ -1,5 +1,6 @@
 use crate::condition_code_flag::JumpConditionCode;
 use crate::registers::OperandSize;
+use crate::DisassembleError;
 use capstone::arch::x86::{X86InsnDetail, X86OperandType};
 use capstone::prelude::DetailsArchInsn;
 use capstone::Insn;
@@ -16,25 +17,19 @@ impl JCCInstruction {
         _instruction: &Insn,
         detail: &X86InsnDetail,
         op_code: JumpConditionCode,
-    ) -> Self {
+    ) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
         if operands.len() != 1 {
-            todo!()
+            return Err(DisassembleError::BadOperandCount(operands.len()));
         }
-        let operand_size = OperandSize::from_capstone_size(operands[0].size);
+        let _operand_size = OperandSize::from_capstone_size(operands[0].size)?;
         match &operands[0].op_type {
-            X86OperandType::Reg(_) => {
-                todo!()
-            }
-            X86OperandType::Imm(imm) => JCCInstruction {
+            X86OperandType::Imm(imm) => Ok(JCCInstruction {
                 absolute_target: *imm as u64,
                 condition_code: op_code,
-            },
-            X86OperandType::Mem(_) => {
-                todo!()
-            }
-            X86OperandType::Invalid => {
-                todo!()
+            }),
+            X86OperandType::Reg(_) | X86OperandType::Mem(_) | X86OperandType::Invalid => {
+                Err(DisassembleError::UnexpectedOperandType)
             }
         }
     }
@@ -2,6 +2,7 @@ use capstone::arch;
 use capstone::arch::x86::X86Insn;
 use capstone::prelude::{BuildsCapstone, BuildsCapstoneSyntax};
 use thiserror::Error;
+use xed_enum::{DecodeError, EncodeDecodeContext};
 
 use crate::add_instruction::AddInstruction;
 use crate::condition_code_flag::JumpConditionCode;
@@ -20,6 +21,24 @@ pub enum DisassembleError {
     FailedToDisassemble,
     #[error("Failed to configure capstone")]
     FailedToConfigureCapstone,
+    #[error("Unknown instruction id {id} at {address:#x}")]
+    UnknownInstruction { id: u32, address: u64 },
+    #[error("Xed failed to decode instruction at {address:#x}: {error}")]
+    XedDecode { address: u64, error: String },
+    #[error("Unexpected number of operands: {0}")]
+    BadOperandCount(usize),
+    #[error("Unexpected operand type")]
+    UnexpectedOperandType,
+    #[error("Unsupported operand combination")]
+    UnsupportedOperands,
+    #[error("Immediate out of range: {0}")]
+    ImmediateOutOfRange(i64),
+    #[error("Unknown register id: {0}")]
+    UnknownRegister(u16),
+    #[error("Unexpected operand size: {0}")]
+    UnexpectedOperandSize(u8),
+    #[error("Unexpected scale: {0}")]
+    UnexpectedScale(i32),
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -30,11 +49,14 @@ pub enum ImmediateOperand {
 }
 
 impl ImmediateOperand {
-    pub fn from_capstone_displacement(capstone_displacement: i64) -> ImmediateOperand {
+    pub fn from_capstone_displacement(
+        capstone_displacement: i64,
+    ) -> Result<ImmediateOperand, DisassembleError> {
+        // x86 doesn't have 64 bit displacements
         let res: i32 = capstone_displacement
             .try_into()
-            .expect("But x86 doesn't have 64 bit displacements?");
-        ImmediateOperand::Imm32(res)
+            .map_err(|_| DisassembleError::ImmediateOutOfRange(capstone_displacement))?;
+        Ok(ImmediateOperand::Imm32(res))
     }
 }
 
@@ -47,16 +69,14 @@ pub enum X86Scale {
 }
 
 impl X86Scale {
-    pub fn from_capstone_scale(capstone_scale: i32) -> X86Scale {
-        match capstone_scale {
+    pub fn from_capstone_scale(capstone_scale: i32) -> Result<X86Scale, DisassembleError> {
+        Ok(match capstone_scale {
             1 => X86Scale::One,
             2 => X86Scale::Two,
             4 => X86Scale::Four,
             8 => X86Scale::Eight,
-            _ => {
-                panic!("Unexpected scale?")
-            }
-        }
+            _ => return Err(DisassembleError::UnexpectedScale(capstone_scale)),
+        })
     }
 }
 
@@ -132,7 +152,7 @@ pub fn disassemble(bytes: &[u8], address: u64) -> Result<Vec<X86Instruction>, Di
         .syntax(arch::x86::ArchSyntax::Intel)
         .detail(true)
         .build()
-        .expect("Shouldn't fail to instantiate capstone.");
+        .map_err(|_| DisassembleError::FailedToConfigureCapstone)?;
     let instructions = capstone
         .disasm_all(bytes, address)
         .map_err(|_| DisassembleError::FailedToDisassemble)?;
@@ -149,46 +169,82 @@ pub fn disassemble(bytes: &[u8], address: u64) -> Result<Vec<X86Instruction>, Di
                 .ok_or(DisassembleError::FailedToDisassemble)?;
             Ok(match instruction_type {
                 X86Insn::X86_INS_PUSH => {
-                    X86Instruction::Push(PushInstruction::from_detail(x86_detail))
+                    X86Instruction::Push(PushInstruction::from_detail(x86_detail)?)
                 }
                 X86Insn::X86_INS_MOV => {
-                    X86Instruction::Mov(MovInstruction::from_detail(x86_detail))
+                    X86Instruction::Mov(MovInstruction::from_detail(x86_detail)?)
                 }
                 X86Insn::X86_INS_SHL => {
-                    X86Instruction::Shl(ShlInstruction::from_details(x86_detail))
+                    X86Instruction::Shl(ShlInstruction::from_details(x86_detail)?)
                 }
                 X86Insn::X86_INS_SUB => {
-                    X86Instruction::Sub(SubInstruction::from_details(x86_detail))
+                    X86Instruction::Sub(SubInstruction::from_details(x86_detail)?)
                 }
                 X86Insn::X86_INS_ADD => {
-                    X86Instruction::Add(AddInstruction::from_details(x86_detail))
+                    X86Instruction::Add(AddInstruction::from_details(x86_detail)?)
                 }
                 X86Insn::X86_INS_SETB => {
-                    X86Instruction::SetB(SetBInstruction::from_details(x86_detail))
+                    X86Instruction::SetB(SetBInstruction::from_details(x86_detail)?)
                 }
                 X86Insn::X86_INS_TEST => {
-                    X86Instruction::Test(TestInstruction::from_details(x86_detail))
+                    X86Instruction::Test(TestInstruction::from_details(x86_detail)?)
                 }
                 X86Insn::X86_INS_JBE => X86Instruction::JCC(JCCInstruction::from_details(
                     instruction,
                     x86_detail,
                     JumpConditionCode::BE,
-                )),
+                )?),
                 X86Insn::X86_INS_JNE => X86Instruction::JCC(JCCInstruction::from_details(
                     instruction,
                     x86_detail,
                     JumpConditionCode::NE,
-                )),
+                )?),
                 X86Insn::X86_INS_RET => {
-                    X86Instruction::Ret(RetInstruction::from_details(x86_detail))
+                    X86Instruction::Ret(RetInstruction::from_details(x86_detail)?)
                 }
                 _ => {
-                    todo!("Unimplemented instruction id: {}", instruction.id().0)
+                    return Err(DisassembleError::UnknownInstruction {
+                        id: instruction.id().0,
+                        address: instruction.address(),
+                    })
                 }
             })
         })
         .collect::<Result<Vec<_>, _>>()
 }
 
+/// Disassembles with capstone, then decodes each instruction capstone found into the xed derived
+/// [`xed_enum::X86Instruction`], which covers every instruction xed knows about.
+pub fn disassemble_xed(
+    bytes: &[u8],
+    address: u64,
+) -> Result<Vec<(u64, xed_enum::X86Instruction)>, DisassembleError> {
+    let capstone = capstone::Capstone::new()
+        .x86()
+        .mode(arch::x86::ArchMode::Mode64)
+        .syntax(arch::x86::ArchSyntax::Intel)
+        .build()
+        .map_err(|_| DisassembleError::FailedToConfigureCapstone)?;
+    let instructions = capstone
+        .disasm_all(bytes, address)
+        .map_err(|_| DisassembleError::FailedToDisassemble)?;
+    let mut context = EncodeDecodeContext::new();
+    instructions
+        .iter()
+        .map(|instruction| {
+            let (decoded, _) = xed_enum::X86Instruction::decode_one(instruction.bytes(), &mut context)
+                .map_err(|err| DisassembleError::XedDecode {
+                    address: instruction.address(),
+                    error: match err {
+                        DecodeError::XedError(error) => error,
+                        DecodeError::Undecodable { error, .. } => error,
+                        DecodeError::Unsupported(error) => error,
+                    },
+                })?;
+            Ok((instruction.address(), decoded))
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
 #[cfg(test)]
 mod tests;
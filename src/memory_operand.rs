@@ -2,7 +2,7 @@ use capstone::arch::x86::X86OpMem;
 use capstone::arch::x86::X86Reg::X86_REG_INVALID;
 
 use crate::registers::{GeneralRegister, OperandSize, Register16, Register32, Register64, Register8, SegmentRegister};
-use crate::{ImmediateOperand, X86Scale};
+use crate::{DisassembleError, ImmediateOperand, X86Scale};
 
 //todo consider templating to avoid duplication
 
@@ -13,12 +13,10 @@ pub enum MemoryOperandOrRegister8 {
 }
 
 impl MemoryOperandOrRegister8 {
-    pub fn unwrap_reg(&self) -> Register8 {
+    pub fn unwrap_reg(&self) -> Result<Register8, DisassembleError> {
         match self {
-            MemoryOperandOrRegister8::Reg(reg) => *reg,
-            MemoryOperandOrRegister8::Mem(_) => {
-                panic!("Expected register")
-            }
+            MemoryOperandOrRegister8::Reg(reg) => Ok(*reg),
+            MemoryOperandOrRegister8::Mem(_) => Err(DisassembleError::UnexpectedOperandType),
         }
     }
 }
@@ -30,12 +28,10 @@ pub enum MemoryOperandOrRegister16 {
 }
 
 impl MemoryOperandOrRegister16 {
-    pub fn unwrap_reg(&self) -> Register16 {
+    pub fn unwrap_reg(&self) -> Result<Register16, DisassembleError> {
         match self {
-            MemoryOperandOrRegister16::Reg(reg) => *reg,
-            MemoryOperandOrRegister16::Mem(_) => {
-                panic!("Expected register")
-            }
+            MemoryOperandOrRegister16::Reg(reg) => Ok(*reg),
+            MemoryOperandOrRegister16::Mem(_) => Err(DisassembleError::UnexpectedOperandType),
         }
     }
 }
@@ -47,12 +43,10 @@ pub enum MemoryOperandOrRegister32 {
 }
 
 impl MemoryOperandOrRegister32 {
-    pub fn unwrap_reg(&self) -> Register32 {
+    pub fn unwrap_reg(&self) -> Result<Register32, DisassembleError> {
         match self {
-            MemoryOperandOrRegister32::Reg(reg) => *reg,
-            MemoryOperandOrRegister32::Mem(_) => {
-                panic!("Expected register")
-            }
+            MemoryOperandOrRegister32::Reg(reg) => Ok(*reg),
+            MemoryOperandOrRegister32::Mem(_) => Err(DisassembleError::UnexpectedOperandType),
         }
     }
 }
@@ -64,12 +58,10 @@ pub enum MemoryOperandOrRegister64 {
 }
 
 impl MemoryOperandOrRegister64 {
-    pub fn unwrap_reg(&self) -> Register64 {
+    pub fn unwrap_reg(&self) -> Result<Register64, DisassembleError> {
         match self {
-            MemoryOperandOrRegister64::Reg(reg) => *reg,
-            MemoryOperandOrRegister64::Mem(_) => {
-                panic!("Expected register")
-            }
+            MemoryOperandOrRegister64::Reg(reg) => Ok(*reg),
+            MemoryOperandOrRegister64::Mem(_) => Err(DisassembleError::UnexpectedOperandType),
         }
     }
 }
@@ -94,19 +86,24 @@ impl MemoryOperand {
         }
     }
 
-    pub fn from_mem(mem: &X86OpMem) -> Self {
-        let base = GeneralRegister::new(mem.base(), OperandSize::QuadWord); //todo what about not 64 bit stuff
+    pub fn from_mem(mem: &X86OpMem) -> Result<Self, DisassembleError> {
+        let base = GeneralRegister::new(mem.base(), OperandSize::QuadWord)?; //todo what about not 64 bit stuff
         let index = if mem.index() != X86_REG_INVALID.into() {
-            Some(GeneralRegister::new(mem.index(), OperandSize::QuadWord))
+            Some(GeneralRegister::new(mem.index(), OperandSize::QuadWord)?)
         } else {
             None
         };
-        MemoryOperand {
-            seg: todo!(),
+        let seg = if mem.segment() != X86_REG_INVALID.into() {
+            Some(SegmentRegister::new(mem.segment())?)
+        } else {
+            None
+        };
+        Ok(MemoryOperand {
+            seg,
             base,
-            scale: X86Scale::from_capstone_scale(mem.scale()),
+            scale: X86Scale::from_capstone_scale(mem.scale())?,
             index,
-            offset: ImmediateOperand::from_capstone_displacement(mem.disp()),
-        }
+            offset: ImmediateOperand::from_capstone_displacement(mem.disp())?,
+        })
     }
 }
@@ -7,6 +7,7 @@ use crate::memory_operand::{
     MemoryOperandOrRegister8,
 };
 use crate::registers::{OperandSize, Register16, Register32, Register64, Register8};
+use crate::DisassembleError;
 
 //https://www.felixcloutier.com/x86/mov
 //https://www.felixcloutier.com/x86/mov-1
@@ -48,102 +49,79 @@ pub enum MovInstruction {
 }
 
 impl MovInstruction {
-    pub fn from_detail(detail: &X86InsnDetail) -> Self {
+    pub fn from_detail(detail: &X86InsnDetail) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
+        if operands.len() != 2 {
+            return Err(DisassembleError::BadOperandCount(operands.len()));
+        }
         let dst_operand = &operands[0];
         let src_operand = &operands[1];
-        let dst_operand_size = OperandSize::from_capstone_size(dst_operand.size);
-        let src_operand_size = OperandSize::from_capstone_size(src_operand.size);
+        let dst_operand_size = OperandSize::from_capstone_size(dst_operand.size)?;
+        let src_operand_size = OperandSize::from_capstone_size(src_operand.size)?;
         match &dst_operand.op_type {
             X86OperandType::Reg(dst_reg_id) => {
                 //todo handle segment register
                 // let dst_general_register = GeneralRegister::new(*dst_reg_id, dst_operand_size);
                 match &src_operand.op_type {
                     X86OperandType::Reg(src_reg_id) => {
-                        assert_eq!(src_operand_size, dst_operand_size);
+                        if src_operand_size != dst_operand_size {
+                            return Err(DisassembleError::UnexpectedOperandSize(src_operand.size));
+                        }
                         match src_operand_size {
                             OperandSize::QuadWord => {
-                                let dst = Register64::new(*dst_reg_id);
-                                let src = Register64::new(*src_reg_id);
-                                MovInstruction::R64Rm64 {
+                                let dst = Register64::new(*dst_reg_id)?;
+                                let src = Register64::new(*src_reg_id)?;
+                                Ok(MovInstruction::R64Rm64 {
                                     src: MemoryOperandOrRegister64::Reg(src),
-                                    dst: dst,
-                                }
-                            }
-                            OperandSize::DoubleWord => {
-                                todo!()
-                            }
-                            OperandSize::Word => {
-                                todo!()
+                                    dst,
+                                })
                             }
-                            OperandSize::HalfWord => {
-                                todo!()
+                            OperandSize::DoubleWord | OperandSize::Word | OperandSize::HalfWord => {
+                                Err(DisassembleError::UnsupportedOperands)
                             }
                         }
                     }
-                    X86OperandType::Imm(_) => {
-                        todo!()
-                    }
+                    X86OperandType::Imm(_) => Err(DisassembleError::UnsupportedOperands),
                     X86OperandType::Mem(src_mem) => {
-                        assert_eq!(src_operand_size, dst_operand_size);
+                        if src_operand_size != dst_operand_size {
+                            return Err(DisassembleError::UnexpectedOperandSize(src_operand.size));
+                        }
                         match src_operand_size {
                             OperandSize::QuadWord => {
-                                let dst = Register64::new(*dst_reg_id);
+                                let dst = Register64::new(*dst_reg_id)?;
                                 let src = MemoryOperandOrRegister64::Mem(MemoryOperand::from_mem(
                                     src_mem,
-                                ));
-                                MovInstruction::R64Rm64 { src, dst }
-                            }
-                            OperandSize::DoubleWord => {
-                                todo!()
+                                )?);
+                                Ok(MovInstruction::R64Rm64 { src, dst })
                             }
-                            OperandSize::Word => {
-                                todo!()
-                            }
-                            OperandSize::HalfWord => {
-                                todo!()
+                            OperandSize::DoubleWord | OperandSize::Word | OperandSize::HalfWord => {
+                                Err(DisassembleError::UnsupportedOperands)
                             }
                         }
                     }
-                    X86OperandType::Invalid => {
-                        todo!()
-                    }
+                    X86OperandType::Invalid => Err(DisassembleError::UnexpectedOperandType),
                 }
             }
-            X86OperandType::Imm(_) => {
-                todo!()
-            }
             X86OperandType::Mem(mem) => match dst_operand_size {
                 OperandSize::QuadWord => {
-                    let dst = MemoryOperandOrRegister64::Mem(MemoryOperand::from_mem(mem));
+                    let dst = MemoryOperandOrRegister64::Mem(MemoryOperand::from_mem(mem)?);
                     match &src_operand.op_type {
                         X86OperandType::Reg(reg_id) => {
-                            let src = Register64::new(*reg_id);
-                            MovInstruction::Rm64R64 { src, dst }
-                        }
-                        X86OperandType::Imm(_) => {
-                            todo!()
+                            let src = Register64::new(*reg_id)?;
+                            Ok(MovInstruction::Rm64R64 { src, dst })
                         }
-                        X86OperandType::Mem(_) => {
-                            todo!()
-                        }
-                        X86OperandType::Invalid => {
-                            todo!()
+                        X86OperandType::Imm(_) => Err(DisassembleError::UnsupportedOperands),
+                        X86OperandType::Mem(_) | X86OperandType::Invalid => {
+                            Err(DisassembleError::UnexpectedOperandType)
                         }
                     }
                 }
-                OperandSize::DoubleWord => {
-                    todo!()
-                }
-                OperandSize::Word => {
-                    todo!()
-                }
-                OperandSize::HalfWord => {
-                    todo!()
+                OperandSize::DoubleWord | OperandSize::Word | OperandSize::HalfWord => {
+                    Err(DisassembleError::UnsupportedOperands)
                 }
             },
-            X86OperandType::Invalid => {
-                todo!()
+            X86OperandType::Imm(_) | X86OperandType::Invalid => {
+                Err(DisassembleError::UnexpectedOperandType)
             }
         }
     }
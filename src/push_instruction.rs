@@ -1,6 +1,6 @@
 use crate::memory_operand::MemoryOperand;
 use crate::registers::{GeneralRegisterWordAndBigger, OperandSize, SegmentRegister};
-use crate::ImmediateOperand;
+use crate::{DisassembleError, ImmediateOperand};
 use capstone::arch::x86::{X86InsnDetail, X86OperandType};
 use capstone::prelude::DetailsArchInsn;
 use itertools::Itertools;
@@ -23,24 +23,21 @@ pub enum PushInstruction {
 }
 
 impl PushInstruction {
-    pub fn from_detail(detail: &X86InsnDetail) -> Self {
+    pub fn from_detail(detail: &X86InsnDetail) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
+        if operands.len() != 1 {
+            return Err(DisassembleError::BadOperandCount(operands.len()));
+        }
         let first_operand = &operands[0];
-        let operand_size = OperandSize::from_capstone_size(first_operand.size);
+        let operand_size = OperandSize::from_capstone_size(first_operand.size)?;
         match first_operand.op_type {
             X86OperandType::Reg(register_id) => {
-                let register = GeneralRegisterWordAndBigger::new(register_id, operand_size);
-                PushInstruction::Register { register }
-            }
-            X86OperandType::Imm(_) => {
-                todo!()
-            }
-            X86OperandType::Mem(_) => {
-                todo!()
-            }
-            X86OperandType::Invalid => {
-                todo!()
+                let register = GeneralRegisterWordAndBigger::new(register_id, operand_size)?;
+                Ok(PushInstruction::Register { register })
             }
+            X86OperandType::Imm(_) => Err(DisassembleError::UnsupportedOperands),
+            X86OperandType::Mem(_) => Err(DisassembleError::UnsupportedOperands),
+            X86OperandType::Invalid => Err(DisassembleError::UnexpectedOperandType),
         }
     }
 }
@@ -1,6 +1,8 @@
 use capstone::arch::x86::X86Reg::*;
 use capstone::RegId;
 
+use crate::DisassembleError;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum SegmentRegister {
     CS,
@@ -11,6 +13,20 @@ pub enum SegmentRegister {
     GS,
 }
 
+impl SegmentRegister {
+    pub fn new(register_id: RegId) -> Result<Self, DisassembleError> {
+        Ok(match register_id.0 as u32 {
+            X86_REG_CS => SegmentRegister::CS,
+            X86_REG_SS => SegmentRegister::SS,
+            X86_REG_DS => SegmentRegister::DS,
+            X86_REG_ES => SegmentRegister::ES,
+            X86_REG_FS => SegmentRegister::FS,
+            X86_REG_GS => SegmentRegister::GS,
+            _ => return Err(DisassembleError::UnknownRegister(register_id.0)),
+        })
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Register8 {
     AL,
@@ -36,12 +52,16 @@ pub enum Register8 {
 }
 
 impl Register8 {
-    pub fn new(register_id: RegId) -> Self {
-        match register_id.0 as u32 {
+    pub fn new(register_id: RegId) -> Result<Self, DisassembleError> {
+        Ok(match register_id.0 as u32 {
             X86_REG_AL => Register8::AL,
             X86_REG_BL => Register8::BL,
             X86_REG_CL => Register8::CL,
             X86_REG_DL => Register8::DL,
+            X86_REG_AH => Register8::AH,
+            X86_REG_BH => Register8::BH,
+            X86_REG_CH => Register8::CH,
+            X86_REG_DH => Register8::DH,
             X86_REG_SIL => Register8::SIL,
             X86_REG_DIL => Register8::DIL,
             X86_REG_BPL => Register8::BPL,
@@ -54,13 +74,8 @@ impl Register8 {
             X86_REG_R13B => Register8::R13B,
             X86_REG_R14B => Register8::R14B,
             X86_REG_R15B => Register8::R15B,
-            _ => {
-                panic!(
-                    "Capstone should never give us an unknown register id: {}",
-                    register_id.0
-                )
-            }
-        }
+            _ => return Err(DisassembleError::UnknownRegister(register_id.0)),
+        })
     }
 }
 
@@ -85,8 +100,8 @@ pub enum Register16 {
 }
 
 impl Register16 {
-    pub fn new(register_id: RegId) -> Self {
-        match register_id.0 as u32 {
+    pub fn new(register_id: RegId) -> Result<Self, DisassembleError> {
+        Ok(match register_id.0 as u32 {
             X86_REG_AX => Register16::AX,
             X86_REG_BX => Register16::BX,
             X86_REG_CX => Register16::CX,
@@ -103,13 +118,8 @@ impl Register16 {
             X86_REG_R13W => Register16::R13W,
             X86_REG_R14W => Register16::R14W,
             X86_REG_R15W => Register16::R15W,
-            _ => {
-                panic!(
-                    "Capstone should never give us an unknown register id: {}",
-                    register_id.0
-                )
-            }
-        }
+            _ => return Err(DisassembleError::UnknownRegister(register_id.0)),
+        })
     }
 
     pub fn widen_register(&self) -> Register64 {
@@ -155,8 +165,8 @@ pub enum Register32 {
 }
 
 impl Register32 {
-    pub fn new(register_id: RegId) -> Self {
-        match register_id.0 as u32 {
+    pub fn new(register_id: RegId) -> Result<Self, DisassembleError> {
+        Ok(match register_id.0 as u32 {
             X86_REG_EAX => Register32::EAX,
             X86_REG_EBX => Register32::EBX,
             X86_REG_ECX => Register32::ECX,
@@ -173,13 +183,8 @@ impl Register32 {
             X86_REG_R13D => Register32::R13D,
             X86_REG_R14D => Register32::R14D,
             X86_REG_R15D => Register32::R15D,
-            _ => {
-                panic!(
-                    "Capstone should never give us an unknown register id: {}",
-                    register_id.0
-                )
-            }
-        }
+            _ => return Err(DisassembleError::UnknownRegister(register_id.0)),
+        })
     }
 
     pub fn widen_register(&self) -> Register64 {
@@ -225,8 +230,8 @@ pub enum Register64 {
 }
 
 impl Register64 {
-    pub fn new(register_id: RegId) -> Self {
-        match register_id.0 as u32 {
+    pub fn new(register_id: RegId) -> Result<Self, DisassembleError> {
+        Ok(match register_id.0 as u32 {
             X86_REG_RAX => Register64::RAX,
             X86_REG_RBX => Register64::RBX,
             X86_REG_RCX => Register64::RCX,
@@ -243,13 +248,8 @@ impl Register64 {
             X86_REG_R13 => Register64::R13,
             X86_REG_R14 => Register64::R14,
             X86_REG_R15 => Register64::R15,
-            _ => {
-                panic!(
-                    "Capstone should never give us an unknown register id: {}",
-                    register_id.0
-                )
-            }
-        }
+            _ => return Err(DisassembleError::UnknownRegister(register_id.0)),
+        })
     }
 }
 
@@ -262,16 +262,14 @@ pub enum OperandSize {
 }
 
 impl OperandSize {
-    pub fn from_capstone_size(size: u8) -> OperandSize {
-        match size {
+    pub fn from_capstone_size(size: u8) -> Result<OperandSize, DisassembleError> {
+        Ok(match size {
             8 => Self::QuadWord,
             4 => Self::DoubleWord,
             2 => Self::Word,
             1 => Self::HalfWord,
-            _ => {
-                panic!("Unexpected operand size from capstone")
-            }
-        }
+            _ => return Err(DisassembleError::UnexpectedOperandSize(size)),
+        })
     }
 }
 
@@ -284,13 +282,13 @@ pub enum GeneralRegister {
 }
 
 impl GeneralRegister {
-    pub fn new(register_id: RegId, operand_size: OperandSize) -> Self {
-        match operand_size {
-            OperandSize::QuadWord => GeneralRegister::Register64(Register64::new(register_id)),
-            OperandSize::DoubleWord => GeneralRegister::Register32(Register32::new(register_id)),
-            OperandSize::Word => GeneralRegister::Register16(Register16::new(register_id)),
-            OperandSize::HalfWord => GeneralRegister::Register8(Register8::new(register_id)),
-        }
+    pub fn new(register_id: RegId, operand_size: OperandSize) -> Result<Self, DisassembleError> {
+        Ok(match operand_size {
+            OperandSize::QuadWord => GeneralRegister::Register64(Register64::new(register_id)?),
+            OperandSize::DoubleWord => GeneralRegister::Register32(Register32::new(register_id)?),
+            OperandSize::Word => GeneralRegister::Register16(Register16::new(register_id)?),
+            OperandSize::HalfWord => GeneralRegister::Register8(Register8::new(register_id)?),
+        })
     }
 }
 
@@ -302,21 +300,19 @@ pub enum GeneralRegisterWordAndBigger {
 }
 
 impl GeneralRegisterWordAndBigger {
-    pub fn new(register_id: RegId, operand_size: OperandSize) -> Self {
-        match operand_size {
+    pub fn new(register_id: RegId, operand_size: OperandSize) -> Result<Self, DisassembleError> {
+        Ok(match operand_size {
             OperandSize::QuadWord => {
-                GeneralRegisterWordAndBigger::Register64(Register64::new(register_id))
+                GeneralRegisterWordAndBigger::Register64(Register64::new(register_id)?)
             }
             OperandSize::DoubleWord => {
-                GeneralRegisterWordAndBigger::Register32(Register32::new(register_id))
+                GeneralRegisterWordAndBigger::Register32(Register32::new(register_id)?)
             }
             OperandSize::Word => {
-                GeneralRegisterWordAndBigger::Register16(Register16::new(register_id))
+                GeneralRegisterWordAndBigger::Register16(Register16::new(register_id)?)
             }
-            OperandSize::HalfWord => {
-                panic!("Shouldn't use half words in this function")
-            }
-        }
+            OperandSize::HalfWord => return Err(DisassembleError::UnexpectedOperandSize(1)),
+        })
     }
 
     pub fn widen_register(&self) -> Register64 {
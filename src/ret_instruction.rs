@@ -2,34 +2,31 @@ use capstone::arch::x86::{X86InsnDetail, X86OperandType};
 use capstone::prelude::DetailsArchInsn;
 use itertools::Itertools;
 
+use crate::DisassembleError;
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct RetInstruction {
     to_pop: Option<i16>,
 }
 
 impl RetInstruction {
-    pub fn from_details(detail: &X86InsnDetail) -> Self {
+    pub fn from_details(detail: &X86InsnDetail) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
-        if operands.is_empty() {
-            return Self { to_pop: None };
-        }
-        match &operands[0].op_type {
-            X86OperandType::Reg(_) => {
-                todo!()
-            }
-            X86OperandType::Imm(imm) => RetInstruction {
-                to_pop: Some(
-                    (*imm)
-                        .try_into()
-                        .expect("malformed immediate from capstone"),
-                ),
+        match operands.as_slice() {
+            [] => Ok(Self { to_pop: None }),
+            [operand] => match &operand.op_type {
+                X86OperandType::Imm(imm) => Ok(RetInstruction {
+                    to_pop: Some(
+                        (*imm)
+                            .try_into()
+                            .map_err(|_| DisassembleError::ImmediateOutOfRange(*imm))?,
+                    ),
+                }),
+                X86OperandType::Reg(_) | X86OperandType::Mem(_) | X86OperandType::Invalid => {
+                    Err(DisassembleError::UnexpectedOperandType)
+                }
             },
-            X86OperandType::Mem(_) => {
-                todo!()
-            }
-            X86OperandType::Invalid => {
-                todo!()
-            }
+            _ => Err(DisassembleError::BadOperandCount(operands.len())),
         }
     }
 }
@@ -1,5 +1,6 @@
-use crate::memory_operand::MemoryOperandOrRegister8;
+use crate::memory_operand::{MemoryOperand, MemoryOperandOrRegister8};
 use crate::registers::Register8;
+use crate::DisassembleError;
 use capstone::arch::x86::{X86InsnDetail, X86OperandType};
 use capstone::prelude::DetailsArchInsn;
 use itertools::Itertools;
@@ -10,23 +11,20 @@ pub struct SetBInstruction {
 }
 
 impl SetBInstruction {
-    pub fn from_details(detail: &X86InsnDetail) -> Self {
+    pub fn from_details(detail: &X86InsnDetail) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
         if operands.len() != 1 {
-            todo!()
+            return Err(DisassembleError::BadOperandCount(operands.len()));
         }
         match &operands[0].op_type {
-            X86OperandType::Reg(reg_id) => SetBInstruction {
-                target: MemoryOperandOrRegister8::Reg(Register8::new(*reg_id)),
-            },
-            X86OperandType::Imm(_) => {
-                todo!()
-            }
-            X86OperandType::Mem(_) => {
-                todo!()
-            }
-            X86OperandType::Invalid => {
-                todo!()
+            X86OperandType::Reg(reg_id) => Ok(SetBInstruction {
+                target: MemoryOperandOrRegister8::Reg(Register8::new(*reg_id)?),
+            }),
+            X86OperandType::Mem(mem) => Ok(SetBInstruction {
+                target: MemoryOperandOrRegister8::Mem(MemoryOperand::from_mem(mem)?),
+            }),
+            X86OperandType::Imm(_) | X86OperandType::Invalid => {
+                Err(DisassembleError::UnexpectedOperandType)
             }
         }
     }
@@ -7,6 +7,8 @@ use crate::memory_operand::{
     MemoryOperandOrRegister8,
 };
 use crate::registers::{OperandSize, Register16};
+use crate::utils::imm_i8;
+use crate::DisassembleError;
 
 //https://www.felixcloutier.com/x86/sal:sar:shl:shr
 #[derive(Debug, Eq, PartialEq)]
@@ -54,55 +56,38 @@ pub enum ShlInstruction {
 }
 
 impl ShlInstruction {
-    pub fn from_details(detail: &X86InsnDetail) -> Self {
+    pub fn from_details(detail: &X86InsnDetail) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
         if operands.len() == 1 {
-            todo!()
+            Err(DisassembleError::UnsupportedOperands)
         } else if operands.len() == 2 {
-            let target_operand_size = OperandSize::from_capstone_size(operands[0].size);
+            let target_operand_size = OperandSize::from_capstone_size(operands[0].size)?;
             match &operands[0].op_type {
                 X86OperandType::Reg(reg_id) => match target_operand_size {
-                    OperandSize::QuadWord => {
-                        todo!()
-                    }
-                    OperandSize::DoubleWord => {
-                        todo!()
-                    }
                     OperandSize::Word => {
-                        let target = MemoryOperandOrRegister16::Reg(Register16::new(*reg_id));
+                        let target = MemoryOperandOrRegister16::Reg(Register16::new(*reg_id)?);
                         match &operands[1].op_type {
-                            X86OperandType::Reg(_) => {
-                                todo!()
-                            }
+                            X86OperandType::Reg(_) => Err(DisassembleError::UnsupportedOperands),
                             X86OperandType::Imm(imm) => {
-                                let imm8 =
-                                    (*imm).try_into().expect("malformed data from capstone?");
-                                ShlInstruction::Rm16Imm8 { target, imm8 }
+                                let imm8 = imm_i8(*imm)?;
+                                Ok(ShlInstruction::Rm16Imm8 { target, imm8 })
                             }
-                            X86OperandType::Mem(_) => {
-                                todo!()
-                            }
-                            X86OperandType::Invalid => {
-                                todo!()
+                            X86OperandType::Mem(_) | X86OperandType::Invalid => {
+                                Err(DisassembleError::UnexpectedOperandType)
                             }
                         }
                     }
-                    OperandSize::HalfWord => {
-                        todo!()
+                    OperandSize::QuadWord | OperandSize::DoubleWord | OperandSize::HalfWord => {
+                        Err(DisassembleError::UnsupportedOperands)
                     }
                 },
-                X86OperandType::Imm(_) => {
-                    todo!()
-                }
-                X86OperandType::Mem(_) => {
-                    todo!()
-                }
-                X86OperandType::Invalid => {
-                    todo!()
+                X86OperandType::Mem(_) => Err(DisassembleError::UnsupportedOperands),
+                X86OperandType::Imm(_) | X86OperandType::Invalid => {
+                    Err(DisassembleError::UnexpectedOperandType)
                 }
             }
         } else {
-            todo!()
+            Err(DisassembleError::BadOperandCount(operands.len()))
         }
     }
 }
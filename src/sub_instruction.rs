@@ -2,8 +2,10 @@ use capstone::arch::x86::{X86InsnDetail, X86OperandType};
 use capstone::prelude::DetailsArchInsn;
 use itertools::Itertools;
 
-use crate::memory_operand::{MemoryOperandOrRegister16, MemoryOperandOrRegister64};
-use crate::registers::{OperandSize, Register16, Register64};
+use crate::memory_operand::MemoryOperandOrRegister64;
+use crate::registers::{OperandSize, Register64};
+use crate::utils::imm_i32;
+use crate::DisassembleError;
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum SubInstruction {
@@ -18,67 +20,36 @@ pub enum SubInstruction {
 }
 
 impl SubInstruction {
-    pub fn from_details(detail: &X86InsnDetail) -> Self {
+    pub fn from_details(detail: &X86InsnDetail) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
         if operands.len() != 2 {
-            todo!()
+            return Err(DisassembleError::BadOperandCount(operands.len()));
         }
-        let target_op_size = OperandSize::from_capstone_size(operands[0].size);
+        let target_op_size = OperandSize::from_capstone_size(operands[0].size)?;
         match &operands[0].op_type {
             X86OperandType::Reg(reg_id) => match target_op_size {
                 OperandSize::QuadWord => {
-                    let target = MemoryOperandOrRegister64::Reg(Register64::new(*reg_id));
+                    let target = MemoryOperandOrRegister64::Reg(Register64::new(*reg_id)?);
                     match &operands[1].op_type {
                         X86OperandType::Reg(reg_id) => {
-                            let to_sub = Register64::new(*reg_id);
-                            SubInstruction::R64Rm64 { target, to_sub }
+                            let to_sub = Register64::new(*reg_id)?;
+                            Ok(SubInstruction::R64Rm64 { target, to_sub })
                         }
-                        X86OperandType::Imm(imm) => SubInstruction::R64Imm32 {
+                        X86OperandType::Imm(imm) => Ok(SubInstruction::R64Imm32 {
                             target,
-                            imm32: (*imm)
-                                .try_into()
-                                .expect("Unexpected immediate received from capstone"),
-                        },
-                        X86OperandType::Mem(_) => {
-                            todo!()
-                        }
-                        X86OperandType::Invalid => {
-                            todo!()
-                        }
-                    }
-                }
-                OperandSize::DoubleWord => {
-                    todo!()
-                }
-                OperandSize::Word => {
-                    let target = MemoryOperandOrRegister16::Reg(Register16::new(*reg_id));
-                    match &operands[1].op_type {
-                        X86OperandType::Reg(_) => {
-                            todo!()
-                        }
-                        X86OperandType::Imm(_) => {
-                            todo!()
-                        }
-                        X86OperandType::Mem(_) => {
-                            todo!()
-                        }
-                        X86OperandType::Invalid => {
-                            todo!()
-                        }
+                            imm32: imm_i32(*imm)?,
+                        }),
+                        X86OperandType::Mem(_) => Err(DisassembleError::UnsupportedOperands),
+                        X86OperandType::Invalid => Err(DisassembleError::UnexpectedOperandType),
                     }
                 }
-                OperandSize::HalfWord => {
-                    todo!()
+                OperandSize::DoubleWord | OperandSize::Word | OperandSize::HalfWord => {
+                    Err(DisassembleError::UnsupportedOperands)
                 }
             },
-            X86OperandType::Imm(_) => {
-                todo!()
-            }
-            X86OperandType::Mem(_) => {
-                todo!()
-            }
-            X86OperandType::Invalid => {
-                todo!()
+            X86OperandType::Mem(_) => Err(DisassembleError::UnsupportedOperands),
+            X86OperandType::Imm(_) | X86OperandType::Invalid => {
+                Err(DisassembleError::UnexpectedOperandType)
             }
         }
     }
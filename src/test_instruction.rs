@@ -1,5 +1,7 @@
 use crate::memory_operand::MemoryOperandOrRegister8;
 use crate::registers::{OperandSize, Register8};
+use crate::utils::imm_i8;
+use crate::DisassembleError;
 use capstone::arch::x86::{X86InsnDetail, X86OperandType};
 use capstone::prelude::DetailsArchInsn;
 use itertools::Itertools;
@@ -13,62 +15,40 @@ pub enum TestInstruction {
 }
 
 impl TestInstruction {
-    pub fn from_details(detail: &X86InsnDetail) -> Self {
+    pub fn from_details(detail: &X86InsnDetail) -> Result<Self, DisassembleError> {
         let operands = detail.operands().collect_vec();
         if operands.len() != 2 {
-            todo!()
+            return Err(DisassembleError::BadOperandCount(operands.len()));
         }
-        let first_op_size = OperandSize::from_capstone_size(operands[0].size);
+        let first_op_size = OperandSize::from_capstone_size(operands[0].size)?;
         match &operands[0].op_type {
             X86OperandType::Reg(reg_id) => match first_op_size {
-                OperandSize::QuadWord => {
-                    todo!()
-                }
-                OperandSize::DoubleWord => {
-                    todo!()
-                }
-                OperandSize::Word => {
-                    todo!()
-                }
                 OperandSize::HalfWord => {
-                    let op_one = MemoryOperandOrRegister8::Reg(Register8::new(*reg_id));
-                    let operand_two_size = OperandSize::from_capstone_size(operands[1].size);
+                    let op_one = MemoryOperandOrRegister8::Reg(Register8::new(*reg_id)?);
+                    let operand_two_size = OperandSize::from_capstone_size(operands[1].size)?;
                     match &operands[1].op_type {
-                        X86OperandType::Reg(_) => {
-                            todo!()
-                        }
+                        X86OperandType::Reg(_) => Err(DisassembleError::UnsupportedOperands),
                         X86OperandType::Imm(imm) => match operand_two_size {
-                            OperandSize::QuadWord => {
-                                todo!()
-                            }
-                            OperandSize::DoubleWord => {
-                                todo!()
-                            }
-                            OperandSize::Word => {
-                                todo!()
-                            }
                             OperandSize::HalfWord => {
-                                let imm8 = (*imm).try_into().expect("Bad immediate from capstone");
-                                TestInstruction::Rm8Imm8 { rm8: op_one, imm8 }
+                                let imm8 = imm_i8(*imm)?;
+                                Ok(TestInstruction::Rm8Imm8 { rm8: op_one, imm8 })
+                            }
+                            OperandSize::QuadWord | OperandSize::DoubleWord | OperandSize::Word => {
+                                Err(DisassembleError::UnexpectedOperandSize(operands[1].size))
                             }
                         },
-                        X86OperandType::Mem(_) => {
-                            todo!()
-                        }
-                        X86OperandType::Invalid => {
-                            todo!()
+                        X86OperandType::Mem(_) | X86OperandType::Invalid => {
+                            Err(DisassembleError::UnexpectedOperandType)
                         }
                     }
                 }
+                OperandSize::QuadWord | OperandSize::DoubleWord | OperandSize::Word => {
+                    Err(DisassembleError::UnsupportedOperands)
+                }
             },
-            X86OperandType::Imm(_) => {
-                todo!()
-            }
-            X86OperandType::Mem(_) => {
-                todo!()
-            }
-            X86OperandType::Invalid => {
-                todo!()
+            X86OperandType::Mem(_) => Err(DisassembleError::UnsupportedOperands),
+            X86OperandType::Imm(_) | X86OperandType::Invalid => {
+                Err(DisassembleError::UnexpectedOperandType)
             }
         }
     }
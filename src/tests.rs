@@ -1,8 +1,11 @@
 use std::arch::asm;
 use std::ffi::c_void;
 
-use crate::utils::get_function_bytes;
-use crate::{disassemble, function_end_guard};
+use crate::registers::OperandSize;
+use crate::utils::{get_function_bytes, imm_i32, imm_i8};
+use crate::{
+    disassemble, disassemble_xed, function_end_guard, DisassembleError, ImmediateOperand, X86Scale,
+};
 
 #[no_mangle]
 fn sample_assembly() {
@@ -18,3 +21,60 @@ pub fn disassemble_sample_assembly() {
     let function_bytes = get_function_bytes(raw_function_ptr);
     let res = disassemble(function_bytes, raw_function_ptr as u64).unwrap();
 }
+
+#[no_mangle]
+fn unknown_instruction_assembly() {
+    unsafe {
+        asm!("mov rax, rcx", "cpuid", "pause",);
+        function_end_guard!();
+    }
+}
+
+#[test]
+pub fn disassemble_unknown_instruction() {
+    let raw_function_ptr = unknown_instruction_assembly as *const c_void;
+    let function_bytes = get_function_bytes(raw_function_ptr);
+    let res = disassemble(function_bytes, raw_function_ptr as u64);
+    assert!(matches!(
+        res,
+        Err(DisassembleError::UnknownInstruction { .. })
+    ));
+}
+
+#[test]
+pub fn disassemble_xed_sample_assembly() {
+    let raw_function_ptr = unknown_instruction_assembly as *const c_void;
+    let function_bytes = get_function_bytes(raw_function_ptr);
+    let res = disassemble_xed(function_bytes, raw_function_ptr as u64).unwrap();
+    assert_eq!(res.len(), 3);
+    assert_eq!(res[0].0, raw_function_ptr as u64);
+    assert!(matches!(res[0].1, xed_enum::X86Instruction::MOV(_)));
+    assert!(matches!(res[1].1, xed_enum::X86Instruction::CPUID(_)));
+    assert!(matches!(res[2].1, xed_enum::X86Instruction::PAUSE(_)));
+}
+
+#[test]
+pub fn conversion_helpers_report_errors() {
+    assert!(matches!(imm_i8(-1), Ok(-1)));
+    assert!(matches!(imm_i8(0xff), Ok(-1)));
+    assert!(matches!(
+        imm_i8(0x100),
+        Err(DisassembleError::ImmediateOutOfRange(0x100))
+    ));
+    assert!(matches!(
+        imm_i32(i64::MIN),
+        Err(DisassembleError::ImmediateOutOfRange(i64::MIN))
+    ));
+    assert!(matches!(
+        X86Scale::from_capstone_scale(3),
+        Err(DisassembleError::UnexpectedScale(3))
+    ));
+    assert!(matches!(
+        OperandSize::from_capstone_size(16),
+        Err(DisassembleError::UnexpectedOperandSize(16))
+    ));
+    assert!(matches!(
+        ImmediateOperand::from_capstone_displacement(i64::MAX),
+        Err(DisassembleError::ImmediateOutOfRange(i64::MAX))
+    ));
+}
@@ -1,6 +1,8 @@
 use std::ffi::c_void;
 use std::ptr::slice_from_raw_parts;
 
+use crate::DisassembleError;
+
 fn find_subsequence(haystack: &[u8], needle: &[u8]) -> Option<usize> {
     haystack
         .windows(needle.len())
@@ -38,38 +40,38 @@ pub fn get_function_bytes(raw_function_ptr: *const c_void) -> &'static [u8] {
     function_bytes
 }
 
-pub fn imm_i8(imm: i64) -> i8 {
+pub fn imm_i8(imm: i64) -> Result<i8, DisassembleError> {
     if imm < 0 {
         imm.try_into()
-            .expect("Unexpectedly large constant from capstone")
+            .map_err(|_| DisassembleError::ImmediateOutOfRange(imm))
     } else {
         let unsigned: u8 = imm
             .try_into()
-            .expect("Unexpectedly large constant from capstone");
-        unsigned as i8
+            .map_err(|_| DisassembleError::ImmediateOutOfRange(imm))?;
+        Ok(unsigned as i8)
     }
 }
 
-pub fn imm_i16(imm: i64) -> i16 {
+pub fn imm_i16(imm: i64) -> Result<i16, DisassembleError> {
     if imm < 0 {
         imm.try_into()
-            .expect("Unexpectedly large constant from capstone")
+            .map_err(|_| DisassembleError::ImmediateOutOfRange(imm))
     } else {
         let unsigned: u16 = imm
             .try_into()
-            .expect("Unexpectedly large constant from capstone");
-        unsigned as i16
+            .map_err(|_| DisassembleError::ImmediateOutOfRange(imm))?;
+        Ok(unsigned as i16)
     }
 }
 
-pub fn imm_i32(imm: i64) -> i32 {
+pub fn imm_i32(imm: i64) -> Result<i32, DisassembleError> {
     if imm < 0 {
         imm.try_into()
-            .expect("Unexpectedly large constant from capstone")
+            .map_err(|_| DisassembleError::ImmediateOutOfRange(imm))
     } else {
         let unsigned: u32 = imm
             .try_into()
-            .expect("Unexpectedly large constant from capstone");
-        unsigned as i32
+            .map_err(|_| DisassembleError::ImmediateOutOfRange(imm))?;
+        Ok(unsigned as i32)
     }
 }
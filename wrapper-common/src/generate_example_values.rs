@@ -1,7 +1,7 @@
 use std::iter;
 use enum_iterator::all;
-use crate::memory_operand::{GeneralReg, X86Scale};
-use crate::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8, RegSegment};
+use crate::memory_operand::{GeneralReg, GeneralReg163264, GeneralReg3264, X86Scale};
+use crate::registers::{Reg16WithoutRIP, Reg16WithRIP, Reg32WithoutRIP, Reg32WithRIP, Reg64WithoutRIP, Reg64WithRIP, Reg8, RegBnd, RegBndConfig, RegControl, RegControlExtra, RegDebug, RegFloat, RegFloatControl, RegMask, RegMMX, RegSegment, RegSegmentBase, RegSpecial, RegTMM, RegXMM, RegYMM, RegZMM};
 
 pub trait GenerateExampleValues {
     fn generate() -> impl Iterator<Item=Self>;
@@ -44,6 +44,114 @@ impl GenerateExampleValues for Reg8 {
     }
 }
 
+impl GenerateExampleValues for RegMMX {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegXMM {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegYMM {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegZMM {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegTMM {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegMask {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for Reg64WithoutRIP {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for Reg32WithoutRIP {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for Reg16WithoutRIP {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegFloat {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegFloatControl {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegBnd {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegBndConfig {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegSpecial {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegControl {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegControlExtra {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegSegmentBase {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
+impl GenerateExampleValues for RegDebug {
+    fn generate() -> impl Iterator<Item=Self> {
+        all::<Self>()
+    }
+}
+
 impl GenerateExampleValues for X86Scale {
     fn generate() -> impl Iterator<Item=Self> {
         all::<Self>()
@@ -62,6 +170,23 @@ impl GenerateExampleValues for GeneralReg {
     }
 }
 
+impl GenerateExampleValues for GeneralReg3264 {
+    fn generate() -> impl Iterator<Item=Self> {
+        let reg64 = Reg64WithRIP::generate().map(GeneralReg3264::Reg64);
+        let reg32 = Reg32WithRIP::generate().map(GeneralReg3264::Reg32);
+        reg64.chain(reg32)
+    }
+}
+
+impl GenerateExampleValues for GeneralReg163264 {
+    fn generate() -> impl Iterator<Item=Self> {
+        let reg64 = Reg64WithRIP::generate().map(GeneralReg163264::Reg64);
+        let reg32 = Reg32WithRIP::generate().map(GeneralReg163264::Reg32);
+        let reg16 = Reg16WithRIP::generate().map(GeneralReg163264::Reg16);
+        reg64.chain(reg32).chain(reg16)
+    }
+}
+
 impl GenerateExampleValues for i64 {
     fn generate() -> impl Iterator<Item=Self> {
         vec![i64::MIN, 0, 1, 2, i64::MAX].into_iter()
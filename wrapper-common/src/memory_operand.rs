@@ -70,7 +70,7 @@ impl X86Scale {
             X86Scale::One => 1,
             X86Scale::Two => 2,
             X86Scale::Four => 4,
-            X86Scale::Eight => 9,
+            X86Scale::Eight => 8,
         }
     }
 }
@@ -94,6 +94,8 @@ impl GeneralReg {
         }
     }
 
+    /// None if `reg` isn't a general purpose register, or is one whose width has to come from `width` and
+    /// `width` is missing.
     pub fn try_new(reg: xed_reg_enum_t, width: Option<u32>) -> Option<Self> {
         use xed_sys::*;
         let class: xed_reg_class_enum_t = unsafe { xed_reg_class(reg) };
@@ -102,22 +104,32 @@ impl GeneralReg {
                 if reg >= XED_REG_GPR64_FIRST && reg <= XED_REG_GPR64_LAST{
                     return Some(GeneralReg::Reg64(Reg64WithRIP::try_new(reg)?))
                 }
+                if reg >= XED_REG_GPR32_FIRST && reg <= XED_REG_GPR32_LAST{
+                    return Some(GeneralReg::Reg32(Reg32WithRIP::try_new(reg)?))
+                }
+                if reg >= XED_REG_GPR16_FIRST && reg <= XED_REG_GPR16_LAST{
+                    return Some(GeneralReg::Reg16(Reg16WithRIP::try_new(reg)?))
+                }
+                if (reg >= XED_REG_GPR8_FIRST && reg <= XED_REG_GPR8_LAST) || (reg >= XED_REG_GPR8h_FIRST && reg <= XED_REG_GPR8h_LAST){
+                    return Some(GeneralReg::Reg8(Reg8::try_new(reg)?))
+                }
                 match width {
-                    None => todo!(),
                     Some(64) => GeneralReg::Reg64(Reg64WithRIP::try_new(reg)?),
                     Some(32) => GeneralReg::Reg32(Reg32WithRIP::try_new(reg)?),
                     Some(16) => GeneralReg::Reg16(Reg16WithRIP::try_new(reg)?),
-                    width => todo!("{width:?}")
+                    _ => return None,
                 }
             }
             XED_REG_CLASS_GPR8 => GeneralReg::Reg8(Reg8::try_new(reg)?),
             XED_REG_CLASS_GPR16 => GeneralReg::Reg16(Reg16WithRIP::try_new(reg)?),
             XED_REG_CLASS_GPR32 => GeneralReg::Reg32(Reg32WithRIP::try_new(reg)?),
             XED_REG_CLASS_GPR64 => GeneralReg::Reg64(Reg64WithRIP::try_new(reg)?),
-            XED_REG_CLASS_INVALID => {
-                dbg!(reg);
-                todo!()
-            }
+            XED_REG_CLASS_IP => match reg {
+                XED_REG_RIP => GeneralReg::Reg64(Reg64WithRIP::RIP),
+                XED_REG_EIP => GeneralReg::Reg32(Reg32WithRIP::EIP),
+                XED_REG_IP => GeneralReg::Reg16(Reg16WithRIP::IP),
+                _ => return None,
+            },
             _ => return None,
         })
     }
@@ -150,7 +162,10 @@ impl GeneralReg3264 {
     }
 
     pub fn to_xed(&self) -> xed_reg_enum_t{
-        todo!("self:?")
+        match self {
+            GeneralReg3264::Reg64(reg) => reg.to_xed(),
+            GeneralReg3264::Reg32(reg) => reg.to_xed(),
+        }
     }
 }
 
@@ -174,7 +189,11 @@ impl GeneralReg163264 {
     }
 
     pub fn to_xed(&self) -> xed_reg_enum_t{
-        todo!("self:?")
+        match self {
+            GeneralReg163264::Reg64(reg) => reg.to_xed(),
+            GeneralReg163264::Reg32(reg) => reg.to_xed(),
+            GeneralReg163264::Reg16(reg) => reg.to_xed(),
+        }
     }
 }
 
@@ -1,15 +1,16 @@
 use std::collections::{BTreeSet, HashSet};
+use std::ops::Range;
 
 use capstone::arch::x86::X86Operand;
 use capstone::RegId;
-use enum_iterator::Sequence;
+use enum_iterator::{all, Sequence};
 use proc_macro2::Ident;
 use quote::format_ident;
 use serde::{Deserialize, Serialize};
 use xed_sys::*;
 use crate::operand_width::XedOperandWidth;
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, Sequence)]
 pub enum RegMMX {
     MM0,
     MM1,
@@ -41,7 +42,7 @@ impl RegMMX {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize, Sequence)]
 pub enum RegXMM {
     XMM0,
     XMM1,
@@ -228,7 +229,7 @@ impl RegXMM {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, Sequence)]
 pub enum RegYMM {
     YMM0,
     YMM1,
@@ -382,7 +383,7 @@ impl RegYMM {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize, Sequence)]
 pub enum RegZMM {
     ZMM0,
     ZMM1,
@@ -499,7 +500,7 @@ impl RegZMM {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, Sequence)]
 pub enum RegTMM {
     TMM0,
     TMM1,
@@ -531,7 +532,7 @@ impl RegTMM {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq,Ord, PartialOrd, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq,Ord, PartialOrd, Serialize, Deserialize, Sequence)]
 pub enum RegMask {
     K0,
     K1,
@@ -688,7 +689,7 @@ impl Reg64WithRIP {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, Sequence)]
 pub enum Reg64WithoutRIP {
     RAX,
     RBX,
@@ -1171,7 +1172,7 @@ impl Reg8 {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize, Sequence)]
 pub enum RegFloat {
     ST0,
     ST1,
@@ -1199,11 +1200,20 @@ impl RegFloat {
     }
 
     pub fn to_xed(&self) -> xed_reg_enum_t {
-        todo!("self:?")
+        match self {
+            RegFloat::ST0 => XED_REG_ST0,
+            RegFloat::ST1 => XED_REG_ST1,
+            RegFloat::ST2 => XED_REG_ST2,
+            RegFloat::ST3 => XED_REG_ST3,
+            RegFloat::ST4 => XED_REG_ST4,
+            RegFloat::ST5 => XED_REG_ST5,
+            RegFloat::ST6 => XED_REG_ST6,
+            RegFloat::ST7 => XED_REG_ST7,
+        }
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq,Ord, PartialOrd, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq,Ord, PartialOrd, Serialize, Deserialize, Sequence)]
 pub enum RegFloatControl {
     X87CONTROL,
     X87STATUS,
@@ -1242,7 +1252,7 @@ impl RegFloatControl {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, Sequence)]
 pub enum RegBnd {
     BND0,
     BND1,
@@ -1266,7 +1276,7 @@ impl RegBnd {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, Sequence)]
 pub enum RegBndConfig {
     BNDCFGU,
     BNDSTATUS,
@@ -1282,7 +1292,7 @@ impl RegBndConfig {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize, Sequence)]
 pub enum RegSpecial {
     GDTR,
     LDTR,
@@ -1330,7 +1340,7 @@ impl RegSpecial {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize, Sequence)]
 pub enum RegControl {
     //several of these exist in encoding but may not actually exist
     CR0,
@@ -1400,7 +1410,7 @@ impl RegControl {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize, Sequence)]
 pub enum RegControlExtra {
     EFER,
     XCR0,
@@ -1474,7 +1484,7 @@ impl RegSegment {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,Serialize, Deserialize, Sequence)]
 pub enum RegSegmentBase {
     FSBase,
     GSBase,
@@ -1497,7 +1507,7 @@ impl RegSegmentBase {
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, Sequence)]
 pub enum RegDebug {
     DR0,
     DR1,
@@ -1972,12 +1982,12 @@ impl RegisterType {
                 Register::Segment(_) => true,
                 _ => false,
             },
-            RegisterType::SingleSegment(_seg) => match reg {
-                Register::Segment(_) => todo!(),
+            RegisterType::SingleSegment(single_seg) => match reg {
+                Register::Segment(seg) => seg == single_seg,
                 _ => false,
             },
-            RegisterType::SomeSegment(_some_segs) => match reg {
-                Register::Segment(_) => todo!(),
+            RegisterType::SomeSegment(some_segs) => match reg {
+                Register::Segment(seg) => some_segs.contains(&seg),
                 _ => false,
             },
             RegisterType::AllDebug => match reg {
@@ -2101,10 +2111,11 @@ pub enum Register {
     Debug(RegDebug),
     Control(RegControl),
     ControlExtra(RegControlExtra),
-    Segment(()),
+    Segment(RegSegment),
 }
 
 impl Register {
+    /// `None` for flags and xed's pseudo registers, which don't correspond to a single architectural register.
     pub fn try_new(reg: xed_reg_enum_t) -> Option<Self> {
         let class: xed_reg_class_enum_t = unsafe { xed_reg_class(reg) };
         Some(match class {
@@ -2113,23 +2124,37 @@ impl Register {
             XED_REG_CLASS_BOUND => Register::Bnd(RegBnd::try_new(reg)?),
             XED_REG_CLASS_CR => Register::Control(RegControl::try_new(reg)?),
             XED_REG_CLASS_DR => Register::Debug(RegDebug::try_new(reg)?),
-            XED_REG_CLASS_FLAGS => todo!(),
-            XED_REG_CLASS_GPR => Register::GP64(Reg64WithRIP::try_new(reg)?),
+            XED_REG_CLASS_FLAGS => return None,
+            XED_REG_CLASS_GPR => {
+                if let Some(reg) = Reg64WithRIP::try_new(reg) {
+                    Register::GP64(reg)
+                } else if let Some(reg) = Reg32WithRIP::try_new(reg) {
+                    Register::GP32(reg)
+                } else if let Some(reg) = Reg16WithRIP::try_new(reg) {
+                    Register::GP16(reg)
+                } else {
+                    Register::GP8(Reg8::try_new(reg)?)
+                }
+            }
             XED_REG_CLASS_GPR16 => Register::GP16(Reg16WithRIP::try_new(reg)?),
             XED_REG_CLASS_GPR32 => Register::GP32(Reg32WithRIP::try_new(reg)?),
             XED_REG_CLASS_GPR64 => Register::GP64(Reg64WithRIP::try_new(reg)?),
             XED_REG_CLASS_GPR8 => Register::GP8(Reg8::try_new(reg)?),
-            XED_REG_CLASS_IP => todo!(),
+            XED_REG_CLASS_IP => match reg {
+                XED_REG_RIP => Register::GP64(Reg64WithRIP::RIP),
+                XED_REG_EIP => Register::GP32(Reg32WithRIP::EIP),
+                _ => Register::GP16(Reg16WithRIP::try_new(reg)?),
+            },
             XED_REG_CLASS_MASK => Register::Mask(RegMask::try_new(reg)?),
             XED_REG_CLASS_MMX => Register::Mmx(RegMMX::try_new(reg)?),
             XED_REG_CLASS_MSR => Register::Special(RegSpecial::try_new(reg)?),
             XED_REG_CLASS_MXCSR => Register::Special(RegSpecial::try_new(reg)?),
-            XED_REG_CLASS_PSEUDO => todo!(),
-            XED_REG_CLASS_PSEUDOX87 => todo!(),
-            XED_REG_CLASS_SR => todo!(),
-            XED_REG_CLASS_TMP => todo!(),
+            XED_REG_CLASS_PSEUDO => return None,
+            XED_REG_CLASS_PSEUDOX87 => Register::FloatControl(RegFloatControl::try_new(reg)?),
+            XED_REG_CLASS_SR => Register::Segment(RegSegment::try_new(reg)?),
+            XED_REG_CLASS_TMP => return None,
             XED_REG_CLASS_TREG => Register::Tmm(RegTMM::try_new(reg)?),
-            XED_REG_CLASS_UIF => todo!(),
+            XED_REG_CLASS_UIF => Register::Special(RegSpecial::try_new(reg)?),
             XED_REG_CLASS_X87 => Register::Float(RegFloat::try_new(reg)?),
             XED_REG_CLASS_XCR => Register::ControlExtra(RegControlExtra::try_new(reg)?),
             XED_REG_CLASS_XMM => Register::Xmm(RegXMM::try_new(reg)?),
@@ -2616,3 +2641,147 @@ impl Register {
         }
     }
 }
+
+/// Aliasing between registers. GPRs alias their 64 bit register, and XMM/YMM registers alias the low bits of
+/// the ZMM register with the same number. Everything else is treated as standalone. In particular MMX registers
+/// alias the x87 data registers, but ST(i) names a register relative to the stack top, so that can't be
+/// expressed statically.
+impl Register {
+    /// The full width register this is a part of, e.g. RAX for AH or ZMM3 for XMM3. Registers which aren't part
+    /// of a larger register are their own parent.
+    pub fn parent(&self) -> Register {
+        match self {
+            Register::GP64(_) | Register::Zmm(_) => *self,
+            Register::GP32(reg) => Register::GP64(Self::enclosing_gp64(reg.to_xed())),
+            Register::GP16(reg) => Register::GP64(Self::enclosing_gp64(reg.to_xed())),
+            Register::GP8(reg) => Register::GP64(Self::enclosing_gp64(reg.to_xed())),
+            Register::Xmm(reg) => Register::Zmm(reg.widen_to_zmm()),
+            Register::Ymm(reg) => Register::Zmm(reg.widen_to_zmm()),
+            _ => *self,
+        }
+    }
+
+    fn enclosing_gp64(reg: xed_reg_enum_t) -> Reg64WithRIP {
+        Reg64WithRIP::try_new(unsafe { xed_get_largest_enclosing_register(reg) }).unwrap()
+    }
+
+    pub fn width_bits(&self) -> u32 {
+        match self {
+            Register::Mmx(_) => 64,
+            Register::Xmm(_) => 128,
+            Register::Ymm(_) => 256,
+            Register::Zmm(_) => 512,
+            Register::Tmm(_) => 8192,
+            Register::Mask(_) => 64,
+            Register::GP64(_) => 64,
+            Register::GP32(_) => 32,
+            Register::GP16(_) => 16,
+            Register::GP8(_) => 8,
+            Register::Float(_) => 80,
+            Register::FloatControl(_) => 16,
+            Register::Bnd(_) => 128,
+            Register::BndConfig(_) => 64,
+            Register::Special(_) => 64,
+            Register::Debug(_) => 64,
+            Register::Control(_) => 64,
+            Register::ControlExtra(_) => 64,
+            Register::Segment(_) => 16,
+        }
+    }
+
+    /// Bits of [`Register::parent`] this register occupies, e.g. `8..16` for AH.
+    pub fn bit_range(&self) -> Range<u32> {
+        match self {
+            Register::GP8(Reg8::AH | Reg8::BH | Reg8::CH | Reg8::DH) => 8..16,
+            _ => 0..self.width_bits(),
+        }
+    }
+
+    /// Whether the two registers share any bits, so AL and AH don't overlap but both overlap EAX.
+    pub fn overlaps(&self, other: &Register) -> bool {
+        if self.parent() != other.parent() {
+            return false;
+        }
+        let self_range = self.bit_range();
+        let other_range = other.bit_range();
+        self_range.start < other_range.end && other_range.start < self_range.end
+    }
+
+    /// Every other register entirely contained in this one, e.g. EAX, AX, AH and AL for RAX.
+    pub fn sub_registers(&self) -> Vec<Register> {
+        let parent = self.parent();
+        let range = self.bit_range();
+        let candidates: Vec<Register> = match parent {
+            Register::GP64(_) => all::<Reg64WithRIP>().map(Register::GP64)
+                .chain(all::<Reg32WithRIP>().map(Register::GP32))
+                .chain(all::<Reg16WithRIP>().map(Register::GP16))
+                .chain(all::<Reg8>().map(Register::GP8))
+                .collect(),
+            Register::Zmm(_) => all::<RegZMM>().map(Register::Zmm)
+                .chain(all::<RegYMM>().map(Register::Ymm))
+                .chain(all::<RegXMM>().map(Register::Xmm))
+                .collect(),
+            _ => vec![],
+        };
+        candidates.into_iter()
+            .filter(|candidate| candidate != self && candidate.parent() == parent)
+            .filter(|candidate| {
+                let candidate_range = candidate.bit_range();
+                range.start <= candidate_range.start && candidate_range.end <= range.end
+            })
+            .collect()
+    }
+
+    /// Whether a write to this register clears the rest of its parent, rather than leaving those bits alone. 32 bit
+    /// GPR writes zero the upper half of the 64 bit register, and VEX/EVEX encoded writes zero everything above
+    /// the destination up to the top of the ZMM register. 8/16 bit GPR writes and legacy SSE writes to XMM
+    /// registers merge. Registers which are their own parent trivially return true.
+    pub fn write_zero_extends(&self, vex_encoded: bool) -> bool {
+        match self {
+            Register::GP16(_) | Register::GP8(_) => false,
+            Register::Xmm(_) => vex_encoded,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registers::{Reg16WithRIP, Reg32WithRIP, Reg64WithRIP, Reg8, Register, RegXMM, RegYMM, RegZMM};
+
+    #[test]
+    fn gpr_aliasing() {
+        let rax = Register::GP64(Reg64WithRIP::RAX);
+        let eax = Register::GP32(Reg32WithRIP::EAX);
+        let al = Register::GP8(Reg8::AL);
+        let ah = Register::GP8(Reg8::AH);
+        assert_eq!(ah.parent(), rax);
+        assert_eq!(Register::GP16(Reg16WithRIP::R9W).parent(), Register::GP64(Reg64WithRIP::R9));
+        assert_eq!(Register::GP8(Reg8::SPL).parent(), Register::GP64(Reg64WithRIP::RSP));
+        assert_eq!(ah.bit_range(), 8..16);
+        assert!(!al.overlaps(&ah));
+        assert!(al.overlaps(&eax) && ah.overlaps(&eax));
+        assert!(!al.overlaps(&Register::GP8(Reg8::BL)));
+        let mut sub_registers = rax.sub_registers();
+        sub_registers.sort_by_key(|reg| reg.width_bits());
+        assert_eq!(sub_registers, vec![al, ah, Register::GP16(Reg16WithRIP::AX), eax]);
+        assert_eq!(Register::GP16(Reg16WithRIP::AX).sub_registers().len(), 2);
+        assert!(eax.write_zero_extends(false));
+        assert!(!al.write_zero_extends(false));
+    }
+
+    #[test]
+    fn vector_aliasing() {
+        let xmm3 = Register::Xmm(RegXMM::XMM3);
+        let ymm3 = Register::Ymm(RegYMM::YMM3);
+        let zmm3 = Register::Zmm(RegZMM::ZMM3);
+        assert_eq!(xmm3.parent(), zmm3);
+        assert_eq!(ymm3.parent(), zmm3);
+        assert!(xmm3.overlaps(&ymm3));
+        assert!(!xmm3.overlaps(&Register::Xmm(RegXMM::XMM4)));
+        assert_eq!(zmm3.sub_registers().len(), 2);
+        assert_eq!(ymm3.sub_registers(), vec![xmm3]);
+        assert!(!xmm3.write_zero_extends(false));
+        assert!(xmm3.write_zero_extends(true));
+    }
+}
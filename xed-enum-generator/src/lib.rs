@@ -5,7 +5,8 @@ use itertools::Itertools;
 use proc_macro2::Ident;
 use quote::{format_ident, quote};
 
-use wrapper_common::registers::OperandWidth;
+use wrapper_common::registers::{OperandWidth, RegisterType};
+use xed_sys::xed_reg_enum_t;
 use xed_wrapper::{FieldType, Variant, xed_data};
 
 //todo have a pattern expander which expands specific instances of MEMZ/IMMV
@@ -141,7 +142,7 @@ pub fn enum_from_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         FieldType::Mem(_) | FieldType::AGen => {
                             quote! {
                                 let instr_template = xed_decoded_inst_inst(xed);
-                                MemoryOperands::from_xed(xed, instr_template, #mem_idx)
+                                MemoryOperands::from_xed(xed, instr_template, #mem_idx)?
                             }
                         }
                         FieldType::Reg(register_type) => {
@@ -161,18 +162,18 @@ pub fn enum_from_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         }
                         FieldType::Ptr => {
                             quote! {
-                                Immediate::from_xed(xed, #second_immediate)
+                                Immediate::from_xed(xed, #second_immediate)?
                             }
                         }
                         FieldType::Imm(width) => {
                             let type_ = imm_width_to_indent(width.clone());
                             quote! {
-                                #type_::from_xed(xed, #second_immediate)
+                                #type_::from_xed(xed, #second_immediate)?
                             }
                         }
                         FieldType::RelBR => {
                             quote! {
-                                RelativeBr::from_xed(xed)
+                                RelativeBr::from_xed(xed)?
                             }
                         }
                     });
@@ -190,19 +191,20 @@ pub fn enum_from_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 });
             }
             closure_vec.push(quote! {
-                vec![#(Box::new(#closures) as Box<dyn FnOnce() -> Option<Self>>),*].into_iter().find_map(|inner|inner()).unwrap()
+                vec![#(Box::new(#closures) as Box<dyn FnOnce() -> Option<Self>>),*].into_iter().find_map(|inner|inner())
+                    .ok_or_else(|| DecodeError::Unsupported(CStr::from_ptr(xed_iform_enum_t2str(iform)).to_str().unwrap().to_string()))
             });
         }
         impls.push(quote! {
             impl #instruction_enum_name {
-                pub fn from_xed(xed: *const xed_decoded_inst_t) -> Self {
+                pub fn from_xed(xed: *const xed_decoded_inst_t) -> Result<Self, DecodeError> {
                     unsafe {
                         let iform = xed_decoded_inst_get_iform_enum(xed);
                         match iform {
                             #(#iform_nums => {
                                 #closure_vec
                             }),*,
-                            _ => panic!()
+                            other => Err(DecodeError::Unsupported(CStr::from_ptr(xed_iform_enum_t2str(other)).to_str().unwrap().to_string()))
                         }
                     }
                 }
@@ -211,19 +213,20 @@ pub fn enum_from_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
     }
 
     proc_macro::TokenStream::from(quote! {
-        use xed_sys::{xed_decoded_inst_t,xed_decoded_inst_get_reg, xed_decoded_inst_get_iform_enum, xed_decoded_inst_inst, xed_decoded_inst_get_iclass, xed_iclass_enum_t2str, xed_decoded_inst_get_operand_width, xed_tables_init};
+        use xed_sys::{xed_decoded_inst_t,xed_decoded_inst_get_reg, xed_decoded_inst_get_iform_enum, xed_decoded_inst_inst, xed_decoded_inst_get_iclass, xed_iclass_enum_t2str, xed_iform_enum_t2str, xed_decoded_inst_get_operand_width, xed_tables_init};
         use xed_wrapper::operands::*;
         use std::ffi::CStr;
 
         impl X86Instruction {
-            pub fn from_xed(xed: *const xed_decoded_inst_t) -> Self {
+            /// Fails with [`DecodeError::Unsupported`] for instructions which have no variant.
+            pub fn from_xed(xed: *const xed_decoded_inst_t) -> Result<Self, DecodeError> {
                 crate::START.call_once(||{
                     unsafe { xed_tables_init(); }
                 });
                 let iclass = unsafe { xed_decoded_inst_get_iclass(xed) };
                 match iclass {
-                    #(#iclass_nums => Self::#iclass_names1(#iclass_names2::from_xed(xed))),*,
-                    other => panic!("{other} {}",unsafe { CStr::from_ptr(xed_iclass_enum_t2str(other)).to_str().unwrap() })
+                    #(#iclass_nums => Ok(Self::#iclass_names1(#iclass_names2::from_xed(xed)?))),*,
+                    other => Err(DecodeError::Unsupported(unsafe { CStr::from_ptr(xed_iclass_enum_t2str(other)) }.to_str().unwrap().to_string()))
                 }
             }
         }
@@ -264,17 +267,14 @@ pub fn enum_to_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
                             assert_eq!(mem.len(), 1);
                             let mem_xed = mem.into_iter().next().unwrap().to_xed_width_bits() as c_uint;
                             variant_field_constructors.push(quote! {
-                                if let MemoryOperands::SIBAddressing { segment, base, scale, index, disp, disp_width } = #variant_ident.clone(){
-                                    xed_mem_gbisd(
-                                        segment.as_ref().map(|seg|seg.to_xed()).unwrap_or(0),
-                                        base.to_xed(),
-                                        index.as_ref().map(|index|index.to_xed()).unwrap_or(0),
-                                        scale.to_xed(),
-                                        xed_disp(disp, disp_width),
-                                        #mem_xed)
-                                } else {
-                                    todo!()
-                                }
+                                let MemoryOperands::SIBAddressing { segment, base, scale, index, disp, disp_width } = #variant_ident.clone();
+                                xed_mem_gbisd(
+                                    segment.as_ref().map(|seg|seg.to_xed()).unwrap_or(0),
+                                    base.as_ref().map(|base|base.to_xed()).unwrap_or(0),
+                                    index.as_ref().map(|index|index.to_xed()).unwrap_or(0),
+                                    scale.to_xed(),
+                                    xed_disp(disp, disp_width),
+                                    #mem_xed)
                             });
                         }
                         FieldType::Reg(_) => {
@@ -297,20 +297,31 @@ pub fn enum_to_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         }
                         FieldType::RelBR => {
                             variant_field_constructors.push(quote! {
-                            todo!()
+                            #variant_ident.to_xed()
                         });
                             second_immediate = true;
                         }
                         FieldType::Ptr => {
                             variant_field_constructors.push(quote! {
-                            todo!()
-                        });
+                                match #variant_ident.to_xed_ptr() {
+                                    Some(operand) => operand,
+                                    None => return Err(EncodeError::OperandOutOfRange { instruction: X86Instruction::#instruction_enum_name(*self) }),
+                                }
+                            });
                             second_immediate = true;
                         }
                         FieldType::AGen => {
+                            // agen operands are never dereferenced, so the memory width is just the address width
                             variant_field_constructors.push(quote! {
-                            todo!()
-                        })
+                                let MemoryOperands::SIBAddressing { segment, base, scale, index, disp, disp_width } = #variant_ident.clone();
+                                xed_mem_gbisd(
+                                    segment.as_ref().map(|seg|seg.to_xed()).unwrap_or(0),
+                                    base.as_ref().map(|base|base.to_xed()).unwrap_or(0),
+                                    index.as_ref().map(|index|index.to_xed()).unwrap_or(0),
+                                    scale.to_xed(),
+                                    xed_disp(disp, disp_width),
+                                    encode_context.address_width_bits())
+                            })
                         }
                     }
                 }
@@ -323,7 +334,7 @@ pub fn enum_to_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
         }
         impls.push(quote! {
             impl #instruction_enum_name {
-                pub fn to_xed(&self, encode_context: &mut EncodeDecodeContext) -> xed_encoder_request_t {
+                pub fn to_xed(&self, encode_context: &mut EncodeDecodeContext) -> Result<xed_encoder_request_t, EncodeError> {
                     crate::START.call_once(||{
                         unsafe { xed_tables_init(); }
                     });
@@ -341,10 +352,10 @@ pub fn enum_to_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         xed_encoder_request_zero_set_mode(encoder_request.as_mut_ptr(), xed_state.as_ptr());
                         let convert_ok = xed_convert_to_encoder_request(encoder_request.as_mut_ptr(), encoder_inst.as_mut_ptr()) != 0;
                         if !convert_ok {
-                            panic!()
+                            return Err(EncodeError::ConversionFailed { instruction: X86Instruction::#instruction_enum_name(*self) });
                         }
 
-                        encoder_request.assume_init()
+                        Ok(encoder_request.assume_init())
                     }
                 }
             }
@@ -357,10 +368,10 @@ pub fn enum_to_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
         #(#impls)*
 
         impl X86Instruction {
-            pub fn to_xed(&self, encode_context: &mut EncodeDecodeContext) -> xed_encoder_request_t {
+            pub fn to_xed(&self, encode_context: &mut EncodeDecodeContext) -> Result<xed_encoder_request_t, EncodeError> {
                 match self {
                     #(Self::#iclass_names1(inner) => inner.to_xed(encode_context)),*,
-                    _ => panic!()
+                    _ => Err(EncodeError::Unsupported { instruction: *self })
                 }
             }
         }
@@ -371,4 +382,361 @@ pub fn enum_to_xed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
 // #[proc_macro]
 // pub fn arbitrary(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
 //     todo!()
-// }
\ No newline at end of file
+// }
+
+/// Registers an operand is restricted to, for operand types which are narrower than their rust type.
+fn allowed_registers(register_type: &RegisterType) -> Option<Vec<xed_reg_enum_t>> {
+    Some(match register_type {
+        RegisterType::SomeXmm(regs) => regs.iter().map(|reg| reg.to_xed()).collect(),
+        RegisterType::SingleXmm(reg) => vec![reg.to_xed()],
+        RegisterType::SomeZmm(regs) => regs.iter().map(|reg| reg.to_xed()).collect(),
+        RegisterType::SingleGP64(reg) => vec![reg.to_xed()],
+        RegisterType::SomeGP32(regs) => regs.iter().map(|reg| reg.to_xed()).collect(),
+        RegisterType::SingleGP32(reg) => vec![reg.to_xed()],
+        RegisterType::SomeGP16(regs) => regs.iter().map(|reg| reg.to_xed()).collect(),
+        RegisterType::SingleGP16(reg) => vec![reg.to_xed()],
+        RegisterType::SomeGP8(regs) => regs.iter().map(|reg| reg.to_xed()).collect(),
+        RegisterType::SingleGP8(reg) => vec![reg.to_xed()],
+        RegisterType::SingleFloat(reg) => vec![reg.to_xed()],
+        RegisterType::SingleSegment(reg) => vec![reg.to_xed()],
+        RegisterType::SomeSegment(regs) => regs.iter().map(|reg| reg.to_xed()).collect(),
+        RegisterType::SomeControl(regs) => regs.iter().map(|reg| reg.to_xed()).collect(),
+        RegisterType::SingleControl(reg) => vec![reg.to_xed()],
+        RegisterType::SingleSpecial(reg) => vec![reg.to_xed()],
+        RegisterType::SingleFloatControl(reg) => vec![reg.to_xed()],
+        _ => return None,
+    })
+}
+
+#[proc_macro]
+pub fn round_trip_samples(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let data = xed_data();
+    let mut impls = vec![];
+    let mut iclass_names = vec![];
+    for (instruction_name, top_level_instruction) in data {
+        let instruction_enum_name = instruction_name.proc_macro_safe_name();
+        iclass_names.push(instruction_enum_name.clone());
+        let mut iforms = vec![];
+        let mut variant_names = vec![];
+        let mut variant_field_names = vec![];
+        let mut variant_types = vec![];
+        let mut variant_samples = vec![];
+        for (iform, variants) in top_level_instruction.variants.iter().sorted_by_key(|(iform, _)| *iform) {
+            for (variant_name, Variant { operands, iform: _, effective_operand_width: _ }) in variants.iter().sorted_by_key(|(vn, _)| *vn) {
+                iforms.push(*iform);
+                variant_names.push(variant_name.proc_macro_safe_name());
+                let mut field_names = vec![];
+                let mut types = vec![];
+                let mut samples = vec![];
+                for (operand_i, operand) in operands.iter().sorted_by_key(|(i, _)| *i) {
+                    field_names.push(format_ident!("operand_{}", operand_i));
+                    let (type_, sample) = match &operand.field_type {
+                        FieldType::Mem(_) | FieldType::AGen => {
+                            (format_ident!("MemoryOperands"), quote! { crate::round_trip::memory_operand_samples() })
+                        }
+                        FieldType::Reg(register_type) => {
+                            let type_ = register_type.type_to_rust_type();
+                            let sample = match allowed_registers(register_type) {
+                                Some(allowed) => quote! {
+                                    #type_::generate().filter(|reg| [#(#allowed),*].contains(&reg.to_xed())).collect()
+                                },
+                                None => quote! { #type_::generate().collect() },
+                            };
+                            (type_, sample)
+                        }
+                        FieldType::Imm(width) => {
+                            let type_ = imm_width_to_indent(width.clone());
+                            (type_.clone(), quote! { #type_::generate().collect() })
+                        }
+                        FieldType::RelBR => (format_ident!("RelativeBr"), quote! { RelativeBr::generate().collect() }),
+                        FieldType::Ptr => (format_ident!("Immediate"), quote! { Immediate::generate().collect() }),
+                    };
+                    types.push(type_);
+                    samples.push(sample);
+                }
+                variant_field_names.push(field_names);
+                variant_types.push(types);
+                variant_samples.push(samples);
+            }
+        }
+        impls.push(quote! {
+            impl #instruction_enum_name {
+                /// Example values for every variant, paired with the variant's iform. Operand samples are
+                /// cycled through together rather than fully multiplied out, so every sample of every operand
+                /// shows up at least once.
+                #[allow(unused_variables)]
+                pub fn round_trip_samples() -> Vec<(xed_iform_enum_t, X86Instruction)> {
+                    let mut res = vec![];
+                    #({
+                        #(let #variant_field_names: Vec<#variant_types> = #variant_samples;)*
+                        let lens: Vec<usize> = vec![#(#variant_field_names.len()),*];
+                        if !lens.contains(&0) {
+                            let len = lens.into_iter().max().unwrap_or(1);
+                            for i in 0..len {
+                                res.push((#iforms, X86Instruction::#instruction_enum_name(Self::#variant_names {
+                                    #(#variant_field_names: #variant_field_names[i % #variant_field_names.len()]),*
+                                })));
+                            }
+                        }
+                    })*
+                    res
+                }
+            }
+        });
+    }
+    proc_macro::TokenStream::from(quote! {
+        use wrapper_common::generate_example_values::GenerateExampleValues;
+        use xed_sys::xed_iform_enum_t;
+
+        #(#impls)*
+
+        impl X86Instruction {
+            pub fn round_trip_samples() -> Vec<(xed_iform_enum_t, X86Instruction)> {
+                let mut res = vec![];
+                #(res.extend(#iclass_names::round_trip_samples());)*
+                res
+            }
+        }
+    })
+}
+
+#[proc_macro]
+pub fn enum_from_parsed(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let data = xed_data();
+    let mut impls = vec![];
+    let mut iclass_nums = vec![];
+    let mut iclass_names1 = vec![];
+    let mut iclass_names2 = vec![];
+    for (instruction_name, top_level_instruction) in data {
+        let instruction_enum_name = instruction_name.proc_macro_safe_name();
+        iclass_nums.push(top_level_instruction.iclass);
+        iclass_names1.push(instruction_enum_name.clone());
+        iclass_names2.push(instruction_enum_name.clone());
+        let mut candidates = vec![];
+        for (_iform, variants) in top_level_instruction.variants.iter().sorted_by_key(|(iform, _)| *iform) {
+            for (variant_name, Variant { operands, iform: _, effective_operand_width: _ }) in variants.iter().sorted_by_key(|(vn, _)| *vn) {
+                let variant_name = variant_name.proc_macro_safe_name();
+                let operand_count = operands.len();
+                let mut field_names = vec![];
+                let mut fields = vec![];
+                for (position, (operand_i, operand)) in operands.iter().sorted_by_key(|(i, _)| *i).enumerate() {
+                    field_names.push(format_ident!("operand_{}", operand_i));
+                    fields.push(match &operand.field_type {
+                        FieldType::Mem(mem) => {
+                            assert_eq!(mem.len(), 1);
+                            let mem_bits = mem.iter().next().unwrap().to_xed_width_bits() as u32;
+                            quote! { crate::parse::parsed_memory(&operands[#position], #mem_bits)? }
+                        }
+                        FieldType::AGen => quote! { crate::parse::parsed_agen(&operands[#position])? },
+                        FieldType::Reg(register_type) => {
+                            let reg_type = register_type.type_to_rust_type();
+                            let try_new = if reg_type == format_ident!("GeneralReg") {
+                                quote! { #reg_type::try_new(reg, None)? }
+                            } else {
+                                quote! { #reg_type::try_new(reg)? }
+                            };
+                            match allowed_registers(register_type) {
+                                Some(allowed) => quote! {
+                                    let reg = crate::parse::parsed_register(&operands[#position])?;
+                                    if ![#(#allowed),*].contains(&reg) {
+                                        return None;
+                                    }
+                                    #try_new
+                                },
+                                None => quote! {
+                                    let reg = crate::parse::parsed_register(&operands[#position])?;
+                                    #try_new
+                                },
+                            }
+                        }
+                        FieldType::Imm(width) => {
+                            let type_ = imm_width_to_indent(width.clone());
+                            quote! {
+                                <#type_ as crate::parse::FromParsedImmediate>::from_parsed(crate::parse::parsed_immediate(&operands[#position])?)?
+                            }
+                        }
+                        FieldType::RelBR => quote! { crate::parse::parsed_relbr(&operands[#position])? },
+                        FieldType::Ptr => quote! { crate::parse::parsed_ptr(&operands[#position])? },
+                    });
+                }
+                candidates.push(quote! {
+                    if operands.len() == #operand_count {
+                        let candidate = (|| Some(Self::#variant_name {
+                            #(#field_names: { #fields }),*
+                        }))();
+                        if let Some(candidate) = candidate {
+                            res.push(candidate);
+                        }
+                    }
+                });
+            }
+        }
+        impls.push(quote! {
+            impl #instruction_enum_name {
+                /// Every variant which accepts `operands`, ordered by iform.
+                pub fn from_parsed_operands(operands: &[crate::parse::ParsedOperand]) -> Vec<Self> {
+                    let mut res = vec![];
+                    #(#candidates)*
+                    res
+                }
+            }
+        });
+    }
+    proc_macro::TokenStream::from(quote! {
+        use xed_sys::xed_iclass_enum_t;
+
+        #(#impls)*
+
+        impl X86Instruction {
+            pub fn candidates_from_parsed(iclass: xed_iclass_enum_t, operands: &[crate::parse::ParsedOperand]) -> Vec<X86Instruction> {
+                match iclass {
+                    #(#iclass_nums => #iclass_names1::from_parsed_operands(operands).into_iter().map(Self::#iclass_names2).collect()),*,
+                    _ => vec![]
+                }
+            }
+        }
+    })
+}
+
+#[proc_macro]
+pub fn instruction_iform(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let data = xed_data();
+    let mut impls = vec![];
+    let mut iclass_names = vec![];
+    for (instruction_name, top_level_instruction) in data {
+        let instruction_enum_name = instruction_name.proc_macro_safe_name();
+        iclass_names.push(instruction_enum_name.clone());
+        let mut iforms = vec![];
+        let mut variant_names = vec![];
+        for (iform, variants) in top_level_instruction.variants.iter().sorted_by_key(|(iform, _)| *iform) {
+            for (variant_name, _) in variants.iter().sorted_by_key(|(vn, _)| *vn) {
+                iforms.push(*iform);
+                variant_names.push(variant_name.proc_macro_safe_name());
+            }
+        }
+        impls.push(quote! {
+            impl #instruction_enum_name {
+                /// The iform this variant was generated from. Width-spread variants share the iform of the
+                /// instruction they were spread from.
+                pub fn iform(&self) -> xed_sys::xed_iform_enum_t {
+                    match self {
+                        #(Self::#variant_names { .. } => #iforms),*
+                    }
+                }
+            }
+        });
+    }
+    proc_macro::TokenStream::from(quote! {
+        #(#impls)*
+
+        impl X86Instruction {
+            pub fn iform(&self) -> xed_sys::xed_iform_enum_t {
+                match self {
+                    #(Self::#iclass_names(inner) => inner.iform()),*
+                }
+            }
+        }
+    })
+}
+
+#[proc_macro]
+pub fn instruction_operands(_: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let data = xed_data();
+    let mut impls = vec![];
+    let mut iclass_names1 = vec![];
+    let mut iclass_names2 = vec![];
+    let mut iclass_names3 = vec![];
+    let mut iclass_names4 = vec![];
+    for (instruction_name, top_level_instruction) in data {
+        let instruction_enum_name = instruction_name.proc_macro_safe_name();
+        iclass_names1.push(instruction_enum_name.clone());
+        iclass_names2.push(instruction_enum_name.clone());
+        iclass_names3.push(instruction_enum_name.clone());
+        iclass_names4.push(instruction_enum_name.clone());
+        let mut variant_names = vec![];
+        let mut variant_field_names = vec![];
+        let mut variant_values = vec![];
+        let mut variant_mapped = vec![];
+        let mut widths = vec![];
+        let mut takes_relbr = false;
+        for (_iform, variants) in top_level_instruction.variants.iter() {
+            for (variant_name, Variant { operands, iform: _, effective_operand_width }) in variants.iter() {
+                variant_names.push(variant_name.proc_macro_safe_name());
+                widths.push(*effective_operand_width);
+                let mut field_names = vec![];
+                let mut values = vec![];
+                let mut mapped = vec![];
+                for (operand_i, operand) in operands.iter().sorted_by_key(|(i, _)| **i) {
+                    let field_name = format_ident!("operand_{}", operand_i);
+                    values.push(match &operand.field_type {
+                        FieldType::Mem(_) | FieldType::AGen => quote!(OperandValue::Memory(*#field_name)),
+                        FieldType::Reg(_) => quote!(OperandValue::Register(#field_name.to_xed())),
+                        FieldType::Imm(_) | FieldType::Ptr => quote!(OperandValue::Immediate(Immediate::from(*#field_name))),
+                        FieldType::RelBR => quote!(OperandValue::RelativeBr(*#field_name)),
+                    });
+                    mapped.push(match &operand.field_type {
+                        FieldType::RelBR => {
+                            takes_relbr = true;
+                            quote!(f(*#field_name)?)
+                        }
+                        _ => quote!(*#field_name),
+                    });
+                    field_names.push(field_name);
+                }
+                variant_field_names.push(field_names);
+                variant_values.push(values);
+                variant_mapped.push(mapped);
+            }
+        }
+        let f = if takes_relbr { quote!(mut f) } else { quote!(_f) };
+        impls.push(quote! {
+            impl #instruction_enum_name {
+                pub fn operand_values(&self) -> Vec<OperandValue> {
+                    match self {
+                        #(Self::#variant_names { #(#variant_field_names),* } => vec![#(#variant_values),*]),*
+                    }
+                }
+
+                pub fn effective_operand_width(&self) -> u32 {
+                    match self {
+                        #(Self::#variant_names { .. } => #widths),*
+                    }
+                }
+
+                pub fn map_relative_br(&self, #f: impl FnMut(RelativeBr) -> Option<RelativeBr>) -> Option<Self> {
+                    match self {
+                        #(Self::#variant_names { #(#variant_field_names),* } => Some(Self::#variant_names {
+                            #(#variant_field_names: #variant_mapped),*
+                        })),*
+                    }
+                }
+            }
+        });
+    }
+    proc_macro::TokenStream::from(quote! {
+        #(#impls)*
+
+        impl X86Instruction {
+            /// The explicit operands, in order, without the variant which holds them.
+            pub fn operand_values(&self) -> Vec<OperandValue> {
+                match self {
+                    #(Self::#iclass_names1(inner) => inner.operand_values()),*
+                }
+            }
+
+            /// The effective operand width in bits the variant is encoded with.
+            pub fn effective_operand_width(&self) -> u32 {
+                match self {
+                    #(Self::#iclass_names2(inner) => inner.effective_operand_width()),*
+                }
+            }
+
+            /// `self` with its branch displacement replaced by `f` of it. None if `f` gives None.
+            pub fn map_relative_br(&self, f: impl FnMut(RelativeBr) -> Option<RelativeBr>) -> Option<Self> {
+                match self {
+                    #(Self::#iclass_names3(inner) => inner.map_relative_br(f).map(Self::#iclass_names4)),*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,98 @@
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::fmt::{Display, Formatter};
+use std::mem::MaybeUninit;
+
+use xed_sys::{xed_format_generic, xed_init_print_info, xed_print_info_t, XED_SYNTAX_ATT, XED_SYNTAX_INTEL};
+
+use crate::{EncodeDecodeContext, X86Instruction};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum Syntax {
+    #[default]
+    Intel,
+    Att,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum HexStyle {
+    #[default]
+    Lower,
+    Upper,
+}
+
+/// Maps an address to a symbol name and the offset of the address from that symbol.
+pub type Symbolizer<'a> = &'a dyn Fn(u64) -> Option<(String, u64)>;
+
+#[derive(Copy, Clone, Default)]
+pub struct FormatOptions<'a> {
+    pub syntax: Syntax,
+    pub hex_style: HexStyle,
+    /// Address the instruction is assumed to be at, used to print absolute branch targets.
+    pub runtime_address: u64,
+    pub symbolizer: Option<Symbolizer<'a>>,
+}
+
+unsafe extern "C" fn symbolizer_trampoline(address: u64, symbol_buffer: *mut c_char, buffer_length: u32, offset: *mut u64, context: *mut c_void) -> c_int {
+    let symbolizer = &*(context as *const Symbolizer);
+    match symbolizer(address) {
+        Some((name, symbol_offset)) => {
+            if buffer_length == 0 {
+                return 0;
+            }
+            let name = name.as_bytes();
+            let len = name.len().min(buffer_length as usize - 1);
+            std::ptr::copy_nonoverlapping(name.as_ptr() as *const c_char, symbol_buffer, len);
+            *symbol_buffer.add(len) = 0;
+            *offset = symbol_offset;
+            1
+        }
+        None => 0,
+    }
+}
+
+impl X86Instruction {
+    /// Formats using xed's formatter. Returns `None` if the instruction can't be encoded in `context`'s mode.
+    pub fn format(&self, context: &mut EncodeDecodeContext, options: &FormatOptions) -> Option<String> {
+        self.with_decoded(context, |decoded| {
+            let mut buffer = vec![0 as c_char; 256];
+            let mut print_info: MaybeUninit<xed_print_info_t> = MaybeUninit::zeroed();
+            let ok = unsafe {
+                xed_init_print_info(print_info.as_mut_ptr());
+                let print_info = print_info.assume_init_mut();
+                print_info.p = decoded;
+                print_info.buf = buffer.as_mut_ptr();
+                print_info.blen = buffer.len() as c_int;
+                print_info.runtime_address = options.runtime_address;
+                print_info.syntax = match options.syntax {
+                    Syntax::Intel => XED_SYNTAX_INTEL,
+                    Syntax::Att => XED_SYNTAX_ATT,
+                };
+                print_info.format_options_valid = 1;
+                print_info.format_options.hex_address_before_symbolic_name = 0;
+                print_info.format_options.lowercase_hex = match options.hex_style {
+                    HexStyle::Lower => 1,
+                    HexStyle::Upper => 0,
+                };
+                if let Some(symbolizer) = options.symbolizer.as_ref() {
+                    print_info.disassembly_callback = Some(symbolizer_trampoline);
+                    print_info.context = symbolizer as *const Symbolizer as *mut c_void;
+                }
+                xed_format_generic(print_info) != 0
+            };
+            if !ok {
+                return None;
+            }
+            Some(unsafe { CStr::from_ptr(buffer.as_ptr()) }.to_str().unwrap().to_string())
+        }).flatten()
+    }
+}
+
+/// Prints Intel syntax for 64 bit mode, falling back to the debug representation for instructions xed won't encode.
+impl Display for X86Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.format(&mut EncodeDecodeContext::new(), &FormatOptions::default()) {
+            Some(formatted) => write!(f, "{formatted}"),
+            None => write!(f, "{self:?}"),
+        }
+    }
+}
@@ -1,26 +1,65 @@
+use std::error::Error;
 use std::ffi::c_uint;
+use std::fmt::{Display, Formatter};
 use std::mem::MaybeUninit;
 use std::sync::Once;
 
-use xed_sys::{XED_ADDRESS_WIDTH_64b, xed_decode, xed_decoded_inst_get_length, xed_decoded_inst_zero_set_mode, xed_error_enum_t2str, XED_MACHINE_MODE_LONG_64, xed_state_init, xed_state_t, xed_state_zero};
+use xed_sys::{XED_ADDRESS_WIDTH_16b, XED_ADDRESS_WIDTH_32b, XED_ADDRESS_WIDTH_64b, xed_address_width_enum_t, xed_decode, xed_decoded_inst_get_length, xed_decoded_inst_zero_set_mode, xed_encode, xed_error_enum_t, xed_error_enum_t2str, XED_ERROR_NONE, XED_MACHINE_MODE_LEGACY_16, XED_MACHINE_MODE_LEGACY_32, XED_MACHINE_MODE_LONG_64, XED_MACHINE_MODE_LONG_COMPAT_32, XED_MACHINE_MODE_REAL_16, xed_machine_mode_enum_t, XED_MAX_INSTRUCTION_BYTES, xed_operand_values_get_effective_address_width, xed_reg_enum_t, xed_state_init, xed_state_t, xed_state_zero};
+use xed_wrapper::operands::{Immediate, MemoryOperands, RelativeBr};
 
-use xed_enum_generator::{enum_from_xed, enum_to_xed, instruction_enums, top_level_instruction_enum};
+use xed_enum_generator::{enum_from_parsed, enum_from_xed, enum_to_xed, instruction_enums, instruction_iform, instruction_operands, round_trip_samples, top_level_instruction_enum};
 
 static START: Once = Once::new();
 
 pub struct EncodeDecodeContext {
     xed_state: MaybeUninit<xed_state_t>,
+    address_width: xed_address_width_enum_t,
 }
 
 impl EncodeDecodeContext {
+    /// 64 bit long mode.
     pub fn new() -> Self {
+        Self::with_mode(XED_MACHINE_MODE_LONG_64, XED_ADDRESS_WIDTH_64b, XED_ADDRESS_WIDTH_64b)
+    }
+
+    /// 32 bit protected mode.
+    pub fn legacy_32() -> Self {
+        Self::with_mode(XED_MACHINE_MODE_LEGACY_32, XED_ADDRESS_WIDTH_32b, XED_ADDRESS_WIDTH_32b)
+    }
+
+    /// 16 bit protected mode.
+    pub fn legacy_16() -> Self {
+        Self::with_mode(XED_MACHINE_MODE_LEGACY_16, XED_ADDRESS_WIDTH_16b, XED_ADDRESS_WIDTH_16b)
+    }
+
+    /// 32 bit code segment running under a 64 bit os.
+    pub fn compat_32() -> Self {
+        Self::with_mode(XED_MACHINE_MODE_LONG_COMPAT_32, XED_ADDRESS_WIDTH_32b, XED_ADDRESS_WIDTH_32b)
+    }
+
+    /// 16 bit real mode.
+    pub fn real() -> Self {
+        Self::with_mode(XED_MACHINE_MODE_REAL_16, XED_ADDRESS_WIDTH_16b, XED_ADDRESS_WIDTH_16b)
+    }
+
+    fn with_mode(machine_mode: xed_machine_mode_enum_t, address_width: xed_address_width_enum_t, stack_address_width: xed_address_width_enum_t) -> Self {
         let mut xed_state: MaybeUninit<xed_state_t> = MaybeUninit::zeroed();
         unsafe {
             xed_state_zero(xed_state.as_mut_ptr());
-            xed_state_init(xed_state.as_mut_ptr(), XED_MACHINE_MODE_LONG_64, XED_ADDRESS_WIDTH_64b, XED_ADDRESS_WIDTH_64b);
+            xed_state_init(xed_state.as_mut_ptr(), machine_mode, address_width, stack_address_width);
         }
         Self {
             xed_state,
+            address_width,
+        }
+    }
+
+    pub fn address_width_bits(&self) -> c_uint {
+        match self.address_width {
+            XED_ADDRESS_WIDTH_16b => 16,
+            XED_ADDRESS_WIDTH_32b => 32,
+            XED_ADDRESS_WIDTH_64b => 64,
+            _ => panic!("Unexpected address width"),
         }
     }
 }
@@ -29,27 +68,150 @@ top_level_instruction_enum!();
 instruction_enums!();
 enum_from_xed!();
 enum_to_xed!();
+round_trip_samples!();
+enum_from_parsed!();
+instruction_iform!();
+instruction_operands!();
+
+pub mod format;
+pub mod operand_info;
+pub mod parse;
+pub mod round_trip;
 
 
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum DecodeError{
-    XedError(String)
+    XedError(String),
+    /// Bytes starting at `offset` into the buffer given to [`Decoder`] could not be decoded.
+    Undecodable { offset: usize, address: u64, error: String },
+    /// xed decoded an instruction, named here by its iclass or iform, which has no variant.
+    Unsupported(String),
+}
+
+/// An explicit operand of an instruction, see [`X86Instruction::operand_values`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OperandValue {
+    Register(xed_reg_enum_t),
+    Memory(MemoryOperands),
+    Immediate(Immediate),
+    RelativeBr(RelativeBr),
+}
+
+#[derive(Debug, Clone)]
+pub enum EncodeError {
+    /// xed could not turn the operands of `instruction` into an encoder request.
+    ConversionFailed { instruction: X86Instruction },
+    /// xed rejected the encoder request built from `instruction`.
+    XedError { error: String, instruction: X86Instruction },
+    /// An operand of `instruction` holds a value too wide for the field xed encodes it in.
+    OperandOutOfRange { instruction: X86Instruction },
+    /// No encoder is generated for `instruction`.
+    Unsupported { instruction: X86Instruction },
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodeError::ConversionFailed { instruction } => write!(f, "could not convert {instruction:?} to an encoder request"),
+            EncodeError::XedError { error, instruction } => write!(f, "xed could not encode {instruction:?}: {error}"),
+            EncodeError::OperandOutOfRange { instruction } => write!(f, "an operand of {instruction:?} is out of range"),
+            EncodeError::Unsupported { instruction } => write!(f, "encoding {instruction:?} is not supported"),
+        }
+    }
+}
+
+impl Error for EncodeError {}
+
+fn xed_error_to_string(error: xed_error_enum_t) -> String {
+    unsafe { CStr::from_ptr(xed_error_enum_t2str(error)) }.to_str().unwrap().to_string()
 }
 
 impl X86Instruction{
-    pub fn encode(&self, encode_context: &mut EncodeDecodeContext) -> ([u8;16], usize){
-        let xed_request = self.to_xed(encode_context);
-        todo!();
+    pub fn encode(&self, encode_context: &mut EncodeDecodeContext) -> Result<([u8;16], usize), EncodeError>{
+        let mut xed_request = self.to_xed(encode_context)?;
+        let mut bytes = [0u8; 16];
+        let mut len: c_uint = 0;
+        let error = unsafe { xed_encode(&mut xed_request, bytes.as_mut_ptr(), XED_MAX_INSTRUCTION_BYTES, &mut len) };
+        if error != XED_ERROR_NONE {
+            return Err(EncodeError::XedError { error: xed_error_to_string(error), instruction: *self });
+        }
+        Ok((bytes, len as usize))
+    }
+
+    /// Encodes then decodes `self`, handing xed's decoded form (with implicit operands filled in) to `f`. The
+    /// decoded instruction points into the encoded bytes, so it can't outlive this call.
+    pub(crate) fn with_decoded<T>(&self, context: &mut EncodeDecodeContext, f: impl FnOnce(&xed_decoded_inst_t) -> T) -> Option<T> {
+        let (bytes, len) = self.encode(context).ok()?;
+        let mut decoded = MaybeUninit::zeroed();
+        unsafe { xed_decoded_inst_zero_set_mode(decoded.as_mut_ptr(), context.xed_state.as_ptr()); }
+        let error = unsafe { xed_decode(decoded.as_mut_ptr(), bytes.as_ptr(), len as c_uint) };
+        if error != XED_ERROR_NONE {
+            return None;
+        }
+        Some(f(unsafe { decoded.assume_init_ref() }))
+    }
+
+    pub fn decode_one<'a,'b>(bytes: &'a [u8], context: &'b mut EncodeDecodeContext) -> Result<(X86Instruction, &'a [u8]),DecodeError> {
+        let (instruction, _, rest) = Self::decode_one_with_address_width(bytes, context)?;
+        Ok((instruction, rest))
     }
 
-    pub fn decode_one<'a,'b>(&self, bytes: &'a [u8], context: &'b mut EncodeDecodeContext) -> Result<(X86Instruction, &'a [u8]),DecodeError> {
+    /// [`X86Instruction::decode_one`], also giving the effective address width in bits, which the variant doesn't
+    /// record when it only affects implicit operands, like the counter of LOOP.
+    pub fn decode_one_with_address_width<'a,'b>(bytes: &'a [u8], context: &'b mut EncodeDecodeContext) -> Result<(X86Instruction, usize, &'a [u8]),DecodeError> {
         let mut decoded = MaybeUninit::zeroed();
         unsafe { xed_decoded_inst_zero_set_mode(decoded.as_mut_ptr(), context.xed_state.as_ptr()); }
         let error = unsafe { xed_decode(decoded.as_mut_ptr(), bytes.as_ptr(), bytes.len() as c_uint) };
         if error != 0 {
-            return Err(DecodeError::XedError(unsafe { CStr::from_ptr(xed_error_enum_t2str(error)) }.to_str().unwrap().to_string()))
+            return Err(DecodeError::XedError(xed_error_to_string(error)))
         }
         let decoded_length = unsafe { xed_decoded_inst_get_length(decoded.as_ptr()) } as usize;
-        Ok((Self::from_xed(decoded.as_ptr()), &bytes[decoded_length..]))
+        let address_width = unsafe { xed_operand_values_get_effective_address_width(decoded.as_ptr()) } as usize;
+        Ok((Self::from_xed(decoded.as_ptr())?, address_width, &bytes[decoded_length..]))
+    }
+}
+
+/// Linear sweep over a byte buffer. Yields `(address, length, instruction)` for each decoded instruction.
+/// Undecodable bytes are reported with their offset, after which decoding resumes at the next byte.
+pub struct Decoder<'a, 'b> {
+    bytes: &'a [u8],
+    offset: usize,
+    start_address: u64,
+    context: &'b mut EncodeDecodeContext,
+}
+
+impl<'a, 'b> Decoder<'a, 'b> {
+    pub fn new(bytes: &'a [u8], start_address: u64, context: &'b mut EncodeDecodeContext) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            start_address,
+            context,
+        }
+    }
+}
+
+impl Iterator for Decoder<'_, '_> {
+    type Item = Result<(u64, usize, X86Instruction), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.bytes.len() {
+            return None;
+        }
+        let offset = self.offset;
+        let address = self.start_address.wrapping_add(offset as u64);
+        let remaining = &self.bytes[offset..];
+        match X86Instruction::decode_one(remaining, self.context) {
+            Ok((instruction, rest)) => {
+                let length = remaining.len() - rest.len();
+                self.offset += length;
+                Some(Ok((address, length, instruction)))
+            }
+            Err(DecodeError::XedError(error)) | Err(DecodeError::Undecodable { error, .. }) | Err(DecodeError::Unsupported(error)) => {
+                self.offset += 1;
+                Some(Err(DecodeError::Undecodable { offset, address, error }))
+            }
+        }
     }
 }
 
@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+
+use xed_sys::{xed_decoded_inst_get_base_reg, xed_decoded_inst_get_iform_enum, xed_decoded_inst_get_index_reg, xed_decoded_inst_get_reg, xed_decoded_inst_get_seg_reg, XED_REG_INVALID, xed_decoded_inst_get_rflags_info, xed_decoded_inst_inst, xed_decoded_inst_operand_action, xed_decoded_inst_operand_length_bits, xed_decoded_inst_t, xed_flag_set_t, xed_inst_noperands, xed_inst_operand, xed_operand_enum_t, xed_operand_is_memory_addressing_register, xed_operand_is_register, xed_operand_name, xed_operand_operand_visibility, XED_OPERAND_ACTION_CR, XED_OPERAND_ACTION_CRW, XED_OPERAND_ACTION_CW, XED_OPERAND_ACTION_R, XED_OPERAND_ACTION_RCW, XED_OPERAND_ACTION_RW, XED_OPERAND_ACTION_W, XED_OPERAND_AGEN, XED_OPERAND_IMM0, XED_OPERAND_IMM1, XED_OPERAND_MEM0, XED_OPERAND_MEM1, XED_OPERAND_PTR, XED_OPERAND_RELBR, XED_OPVIS_EXPLICIT, XED_OPVIS_IMPLICIT, xed_reg_class, xed_reg_class_enum_t, xed_reg_enum_t, xed_simple_flag_get_read_flag_set, xed_simple_flag_get_undefined_flag_set, xed_simple_flag_get_written_flag_set};
+
+use wrapper_common::registers::Register;
+
+use crate::{EncodeDecodeContext, X86Instruction};
+
+/// How an operand is accessed. The conditional forms are for accesses which depend on runtime state, e.g.
+/// the destination of a CMOVcc or a masked store.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OperandAccess {
+    Read,
+    Write,
+    ReadWrite,
+    ConditionalRead,
+    ConditionalWrite,
+    /// Always read, conditionally written.
+    ReadConditionalWrite,
+    /// Conditionally read, always written.
+    ConditionalReadWrite,
+}
+
+impl OperandAccess {
+    pub fn reads(&self) -> bool {
+        !matches!(self, OperandAccess::Write | OperandAccess::ConditionalWrite)
+    }
+
+    pub fn writes(&self) -> bool {
+        !matches!(self, OperandAccess::Read | OperandAccess::ConditionalRead)
+    }
+
+    pub fn is_conditional(&self) -> bool {
+        !matches!(self, OperandAccess::Read | OperandAccess::Write | OperandAccess::ReadWrite)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OperandVisibility {
+    /// Appears in the assembly and as an `operand_N` field.
+    Explicit,
+    /// Fixed by the opcode but still printed, e.g. the CL of `shl eax, cl`.
+    Implicit,
+    /// Neither printed nor a field, e.g. RSP for PUSH or RDX for MUL.
+    Suppressed,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OperandKind {
+    Register(xed_reg_enum_t),
+    /// A memory access, `0` or `1` for instructions like MOVS which access two locations.
+    Memory(u32),
+    AddressGeneration,
+    Immediate,
+    RelativeBranch,
+    Pointer,
+    /// Operands xed tracks which don't fit above, like the effective operand width pseudo operands.
+    Other(xed_operand_enum_t),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct OperandInfo {
+    pub name: xed_operand_enum_t,
+    pub kind: OperandKind,
+    pub access: OperandAccess,
+    pub visibility: OperandVisibility,
+    pub width_bits: u32,
+    /// The `operand_N` field of the variant this operand corresponds to, for explicit operands.
+    pub field: Option<usize>,
+}
+
+impl OperandInfo {
+    pub fn register(&self) -> Option<xed_reg_enum_t> {
+        match self.kind {
+            OperandKind::Register(reg) => Some(reg),
+            _ => None,
+        }
+    }
+
+    pub fn register_class(&self) -> Option<xed_reg_class_enum_t> {
+        self.register().map(|reg| unsafe { xed_reg_class(reg) })
+    }
+}
+
+/// Bits of xed's flag sets. These line up with the bit positions in RFLAGS, except for the x87 condition codes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum X86Flag {
+    CF,
+    PF,
+    AF,
+    ZF,
+    SF,
+    TF,
+    IF,
+    DF,
+    OF,
+    IOPL,
+    NT,
+    RF,
+    VM,
+    AC,
+    VIF,
+    VIP,
+    ID,
+    FC0,
+    FC1,
+    FC2,
+    FC3,
+}
+
+impl X86Flag {
+    pub const ALL: [X86Flag; 21] = [X86Flag::CF, X86Flag::PF, X86Flag::AF, X86Flag::ZF, X86Flag::SF, X86Flag::TF, X86Flag::IF, X86Flag::DF, X86Flag::OF, X86Flag::IOPL, X86Flag::NT, X86Flag::RF, X86Flag::VM, X86Flag::AC, X86Flag::VIF, X86Flag::VIP, X86Flag::ID, X86Flag::FC0, X86Flag::FC1, X86Flag::FC2, X86Flag::FC3];
+
+    /// Mask of this flag within `xed_flag_set_t::flat`.
+    pub fn xed_flag_set_mask(&self) -> u32 {
+        match self {
+            X86Flag::CF => 1 << 0,
+            X86Flag::PF => 1 << 2,
+            X86Flag::AF => 1 << 4,
+            X86Flag::ZF => 1 << 6,
+            X86Flag::SF => 1 << 7,
+            X86Flag::TF => 1 << 8,
+            X86Flag::IF => 1 << 9,
+            X86Flag::DF => 1 << 10,
+            X86Flag::OF => 1 << 11,
+            X86Flag::IOPL => 0b11 << 12,
+            X86Flag::NT => 1 << 14,
+            X86Flag::RF => 1 << 16,
+            X86Flag::VM => 1 << 17,
+            X86Flag::AC => 1 << 18,
+            X86Flag::VIF => 1 << 19,
+            X86Flag::VIP => 1 << 20,
+            X86Flag::ID => 1 << 21,
+            X86Flag::FC0 => 1 << 22,
+            X86Flag::FC1 => 1 << 23,
+            X86Flag::FC2 => 1 << 24,
+            X86Flag::FC3 => 1 << 25,
+        }
+    }
+
+    fn from_flag_set(flag_set: *const xed_flag_set_t) -> HashSet<X86Flag> {
+        if flag_set.is_null() {
+            return HashSet::new();
+        }
+        let flat = unsafe { (*flag_set).flat };
+        X86Flag::ALL.into_iter().filter(|flag| flat & flag.xed_flag_set_mask() != 0).collect()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InstructionOperandInfo {
+    /// Every operand xed knows about, in xed's order.
+    pub operands: Vec<OperandInfo>,
+    pub flags_read: HashSet<X86Flag>,
+    /// Includes flags left undefined, which are also listed in `flags_undefined`.
+    pub flags_written: HashSet<X86Flag>,
+    pub flags_undefined: HashSet<X86Flag>,
+}
+
+impl InstructionOperandInfo {
+    pub fn explicit_operands(&self) -> impl Iterator<Item=&OperandInfo> {
+        self.operands.iter().filter(|operand| operand.visibility == OperandVisibility::Explicit)
+    }
+
+    pub fn implicit_operands(&self) -> impl Iterator<Item=&OperandInfo> {
+        self.operands.iter().filter(|operand| operand.visibility != OperandVisibility::Explicit)
+    }
+
+    /// None if xed reports an operand action this doesn't know about.
+    pub(crate) fn from_decoded(decoded: &xed_decoded_inst_t, expected_iform: xed_sys::xed_iform_enum_t) -> Option<Self> {
+        // if xed picked a different encoding than the one the variant came from, the explicit operands may not
+        // line up with the variant's fields
+        let fields_line_up = unsafe { xed_decoded_inst_get_iform_enum(decoded) } == expected_iform;
+        let inst = unsafe { xed_decoded_inst_inst(decoded) };
+        let mut operands = vec![];
+        let mut explicit_i = 0;
+        for operand_i in 0..unsafe { xed_inst_noperands(inst) } {
+            let operand = unsafe { xed_inst_operand(inst, operand_i) };
+            let name = unsafe { xed_operand_name(operand) };
+            let visibility = match unsafe { xed_operand_operand_visibility(operand) } {
+                XED_OPVIS_EXPLICIT => OperandVisibility::Explicit,
+                XED_OPVIS_IMPLICIT => OperandVisibility::Implicit,
+                _ => OperandVisibility::Suppressed,
+            };
+            let access = match unsafe { xed_decoded_inst_operand_action(decoded, operand_i) } {
+                XED_OPERAND_ACTION_R => OperandAccess::Read,
+                XED_OPERAND_ACTION_W => OperandAccess::Write,
+                XED_OPERAND_ACTION_RW => OperandAccess::ReadWrite,
+                XED_OPERAND_ACTION_CR => OperandAccess::ConditionalRead,
+                XED_OPERAND_ACTION_CW => OperandAccess::ConditionalWrite,
+                XED_OPERAND_ACTION_RCW => OperandAccess::ReadConditionalWrite,
+                XED_OPERAND_ACTION_CRW => OperandAccess::ConditionalReadWrite,
+                _ => return None,
+            };
+            let kind = match name {
+                XED_OPERAND_MEM0 => OperandKind::Memory(0),
+                XED_OPERAND_MEM1 => OperandKind::Memory(1),
+                XED_OPERAND_AGEN => OperandKind::AddressGeneration,
+                XED_OPERAND_IMM0 | XED_OPERAND_IMM1 => OperandKind::Immediate,
+                XED_OPERAND_RELBR => OperandKind::RelativeBranch,
+                XED_OPERAND_PTR => OperandKind::Pointer,
+                name if unsafe { xed_operand_is_register(name) != 0 || xed_operand_is_memory_addressing_register(name) != 0 } => {
+                    OperandKind::Register(unsafe { xed_decoded_inst_get_reg(decoded, name) })
+                }
+                name => OperandKind::Other(name),
+            };
+            let field = if visibility == OperandVisibility::Explicit {
+                explicit_i += 1;
+                if fields_line_up { Some(explicit_i - 1) } else { None }
+            } else {
+                None
+            };
+            operands.push(OperandInfo {
+                name,
+                kind,
+                access,
+                visibility,
+                width_bits: unsafe { xed_decoded_inst_operand_length_bits(decoded, operand_i) },
+                field,
+            });
+        }
+        let rflags_info = unsafe { xed_decoded_inst_get_rflags_info(decoded) };
+        let (flags_read, flags_written, flags_undefined) = if rflags_info.is_null() {
+            (HashSet::new(), HashSet::new(), HashSet::new())
+        } else {
+            unsafe {
+                (X86Flag::from_flag_set(xed_simple_flag_get_read_flag_set(rflags_info)),
+                 X86Flag::from_flag_set(xed_simple_flag_get_written_flag_set(rflags_info)),
+                 X86Flag::from_flag_set(xed_simple_flag_get_undefined_flag_set(rflags_info)))
+            }
+        };
+        Some(Self {
+            operands,
+            flags_read,
+            flags_written,
+            flags_undefined,
+        })
+    }
+}
+
+/// One side of an instruction's def-use information.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct RegisterAccesses {
+    /// Full width registers, so an access to AL shows up as RAX and one to XMM1 as ZMM1.
+    pub registers: HashSet<Register>,
+    pub flags: HashSet<X86Flag>,
+    pub memory: bool,
+}
+
+impl RegisterAccesses {
+    fn add_register(&mut self, reg: xed_reg_enum_t) {
+        if reg == XED_REG_INVALID {
+            return;
+        }
+        // flags are tracked individually, and xed's pseudo registers have no architectural counterpart
+        if let Some(register) = Register::try_new(reg) {
+            self.registers.insert(register.parent());
+        }
+    }
+}
+
+/// Conditional accesses count as accesses. A partial write, like one to AL, is reported as a write of the full
+/// register but not as a read of the bits it leaves alone.
+fn def_use(decoded: &xed_decoded_inst_t, info: &InstructionOperandInfo) -> (RegisterAccesses, RegisterAccesses) {
+    let mut read = RegisterAccesses { flags: info.flags_read.clone(), ..RegisterAccesses::default() };
+    let mut written = RegisterAccesses { flags: info.flags_written.clone(), ..RegisterAccesses::default() };
+    for operand in info.operands.iter() {
+        match operand.kind {
+            OperandKind::Register(reg) => {
+                if operand.access.reads() {
+                    read.add_register(reg);
+                }
+                if operand.access.writes() {
+                    written.add_register(reg);
+                }
+            }
+            OperandKind::Memory(mem_i) => {
+                read.memory |= operand.access.reads();
+                written.memory |= operand.access.writes();
+                unsafe {
+                    read.add_register(xed_decoded_inst_get_base_reg(decoded, mem_i));
+                    read.add_register(xed_decoded_inst_get_index_reg(decoded, mem_i));
+                    read.add_register(xed_decoded_inst_get_seg_reg(decoded, mem_i));
+                }
+            }
+            OperandKind::AddressGeneration => {
+                unsafe {
+                    read.add_register(xed_decoded_inst_get_base_reg(decoded, 0));
+                    read.add_register(xed_decoded_inst_get_index_reg(decoded, 0));
+                }
+            }
+            OperandKind::Immediate | OperandKind::RelativeBranch | OperandKind::Pointer | OperandKind::Other(_) => {}
+        }
+    }
+    (read, written)
+}
+
+impl X86Instruction {
+    /// Access, visibility and width of every operand including implicit and suppressed ones, plus the flags read
+    /// and written, as given by xed's tables. Widths depend on the mode of `context`. Returns `None` if the
+    /// instruction can't be encoded in that mode.
+    ///
+    /// This isn't a table generated per iform next to [`X86Instruction::iform`] because xed's static operand
+    /// tables don't hold the answers. Implicit registers are nonterminals, like the accumulator or stack pointer
+    /// of the effective operand and address width, widths are width codes scaled by those same widths, and the
+    /// flags some instructions touch depend on their operands, like a shift by an immediate zero. xed resolves all
+    /// of these when decoding a concrete encoding in a given mode, so the instruction is encoded and decoded again.
+    pub fn operand_info(&self, context: &mut EncodeDecodeContext) -> Option<InstructionOperandInfo> {
+        let iform = self.iform();
+        self.with_decoded(context, |decoded| InstructionOperandInfo::from_decoded(decoded, iform)).flatten()
+    }
+
+    /// Registers (including those used for addressing), flags and memory this instruction may read.
+    pub fn registers_read(&self, context: &mut EncodeDecodeContext) -> Option<RegisterAccesses> {
+        self.def_use(context).map(|(read, _)| read)
+    }
+
+    /// Registers, flags and memory this instruction may write.
+    pub fn registers_written(&self, context: &mut EncodeDecodeContext) -> Option<RegisterAccesses> {
+        self.def_use(context).map(|(_, written)| written)
+    }
+
+    fn def_use(&self, context: &mut EncodeDecodeContext) -> Option<(RegisterAccesses, RegisterAccesses)> {
+        let iform = self.iform();
+        self.with_decoded(context, |decoded| InstructionOperandInfo::from_decoded(decoded, iform).map(|info| def_use(decoded, &info))).flatten()
+    }
+}
@@ -0,0 +1,364 @@
+use std::ffi::CString;
+use std::str::FromStr;
+
+use xed_sys::{str2xed_iclass_enum_t, str2xed_reg_enum_t, xed_iclass_enum_t, XED_ICLASS_INVALID, xed_reg_enum_t, XED_REG_INVALID, XED_REG_R13, XED_REG_RBP, XED_REG_RIP};
+
+use wrapper_common::memory_operand::{GeneralReg, X86Scale};
+use wrapper_common::registers::RegSegment;
+use xed_wrapper::operands::{Imm16, Imm32, Imm64, Imm8, Immediate, MemoryOperands, RelativeBr};
+
+use crate::{EncodeDecodeContext, OperandValue, X86Instruction};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    EmptyLine,
+    UnknownMnemonic(String),
+    BadOperand(String),
+    /// The mnemonic is known, but no variant accepts these operands.
+    NoMatchingVariant(String),
+}
+
+/// An operand of a line of Intel syntax assembly, before it has been matched against any variant.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParsedOperand {
+    Register(xed_reg_enum_t),
+    Memory {
+        width_bits: Option<u32>,
+        operand: MemoryOperands,
+    },
+    Immediate(i64),
+}
+
+pub trait FromParsedImmediate: Sized {
+    fn from_parsed(value: i64) -> Option<Self>;
+}
+
+impl FromParsedImmediate for Imm8 {
+    fn from_parsed(value: i64) -> Option<Self> {
+        if let Ok(value) = i8::try_from(value) {
+            return Some(Imm8(value));
+        }
+        u8::try_from(value).ok().map(|value| Imm8(value as i8))
+    }
+}
+
+impl FromParsedImmediate for Imm16 {
+    fn from_parsed(value: i64) -> Option<Self> {
+        if let Ok(value) = i16::try_from(value) {
+            return Some(Imm16(value));
+        }
+        u16::try_from(value).ok().map(|value| Imm16(value as i16))
+    }
+}
+
+impl FromParsedImmediate for Imm32 {
+    fn from_parsed(value: i64) -> Option<Self> {
+        if let Ok(value) = i32::try_from(value) {
+            return Some(Imm32(value));
+        }
+        u32::try_from(value).ok().map(|value| Imm32(value as i32))
+    }
+}
+
+impl FromParsedImmediate for Imm64 {
+    fn from_parsed(value: i64) -> Option<Self> {
+        Some(Imm64(value as u64))
+    }
+}
+
+pub fn parsed_register(operand: &ParsedOperand) -> Option<xed_reg_enum_t> {
+    match operand {
+        ParsedOperand::Register(reg) => Some(*reg),
+        _ => None,
+    }
+}
+
+pub fn parsed_memory(operand: &ParsedOperand, expected_width_bits: u32) -> Option<MemoryOperands> {
+    match operand {
+        ParsedOperand::Memory { width_bits, operand } => {
+            match width_bits {
+                Some(width_bits) if *width_bits != expected_width_bits => None,
+                _ => Some(*operand),
+            }
+        }
+        _ => None,
+    }
+}
+
+pub fn parsed_agen(operand: &ParsedOperand) -> Option<MemoryOperands> {
+    match operand {
+        ParsedOperand::Memory { width_bits: _, operand } => Some(*operand),
+        _ => None,
+    }
+}
+
+pub fn parsed_immediate(operand: &ParsedOperand) -> Option<i64> {
+    match operand {
+        ParsedOperand::Immediate(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Only picks out the variants taking a branch, [`X86Instruction::parse_at`] then replaces the displacement
+/// with one relative to the end of the instruction.
+pub fn parsed_relbr(operand: &ParsedOperand) -> Option<RelativeBr> {
+    let value = parsed_immediate(operand)?;
+    if let Ok(disp) = i8::try_from(value) {
+        return Some(RelativeBr::Disp8(disp));
+    }
+    if let Ok(disp) = i32::try_from(value) {
+        return Some(RelativeBr::Disp32(disp));
+    }
+    None
+}
+
+pub fn parsed_ptr(operand: &ParsedOperand) -> Option<Immediate> {
+    let value = parsed_immediate(operand)?;
+    Some(match i32::try_from(value) {
+        Ok(value) => Immediate::I32(value),
+        Err(_) => Immediate::U64(value as u64),
+    })
+}
+
+fn parse_number(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest.trim()),
+        None => (false, text),
+    };
+    let value = if let Some(hex) = text.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16).ok()?
+    } else if let Some(hex) = text.strip_suffix('h') {
+        u64::from_str_radix(hex, 16).ok()?
+    } else {
+        text.parse::<u64>().ok()?
+    };
+    Some(if negative { (value as i64).wrapping_neg() } else { value as i64 })
+}
+
+fn parse_register(text: &str) -> Option<xed_reg_enum_t> {
+    let name = CString::new(text.trim().to_uppercase()).ok()?;
+    let reg = unsafe { str2xed_reg_enum_t(name.as_ptr()) };
+    if reg == XED_REG_INVALID {
+        None
+    } else {
+        Some(reg)
+    }
+}
+
+fn ptr_width_bits(text: &str) -> Option<u32> {
+    Some(match text {
+        "byte" => 8,
+        "word" => 16,
+        "dword" => 32,
+        "fword" => 48,
+        "qword" => 64,
+        "tbyte" => 80,
+        "xmmword" => 128,
+        "ymmword" => 256,
+        "zmmword" => 512,
+        _ => return None,
+    })
+}
+
+fn parse_memory(text: &str) -> Option<ParsedOperand> {
+    let (before_bracket, rest) = text.split_once('[')?;
+    let inside = rest.strip_suffix(']')?;
+    let mut before_bracket = before_bracket.trim();
+    let mut segment = None;
+    if let Some(prefix) = before_bracket.strip_suffix(':') {
+        let (prefix, segment_name) = prefix.rsplit_once(' ').unwrap_or(("", prefix));
+        segment = Some(RegSegment::try_new(parse_register(segment_name)?)?);
+        before_bracket = prefix.trim();
+    }
+    let width_bits = match before_bracket.strip_suffix("ptr").map(str::trim) {
+        // xed prints a bare `ptr` for operands which aren't dereferenced, like lea's
+        Some("") => None,
+        Some(width) => Some(ptr_width_bits(width)?),
+        None if before_bracket.is_empty() => None,
+        None => return None,
+    };
+    let mut base = None;
+    let mut index = None;
+    let mut scale = X86Scale::One;
+    let mut disp = 0i64;
+    // split on + and - while keeping the sign with each term
+    let mut terms = vec![];
+    let mut current = String::new();
+    for c in inside.chars() {
+        if (c == '+' || c == '-') && !current.trim().is_empty() {
+            terms.push(current.clone());
+            current.clear();
+        }
+        if c != '+' {
+            current.push(c);
+        }
+    }
+    terms.push(current);
+    for term in terms {
+        let term = term.trim();
+        if let Some(value) = parse_number(term) {
+            disp = disp.wrapping_add(value);
+        } else if let Some((reg, term_scale)) = term.split_once('*') {
+            index = Some(GeneralReg::try_new(parse_register(reg)?, None)?);
+            scale = match parse_number(term_scale)? {
+                1 => X86Scale::One,
+                2 => X86Scale::Two,
+                4 => X86Scale::Four,
+                8 => X86Scale::Eight,
+                _ => return None,
+            };
+        } else {
+            let reg = GeneralReg::try_new(parse_register(term)?, None)?;
+            if base.is_none() {
+                base = Some(reg);
+            } else {
+                index = Some(reg);
+            }
+        }
+    }
+    let disp_width = match base.map(|base| base.to_xed()) {
+        Some(XED_REG_RIP) | None => 32,
+        Some(XED_REG_RBP) | Some(XED_REG_R13) if disp == 0 => 8,
+        _ if disp == 0 => 0,
+        _ if i8::try_from(disp).is_ok() => 8,
+        _ => 32,
+    };
+    Some(ParsedOperand::Memory {
+        width_bits,
+        operand: MemoryOperands::SIBAddressing {
+            segment,
+            scale,
+            index,
+            base,
+            disp,
+            disp_width,
+        },
+    })
+}
+
+fn parse_operand(text: &str) -> Result<ParsedOperand, ParseError> {
+    let text = text.trim();
+    let res = if text.contains('[') {
+        parse_memory(text)
+    } else if let Some(value) = parse_number(text) {
+        Some(ParsedOperand::Immediate(value))
+    } else {
+        parse_register(text).map(ParsedOperand::Register)
+    };
+    res.ok_or_else(|| ParseError::BadOperand(text.to_string()))
+}
+
+/// Intel mnemonics which xed spells differently.
+fn iclass_name(mnemonic: &str) -> &str {
+    match mnemonic {
+        "ret" => "ret_near",
+        "call" => "call_near",
+        "je" => "jz",
+        "jne" => "jnz",
+        "jc" | "jnae" => "jb",
+        "jae" | "jnc" => "jnb",
+        "ja" => "jnbe",
+        "jna" => "jbe",
+        "jg" => "jnle",
+        "jge" => "jnl",
+        "jnge" => "jl",
+        "jng" => "jle",
+        "jpe" => "jp",
+        "jpo" => "jnp",
+        other => other,
+    }
+}
+
+fn iclass_from_mnemonic(mnemonic: &str) -> Option<xed_iclass_enum_t> {
+    let name = CString::new(iclass_name(mnemonic).to_uppercase()).ok()?;
+    let iclass = unsafe { str2xed_iclass_enum_t(name.as_ptr()) };
+    if iclass == XED_ICLASS_INVALID {
+        None
+    } else {
+        Some(iclass)
+    }
+}
+
+/// Displacement of `target` from the end of an instruction at `runtime_address` taking `length` bytes, if it
+/// fits in `width`'s displacement.
+fn relbr_to(target: u64, runtime_address: u64, length: usize, width: RelativeBr) -> Option<RelativeBr> {
+    let disp = target.wrapping_sub(runtime_address).wrapping_sub(length as u64) as i64;
+    match width {
+        RelativeBr::Disp8(_) => i8::try_from(disp).ok().map(RelativeBr::Disp8),
+        RelativeBr::Disp16(_) => i16::try_from(disp).ok().map(RelativeBr::Disp16),
+        RelativeBr::Disp32(_) => i32::try_from(disp).ok().map(RelativeBr::Disp32),
+    }
+}
+
+fn has_relbr(instruction: &X86Instruction) -> bool {
+    instruction.operand_values().iter().any(|value| matches!(value, OperandValue::RelativeBr(_)))
+}
+
+impl X86Instruction {
+    /// Parses a single line of Intel syntax, as printed by [`X86Instruction::format`] for an instruction at
+    /// `runtime_address`. Branch targets are absolute, like xed prints them. Where several variants accept the
+    /// operands, the first (by iform) which xed can encode in `context`'s mode is picked, trying a short
+    /// displacement before a long one for branches.
+    pub fn parse_at(line: &str, runtime_address: u64, context: &mut EncodeDecodeContext) -> Result<Self, ParseError> {
+        let line = line.trim().to_lowercase();
+        if line.is_empty() {
+            return Err(ParseError::EmptyLine);
+        }
+        let (mnemonic, operands) = line.split_once(char::is_whitespace).unwrap_or((line.as_str(), ""));
+        let iclass = iclass_from_mnemonic(mnemonic).ok_or_else(|| ParseError::UnknownMnemonic(mnemonic.to_string()))?;
+        let operands = if operands.trim().is_empty() {
+            vec![]
+        } else {
+            operands.split(',').map(parse_operand).collect::<Result<Vec<_>, _>>()?
+        };
+        let target = operands.iter().find_map(parsed_immediate).unwrap_or(0) as u64;
+        // matched against the target's offset from the instruction, which unlike the target itself fits a displacement
+        let relative = operands
+            .iter()
+            .map(|operand| match operand {
+                ParsedOperand::Immediate(value) => ParsedOperand::Immediate(value.wrapping_sub(runtime_address as i64)),
+                other => *other,
+            })
+            .collect::<Vec<_>>();
+        let branches = X86Instruction::candidates_from_parsed(iclass, relative.as_slice())
+            .into_iter()
+            .filter(has_relbr)
+            .flat_map(|candidate| {
+                [RelativeBr::Disp8(0), RelativeBr::Disp32(0)]
+                    .into_iter()
+                    .filter_map(move |width| candidate.map_relative_br(|_| Some(width)))
+            })
+            .collect::<Vec<_>>();
+        let others = X86Instruction::candidates_from_parsed(iclass, operands.as_slice())
+            .into_iter()
+            .filter(|candidate| !has_relbr(candidate));
+        for candidate in others {
+            if candidate.encode(context).is_ok() {
+                return Ok(candidate);
+            }
+        }
+        for placeholder in branches {
+            let Ok((_, length)) = placeholder.encode(context) else { continue };
+            let Some(candidate) = placeholder.map_relative_br(|width| relbr_to(target, runtime_address, length, width)) else { continue };
+            // the variant for a short and a near branch only differs in the displacement width, so the decoder
+            // has the final say on which it is
+            if let Ok((bytes, length)) = candidate.encode(context) {
+                if let Ok((decoded, _)) = X86Instruction::decode_one(&bytes[..length], context) {
+                    return Ok(decoded);
+                }
+            }
+        }
+        Err(ParseError::NoMatchingVariant(line.to_string()))
+    }
+}
+
+/// Parses what [`Display`](std::fmt::Display) prints, which is for 64 bit mode with the instruction at address
+/// zero. Use [`X86Instruction::parse_at`] for other modes and addresses.
+impl FromStr for X86Instruction {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        X86Instruction::parse_at(line, 0, &mut EncodeDecodeContext::new())
+    }
+}
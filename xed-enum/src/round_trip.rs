@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::mem::discriminant;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use xed_sys::{xed_iform_enum_t, xed_iform_enum_t2str};
+
+use wrapper_common::memory_operand::{GeneralReg, X86Scale};
+use wrapper_common::registers::{Reg64WithRIP, RegSegment};
+use xed_wrapper::operands::MemoryOperands;
+
+use crate::{EncodeDecodeContext, EncodeError, X86Instruction};
+
+#[derive(Clone, Debug)]
+pub enum RoundTripOutcome {
+    Passed,
+    /// Decoding the encoded bytes produced a different instruction, which isn't just another opcode for it.
+    Mismatch(X86Instruction),
+    /// Encoded fine, but xed could not decode the result.
+    DecodeFailed,
+    /// xed found no encoding for the sample's operands. Example values are generated per operand, so not every
+    /// combination is a valid instruction, and these don't count as failures.
+    Rejected,
+    /// Building the encoder request failed, which is a bug in the generated conversion rather than an invalid
+    /// sample.
+    EncodeFailed(EncodeError),
+    Panicked,
+}
+
+/// What xed reports when no encoding matches the operands of a request.
+const NO_ENCODING: &str = "GENERAL_ERROR";
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct IformRoundTripCounts {
+    pub passed: usize,
+    pub failed: usize,
+    pub rejected: usize,
+}
+
+/// A small set of 64 bit mode memory operands, in the form xed decodes them to. The full
+/// `GenerateExampleValues` product is far too large to try against every variant.
+pub fn memory_operand_samples() -> Vec<MemoryOperands> {
+    let mut res = vec![];
+    for base in [Reg64WithRIP::RAX, Reg64WithRIP::RSP, Reg64WithRIP::RBP, Reg64WithRIP::R13, Reg64WithRIP::R15] {
+        res.push(MemoryOperands::SIBAddressing {
+            segment: None,
+            scale: X86Scale::One,
+            index: None,
+            base: Some(GeneralReg::Reg64(base)),
+            disp: 16,
+            disp_width: 8,
+        });
+        res.push(MemoryOperands::SIBAddressing {
+            segment: None,
+            scale: X86Scale::Four,
+            index: Some(GeneralReg::Reg64(Reg64WithRIP::RCX)),
+            base: Some(GeneralReg::Reg64(base)),
+            disp: -0x12345678,
+            disp_width: 32,
+        });
+    }
+    res.push(MemoryOperands::SIBAddressing {
+        segment: Some(RegSegment::FS),
+        scale: X86Scale::Eight,
+        index: Some(GeneralReg::Reg64(Reg64WithRIP::R9)),
+        base: Some(GeneralReg::Reg64(Reg64WithRIP::RDX)),
+        disp: -1,
+        disp_width: 8,
+    });
+    res.push(MemoryOperands::SIBAddressing {
+        segment: None,
+        scale: X86Scale::One,
+        index: None,
+        base: Some(GeneralReg::Reg64(Reg64WithRIP::RIP)),
+        disp: 0x1000,
+        disp_width: 32,
+    });
+    res
+}
+
+pub fn round_trip_one(instruction: X86Instruction, context: &mut EncodeDecodeContext) -> RoundTripOutcome {
+    catch_unwind(AssertUnwindSafe(|| {
+        let (bytes, len) = match instruction.encode(context) {
+            Ok(encoded) => encoded,
+            Err(EncodeError::XedError { error, .. }) if error == NO_ENCODING => return RoundTripOutcome::Rejected,
+            Err(error) => return RoundTripOutcome::EncodeFailed(error),
+        };
+        match X86Instruction::decode_one(&bytes[..len], context) {
+            Ok((decoded, _)) if equivalent(&instruction, &decoded) => RoundTripOutcome::Passed,
+            Ok((decoded, _)) => RoundTripOutcome::Mismatch(decoded),
+            Err(_) => RoundTripOutcome::DecodeFailed,
+        }
+    })).unwrap_or(RoundTripOutcome::Panicked)
+}
+
+fn iform_name(iform: xed_iform_enum_t) -> String {
+    unsafe { CStr::from_ptr(xed_iform_enum_t2str(iform)) }.to_str().unwrap().to_string()
+}
+
+/// Whether `decoded` is `instruction`, or the same operation under another opcode, like `ADD_GPRV_GPRV_01_64` and
+/// `ADD_GPRV_GPRV_03_64`. xed is free to pick either when encoding, so both decode back to an equivalent variant
+/// of the same iclass, with the same operand width and operands.
+fn equivalent(instruction: &X86Instruction, decoded: &X86Instruction) -> bool {
+    if instruction == decoded {
+        return true;
+    }
+    let iform = iform_name(instruction.iform());
+    let decoded_iform = iform_name(decoded.iform());
+    let (Some((stem, opcode)), Some((decoded_stem, decoded_opcode))) = (iform.rsplit_once('_'), decoded_iform.rsplit_once('_')) else {
+        return false;
+    };
+    let is_opcode = |part: &str| part.len() == 2 && part.chars().all(|c| c.is_ascii_hexdigit());
+    stem == decoded_stem && is_opcode(opcode) && is_opcode(decoded_opcode)
+        && discriminant(instruction) == discriminant(decoded)
+        && instruction.effective_operand_width() == decoded.effective_operand_width()
+        && instruction.operand_values() == decoded.operand_values()
+}
+
+/// Round trips every sample from [`X86Instruction::round_trip_samples`], keyed by iform name.
+pub fn round_trip_all(context: &mut EncodeDecodeContext) -> BTreeMap<String, IformRoundTripCounts> {
+    let mut res: BTreeMap<String, IformRoundTripCounts> = BTreeMap::new();
+    for (iform, instruction) in X86Instruction::round_trip_samples() {
+        let counts = res.entry(iform_name(iform)).or_default();
+        match round_trip_one(instruction, context) {
+            RoundTripOutcome::Passed => counts.passed += 1,
+            RoundTripOutcome::Rejected => counts.rejected += 1,
+            RoundTripOutcome::Mismatch(_) | RoundTripOutcome::DecodeFailed | RoundTripOutcome::EncodeFailed(_) | RoundTripOutcome::Panicked => counts.failed += 1,
+        }
+    }
+    res
+}
@@ -3,13 +3,17 @@ use std::mem::MaybeUninit;
 
 use capstone::arch::x86::ArchMode;
 use capstone::prelude::BuildsCapstone;
-use xed_sys::{XED_ADDRESS_WIDTH_64b, xed_convert_to_encoder_request, xed_decode, xed_decoded_inst_dump, xed_decoded_inst_t, xed_decoded_inst_zero_set_mode, xed_disp, xed_encode, xed_encoder_instruction_t, xed_encoder_request_t, xed_encoder_request_zero_set_mode, xed_error_enum_t2str, XED_ERROR_NONE, XED_ICLASS_ADD, XED_ICLASS_JMP, XED_ICLASS_JMP_FAR, xed_inst1, xed_inst2, XED_MACHINE_MODE_LONG_64, XED_MAX_INSTRUCTION_BYTES, xed_mem_b, xed_mem_bd, xed_mem_gbisd, xed_reg, XED_REG_RBX, XED_REG_RCX, xed_state_init, xed_state_t, xed_state_zero, xed_tables_init};
+use xed_sys::{XED_ADDRESS_WIDTH_64b, xed_convert_to_encoder_request, xed_decode, xed_decoded_inst_dump, xed_decoded_inst_t, xed_decoded_inst_zero_set_mode, xed_disp, xed_encode, xed_encoder_instruction_t, xed_encoder_request_t, xed_encoder_request_zero_set_mode, xed_error_enum_t2str, XED_ERROR_NONE, XED_ICLASS_ADD, XED_ICLASS_JMP, XED_ICLASS_JMP_FAR, xed_inst1, xed_inst2, XED_MACHINE_MODE_LONG_64, XED_MAX_INSTRUCTION_BYTES, xed_mem_b, xed_mem_bd, xed_mem_gbisd, xed_reg, XED_REG_CLASS_GPR, XED_REG_EDX, XED_REG_ESI, XED_REG_RAX, XED_REG_RBX, XED_REG_RCX, XED_REG_RDX, xed_state_init, xed_state_t, xed_state_zero, xed_tables_init};
 
 use wrapper_common::memory_operand::{GeneralReg, X86Scale};
-use wrapper_common::registers::{Reg32WithRIP, Reg64WithRIP};
-use xed_wrapper::operands::MemoryOperands;
+use wrapper_common::registers::{Reg32WithRIP, Reg64WithRIP, Reg8, Register};
+use xed_wrapper::operands::{Imm16, Imm8, MemoryOperands};
 
-use crate::{CMP, EncodeDecodeContext, JMP, X86Instruction};
+use crate::{CMP, DecodeError, Decoder, EncodeDecodeContext, ENTER, JMP, MOV, MUL, RET_NEAR, SHR, X86Instruction};
+use crate::format::{FormatOptions, HexStyle, Syntax};
+use crate::operand_info::{OperandAccess, OperandKind, OperandVisibility, X86Flag};
+use crate::parse::ParseError;
+use crate::round_trip::round_trip_all;
 
 pub struct EncodedInstr {
     bytes: [u8; 15],
@@ -73,7 +77,7 @@ pub fn encode() {
     let error = unsafe { xed_decode(xed_decoded.as_mut_ptr(), bytes.as_ptr(), actual_instruction_len as c_uint) };
     if error == XED_ERROR_NONE {
         decoded_dump(xed_decoded.as_ptr());
-        let decoded = X86Instruction::from_xed(xed_decoded.as_ptr());
+        let decoded = X86Instruction::from_xed(xed_decoded.as_ptr()).unwrap();
         dbg!(decoded);
         todo!()
     } else {
@@ -91,11 +95,11 @@ pub fn test_mem() {
             segment: None,
             scale: X86Scale::One,
             index: None,
-            base: GeneralReg::Reg64(Reg64WithRIP::R15),
+            base: Some(GeneralReg::Reg64(Reg64WithRIP::R15)),
             disp: 128,
             disp_width: 8,
         }
-    }).to_xed(&mut encode_context);
+    }).to_xed(&mut encode_context).unwrap();
     let mut array = [0u8; 16];
     let mut len = 0u32;
 
@@ -147,7 +151,7 @@ pub fn round_trip() {
     let mut xed: xed_encoder_request_t = CMP::CMP_GPRV_GPRV_3B_32 {
         operand_0: Reg32WithRIP::EDX,
         operand_1: Reg32WithRIP::ESI,
-    }.to_xed(&mut encode_context);
+    }.to_xed(&mut encode_context).unwrap();
     let mut array = [0u8; 16];
     let mut len = 0;
 
@@ -171,11 +175,219 @@ pub fn round_trip() {
     }
     unsafe { dbg!(decoded.assume_init_ref()); }
     decoded_dump(decoded.as_ptr());
-    let res = X86Instruction::from_xed(decoded.as_ptr());
+    let res = X86Instruction::from_xed(decoded.as_ptr()).unwrap();
     dbg!(res);
     todo!()
 }
 
+#[test]
+pub fn encode_instruction() {
+    let mut encode_context = EncodeDecodeContext::new();
+    let (bytes, len) = X86Instruction::CMP(CMP::CMP_GPRV_GPRV_3B_32 {
+        operand_0: Reg32WithRIP::EDX,
+        operand_1: Reg32WithRIP::ESI,
+    }).encode(&mut encode_context).unwrap();
+    assert_eq!(&bytes[..len], &[0x3b, 0xd6]);
+    let (bytes, len) = X86Instruction::JMP(JMP::JMP_MEMV_64 {
+        operand_0: MemoryOperands::SIBAddressing {
+            segment: None,
+            scale: X86Scale::One,
+            index: None,
+            base: Some(GeneralReg::Reg64(Reg64WithRIP::R15)),
+            disp: 136,
+            disp_width: 32,
+        }
+    }).encode(&mut encode_context).unwrap();
+    let capstone = capstone::Capstone::new().x86().mode(ArchMode::Mode64).build().unwrap();
+    let instrs = capstone.disasm_all(&bytes[..len], 0).unwrap();
+    assert_eq!(instrs.len(), 1);
+    assert_eq!(instrs.iter().next().unwrap().mnemonic(), Some("jmp"));
+}
+
+#[test]
+pub fn decoder_skips_undecodable() {
+    let mut context = EncodeDecodeContext::new();
+    // 0x06 (push es) is invalid in 64 bit mode
+    let bytes = [0x3b, 0xd6, 0x06, 0x3b, 0xd6];
+    let cmp = X86Instruction::CMP(CMP::CMP_GPRV_GPRV_3B_32 {
+        operand_0: Reg32WithRIP::EDX,
+        operand_1: Reg32WithRIP::ESI,
+    });
+    let res = Decoder::new(bytes.as_slice(), 0x1000, &mut context).collect::<Vec<_>>();
+    assert_eq!(res.len(), 3);
+    assert_eq!(res[0], Ok((0x1000, 2, cmp)));
+    assert!(matches!(res[1], Err(DecodeError::Undecodable { offset: 2, address: 0x1002, .. })));
+    assert_eq!(res[2], Ok((0x1003, 2, cmp)));
+}
+
+#[test]
+pub fn decoder_reads_unsigned_immediates() {
+    let mut context = EncodeDecodeContext::new();
+    // xed decodes these immediates as unsigned: shr r12, 3; mov al, 0xff; ret 0x8010; enter 0x20, 1
+    let bytes = [0x49, 0xc1, 0xec, 0x03, 0xb0, 0xff, 0xc2, 0x10, 0x80, 0xc8, 0x20, 0x00, 0x01];
+    let res = Decoder::new(bytes.as_slice(), 0x1000, &mut context).collect::<Vec<_>>();
+    assert_eq!(res, vec![
+        Ok((0x1000, 4, X86Instruction::SHR(SHR::SHR_GPRV_IMMB_64 { operand_0: Reg64WithRIP::R12, operand_1: Imm8(3) }))),
+        Ok((0x1004, 2, X86Instruction::MOV(MOV::MOV_GPR8_IMMB_B0 { operand_0: Reg8::AL, operand_1: Imm8(-1) }))),
+        Ok((0x1006, 3, X86Instruction::RET_NEAR(RET_NEAR::RET_NEAR_IMMW { operand_0: Imm16(0x8010u16 as i16) }))),
+        Ok((0x1009, 4, X86Instruction::ENTER(ENTER::ENTER_IMMW_IMMB { operand_0: Imm16(0x20), operand_1: Imm8(1) }))),
+    ]);
+}
+
+#[test]
+pub fn legacy_modes_round_trip() {
+    // mov ax, [bx] in 16 bit protected mode, mov eax, [ebx] in 32 bit protected and compatibility mode, and
+    // mov ax, [0x1234] in real mode
+    let cases: [(EncodeDecodeContext, &[u8]); 4] = [
+        (EncodeDecodeContext::legacy_16(), &[0x8b, 0x07]),
+        (EncodeDecodeContext::legacy_32(), &[0x8b, 0x03]),
+        (EncodeDecodeContext::compat_32(), &[0x8b, 0x03]),
+        (EncodeDecodeContext::real(), &[0x8b, 0x06, 0x34, 0x12]),
+    ];
+    for (mut context, bytes) in cases {
+        let (decoded, rest) = X86Instruction::decode_one(bytes, &mut context).unwrap();
+        assert!(rest.is_empty());
+        let (encoded, len) = decoded.encode(&mut context).unwrap();
+        assert_eq!(&encoded[..len], bytes);
+    }
+}
+
+#[test]
+pub fn round_trip_every_variant() {
+    let counts = round_trip_all(&mut EncodeDecodeContext::new());
+    let failed = counts.iter().filter(|(_, counts)| counts.failed > 0).map(|(iform, _)| iform.as_str()).collect::<Vec<_>>();
+    assert!(failed.is_empty(), "iforms failing to round trip: {failed:?}");
+    // every sample of an iform being rejected is what a broken to_xed looks like, unless the iform only exists
+    // outside of 64 bit mode
+    let legacy_counts = round_trip_all(&mut EncodeDecodeContext::legacy_32());
+    let never_passed = counts.iter()
+        .filter(|(iform, counts)| counts.passed == 0 && legacy_counts.get(*iform).map_or(true, |legacy| legacy.passed == 0))
+        .map(|(iform, _)| iform.as_str())
+        .collect::<Vec<_>>();
+    assert!(never_passed.is_empty(), "iforms with no sample round tripping: {never_passed:?}");
+}
+
+#[test]
+pub fn display_and_parse() {
+    let cmp = X86Instruction::CMP(CMP::CMP_GPRV_GPRV_3B_32 {
+        operand_0: Reg32WithRIP::EDX,
+        operand_1: Reg32WithRIP::ESI,
+    });
+    assert_eq!(cmp.to_string(), "cmp edx, esi");
+    let att = cmp.format(&mut EncodeDecodeContext::new(), &FormatOptions { syntax: Syntax::Att, ..FormatOptions::default() }).unwrap();
+    assert!(att.starts_with("cmp"));
+    assert!(att.ends_with("%esi, %edx"));
+
+    for line in ["cmp edx, esi", "mov rax, qword ptr [rbx+rcx*8+0x10]", "add dword ptr fs:[rax-0x8], 0x7f", "lea rdx, ptr [rip+0x1000]"] {
+        let parsed: X86Instruction = line.parse().unwrap();
+        assert_eq!(parsed.to_string(), line);
+    }
+    assert!(matches!("cmp edx".parse::<X86Instruction>(), Err(ParseError::NoMatchingVariant(_))));
+    assert!(matches!("notaninstruction".parse::<X86Instruction>(), Err(ParseError::UnknownMnemonic(_))));
+}
+
+#[test]
+pub fn format_and_parse_branches() {
+    let branches: [&[u8]; 8] = [
+        &[0xeb, 0x10],
+        &[0xeb, 0x7f],
+        &[0xe9, 0x00, 0x01, 0x00, 0x00],
+        &[0x74, 0xf0],
+        &[0x0f, 0x85, 0x00, 0x02, 0x00, 0x00],
+        &[0xe8, 0x78, 0x56, 0x34, 0x12],
+        &[0xe2, 0xfe],
+        &[0xe3, 0x05],
+    ];
+    let mut context = EncodeDecodeContext::new();
+    for bytes in branches {
+        let (branch, _) = X86Instruction::decode_one(bytes, &mut context).unwrap();
+        assert_eq!(branch.to_string().parse::<X86Instruction>().unwrap(), branch);
+        for runtime_address in [0, 0x401000, 0xffff_ffff_ffff_fff0] {
+            let formatted = branch.format(&mut context, &FormatOptions { runtime_address, ..FormatOptions::default() }).unwrap();
+            assert_eq!(X86Instruction::parse_at(&formatted, runtime_address, &mut context).unwrap(), branch, "{formatted} at {runtime_address:#x}");
+        }
+    }
+}
+
+#[test]
+pub fn display_with_symbolizer() {
+    let mut context = EncodeDecodeContext::new();
+    let jmp: X86Instruction = "jmp 0x10".parse().unwrap();
+    let symbolizer = |address: u64| if address >= 0x1000 { Some(("target".to_string(), address - 0x1000)) } else { None };
+    let options = FormatOptions { runtime_address: 0x1000, symbolizer: Some(&symbolizer), ..FormatOptions::default() };
+    let formatted = jmp.format(&mut context, &options).unwrap();
+    assert!(formatted.contains("<target+0x"));
+    let options = FormatOptions { runtime_address: 0xa000, hex_style: HexStyle::Upper, ..FormatOptions::default() };
+    let formatted = jmp.format(&mut context, &options).unwrap();
+    assert!(formatted.contains("0xA0"));
+}
+
+#[test]
+pub fn operand_info() {
+    let mut context = EncodeDecodeContext::new();
+    let mul = X86Instruction::MUL(MUL::MUL_GPRV_64 { operand_0: Reg64WithRIP::RCX });
+    assert_eq!(mul.iform(), xed_sys::XED_IFORM_MUL_GPRv);
+    let info = mul.operand_info(&mut context).unwrap();
+    let explicit = info.explicit_operands().collect::<Vec<_>>();
+    assert_eq!(explicit.len(), 1);
+    assert_eq!(explicit[0].kind, OperandKind::Register(XED_REG_RCX));
+    assert_eq!(explicit[0].access, OperandAccess::Read);
+    assert_eq!(explicit[0].width_bits, 64);
+    assert_eq!(explicit[0].field, Some(0));
+    assert_eq!(explicit[0].register_class(), Some(XED_REG_CLASS_GPR));
+    let rax = info.operands.iter().find(|operand| operand.kind == OperandKind::Register(XED_REG_RAX)).unwrap();
+    assert_eq!(rax.access, OperandAccess::ReadWrite);
+    assert_eq!(rax.visibility, OperandVisibility::Suppressed);
+    let rdx = info.operands.iter().find(|operand| operand.kind == OperandKind::Register(XED_REG_RDX)).unwrap();
+    assert_eq!(rdx.access, OperandAccess::Write);
+    assert!(info.flags_read.is_empty());
+    assert!(info.flags_written.contains(&X86Flag::CF) && info.flags_written.contains(&X86Flag::OF));
+    assert!(info.flags_undefined.contains(&X86Flag::ZF));
+
+    let cmp = X86Instruction::CMP(CMP::CMP_GPRV_GPRV_3B_32 {
+        operand_0: Reg32WithRIP::EDX,
+        operand_1: Reg32WithRIP::ESI,
+    });
+    let info = cmp.operand_info(&mut context).unwrap();
+    let explicit = info.explicit_operands().collect::<Vec<_>>();
+    assert_eq!(explicit[0].kind, OperandKind::Register(XED_REG_EDX));
+    assert_eq!(explicit[1].kind, OperandKind::Register(XED_REG_ESI));
+    assert!(explicit.iter().all(|operand| operand.access == OperandAccess::Read));
+    assert_eq!(info.flags_written, [X86Flag::CF, X86Flag::PF, X86Flag::AF, X86Flag::ZF, X86Flag::SF, X86Flag::OF].into_iter().collect());
+    assert!(info.flags_undefined.is_empty());
+}
+
+#[test]
+pub fn registers_read_and_written() {
+    let mut context = EncodeDecodeContext::new();
+    let mul = X86Instruction::MUL(MUL::MUL_GPR8 { operand_0: Reg8::CL });
+    let read = mul.registers_read(&mut context).unwrap();
+    let written = mul.registers_written(&mut context).unwrap();
+    assert_eq!(read.registers, [Register::GP64(Reg64WithRIP::RCX), Register::GP64(Reg64WithRIP::RAX)].into_iter().collect());
+    assert_eq!(written.registers, [Register::GP64(Reg64WithRIP::RAX)].into_iter().collect());
+    assert!(written.flags.contains(&X86Flag::CF));
+    assert!(!read.memory && !written.memory);
+
+    let jmp = X86Instruction::JMP(JMP::JMP_MEMV_64 {
+        operand_0: MemoryOperands::SIBAddressing {
+            segment: None,
+            scale: X86Scale::Eight,
+            index: Some(GeneralReg::Reg64(Reg64WithRIP::RCX)),
+            base: Some(GeneralReg::Reg64(Reg64WithRIP::R15)),
+            disp: 0,
+            disp_width: 0,
+        }
+    });
+    let read = jmp.registers_read(&mut context).unwrap();
+    let written = jmp.registers_written(&mut context).unwrap();
+    assert!(read.registers.contains(&Register::GP64(Reg64WithRIP::R15)));
+    assert!(read.registers.contains(&Register::GP64(Reg64WithRIP::RCX)));
+    assert!(read.memory);
+    assert!(!written.memory);
+    assert!(written.registers.contains(&Register::GP64(Reg64WithRIP::RIP)));
+    assert!(written.flags.is_empty());
+}
+
 fn decoded_dump(decoded: *const xed_decoded_inst_t) {
     let mut chars = vec![0i8; 10000];
     unsafe { xed_decoded_inst_dump(decoded, chars.as_mut_ptr(), 10000); }
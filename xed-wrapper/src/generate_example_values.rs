@@ -4,14 +4,14 @@ use wrapper_common::generate_example_values::GenerateExampleValues;
 use wrapper_common::memory_operand::{GeneralReg, X86Scale};
 use wrapper_common::registers::RegSegment;
 
-use crate::operands::MemoryOperands;
+use crate::operands::{Imm16, Imm32, Imm64, Imm8, Immediate, MemoryOperands, RelativeBr};
 
 impl GenerateExampleValues for MemoryOperands {
     fn generate() -> impl Iterator<Item=Self> {
         let segments = Option::<RegSegment>::generate().collect_vec();
         let scale = X86Scale::generate().collect_vec();
         let index = Option::<GeneralReg>::generate().collect_vec();
-        let base = GeneralReg::generate().collect_vec();
+        let base = Option::<GeneralReg>::generate().collect_vec();
         let disp = i64::generate().collect_vec();
         let disp_width = [8, 16, 32, 64].into_iter().collect_vec();
         iproduct!(segments,scale, index,base,disp,disp_width).map(|(segment, scale, index, base, disp, disp_width)| {
@@ -33,6 +33,49 @@ impl GenerateExampleValues for MemoryOperands {
     }
 }
 
+impl GenerateExampleValues for Imm8 {
+    fn generate() -> impl Iterator<Item=Self> {
+        vec![i8::MIN, -1, 0, 1, i8::MAX].into_iter().map(Imm8)
+    }
+}
+
+impl GenerateExampleValues for Imm16 {
+    fn generate() -> impl Iterator<Item=Self> {
+        vec![i16::MIN, -1, 0, 1, i16::MAX].into_iter().map(Imm16)
+    }
+}
+
+impl GenerateExampleValues for Imm32 {
+    fn generate() -> impl Iterator<Item=Self> {
+        vec![i32::MIN, -1, 0, 1, i32::MAX].into_iter().map(Imm32)
+    }
+}
+
+impl GenerateExampleValues for Imm64 {
+    fn generate() -> impl Iterator<Item=Self> {
+        u64::generate().map(Imm64)
+    }
+}
+
+impl GenerateExampleValues for RelativeBr {
+    fn generate() -> impl Iterator<Item=Self> {
+        let disp8 = vec![i8::MIN, 0, i8::MAX].into_iter().map(RelativeBr::Disp8);
+        let disp16 = vec![i16::MIN, 0, i16::MAX].into_iter().map(RelativeBr::Disp16);
+        let disp32 = vec![i32::MIN, 0, i32::MAX].into_iter().map(RelativeBr::Disp32);
+        disp8.chain(disp16).chain(disp32)
+    }
+}
+
+impl GenerateExampleValues for Immediate {
+    fn generate() -> impl Iterator<Item=Self> {
+        let i8s = Imm8::generate().map(|Imm8(inner)| Immediate::I8(inner));
+        let i16s = Imm16::generate().map(|Imm16(inner)| Immediate::I16(inner));
+        let i32s = Imm32::generate().map(|Imm32(inner)| Immediate::I32(inner));
+        let u64s = u64::generate().map(Immediate::U64);
+        i8s.chain(i16s).chain(i32s).chain(u64s)
+    }
+}
+
 #[test]
 pub fn check_len() {
     let len = MemoryOperands::generate().count();
@@ -50,7 +93,7 @@ pub fn check_iter_time() {
             for _ in MemoryOperands::generate().step_by(10000) {
                 let operand: MemoryOperands = operand;
                 let MemoryOperands::SIBAddressing { segment, base, scale, index, disp, disp_width } = operand;
-                std::hint::black_box(unsafe { xed_mem_gbisd(segment.as_ref().map(|seg| seg.to_xed()).unwrap_or(0), base.to_xed(), index.as_ref().map(|index| index.to_xed()).unwrap_or(0), scale.to_xed(), xed_disp(disp, disp_width), 64) });
+                std::hint::black_box(unsafe { xed_mem_gbisd(segment.as_ref().map(|seg| seg.to_xed()).unwrap_or(0), base.as_ref().map(|base| base.to_xed()).unwrap_or(0), index.as_ref().map(|index| index.to_xed()).unwrap_or(0), scale.to_xed(), xed_disp(disp, disp_width), 64) });
             }
         }
     })
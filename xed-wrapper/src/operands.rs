@@ -1,6 +1,6 @@
 use std::ffi::c_uint;
 
-use xed_sys::{xed_decoded_inst_get_base_reg, xed_decoded_inst_get_branch_displacement, xed_decoded_inst_get_branch_displacement_width, xed_decoded_inst_get_immediate_is_signed, xed_decoded_inst_get_immediate_width, xed_decoded_inst_get_index_reg, xed_decoded_inst_get_memory_displacement, xed_decoded_inst_get_memory_displacement_width, xed_decoded_inst_get_scale, xed_decoded_inst_get_second_immediate, xed_decoded_inst_get_seg_reg, xed_decoded_inst_get_signed_immediate, xed_decoded_inst_get_unsigned_immediate, xed_decoded_inst_t, xed_encoder_operand_t, xed_imm0, xed_inst_t, XED_REG_INVALID, xed_uint_t};
+use xed_sys::{xed_decoded_inst_get_base_reg, xed_decoded_inst_get_branch_displacement, xed_decoded_inst_get_branch_displacement_width, xed_decoded_inst_get_immediate_is_signed, xed_decoded_inst_get_immediate_width, xed_decoded_inst_get_index_reg, xed_decoded_inst_get_memory_displacement, xed_decoded_inst_get_memory_displacement_width_bits, xed_decoded_inst_get_scale, xed_decoded_inst_get_second_immediate, xed_decoded_inst_get_seg_reg, xed_decoded_inst_get_signed_immediate, xed_decoded_inst_get_unsigned_immediate, xed_decoded_inst_t, xed_encoder_operand_t, xed_imm0, xed_inst_t, xed_ptr, xed_relbr, XED_REG_INVALID, xed_uint_t};
 
 use wrapper_common::memory_operand::{GeneralReg, X86Scale};
 use wrapper_common::registers::RegSegment;
@@ -13,38 +13,53 @@ pub enum RelativeBr {
 }
 
 impl RelativeBr {
-    pub fn from_xed(xed: *const xed_decoded_inst_t) -> Self {
+    /// None if xed decoded a displacement of another width.
+    pub fn from_xed(xed: *const xed_decoded_inst_t) -> Option<Self> {
         unsafe {
-            match xed_decoded_inst_get_branch_displacement_width(xed) {
+            Some(match xed_decoded_inst_get_branch_displacement_width(xed) {
                 1 => RelativeBr::Disp8(xed_decoded_inst_get_branch_displacement(xed) as i8),
                 2 => RelativeBr::Disp16(xed_decoded_inst_get_branch_displacement(xed) as i16),
                 4 => RelativeBr::Disp32(xed_decoded_inst_get_branch_displacement(xed)),
-                _ => todo!(""),
+                _ => return None,
+            })
+        }
+    }
+
+    pub fn to_xed(&self) -> xed_encoder_operand_t {
+        unsafe {
+            match self {
+                RelativeBr::Disp8(disp) => xed_relbr(*disp as i32, 8),
+                RelativeBr::Disp16(disp) => xed_relbr(*disp as i32, 16),
+                RelativeBr::Disp32(disp) => xed_relbr(*disp, 32),
             }
         }
     }
 }
 
+/// The bits of the first immediate of `xed`, if it is `width` bytes wide. xed decodes some immediates as signed and
+/// others, like shift counts and the operands of RET and ENTER, as unsigned.
+unsafe fn first_immediate(xed: *const xed_decoded_inst_t, width: xed_uint_t) -> Option<u64> {
+    if xed_decoded_inst_get_immediate_width(xed) != width {
+        return None;
+    }
+    Some(if xed_decoded_inst_get_immediate_is_signed(xed) != 0 {
+        xed_decoded_inst_get_signed_immediate(xed) as i64 as u64
+    } else {
+        xed_decoded_inst_get_unsigned_immediate(xed)
+    })
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Imm8(pub i8);
 
 impl Imm8 {
-    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Self {
+    /// None if the immediate isn't 8 bits wide.
+    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Option<Self> {
         unsafe {
             if second_immediate {
-                return Self(dbg!(xed_decoded_inst_get_second_immediate(xed) as i8));
-            }
-            let signed = xed_decoded_inst_get_immediate_is_signed(xed) != 0;
-            match xed_decoded_inst_get_immediate_width(xed) {
-                1 => {
-                    if signed {
-                        Self(xed_decoded_inst_get_signed_immediate(xed) as i8)
-                    } else {
-                        todo!()
-                    }
-                }
-                _ => todo!(),
+                return Some(Self(xed_decoded_inst_get_second_immediate(xed) as i8));
             }
+            Some(Self(first_immediate(xed, 1)? as i8))
         }
     }
 
@@ -61,21 +76,12 @@ impl Imm8 {
 pub struct Imm16(pub i16);
 
 impl Imm16 {
-    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Self {
-        unsafe {
-            assert!(!second_immediate);
-            let signed = xed_decoded_inst_get_immediate_is_signed(xed) != 0;
-            match xed_decoded_inst_get_immediate_width(xed) {
-                2 => {
-                    if signed {
-                        Self(xed_decoded_inst_get_signed_immediate(xed) as i16)
-                    } else {
-                        todo!()
-                    }
-                }
-                _ => todo!(),
-            }
+    /// None if the immediate isn't 16 bits wide. Second immediates are only ever 8 bits.
+    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Option<Self> {
+        if second_immediate {
+            return None;
         }
+        Some(Self(unsafe { first_immediate(xed, 2)? } as i16))
     }
 
     pub fn to_xed(&self) -> xed_encoder_operand_t {
@@ -91,21 +97,12 @@ impl Imm16 {
 pub struct Imm32(pub i32);
 
 impl Imm32 {
-    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Self {
-        unsafe {
-            assert!(!second_immediate);
-            let signed = xed_decoded_inst_get_immediate_is_signed(xed) != 0;
-            match xed_decoded_inst_get_immediate_width(xed) {
-                4 => {
-                    if signed {
-                        Self(xed_decoded_inst_get_signed_immediate(xed) as i32)
-                    } else {
-                        todo!()
-                    }
-                }
-                _ => todo!(),
-            }
+    /// None if the immediate isn't 32 bits wide. Second immediates are only ever 8 bits.
+    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Option<Self> {
+        if second_immediate {
+            return None;
         }
+        Some(Self(unsafe { first_immediate(xed, 4)? } as i32))
     }
 
     pub fn to_xed(&self) -> xed_encoder_operand_t {
@@ -114,28 +111,19 @@ impl Imm32 {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-pub struct Imm64(u64);
+pub struct Imm64(pub u64);
 
 impl Imm64 {
-    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Self {
-        unsafe {
-            assert!(!second_immediate);
-            let signed = xed_decoded_inst_get_immediate_is_signed(xed) != 0;
-            match xed_decoded_inst_get_immediate_width(xed) {
-                8 => {
-                    if !signed {
-                        Self(xed_decoded_inst_get_unsigned_immediate(xed))
-                    } else {
-                        todo!()
-                    }
-                }
-                _ => todo!(),
-            }
+    /// None if the immediate isn't 64 bits wide. Second immediates are only ever 8 bits.
+    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Option<Self> {
+        if second_immediate {
+            return None;
         }
+        Some(Self(unsafe { first_immediate(xed, 8)? }))
     }
 
     pub fn to_xed(&self) -> xed_encoder_operand_t {
-        unsafe { xed_imm0(self.0 as u64, 32) }
+        unsafe { xed_imm0(self.0, 64) }
     }
 }
 
@@ -148,40 +136,21 @@ pub enum Immediate {
 }
 
 impl Immediate {
-    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Self {
+    /// None if the immediate has a width none of the variants have.
+    pub fn from_xed(xed: *const xed_decoded_inst_t, second_immediate: bool) -> Option<Self> {
         unsafe {
             if second_immediate {
-                return Self::I8(xed_decoded_inst_get_second_immediate(xed) as i8);
-            }
-            let signed = xed_decoded_inst_get_immediate_is_signed(xed) != 0;
-            match xed_decoded_inst_get_immediate_width(xed) {
-                1 => {
-                    if signed {
-                        Self::I8(xed_decoded_inst_get_signed_immediate(xed) as i8)
-                    } else {
-                        todo!()
-                    }
-                }
-                2 => {
-                    if !signed {
-                        todo!()
-                    }
-                    Self::I16(xed_decoded_inst_get_signed_immediate(xed) as i16)
-                }
-                4 => {
-                    if !signed {
-                        todo!()
-                    }
-                    Self::I32(xed_decoded_inst_get_signed_immediate(xed) as i32)
-                }
-                8 => {
-                    if signed {
-                        todo!()
-                    }
-                    Self::U64(xed_decoded_inst_get_unsigned_immediate(xed))
-                }
-                _ => todo!(),
+                return Some(Self::I8(xed_decoded_inst_get_second_immediate(xed) as i8));
             }
+            let width = xed_decoded_inst_get_immediate_width(xed);
+            let value = first_immediate(xed, width)?;
+            Some(match width {
+                1 => Self::I8(value as i8),
+                2 => Self::I16(value as i16),
+                4 => Self::I32(value as i32),
+                8 => Self::U64(value),
+                _ => return None,
+            })
         }
     }
 
@@ -211,6 +180,42 @@ impl Immediate {
             }
         }
     }
+
+    /// None if the value doesn't fit in the 32 bits xed takes far pointer offsets as.
+    pub fn to_xed_ptr(&self) -> Option<xed_encoder_operand_t> {
+        unsafe {
+            Some(match self {
+                Immediate::I8(inner) => xed_ptr(*inner as i32, 8),
+                Immediate::I16(inner) => xed_ptr(*inner as i32, 16),
+                Immediate::I32(inner) => xed_ptr(*inner, 32),
+                Immediate::U64(inner) => xed_ptr(i32::try_from(*inner).ok()?, 64)
+            })
+        }
+    }
+}
+
+impl From<Imm8> for Immediate {
+    fn from(imm: Imm8) -> Self {
+        Immediate::I8(imm.0)
+    }
+}
+
+impl From<Imm16> for Immediate {
+    fn from(imm: Imm16) -> Self {
+        Immediate::I16(imm.0)
+    }
+}
+
+impl From<Imm32> for Immediate {
+    fn from(imm: Imm32) -> Self {
+        Immediate::I32(imm.0)
+    }
+}
+
+impl From<Imm64> for Immediate {
+    fn from(imm: Imm64) -> Self {
+        Immediate::U64(imm.0)
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -219,31 +224,34 @@ pub enum MemoryOperands {
         segment: Option<RegSegment>,
         scale: X86Scale,
         index: Option<GeneralReg>,
-        base: GeneralReg,
+        base: Option<GeneralReg>,
         disp: i64,
         disp_width: xed_uint_t
     },
 }
 
 impl MemoryOperands {
+    /// None if xed decoded a register the operand can't hold.
     pub fn from_xed(
         instr: *const xed_decoded_inst_t,
         _instr_template: *const xed_inst_t,
         mem_idx: c_uint,
-    ) -> Self {
+    ) -> Option<Self> {
         unsafe {
             let base_reg_raw = xed_decoded_inst_get_base_reg(instr, mem_idx);
             let seg_reg_raw = xed_decoded_inst_get_seg_reg(instr, mem_idx);
             let index_raw = xed_decoded_inst_get_index_reg(instr, mem_idx);
             let memory_disp_raw = xed_decoded_inst_get_memory_displacement(instr, mem_idx);
-            let disp_width = xed_decoded_inst_get_memory_displacement_width(instr, mem_idx);
+            let disp_width = xed_decoded_inst_get_memory_displacement_width_bits(instr, mem_idx);
             let scale_raw = xed_decoded_inst_get_scale(instr, mem_idx);
             let scale = if scale_raw != 0 { X86Scale::from_raw_scale(scale_raw as i32) } else { X86Scale::One };
-            let index = if index_raw != 0 { Some(GeneralReg::try_new(index_raw, None).unwrap()) } else { None };
-            let base = GeneralReg::try_new(base_reg_raw, None).unwrap();
-            Self::SIBAddressing {
+            // in 16/32 bit addressing the width of the address registers comes from the registers themselves,
+            // not from the effective operand width.
+            let index = if index_raw != XED_REG_INVALID { Some(GeneralReg::try_new(index_raw, None)?) } else { None };
+            let base = if base_reg_raw != XED_REG_INVALID { Some(GeneralReg::try_new(base_reg_raw, None)?) } else { None };
+            Some(Self::SIBAddressing {
                 segment: if seg_reg_raw != XED_REG_INVALID {
-                    Some(RegSegment::try_new(seg_reg_raw).unwrap())
+                    Some(RegSegment::try_new(seg_reg_raw)?)
                 } else {
                     None
                 },
@@ -252,7 +260,7 @@ impl MemoryOperands {
                 base,
                 disp: memory_disp_raw,
                 disp_width
-            }
+            })
         }
     }
 }